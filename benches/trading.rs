@@ -0,0 +1,91 @@
+//! Latency benchmarks for the trade hot path: per-protocol buy instruction building, full
+//! transaction assembly with and without an address lookup table, and wire serialization.
+//!
+//! Run with `cargo bench --bench trading`. Fixtures come from
+//! `sol_trade_sdk::perf::bench_harness`; edit `sample_swap_params` output there (or here) to
+//! benchmark your own gas strategy, lookup tables or middleware.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use sol_trade_sdk::perf::bench_harness::{
+    assemble_transaction, build_buy_instructions, sample_lookup_table, sample_swap_params,
+    ALL_DEX_TYPES,
+};
+use sol_trade_sdk::swqos::serialization::{
+    serialize_transaction_bincode_sync, serialize_transaction_sync,
+};
+use solana_transaction_status::UiTransactionEncoding;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread().build().expect("tokio runtime")
+}
+
+fn bench_build_buy_instructions(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("build_buy_instructions");
+    for dex in ALL_DEX_TYPES {
+        let params = sample_swap_params(dex);
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{dex:?}")), &params, |b, p| {
+            b.to_async(&rt).iter(|| async { build_buy_instructions(black_box(p)).await.unwrap() })
+        });
+    }
+    group.finish();
+}
+
+fn bench_assemble_transaction(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("assemble_transaction");
+    for dex in ALL_DEX_TYPES {
+        let mut params = sample_swap_params(dex);
+        let instructions = rt.block_on(build_buy_instructions(&params)).unwrap();
+        let name = format!("{dex:?}");
+
+        group.bench_with_input(BenchmarkId::new("no_alt", &name), &params, |b, p| {
+            b.iter(|| assemble_transaction(black_box(p), black_box(&instructions)).unwrap())
+        });
+
+        params.address_lookup_table_accounts = vec![sample_lookup_table(&instructions)];
+        group.bench_with_input(BenchmarkId::new("alt", &name), &params, |b, p| {
+            b.iter(|| assemble_transaction(black_box(p), black_box(&instructions)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_serialization(c: &mut Criterion) {
+    let rt = runtime();
+    let params = sample_swap_params(sol_trade_sdk::trading::factory::DexType::PumpSwap);
+    let instructions = rt.block_on(build_buy_instructions(&params)).unwrap();
+    let mut group = c.benchmark_group("serialization");
+
+    // Fresh transaction per iteration so the shared per-signature encoding cache never hits.
+    group.bench_function("bincode", |b| {
+        b.iter_batched(
+            || assemble_transaction(&params, &instructions).unwrap(),
+            |tx| serialize_transaction_bincode_sync(&tx).unwrap(),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("base64", |b| {
+        b.iter_batched(
+            || assemble_transaction(&params, &instructions).unwrap(),
+            |tx| serialize_transaction_sync(&tx, UiTransactionEncoding::Base64).unwrap(),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    // Same transaction every iteration: the cost every additional SWQOS provider pays.
+    let tx = assemble_transaction(&params, &instructions).unwrap();
+    group.bench_function("base64_shared", |b| {
+        b.iter(|| {
+            serialize_transaction_sync(black_box(&tx), UiTransactionEncoding::Base64).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_build_buy_instructions,
+    bench_assemble_transaction,
+    bench_serialization
+);
+criterion_main!(benches);
@@ -112,7 +112,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
@@ -155,7 +155,7 @@ async fn pumpfun_copy_trade_with_grpc(
         dex_type: DexType::PumpFun,
         input_token_type: sol_trade_sdk::TradeTokenType::SOL,
         mint: mint_pubkey,
-        input_token_amount: 100_000,
+        input_token_amount: Some(100_000),
         slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::PumpFun(PumpFunParams::from_trade(
@@ -177,16 +177,29 @@ async fn pumpfun_copy_trade_with_grpc(
         )),
         address_lookup_table_accounts: alt.into_iter().collect(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: false,
         close_input_token_ata: false,
         create_mint_ata: true,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     client.buy(buy_params).await?;
 
@@ -105,7 +105,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
@@ -143,35 +143,35 @@ async fn bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult<()>
         dex_type: DexType::Bonk,
         input_token_type: input_token_type.clone(),
         mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
+        input_token_amount: Some(buy_sol_amount),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
-        extension_params: DexParamEnum::Bonk(BonkParams::from_trade(
-            trade_info.virtual_base,
-            trade_info.virtual_quote,
-            trade_info.real_base_after,
-            trade_info.real_quote_after,
-            trade_info.pool_state,
-            trade_info.base_vault,
-            trade_info.quote_vault,
-            trade_info.base_token_program,
-            trade_info.platform_config,
-            trade_info.platform_associated_account,
-            trade_info.creator_associated_account,
-            trade_info.global_config,
-        )),
+        extension_params: DexParamEnum::Bonk(BonkParams::try_from(&trade_info)?),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: false,
         create_mint_ata: true,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy: gas_fee_strategy.clone(),
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
@@ -204,12 +204,14 @@ async fn bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult<()>
         dex_type: DexType::Bonk,
         output_token_type: input_token_type,
         mint: mint_pubkey,
-        input_token_amount: amount_token,
+        input_token_amount: Some(amount_token),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
         extension_params: DexParamEnum::Bonk(sell_extension),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         with_tip: false,
         durable_nonce: None,
@@ -217,9 +219,20 @@ async fn bonk_copy_trade_with_grpc(trade_info: BonkTradeEvent) -> AnyResult<()>
         close_output_token_ata: false,
         close_mint_token_ata: false,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy: gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
@@ -75,7 +75,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
@@ -114,7 +114,7 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         dex_type: DexType::Bonk,
         input_token_type: token_type.clone(),
         mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
+        input_token_amount: Some(buy_sol_amount),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::Bonk(BonkParams::from_dev_trade(
@@ -132,16 +132,29 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         )),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: true,
         create_mint_ata: true,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy: gas_fee_strategy.clone(),
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
@@ -168,7 +181,7 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         dex_type: DexType::Bonk,
         output_token_type: token_type,
         mint: mint_pubkey,
-        input_token_amount: amount_token,
+        input_token_amount: Some(amount_token),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
         extension_params: DexParamEnum::Bonk(BonkParams::immediate_sell(
@@ -180,6 +193,8 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         )),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: true,
@@ -187,9 +202,20 @@ async fn bonk_sniper_trade_with_shreds(trade_info: BonkTradeEvent) -> AnyResult<
         with_tip: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy: gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
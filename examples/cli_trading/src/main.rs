@@ -610,22 +610,35 @@ async fn handle_buy_pumpfun(
         dex_type: DexType::PumpFun,
         input_token_type: TradeTokenType::SOL,
         mint: mint_pubkey,
-        input_token_amount: sol_lamports,
+        input_token_amount: Some(sol_lamports),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::PumpFun(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: false,
         close_input_token_ata: false,
         create_mint_ata,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
@@ -667,22 +680,35 @@ async fn handle_buy_pumpswap(
         dex_type: DexType::PumpSwap,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: sol_lamports,
+        input_token_amount: Some(sol_lamports),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::PumpSwap(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: false,
         create_mint_ata,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
@@ -724,22 +750,35 @@ async fn handle_buy_bonk(
         dex_type: DexType::Bonk,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: sol_lamports,
+        input_token_amount: Some(sol_lamports),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::Bonk(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: false,
         create_mint_ata,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
@@ -785,22 +824,35 @@ async fn handle_buy_raydium_v4(
         dex_type: DexType::RaydiumAmmV4,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: sol_lamports,
+        input_token_amount: Some(sol_lamports),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::RaydiumAmmV4(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: false,
         create_mint_ata,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
@@ -847,22 +899,35 @@ async fn handle_buy_raydium_cpmm(
         dex_type: DexType::RaydiumCpmm,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: sol_lamports,
+        input_token_amount: Some(sol_lamports),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::RaydiumCpmm(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: false,
         create_mint_ata,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.buy(buy_params).await {
         Ok((_, signature, _, _)) => {
@@ -1017,22 +1082,35 @@ async fn handle_sell_pumpfun(
         dex_type: DexType::PumpFun,
         output_token_type: TradeTokenType::SOL,
         mint: mint_pubkey,
-        input_token_amount: amount as u64,
+        input_token_amount: Some(amount as u64),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         with_tip: false,
         extension_params: DexParamEnum::PumpFun(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: false,
         close_mint_token_ata: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
 
     match client.sell(sell_params).await {
@@ -1077,22 +1155,35 @@ async fn handle_sell_pumpswap(
         dex_type: DexType::PumpSwap,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: amount as u64,
+        input_token_amount: Some(amount as u64),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         with_tip: false,
         extension_params: DexParamEnum::PumpSwap(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: false,
         close_mint_token_ata: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
@@ -1137,22 +1228,35 @@ async fn handle_sell_bonk(
         dex_type: DexType::Bonk,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: amount as u64,
+        input_token_amount: Some(amount as u64),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         with_tip: false,
         extension_params: DexParamEnum::Bonk(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: false,
         close_mint_token_ata: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
@@ -1200,22 +1304,35 @@ async fn handle_sell_raydium_v4(
         dex_type: DexType::RaydiumAmmV4,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: amount as u64,
+        input_token_amount: Some(amount as u64),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         with_tip: false,
         extension_params: DexParamEnum::RaydiumAmmV4(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: false,
         close_mint_token_ata: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
@@ -1264,22 +1381,35 @@ async fn handle_sell_raydium_cpmm(
         dex_type: DexType::RaydiumCpmm,
         output_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: amount as u64,
+        input_token_amount: Some(amount as u64),
         slippage_basis_points: slippage,
         recent_blockhash: Some(recent_blockhash),
         with_tip: false,
         extension_params: DexParamEnum::RaydiumCpmm(param),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: false,
         close_mint_token_ata: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     match client.sell(sell_params).await {
         Ok((_, signature, _, _)) => {
@@ -1393,7 +1523,7 @@ async fn initialize_real_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
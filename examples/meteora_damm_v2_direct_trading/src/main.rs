@@ -44,22 +44,35 @@ async fn main() -> AnyResult<()> {
         dex_type: DexType::MeteoraDammV2,
         input_token_type: TradeTokenType::USDC, // or USDC
         mint: mint_pubkey,
-        input_token_amount: input_token_amount,
+        input_token_amount: Some(input_token_amount),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::MeteoraDammV2(pool_params),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: false, //if input token is SOL/WSOL,set to true,if input token is USDC,set to false.
         close_input_token_ata: false, //if input token is SOL/WSOL,set to true,if input token is USDC,set to false.
         create_mint_ata: true,
         durable_nonce: None,
-        fixed_output_token_amount: Some(min_buy_output),
+        fixed_output_token_amount: None,
+        min_output_token_amount: Some(min_buy_output),
         gas_fee_strategy: gas_fee_strategy.clone(),
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
@@ -82,7 +95,7 @@ async fn main() -> AnyResult<()> {
         dex_type: DexType::MeteoraDammV2,
         output_token_type: TradeTokenType::USDC,
         mint: mint_pubkey,
-        input_token_amount: amount_token,
+        input_token_amount: Some(amount_token),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
         with_tip: false,
@@ -92,15 +105,28 @@ async fn main() -> AnyResult<()> {
         ),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: false, //if output token is SOL/WSOL,set to true,if output token is USDC,set to false.
         close_output_token_ata: false, //if output token is SOL/WSOL,set to true,if output token is USDC,set to false.
         close_mint_token_ata: false,
         durable_nonce: None,
-        fixed_output_token_amount: Some(min_sell_output),
+        fixed_output_token_amount: None,
+        min_output_token_amount: Some(min_sell_output),
         gas_fee_strategy: gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
@@ -133,7 +159,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
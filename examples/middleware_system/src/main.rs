@@ -31,7 +31,7 @@ impl InstructionMiddleware for CustomMiddleware {
     fn process_protocol_instructions(
         &self,
         protocol_instructions: Vec<Instruction>,
-        _protocol_name: &str,
+        _dex_type: DexType,
         _is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         // do anything you want here
@@ -42,7 +42,7 @@ impl InstructionMiddleware for CustomMiddleware {
     fn process_full_instructions(
         &self,
         full_instructions: Vec<Instruction>,
-        _protocol_name: &str,
+        _dex_type: DexType,
         _is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         // do anything you want here
@@ -67,7 +67,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
@@ -95,7 +95,7 @@ async fn test_middleware() -> AnyResult<()> {
         dex_type: DexType::PumpSwap,
         input_token_type: TradeTokenType::WSOL,
         mint: mint_pubkey,
-        input_token_amount: buy_sol_cost,
+        input_token_amount: Some(buy_sol_cost),
         slippage_basis_points: slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::PumpSwap(
@@ -104,16 +104,29 @@ async fn test_middleware() -> AnyResult<()> {
         ),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: true,
         create_mint_ata: true,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy: gas_fee_strategy,
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     client.buy(buy_params).await?;
     println!("tip: This transaction will not succeed because we're using a test account. You can modify the code to initialize the payer with your own private key");
@@ -2,6 +2,7 @@
 //!
 //! 监听创建者首次买入（Create 后同笔/首笔 Buy，is_created_buy == true），
 //! 用事件参数（含 is_cashback_coin）构造 from_dev_trade 并执行一次买+卖。
+//! 设置 `HONEYPOT_CHECK=1` 时，买入后先模拟小额卖出（can_sell），不可卖则不发送卖单。
 
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -105,7 +106,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
@@ -131,7 +132,7 @@ async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent
         dex_type: DexType::PumpFun,
         input_token_type: TradeTokenType::SOL,
         mint: mint_pubkey,
-        input_token_amount: buy_sol_amount,
+        input_token_amount: Some(buy_sol_amount),
         slippage_basis_points,
         recent_blockhash: Some(recent_blockhash),
         extension_params: DexParamEnum::PumpFun(PumpFunParams::from_dev_trade(
@@ -150,16 +151,29 @@ async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent
         )),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_input_token_ata: true,
         close_input_token_ata: true,
         create_mint_ata: true,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy: gas_fee_strategy.clone(),
         simulate: false,
         use_exact_sol_amount: None,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        skip_risk_checks: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
     let (ok, sigs, err, _) = client.buy(buy_params).await?;
     if !ok {
@@ -182,23 +196,45 @@ async fn pumpfun_sniper_trade(e: sol_parser_sdk::core::events::PumpFunTradeEvent
         dex_type: DexType::PumpFun,
         output_token_type: TradeTokenType::SOL,
         mint: mint_pubkey,
-        input_token_amount: amount_token,
+        input_token_amount: Some(amount_token),
         slippage_basis_points,
         recent_blockhash: Some(client.infrastructure.rpc.get_latest_blockhash().await?),
         with_tip: false,
         extension_params: DexParamEnum::PumpFun(sell_extension),
         address_lookup_table_accounts: Vec::new(),
         wait_tx_confirmed: true,
+        confirmation: Default::default(),
+        rpc_send_options: None,
         wait_for_all_submits: false,
         create_output_token_ata: true,
         close_output_token_ata: true,
         close_mint_token_ata: false,
         durable_nonce: None,
         fixed_output_token_amount: None,
+        min_output_token_amount: None,
         gas_fee_strategy,
         simulate: false,
         grpc_recv_us: None,
+        event_slot: None,
+        data_size_limit: None,
+        memo: Default::default(),
+        client_order_id: None,
+        latency_budget: None,
+        reduce_only: false,
+        fee_payer: None,
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        additional_signers: Vec::new(),
     };
+    // 可选的买后貔貅检测：模拟卖出 1% 持仓，冻结/黑名单代币在此暴露
+    if std::env::var("HONEYPOT_CHECK").is_ok_and(|v| v == "1") {
+        let check = client.can_sell(mint_pubkey, sell_params.clone()).await?;
+        if !check.is_sellable() {
+            anyhow::bail!("honeypot suspected, not selling: {:?}", check);
+        }
+        println!("貔貅检测通过：模拟卖出成功");
+    }
+
     let (ok, sigs, err, _) = client.sell(sell_params).await?;
     if !ok {
         anyhow::bail!("sell failed: {:?}; signatures: {:?}", err, sigs);
@@ -323,7 +323,7 @@ async fn create_solana_trade_client() -> AnyResult<SolanaTrade> {
         // .create_wsol_ata_on_startup(true)  // default: true
         // .use_seed_optimize(true)            // default: true
         // .log_enabled(true)                  // default: true
-        // .check_min_tip(false)               // default: false
+        // .check_min_tip(true)                // default: true
         // .swqos_cores_from_end(false)        // default: false
         // .mev_protection(false)              // default: false
         .build();
@@ -339,35 +339,8 @@ async fn pumpswap_trade_with_grpc_buy_event(
     selection: EventSelection,
     trade_info: PumpSwapBuyEvent,
 ) -> AnyResult<EventAction> {
-    let params = PumpSwapParams::from_trade_with_fee_basis_points(
-        trade_info.pool,
-        trade_info.base_mint,
-        trade_info.quote_mint,
-        trade_info.pool_base_token_account,
-        trade_info.pool_quote_token_account,
-        trade_info.pool_base_token_reserves,
-        trade_info.pool_quote_token_reserves,
-        trade_info.virtual_quote_reserves,
-        trade_info.coin_creator_vault_ata,
-        trade_info.coin_creator_vault_authority,
-        trade_info.base_token_program,
-        trade_info.quote_token_program,
-        trade_info.protocol_fee_recipient,
-        Pubkey::default(),
-        trade_info.coin_creator,
-        trade_info.cashback_fee_basis_points != 0 || trade_info.cashback != 0,
-        trade_info.cashback_fee_basis_points,
-        trade_info.lp_fee_basis_points,
-        trade_info.protocol_fee_basis_points,
-        trade_info.coin_creator_fee_basis_points,
-    );
-    let mint = if trade_info.base_mint == sol_trade_sdk::constants::USDC_TOKEN_ACCOUNT
-        || trade_info.base_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-    {
-        trade_info.quote_mint
-    } else {
-        trade_info.base_mint
-    };
+    let params = PumpSwapParams::try_from(&trade_info)?;
+    let mint = params.traded_mint().expect("checked by try_from");
     pumpswap_trade_with_grpc(
         &client,
         &blockhash_cache,
@@ -387,35 +360,8 @@ async fn pumpswap_trade_with_grpc_sell_event(
     selection: EventSelection,
     trade_info: PumpSwapSellEvent,
 ) -> AnyResult<EventAction> {
-    let params = PumpSwapParams::from_trade_with_fee_basis_points(
-        trade_info.pool,
-        trade_info.base_mint,
-        trade_info.quote_mint,
-        trade_info.pool_base_token_account,
-        trade_info.pool_quote_token_account,
-        trade_info.pool_base_token_reserves,
-        trade_info.pool_quote_token_reserves,
-        trade_info.virtual_quote_reserves,
-        trade_info.coin_creator_vault_ata,
-        trade_info.coin_creator_vault_authority,
-        trade_info.base_token_program,
-        trade_info.quote_token_program,
-        trade_info.protocol_fee_recipient,
-        Pubkey::default(),
-        trade_info.coin_creator,
-        trade_info.cashback_fee_basis_points != 0 || trade_info.cashback != 0,
-        trade_info.cashback_fee_basis_points,
-        trade_info.lp_fee_basis_points,
-        trade_info.protocol_fee_basis_points,
-        trade_info.coin_creator_fee_basis_points,
-    );
-    let mint = if trade_info.base_mint == sol_trade_sdk::constants::USDC_TOKEN_ACCOUNT
-        || trade_info.base_mint == sol_trade_sdk::constants::WSOL_TOKEN_ACCOUNT
-    {
-        trade_info.quote_mint
-    } else {
-        trade_info.base_mint
-    };
+    let params = PumpSwapParams::try_from(&trade_info)?;
+    let mint = params.traded_mint().expect("checked by try_from");
     pumpswap_trade_with_grpc(
         &client,
         &blockhash_cache,
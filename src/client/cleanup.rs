@@ -0,0 +1,121 @@
+//! Post-snipe cleanup: sell the whole position, close the mint account, unwrap residual WSOL.
+//! 狙击后清理：卖出全部持仓、关闭 mint 代币账户（seed 或标准 ATA）、解包剩余 WSOL，并统计回收的 SOL。
+
+use super::{TradeSellParams, TradingClient, UtilityTxOptions};
+use crate::common::fast_fn::{
+    get_associated_token_address_with_program_id_fast,
+    get_associated_token_address_with_program_id_fast_use_seed,
+};
+use crate::swqos::common::TradeError;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+/// Result of [`TradingClient::sell_all_and_close_everything`].
+#[derive(Debug, Clone)]
+pub struct CleanupOutcome {
+    /// Token amount sold (the full balance); 0 when the account was empty or missing.
+    pub sold_amount: u64,
+    pub sell_signatures: Vec<Signature>,
+    /// Set when the sell failed; the mint account and WSOL are then left untouched.
+    pub sell_error: Option<TradeError>,
+    /// Separate close transaction, only sent when there was nothing to sell (a successful sell
+    /// closes the account in the same transaction).
+    pub close_signature: Option<String>,
+    /// WSOL ATA close transaction, when the payer still had one after the sell.
+    pub unwrap_signature: Option<String>,
+    /// Change of the payer SOL balance over the whole sequence, fees and tips included.
+    /// Negative when fees exceeded sale proceeds and reclaimed rent.
+    pub sol_recovered: i64,
+}
+
+impl TradingClient {
+    /// Sell the payer's full balance of `params.mint`, close its token account (seed or standard
+    /// ATA, following `use_seed_optimize`) and close the WSOL ATA to unwrap whatever is left.
+    ///
+    /// `params.input_token_amount` is replaced by the on-chain balance, and the sell is forced to
+    /// close the mint account and wait for confirmation. Cleanup transactions (empty-account close,
+    /// WSOL unwrap) use the fee settings in `options`.
+    pub async fn sell_all_and_close_everything(
+        &self,
+        mut params: TradeSellParams,
+        options: &UtilityTxOptions,
+    ) -> Result<CleanupOutcome, anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        let payer = self.payer.pubkey();
+        let mint = params.mint;
+        let sol_before = rpc.get_balance(&payer).await?;
+
+        let (token_program, _) =
+            crate::trading::common::get_mint_program_and_decimals(rpc, &mint).await?;
+        let token_account = get_associated_token_address_with_program_id_fast_use_seed(
+            &payer,
+            &mint,
+            &token_program,
+            self.use_seed_optimize,
+        );
+        let balance = match rpc.get_token_account_balance(&token_account).await {
+            Ok(balance) => Some(
+                balance
+                    .amount
+                    .parse::<u64>()
+                    .map_err(|_| anyhow::anyhow!("Failed to parse token balance"))?,
+            ),
+            Err(_) => None,
+        };
+
+        let mut outcome = CleanupOutcome {
+            sold_amount: 0,
+            sell_signatures: Vec::new(),
+            sell_error: None,
+            close_signature: None,
+            unwrap_signature: None,
+            sol_recovered: 0,
+        };
+
+        match balance {
+            Some(amount) if amount > 0 => {
+                params.input_token_amount = Some(amount);
+                params.fixed_output_token_amount = None;
+                params.close_mint_token_ata = true;
+                params.wait_tx_confirmed = true;
+                let (success, signatures, error, _) = self.sell(params).await?;
+                outcome.sell_signatures = signatures;
+                if !success {
+                    outcome.sell_error = error;
+                    outcome.sol_recovered =
+                        rpc.get_balance(&payer).await? as i64 - sol_before as i64;
+                    return Ok(outcome);
+                }
+                outcome.sold_amount = amount;
+            }
+            Some(_) => {
+                let close = crate::common::spl_token::close_account(
+                    &token_program,
+                    &token_account,
+                    &payer,
+                    &payer,
+                    &[],
+                )?;
+                outcome.close_signature =
+                    Some(self.send_utility_instructions(vec![close], options).await?);
+            }
+            None => {}
+        }
+
+        let wsol_account = get_associated_token_address_with_program_id_fast(
+            &payer,
+            &crate::constants::WSOL_TOKEN_ACCOUNT,
+            &crate::constants::TOKEN_PROGRAM,
+        );
+        let _wsol_guard =
+            crate::trading::common::wsol_manager::lock_wsol_account(&payer, true).await;
+        if rpc.get_account(&wsol_account).await.is_ok() {
+            let instructions = crate::trading::common::wsol_manager::close_wsol(&payer);
+            outcome.unwrap_signature =
+                Some(self.send_utility_instructions(instructions, options).await?);
+        }
+
+        outcome.sol_recovered = rpc.get_balance(&payer).await? as i64 - sol_before as i64;
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,115 @@
+//! Honeypot check: simulate a tiny sell of a held mint to see whether it can be sold at all.
+//!
+//! Freeze-authority honeypots freeze the buyer's token account; blacklist and transfer-hook
+//! honeypots let the account be but fail every transfer out of it. Both show up only when a sell
+//! is attempted, so [`TradingClient::can_sell`] builds the real sell transaction for a small
+//! amount and simulates it. The simulation needs tokens to move: run it right after a buy, or
+//! before one only when the wallet already holds some of the mint.
+//! 貔貅检测：模拟卖出少量持仓，判断代币是否可卖（冻结、黑名单、转账钩子拦截等）。
+
+use super::{PreflightError, TradeSellParams, TradingClient};
+use crate::trading::core::params::DexParamEnum;
+use solana_sdk::pubkey::Pubkey;
+
+/// Share of the balance [`TradingClient::can_sell`] simulates when no amount is given, in basis
+/// points (at least one base unit).
+pub const SELL_CHECK_DEFAULT_BPS: u64 = 100;
+
+/// Outcome of [`TradingClient::can_sell`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SellCheck {
+    /// The simulated sell succeeded.
+    Sellable,
+    /// The payer's token account is frozen by the mint's freeze authority.
+    Frozen { account: Pubkey },
+    /// The simulated sell failed; `reason` is the simulation error (e.g. a blacklist or transfer
+    /// hook rejecting the transfer, or a pool that refuses sells).
+    Rejected { reason: String },
+    /// The payer holds none of the mint, so there is nothing to simulate.
+    NoBalance,
+}
+
+impl SellCheck {
+    pub fn is_sellable(&self) -> bool {
+        matches!(self, Self::Sellable)
+    }
+}
+
+/// Tokens to simulate selling out of a non-zero `balance`.
+fn probe_amount(requested: Option<u64>, balance: u64) -> u64 {
+    requested
+        .unwrap_or_else(|| (balance as u128 * SELL_CHECK_DEFAULT_BPS as u128 / 10_000) as u64)
+        .clamp(1, balance)
+}
+
+impl TradingClient {
+    /// Simulate selling a small amount of `mint` with `params` (its `mint` is replaced) and report
+    /// whether the sell would go through.
+    ///
+    /// The probe sells `params.input_token_amount` when set, otherwise
+    /// [`SELL_CHECK_DEFAULT_BPS`] of the payer's balance. Nothing is sent: `simulate` is forced
+    /// on, no account is closed, no tip is paid, and the order id, slot gate and halt flags are
+    /// bypassed since no trade takes place. RPC and parameter errors are returned as `Err`.
+    pub async fn can_sell(
+        &self,
+        mint: Pubkey,
+        mut params: TradeSellParams,
+    ) -> Result<SellCheck, anyhow::Error> {
+        params.mint = mint;
+        let amount = params.input_token_amount.take();
+        params.simulate = true;
+        params.wait_tx_confirmed = false;
+        params.close_mint_token_ata = false;
+        params.close_output_token_ata = false;
+        params.with_tip = false;
+        params.reduce_only = true;
+        params.client_order_id = None;
+        params.event_slot = None;
+        params.grpc_recv_us = None;
+        params.latency_budget = None;
+        if let DexParamEnum::PumpFun(p) = &mut params.extension_params {
+            p.close_token_account_when_sell = Some(false);
+        }
+
+        match self.preflight_sell(&params).await {
+            Ok(()) => {}
+            Err(PreflightError::AccountFrozen { account, .. }) => {
+                return Ok(SellCheck::Frozen { account });
+            }
+            Err(PreflightError::MissingAccount { mint: missing, .. }) if missing == mint => {
+                return Ok(SellCheck::NoBalance);
+            }
+            Err(PreflightError::Rpc(e)) => return Err(anyhow::anyhow!(e)),
+            // Anything else (wrong token program, missing quote account) fails the simulation.
+            Err(_) => {}
+        }
+        let balance = self.payer_full_balance(&mint).await?;
+        if balance == 0 {
+            return Ok(SellCheck::NoBalance);
+        }
+        params.input_token_amount = Some(probe_amount(amount, balance));
+
+        let (ok, _, err, _) = self.sell_once(params, Vec::new(), None, None).await?;
+        Ok(if ok {
+            SellCheck::Sellable
+        } else {
+            SellCheck::Rejected {
+                reason: err.map(|e| e.message).unwrap_or_else(|| "simulation failed".to_string()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_amount_is_small_but_sellable() {
+        assert_eq!(probe_amount(None, 1_000_000), 10_000);
+        assert_eq!(probe_amount(None, 50), 1);
+        assert_eq!(probe_amount(Some(0), 50), 1);
+        assert_eq!(probe_amount(Some(500), 50), 50);
+        assert_eq!(probe_amount(Some(20), 50), 20);
+    }
+}
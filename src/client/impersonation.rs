@@ -0,0 +1,218 @@
+//! Funded simulation: dry-run trades at sizes the test wallet cannot cover.
+//!
+//! Public RPC has no account overrides, so the balances are borrowed instead: a
+//! [`SimulationFunding`] names wealthy wallets (a whale, an exchange hot wallet) and the amounts
+//! they lend, and the simulated transaction opens with transfers from them to the payer. Those
+//! wallets never sign; simulations skip signature checks, so the loans run like any other
+//! instruction and the trade sees the real accounts, pool state and compute usage at full size.
+//! A funded transaction is only ever simulated, never sent.
+//! 借款模拟：模拟交易前由大户钱包转入余额（无需其签名），以真实规模验证账户与计算单元。
+
+use super::{DetailedTradeResult, TradeBuyParams, TradeSellParams, TradingClient};
+use crate::common::fast_fn::{
+    create_associated_token_account_idempotent_fast_use_seed,
+    get_associated_token_address_with_program_id_fast,
+};
+use crate::common::spl_token::transfer_checked;
+use crate::constants::WSOL_TOKEN_ACCOUNT;
+use crate::trading::common::get_mint_program_and_decimals;
+use anyhow::Result;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_system_interface::instruction as system_instruction;
+use std::sync::Arc;
+
+/// Balances lent to the payer in simulations; see the module docs.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationFunding {
+    sol: Option<(Pubkey, u64)>,
+    tokens: Vec<TokenLoan>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenLoan {
+    owner: Pubkey,
+    mint: Pubkey,
+    amount: u64,
+}
+
+impl SimulationFunding {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lend `lamports` from the system account `source`.
+    pub fn with_sol(mut self, source: Pubkey, lamports: u64) -> Self {
+        self.sol = Some((source, lamports));
+        self
+    }
+
+    /// Lend `amount` base units of `mint` out of `owner`'s associated token account. The payer's
+    /// token account is created in the simulation when missing, so trades funded this way should
+    /// not create it themselves (`create_input_token_ata = false` on buys).
+    pub fn with_tokens(mut self, owner: Pubkey, mint: Pubkey, amount: u64) -> Self {
+        self.tokens.push(TokenLoan { owner, mint, amount });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sol.is_none() && self.tokens.is_empty()
+    }
+}
+
+impl TradingClient {
+    /// Lend the payer `funding` in every simulated trade of this client; live trades ignore it.
+    /// See [`TradingClient::simulate_buy_funded`] for a one-off dry run.
+    pub fn with_simulation_funding(mut self, funding: SimulationFunding) -> Self {
+        self.simulation_funding = Some(Arc::new(funding));
+        self
+    }
+
+    /// Simulate `params` with `funding` lent to the payer. `simulate` is forced on and the order
+    /// id, slot gate and latency budget are bypassed since nothing is sent.
+    pub async fn simulate_buy_funded(
+        &self,
+        mut params: TradeBuyParams,
+        funding: SimulationFunding,
+    ) -> DetailedTradeResult {
+        params.simulate = true;
+        params.wait_tx_confirmed = false;
+        params.client_order_id = None;
+        params.event_slot = None;
+        params.grpc_recv_us = None;
+        params.latency_budget = None;
+        self.clone()
+            .with_simulation_funding(funding)
+            .buy_once(params, None, Vec::new(), None, None)
+            .await
+    }
+
+    /// Sell counterpart of [`TradingClient::simulate_buy_funded`]: lend the tokens to sell (and
+    /// any SOL the sell needs) to check a full-size exit without holding the position.
+    pub async fn simulate_sell_funded(
+        &self,
+        mut params: TradeSellParams,
+        funding: SimulationFunding,
+    ) -> DetailedTradeResult {
+        params.simulate = true;
+        params.wait_tx_confirmed = false;
+        params.client_order_id = None;
+        params.event_slot = None;
+        params.grpc_recv_us = None;
+        params.latency_budget = None;
+        self.clone()
+            .with_simulation_funding(funding)
+            .sell_once(params, Vec::new(), None, None)
+            .await
+    }
+
+    /// Loan instructions that open a simulated trade; empty for live trades or without funding.
+    pub(super) async fn simulation_prelude(&self, simulate: bool) -> Result<Vec<Instruction>> {
+        let funding = match &self.simulation_funding {
+            Some(funding) if simulate && !funding.is_empty() => funding,
+            _ => return Ok(Vec::new()),
+        };
+        let rpc = &self.infrastructure.rpc;
+        let mut loans = Vec::with_capacity(funding.tokens.len());
+        for loan in &funding.tokens {
+            let (token_program, decimals) = get_mint_program_and_decimals(rpc, &loan.mint).await?;
+            let destination = self.payer_token_account(&loan.mint, &token_program);
+            let create = match rpc.get_account(&destination).await {
+                Ok(_) => None,
+                // Same address rule as payer_token_account: WSOL always uses the standard ATA.
+                Err(_) => Some(self.use_seed_optimize && loan.mint != WSOL_TOKEN_ACCOUNT),
+            };
+            loans.push(PreparedLoan { loan: *loan, token_program, decimals, destination, create });
+        }
+        prelude_instructions(&self.payer.pubkey(), funding.sol, &loans)
+    }
+}
+
+/// A token loan resolved against the chain.
+struct PreparedLoan {
+    loan: TokenLoan,
+    token_program: Pubkey,
+    decimals: u8,
+    /// The payer's token account for the mint.
+    destination: Pubkey,
+    /// `Some(use_seed)` when `destination` does not exist yet and must be created first.
+    create: Option<bool>,
+}
+
+/// Instructions lending SOL and tokens to `payer`.
+fn prelude_instructions(
+    payer: &Pubkey,
+    sol: Option<(Pubkey, u64)>,
+    loans: &[PreparedLoan],
+) -> Result<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    if let Some((source, lamports)) = sol {
+        instructions.push(system_instruction::transfer(&source, payer, lamports));
+    }
+    for PreparedLoan { loan, token_program, decimals, destination, create } in loans {
+        if let Some(use_seed) = create {
+            instructions.extend(create_associated_token_account_idempotent_fast_use_seed(
+                payer,
+                payer,
+                &loan.mint,
+                token_program,
+                *use_seed,
+            ));
+        }
+        let source = get_associated_token_address_with_program_id_fast(
+            &loan.owner,
+            &loan.mint,
+            token_program,
+        );
+        instructions.push(transfer_checked(
+            token_program,
+            &source,
+            &loan.mint,
+            destination,
+            &loan.owner,
+            loan.amount,
+            *decimals,
+            &[],
+        )?);
+    }
+    Ok(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TOKEN_PROGRAM;
+
+    #[test]
+    fn loans_move_out_of_the_lenders_accounts_to_the_payer() {
+        let payer = Pubkey::new_unique();
+        let whale = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let loan = PreparedLoan {
+            loan: TokenLoan { owner: whale, mint, amount: 5_000_000 },
+            token_program: TOKEN_PROGRAM,
+            decimals: 6,
+            destination: Pubkey::new_unique(),
+            create: Some(false),
+        };
+        let instructions =
+            prelude_instructions(&payer, Some((whale, 10_000_000_000)), &[loan]).unwrap();
+        assert_eq!(instructions.len(), 3);
+
+        // SOL loan: the lender is the source; its signature is left empty in the simulation.
+        assert_eq!(instructions[0].accounts[0].pubkey, whale);
+        assert_eq!(instructions[0].accounts[1].pubkey, payer);
+        // The payer pays for its own token account.
+        assert!(instructions[1].accounts.iter().any(|a| a.pubkey == payer && a.is_signer));
+        // Token loan: moved out of the whale's ATA under the whale's authority.
+        let transfer = &instructions[2];
+        assert_eq!(transfer.program_id, TOKEN_PROGRAM);
+        assert_eq!(
+            transfer.accounts[0].pubkey,
+            get_associated_token_address_with_program_id_fast(&whale, &mint, &TOKEN_PROGRAM)
+        );
+        assert_eq!(transfer.accounts[3].pubkey, whale);
+        assert!(transfer.accounts[3].is_signer);
+    }
+}
@@ -0,0 +1,317 @@
+//! Leveraged long/short on marginfi: deposit collateral, borrow, swap through the normal trade path.
+//! 杠杆多空：在 marginfi 存入抵押物并借款，再用现有交易执行器完成兑换；平仓时反向兑换后还款取回抵押物。
+//!
+//! Long token X: collateral USDC, borrow SOL (WSOL account), [`LeverageSwap::Buy`] X with it.
+//! Short token X: borrow X, [`LeverageSwap::Sell`] it; close by buying X back.
+//!
+//! Only marginfi is supported. Kamino lending needs obligation accounts and reserve refreshes
+//! that this module does not build; Kamino appears in the SDK only as a flash-loan source
+//! ([`crate::instruction::flashloan`]).
+
+use super::{TradeBuyParams, TradeSellParams, TradeTokenType, TradingClient, UtilityTxOptions};
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast;
+use crate::constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::marginfi::{self, MarginfiAccounts};
+use crate::swqos::common::TradeError;
+use crate::trading::common::close_wsol;
+use crate::trading::factory::DexType;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+/// One collateral bank and one borrow bank on a marginfi account owned by the payer.
+#[derive(Debug, Clone)]
+pub struct LeveragedPosition {
+    pub group: Pubkey,
+    pub marginfi_account: Pubkey,
+    pub collateral_bank: Pubkey,
+    /// Payer token account for the collateral mint.
+    pub collateral_token_account: Pubkey,
+    pub collateral_token_program: Pubkey,
+    pub collateral_amount: u64,
+    pub borrow_bank: Pubkey,
+    /// Payer token account receiving the borrow (WSOL ATA when borrowing SOL).
+    pub borrow_token_account: Pubkey,
+    pub borrow_token_program: Pubkey,
+    pub borrow_amount: u64,
+    /// Bank/oracle pairs of every active balance after the borrow (collateral and borrow bank
+    /// included); marginfi rejects the borrow without them.
+    pub health_accounts: Vec<Pubkey>,
+}
+
+impl LeveragedPosition {
+    fn collateral(&self, authority: Pubkey) -> MarginfiAccounts {
+        MarginfiAccounts {
+            group: self.group,
+            marginfi_account: self.marginfi_account,
+            authority,
+            bank: self.collateral_bank,
+            token_account: self.collateral_token_account,
+            token_program: self.collateral_token_program,
+        }
+    }
+
+    fn debt(&self, authority: Pubkey) -> MarginfiAccounts {
+        MarginfiAccounts {
+            group: self.group,
+            marginfi_account: self.marginfi_account,
+            authority,
+            bank: self.borrow_bank,
+            token_account: self.borrow_token_account,
+            token_program: self.borrow_token_program,
+        }
+    }
+
+    /// Deposit the collateral and borrow `borrow_amount` in one transaction.
+    pub fn open_instructions(&self, authority: Pubkey) -> Vec<Instruction> {
+        vec![
+            marginfi::deposit(&self.collateral(authority), self.collateral_amount),
+            marginfi::borrow(&self.debt(authority), self.borrow_amount, &self.health_accounts),
+        ]
+    }
+
+    /// Point a long's buy at the borrowed WSOL instead of wrapping fresh SOL.
+    ///
+    /// Applies when the borrow lands in the payer's WSOL ATA and the buy pays with SOL or WSOL.
+    /// PumpFun spends native SOL, so the loan is unwrapped into the payer ahead of the swap;
+    /// every other DEX spends the WSOL account directly.
+    fn spend_borrowed_wsol(&self, params: &mut TradeBuyParams, payer: &Pubkey) {
+        let wsol_ata = get_associated_token_address_with_program_id_fast(
+            payer,
+            &WSOL_TOKEN_ACCOUNT,
+            &TOKEN_PROGRAM,
+        );
+        if self.borrow_token_account != wsol_ata
+            || !matches!(params.input_token_type, TradeTokenType::SOL | TradeTokenType::WSOL)
+        {
+            return;
+        }
+        params.create_input_token_ata = false;
+        if params.dex_type == DexType::PumpFun {
+            params.input_token_type = TradeTokenType::SOL;
+            params.close_input_token_ata = false;
+            params.pre_instructions.splice(0..0, close_wsol(payer));
+        } else {
+            params.input_token_type = TradeTokenType::WSOL;
+        }
+    }
+
+    /// Repay the whole debt (interest included) and withdraw all collateral.
+    ///
+    /// `remaining_health_accounts` are the pairs of balances the account still holds afterwards;
+    /// empty when this position was its only one.
+    pub fn close_instructions(
+        &self,
+        authority: Pubkey,
+        remaining_health_accounts: &[Pubkey],
+    ) -> Vec<Instruction> {
+        vec![
+            marginfi::repay(&self.debt(authority), 0, true),
+            marginfi::withdraw(&self.collateral(authority), 0, true, remaining_health_accounts),
+        ]
+    }
+}
+
+/// Swap leg of a leveraged position, executed with [`TradingClient::buy`] / [`TradingClient::sell`].
+#[derive(Clone)]
+pub enum LeverageSwap {
+    Buy(Box<TradeBuyParams>),
+    Sell(Box<TradeSellParams>),
+}
+
+/// Result of opening or closing a leveraged position.
+#[derive(Debug, Clone)]
+pub struct LeverageOutcome {
+    /// Deposit+borrow or repay+withdraw transaction. `None` when closing stopped at a failed swap.
+    pub lending_signature: Option<String>,
+    pub swap_success: bool,
+    pub swap_signatures: Vec<Signature>,
+    pub swap_error: Option<TradeError>,
+}
+
+impl TradingClient {
+    /// Open a leveraged position: deposit + borrow, then swap the borrowed amount.
+    ///
+    /// The swap's `input_token_amount` is set to `borrow_amount`. A buy paid in SOL spends the
+    /// borrowed WSOL rather than wrapping the payer's own SOL. If the swap fails the loan stays
+    /// open with the borrowed funds in `borrow_token_account`; check `swap_success` and retry the
+    /// swap or close the position.
+    pub async fn open_leveraged_position(
+        &self,
+        position: &LeveragedPosition,
+        swap: LeverageSwap,
+        options: &UtilityTxOptions,
+    ) -> Result<LeverageOutcome, anyhow::Error> {
+        if position.borrow_amount == 0 {
+            return Err(anyhow::anyhow!("borrow_amount must be greater than 0"));
+        }
+        let lending_signature = self
+            .send_utility_instructions(position.open_instructions(self.payer.pubkey()), options)
+            .await?;
+        let (swap_success, swap_signatures, swap_error) = match swap {
+            LeverageSwap::Buy(mut params) => {
+                params.input_token_amount = Some(position.borrow_amount);
+                position.spend_borrowed_wsol(&mut params, &self.payer.pubkey());
+                let (ok, sigs, err, _) = self.buy(*params).await?;
+                (ok, sigs, err)
+            }
+            LeverageSwap::Sell(mut params) => {
+                params.input_token_amount = Some(position.borrow_amount);
+                let (ok, sigs, err, _) = self.sell(*params).await?;
+                (ok, sigs, err)
+            }
+        };
+        Ok(LeverageOutcome {
+            lending_signature: Some(lending_signature),
+            swap_success,
+            swap_signatures,
+            swap_error,
+        })
+    }
+
+    /// Close a leveraged position: swap back into the borrowed asset, then repay all + withdraw all.
+    ///
+    /// The swap must leave enough in `borrow_token_account` to cover the debt with interest (for a
+    /// short, buy with `fixed_output_token_amount`). Nothing is repaid when the swap fails.
+    pub async fn close_leveraged_position(
+        &self,
+        position: &LeveragedPosition,
+        swap: LeverageSwap,
+        remaining_health_accounts: &[Pubkey],
+        options: &UtilityTxOptions,
+    ) -> Result<LeverageOutcome, anyhow::Error> {
+        let (swap_success, swap_signatures, swap_error) = match swap {
+            LeverageSwap::Buy(params) => {
+                let (ok, sigs, err, _) = self.buy(*params).await?;
+                (ok, sigs, err)
+            }
+            LeverageSwap::Sell(params) => {
+                let (ok, sigs, err, _) = self.sell(*params).await?;
+                (ok, sigs, err)
+            }
+        };
+        if !swap_success {
+            return Ok(LeverageOutcome {
+                lending_signature: None,
+                swap_success,
+                swap_signatures,
+                swap_error,
+            });
+        }
+        let lending_signature = self
+            .send_utility_instructions(
+                position.close_instructions(self.payer.pubkey(), remaining_health_accounts),
+                options,
+            )
+            .await?;
+        Ok(LeverageOutcome {
+            lending_signature: Some(lending_signature),
+            swap_success,
+            swap_signatures,
+            swap_error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::dummy_pumpfun_params;
+    use super::*;
+    use crate::client::{BuyAmount, SimpleBuyParams};
+    use crate::common::GasFeeStrategy;
+    use solana_hash::Hash;
+
+    fn position() -> LeveragedPosition {
+        LeveragedPosition {
+            group: Pubkey::new_unique(),
+            marginfi_account: Pubkey::new_unique(),
+            collateral_bank: Pubkey::new_unique(),
+            collateral_token_account: Pubkey::new_unique(),
+            collateral_token_program: TOKEN_PROGRAM,
+            collateral_amount: 100_000_000,
+            borrow_bank: Pubkey::new_unique(),
+            borrow_token_account: Pubkey::new_unique(),
+            borrow_token_program: TOKEN_PROGRAM,
+            borrow_amount: 1_000_000_000,
+            health_accounts: vec![Pubkey::new_unique(); 4],
+        }
+    }
+
+    #[test]
+    fn open_deposits_collateral_then_borrows() {
+        let position = position();
+        let ixs = position.open_instructions(Pubkey::new_unique());
+
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].data[..8], marginfi::DEPOSIT_DISCRIMINATOR);
+        assert_eq!(ixs[0].accounts[3].pubkey, position.collateral_bank);
+        assert_eq!(ixs[1].data[..8], marginfi::BORROW_DISCRIMINATOR);
+        assert_eq!(ixs[1].data[8..16], position.borrow_amount.to_le_bytes());
+        assert_eq!(ixs[1].accounts[3].pubkey, position.borrow_bank);
+        assert_eq!(ixs[1].accounts.len(), 8 + position.health_accounts.len());
+    }
+
+    fn long_buy(dex_type: DexType, pay_with: TradeTokenType) -> TradeBuyParams {
+        let mut params: TradeBuyParams = SimpleBuyParams::new(
+            dex_type,
+            pay_with,
+            Pubkey::new_unique(),
+            BuyAmount::WithMaxInput { quote_amount: 1 },
+            dummy_pumpfun_params(),
+            Hash::new_unique(),
+            GasFeeStrategy::new(),
+        )
+        .into();
+        params.create_input_token_ata = true;
+        params
+    }
+
+    #[test]
+    fn long_buy_spends_the_borrowed_wsol() {
+        let payer = Pubkey::new_unique();
+        let mut position = position();
+        position.borrow_token_account = get_associated_token_address_with_program_id_fast(
+            &payer,
+            &WSOL_TOKEN_ACCOUNT,
+            &TOKEN_PROGRAM,
+        );
+
+        let mut params = long_buy(DexType::PumpSwap, TradeTokenType::SOL);
+        position.spend_borrowed_wsol(&mut params, &payer);
+        assert!(matches!(params.input_token_type, TradeTokenType::WSOL));
+        assert!(!params.create_input_token_ata);
+        assert!(params.pre_instructions.is_empty());
+
+        let mut params = long_buy(DexType::PumpFun, TradeTokenType::WSOL);
+        params.close_input_token_ata = true;
+        position.spend_borrowed_wsol(&mut params, &payer);
+        assert!(matches!(params.input_token_type, TradeTokenType::SOL));
+        assert!(!params.create_input_token_ata);
+        assert!(!params.close_input_token_ata);
+        assert_eq!(params.pre_instructions, close_wsol(&payer));
+    }
+
+    #[test]
+    fn buy_from_another_borrow_account_is_left_alone() {
+        let payer = Pubkey::new_unique();
+        let position = position();
+
+        let mut params = long_buy(DexType::PumpSwap, TradeTokenType::SOL);
+        position.spend_borrowed_wsol(&mut params, &payer);
+        assert!(matches!(params.input_token_type, TradeTokenType::SOL));
+        assert!(params.create_input_token_ata);
+    }
+
+    #[test]
+    fn close_repays_all_then_withdraws_all() {
+        let position = position();
+        let ixs = position.close_instructions(Pubkey::new_unique(), &[]);
+
+        assert_eq!(ixs[0].data[..8], marginfi::REPAY_DISCRIMINATOR);
+        assert_eq!(ixs[0].data[16..], [1, 1]);
+        assert_eq!(ixs[1].data[..8], marginfi::WITHDRAW_DISCRIMINATOR);
+        assert_eq!(ixs[1].data[16..], [1, 1]);
+        assert_eq!(ixs[1].accounts.len(), 8);
+    }
+}
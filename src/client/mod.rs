@@ -1,21 +1,40 @@
 //! High-level [`TradingClient`], [`TradingInfrastructure`], and trade parameter types.
 
+use crate::common::landing_model::LandingFeeModel;
+use crate::common::latency_budget::LatencyBudget;
 use crate::common::nonce_cache::DurableNonceInfo;
+use crate::common::order_dedup::{OrderClaim, OrderDedup, OrderIdStore};
+use crate::common::risk_manager::RiskManager;
+use crate::common::rpc_usage::{MeteredRpcSender, RpcCreditTable, RpcUsage, RpcUsageStats};
 use crate::common::sdk_log;
-use crate::common::GasFeeStrategy;
+use crate::common::slot_tracker::{SlotGate, SlotTracker};
+use crate::common::state_snapshot::StateSnapshot;
+use crate::common::trade_events::{TradeEventBus, TradeExecuted};
+use crate::common::trading_halt::TradingHalt;
 use crate::common::SolanaRpcClient;
-use crate::common::{InfrastructureConfig, TradeConfig};
+use crate::common::{Congestion, ExplorerCluster, GasFeeModel, GasFeeStrategy, SwqosSubmitTiming};
+use crate::common::{
+    InfrastructureConfig, StaleReserveAction, StaleReserveGuard, TradeConfig, TransactionSplit,
+};
 #[cfg(feature = "perf-trace")]
 use crate::constants::trade::trade::DEFAULT_SLIPPAGE;
 use crate::constants::SOL_TOKEN_ACCOUNT;
 use crate::constants::USD1_TOKEN_ACCOUNT;
 use crate::constants::USDC_TOKEN_ACCOUNT;
 use crate::constants::WSOL_TOKEN_ACCOUNT;
-use crate::swqos::common::TradeError;
+use crate::perf::thread_tuning::HotPathTuning;
+use crate::swqos::common::{
+    ConfirmationLevel, ConfirmationOptions, HttpClientTuning, RpcSendOptions, TradeError,
+    RPC_SEND_OPTIONS,
+};
 use crate::swqos::SwqosClient;
 use crate::swqos::SwqosConfig;
 use crate::swqos::SwqosType;
 use crate::swqos::TradeType;
+use crate::trading::account_discovery::AccountDiscovery;
+use crate::trading::common::fill::Fill;
+use crate::trading::common::ComputeBudgetManager;
+use crate::trading::common::{WsolGuard, WsolLockMode};
 use crate::trading::core::params::BonkParams;
 use crate::trading::core::params::DexParamEnum;
 use crate::trading::core::params::MeteoraDammV2Params;
@@ -23,20 +42,50 @@ use crate::trading::core::params::PumpFunParams;
 use crate::trading::core::params::PumpSwapParams;
 use crate::trading::core::params::RaydiumAmmV4Params;
 use crate::trading::core::params::RaydiumCpmmParams;
+use crate::trading::core::signing_pool::SigningPool;
 use crate::trading::factory::DexType;
+use crate::trading::fee_sponsor::SendMode;
 use crate::trading::MiddlewareManager;
 use crate::trading::SwapParams;
 use crate::trading::TradeFactory;
+use arc_swap::{ArcSwap, ArcSwapOption};
+use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use rustls::crypto::{ring::default_provider, CryptoProvider};
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::rpc_client::RpcClientConfig;
 use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::message::AddressLookupTableAccount;
 use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signature};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 #[allow(unused_imports)]
 use tracing::{debug, error, info, warn};
 
+mod cleanup;
+mod honeypot;
+mod impersonation;
+mod leverage;
+mod offline;
+mod plan;
+mod preflight;
+mod provisioning;
+mod queue;
+pub use cleanup::CleanupOutcome;
+pub use honeypot::{SellCheck, SELL_CHECK_DEFAULT_BPS};
+pub use impersonation::SimulationFunding;
+pub use leverage::{LeverageOutcome, LeverageSwap, LeveragedPosition};
+pub use offline::{OfflineSignature, OfflineTransaction};
+pub use plan::{AccountAction, AccountActionKind, FeeLane, TradePlan};
+pub use preflight::PreflightError;
+pub use provisioning::{AtaProvisioning, ATAS_PER_TRANSACTION};
+pub use queue::{
+    QueuedTrade, QueuedTradeResult, TradePriority, TradeQueue, TradeQueueConfig, TradeQueueStats,
+};
+
 /// Single place to validate that protocol params match the given DEX type (avoids duplicate match in buy/sell).
 #[inline(always)]
 fn validate_protocol_params(dex_type: DexType, params: &DexParamEnum) -> bool {
@@ -73,13 +122,64 @@ pub async fn find_pool_by_mint(
     }
 }
 
+/// How the wallet holds the quote side of a trade.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteKind {
+    /// Native lamports, wrapped/unwrapped around the swap.
+    Native,
+    /// An SPL token account (WSOL, stables, LSTs, ...).
+    Spl,
+}
+
+/// A quote token the chosen DEX builder cannot route, rejected by [`TradingClient::buy`] /
+/// [`TradingClient::sell`] before anything is built (downcast the `anyhow::Error` to match it).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedQuoteError {
+    pub dex_type: DexType,
+    pub quote_mint: Pubkey,
+}
+
+impl std::fmt::Display for UnsupportedQuoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "quote mint {} is not supported on {:?}", self.quote_mint, self.dex_type)
+    }
+}
+
+impl std::error::Error for UnsupportedQuoteError {}
+
 /// Type of the token to buy
-#[derive(Clone, PartialEq)]
+///
+/// `SOL`/`WSOL`/`USD1`/`USDC` are shortcuts for the common quotes; `Spl(mint)` trades pools
+/// quoted in any other SPL mint (e.g. JitoSOL) on Raydium CPMM/AMM v4 and Meteora DAMM v2.
+#[derive(Debug, Clone, PartialEq)]
 pub enum TradeTokenType {
     SOL,
     WSOL,
     USD1,
     USDC,
+    Spl(Pubkey),
+}
+
+impl TradeTokenType {
+    /// Mint passed to the instruction builders (`SOL` keeps the native sentinel).
+    #[inline]
+    pub fn quote_mint(&self) -> Pubkey {
+        match self {
+            TradeTokenType::SOL => SOL_TOKEN_ACCOUNT,
+            TradeTokenType::WSOL => WSOL_TOKEN_ACCOUNT,
+            TradeTokenType::USD1 => USD1_TOKEN_ACCOUNT,
+            TradeTokenType::USDC => USDC_TOKEN_ACCOUNT,
+            TradeTokenType::Spl(mint) => *mint,
+        }
+    }
+
+    #[inline]
+    pub fn quote_kind(&self) -> QuoteKind {
+        match self {
+            TradeTokenType::SOL => QuoteKind::Native,
+            _ => QuoteKind::Spl,
+        }
+    }
 }
 
 /// Account lifecycle policy for high-level trade requests.
@@ -141,6 +241,9 @@ pub enum BuyAmount {
     /// calculation while letting the chain fail if the max quote budget is
     /// exceeded.
     WithMaxInput { quote_amount: u64 },
+    /// Spend the payer's whole quote token balance (WSOL/USDC/USD1/SPL account); slippage applies
+    /// to minimum output. Not available when paying with native SOL.
+    AllQuote,
 }
 
 /// High-level sell sizing intent.
@@ -159,6 +262,29 @@ pub enum SellAmount {
     /// Example: receive exactly `0.1 SOL` or `100 USDC`, but spend no more than
     /// `max_input_amount` token base units.
     ExactOutput { output_amount: u64, max_input_amount: u64 },
+    /// Sell the payer's whole token balance, read from chain right before building.
+    All,
+}
+
+/// Amount for [`TradingClient::withdraw`] and [`TradingClient::withdraw_token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawAmount {
+    /// Withdraw exactly this many base units (lamports for SOL).
+    Exact(u64),
+    /// Sweep the whole balance. For SOL, the transaction fee is kept back.
+    All,
+}
+
+/// SPL Memo appended to a trade so on-chain analytics can attribute the fill to a strategy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TradeMemo {
+    /// Use the client's tag ([`TradingClient::with_memo_tag`]); no memo when it has none.
+    #[default]
+    ClientDefault,
+    /// Tag this trade with the given text (strategy id, order id, ...).
+    Tag(String),
+    /// No memo even when the client has a tag, for size-critical transactions.
+    Off,
 }
 
 /// Simpler buy request that describes trade intent instead of low-level ATA flags.
@@ -184,6 +310,9 @@ pub struct SimpleBuyParams {
     pub amount: BuyAmount,
     /// Optional slippage in basis points. `100` means 1%.
     pub slippage_basis_points: Option<u64>,
+    /// Minimum output of an exact-input trade, replacing the slippage-derived one. Required on
+    /// Meteora DAMM v2, which has no client-side quote.
+    pub min_output_amount: Option<u64>,
     /// Recent blockhash for non-nonce transactions.
     ///
     /// The SDK intentionally does not fetch blockhash on the hot path. Use
@@ -194,6 +323,10 @@ pub struct SimpleBuyParams {
     pub extension_params: DexParamEnum,
     /// Compute unit price/limit and relay tip configuration.
     pub gas_fee_strategy: GasFeeStrategy,
+    /// Loaded-accounts data size limit in bytes; overrides the strategy's per-DEX value.
+    pub data_size_limit: Option<u32>,
+    /// Memo tag appended to the transaction. See [`TradeMemo`].
+    pub memo: TradeMemo,
     /// ATA creation/close behavior. See [`AccountPolicy`].
     pub account_policy: AccountPolicy,
     /// Optional Address Lookup Tables to reduce transaction size.
@@ -201,6 +334,10 @@ pub struct SimpleBuyParams {
     pub address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     /// Wait until the transaction is confirmed before returning.
     pub wait_tx_confirmed: bool,
+    /// Level and timeout of that wait. Default: `confirmed` within 15 s.
+    pub confirmation: ConfirmationOptions,
+    /// `sendTransaction` settings of the `Default` (RPC) route; `None` uses the client's.
+    pub rpc_send_options: Option<RpcSendOptions>,
     /// Wait for every SWQoS route's submit response so all signatures can be
     /// returned. Useful when confirming through poll-any semantics or monitoring
     /// route variants externally. Recent-blockhash variants are not mutually
@@ -212,6 +349,17 @@ pub struct SimpleBuyParams {
     pub simulate: bool,
     /// Optional upstream receive timestamp in microseconds for latency tracing.
     pub grpc_recv_us: Option<i64>,
+    /// Slot of the triggering event, checked against the client's slot gate.
+    pub event_slot: Option<u64>,
+    /// Idempotency key; a repeat within the client's dedup window is rejected.
+    pub client_order_id: Option<String>,
+    /// End-to-end latency budget checked after each stage. Keep a clone of the budget to read
+    /// its [`LatencyBudget::report`] after the trade returns.
+    pub latency_budget: Option<LatencyBudget>,
+    /// Bypass the client's risk limits for this trade (manual trades). Still recorded.
+    pub skip_risk_checks: bool,
+    /// Separate fee payer for this trade; see [`TradeBuyParams::fee_payer`].
+    pub fee_payer: Option<Arc<Keypair>>,
 }
 
 /// Simpler sell request that describes trade intent instead of low-level ATA flags.
@@ -230,12 +378,19 @@ pub struct SimpleSellParams {
     pub amount: SellAmount,
     /// Optional slippage in basis points. `100` means 1%.
     pub slippage_basis_points: Option<u64>,
+    /// Minimum output of an exact-input trade, replacing the slippage-derived one. Required on
+    /// Meteora DAMM v2, which has no client-side quote.
+    pub min_output_amount: Option<u64>,
     /// Recent blockhash for non-nonce transactions.
     pub recent_blockhash: Option<Hash>,
     /// Protocol-specific parameters, for example `DexParamEnum::PumpFun(...)`.
     pub extension_params: DexParamEnum,
     /// Compute unit price/limit and relay tip configuration.
     pub gas_fee_strategy: GasFeeStrategy,
+    /// Loaded-accounts data size limit in bytes; overrides the strategy's per-DEX value.
+    pub data_size_limit: Option<u32>,
+    /// Memo tag appended to the transaction. See [`TradeMemo`].
+    pub memo: TradeMemo,
     /// ATA creation/close behavior. See [`AccountPolicy`].
     pub account_policy: AccountPolicy,
     /// Optional Address Lookup Tables to reduce transaction size.
@@ -243,6 +398,10 @@ pub struct SimpleSellParams {
     pub address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     /// Wait until the transaction is confirmed before returning.
     pub wait_tx_confirmed: bool,
+    /// Level and timeout of that wait. Default: `confirmed` within 15 s.
+    pub confirmation: ConfirmationOptions,
+    /// `sendTransaction` settings of the `Default` (RPC) route; `None` uses the client's.
+    pub rpc_send_options: Option<RpcSendOptions>,
     /// Wait for every SWQoS route's submit response so all signatures can be
     /// returned. Useful when confirming through poll-any semantics or monitoring
     /// route variants externally. Recent-blockhash variants are not mutually
@@ -256,6 +415,17 @@ pub struct SimpleSellParams {
     pub with_tip: bool,
     /// Optional upstream receive timestamp in microseconds for latency tracing.
     pub grpc_recv_us: Option<i64>,
+    /// Slot of the triggering event, checked against the client's slot gate.
+    pub event_slot: Option<u64>,
+    /// Idempotency key; a repeat within the client's dedup window is rejected.
+    pub client_order_id: Option<String>,
+    /// End-to-end latency budget checked after each stage. Keep a clone of the budget to read
+    /// its [`LatencyBudget::report`] after the trade returns.
+    pub latency_budget: Option<LatencyBudget>,
+    /// Risk-reducing sell: still sent while trading is halted ([`TradingClient::halt`]).
+    pub reduce_only: bool,
+    /// Separate fee payer for this trade; see [`TradeBuyParams::fee_payer`].
+    pub fee_payer: Option<Arc<Keypair>>,
 }
 
 impl SimpleBuyParams {
@@ -283,16 +453,26 @@ impl SimpleBuyParams {
             mint,
             amount,
             slippage_basis_points: None,
+            min_output_amount: None,
             recent_blockhash: Some(recent_blockhash),
             extension_params,
             gas_fee_strategy,
+            data_size_limit: None,
+            memo: TradeMemo::ClientDefault,
             account_policy: AccountPolicy::Auto,
             address_lookup_table_accounts: Vec::new(),
             wait_tx_confirmed: false,
+            confirmation: ConfirmationOptions::default(),
+            rpc_send_options: None,
             wait_for_all_submits: false,
             durable_nonce: None,
             simulate: false,
             grpc_recv_us: None,
+            event_slot: None,
+            client_order_id: None,
+            latency_budget: None,
+            skip_risk_checks: false,
+            fee_payer: None,
         }
     }
 
@@ -329,6 +509,12 @@ impl SimpleBuyParams {
         self
     }
 
+    /// Set the minimum output of an exact-input trade directly instead of via slippage.
+    pub fn min_output(mut self, amount: u64) -> Self {
+        self.min_output_amount = Some(amount);
+        self
+    }
+
     /// Set account lifecycle behavior. Bots usually want `HotPathMinimal`;
     /// normal integrations can keep the default `Auto`.
     pub fn account_policy(mut self, value: AccountPolicy) -> Self {
@@ -359,6 +545,21 @@ impl SimpleBuyParams {
         self
     }
 
+    /// Wait for confirmation at `level` for up to `timeout` before returning (implies
+    /// `wait_tx_confirmed`). A timeout fails with `CONFIRMATION_TIMEOUT_ERROR_CODE`.
+    pub fn confirmation(mut self, level: ConfirmationLevel, timeout: std::time::Duration) -> Self {
+        self.wait_tx_confirmed = true;
+        self.confirmation = ConfirmationOptions::new(level, timeout);
+        self
+    }
+
+    /// Override the client's `sendTransaction` settings on the `Default` (RPC) route, e.g. to
+    /// enable preflight or set the node's retry count for this trade.
+    pub fn rpc_send_options(mut self, options: RpcSendOptions) -> Self {
+        self.rpc_send_options = Some(options);
+        self
+    }
+
     /// Wait for all SWQoS submit responses and return submitted signatures.
     pub fn wait_for_all_submits(mut self, value: bool) -> Self {
         self.wait_for_all_submits = value;
@@ -376,6 +577,48 @@ impl SimpleBuyParams {
         self.grpc_recv_us = Some(value);
         self
     }
+
+    /// Set the loaded-accounts data size limit in bytes, overriding the gas fee strategy.
+    pub fn data_size_limit(mut self, value: u32) -> Self {
+        self.data_size_limit = Some(value);
+        self
+    }
+
+    /// Tag this trade with an SPL Memo, overriding the client's tag.
+    pub fn memo(mut self, tag: impl Into<String>) -> Self {
+        self.memo = TradeMemo::Tag(tag.into());
+        self
+    }
+
+    /// Send without a memo even when the client has a tag.
+    pub fn without_memo(mut self) -> Self {
+        self.memo = TradeMemo::Off;
+        self
+    }
+
+    /// Attach the slot of the triggering event for slot-aware gating.
+    pub fn event_slot(mut self, value: u64) -> Self {
+        self.event_slot = Some(value);
+        self
+    }
+
+    /// Attach an idempotency key checked against the client's order dedup store.
+    pub fn client_order_id(mut self, value: impl Into<String>) -> Self {
+        self.client_order_id = Some(value.into());
+        self
+    }
+
+    /// Attach an end-to-end latency budget (build, sign, serialize, submit).
+    pub fn latency_budget(mut self, value: LatencyBudget) -> Self {
+        self.latency_budget = Some(value);
+        self
+    }
+
+    /// Bypass the client's risk limits (manual trades).
+    pub fn skip_risk_checks(mut self, value: bool) -> Self {
+        self.skip_risk_checks = value;
+        self
+    }
 }
 
 impl SimpleSellParams {
@@ -404,17 +647,27 @@ impl SimpleSellParams {
             mint,
             amount,
             slippage_basis_points: None,
+            min_output_amount: None,
             recent_blockhash: Some(recent_blockhash),
             extension_params,
             gas_fee_strategy,
+            data_size_limit: None,
+            memo: TradeMemo::ClientDefault,
             account_policy: AccountPolicy::Auto,
             address_lookup_table_accounts: Vec::new(),
             wait_tx_confirmed: false,
+            confirmation: ConfirmationOptions::default(),
+            rpc_send_options: None,
             wait_for_all_submits: false,
             durable_nonce: None,
             simulate: false,
             with_tip: true,
             grpc_recv_us: None,
+            event_slot: None,
+            client_order_id: None,
+            latency_budget: None,
+            reduce_only: false,
+            fee_payer: None,
         }
     }
 
@@ -449,6 +702,12 @@ impl SimpleSellParams {
         self
     }
 
+    /// Set the minimum output of an exact-input trade directly instead of via slippage.
+    pub fn min_output(mut self, amount: u64) -> Self {
+        self.min_output_amount = Some(amount);
+        self
+    }
+
     /// Set account lifecycle behavior. Bots usually want `HotPathMinimal`;
     /// normal integrations can keep the default `Auto`.
     pub fn account_policy(mut self, value: AccountPolicy) -> Self {
@@ -479,6 +738,21 @@ impl SimpleSellParams {
         self
     }
 
+    /// Wait for confirmation at `level` for up to `timeout` before returning (implies
+    /// `wait_tx_confirmed`). A timeout fails with `CONFIRMATION_TIMEOUT_ERROR_CODE`.
+    pub fn confirmation(mut self, level: ConfirmationLevel, timeout: std::time::Duration) -> Self {
+        self.wait_tx_confirmed = true;
+        self.confirmation = ConfirmationOptions::new(level, timeout);
+        self
+    }
+
+    /// Override the client's `sendTransaction` settings on the `Default` (RPC) route, e.g. to
+    /// enable preflight or set the node's retry count for this trade.
+    pub fn rpc_send_options(mut self, options: RpcSendOptions) -> Self {
+        self.rpc_send_options = Some(options);
+        self
+    }
+
     /// Wait for all SWQoS submit responses and return submitted signatures.
     pub fn wait_for_all_submits(mut self, value: bool) -> Self {
         self.wait_for_all_submits = value;
@@ -502,6 +776,166 @@ impl SimpleSellParams {
         self.grpc_recv_us = Some(value);
         self
     }
+
+    /// Set the loaded-accounts data size limit in bytes, overriding the gas fee strategy.
+    pub fn data_size_limit(mut self, value: u32) -> Self {
+        self.data_size_limit = Some(value);
+        self
+    }
+
+    /// Tag this trade with an SPL Memo, overriding the client's tag.
+    pub fn memo(mut self, tag: impl Into<String>) -> Self {
+        self.memo = TradeMemo::Tag(tag.into());
+        self
+    }
+
+    /// Send without a memo even when the client has a tag.
+    pub fn without_memo(mut self) -> Self {
+        self.memo = TradeMemo::Off;
+        self
+    }
+
+    /// Attach the slot of the triggering event for slot-aware gating.
+    pub fn event_slot(mut self, value: u64) -> Self {
+        self.event_slot = Some(value);
+        self
+    }
+
+    /// Attach an idempotency key checked against the client's order dedup store.
+    pub fn client_order_id(mut self, value: impl Into<String>) -> Self {
+        self.client_order_id = Some(value.into());
+        self
+    }
+
+    /// Attach an end-to-end latency budget (build, sign, serialize, submit).
+    pub fn latency_budget(mut self, value: LatencyBudget) -> Self {
+        self.latency_budget = Some(value);
+        self
+    }
+
+    /// Mark as risk-reducing so the sell still goes out while trading is halted.
+    pub fn reduce_only(mut self, value: bool) -> Self {
+        self.reduce_only = value;
+        self
+    }
+}
+
+/// Fee and routing options for utility transactions (WSOL wrap/unwrap, ATA setup).
+///
+/// The plain helpers such as [`TradingClient::wrap_sol_to_wsol`] send through the RPC
+/// without a compute-unit price, which rarely lands during congestion. The
+/// `*_with_options` variants read the buy-side values of `gas_fee_strategy` instead.
+#[derive(Clone)]
+pub struct UtilityTxOptions {
+    /// Compute unit price/limit and relay tip configuration (buy-side values are used).
+    pub gas_fee_strategy: GasFeeStrategy,
+    /// Broadcast through every configured SWQOS route instead of the RPC only.
+    ///
+    /// Each route signs its own transaction variant because relay tips use different
+    /// accounts. With a recent blockhash several variants may land, so set
+    /// `durable_nonce` for non-idempotent operations such as wrapping SOL.
+    pub broadcast_via_swqos: bool,
+    /// Durable nonce info. When `None`, a recent blockhash is fetched from the RPC.
+    pub durable_nonce: Option<DurableNonceInfo>,
+}
+
+impl UtilityTxOptions {
+    /// Create options that send through the RPC with the `Default` priority fee of `gas_fee_strategy`.
+    pub fn new(gas_fee_strategy: GasFeeStrategy) -> Self {
+        Self { gas_fee_strategy, broadcast_via_swqos: false, durable_nonce: None }
+    }
+
+    /// Broadcast through all SWQOS routes with their configured tips.
+    pub fn broadcast_via_swqos(mut self, value: bool) -> Self {
+        self.broadcast_via_swqos = value;
+        self
+    }
+
+    /// Use a durable nonce instead of fetching a recent blockhash.
+    pub fn durable_nonce(mut self, value: DurableNonceInfo) -> Self {
+        self.durable_nonce = Some(value);
+        self
+    }
+}
+
+/// Routing and confirmation options for [`TradingClient::broadcast`].
+#[derive(Debug, Clone)]
+pub struct BroadcastOptions {
+    /// SWQOS routes to send through; `None` uses every configured route.
+    pub swqos_types: Option<Vec<SwqosType>>,
+    /// Side passed to the routes (some pick endpoints or logs by it). Defaults to `Buy`.
+    pub trade_type: TradeType,
+    /// Wait until the transaction reaches `confirmation.level`.
+    pub wait_tx_confirmed: bool,
+    pub confirmation: ConfirmationOptions,
+}
+
+impl Default for BroadcastOptions {
+    fn default() -> Self {
+        Self {
+            swqos_types: None,
+            trade_type: TradeType::Buy,
+            wait_tx_confirmed: true,
+            confirmation: ConfirmationOptions::default(),
+        }
+    }
+}
+
+/// Counters of one SWQOS route's [`TradingClient::broadcast`] submissions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadcastRouteStats {
+    /// Submissions the route accepted.
+    pub accepted: u64,
+    /// Submissions the route rejected or failed to deliver.
+    pub rejected: u64,
+    /// Accepted submissions of broadcasts that then confirmed (`wait_tx_confirmed` only).
+    pub confirmed: u64,
+    /// Total submit time in µs over `accepted + rejected` submissions.
+    pub submit_us_total: u64,
+}
+
+impl BroadcastRouteStats {
+    /// Mean submit time in µs, `None` before the first submission.
+    pub fn mean_submit_us(&self) -> Option<u64> {
+        let submits = self.accepted + self.rejected;
+        (submits > 0).then(|| self.submit_us_total / submits)
+    }
+}
+
+impl BroadcastOptions {
+    /// Send through these routes only.
+    pub fn swqos_types(mut self, value: Vec<SwqosType>) -> Self {
+        self.swqos_types = Some(value);
+        self
+    }
+
+    pub fn trade_type(mut self, value: TradeType) -> Self {
+        self.trade_type = value;
+        self
+    }
+
+    pub fn wait_tx_confirmed(mut self, value: bool) -> Self {
+        self.wait_tx_confirmed = value;
+        self
+    }
+
+    /// Wait for `level` for at most `timeout`; implies `wait_tx_confirmed`.
+    pub fn confirmation(mut self, level: ConfirmationLevel, timeout: std::time::Duration) -> Self {
+        self.wait_tx_confirmed = true;
+        self.confirmation = ConfirmationOptions::new(level, timeout);
+        self
+    }
+}
+
+/// Compute unit limit/price of the `Default` (RPC) buy-side strategy used for utility transactions.
+#[inline]
+fn default_utility_fee(gas_fee_strategy: &GasFeeStrategy) -> Result<(u32, u64), anyhow::Error> {
+    gas_fee_strategy
+        .get_strategies(TradeType::Buy)
+        .into_iter()
+        .find(|(swqos_type, _, _)| *swqos_type == SwqosType::Default)
+        .map(|(_, _, value)| (value.cu_limit, value.cu_price))
+        .ok_or_else(|| anyhow::anyhow!("No default gas fee strategy found"))
 }
 
 /// Shared infrastructure components that can be reused across multiple wallets
@@ -511,6 +945,8 @@ impl SimpleSellParams {
 pub struct TradingInfrastructure {
     /// Shared RPC client for blockchain interactions
     pub rpc: Arc<SolanaRpcClient>,
+    /// Call counters of `rpc`; see [`crate::common::rpc_usage`].
+    pub rpc_usage: Arc<RpcUsage>,
     /// Shared SWQOS clients for transaction priority and routing. Arc<Vec<..>> so cloning into SwapParams is a single Arc clone.
     pub swqos_clients: Arc<Vec<Arc<SwqosClient>>>,
     /// Configuration used to create this infrastructure
@@ -528,7 +964,26 @@ impl TradingInfrastructure {
     /// - Creates RPC client with connection pool
     /// - Creates SWQOS clients (each with their own HTTP client)
     /// - Initializes rent cache and starts background updater
+    ///
+    /// When no configured SWQOS provider connects, falls back to the RPC `Default` route; use
+    /// [`Self::try_new`] to fail instead.
     pub async fn new(config: InfrastructureConfig) -> Self {
+        Self::build(config, true).await
+    }
+
+    /// [`Self::new`], failing with a `NoSwqosAvailable` [`TradeError`] when every configured
+    /// provider is blacklisted or fails to connect, instead of falling back to the RPC route.
+    pub async fn try_new(config: InfrastructureConfig) -> Result<Self, anyhow::Error> {
+        let infrastructure = Self::build(config, false).await;
+        if infrastructure.swqos_clients.is_empty() {
+            return Err(anyhow::Error::new(TradeError::no_swqos_available(
+                "every configured SWQOS provider is blacklisted or failed to connect",
+            )));
+        }
+        Ok(infrastructure)
+    }
+
+    async fn build(config: InfrastructureConfig, fallback_to_rpc: bool) -> Self {
         // Install crypto provider (idempotent)
         if CryptoProvider::get_default().is_none() {
             let _ = default_provider()
@@ -536,10 +991,11 @@ impl TradingInfrastructure {
                 .map_err(|e| anyhow::anyhow!("Failed to install crypto provider: {:?}", e));
         }
 
-        // Create RPC client
-        let rpc = Arc::new(SolanaRpcClient::new_with_commitment(
-            config.rpc_url.clone(),
-            config.commitment.clone(),
+        // Create RPC client, metered for usage accounting
+        let rpc_usage = Arc::new(RpcUsage::new(&config.rpc_url));
+        let rpc = Arc::new(SolanaRpcClient::new_sender(
+            MeteredRpcSender::new(&config.rpc_url, rpc_usage.clone()),
+            RpcClientConfig::with_commitment(config.commitment.clone()),
         ));
 
         // Initialize rent cache (with timeout so slow RPC doesn't block forever)
@@ -563,63 +1019,11 @@ impl TradingInfrastructure {
         }
         crate::common::seed::start_rent_updater(rpc.clone());
 
-        // Create SWQOS clients with blacklist checking（QUIC 握手可能较慢，单节点超时 15s）
-        const SWQOS_CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
         let swqos_configs = normalize_swqos_configs(&config.rpc_url, &config.swqos_configs);
-        let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
-        for swqos in &swqos_configs {
-            if swqos.is_blacklisted() {
-                if sdk_log::sdk_log_enabled() {
-                    warn!(target: "sol_trade_sdk", "⚠️ SWQOS {:?} is blacklisted, skipping", swqos.swqos_type());
-                }
-                continue;
-            }
-            match tokio::time::timeout(
-                SWQOS_CLIENT_TIMEOUT,
-                SwqosConfig::get_swqos_client(
-                    config.rpc_url.clone(),
-                    config.commitment.clone(),
-                    swqos.clone(),
-                    config.mev_protection,
-                ),
-            )
-            .await
-            {
-                Ok(Ok(swqos_client)) => swqos_clients.push(swqos_client),
-                Ok(Err(err)) => {
-                    eprintln!(
-                        "⚠️  SWQOS {:?} 初始化失败: {}（已从列表中排除）",
-                        swqos.swqos_type(),
-                        err
-                    );
-                    if sdk_log::sdk_log_enabled() {
-                        warn!(
-                            target: "sol_trade_sdk",
-                            "failed to create {:?} swqos client: {err}. Excluding from swqos list",
-                            swqos.swqos_type()
-                        );
-                    }
-                }
-                Err(_) => {
-                    eprintln!(
-                        "⚠️  SWQOS {:?} 初始化超时（{}s），已跳过",
-                        swqos.swqos_type(),
-                        SWQOS_CLIENT_TIMEOUT.as_secs()
-                    );
-                    if sdk_log::sdk_log_enabled() {
-                        warn!(
-                            target: "sol_trade_sdk",
-                            "swqos {:?} init timed out ({}s), skipping",
-                            swqos.swqos_type(),
-                            SWQOS_CLIENT_TIMEOUT.as_secs()
-                        );
-                    }
-                }
-            }
-        }
+        let mut swqos_clients = Self::connect_swqos_clients(&config, &swqos_configs).await;
 
         // 若全部失败、被黑名单跳过或仅配置了不可用通道，至少保留一条 Rpc Default，否则 execute_parallel 会因 swqos_clients 为空直接报错。
-        if swqos_clients.is_empty() {
+        if swqos_clients.is_empty() && fallback_to_rpc {
             eprintln!(
                 "⚠️  无任何 SWQOS 客户端初始化成功，将回退为普通 RPC 发送: {}",
                 config.rpc_url
@@ -636,6 +1040,7 @@ impl TradingInfrastructure {
                 config.commitment.clone(),
                 SwqosConfig::Default(config.rpc_url.clone()),
                 config.mev_protection,
+                &HttpClientTuning::default(),
             )
             .await
             {
@@ -691,12 +1096,80 @@ impl TradingInfrastructure {
 
         Self {
             rpc,
+            rpc_usage,
             swqos_clients: Arc::new(swqos_clients),
             config,
             max_sender_concurrency,
             effective_core_ids,
         }
     }
+
+    /// Providers the SWQOS clients were created for, in submit order.
+    pub fn active_providers(&self) -> Vec<SwqosType> {
+        self.swqos_clients.iter().map(|client| client.get_swqos_type()).collect()
+    }
+
+    /// Connect a client per config, skipping blacklisted ones and those that fail or time out.
+    async fn connect_swqos_clients(
+        config: &InfrastructureConfig,
+        swqos_configs: &[SwqosConfig],
+    ) -> Vec<Arc<SwqosClient>> {
+        // Create SWQOS clients with blacklist checking（QUIC 握手可能较慢，单节点超时 15s）
+        const SWQOS_CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+        let mut swqos_clients: Vec<Arc<SwqosClient>> = vec![];
+        for swqos in swqos_configs {
+            if swqos.is_blacklisted() {
+                if sdk_log::sdk_log_enabled() {
+                    warn!(target: "sol_trade_sdk", "⚠️ SWQOS {:?} is blacklisted, skipping", swqos.swqos_type());
+                }
+                continue;
+            }
+            match tokio::time::timeout(
+                SWQOS_CLIENT_TIMEOUT,
+                SwqosConfig::get_swqos_client(
+                    config.rpc_url.clone(),
+                    config.commitment.clone(),
+                    swqos.clone(),
+                    config.mev_protection,
+                    &config.http_tuning_for(swqos.swqos_type()),
+                ),
+            )
+            .await
+            {
+                Ok(Ok(swqos_client)) => swqos_clients.push(swqos_client),
+                Ok(Err(err)) => {
+                    eprintln!(
+                        "⚠️  SWQOS {:?} 初始化失败: {}（已从列表中排除）",
+                        swqos.swqos_type(),
+                        err
+                    );
+                    if sdk_log::sdk_log_enabled() {
+                        warn!(
+                            target: "sol_trade_sdk",
+                            "failed to create {:?} swqos client: {err}. Excluding from swqos list",
+                            swqos.swqos_type()
+                        );
+                    }
+                }
+                Err(_) => {
+                    eprintln!(
+                        "⚠️  SWQOS {:?} 初始化超时（{}s），已跳过",
+                        swqos.swqos_type(),
+                        SWQOS_CLIENT_TIMEOUT.as_secs()
+                    );
+                    if sdk_log::sdk_log_enabled() {
+                        warn!(
+                            target: "sol_trade_sdk",
+                            "swqos {:?} init timed out ({}s), skipping",
+                            swqos.swqos_type(),
+                            SWQOS_CLIENT_TIMEOUT.as_secs()
+                        );
+                    }
+                }
+            }
+        }
+        swqos_clients
+    }
 }
 
 /// When using `TradeConfig::with_swqos_cores_from_end(true)`, returns the same "last N" core indices
@@ -716,6 +1189,13 @@ pub fn recommended_sender_thread_core_indices(swqos_count: usize) -> Option<Vec<
     Some((start..num_cores).collect())
 }
 
+/// Result of [`TradingClient::buy_detailed`] / [`TradingClient::sell_detailed`]: the
+/// [`TradingClient::buy`] result with the full [`SwqosSubmitTiming`] of every lane, raw
+/// transactions included when enabled. When confirmation was awaited, the landed lane carries
+/// its slot, block time and (given `event_slot`) event-to-land latency in slots.
+pub type DetailedTradeResult =
+    Result<(bool, Vec<Signature>, Option<TradeError>, Vec<SwqosSubmitTiming>), anyhow::Error>;
+
 /// Main trading client for Solana DeFi protocols
 ///
 /// `SolTradingSDK` provides a unified interface for trading across multiple Solana DEXs
@@ -727,8 +1207,24 @@ pub struct TradingClient {
     /// Shared infrastructure (RPC client, SWQOS clients)
     /// Can be shared across multiple TradingClient instances with different wallets
     pub infrastructure: Arc<TradingInfrastructure>,
+    /// SWQOS clients new trades are sent through. Starts as the infrastructure's set; replaced by
+    /// [`TradingClient::update_swqos`] and shared by clones of this client.
+    pub swqos_clients: Arc<ArcSwap<Vec<Arc<SwqosClient>>>>,
+    /// Client-wide fee strategy set by [`TradingClient::update_gas_strategy`]. When set, it is
+    /// used instead of each trade's `gas_fee_strategy`. Shared by clones of this client.
+    pub gas_strategy_override: Arc<ArcSwapOption<GasFeeStrategy>>,
+    /// Custom fee model set by [`TradingClient::with_gas_fee_model`]. When set, trades are built
+    /// with its fees instead of their own `gas_fee_strategy` (the override above still wins).
+    pub gas_fee_model: Option<Arc<dyn GasFeeModel>>,
+    /// Congestion handed to `gas_fee_model`. Shared by clones of this client.
+    pub congestion: Arc<ArcSwap<Congestion>>,
+    /// Learns landing prices from confirmed trades; set by
+    /// [`TradingClient::with_landing_fee_model`].
+    pub landing_model: Option<Arc<LandingFeeModel>>,
     /// Optional middleware manager for custom transaction processing
     pub middleware_manager: Option<Arc<MiddlewareManager>>,
+    /// Optional compute budget policy (e.g. CU price from an external fee oracle)
+    pub compute_budget_manager: Option<Arc<dyn ComputeBudgetManager>>,
     /// Whether to use seed optimization for all ATA operations (default: true)
     /// Applies to all token account creations across buy and sell operations
     pub use_seed_optimize: bool,
@@ -736,17 +1232,75 @@ pub struct TradingClient {
     pub use_dedicated_sender_threads: bool,
     /// Internal: core indices for dedicated sender threads. Trimmed to ≤ max_sender_concurrency at set.
     pub sender_thread_cores: Option<Arc<Vec<usize>>>,
+    /// Dedicated signing threads shared by every trade. Set via with_signing_pool(). `None` signs on the sender task.
+    pub signing_pool: Option<Arc<SigningPool>>,
     /// Internal: precomputed at infra init (min(swqos_count, 2/3*cores)). Not user-configurable.
     pub max_sender_concurrency: usize,
     /// Internal: precomputed at infra init for job affinity. Not user-configurable.
     pub effective_core_ids: Arc<Vec<core_affinity::CoreId>>,
     /// Whether to output all SDK logs (from TradeConfig.log_enabled).
     pub log_enabled: bool,
-    /// Whether to check minimum tip per SWQOS (from TradeConfig.check_min_tip). Default false for lower latency.
+    /// Whether to check minimum tip per SWQOS (from TradeConfig.check_min_tip). Default true.
     pub check_min_tip: bool,
+    /// Allowed destinations for [`TradingClient::withdraw`] (from TradeConfig.withdraw_allowlist). `None` allows any.
+    pub withdraw_allowlist: Option<Arc<Vec<Pubkey>>>,
+    /// Stale-reserve check run before buy/sell (from TradeConfig.stale_reserve_guard). `None` disables it.
+    pub stale_reserve_guard: Option<StaleReserveGuard>,
+    /// Slot-lag gate run before buy/sell when the params carry `event_slot`. `None` disables it.
+    pub slot_gate: Option<SlotGate>,
+    /// Rejects repeated `client_order_id`s within a window. `None` disables it.
+    pub order_dedup: Option<OrderDedup>,
+    /// Pre-trade limits consulted by buy; successful buys are recorded into it. `None` disables it.
+    pub risk_manager: Option<Arc<RiskManager>>,
+    /// Re-quote attempts after a confirmed slippage failure (from TradeConfig.slippage_retry_attempts).
+    pub slippage_retry_attempts: u32,
+    /// Resends after a confirmation timeout whose blockhash expired (from
+    /// TradeConfig.blockhash_retry_attempts).
+    pub blockhash_retry_attempts: u32,
+    /// Latency budget of the preflight simulation gate (from TradeConfig.simulation_gate). `None` disables it.
+    pub simulation_gate: Option<std::time::Duration>,
+    /// `sendTransaction` settings of the `Default` (RPC) route for trades that do not set their
+    /// own (from TradeConfig.rpc_send_options).
+    pub rpc_send_options: RpcSendOptions,
+    /// Capture each lane's wire transaction in detailed results (TradeConfig.raw_transactions).
+    pub raw_transactions: Option<ExplorerCluster>,
+    /// Run [`TradingClient::preflight_buy`] / [`TradingClient::preflight_sell`] before every trade.
+    pub preflight_validation: bool,
+    /// Contended payer WSOL ATA handling; see [`TradingClient::with_wsol_lock_mode`].
+    pub wsol_lock_mode: WsolLockMode,
+    /// Confirmed buys/sells are published here as [`TradeExecuted`]; see [`TradingClient::subscribe_trades`].
+    pub trade_events: TradeEventBus,
+    /// Memo tag appended to every trade that does not set its own [`TradeMemo`]. Set via
+    /// [`TradingClient::with_memo_tag`].
+    pub memo_tag: Option<Arc<str>>,
+    /// Kill switch checked before every buy/sell; see [`TradingClient::halt`]. Shared by clones
+    /// of this client.
+    pub trading_halt: Arc<TradingHalt>,
+    /// Per-route counters of [`TradingClient::broadcast`]; see
+    /// [`TradingClient::broadcast_stats`]. Shared by clones of this client.
+    pub broadcast_stats: Arc<Mutex<HashMap<SwqosType, BroadcastRouteStats>>>,
+    /// Signatures sent for each recent `client_order_id`, oldest order first; see
+    /// [`TradingClient::order_signatures`]. Shared by clones of this client.
+    pub order_signatures: Arc<Mutex<VecDeque<(String, Vec<Signature>)>>>,
+    /// Direct SWQOS submission or a fee sponsor; see [`TradingClient::with_send_mode`].
+    pub send_mode: SendMode,
+    /// Handling of trades too large for one transaction (from TradeConfig.transaction_split).
+    pub transaction_split: TransactionSplit,
+    /// Balances lent to the payer in simulated trades; see
+    /// [`TradingClient::with_simulation_funding`].
+    pub simulation_funding: Option<Arc<SimulationFunding>>,
+}
+
+/// Clients registered per payer wallet; see [`TradingClient::get_instance_for`].
+#[derive(Default)]
+struct InstanceRegistry {
+    by_wallet: BTreeMap<Pubkey, Arc<TradingClient>>,
+    /// Wallet of the client most recently built with [`TradingClient::new`], which the
+    /// deprecated [`TradingClient::get_instance`] returns.
+    last_created: Option<Pubkey>,
 }
 
-static INSTANCE: Mutex<Option<Arc<TradingClient>>> = Mutex::new(None);
+static INSTANCES: Lazy<Mutex<InstanceRegistry>> = Lazy::new(Default::default);
 
 /// 🔄 向后兼容：SolanaTrade 别名
 pub type SolanaTrade = TradingClient;
@@ -756,14 +1310,41 @@ impl Clone for TradingClient {
         Self {
             payer: self.payer.clone(),
             infrastructure: self.infrastructure.clone(),
+            swqos_clients: self.swqos_clients.clone(),
+            gas_strategy_override: self.gas_strategy_override.clone(),
+            gas_fee_model: self.gas_fee_model.clone(),
+            congestion: self.congestion.clone(),
+            landing_model: self.landing_model.clone(),
             middleware_manager: self.middleware_manager.clone(),
+            compute_budget_manager: self.compute_budget_manager.clone(),
             use_seed_optimize: self.use_seed_optimize,
             use_dedicated_sender_threads: self.use_dedicated_sender_threads,
             sender_thread_cores: self.sender_thread_cores.clone(),
+            signing_pool: self.signing_pool.clone(),
             max_sender_concurrency: self.max_sender_concurrency,
             effective_core_ids: self.effective_core_ids.clone(),
             log_enabled: self.log_enabled,
             check_min_tip: self.check_min_tip,
+            withdraw_allowlist: self.withdraw_allowlist.clone(),
+            stale_reserve_guard: self.stale_reserve_guard,
+            slot_gate: self.slot_gate.clone(),
+            order_dedup: self.order_dedup.clone(),
+            risk_manager: self.risk_manager.clone(),
+            slippage_retry_attempts: self.slippage_retry_attempts,
+            blockhash_retry_attempts: self.blockhash_retry_attempts,
+            simulation_gate: self.simulation_gate,
+            rpc_send_options: self.rpc_send_options,
+            raw_transactions: self.raw_transactions,
+            preflight_validation: self.preflight_validation,
+            wsol_lock_mode: self.wsol_lock_mode,
+            trade_events: self.trade_events.clone(),
+            memo_tag: self.memo_tag.clone(),
+            trading_halt: self.trading_halt.clone(),
+            broadcast_stats: self.broadcast_stats.clone(),
+            order_signatures: self.order_signatures.clone(),
+            send_mode: self.send_mode.clone(),
+            transaction_split: self.transaction_split,
+            simulation_funding: self.simulation_funding.clone(),
         }
     }
 }
@@ -781,8 +1362,12 @@ pub struct TradeBuyParams {
     pub input_token_type: TradeTokenType,
     /// Public key of the token to purchase
     pub mint: Pubkey,
-    /// Amount of tokens to buy (in smallest token units)
-    pub input_token_amount: u64,
+    /// Amount of the input (quote) token to spend, in smallest units.
+    ///
+    /// `None` spends the payer's whole balance of the quote token account (all WSOL, all USDC,
+    /// ...), read via RPC before building. Not allowed with `TradeTokenType::SOL`; with WSOL the
+    /// existing balance is spent as-is (`create_input_token_ata` is ignored, nothing is wrapped).
+    pub input_token_amount: Option<u64>,
     /// Optional slippage tolerance in basis points (e.g., 100 = 1%)
     pub slippage_basis_points: Option<u64>,
     /// Recent blockhash for transaction validity
@@ -795,6 +1380,11 @@ pub struct TradeBuyParams {
     pub address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     /// Whether to wait for transaction confirmation before returning
     pub wait_tx_confirmed: bool,
+    /// Level and timeout of that wait; see [`crate::ConfirmationStatus::from_result`] to tell a
+    /// processed landing, a confirmation and a timeout apart in the result.
+    pub confirmation: ConfirmationOptions,
+    /// `sendTransaction` settings of the `Default` (RPC) route; `None` uses the client's.
+    pub rpc_send_options: Option<RpcSendOptions>,
     /// When true, wait for every SWQOS route's HTTP submit response so all
     /// submitted signatures are returned. This applies whether SDK confirmation
     /// is enabled or the caller monitors externally. Recent-blockhash route
@@ -812,9 +1402,18 @@ pub struct TradeBuyParams {
     /// Optional fixed output token amount. On exact-out capable DEXes this uses
     /// the exact-out instruction and treats `input_token_amount` as max input.
     pub fixed_output_token_amount: Option<u64>,
+    /// Optional minimum output of an exact-input trade; replaces the slippage-derived minimum
+    /// on every DEX. Mutually exclusive with `fixed_output_token_amount`.
+    pub min_output_token_amount: Option<u64>,
     /// Gas fee strategy
     pub gas_fee_strategy: GasFeeStrategy,
-    /// Whether to simulate the transaction instead of executing it
+    /// Loaded-accounts data size limit in bytes (`SetLoadedAccountsDataSizeLimit`). Overrides
+    /// [`GasFeeStrategy::get_data_size_limit`] for this DEX; `None` uses the strategy value.
+    pub data_size_limit: Option<u32>,
+    /// Memo tag appended to the transaction. See [`TradeMemo`].
+    pub memo: TradeMemo,
+    /// Whether to simulate the transaction instead of executing it. A trade that needs a
+    /// [`TransactionSplit`] fails with `SIMULATION_UNSUPPORTED_ERROR_CODE` instead.
     pub simulate: bool,
     /// Use exact quote-input buy instructions (legacy PumpFun uses SOL quote; V2/PumpSwap use generic quote).
     /// When Some(true) or None (default), the exact SOL/quote amount is spent and slippage is applied to output tokens.
@@ -823,10 +1422,80 @@ pub struct TradeBuyParams {
     pub use_exact_sol_amount: Option<bool>,
     /// Optional upstream receive timestamp (e.g. gRPC recv) in microseconds for latency tracing.
     pub grpc_recv_us: Option<i64>,
+    /// Slot of the triggering event (e.g. the copied transaction). When set and the client has a
+    /// slot gate, the trade is dropped if the cluster has advanced too far past it.
+    pub event_slot: Option<u64>,
+    /// Idempotency key. When the client has order dedup enabled, the id is claimed right before
+    /// the first send, so a local rejection leaves it free for a retry; a second submission
+    /// with a claimed id inside the window fails with `DuplicateOrder`.
+    pub client_order_id: Option<String>,
+    /// End-to-end latency budget checked after each stage. Keep a clone of the budget to read
+    /// its [`LatencyBudget::report`] after the trade returns.
+    pub latency_budget: Option<LatencyBudget>,
+    /// Bypass the client's [`RiskManager`] limits for this trade (e.g. manual trades). A successful
+    /// buy is still recorded against the mint's exposure.
+    pub skip_risk_checks: bool,
+    /// Separate fee payer for this trade (sponsored fees, keeping the trading wallet's SOL
+    /// balance steady). It pays the fee and relay tip and must be the durable nonce authority;
+    /// the client's payer still owns the token accounts and co-signs. `None` uses the payer.
+    pub fee_payer: Option<Arc<Keypair>>,
+    /// Extra instructions placed before the swap, after ATA creation and SOL wrapping (e.g. a
+    /// transfer), without writing an [`InstructionMiddleware`](crate::InstructionMiddleware).
+    pub pre_instructions: Vec<Instruction>,
+    /// Extra instructions placed after the swap, before ATA closes and SOL unwrapping.
+    pub post_instructions: Vec<Instruction>,
+    /// Extra keypairs `pre_instructions`/`post_instructions` need (delegate, flash-loan
+    /// receiver, ...), signed next to the payer.
+    pub additional_signers: Vec<Arc<Keypair>>,
 }
 
-/// Parameters for executing sell orders across different DEX protocols
-///
+/// Every field; the fee payer shows its public key and lookup tables their addresses.
+impl std::fmt::Debug for TradeBuyParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lookup_tables: Vec<Pubkey> =
+            self.address_lookup_table_accounts.iter().map(|table| table.key).collect();
+        f.debug_struct("TradeBuyParams")
+            .field("dex_type", &self.dex_type)
+            .field("input_token_type", &self.input_token_type)
+            .field("mint", &self.mint)
+            .field("input_token_amount", &self.input_token_amount)
+            .field("slippage_basis_points", &self.slippage_basis_points)
+            .field("recent_blockhash", &self.recent_blockhash)
+            .field("extension_params", &self.extension_params)
+            .field("address_lookup_tables", &lookup_tables)
+            .field("wait_tx_confirmed", &self.wait_tx_confirmed)
+            .field("confirmation", &self.confirmation)
+            .field("rpc_send_options", &self.rpc_send_options)
+            .field("wait_for_all_submits", &self.wait_for_all_submits)
+            .field("create_input_token_ata", &self.create_input_token_ata)
+            .field("close_input_token_ata", &self.close_input_token_ata)
+            .field("create_mint_ata", &self.create_mint_ata)
+            .field("durable_nonce", &self.durable_nonce)
+            .field("fixed_output_token_amount", &self.fixed_output_token_amount)
+            .field("min_output_token_amount", &self.min_output_token_amount)
+            .field("gas_fee_strategy", &self.gas_fee_strategy)
+            .field("data_size_limit", &self.data_size_limit)
+            .field("memo", &self.memo)
+            .field("simulate", &self.simulate)
+            .field("use_exact_sol_amount", &self.use_exact_sol_amount)
+            .field("grpc_recv_us", &self.grpc_recv_us)
+            .field("event_slot", &self.event_slot)
+            .field("client_order_id", &self.client_order_id)
+            .field("latency_budget", &self.latency_budget)
+            .field("skip_risk_checks", &self.skip_risk_checks)
+            .field("fee_payer", &self.fee_payer.as_ref().map(|fee_payer| fee_payer.pubkey()))
+            .field("pre_instructions", &self.pre_instructions)
+            .field("post_instructions", &self.post_instructions)
+            .field(
+                "additional_signers",
+                &self.additional_signers.iter().map(|signer| signer.pubkey()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Parameters for executing sell orders across different DEX protocols
+///
 /// Contains all necessary configuration for selling tokens, including
 /// protocol-specific settings, tip preferences, account management options, and transaction preferences.
 #[derive(Clone)]
@@ -838,8 +1507,9 @@ pub struct TradeSellParams {
     pub output_token_type: TradeTokenType,
     /// Public key of the token to sell
     pub mint: Pubkey,
-    /// Amount of tokens to sell (in smallest token units)
-    pub input_token_amount: u64,
+    /// Amount of tokens to sell, in smallest token units. `None` sells the payer's whole balance
+    /// of `mint` (seed or standard ATA, following `use_seed_optimize`), read via RPC before building.
+    pub input_token_amount: Option<u64>,
     /// Optional slippage tolerance in basis points (e.g., 100 = 1%)
     pub slippage_basis_points: Option<u64>,
     /// Recent blockhash for transaction validity
@@ -854,6 +1524,11 @@ pub struct TradeSellParams {
     pub address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     /// Whether to wait for transaction confirmation before returning
     pub wait_tx_confirmed: bool,
+    /// Level and timeout of that wait; see [`crate::ConfirmationStatus::from_result`] to tell a
+    /// processed landing, a confirmation and a timeout apart in the result.
+    pub confirmation: ConfirmationOptions,
+    /// `sendTransaction` settings of the `Default` (RPC) route; `None` uses the client's.
+    pub rpc_send_options: Option<RpcSendOptions>,
     /// When true, wait for every SWQOS route's HTTP submit response so all
     /// submitted signatures are returned. This applies whether SDK confirmation
     /// is enabled or the caller monitors externally. Recent-blockhash route
@@ -871,12 +1546,91 @@ pub struct TradeSellParams {
     /// Optional fixed output token amount. On exact-out capable DEXes this uses
     /// the exact-out instruction and treats `input_token_amount` as max input.
     pub fixed_output_token_amount: Option<u64>,
+    /// Optional minimum output of an exact-input trade; replaces the slippage-derived minimum
+    /// on every DEX. Mutually exclusive with `fixed_output_token_amount`.
+    pub min_output_token_amount: Option<u64>,
     /// Gas fee strategy
     pub gas_fee_strategy: GasFeeStrategy,
-    /// Whether to simulate the transaction instead of executing it
+    /// Loaded-accounts data size limit in bytes (`SetLoadedAccountsDataSizeLimit`). Overrides
+    /// [`GasFeeStrategy::get_data_size_limit`] for this DEX; `None` uses the strategy value.
+    pub data_size_limit: Option<u32>,
+    /// Memo tag appended to the transaction. See [`TradeMemo`].
+    pub memo: TradeMemo,
+    /// Whether to simulate the transaction instead of executing it. A trade that needs a
+    /// [`TransactionSplit`] fails with `SIMULATION_UNSUPPORTED_ERROR_CODE` instead.
     pub simulate: bool,
     /// Optional upstream receive timestamp (e.g. gRPC recv) in microseconds for latency tracing.
     pub grpc_recv_us: Option<i64>,
+    /// Slot of the triggering event (e.g. the copied transaction). When set and the client has a
+    /// slot gate, the trade is dropped if the cluster has advanced too far past it.
+    pub event_slot: Option<u64>,
+    /// Idempotency key. When the client has order dedup enabled, the id is claimed right before
+    /// the first send, so a local rejection leaves it free for a retry; a second submission
+    /// with a claimed id inside the window fails with `DuplicateOrder`.
+    pub client_order_id: Option<String>,
+    /// End-to-end latency budget checked after each stage. Keep a clone of the budget to read
+    /// its [`LatencyBudget::report`] after the trade returns.
+    pub latency_budget: Option<LatencyBudget>,
+    /// Risk-reducing sell (e.g. unwinding a position): still sent while the client or this mint
+    /// is halted ([`TradingClient::halt`], [`TradingClient::halt_mint`]).
+    pub reduce_only: bool,
+    /// Separate fee payer for this trade (sponsored fees, keeping the trading wallet's SOL
+    /// balance steady). It pays the fee and relay tip and must be the durable nonce authority;
+    /// the client's payer still owns the token accounts and co-signs. `None` uses the payer.
+    pub fee_payer: Option<Arc<Keypair>>,
+    /// Extra instructions placed before the swap, after ATA creation and SOL wrapping (e.g. a
+    /// transfer), without writing an [`InstructionMiddleware`](crate::InstructionMiddleware).
+    pub pre_instructions: Vec<Instruction>,
+    /// Extra instructions placed after the swap, before ATA closes and SOL unwrapping.
+    pub post_instructions: Vec<Instruction>,
+    /// Extra keypairs `pre_instructions`/`post_instructions` need (delegate, flash-loan
+    /// receiver, ...), signed next to the payer.
+    pub additional_signers: Vec<Arc<Keypair>>,
+}
+
+/// Every field; the fee payer shows its public key and lookup tables their addresses.
+impl std::fmt::Debug for TradeSellParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lookup_tables: Vec<Pubkey> =
+            self.address_lookup_table_accounts.iter().map(|table| table.key).collect();
+        f.debug_struct("TradeSellParams")
+            .field("dex_type", &self.dex_type)
+            .field("output_token_type", &self.output_token_type)
+            .field("mint", &self.mint)
+            .field("input_token_amount", &self.input_token_amount)
+            .field("slippage_basis_points", &self.slippage_basis_points)
+            .field("recent_blockhash", &self.recent_blockhash)
+            .field("with_tip", &self.with_tip)
+            .field("extension_params", &self.extension_params)
+            .field("address_lookup_tables", &lookup_tables)
+            .field("wait_tx_confirmed", &self.wait_tx_confirmed)
+            .field("confirmation", &self.confirmation)
+            .field("rpc_send_options", &self.rpc_send_options)
+            .field("wait_for_all_submits", &self.wait_for_all_submits)
+            .field("create_output_token_ata", &self.create_output_token_ata)
+            .field("close_output_token_ata", &self.close_output_token_ata)
+            .field("close_mint_token_ata", &self.close_mint_token_ata)
+            .field("durable_nonce", &self.durable_nonce)
+            .field("fixed_output_token_amount", &self.fixed_output_token_amount)
+            .field("min_output_token_amount", &self.min_output_token_amount)
+            .field("gas_fee_strategy", &self.gas_fee_strategy)
+            .field("data_size_limit", &self.data_size_limit)
+            .field("memo", &self.memo)
+            .field("simulate", &self.simulate)
+            .field("grpc_recv_us", &self.grpc_recv_us)
+            .field("event_slot", &self.event_slot)
+            .field("client_order_id", &self.client_order_id)
+            .field("latency_budget", &self.latency_budget)
+            .field("reduce_only", &self.reduce_only)
+            .field("fee_payer", &self.fee_payer.as_ref().map(|fee_payer| fee_payer.pubkey()))
+            .field("pre_instructions", &self.pre_instructions)
+            .field("post_instructions", &self.post_instructions)
+            .field(
+                "additional_signers",
+                &self.additional_signers.iter().map(|signer| signer.pubkey()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
 }
 
 #[inline]
@@ -891,7 +1645,7 @@ fn buy_account_flags(policy: AccountPolicy) -> (bool, bool, bool) {
 #[inline]
 fn sell_account_flags(policy: AccountPolicy, receive_as: &TradeTokenType) -> (bool, bool, bool) {
     match policy {
-        AccountPolicy::Auto => (*receive_as != TradeTokenType::SOL, false, false),
+        AccountPolicy::Auto => (receive_as.quote_kind() == QuoteKind::Spl, false, false),
         AccountPolicy::HotPathMinimal | AccountPolicy::AssumePrepared => (false, false, false),
         AccountPolicy::CreateMissing => (true, false, false),
     }
@@ -901,11 +1655,12 @@ impl From<SimpleBuyParams> for TradeBuyParams {
     fn from(params: SimpleBuyParams) -> Self {
         let (input_token_amount, fixed_output_token_amount, use_exact_sol_amount) =
             match params.amount {
-                BuyAmount::ExactInput(amount) => (amount, None, Some(true)),
+                BuyAmount::ExactInput(amount) => (Some(amount), None, Some(true)),
                 BuyAmount::ExactOutput { output_amount, max_input_amount } => {
-                    (max_input_amount, Some(output_amount), Some(true))
+                    (Some(max_input_amount), Some(output_amount), Some(true))
                 }
-                BuyAmount::WithMaxInput { quote_amount } => (quote_amount, None, Some(false)),
+                BuyAmount::WithMaxInput { quote_amount } => (Some(quote_amount), None, Some(false)),
+                BuyAmount::AllQuote => (None, None, Some(true)),
             };
         let (create_input_token_ata, create_mint_ata, close_input_token_ata) =
             buy_account_flags(params.account_policy);
@@ -920,16 +1675,29 @@ impl From<SimpleBuyParams> for TradeBuyParams {
             extension_params: params.extension_params,
             address_lookup_table_accounts: params.address_lookup_table_accounts,
             wait_tx_confirmed: params.wait_tx_confirmed,
+            confirmation: params.confirmation,
+            rpc_send_options: params.rpc_send_options,
             wait_for_all_submits: params.wait_for_all_submits,
             create_input_token_ata,
             close_input_token_ata,
             create_mint_ata,
             durable_nonce: params.durable_nonce,
             fixed_output_token_amount,
+            min_output_token_amount: params.min_output_amount,
             gas_fee_strategy: params.gas_fee_strategy,
+            data_size_limit: params.data_size_limit,
+            memo: params.memo,
             simulate: params.simulate,
             use_exact_sol_amount,
             grpc_recv_us: params.grpc_recv_us,
+            event_slot: params.event_slot,
+            client_order_id: params.client_order_id,
+            latency_budget: params.latency_budget,
+            skip_risk_checks: params.skip_risk_checks,
+            fee_payer: params.fee_payer,
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            additional_signers: Vec::new(),
         }
     }
 }
@@ -937,10 +1705,11 @@ impl From<SimpleBuyParams> for TradeBuyParams {
 impl From<SimpleSellParams> for TradeSellParams {
     fn from(params: SimpleSellParams) -> Self {
         let (input_token_amount, fixed_output_token_amount) = match params.amount {
-            SellAmount::ExactInput(amount) => (amount, None),
+            SellAmount::ExactInput(amount) => (Some(amount), None),
             SellAmount::ExactOutput { output_amount, max_input_amount } => {
-                (max_input_amount, Some(output_amount))
+                (Some(max_input_amount), Some(output_amount))
             }
+            SellAmount::All => (None, None),
         };
         let (create_output_token_ata, close_output_token_ata, close_mint_token_ata) =
             sell_account_flags(params.account_policy, &params.receive_as);
@@ -956,15 +1725,28 @@ impl From<SimpleSellParams> for TradeSellParams {
             extension_params: params.extension_params,
             address_lookup_table_accounts: params.address_lookup_table_accounts,
             wait_tx_confirmed: params.wait_tx_confirmed,
+            confirmation: params.confirmation,
+            rpc_send_options: params.rpc_send_options,
             wait_for_all_submits: params.wait_for_all_submits,
             create_output_token_ata,
             close_output_token_ata,
             close_mint_token_ata,
             durable_nonce: params.durable_nonce,
             fixed_output_token_amount,
+            min_output_token_amount: params.min_output_amount,
             gas_fee_strategy: params.gas_fee_strategy,
+            data_size_limit: params.data_size_limit,
+            memo: params.memo,
             simulate: params.simulate,
             grpc_recv_us: params.grpc_recv_us,
+            event_slot: params.event_slot,
+            client_order_id: params.client_order_id,
+            latency_budget: params.latency_budget,
+            reduce_only: params.reduce_only,
+            fee_payer: params.fee_payer,
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            additional_signers: Vec::new(),
         }
     }
 }
@@ -995,15 +1777,42 @@ impl TradingClient {
 
         Self {
             payer,
+            swqos_clients: Arc::new(ArcSwap::new(infrastructure.swqos_clients.clone())),
+            gas_strategy_override: Arc::new(ArcSwapOption::empty()),
+            gas_fee_model: None,
+            congestion: Arc::default(),
+            landing_model: None,
             infrastructure,
             middleware_manager: None,
+            compute_budget_manager: None,
             use_seed_optimize,
             use_dedicated_sender_threads: false,
             sender_thread_cores: None,
+            signing_pool: None,
             max_sender_concurrency,
             effective_core_ids,
             log_enabled: true,
-            check_min_tip: false,
+            check_min_tip: true,
+            withdraw_allowlist: None,
+            stale_reserve_guard: None,
+            slot_gate: None,
+            order_dedup: None,
+            risk_manager: None,
+            slippage_retry_attempts: 0,
+            blockhash_retry_attempts: 0,
+            simulation_gate: None,
+            rpc_send_options: RpcSendOptions::default(),
+            raw_transactions: None,
+            preflight_validation: false,
+            wsol_lock_mode: WsolLockMode::default(),
+            trade_events: TradeEventBus::default(),
+            memo_tag: None,
+            trading_halt: Arc::default(),
+            broadcast_stats: Arc::default(),
+            order_signatures: Arc::default(),
+            send_mode: SendMode::Direct,
+            transaction_split: TransactionSplit::default(),
+            simulation_funding: None,
         }
     }
 
@@ -1041,15 +1850,42 @@ impl TradingClient {
 
         Self {
             payer,
+            swqos_clients: Arc::new(ArcSwap::new(infrastructure.swqos_clients.clone())),
+            gas_strategy_override: Arc::new(ArcSwapOption::empty()),
+            gas_fee_model: None,
+            congestion: Arc::default(),
+            landing_model: None,
             infrastructure,
             middleware_manager: None,
+            compute_budget_manager: None,
             use_seed_optimize,
             use_dedicated_sender_threads: false,
             sender_thread_cores: None,
+            signing_pool: None,
             max_sender_concurrency,
             effective_core_ids,
             log_enabled: true,
-            check_min_tip: false,
+            check_min_tip: true,
+            withdraw_allowlist: None,
+            stale_reserve_guard: None,
+            slot_gate: None,
+            order_dedup: None,
+            risk_manager: None,
+            slippage_retry_attempts: 0,
+            blockhash_retry_attempts: 0,
+            simulation_gate: None,
+            rpc_send_options: RpcSendOptions::default(),
+            raw_transactions: None,
+            preflight_validation: false,
+            wsol_lock_mode: WsolLockMode::default(),
+            trade_events: TradeEventBus::default(),
+            memo_tag: None,
+            trading_halt: Arc::default(),
+            broadcast_stats: Arc::default(),
+            order_signatures: Arc::default(),
+            send_mode: SendMode::Direct,
+            transaction_split: TransactionSplit::default(),
+            simulation_funding: None,
         }
     }
 
@@ -1178,13 +2014,36 @@ impl TradingClient {
     /// Returns a configured `SolTradingSDK` instance ready for trading operations
     #[inline]
     pub async fn new(payer: Arc<Keypair>, trade_config: TradeConfig) -> Self {
+        Self::init(payer, trade_config, false)
+            .await
+            .expect("lenient TradingInfrastructure init does not fail")
+    }
+
+    /// [`Self::new`], failing with a `NoSwqosAvailable` [`TradeError`] when every configured
+    /// SWQOS provider is blacklisted or fails to connect (see [`TradingInfrastructure::try_new`]).
+    pub async fn try_new(
+        payer: Arc<Keypair>,
+        trade_config: TradeConfig,
+    ) -> Result<Self, anyhow::Error> {
+        Self::init(payer, trade_config, true).await
+    }
+
+    async fn init(
+        payer: Arc<Keypair>,
+        trade_config: TradeConfig,
+        strict_swqos: bool,
+    ) -> Result<Self, anyhow::Error> {
         // 设置 SDK 全局日志开关，后续所有 SDK 内日志（SWQOS/WSOL/耗时等）均受此控制
         sdk_log::set_sdk_log_enabled(trade_config.log_enabled);
         // 预热高性能时钟，避免首笔交易时触发 3 次 Utc::now() 校准
         let _ = crate::common::clock::now_micros();
         // Create infrastructure from trade config
         let infra_config = InfrastructureConfig::from_trade_config(&trade_config);
-        let infrastructure = Arc::new(TradingInfrastructure::new(infra_config).await);
+        let infrastructure = Arc::new(if strict_swqos {
+            TradingInfrastructure::try_new(infra_config).await?
+        } else {
+            TradingInfrastructure::new(infra_config).await
+        });
 
         // Initialize wallet-specific caches
         crate::common::fast_fn::fast_init(&payer.pubkey());
@@ -1221,20 +2080,50 @@ impl TradingClient {
         let instance = Self {
             payer,
             infrastructure: infrastructure.clone(),
+            swqos_clients: Arc::new(ArcSwap::new(infrastructure.swqos_clients.clone())),
+            gas_strategy_override: Arc::new(ArcSwapOption::empty()),
+            gas_fee_model: None,
+            congestion: Arc::default(),
+            landing_model: None,
             middleware_manager: None,
+            compute_budget_manager: None,
             use_seed_optimize: trade_config.use_seed_optimize,
             use_dedicated_sender_threads: false,
             sender_thread_cores: None,
+            signing_pool: None,
             max_sender_concurrency: infrastructure.max_sender_concurrency,
             effective_core_ids: infrastructure.effective_core_ids.clone(),
             log_enabled: trade_config.log_enabled,
             check_min_tip: trade_config.check_min_tip,
+            withdraw_allowlist: trade_config.withdraw_allowlist.map(Arc::new),
+            stale_reserve_guard: trade_config.stale_reserve_guard,
+            slot_gate: None,
+            order_dedup: None,
+            risk_manager: None,
+            slippage_retry_attempts: trade_config.slippage_retry_attempts,
+            blockhash_retry_attempts: trade_config.blockhash_retry_attempts,
+            simulation_gate: trade_config.simulation_gate,
+            rpc_send_options: trade_config.rpc_send_options,
+            raw_transactions: trade_config.raw_transactions,
+            preflight_validation: false,
+            wsol_lock_mode: WsolLockMode::default(),
+            trade_events: TradeEventBus::default(),
+            memo_tag: None,
+            trading_halt: Arc::default(),
+            broadcast_stats: Arc::default(),
+            order_signatures: Arc::default(),
+            send_mode: SendMode::Direct,
+            transaction_split: trade_config.transaction_split,
+            simulation_funding: None,
         };
 
-        let mut current = INSTANCE.lock();
-        *current = Some(Arc::new(instance.clone()));
+        let wallet = instance.payer.pubkey();
+        let mut registry = INSTANCES.lock();
+        registry.by_wallet.insert(wallet, Arc::new(instance.clone()));
+        registry.last_created = Some(wallet);
+        drop(registry);
 
-        instance
+        Ok(instance)
     }
 
     /// Adds a middleware manager to the SolanaTrade instance
@@ -1252,6 +2141,196 @@ impl TradingClient {
         self
     }
 
+    /// Replaces how compute budget instructions are emitted for every transaction this client
+    /// builds, e.g. to take the CU price from an external fee oracle instead of the gas fee
+    /// strategy. See [`ComputeBudgetManager`].
+    pub fn with_compute_budget_manager(
+        mut self,
+        compute_budget_manager: Arc<dyn ComputeBudgetManager>,
+    ) -> Self {
+        self.compute_budget_manager = Some(compute_budget_manager);
+        self
+    }
+
+    /// Build every trade with fees from `model` instead of the trade's `gas_fee_strategy`, given
+    /// the client's [`Congestion`]. See [`GasFeeModel`].
+    pub fn with_gas_fee_model(mut self, model: Arc<dyn GasFeeModel>) -> Self {
+        self.gas_fee_model = Some(model);
+        self
+    }
+
+    /// Use `model` as the [`GasFeeModel`] and record the lanes of every confirmed trade into
+    /// it, so fees follow the CU price and tip this client's transactions actually land at.
+    pub fn with_landing_fee_model(mut self, model: Arc<LandingFeeModel>) -> Self {
+        self.gas_fee_model = Some(model.clone());
+        self.landing_model = Some(model);
+        self
+    }
+
+    /// Restrict [`Self::withdraw`] destinations; use when building from shared infrastructure
+    /// (the `TradeConfig` path sets this via `TradeConfigBuilder::withdraw_allowlist`).
+    pub fn with_withdraw_allowlist(mut self, destinations: Vec<Pubkey>) -> Self {
+        self.withdraw_allowlist = Some(Arc::new(destinations));
+        self
+    }
+
+    /// Refresh or reject trades whose params were built from an event older than `max_age`.
+    pub fn with_max_reserve_age(
+        mut self,
+        max_age: std::time::Duration,
+        action: StaleReserveAction,
+    ) -> Self {
+        self.stale_reserve_guard = Some(StaleReserveGuard::new(max_age, action));
+        self
+    }
+
+    /// Drop trades whose `event_slot` is more than `max_slot_lag` slots behind `tracker`.
+    /// Late copy-trades usually fill at a worse price than the trade they copy.
+    pub fn with_slot_gate(mut self, tracker: SlotTracker, max_slot_lag: u64) -> Self {
+        self.slot_gate = Some(SlotGate::new(tracker, max_slot_lag));
+        self
+    }
+
+    /// Reject a second buy/sell carrying the same `client_order_id` within `window`
+    /// (process-local store). Guards against double submits after reconnects. The id is
+    /// claimed right before the first send, so a trade rejected locally (slot gate, stale
+    /// reserves, failed build) can be retried with the same id.
+    pub fn with_order_dedup(mut self, window: std::time::Duration) -> Self {
+        self.order_dedup = Some(OrderDedup::in_memory(window));
+        self
+    }
+
+    /// Same as [`Self::with_order_dedup`] with a custom [`OrderIdStore`], e.g. one shared across processes.
+    pub fn with_order_dedup_store(
+        mut self,
+        store: Arc<dyn OrderIdStore>,
+        window: std::time::Duration,
+    ) -> Self {
+        self.order_dedup = Some(OrderDedup::new(store, window));
+        self
+    }
+
+    /// Snapshot of the state this client owns: [`RiskManager`] positions and the dedup keys of
+    /// its [`OrderIdStore`]. Add strategy state (pending orders, DCA progress) before
+    /// [`StateSnapshot::save`].
+    pub fn snapshot_state(&self) -> StateSnapshot {
+        let mut snapshot = StateSnapshot::new();
+        if let Some(risk) = &self.risk_manager {
+            risk.save_state(&mut snapshot);
+        }
+        if let Some(dedup) = &self.order_dedup {
+            snapshot.dedup_keys = dedup.store.export_keys(dedup.window);
+        }
+        snapshot
+    }
+
+    /// Restore what [`Self::snapshot_state`] saved into this client's risk manager and dedup
+    /// store. Call after configuring them and before trading.
+    pub fn restore_state(&self, snapshot: &StateSnapshot) {
+        if let Some(risk) = &self.risk_manager {
+            risk.restore_state(snapshot);
+        }
+        if let Some(dedup) = &self.order_dedup {
+            dedup.store.import_keys(&snapshot.dedup_keys);
+        }
+    }
+
+    /// Enforce `risk_manager` limits before every buy (see [`RiskManager`]). Rejections surface as
+    /// a [`crate::common::risk_manager::RiskRejection`] error; set `skip_risk_checks` on manual trades to bypass.
+    /// Buys reserve their exposure and cooldown until they fail or confirm; confirmed sells are
+    /// decoded in the background and recorded, realizing PnL when they sell the whole balance.
+    pub fn with_risk_manager(mut self, risk_manager: Arc<RiskManager>) -> Self {
+        self.risk_manager = Some(risk_manager);
+        self
+    }
+
+    /// Validate ATA flags against the payer's accounts, and reject frozen or wrong-program token
+    /// accounts, before every buy/sell (see
+    /// [`TradingClient::preflight_buy`] / [`TradingClient::preflight_sell`]). Rejections surface
+    /// as a [`PreflightError`]. Adds one `getMultipleAccounts` per trade.
+    pub fn with_preflight_validation(mut self) -> Self {
+        self.preflight_validation = true;
+        self
+    }
+
+    /// How a trade waits for the payer WSOL ATA while another trade closes it, or closes it while
+    /// others use it. Each trade holds the account only until its transaction is sent.
+    /// [`WsolLockMode::Wait`] (default) queues behind the other trade; [`WsolLockMode::FailFast`]
+    /// fails with [`PreflightError::WsolAccountInUse`] instead, for latency-sensitive bots.
+    pub fn with_wsol_lock_mode(mut self, mode: WsolLockMode) -> Self {
+        self.wsol_lock_mode = mode;
+        self
+    }
+
+    /// Append an SPL Memo with `tag` (at most [`crate::trading::common::MAX_MEMO_LEN`] bytes) to
+    /// every trade, for on-chain attribution. Trades opt out or override via [`TradeMemo`].
+    pub fn with_memo_tag(mut self, tag: impl Into<String>) -> Self {
+        self.memo_tag = Some(Arc::from(tag.into()));
+        self
+    }
+
+    /// Memo tag a trade carrying `memo` is sent with.
+    fn resolve_memo(&self, memo: TradeMemo) -> Option<Arc<str>> {
+        match memo {
+            TradeMemo::ClientDefault => self.memo_tag.clone(),
+            TradeMemo::Tag(tag) => Some(Arc::from(tag)),
+            TradeMemo::Off => None,
+        }
+    }
+
+    /// Re-quote from fresh reserves and resend up to `attempts` times after a confirmed slippage failure.
+    pub fn with_slippage_retry_attempts(mut self, attempts: u32) -> Self {
+        self.slippage_retry_attempts = attempts;
+        self
+    }
+
+    /// Rebuild with a fresh blockhash and resend, up to `attempts` times, when a confirmed trade
+    /// times out and its blockhash has expired at `confirmed`. An attempt found on-chain by then
+    /// is reported instead of resending. Every attempt stays watched: whichever signature lands
+    /// resolves the order, reported first in the result; [`Self::order_signatures`] lists them.
+    /// Only trades passing `recent_blockhash` are resent: durable nonce trades never expire, and
+    /// without a blockhash there is nothing to rebuild on.
+    pub fn with_blockhash_retry_attempts(mut self, attempts: u32) -> Self {
+        self.blockhash_retry_attempts = attempts;
+        self
+    }
+
+    /// Simulate each real trade before sending and abort when the simulation fails.
+    /// Waits at most `max_latency` for the simulation; past that the trade is sent ungated.
+    pub fn with_simulation_gate(mut self, max_latency: std::time::Duration) -> Self {
+        self.simulation_gate = Some(max_latency);
+        self
+    }
+
+    /// `sendTransaction` settings of the `Default` (RPC) route for trades that do not set their
+    /// own, e.g. preflight enabled for conservative bots.
+    pub fn with_rpc_send_options(mut self, options: RpcSendOptions) -> Self {
+        self.rpc_send_options = options;
+        self
+    }
+
+    /// Attach the base64 wire transaction and a `cluster` explorer link of every lane to the
+    /// results of [`TradingClient::buy_detailed`] / [`TradingClient::sell_detailed`].
+    pub fn with_raw_transactions(mut self, cluster: ExplorerCluster) -> Self {
+        self.raw_transactions = Some(cluster);
+        self
+    }
+
+    /// How trades are sent. [`SendMode::Sponsored`] lets a fee sponsor pay the fees: such trades
+    /// need a recent blockhash and a `Default` (RPC) SWQOS route, carry no relay tip, and backrun
+    /// bundles are still sent directly. [`SendMode::JitoTipOnly`] sends through Jito only, tipped
+    /// and without a CU price.
+    pub fn with_send_mode(mut self, send_mode: SendMode) -> Self {
+        self.send_mode = send_mode;
+        self
+    }
+
+    /// How trades too large for one transaction are sent; see [`TransactionSplit`].
+    pub fn with_transaction_split(mut self, split: TransactionSplit) -> Self {
+        self.transaction_split = split;
+        self
+    }
+
     /// **Advanced.** Use dedicated OS threads for sender pool (and optionally pin to cores).  
     /// By default the SDK uses a shared tokio pool; this can reduce scheduling contention when sending many txs.  
     /// Concurrency and core count are capped internally (≤ max submit lanes, ≤ 2/3 of CPU cores).
@@ -1287,6 +2366,56 @@ impl TradingClient {
         self
     }
 
+    /// **Advanced.** Sign and serialize every SWQOS lane's transaction on `pool` instead of the
+    /// tokio sender task, so bursts of concurrent trades don't stall the reactor. One pool can be
+    /// shared by several clients; pin it to cores not used by the sender threads.
+    pub fn with_signing_pool(mut self, pool: Arc<SigningPool>) -> Self {
+        self.signing_pool = Some(pool);
+        self
+    }
+
+    /// **Advanced.** Pin the trading hot path to cores and raise its thread priority (see
+    /// [`HotPathTuning`]). The tuning is installed process-wide first, then:
+    /// - `swqos_submit`: dedicated sender threads on its cores, as
+    ///   [`Self::with_dedicated_sender_threads`], running at its priority.
+    /// - `build_sign`: a [`SigningPool`] with one thread per listed core (or one unpinned thread).
+    /// - `event_callback`: applied when your event thread calls [`crate::perf::enter_hot_path`].
+    ///
+    /// Sender threads already started by an earlier client keep their priority; configure this
+    /// on the first client. 热路径绑核与优先级：事件回调、签名、SWQOS 发送分别配置。
+    pub fn with_hot_path_tuning(mut self, tuning: HotPathTuning) -> Self {
+        let build_sign = tuning.build_sign.clone();
+        let swqos_submit = tuning.swqos_submit.clone();
+        tuning.install();
+        if let Some(submit) = swqos_submit {
+            self = self.with_dedicated_sender_threads(Some(submit.cores));
+        }
+        if let Some(sign) = build_sign {
+            match SigningPool::with_tuning(sign.cores.len(), sign) {
+                Ok(pool) => self.signing_pool = Some(pool),
+                Err(e) => {
+                    if crate::common::sdk_log::sdk_log_enabled() {
+                        warn!(target: "sol_trade_sdk", "Signing pool not started: {}", e);
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Publish this client's confirmed trades on `bus` instead of its own, so one subscriber
+    /// sees the trades of several clients (e.g. one per wallet).
+    pub fn with_trade_event_bus(mut self, bus: TradeEventBus) -> Self {
+        self.trade_events = bus;
+        self
+    }
+
+    /// Receive a [`TradeExecuted`] for every buy/sell of this client that confirms
+    /// (`wait_tx_confirmed = true`, not simulated). 订阅已确认成交事件。
+    pub fn subscribe_trades(&self) -> tokio::sync::broadcast::Receiver<TradeExecuted> {
+        self.trade_events.subscribe()
+    }
+
     /// Gets the RPC client instance for direct Solana blockchain interactions
     ///
     /// This provides access to the underlying Solana RPC client that can be used
@@ -1298,22 +2427,169 @@ impl TradingClient {
         &self.infrastructure.rpc
     }
 
-    /// Gets the current globally shared SolanaTrade instance
-    ///
-    /// This provides access to the singleton instance that was created with `new()`.
-    /// Useful for accessing the trading instance from different parts of the application.
-    ///
-    /// # Returns
-    /// Returns the Arc-wrapped SolanaTrade instance
+    /// RPC calls made through [`Self::get_rpc`] so far, per method and per subsystem, priced with
+    /// `credits` (e.g. [`RpcCreditTable::helius`]). Counters are shared with every client on the
+    /// same infrastructure.
+    /// RPC 用量：按方法与子系统统计的调用次数与估算 credits。
+    pub fn rpc_usage(&self, credits: &RpcCreditTable) -> RpcUsageStats {
+        self.infrastructure.rpc_usage.stats(credits)
+    }
+
+    /// Zero the counters behind [`Self::rpc_usage`].
+    pub fn reset_rpc_usage(&self) {
+        self.infrastructure.rpc_usage.reset();
+    }
+
+    /// Remaining validity of a blockhash fetched at `blockhash_slot`, from the tracker passed to
+    /// [`Self::with_slot_gate`]; see [`SlotTracker::blockhash_validity`]. `None` without a slot
+    /// gate or before its first slot update.
+    pub fn blockhash_validity(
+        &self,
+        blockhash_slot: u64,
+    ) -> Option<crate::common::slot_tracker::BlockhashValidity> {
+        self.slot_gate.as_ref()?.tracker.blockhash_validity(blockhash_slot)
+    }
+
+    /// Gets the client most recently created with `new()`, whichever wallet it trades for.
     ///
     /// # Panics
     /// Panics if no instance has been initialized yet. Make sure to call `new()` first.
+    #[deprecated(
+        note = "returns whichever client was created last; use `get_instance_for(&wallet)` or `instances()`"
+    )]
     pub fn get_instance() -> Arc<Self> {
-        let instance = INSTANCE.lock();
-        instance
-            .as_ref()
+        let registry = INSTANCES.lock();
+        registry
+            .last_created
+            .and_then(|wallet| registry.by_wallet.get(&wallet).cloned())
             .expect("SolanaTrade instance not initialized. Please call new() first.")
-            .clone()
+    }
+
+    /// Gets the registered client whose payer is `wallet`.
+    ///
+    /// Clients created with `new()` register themselves; clients built with
+    /// [`Self::from_infrastructure`] register through [`Self::register_instance`]. A later
+    /// registration for the same wallet replaces the earlier one.
+    /// 按钱包获取已注册的客户端，多钱包场景下不会拿到其它钱包的实例。
+    pub fn get_instance_for(wallet: &Pubkey) -> Option<Arc<Self>> {
+        INSTANCES.lock().by_wallet.get(wallet).cloned()
+    }
+
+    /// All registered clients, one per wallet, ordered by wallet.
+    pub fn instances() -> Vec<Arc<Self>> {
+        INSTANCES.lock().by_wallet.values().cloned().collect()
+    }
+
+    /// Register a snapshot of this client under its payer wallet for [`Self::get_instance_for`].
+    /// Settings changed afterwards are not reflected; register again after configuring.
+    pub fn register_instance(&self) -> Arc<Self> {
+        let client = Arc::new(self.clone());
+        INSTANCES.lock().by_wallet.insert(self.payer.pubkey(), client.clone());
+        client
+    }
+
+    /// Replace the SWQOS providers used by subsequent trades, e.g. to drop one during an outage.
+    ///
+    /// The new set is connected first (the RPC `Default` route is always included) and then
+    /// swapped in atomically; trades already sending finish on the old set. The RPC client is
+    /// untouched, and `max_sender_concurrency` keeps its startup value. Fails, keeping the current
+    /// set, when no provider could be connected.
+    /// 热更新 SWQOS 通道：连接成功后原子替换，无需重建客户端或 RPC。
+    pub async fn update_swqos(&self, swqos_configs: Vec<SwqosConfig>) -> Result<(), anyhow::Error> {
+        let config = &self.infrastructure.config;
+        let swqos_configs = normalize_swqos_configs(&config.rpc_url, &swqos_configs);
+        let clients = TradingInfrastructure::connect_swqos_clients(config, &swqos_configs).await;
+        if clients.is_empty() {
+            return Err(anyhow::Error::new(TradeError::no_swqos_available(
+                "no SWQOS client could be connected; keeping the current set",
+            )));
+        }
+        self.swqos_clients.store(Arc::new(clients));
+        Ok(())
+    }
+
+    /// Providers new trades are sent through (after [`Self::update_swqos`], if called).
+    pub fn active_providers(&self) -> Vec<SwqosType> {
+        self.swqos_clients.load().iter().map(|client| client.get_swqos_type()).collect()
+    }
+
+    /// Build every subsequent trade with `strategy` instead of the trade's own
+    /// `gas_fee_strategy`; `None` goes back to the per-trade strategies.
+    /// 热更新费率策略：对之后的所有交易生效。
+    pub fn update_gas_strategy(&self, strategy: Option<GasFeeStrategy>) {
+        self.gas_strategy_override.store(strategy.map(Arc::new));
+    }
+
+    /// Fee strategy a `trade_type` trade on `dex` carrying `trade_strategy` is built with: the
+    /// [`Self::update_gas_strategy`] override, else the [`GasFeeModel`], else `trade_strategy`.
+    pub(crate) fn effective_gas_strategy(
+        &self,
+        dex: DexType,
+        trade_type: TradeType,
+        trade_strategy: &GasFeeStrategy,
+    ) -> GasFeeStrategy {
+        if let Some(strategy) = &*self.gas_strategy_override.load() {
+            return GasFeeStrategy::clone(strategy);
+        }
+        match &self.gas_fee_model {
+            Some(model) => {
+                let fees = model.fees_for(dex, trade_type, &self.congestion.load());
+                GasFeeStrategy::from_fee_params(dex, trade_type, &fees)
+            }
+            None => trade_strategy.clone(),
+        }
+    }
+
+    /// Congestion the [`GasFeeModel`] currently sees.
+    pub fn congestion(&self) -> Congestion {
+        **self.congestion.load()
+    }
+
+    /// Replace the congestion handed to the [`GasFeeModel`], e.g. from the caller's own fee feed.
+    pub fn set_congestion(&self, congestion: Congestion) {
+        self.congestion.store(Arc::new(congestion));
+    }
+
+    /// Refresh the congestion from `getRecentPrioritizationFees` for transactions writing
+    /// `accounts` (empty for network-wide fees). Run it on a timer, not per trade.
+    pub async fn refresh_congestion(
+        &self,
+        accounts: &[Pubkey],
+    ) -> Result<Congestion, anyhow::Error> {
+        let fees = self.infrastructure.rpc.get_recent_prioritization_fees(accounts).await?;
+        let congestion =
+            Congestion::from_prioritization_fees(fees.iter().map(|f| f.prioritization_fee));
+        self.set_congestion(congestion);
+        Ok(congestion)
+    }
+
+    /// Reject every new buy and sell with a `TradingHalted` [`TradeError`] until
+    /// [`Self::resume`]. Sells with `reduce_only` set still go through. Applies to clones of this
+    /// client too; trades already being sent are not recalled.
+    /// 熔断：立即拒绝所有新的买卖（`reduce_only` 卖出除外）。
+    pub fn halt(&self) {
+        self.trading_halt.halt();
+    }
+
+    /// Lift [`Self::halt`]. Mints halted with [`Self::halt_mint`] stay halted.
+    pub fn resume(&self) {
+        self.trading_halt.resume();
+    }
+
+    /// Reject new buys and sells of `mint` until [`Self::resume_mint`]; `reduce_only` sells still
+    /// go through.
+    pub fn halt_mint(&self, mint: Pubkey) {
+        self.trading_halt.halt_mint(mint);
+    }
+
+    /// Lift [`Self::halt_mint`] for `mint`.
+    pub fn resume_mint(&self, mint: &Pubkey) {
+        self.trading_halt.resume_mint(mint);
+    }
+
+    /// Whether new trades of `mint` are rejected, by [`Self::halt`] or [`Self::halt_mint`].
+    pub fn is_halted(&self, mint: &Pubkey) -> bool {
+        self.trading_halt.is_mint_halted(mint)
     }
 
     /// Execute a buy order for a specified token
@@ -1348,9 +2624,215 @@ impl TradingClient {
         (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
         anyhow::Error,
     > {
+        legacy_trade_result(self.buy_with_backrun_target(params, None).await)
+    }
+
+    /// [`TradingClient::buy`] returning the full submit timing of every lane, with its raw
+    /// transaction and explorer link when [`TradingClient::with_raw_transactions`] is set.
+    pub async fn buy_detailed(&self, params: TradeBuyParams) -> DetailedTradeResult {
+        self.buy_with_backrun_target(params, None).await
+    }
+
+    /// Backrun mode for copy trading: bundle the built buy right after `target_transaction`
+    /// via Jito `sendBundle`, so the fill lands in the same block directly behind the target.
+    ///
+    /// `target_transaction` must be the full signed transaction observed before it landed
+    /// (e.g. from a shred stream); once it is on-chain the bundle can no longer land.
+    /// Requires a Jito SWQOS client and a Jito buy entry in `gas_fee_strategy`.
+    pub async fn buy_backrun(
+        &self,
+        params: TradeBuyParams,
+        target_transaction: VersionedTransaction,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        legacy_trade_result(
+            self.buy_with_backrun_target(params, Some(Arc::new(target_transaction))).await,
+        )
+    }
+
+    async fn buy_with_backrun_target(
+        &self,
+        params: TradeBuyParams,
+        backrun_target: Option<Arc<VersionedTransaction>>,
+    ) -> DetailedTradeResult {
+        #[cfg(feature = "otel")]
+        let span = crate::common::otel::TradeSpan::start(
+            TradeType::Buy,
+            params.dex_type,
+            &params.mint,
+            params.input_token_amount,
+            params.simulate,
+            params.client_order_id.as_deref(),
+        );
+        let result = self.execute_buy(params, backrun_target).await;
+        #[cfg(feature = "otel")]
+        span.finish(&result);
+        result
+    }
+
+    async fn execute_buy(
+        &self,
+        mut params: TradeBuyParams,
+        backrun_target: Option<Arc<VersionedTransaction>>,
+    ) -> DetailedTradeResult {
+        self.trading_halt.check("buy", &params.mint)?;
+        // Taken before reading a whole-balance amount so a concurrent unwrap cannot drain it.
+        let wsol_guard = self
+            .lock_trade_wsol(
+                params.dex_type,
+                &params.input_token_type,
+                params.close_input_token_ata,
+            )
+            .await?;
+        let amount = match params.input_token_amount {
+            Some(amount) => amount,
+            None => {
+                if params.input_token_type.quote_kind() == QuoteKind::Native {
+                    return Err(anyhow::anyhow!(
+                        "buy input_token_amount None (whole balance) needs a token quote; size native SOL buys explicitly"
+                    ));
+                }
+                params.create_input_token_ata = false;
+                self.payer_full_balance(&params.input_token_type.quote_mint()).await?
+            }
+        };
+        params.input_token_amount = Some(amount);
+        if self.preflight_validation {
+            self.preflight_buy(&params).await?;
+        }
+        // Reserved before the send so concurrent buys of the mint share the limits; dropping the
+        // reservation on any failure below releases it.
+        let reservation = match self.risk_manager.as_ref().filter(|_| !params.skip_risk_checks) {
+            Some(risk) if params.simulate => {
+                risk.check_buy(&params.mint, amount)?;
+                None
+            }
+            Some(risk) => Some(risk.reserve_buy(&params.mint, amount)?),
+            None => None,
+        };
+        let order_claim = self.order_claim(params.client_order_id.as_deref());
+        let (mint, simulate) = (params.mint, params.simulate);
+        let confirms = params.wait_tx_confirmed && !simulate;
+        let (client_order_id, event_slot) = (params.client_order_id.clone(), params.event_slot);
+        let mut result =
+            self.buy_with_retries(params, backrun_target, wsol_guard, order_claim).await;
+        set_event_to_land(&mut result, event_slot);
+        match (&result, reservation) {
+            (Ok((true, ..)), Some(reservation)) if confirms => reservation.confirm(),
+            (Ok((true, ..)), Some(reservation)) => reservation.submitted(),
+            // `skip_risk_checks` buys are not reserved but still recorded once confirmed.
+            (Ok((true, ..)), None) if confirms => {
+                if let Some(risk) = self.risk_manager.as_ref() {
+                    risk.record_buy(&mint, amount);
+                }
+            }
+            _ => {}
+        }
+        if let (true, Ok((true, signatures, ..))) = (confirms, &result) {
+            self.on_trade_confirmed(signatures, client_order_id, None);
+        }
+        self.record_landing(&result);
+        result
+    }
+
+    /// Buy with the configured retries. Every attempt holds the WSOL account until it is sent:
+    /// the first one with `wsol_guard`, later ones lock it again. All attempts share
+    /// `order_claim`, so the order id is claimed once, at the first send.
+    async fn buy_with_retries(
+        &self,
+        mut params: TradeBuyParams,
+        backrun_target: Option<Arc<VersionedTransaction>>,
+        mut wsol_guard: Option<WsolGuard>,
+        order_claim: Option<OrderClaim>,
+    ) -> DetailedTradeResult {
+        // A backrun bundle cannot be retried once its target has landed.
+        let retries = self.slippage_retry_attempts > 0 || self.blockhash_retry_attempts > 0;
+        if !retries || backrun_target.is_some() {
+            let client_order_id = params.client_order_id.clone();
+            let result =
+                self.buy_once(params, backrun_target, Vec::new(), wsol_guard, order_claim).await;
+            self.record_order_signatures(client_order_id.as_deref(), &result);
+            return result;
+        }
+        let (mut slippage_attempt, mut blockhash_attempt) = (0, 0);
+        let mut prior_signatures = Vec::new();
+        loop {
+            let guard = match wsol_guard.take() {
+                Some(guard) => Some(guard),
+                None => {
+                    self.lock_trade_wsol(
+                        params.dex_type,
+                        &params.input_token_type,
+                        params.close_input_token_ata,
+                    )
+                    .await?
+                }
+            };
+            let mut result = self
+                .buy_once(
+                    params.clone(),
+                    None,
+                    prior_signatures.clone(),
+                    guard,
+                    order_claim.clone(),
+                )
+                .await;
+            self.record_order_signatures(params.client_order_id.as_deref(), &result);
+            if !params.wait_tx_confirmed {
+                return result;
+            }
+            if slippage_attempt < self.slippage_retry_attempts
+                && is_slippage_failure(params.dex_type, &result)
+            {
+                slippage_attempt += 1;
+                // The failure landed, so no earlier attempt can land any more.
+                prior_signatures.clear();
+                self.prepare_slippage_retry(
+                    &params.mint,
+                    &mut params.extension_params,
+                    &mut params.recent_blockhash,
+                    &mut params.durable_nonce,
+                )
+                .await?;
+                continue;
+            }
+            if blockhash_attempt < self.blockhash_retry_attempts {
+                if let Some(blockhash) = self
+                    .prepare_blockhash_retry(
+                        &mut result,
+                        params.recent_blockhash,
+                        params.durable_nonce.is_some(),
+                        params.client_order_id.as_deref(),
+                        &prior_signatures,
+                    )
+                    .await?
+                {
+                    blockhash_attempt += 1;
+                    chain_signatures(&mut prior_signatures, &result);
+                    params.recent_blockhash = Some(blockhash);
+                    continue;
+                }
+            }
+            return result;
+        }
+    }
+
+    /// One attempt of a buy; `prior_signatures` are the order's earlier attempts still watched
+    /// for landing (see [`Self::with_blockhash_retry_attempts`]), `wsol_guard` is released once
+    /// the transaction is sent and `order_claim` is taken right before it.
+    async fn buy_once(
+        &self,
+        params: TradeBuyParams,
+        backrun_target: Option<Arc<VersionedTransaction>>,
+        prior_signatures: Vec<Signature>,
+        wsol_guard: Option<WsolGuard>,
+        order_claim: Option<OrderClaim>,
+    ) -> DetailedTradeResult {
         validate_trade_safety(
             "buy",
-            params.input_token_amount,
+            params.input_token_amount.unwrap_or(0),
             params.fixed_output_token_amount,
             params.slippage_basis_points,
         )?;
@@ -1367,28 +2849,26 @@ impl TradingClient {
                 DEFAULT_SLIPPAGE
             );
         }
-        if params.input_token_type == TradeTokenType::USD1 && params.dex_type != DexType::Bonk {
-            return Err(anyhow::anyhow!(
-                " Current version only supports USD1 trading on Bonk protocols"
-            ));
-        }
-        let protocol_params = params.extension_params;
+        validate_quote_token_support(params.dex_type, &params.input_token_type)?;
+        let mut protocol_params = params.extension_params;
         if !validate_protocol_params(params.dex_type, &protocol_params) {
             return Err(anyhow::anyhow!(
                 "Invalid protocol params for Trade (dex={:?})",
                 params.dex_type
             ));
         }
-        let input_token_mint = if params.input_token_type == TradeTokenType::SOL {
-            SOL_TOKEN_ACCOUNT
-        } else if params.input_token_type == TradeTokenType::WSOL {
-            WSOL_TOKEN_ACCOUNT
-        } else if params.input_token_type == TradeTokenType::USDC {
-            USDC_TOKEN_ACCOUNT
-        } else {
-            USD1_TOKEN_ACCOUNT
-        };
+        self.check_slot_gate(params.event_slot)?;
+        self.ensure_fresh_reserves(params.grpc_recv_us, &params.mint, &mut protocol_params).await?;
+        protocol_params.resolve_token_programs(&self.infrastructure.rpc, &params.mint).await?;
+        let input_token_mint = params.input_token_type.quote_mint();
         let executor = TradeFactory::create_executor(params.dex_type);
+        let gas_fee_strategy =
+            self.effective_gas_strategy(params.dex_type, TradeType::Buy, &params.gas_fee_strategy);
+        let data_size_limit = params
+            .data_size_limit
+            .or_else(|| gas_fee_strategy.get_data_size_limit(params.dex_type));
+        let heap_frame_bytes = gas_fee_strategy.get_heap_frame(params.dex_type);
+        let simulation_prelude = self.simulation_prelude(params.simulate).await?;
         let buy_params = SwapParams {
             rpc: Some(self.infrastructure.rpc.clone()),
             payer: self.payer.clone(),
@@ -1397,15 +2877,19 @@ impl TradingClient {
             output_mint: params.mint,
             input_token_program: None,
             output_token_program: None,
-            input_amount: Some(params.input_token_amount),
+            input_amount: params.input_token_amount,
             slippage_basis_points: params.slippage_basis_points,
             address_lookup_table_accounts: params.address_lookup_table_accounts,
             recent_blockhash: params.recent_blockhash,
             wait_tx_confirmed: params.wait_tx_confirmed,
+            confirmation: params.confirmation,
+            rpc_send_options: params.rpc_send_options.unwrap_or(self.rpc_send_options),
+            raw_transactions: self.raw_transactions,
             protocol_params,
             open_seed_optimize: self.use_seed_optimize, // 使用全局seed优化配置
-            swqos_clients: self.infrastructure.swqos_clients.clone(),
+            swqos_clients: self.swqos_clients.load_full(),
             middleware_manager: self.middleware_manager.clone(),
+            compute_budget_manager: self.compute_budget_manager.clone(),
             durable_nonce: params.durable_nonce,
             with_tip: true,
             create_input_mint_ata: params.create_input_token_ata,
@@ -1413,7 +2897,11 @@ impl TradingClient {
             create_output_mint_ata: params.create_mint_ata,
             close_output_mint_ata: false,
             fixed_output_amount: params.fixed_output_token_amount,
-            gas_fee_strategy: params.gas_fee_strategy,
+            min_out_override: params.min_output_token_amount,
+            gas_fee_strategy,
+            data_size_limit,
+            heap_frame_bytes,
+            memo: self.resolve_memo(params.memo),
             simulate: params.simulate,
             log_enabled: self.log_enabled,
             wait_for_all_submits: params.wait_for_all_submits,
@@ -1424,17 +2912,26 @@ impl TradingClient {
             check_min_tip: self.check_min_tip,
             grpc_recv_us: params.grpc_recv_us,
             use_exact_sol_amount: params.use_exact_sol_amount,
+            backrun_target,
+            simulation_gate: self.simulation_gate,
+            latency_budget: params.latency_budget,
+            signing_pool: self.signing_pool.clone(),
+            additional_signers: params.additional_signers,
+            fee_payer: params.fee_payer,
+            pre_instructions: params.pre_instructions,
+            post_instructions: params.post_instructions,
+            send_mode: self.send_mode.clone(),
+            transaction_split: self.transaction_split,
+            simulation_prelude,
+            prior_signatures,
+            wsol_guard,
+            order_claim,
         };
 
         let swap_result = executor.swap(buy_params).await;
-        let result = swap_result.map(|(success, sigs, err, timings)| {
-            let legacy_timings = timings
-                .into_iter()
-                .map(|timing| (timing.swqos_type, timing.submit_done_us))
-                .collect();
-            (success, sigs, err.map(TradeError::from), legacy_timings)
-        });
-        result
+        swap_result.map(|(success, sigs, err, timings)| {
+            (success, sigs, err.map(TradeError::from), timings)
+        })
     }
 
     /// Execute a high-level buy request.
@@ -1482,9 +2979,137 @@ impl TradingClient {
         (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
         anyhow::Error,
     > {
+        legacy_trade_result(self.sell_detailed(params).await)
+    }
+
+    /// [`TradingClient::sell`] returning the full submit timing of every lane, with its raw
+    /// transaction and explorer link when [`TradingClient::with_raw_transactions`] is set.
+    pub async fn sell_detailed(&self, params: TradeSellParams) -> DetailedTradeResult {
+        #[cfg(feature = "otel")]
+        let span = crate::common::otel::TradeSpan::start(
+            TradeType::Sell,
+            params.dex_type,
+            &params.mint,
+            params.input_token_amount,
+            params.simulate,
+            params.client_order_id.as_deref(),
+        );
+        let result = self.execute_sell(params).await;
+        #[cfg(feature = "otel")]
+        span.finish(&result);
+        result
+    }
+
+    async fn execute_sell(&self, mut params: TradeSellParams) -> DetailedTradeResult {
+        if !params.reduce_only {
+            self.trading_halt.check("sell", &params.mint)?;
+        }
+        let closes_position = params.input_token_amount.is_none() || params.close_mint_token_ata;
+        if params.input_token_amount.is_none() {
+            params.input_token_amount = Some(self.payer_full_balance(&params.mint).await?);
+        }
+        let wsol_guard = self
+            .lock_trade_wsol(
+                params.dex_type,
+                &params.output_token_type,
+                params.close_output_token_ata,
+            )
+            .await?;
+        if self.preflight_validation {
+            self.preflight_sell(&params).await?;
+        }
+        let order_claim = self.order_claim(params.client_order_id.as_deref());
+        let confirms = params.wait_tx_confirmed && !params.simulate;
+        let (client_order_id, event_slot) = (params.client_order_id.clone(), params.event_slot);
+        let mut result = self.sell_with_retries(params, wsol_guard, order_claim).await;
+        set_event_to_land(&mut result, event_slot);
+        if let (true, Ok((true, signatures, ..))) = (confirms, &result) {
+            self.on_trade_confirmed(signatures, client_order_id, Some(closes_position));
+        }
+        self.record_landing(&result);
+        result
+    }
+
+    /// Sell counterpart of [`Self::buy_with_retries`].
+    async fn sell_with_retries(
+        &self,
+        mut params: TradeSellParams,
+        mut wsol_guard: Option<WsolGuard>,
+        order_claim: Option<OrderClaim>,
+    ) -> DetailedTradeResult {
+        if self.slippage_retry_attempts == 0 && self.blockhash_retry_attempts == 0 {
+            let client_order_id = params.client_order_id.clone();
+            let result = self.sell_once(params, Vec::new(), wsol_guard, order_claim).await;
+            self.record_order_signatures(client_order_id.as_deref(), &result);
+            return result;
+        }
+        let (mut slippage_attempt, mut blockhash_attempt) = (0, 0);
+        let mut prior_signatures = Vec::new();
+        loop {
+            let guard = match wsol_guard.take() {
+                Some(guard) => Some(guard),
+                None => {
+                    self.lock_trade_wsol(
+                        params.dex_type,
+                        &params.output_token_type,
+                        params.close_output_token_ata,
+                    )
+                    .await?
+                }
+            };
+            let mut result = self
+                .sell_once(params.clone(), prior_signatures.clone(), guard, order_claim.clone())
+                .await;
+            self.record_order_signatures(params.client_order_id.as_deref(), &result);
+            if !params.wait_tx_confirmed {
+                return result;
+            }
+            if slippage_attempt < self.slippage_retry_attempts
+                && is_slippage_failure(params.dex_type, &result)
+            {
+                slippage_attempt += 1;
+                prior_signatures.clear();
+                self.prepare_slippage_retry(
+                    &params.mint,
+                    &mut params.extension_params,
+                    &mut params.recent_blockhash,
+                    &mut params.durable_nonce,
+                )
+                .await?;
+                continue;
+            }
+            if blockhash_attempt < self.blockhash_retry_attempts {
+                if let Some(blockhash) = self
+                    .prepare_blockhash_retry(
+                        &mut result,
+                        params.recent_blockhash,
+                        params.durable_nonce.is_some(),
+                        params.client_order_id.as_deref(),
+                        &prior_signatures,
+                    )
+                    .await?
+                {
+                    blockhash_attempt += 1;
+                    chain_signatures(&mut prior_signatures, &result);
+                    params.recent_blockhash = Some(blockhash);
+                    continue;
+                }
+            }
+            return result;
+        }
+    }
+
+    /// Sell counterpart of [`Self::buy_once`].
+    async fn sell_once(
+        &self,
+        params: TradeSellParams,
+        prior_signatures: Vec<Signature>,
+        wsol_guard: Option<WsolGuard>,
+        order_claim: Option<OrderClaim>,
+    ) -> DetailedTradeResult {
         validate_trade_safety(
             "sell",
-            params.input_token_amount,
+            params.input_token_amount.unwrap_or(0),
             params.fixed_output_token_amount,
             params.slippage_basis_points,
         )?;
@@ -1501,28 +3126,26 @@ impl TradingClient {
                 "Must provide either recent_blockhash or durable_nonce for sell (required for transaction validity)"
             ));
         }
-        if params.output_token_type == TradeTokenType::USD1 && params.dex_type != DexType::Bonk {
-            return Err(anyhow::anyhow!(
-                " Current version only supports USD1 trading on Bonk protocols"
-            ));
-        }
-        let protocol_params = params.extension_params;
+        validate_quote_token_support(params.dex_type, &params.output_token_type)?;
+        let mut protocol_params = params.extension_params;
         if !validate_protocol_params(params.dex_type, &protocol_params) {
             return Err(anyhow::anyhow!(
                 "Invalid protocol params for Trade (dex={:?})",
                 params.dex_type
             ));
         }
+        self.check_slot_gate(params.event_slot)?;
+        self.ensure_fresh_reserves(params.grpc_recv_us, &params.mint, &mut protocol_params).await?;
+        protocol_params.resolve_token_programs(&self.infrastructure.rpc, &params.mint).await?;
         let executor = TradeFactory::create_executor(params.dex_type);
-        let output_token_mint = if params.output_token_type == TradeTokenType::SOL {
-            SOL_TOKEN_ACCOUNT
-        } else if params.output_token_type == TradeTokenType::WSOL {
-            WSOL_TOKEN_ACCOUNT
-        } else if params.output_token_type == TradeTokenType::USDC {
-            USDC_TOKEN_ACCOUNT
-        } else {
-            USD1_TOKEN_ACCOUNT
-        };
+        let output_token_mint = params.output_token_type.quote_mint();
+        let gas_fee_strategy =
+            self.effective_gas_strategy(params.dex_type, TradeType::Sell, &params.gas_fee_strategy);
+        let data_size_limit = params
+            .data_size_limit
+            .or_else(|| gas_fee_strategy.get_data_size_limit(params.dex_type));
+        let heap_frame_bytes = gas_fee_strategy.get_heap_frame(params.dex_type);
+        let simulation_prelude = self.simulation_prelude(params.simulate).await?;
         let sell_params = SwapParams {
             rpc: Some(self.infrastructure.rpc.clone()),
             payer: self.payer.clone(),
@@ -1531,23 +3154,31 @@ impl TradingClient {
             output_mint: output_token_mint,
             input_token_program: None,
             output_token_program: None,
-            input_amount: Some(params.input_token_amount),
+            input_amount: params.input_token_amount,
             slippage_basis_points: params.slippage_basis_points,
             address_lookup_table_accounts: params.address_lookup_table_accounts,
             recent_blockhash: params.recent_blockhash,
             wait_tx_confirmed: params.wait_tx_confirmed,
+            confirmation: params.confirmation,
+            rpc_send_options: params.rpc_send_options.unwrap_or(self.rpc_send_options),
+            raw_transactions: self.raw_transactions,
             protocol_params,
             with_tip: params.with_tip,
             open_seed_optimize: self.use_seed_optimize, // 使用全局seed优化配置
-            swqos_clients: self.infrastructure.swqos_clients.clone(),
+            swqos_clients: self.swqos_clients.load_full(),
             middleware_manager: self.middleware_manager.clone(),
+            compute_budget_manager: self.compute_budget_manager.clone(),
             durable_nonce: params.durable_nonce,
             create_input_mint_ata: false,
             close_input_mint_ata: params.close_mint_token_ata,
             create_output_mint_ata: params.create_output_token_ata,
             close_output_mint_ata: params.close_output_token_ata,
             fixed_output_amount: params.fixed_output_token_amount,
-            gas_fee_strategy: params.gas_fee_strategy,
+            min_out_override: params.min_output_token_amount,
+            gas_fee_strategy,
+            data_size_limit,
+            heap_frame_bytes,
+            memo: self.resolve_memo(params.memo),
             simulate: params.simulate,
             log_enabled: self.log_enabled,
             wait_for_all_submits: params.wait_for_all_submits,
@@ -1558,17 +3189,26 @@ impl TradingClient {
             check_min_tip: self.check_min_tip,
             grpc_recv_us: params.grpc_recv_us,
             use_exact_sol_amount: None,
+            backrun_target: None,
+            simulation_gate: self.simulation_gate,
+            latency_budget: params.latency_budget,
+            signing_pool: self.signing_pool.clone(),
+            additional_signers: params.additional_signers,
+            fee_payer: params.fee_payer,
+            pre_instructions: params.pre_instructions,
+            post_instructions: params.post_instructions,
+            send_mode: self.send_mode.clone(),
+            transaction_split: self.transaction_split,
+            simulation_prelude,
+            prior_signatures,
+            wsol_guard,
+            order_claim,
         };
 
         let swap_result = executor.swap(sell_params).await;
-        let result = swap_result.map(|(success, sigs, err, timings)| {
-            let legacy_timings = timings
-                .into_iter()
-                .map(|timing| (timing.swqos_type, timing.submit_done_us))
-                .collect();
-            (success, sigs, err.map(TradeError::from), legacy_timings)
-        });
-        result
+        swap_result.map(|(success, sigs, err, timings)| {
+            (success, sigs, err.map(TradeError::from), timings)
+        })
     }
 
     /// Execute a high-level sell request.
@@ -1583,16 +3223,17 @@ impl TradingClient {
         self.sell(params.into()).await
     }
 
-    /// Execute a sell order for a percentage of the specified token amount
+    /// Execute a sell order for a share of the token balance, given in basis points
     ///
     /// This is a convenience function that calculates the exact amount to sell based on
-    /// a percentage of the total token amount and then calls the `sell` function.
+    /// a share of the total token amount and then calls the `sell` function.
     ///
     /// # Arguments
     ///
     /// * `params` - Sell trade parameters (will be modified with calculated token amount)
-    /// * `amount_token` - Total amount of tokens available (in smallest token units)
-    /// * `percent` - Percentage of tokens to sell (1-100, where 100 = 100%)
+    /// * `amount_token` - Total amount of tokens available (in smallest token units). `None`
+    ///   fetches the payer's balance of `params.mint` (seed or standard ATA)
+    /// * `basis_points` - Share of tokens to sell (1-10000, where 10000 = 100%, 5000 = 50%)
     ///
     /// # Returns
     ///
@@ -1602,7 +3243,8 @@ impl TradingClient {
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - `percent` is 0 or greater than 100
+    /// - `basis_points` is 0 or greater than 10000
+    /// - The computed amount rounds down to zero
     /// - Invalid protocol parameters are provided for the specified DEX type
     /// - The transaction fails to execute
     /// - Network or RPC errors occur
@@ -1612,20 +3254,153 @@ impl TradingClient {
     pub async fn sell_by_percent(
         &self,
         mut params: TradeSellParams,
-        amount_token: u64,
-        percent: u64,
+        amount_token: Option<u64>,
+        basis_points: u64,
     ) -> Result<
         (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
         anyhow::Error,
     > {
-        if percent == 0 || percent > 100 {
-            return Err(anyhow::anyhow!("Percentage must be between 1 and 100"));
-        }
-        let amount = amount_token * percent / 100;
-        params.input_token_amount = amount;
+        let amount_token = match amount_token {
+            Some(amount) => amount,
+            None => self.payer_full_balance(&params.mint).await?,
+        };
+        params.input_token_amount = Some(amount_by_basis_points(amount_token, basis_points)?);
         self.sell(params).await
     }
 
+    /// Feed the lane outcomes of a confirmed trade to the [`LandingFeeModel`], if any.
+    fn record_landing(&self, result: &DetailedTradeResult) {
+        if let (Some(model), Ok((_, _, _, timings))) = (&self.landing_model, result) {
+            model.record(timings);
+        }
+    }
+
+    /// Decode the landed one of `signatures` off the caller's task, publish it on
+    /// [`Self::trade_events`] and, for a sell (`sell_closes_position` set), record its proceeds
+    /// with the risk manager (see [`RiskManager::record_sell_fill`]). Only one lane's
+    /// transaction lands; the others fail to fetch and are skipped. A signature several lanes
+    /// sent is fetched once per round, and the bus drops it if already published. The RPC may
+    /// not serve a just-confirmed transaction yet, so failed rounds are retried with a backoff
+    /// (see [`TRADE_EXECUTED_PARSE_ATTEMPTS`]).
+    fn on_trade_confirmed(
+        &self,
+        signatures: &[Signature],
+        client_order_id: Option<String>,
+        sell_closes_position: Option<bool>,
+    ) {
+        let bus = self.trade_events.has_subscribers().then(|| self.trade_events.clone());
+        let risk = sell_closes_position.and(self.risk_manager.clone());
+        if bus.is_none() && risk.is_none() {
+            return;
+        }
+        let rpc = self.infrastructure.rpc.clone();
+        let mut seen = HashSet::with_capacity(signatures.len());
+        let signatures: Vec<Signature> =
+            signatures.iter().copied().filter(|signature| seen.insert(*signature)).collect();
+        tokio::spawn(async move {
+            let mut backoff = TRADE_EXECUTED_PARSE_BACKOFF;
+            for attempt in 1..=TRADE_EXECUTED_PARSE_ATTEMPTS {
+                for signature in &signatures {
+                    match crate::trading::common::fill::parse_trade_from_signature(&rpc, signature)
+                        .await
+                    {
+                        Ok(fill) => {
+                            if let (Some(risk), Some(closes)) = (&risk, sell_closes_position) {
+                                risk.record_sell_fill(&fill, closes);
+                            }
+                            if let Some(bus) = &bus {
+                                bus.publish(TradeExecuted::from_fill(fill, client_order_id));
+                            }
+                            return;
+                        }
+                        Err(e) => {
+                            if sdk_log::sdk_log_enabled() {
+                                debug!(
+                                    target: "sol_trade_sdk",
+                                    "TradeExecuted not decoded for {} (attempt {}): {}",
+                                    signature, attempt, e
+                                );
+                            }
+                        }
+                    }
+                }
+                if attempt < TRADE_EXECUTED_PARSE_ATTEMPTS {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+            }
+        });
+    }
+
+    /// Fetch a confirmed transaction and decode its swap into a normalized [`Fill`].
+    ///
+    /// Works for any wallet's trade on the supported DEXes; the fee payer is the trader.
+    pub async fn parse_trade_from_signature(
+        &self,
+        signature: &Signature,
+    ) -> Result<Fill, anyhow::Error> {
+        crate::trading::common::fill::parse_trade_from_signature(
+            &self.infrastructure.rpc,
+            signature,
+        )
+        .await
+    }
+
+    /// Probe a buy of `mint` on `dex` by simulation to verify its accounts and find the ones the
+    /// program expects instead; see [`crate::trading::account_discovery`]. Nothing is sent.
+    /// 模拟一笔小额买入以发现/校验交易所需账户。
+    pub async fn discover_accounts(
+        &self,
+        mint: &Pubkey,
+        dex: DexType,
+        params: Option<DexParamEnum>,
+    ) -> Result<AccountDiscovery, anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        match params {
+            Some(params) => {
+                crate::trading::account_discovery::discover_accounts_with_params(
+                    rpc,
+                    &self.payer,
+                    mint,
+                    dex,
+                    params,
+                )
+                .await
+            }
+            None => {
+                crate::trading::account_discovery::discover_accounts(rpc, &self.payer, mint, dex)
+                    .await
+            }
+        }
+    }
+
+    /// Volume, holder count, top-10 share and LP share of `mint`; see
+    /// [`crate::common::mint_analytics::analyze_mint`].
+    pub async fn analyze_mint(
+        &self,
+        mint: &Pubkey,
+        volume: crate::common::mint_analytics::VolumeSource<'_>,
+    ) -> Result<crate::common::mint_analytics::MintAnalytics, anyhow::Error> {
+        crate::common::mint_analytics::analyze_mint(&self.infrastructure.rpc, mint, volume).await
+    }
+
+    /// Bundled-launch score of `mint` from the funding of its first `max_buyers` buyers; see
+    /// [`crate::common::mint_analytics::analyze_bundles`].
+    pub async fn analyze_bundles(
+        &self,
+        mint: &Pubkey,
+        creator: &Pubkey,
+        max_buyers: usize,
+    ) -> Result<crate::common::mint_analytics::BundleAnalysis, anyhow::Error> {
+        crate::common::mint_analytics::analyze_bundles(
+            &self.infrastructure.rpc,
+            mint,
+            creator,
+            max_buyers,
+        )
+        .await
+    }
+
     /// Wraps native SOL into wSOL (Wrapped SOL) for use in SPL token operations
     ///
     /// This function creates a wSOL associated token account (if it doesn't exist),
@@ -1675,8 +3450,9 @@ impl TradingClient {
     /// - Transaction fails to execute or confirm
     /// - Network or RPC errors occur
     pub async fn close_wsol(&self) -> Result<String, anyhow::Error> {
-        use crate::trading::common::wsol_manager::close_wsol;
+        use crate::trading::common::wsol_manager::{close_wsol, lock_wsol_account};
         use solana_sdk::transaction::Transaction;
+        let _wsol_guard = lock_wsol_account(&self.payer.pubkey(), true).await;
         let recent_blockhash = self.infrastructure.rpc.get_latest_blockhash().await?;
         let instructions = close_wsol(&self.payer.pubkey());
         let mut transaction =
@@ -1748,10 +3524,13 @@ impl TradingClient {
     pub async fn wrap_wsol_to_sol(&self, amount: u64) -> Result<String, anyhow::Error> {
         use crate::common::seed::get_associated_token_address_with_program_id_use_seed;
         use crate::trading::common::wsol_manager::{
-            wrap_wsol_to_sol as wrap_wsol_to_sol_internal, wrap_wsol_to_sol_without_create,
+            lock_wsol_account, wrap_wsol_to_sol as wrap_wsol_to_sol_internal,
+            wrap_wsol_to_sol_without_create,
         };
         use solana_sdk::transaction::Transaction;
 
+        // 转出 WSOL 期间独占 WSOL ATA，避免与并发交易冲突
+        let _wsol_guard = lock_wsol_account(&self.payer.pubkey(), true).await;
         // 检查临时seed账户是否已存在
         let seed_ata_address = get_associated_token_address_with_program_id_use_seed(
             &self.payer.pubkey(),
@@ -1777,6 +3556,538 @@ impl TradingClient {
         Ok(signature.to_string())
     }
 
+    /// Same as [`Self::wrap_sol_to_wsol`], but carries the priority fee (and optional SWQOS tips)
+    /// from `options` so the transaction lands during congestion.
+    pub async fn wrap_sol_to_wsol_with_options(
+        &self,
+        amount: u64,
+        options: &UtilityTxOptions,
+    ) -> Result<String, anyhow::Error> {
+        use crate::trading::common::wsol_manager::handle_wsol;
+        let instructions = handle_wsol(&self.payer.pubkey(), amount).into_vec();
+        self.send_utility_instructions(instructions, options).await
+    }
+
+    /// Same as [`Self::close_wsol`], but carries the priority fee (and optional SWQOS tips)
+    /// from `options`.
+    pub async fn close_wsol_with_options(
+        &self,
+        options: &UtilityTxOptions,
+    ) -> Result<String, anyhow::Error> {
+        use crate::trading::common::wsol_manager::{close_wsol, lock_wsol_account};
+        let _wsol_guard = lock_wsol_account(&self.payer.pubkey(), true).await;
+        let instructions = close_wsol(&self.payer.pubkey());
+        self.send_utility_instructions(instructions, options).await
+    }
+
+    /// Same as [`Self::create_wsol_ata`], but carries the priority fee (and optional SWQOS tips)
+    /// from `options`.
+    pub async fn create_wsol_ata_with_options(
+        &self,
+        options: &UtilityTxOptions,
+    ) -> Result<String, anyhow::Error> {
+        use crate::trading::common::wsol_manager::create_wsol_ata;
+        let instructions = create_wsol_ata(&self.payer.pubkey());
+        if instructions.is_empty() {
+            return Err(anyhow::anyhow!("wSOL ATA already exists or no instructions needed"));
+        }
+        self.send_utility_instructions(instructions, options).await
+    }
+
+    /// Same as [`Self::wrap_wsol_to_sol`], but carries the priority fee (and optional SWQOS tips)
+    /// from `options`.
+    pub async fn wrap_wsol_to_sol_with_options(
+        &self,
+        amount: u64,
+        options: &UtilityTxOptions,
+    ) -> Result<String, anyhow::Error> {
+        use crate::common::seed::get_associated_token_address_with_program_id_use_seed;
+        use crate::trading::common::wsol_manager::{
+            lock_wsol_account, wrap_wsol_to_sol as wrap_wsol_to_sol_internal,
+            wrap_wsol_to_sol_without_create,
+        };
+
+        let seed_ata_address = get_associated_token_address_with_program_id_use_seed(
+            &self.payer.pubkey(),
+            &crate::constants::WSOL_TOKEN_ACCOUNT,
+            &crate::constants::TOKEN_PROGRAM,
+        )?;
+        let _wsol_guard = lock_wsol_account(&self.payer.pubkey(), true).await;
+        let account_exists = self.infrastructure.rpc.get_account(&seed_ata_address).await.is_ok();
+        let instructions = if account_exists {
+            wrap_wsol_to_sol_without_create(&self.payer.pubkey(), amount)?
+        } else {
+            wrap_wsol_to_sol_internal(&self.payer.pubkey(), amount)?
+        };
+        self.send_utility_instructions(instructions, options).await
+    }
+
+    /// Send an externally built, already signed transaction through the SWQOS fan-out.
+    ///
+    /// The same transaction (one signature) goes to every selected route concurrently, so it can
+    /// land at most once. Relays that require a tip only accept it when it already transfers to
+    /// one of their tip accounts. Returns the same shape as [`Self::buy`]: success, the signature,
+    /// the last error and per-route submit-done timestamps (µs); with `wait_tx_confirmed`,
+    /// success also requires confirmation at `options.confirmation`. Each route's outcome is
+    /// counted in [`Self::broadcast_stats`]. Rejected while [`Self::halt`] is active.
+    /// 广播外部构建并已签名的交易：经所有（或指定的）SWQOS 通道并发发送，可选等待确认。
+    pub async fn broadcast(
+        &self,
+        transaction: VersionedTransaction,
+        options: BroadcastOptions,
+    ) -> Result<
+        (bool, Vec<Signature>, Option<TradeError>, Vec<(crate::swqos::SwqosType, i64)>),
+        anyhow::Error,
+    > {
+        if self.trading_halt.is_halted() {
+            return Err(anyhow::Error::new(TradeError {
+                code: crate::common::trading_halt::TRADING_HALTED_ERROR_CODE,
+                message: "TradingHalted: broadcast rejected, all trading is halted".to_string(),
+                instruction: None,
+            }));
+        }
+        let signature = *transaction
+            .signatures
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("Transaction to broadcast is not signed"))?;
+        let swqos_clients = self.swqos_clients.load_full();
+        let routes: Vec<&Arc<SwqosClient>> = swqos_clients
+            .iter()
+            .filter(|client| match &options.swqos_types {
+                Some(types) => types.contains(&client.get_swqos_type()),
+                None => true,
+            })
+            .collect();
+        if routes.is_empty() {
+            return Err(anyhow::anyhow!("No configured SWQOS route matches the broadcast options"));
+        }
+
+        let trade_type = options.trade_type;
+        let rpc_send_options = self.rpc_send_options;
+        let submit_start_us = crate::common::clock::now_micros();
+        let submits = futures::future::join_all(routes.iter().map(|client| {
+            let transaction = &transaction;
+            async move {
+                let submit = client.send_transaction(trade_type, transaction, false);
+                let result = RPC_SEND_OPTIONS.scope(rpc_send_options, submit).await;
+                (client.get_swqos_type(), crate::common::clock::now_micros(), result)
+            }
+        }))
+        .await;
+        let mut timings = Vec::with_capacity(submits.len());
+        let mut accepted = false;
+        let mut last_error = None;
+        let mut accepted_routes = Vec::with_capacity(submits.len());
+        for (swqos_type, submit_done_us, result) in submits {
+            timings.push((swqos_type, submit_done_us));
+            let mut stats = self.broadcast_stats.lock();
+            let route = stats.entry(swqos_type).or_default();
+            route.submit_us_total += (submit_done_us - submit_start_us).max(0) as u64;
+            match result {
+                Ok(()) => {
+                    accepted = true;
+                    route.accepted += 1;
+                    accepted_routes.push(swqos_type);
+                }
+                Err(e) => {
+                    route.rejected += 1;
+                    if self.log_enabled {
+                        warn!(target: "sol_trade_sdk", "Broadcast via {:?} failed: {}", swqos_type, e);
+                    }
+                    last_error = Some(TradeError::from(e));
+                }
+            }
+        }
+        if !accepted {
+            return Ok((false, vec![signature], last_error, timings));
+        }
+        if !options.wait_tx_confirmed {
+            return Ok((true, vec![signature], None, timings));
+        }
+        match crate::swqos::common::poll_any_transaction_confirmation_with(
+            &self.infrastructure.rpc,
+            &[signature],
+            options.confirmation,
+        )
+        .await
+        {
+            Ok(_) => {
+                let mut stats = self.broadcast_stats.lock();
+                for swqos_type in accepted_routes {
+                    stats.entry(swqos_type).or_default().confirmed += 1;
+                }
+                Ok((true, vec![signature], None, timings))
+            }
+            Err(e) => Ok((false, vec![signature], Some(TradeError::from(e)), timings)),
+        }
+    }
+
+    /// Per-route counters of [`Self::broadcast`] since this client (or the one it was cloned
+    /// from) was built.
+    pub fn broadcast_stats(&self) -> Vec<(SwqosType, BroadcastRouteStats)> {
+        self.broadcast_stats
+            .lock()
+            .iter()
+            .map(|(swqos_type, stats)| (*swqos_type, *stats))
+            .collect()
+    }
+
+    /// Sign and send utility instructions with the fee settings in `options`, then wait for confirmation.
+    ///
+    /// RPC path: prepends the `Default` compute budget from the strategy.
+    /// SWQOS path: fans out through [`crate::trading::core::async_executor::execute_parallel`]
+    /// with every route's tip, then polls all returned signatures.
+    async fn send_utility_instructions(
+        &self,
+        instructions: Vec<solana_sdk::instruction::Instruction>,
+        options: &UtilityTxOptions,
+    ) -> Result<String, anyhow::Error> {
+        use crate::trading::common::build_transaction;
+
+        let rpc = &self.infrastructure.rpc;
+        let recent_blockhash = if options.durable_nonce.is_some() {
+            None
+        } else {
+            Some(rpc.get_latest_blockhash().await?)
+        };
+
+        if !options.broadcast_via_swqos {
+            let (unit_limit, unit_price) = default_utility_fee(&options.gas_fee_strategy)?;
+            let transaction = build_transaction(
+                &self.payer,
+                unit_limit,
+                unit_price,
+                &instructions,
+                &[],
+                recent_blockhash,
+                None,
+                self.compute_budget_manager.as_ref(),
+                None,
+                true,
+                false,
+                &Pubkey::default(),
+                0.0,
+                options.durable_nonce.as_ref(),
+            )?;
+            let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+            return Ok(signature.to_string());
+        }
+
+        let sender_config = crate::trading::core::params::SenderConcurrencyConfig {
+            sender_thread_cores: self.sender_thread_cores.clone(),
+            effective_core_ids: self.effective_core_ids.clone(),
+            max_sender_concurrency: self.max_sender_concurrency,
+            signing_pool: self.signing_pool.clone(),
+            rpc_send_options: self.rpc_send_options,
+            raw_transactions: None,
+        };
+        let swqos_clients = self.swqos_clients.load_full();
+        let (_, signatures, last_error, _) =
+            crate::trading::core::async_executor::execute_parallel(
+                swqos_clients.as_slice(),
+                self.payer.clone(),
+                Vec::new(),
+                instructions,
+                Vec::new(),
+                recent_blockhash,
+                options.durable_nonce.clone(),
+                None,
+                self.compute_budget_manager.clone(),
+                "Utility",
+                true,
+                false,
+                true,
+                true,
+                options.gas_fee_strategy.clone(),
+                self.use_dedicated_sender_threads,
+                sender_config,
+                self.check_min_tip,
+                None,
+            )
+            .await?;
+        let submitted: Vec<Signature> =
+            signatures.into_iter().filter(|s| *s != Signature::default()).collect();
+        if submitted.is_empty() {
+            return Err(last_error
+                .unwrap_or_else(|| anyhow::anyhow!("No SWQOS route accepted the transaction")));
+        }
+        let signature =
+            crate::swqos::common::poll_any_transaction_confirmation(rpc, &submitted, true).await?;
+        Ok(signature.to_string())
+    }
+
+    /// Prepare a retry after a slippage failure: refresh reserves so builders recompute min-out,
+    /// and move to a fresh blockhash / nonce (a landed failure consumes the durable nonce).
+    async fn prepare_slippage_retry(
+        &self,
+        mint: &Pubkey,
+        protocol_params: &mut DexParamEnum,
+        recent_blockhash: &mut Option<Hash>,
+        durable_nonce: &mut Option<DurableNonceInfo>,
+    ) -> Result<(), anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        protocol_params.refresh_reserves_by_rpc(rpc, mint).await?;
+        if let Some(nonce_account) = durable_nonce.as_ref().and_then(|n| n.nonce_account) {
+            *durable_nonce = Some(
+                crate::common::nonce_cache::fetch_nonce_info(rpc, nonce_account)
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Failed to refetch durable nonce for retry"))?,
+            );
+        } else if recent_blockhash.is_some() {
+            *recent_blockhash = Some(rpc.get_latest_blockhash().await?);
+        }
+        if sdk_log::sdk_log_enabled() {
+            info!(target: "sol_trade_sdk", "Slippage exceeded, retrying with refreshed reserves");
+        }
+        Ok(())
+    }
+
+    /// Prepare a resend after a confirmation timeout: the fresh blockhash to rebuild with, or
+    /// `None` when the order must not be resent. Only trades built on `recent_blockhash` qualify
+    /// (see [`blockhash_retry_candidate`]), and only once that blockhash has expired at
+    /// `confirmed`; before that the sent transactions can still land, and a rebuild could fill
+    /// the order twice. An attempt found on-chain by then (history included) replaces `result`
+    /// instead of being resent.
+    async fn prepare_blockhash_retry(
+        &self,
+        result: &mut DetailedTradeResult,
+        recent_blockhash: Option<Hash>,
+        durable_nonce: bool,
+        client_order_id: Option<&str>,
+        prior_signatures: &[Signature],
+    ) -> Result<Option<Hash>, anyhow::Error> {
+        let Some(blockhash) = blockhash_retry_candidate(result, recent_blockhash, durable_nonce)
+        else {
+            return Ok(None);
+        };
+        let rpc = &self.infrastructure.rpc;
+        // Expiry first: once it is past, no attempt can land after the status lookup below.
+        let blockhash_valid =
+            rpc.is_blockhash_valid(&blockhash, CommitmentConfig::confirmed()).await?;
+        let mut attempts = prior_signatures.to_vec();
+        chain_signatures(&mut attempts, result);
+        let statuses = if attempts.is_empty() {
+            Vec::new()
+        } else {
+            rpc.get_signature_statuses_with_history(&attempts).await?.value
+        };
+        let landed = attempts
+            .iter()
+            .zip(&statuses)
+            .find_map(|(signature, status)| status.as_ref().map(|status| (*signature, status)));
+        match blockhash_retry_step(blockhash_valid, landed.map(|(signature, _)| signature)) {
+            BlockhashRetryStep::Keep => return Ok(None),
+            BlockhashRetryStep::Landed(signature) => {
+                let error = match landed.and_then(|(_, status)| status.err.as_ref()) {
+                    Some(err) => {
+                        let tx_err: solana_sdk::transaction::TransactionError =
+                            serde_json::from_value(serde_json::to_value(err)?)?;
+                        Some(crate::swqos::common::landed_trade_error(&tx_err, tx_err.to_string()))
+                    }
+                    None => None,
+                };
+                let timings = match result {
+                    Ok((.., timings)) => timings.clone(),
+                    Err(_) => Vec::new(),
+                };
+                let mut signatures = vec![signature];
+                signatures.extend(attempts.into_iter().filter(|s| *s != signature));
+                *result = Ok((error.is_none(), signatures, error, timings));
+                return Ok(None);
+            }
+            BlockhashRetryStep::Resend => {}
+        }
+        let fresh = rpc.get_latest_blockhash().await?;
+        if sdk_log::sdk_log_enabled() {
+            info!(
+                target: "sol_trade_sdk",
+                "Blockhash expired before order {} landed, resending with a fresh blockhash",
+                client_order_id.unwrap_or("-")
+            );
+        }
+        Ok(Some(fresh))
+    }
+
+    /// Every signature sent for `client_order_id` across its attempts (slippage and blockhash
+    /// retries included), in sending order. Empty for unknown ids; only the most recent 1024
+    /// orders are kept.
+    pub fn order_signatures(&self, client_order_id: &str) -> Vec<Signature> {
+        self.order_signatures
+            .lock()
+            .iter()
+            .find(|(id, _)| id == client_order_id)
+            .map(|(_, signatures)| signatures.clone())
+            .unwrap_or_default()
+    }
+
+    /// Add the signatures `result` sent to the chain behind [`Self::order_signatures`].
+    fn record_order_signatures(&self, client_order_id: Option<&str>, result: &DetailedTradeResult) {
+        let Some(id) = client_order_id else {
+            return;
+        };
+        let mut orders = self.order_signatures.lock();
+        let index = match orders.iter().position(|(known, _)| known == id) {
+            Some(index) => index,
+            None => {
+                if orders.len() >= ORDER_SIGNATURES_CAPACITY {
+                    orders.pop_front();
+                }
+                orders.push_back((id.to_string(), Vec::new()));
+                orders.len() - 1
+            }
+        };
+        chain_signatures(&mut orders[index].1, result);
+    }
+
+    /// The [`OrderClaim`] for `client_order_id` under [`Self::order_dedup`]; the executor takes
+    /// it right before the first send and rejects a duplicate within the window.
+    fn order_claim(&self, client_order_id: Option<&str>) -> Option<OrderClaim> {
+        let (Some(dedup), Some(id)) = (self.order_dedup.as_ref(), client_order_id) else {
+            return None;
+        };
+        Some(OrderClaim::new(dedup.clone(), id))
+    }
+
+    /// Apply [`Self::slot_gate`]: reject with a `SlotLag` [`TradeError`] when the cluster is too far past `event_slot`.
+    fn check_slot_gate(&self, event_slot: Option<u64>) -> Result<(), anyhow::Error> {
+        check_slot_lag(self.slot_gate.as_ref(), event_slot)
+    }
+
+    /// Hold the payer WSOL ATA for a trade that wraps into, spends from or closes it (see
+    /// [`crate::trading::common::wsol_manager::lock_wsol_account`]). Other trades skip the lock.
+    /// Waits or fails on contention following [`Self::wsol_lock_mode`].
+    async fn lock_trade_wsol(
+        &self,
+        dex_type: DexType,
+        quote: &TradeTokenType,
+        closes: bool,
+    ) -> Result<Option<WsolGuard>, anyhow::Error> {
+        use crate::trading::common::wsol_manager::{lock_wsol_account, try_lock_wsol_account};
+        if plan::quote_account_mint(dex_type, quote) != Some(WSOL_TOKEN_ACCOUNT) {
+            return Ok(None);
+        }
+        let payer = self.payer.pubkey();
+        if self.wsol_lock_mode == WsolLockMode::Wait {
+            return Ok(Some(lock_wsol_account(&payer, closes).await));
+        }
+        try_lock_wsol_account(&payer, closes)
+            .map(Some)
+            .map_err(|in_flight| PreflightError::WsolAccountInUse { in_flight }.into())
+    }
+
+    /// Payer balance of `mint` in the token account the trade path uses (seed or standard ATA).
+    async fn payer_full_balance(&self, mint: &Pubkey) -> Result<u64, anyhow::Error> {
+        let (token_program, _) =
+            crate::trading::common::get_mint_program_and_decimals(self.get_rpc(), mint).await?;
+        self.get_payer_token_balance_with_program(mint, &token_program).await
+    }
+
+    /// Apply [`Self::stale_reserve_guard`]: when the triggering event is older than allowed,
+    /// refresh pool reserves via RPC or abort with a `StaleQuote` [`TradeError`].
+    async fn ensure_fresh_reserves(
+        &self,
+        grpc_recv_us: Option<i64>,
+        mint: &Pubkey,
+        protocol_params: &mut DexParamEnum,
+    ) -> Result<(), anyhow::Error> {
+        let (Some(guard), Some(event_us)) = (self.stale_reserve_guard, grpc_recv_us) else {
+            return Ok(());
+        };
+        let now_us = crate::common::clock::now_micros();
+        if !guard.is_stale(event_us, now_us) {
+            return Ok(());
+        }
+        let age_us = now_us - event_us;
+        match guard.action {
+            StaleReserveAction::Abort => Err(anyhow::Error::new(TradeError {
+                code: crate::swqos::common::STALE_QUOTE_ERROR_CODE,
+                message: format!(
+                    "StaleQuote: reserves are {}us old (max {}us)",
+                    age_us, guard.max_age_us
+                ),
+                instruction: None,
+            })),
+            StaleReserveAction::Refresh => {
+                if sdk_log::sdk_log_enabled() {
+                    info!(
+                        target: "sol_trade_sdk",
+                        "Reserves {}us old (max {}us), refreshing via RPC", age_us, guard.max_age_us
+                    );
+                }
+                protocol_params.refresh_reserves_by_rpc(&self.infrastructure.rpc, mint).await
+            }
+        }
+    }
+
+    /// Withdraw SOL from the payer to `destination` (e.g. a cold wallet).
+    ///
+    /// When a withdraw allowlist is configured, any other destination is rejected
+    /// before anything is sent. `WithdrawAmount::All` sweeps the balance minus the fee.
+    pub async fn withdraw(
+        &self,
+        destination: &Pubkey,
+        amount: WithdrawAmount,
+    ) -> Result<Signature, anyhow::Error> {
+        use solana_sdk::transaction::Transaction;
+        use solana_system_interface::instruction as system_instruction;
+
+        validate_withdraw_destination(
+            self.withdraw_allowlist.as_ref().map(|v| v.as_slice()),
+            &self.payer.pubkey(),
+            destination,
+        )?;
+        let rpc = &self.infrastructure.rpc;
+        let balance = rpc.get_balance(&self.payer.pubkey()).await?;
+        let lamports = match amount {
+            WithdrawAmount::Exact(lamports) => lamports,
+            WithdrawAmount::All => balance.saturating_sub(WITHDRAW_FEE_RESERVE_LAMPORTS),
+        };
+        if lamports == 0 {
+            return Err(anyhow::anyhow!("withdraw amount must be greater than zero"));
+        }
+        if lamports.saturating_add(WITHDRAW_FEE_RESERVE_LAMPORTS) > balance {
+            return Err(anyhow::anyhow!(
+                "Insufficient balance for withdraw: {} lamports + fee > {}",
+                lamports,
+                balance
+            ));
+        }
+
+        let ix = system_instruction::transfer(&self.payer.pubkey(), destination, lamports);
+        let recent_blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.payer.pubkey()),
+            &[self.payer.as_ref()],
+            recent_blockhash,
+        );
+        Ok(rpc.send_and_confirm_transaction(&transaction).await?)
+    }
+
+    /// Withdraw SPL tokens of `mint` from the payer to `destination`, subject to the same allowlist as [`Self::withdraw`].
+    pub async fn withdraw_token(
+        &self,
+        mint: &Pubkey,
+        destination: &Pubkey,
+        amount: WithdrawAmount,
+    ) -> Result<Signature, anyhow::Error> {
+        validate_withdraw_destination(
+            self.withdraw_allowlist.as_ref().map(|v| v.as_slice()),
+            &self.payer.pubkey(),
+            destination,
+        )?;
+        let amount = match amount {
+            WithdrawAmount::Exact(amount) => amount,
+            WithdrawAmount::All => {
+                let (token_program, _) =
+                    crate::trading::common::get_mint_program_and_decimals(self.get_rpc(), mint)
+                        .await?;
+                self.get_payer_token_balance_with_program(mint, &token_program).await?
+            }
+        };
+        self.transfer_token(mint, destination, amount).await
+    }
+
     /// Claim Bonding Curve (Pump) cashback.
     ///
     /// Transfers native SOL from the user's UserVolumeAccumulator to the wallet.
@@ -1832,6 +4143,198 @@ impl TradingClient {
     }
 }
 
+/// Lamports kept back by `WithdrawAmount::All` to pay the single-signature transaction fee.
+const WITHDRAW_FEE_RESERVE_LAMPORTS: u64 = 5_000;
+
+/// Orders kept by [`TradingClient::order_signatures`].
+const ORDER_SIGNATURES_CAPACITY: usize = 1024;
+
+/// Rounds of fetching a confirmed trade for [`TradeExecuted`] before giving up.
+const TRADE_EXECUTED_PARSE_ATTEMPTS: u32 = 4;
+
+/// Wait before the second round; doubled after each further round (250ms, 500ms, 1s).
+const TRADE_EXECUTED_PARSE_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Quote tokens each DEX builder can route. USDC works wherever the pool is USDC-quoted
+/// (PumpFun V2, PumpSwap, Raydium CPMM/AMM v4, Meteora DAMM v2); Bonk pools are SOL or USD1.
+/// Other SPL quotes go through the generic two-token builders only; `Spl` of the native SOL
+/// sentinel is rejected because the builders would treat it as a token account.
+fn validate_quote_token_support(
+    dex_type: DexType,
+    token_type: &TradeTokenType,
+) -> Result<(), UnsupportedQuoteError> {
+    let quote_mint = token_type.quote_mint();
+    let supported = if quote_mint == USD1_TOKEN_ACCOUNT {
+        dex_type == DexType::Bonk
+    } else if quote_mint == USDC_TOKEN_ACCOUNT {
+        dex_type != DexType::Bonk
+    } else if quote_mint == SOL_TOKEN_ACCOUNT {
+        token_type.quote_kind() == QuoteKind::Native
+    } else if quote_mint == WSOL_TOKEN_ACCOUNT {
+        true
+    } else {
+        matches!(dex_type, DexType::RaydiumCpmm | DexType::RaydiumAmmV4 | DexType::MeteoraDammV2)
+    };
+    if supported {
+        Ok(())
+    } else {
+        Err(UnsupportedQuoteError { dex_type, quote_mint })
+    }
+}
+
+/// `SlotLag` rejection of [`TradingClient::check_slot_gate`].
+fn check_slot_lag(gate: Option<&SlotGate>, event_slot: Option<u64>) -> Result<(), anyhow::Error> {
+    let (Some(gate), Some(event_slot)) = (gate, event_slot) else {
+        return Ok(());
+    };
+    match gate.exceeded_lag(event_slot) {
+        Some(lag) => Err(anyhow::Error::new(TradeError {
+            code: crate::swqos::common::SLOT_LAG_ERROR_CODE,
+            message: format!(
+                "SlotLag: cluster is {} slots past event slot {} (max {})",
+                lag, event_slot, gate.max_slot_lag
+            ),
+            instruction: None,
+        })),
+        None => Ok(()),
+    }
+}
+
+/// Reduce per-lane submit timings to the `(swqos_type, submit_done_us)` pairs of the public API.
+fn legacy_trade_result(
+    result: DetailedTradeResult,
+) -> Result<(bool, Vec<Signature>, Option<TradeError>, Vec<(SwqosType, i64)>), anyhow::Error> {
+    result.map(|(success, signatures, error, timings)| {
+        let timings =
+            timings.into_iter().map(|timing| (timing.swqos_type, timing.submit_done_us)).collect();
+        (success, signatures, error, timings)
+    })
+}
+
+/// Fill `event_to_land_slots` of the landed lane when the trade carried its event slot.
+fn set_event_to_land(result: &mut DetailedTradeResult, event_slot: Option<u64>) {
+    let (Ok((_, _, _, timings)), Some(event_slot)) = (result, event_slot) else {
+        return;
+    };
+    for timing in timings {
+        timing.event_to_land_slots = timing.landed_slot.map(|slot| slot.saturating_sub(event_slot));
+    }
+}
+
+/// Whether a trade result is a confirmed on-chain failure with the protocol's slippage error.
+fn is_slippage_failure<T>(
+    dex_type: DexType,
+    result: &Result<(bool, Vec<Signature>, Option<TradeError>, T), anyhow::Error>,
+) -> bool {
+    let code = match result {
+        Ok((false, _, Some(err), _)) => err.code,
+        Err(err) => match err.downcast_ref::<TradeError>() {
+            Some(err) => err.code,
+            None => return false,
+        },
+        _ => return false,
+    };
+    dex_type.slippage_error_codes().contains(&code)
+}
+
+fn is_confirmation_timeout<T>(
+    result: &Result<(bool, Vec<Signature>, Option<TradeError>, T), anyhow::Error>,
+) -> bool {
+    let code = match result {
+        Ok((false, _, Some(err), _)) => err.code,
+        Err(err) => match err.downcast_ref::<TradeError>() {
+            Some(err) => err.code,
+            None => return false,
+        },
+        _ => return false,
+    };
+    code == crate::swqos::common::CONFIRMATION_TIMEOUT_ERROR_CODE
+}
+
+/// Trades eligible for a blockhash resend: confirmation timeouts of transactions built on a
+/// `recent_blockhash`. Durable nonce trades never expire, and trades without a blockhash (the
+/// SDK fetched one internally) cannot be rebuilt on a fresh one, so neither is ever resent.
+fn blockhash_retry_candidate<T>(
+    result: &Result<(bool, Vec<Signature>, Option<TradeError>, T), anyhow::Error>,
+    recent_blockhash: Option<Hash>,
+    durable_nonce: bool,
+) -> Option<Hash> {
+    recent_blockhash.filter(|_| !durable_nonce && is_confirmation_timeout(result))
+}
+
+/// What [`TradingClient::prepare_blockhash_retry`] does with a timed-out order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockhashRetryStep {
+    /// Report the timeout as is: the blockhash is still valid, so an attempt may yet land.
+    Keep,
+    /// Rebuild with a fresh blockhash: it expired and no attempt landed.
+    Resend,
+    /// Report this attempt, which landed after its poll gave up.
+    Landed(Signature),
+}
+
+/// Decide from the blockhash validity at `confirmed`, checked before the status lookup, and the
+/// first of the order's signatures found on-chain.
+fn blockhash_retry_step(blockhash_valid: bool, landed: Option<Signature>) -> BlockhashRetryStep {
+    match landed {
+        Some(signature) => BlockhashRetryStep::Landed(signature),
+        None if blockhash_valid => BlockhashRetryStep::Keep,
+        None => BlockhashRetryStep::Resend,
+    }
+}
+
+/// Add the signatures `result` sent to the order's chain of attempts.
+fn chain_signatures<T>(
+    chain: &mut Vec<Signature>,
+    result: &Result<(bool, Vec<Signature>, Option<TradeError>, T), anyhow::Error>,
+) {
+    if let Ok((_, signatures, ..)) = result {
+        for signature in signatures {
+            if *signature != Signature::default() && !chain.contains(signature) {
+                chain.push(*signature);
+            }
+        }
+    }
+}
+
+fn validate_withdraw_destination(
+    allowlist: Option<&[Pubkey]>,
+    payer: &Pubkey,
+    destination: &Pubkey,
+) -> Result<(), anyhow::Error> {
+    if destination == payer {
+        return Err(anyhow::anyhow!("withdraw destination must differ from the payer"));
+    }
+    if let Some(allowlist) = allowlist {
+        if !allowlist.contains(destination) {
+            return Err(anyhow::anyhow!(
+                "withdraw destination {} is not in the withdraw allowlist",
+                destination
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `amount * basis_points / 10_000`, rounded down, with u128 intermediate math.
+fn amount_by_basis_points(amount: u64, basis_points: u64) -> Result<u64, anyhow::Error> {
+    if basis_points == 0 || basis_points > 10_000 {
+        return Err(anyhow::anyhow!(
+            "Basis points must be between 1 and 10000, got {}",
+            basis_points
+        ));
+    }
+    let share = (amount as u128 * basis_points as u128 / 10_000) as u64;
+    if share == 0 {
+        return Err(anyhow::anyhow!(
+            "{} bps of {} rounds down to zero tokens",
+            basis_points,
+            amount
+        ));
+    }
+    Ok(share)
+}
+
 fn validate_trade_safety(
     side: &str,
     input_amount: u64,
@@ -1863,7 +4366,7 @@ mod tests {
     use crate::swqos::SwqosRegion;
     use std::sync::Arc;
 
-    fn dummy_pumpfun_params() -> DexParamEnum {
+    pub(super) fn dummy_pumpfun_params() -> DexParamEnum {
         DexParamEnum::PumpFun(PumpFunParams {
             bonding_curve: Arc::new(Default::default()),
             associated_bonding_curve: Pubkey::default(),
@@ -1885,12 +4388,240 @@ mod tests {
         assert!(validate_trade_safety("sell", 1, None, Some(u64::MAX)).is_err());
     }
 
+    #[test]
+    fn basis_points_share_uses_wide_math() {
+        assert_eq!(amount_by_basis_points(1_000, 10_000).unwrap(), 1_000);
+        assert_eq!(amount_by_basis_points(1_000, 2_500).unwrap(), 250);
+        assert_eq!(amount_by_basis_points(u64::MAX, 10_000).unwrap(), u64::MAX);
+        assert_eq!(amount_by_basis_points(u64::MAX, 5_000).unwrap(), u64::MAX / 2);
+        assert!(amount_by_basis_points(1_000, 0).is_err());
+        assert!(amount_by_basis_points(1_000, 10_001).is_err());
+        assert!(amount_by_basis_points(1, 1).is_err());
+    }
+
     #[test]
     fn trade_safety_accepts_bounded_values() {
         assert!(validate_trade_safety("buy", 1, None, None).is_ok());
         assert!(validate_trade_safety("buy", 1, Some(1), Some(9_999)).is_ok());
     }
 
+    #[test]
+    fn quote_token_support_matches_builders() {
+        for dex in [
+            DexType::PumpFun,
+            DexType::PumpSwap,
+            DexType::RaydiumCpmm,
+            DexType::RaydiumAmmV4,
+            DexType::MeteoraDammV2,
+        ] {
+            assert!(validate_quote_token_support(dex, &TradeTokenType::USDC).is_ok());
+            assert!(validate_quote_token_support(dex, &TradeTokenType::USD1).is_err());
+        }
+        assert!(validate_quote_token_support(DexType::Bonk, &TradeTokenType::USD1).is_ok());
+        assert!(validate_quote_token_support(DexType::Bonk, &TradeTokenType::USDC).is_err());
+    }
+
+    #[test]
+    fn spl_quote_resolves_mint_and_kind() {
+        let jitosol = Pubkey::new_unique();
+        let quote = TradeTokenType::Spl(jitosol);
+
+        assert_eq!(quote.quote_mint(), jitosol);
+        assert_eq!(quote.quote_kind(), QuoteKind::Spl);
+        assert_eq!(TradeTokenType::SOL.quote_kind(), QuoteKind::Native);
+        assert_eq!(TradeTokenType::Spl(USDC_TOKEN_ACCOUNT).quote_mint(), USDC_TOKEN_ACCOUNT);
+
+        assert!(validate_quote_token_support(DexType::RaydiumCpmm, &quote).is_ok());
+        assert!(validate_quote_token_support(DexType::MeteoraDammV2, &quote).is_ok());
+        assert!(validate_quote_token_support(DexType::PumpSwap, &quote).is_err());
+        assert!(validate_quote_token_support(
+            DexType::Bonk,
+            &TradeTokenType::Spl(USDC_TOKEN_ACCOUNT)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn unsupported_quotes_are_rejected_with_a_typed_error() {
+        let jitosol = Pubkey::new_unique();
+        for dex in [DexType::PumpFun, DexType::PumpSwap, DexType::Bonk] {
+            let err = validate_quote_token_support(dex, &TradeTokenType::Spl(jitosol)).unwrap_err();
+            assert_eq!(err, UnsupportedQuoteError { dex_type: dex, quote_mint: jitosol });
+        }
+        assert_eq!(
+            validate_quote_token_support(
+                DexType::RaydiumCpmm,
+                &TradeTokenType::Spl(SOL_TOKEN_ACCOUNT)
+            ),
+            Err(UnsupportedQuoteError {
+                dex_type: DexType::RaydiumCpmm,
+                quote_mint: SOL_TOKEN_ACCOUNT
+            })
+        );
+        assert!(validate_quote_token_support(DexType::PumpFun, &TradeTokenType::SOL).is_ok());
+        assert!(validate_quote_token_support(
+            DexType::PumpSwap,
+            &TradeTokenType::Spl(WSOL_TOKEN_ACCOUNT)
+        )
+        .is_ok());
+
+        let err: anyhow::Error =
+            validate_quote_token_support(DexType::Bonk, &TradeTokenType::USDC).unwrap_err().into();
+        assert!(err.downcast_ref::<UnsupportedQuoteError>().is_some());
+    }
+
+    #[test]
+    fn slippage_failure_matches_protocol_error_codes() {
+        let failed =
+            |code: u32| -> Result<(bool, Vec<Signature>, Option<TradeError>, ()), anyhow::Error> {
+                Ok((
+                    false,
+                    vec![],
+                    Some(TradeError { code, message: String::new(), instruction: None }),
+                    (),
+                ))
+            };
+
+        assert!(is_slippage_failure(DexType::PumpFun, &failed(6003)));
+        assert!(is_slippage_failure(DexType::RaydiumAmmV4, &failed(30)));
+        assert!(!is_slippage_failure(DexType::PumpSwap, &failed(6003)));
+        assert!(!is_slippage_failure(DexType::PumpFun, &Ok((true, vec![], None, ()))));
+    }
+
+    #[test]
+    fn timed_out_attempts_chain_their_signatures() {
+        let timeout = crate::swqos::common::CONFIRMATION_TIMEOUT_ERROR_CODE;
+        let first = Signature::new_unique();
+        let attempt = |signatures: Vec<Signature>, code: u32| -> Result<_, anyhow::Error> {
+            let error = TradeError { code, message: String::new(), instruction: None };
+            Ok((false, signatures, Some(error), ()))
+        };
+
+        let timed_out = attempt(vec![first, first, Signature::default()], timeout);
+        assert!(is_confirmation_timeout(&timed_out));
+        assert!(!is_confirmation_timeout(&attempt(vec![first], 6003)));
+        assert!(!is_confirmation_timeout(&Ok((true, vec![first], None, ()))));
+
+        let mut chain = Vec::new();
+        chain_signatures(&mut chain, &timed_out);
+        let second = Signature::new_unique();
+        chain_signatures(&mut chain, &attempt(vec![second, first], timeout));
+        assert_eq!(chain, vec![first, second]);
+    }
+
+    #[test]
+    fn blockhash_retry_resends_only_expired_unlanded_orders() {
+        let timeout = crate::swqos::common::CONFIRMATION_TIMEOUT_ERROR_CODE;
+        let timed_out: Result<_, anyhow::Error> = Ok((
+            false,
+            vec![Signature::new_unique()],
+            Some(TradeError { code: timeout, message: String::new(), instruction: None }),
+            (),
+        ));
+        let blockhash = Hash::new_unique();
+        assert_eq!(blockhash_retry_candidate(&timed_out, Some(blockhash), false), Some(blockhash));
+        // Durable nonce trades and trades without a blockhash are never resent.
+        assert_eq!(blockhash_retry_candidate(&timed_out, Some(blockhash), true), None);
+        assert_eq!(blockhash_retry_candidate(&timed_out, None, false), None);
+        let landed: Result<_, anyhow::Error> = Ok((true, vec![Signature::new_unique()], None, ()));
+        assert_eq!(blockhash_retry_candidate(&landed, Some(blockhash), false), None);
+
+        let late = Signature::new_unique();
+        assert_eq!(blockhash_retry_step(false, None), BlockhashRetryStep::Resend);
+        assert_eq!(blockhash_retry_step(true, None), BlockhashRetryStep::Keep);
+        // A late landing is reported instead of sending the order again.
+        assert_eq!(blockhash_retry_step(false, Some(late)), BlockhashRetryStep::Landed(late));
+        assert_eq!(blockhash_retry_step(true, Some(late)), BlockhashRetryStep::Landed(late));
+    }
+
+    #[test]
+    fn event_to_land_is_set_on_the_landed_lane_only() {
+        let timing = |landed_slot| SwqosSubmitTiming {
+            swqos_type: SwqosType::Jito,
+            strategy_type: crate::common::GasFeeStrategyType::Normal,
+            submit_done_us: 0,
+            signature: Signature::new_unique(),
+            cu_price: 0,
+            tip_lamports: 0,
+            landed: Some(landed_slot.is_some()),
+            landed_slot,
+            block_time: None,
+            event_to_land_slots: None,
+            raw_transaction: None,
+        };
+        let landed = |timings| -> DetailedTradeResult { Ok((true, Vec::new(), None, timings)) };
+        let lanes = |result: &DetailedTradeResult| -> Vec<Option<u64>> {
+            let timings = &result.as_ref().unwrap().3;
+            timings.iter().map(|timing| timing.event_to_land_slots).collect()
+        };
+
+        let mut result = landed(vec![timing(None), timing(Some(110))]);
+        set_event_to_land(&mut result, Some(100));
+        assert_eq!(lanes(&result), [None, Some(10)]);
+
+        // Without an event slot nothing is measured; a later event slot saturates at 0.
+        let mut result = landed(vec![timing(Some(110))]);
+        set_event_to_land(&mut result, None);
+        assert_eq!(lanes(&result), [None]);
+        set_event_to_land(&mut result, Some(120));
+        assert_eq!(lanes(&result), [Some(0)]);
+    }
+
+    #[test]
+    fn slot_gate_rejection_leaves_the_order_id_for_a_retry() {
+        let tracker = SlotTracker::new();
+        tracker.observe(1_000);
+        let gate = SlotGate::new(tracker, 10);
+        let dedup = OrderDedup::in_memory(std::time::Duration::from_secs(60));
+
+        // The stale event stops the trade before the executor would claim the id.
+        let first = OrderClaim::new(dedup.clone(), "order-1");
+        assert!(check_slot_lag(Some(&gate), Some(900)).is_err());
+        assert!(!first.is_claimed());
+
+        // The caller's retry on a fresh event reaches the send and claims the same id.
+        let retry = OrderClaim::new(dedup.clone(), "order-1");
+        assert!(check_slot_lag(Some(&gate), Some(995)).is_ok());
+        assert!(retry.claim().is_ok());
+        assert!(OrderClaim::new(dedup, "order-1").claim().is_err());
+    }
+
+    #[test]
+    fn stale_reserve_guard_compares_event_age() {
+        let guard = StaleReserveGuard::new(
+            std::time::Duration::from_millis(400),
+            StaleReserveAction::Abort,
+        );
+
+        assert!(!guard.is_stale(1_000_000, 1_400_000));
+        assert!(guard.is_stale(1_000_000, 1_400_001));
+    }
+
+    #[test]
+    fn withdraw_destination_respects_allowlist() {
+        let payer = Pubkey::new_unique();
+        let cold = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        assert!(validate_withdraw_destination(None, &payer, &other).is_ok());
+        assert!(validate_withdraw_destination(None, &payer, &payer).is_err());
+        assert!(validate_withdraw_destination(Some(&[cold]), &payer, &cold).is_ok());
+        assert!(validate_withdraw_destination(Some(&[cold]), &payer, &other).is_err());
+    }
+
+    #[test]
+    fn utility_fee_uses_default_rpc_priority_fee() {
+        let strategy = GasFeeStrategy::new();
+        assert!(default_utility_fee(&strategy).is_err());
+
+        strategy.set_default_rpc_fee_strategy(60_000, 50_000, 500_000, 400_000);
+        assert_eq!(default_utility_fee(&strategy).unwrap(), (60_000, 500_000));
+
+        let options = UtilityTxOptions::new(strategy);
+        assert!(!options.broadcast_via_swqos);
+        assert!(options.durable_nonce.is_none());
+    }
+
     #[test]
     fn normalize_swqos_configs_adds_default_rpc_route() {
         let configs = vec![SwqosConfig::Jito("uuid".to_string(), SwqosRegion::Frankfurt, None)];
@@ -1918,22 +4649,32 @@ mod tests {
             mint: Pubkey::new_unique(),
             amount: BuyAmount::WithMaxInput { quote_amount: 10_000 },
             slippage_basis_points: Some(100),
+            min_output_amount: None,
             recent_blockhash: Some(Hash::new_unique()),
             extension_params: dummy_pumpfun_params(),
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            memo: TradeMemo::ClientDefault,
             account_policy: AccountPolicy::HotPathMinimal,
             address_lookup_table_accounts: Vec::new(),
             wait_tx_confirmed: false,
+            confirmation: ConfirmationOptions::default(),
+            rpc_send_options: None,
             wait_for_all_submits: false,
             durable_nonce: None,
             simulate: false,
             grpc_recv_us: None,
+            event_slot: None,
+            client_order_id: None,
+            latency_budget: None,
+            skip_risk_checks: false,
+            fee_payer: None,
         };
 
         let low: TradeBuyParams = simple.into();
 
         assert!(matches!(low.input_token_type, TradeTokenType::SOL));
-        assert_eq!(low.input_token_amount, 10_000);
+        assert_eq!(low.input_token_amount, Some(10_000));
         assert_eq!(low.use_exact_sol_amount, Some(false));
         assert_eq!(low.fixed_output_token_amount, None);
         assert!(!low.create_input_token_ata);
@@ -1949,21 +4690,31 @@ mod tests {
             mint: Pubkey::new_unique(),
             amount: BuyAmount::ExactOutput { output_amount: 42, max_input_amount: 10_000 },
             slippage_basis_points: None,
+            min_output_amount: None,
             recent_blockhash: Some(Hash::new_unique()),
             extension_params: dummy_pumpfun_params(),
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            memo: TradeMemo::ClientDefault,
             account_policy: AccountPolicy::Auto,
             address_lookup_table_accounts: Vec::new(),
             wait_tx_confirmed: false,
+            confirmation: ConfirmationOptions::default(),
+            rpc_send_options: None,
             wait_for_all_submits: false,
             durable_nonce: None,
             simulate: false,
             grpc_recv_us: None,
+            event_slot: None,
+            client_order_id: None,
+            latency_budget: None,
+            skip_risk_checks: false,
+            fee_payer: None,
         };
 
         let low: TradeBuyParams = simple.into();
 
-        assert_eq!(low.input_token_amount, 10_000);
+        assert_eq!(low.input_token_amount, Some(10_000));
         assert_eq!(low.fixed_output_token_amount, Some(42));
         assert_eq!(low.use_exact_sol_amount, Some(true));
         assert!(low.create_mint_ata);
@@ -1982,11 +4733,15 @@ mod tests {
             GasFeeStrategy::new(),
         )
         .slippage_basis_points(250)
-        .account_policy(AccountPolicy::HotPathMinimal);
+        .account_policy(AccountPolicy::HotPathMinimal)
+        .client_order_id("copy-1")
+        .skip_risk_checks(true);
 
         let low: TradeBuyParams = simple.into();
 
         assert_eq!(low.slippage_basis_points, Some(250));
+        assert_eq!(low.client_order_id.as_deref(), Some("copy-1"));
+        assert!(low.skip_risk_checks);
         assert_eq!(low.use_exact_sol_amount, Some(true));
         assert!(!low.create_input_token_ata);
         assert!(!low.create_mint_ata);
@@ -2057,23 +4812,33 @@ mod tests {
             mint: Pubkey::new_unique(),
             amount: SellAmount::ExactInput(50_000),
             slippage_basis_points: None,
+            min_output_amount: None,
             recent_blockhash: Some(Hash::new_unique()),
             extension_params: dummy_pumpfun_params(),
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            memo: TradeMemo::ClientDefault,
             account_policy: AccountPolicy::Auto,
             address_lookup_table_accounts: Vec::new(),
             wait_tx_confirmed: false,
+            confirmation: ConfirmationOptions::default(),
+            rpc_send_options: None,
             wait_for_all_submits: false,
             durable_nonce: None,
             simulate: false,
             with_tip: true,
             grpc_recv_us: None,
+            event_slot: None,
+            client_order_id: None,
+            latency_budget: None,
+            reduce_only: false,
+            fee_payer: None,
         };
 
         let low: TradeSellParams = simple.into();
 
         assert!(matches!(low.output_token_type, TradeTokenType::USDC));
-        assert_eq!(low.input_token_amount, 50_000);
+        assert_eq!(low.input_token_amount, Some(50_000));
         assert!(low.create_output_token_ata);
         assert!(!low.close_output_token_ata);
         assert!(!low.close_mint_token_ata);
@@ -2105,4 +4870,34 @@ mod tests {
         assert_eq!(low.durable_nonce.as_ref().and_then(|n| n.nonce_account), Some(nonce_account));
         assert_eq!(low.durable_nonce.as_ref().and_then(|n| n.current_nonce), Some(nonce_hash));
     }
+
+    #[test]
+    fn whole_balance_amounts_map_to_none() {
+        let buy: TradeBuyParams = SimpleBuyParams::new(
+            DexType::PumpSwap,
+            TradeTokenType::WSOL,
+            Pubkey::new_unique(),
+            BuyAmount::AllQuote,
+            dummy_pumpfun_params(),
+            Hash::new_unique(),
+            GasFeeStrategy::new(),
+        )
+        .into();
+        assert_eq!(buy.input_token_amount, None);
+        assert_eq!(buy.fixed_output_token_amount, None);
+        assert_eq!(buy.use_exact_sol_amount, Some(true));
+
+        let sell: TradeSellParams = SimpleSellParams::new(
+            DexType::PumpFun,
+            TradeTokenType::SOL,
+            Pubkey::new_unique(),
+            SellAmount::All,
+            dummy_pumpfun_params(),
+            Hash::new_unique(),
+            GasFeeStrategy::new(),
+        )
+        .into();
+        assert_eq!(sell.input_token_amount, None);
+        assert_eq!(sell.fixed_output_token_amount, None);
+    }
 }
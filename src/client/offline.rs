@@ -0,0 +1,248 @@
+//! Offline signing with a durable nonce: build on an online machine, sign elsewhere (e.g. a
+//! cold wallet approving a large sell), then broadcast.
+//! 离线签名：使用 durable nonce 构建交易，导出待签消息，在冷钱包签名后导入签名并广播。
+//!
+//! 1. [`TradingClient::prepare_offline_transaction`] fetches the nonce and builds an
+//!    [`OfflineTransaction`]; the nonce keeps it valid until it is used, however long signing
+//!    takes.
+//! 2. Ship [`OfflineTransaction::to_base64`] (or just [`OfflineTransaction::message_base64`]) to
+//!    the signer, which returns an [`OfflineSignature`] from [`OfflineTransaction::sign`] or any
+//!    ed25519 signature over the message bytes.
+//! 3. [`OfflineTransaction::add_signature`] each one, then [`TradingClient::broadcast_offline`].
+//!
+//! The trade builders sign for the client's own payer, so instructions for a wallet whose key is
+//! not on this machine are supplied by the caller.
+
+use super::{BroadcastOptions, TradingClient};
+use crate::swqos::common::TradeError;
+use crate::swqos::SwqosType;
+use anyhow::anyhow;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, AddressLookupTableAccount, Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_system_interface::instruction::advance_nonce_account;
+use std::fmt;
+use std::str::FromStr;
+
+/// One signer's signature over an [`OfflineTransaction`] message, as `<signer>:<signature>`
+/// (both base58) in text form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfflineSignature {
+    pub signer: Pubkey,
+    pub signature: Signature,
+}
+
+impl fmt::Display for OfflineSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.signer, self.signature)
+    }
+}
+
+impl FromStr for OfflineSignature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (signer, signature) =
+            s.trim().split_once(':').ok_or_else(|| anyhow!("expected <signer>:<signature>"))?;
+        Ok(Self { signer: Pubkey::from_str(signer)?, signature: Signature::from_str(signature)? })
+    }
+}
+
+/// Durable-nonce transaction collecting signatures; missing ones are `Signature::default()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineTransaction {
+    transaction: VersionedTransaction,
+}
+
+impl OfflineTransaction {
+    /// Build `instructions` paid by `fee_payer`, prefixed with the advance of `nonce_account`
+    /// (authority `fee_payer`, as everywhere in the SDK) and using `nonce` as the blockhash.
+    /// With lookup tables the message is v0, otherwise legacy.
+    pub fn new(
+        fee_payer: &Pubkey,
+        instructions: &[Instruction],
+        nonce_account: &Pubkey,
+        nonce: Hash,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> Result<Self, anyhow::Error> {
+        let mut all = Vec::with_capacity(instructions.len() + 1);
+        all.push(advance_nonce_account(nonce_account, fee_payer));
+        all.extend_from_slice(instructions);
+        let message = if address_lookup_table_accounts.is_empty() {
+            VersionedMessage::Legacy(Message::new_with_blockhash(&all, Some(fee_payer), &nonce))
+        } else {
+            VersionedMessage::V0(v0::Message::try_compile(
+                fee_payer,
+                &all,
+                address_lookup_table_accounts,
+                nonce,
+            )?)
+        };
+        let required = message.header().num_required_signatures as usize;
+        Ok(Self {
+            transaction: VersionedTransaction {
+                signatures: vec![Signature::default(); required],
+                message,
+            },
+        })
+    }
+
+    /// Bytes every signer signs.
+    pub fn message_bytes(&self) -> Vec<u8> {
+        self.transaction.message.serialize()
+    }
+
+    /// [`Self::message_bytes`] as base64, for signers that only need the message.
+    pub fn message_base64(&self) -> String {
+        STANDARD.encode(self.message_bytes())
+    }
+
+    /// Signers the transaction requires, fee payer first.
+    pub fn required_signers(&self) -> &[Pubkey] {
+        let required = self.transaction.message.header().num_required_signatures as usize;
+        &self.transaction.message.static_account_keys()[..required]
+    }
+
+    /// Required signers without a signature yet.
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.required_signers()
+            .iter()
+            .zip(&self.transaction.signatures)
+            .filter(|(_, signature)| **signature == Signature::default())
+            .map(|(signer, _)| *signer)
+            .collect()
+    }
+
+    pub fn is_fully_signed(&self) -> bool {
+        self.missing_signers().is_empty()
+    }
+
+    /// Sign with `keypair` (must be a required signer), store the signature and return it for
+    /// shipping back to the online machine.
+    pub fn sign(&mut self, keypair: &Keypair) -> Result<OfflineSignature, anyhow::Error> {
+        let signature = keypair
+            .try_sign_message(&self.message_bytes())
+            .map_err(|e| anyhow!("sign failed: {e}"))?;
+        let signed = OfflineSignature { signer: keypair.pubkey(), signature };
+        self.add_signature(signed)?;
+        Ok(signed)
+    }
+
+    /// Store a signature produced elsewhere after checking it against the message.
+    pub fn add_signature(&mut self, signed: OfflineSignature) -> Result<(), anyhow::Error> {
+        let index = self
+            .required_signers()
+            .iter()
+            .position(|key| *key == signed.signer)
+            .ok_or_else(|| anyhow!("{} is not a required signer", signed.signer))?;
+        if !signed.signature.verify(signed.signer.as_ref(), &self.message_bytes()) {
+            return Err(anyhow!("signature of {} does not match the message", signed.signer));
+        }
+        self.transaction.signatures[index] = signed.signature;
+        Ok(())
+    }
+
+    /// The whole transaction, signatures so far included, as base64 (bincode wire format).
+    pub fn to_base64(&self) -> Result<String, anyhow::Error> {
+        Ok(STANDARD.encode(bincode::serialize(&self.transaction)?))
+    }
+
+    /// Read a transaction written by [`Self::to_base64`].
+    pub fn from_base64(encoded: &str) -> Result<Self, anyhow::Error> {
+        let transaction: VersionedTransaction =
+            bincode::deserialize(&STANDARD.decode(encoded.trim())?)?;
+        let required = transaction.message.header().num_required_signatures as usize;
+        if transaction.signatures.len() != required {
+            return Err(anyhow!(
+                "transaction has {} signatures, message requires {}",
+                transaction.signatures.len(),
+                required
+            ));
+        }
+        Ok(Self { transaction })
+    }
+
+    /// The signed transaction; fails while any required signature is missing.
+    pub fn into_transaction(self) -> Result<VersionedTransaction, anyhow::Error> {
+        if let Some(signer) = self.missing_signers().first() {
+            return Err(anyhow!("missing signature for required signer {}", signer));
+        }
+        Ok(self.transaction)
+    }
+}
+
+impl TradingClient {
+    /// Fetch the current value of `nonce_account` and build an [`OfflineTransaction`] of
+    /// `instructions` paid by `fee_payer`, which is also the nonce authority. Add compute budget
+    /// and relay tip instructions yourself; nothing is signed here.
+    pub async fn prepare_offline_transaction(
+        &self,
+        fee_payer: Pubkey,
+        instructions: &[Instruction],
+        nonce_account: Pubkey,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> Result<OfflineTransaction, anyhow::Error> {
+        let nonce =
+            crate::common::nonce_cache::fetch_nonce_info(&self.infrastructure.rpc, nonce_account)
+                .await
+                .and_then(|info| info.current_nonce)
+                .ok_or_else(|| anyhow!("Failed to fetch durable nonce {}", nonce_account))?;
+        OfflineTransaction::new(
+            &fee_payer,
+            instructions,
+            &nonce_account,
+            nonce,
+            address_lookup_table_accounts,
+        )
+    }
+
+    /// [`Self::broadcast`] a fully signed [`OfflineTransaction`].
+    pub async fn broadcast_offline(
+        &self,
+        transaction: OfflineTransaction,
+        options: BroadcastOptions,
+    ) -> Result<(bool, Vec<Signature>, Option<TradeError>, Vec<(SwqosType, i64)>), anyhow::Error>
+    {
+        self.broadcast(transaction.into_transaction()?, options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_system_interface::instruction::transfer;
+
+    #[test]
+    fn signs_offline_and_round_trips_artifacts() {
+        let cold = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let ix = transfer(&cold.pubkey(), &Pubkey::new_unique(), 1_000);
+        let online =
+            OfflineTransaction::new(&cold.pubkey(), &[ix], &nonce_account, Hash::new_unique(), &[])
+                .unwrap();
+        assert_eq!(online.missing_signers(), vec![cold.pubkey()]);
+
+        let mut offline = OfflineTransaction::from_base64(&online.to_base64().unwrap()).unwrap();
+        let signed = offline.sign(&cold).unwrap().to_string();
+
+        let mut online = online;
+        let other = Keypair::new();
+        let forged = OfflineSignature {
+            signer: cold.pubkey(),
+            signature: other.sign_message(&online.message_bytes()),
+        };
+        assert!(online.add_signature(forged).is_err());
+        assert!(online.clone().into_transaction().is_err());
+
+        online.add_signature(signed.parse().unwrap()).unwrap();
+        assert_eq!(online, offline);
+        let tx = online.into_transaction().unwrap();
+        assert!(tx.signatures[0].verify(cold.pubkey().as_ref(), &tx.message.serialize()));
+    }
+}
@@ -0,0 +1,556 @@
+//! Dry-run description of a trade: accounts, rent, expected output and submit lanes.
+//! 交易预览：在不发送、不调用 RPC 的情况下描述一笔交易将做什么，便于排查 create/close ATA 等参数组合。
+
+use super::{TradeBuyParams, TradeSellParams, TradeTokenType, TradingClient};
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
+use crate::common::gas_fee_strategy::GasFeeStrategyType;
+use crate::common::seed::token_account_rent;
+use crate::common::GasFeeStrategy;
+use crate::constants::trade::trade::DEFAULT_SLIPPAGE;
+use crate::constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT};
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::core::async_executor::select_swqos_task_configs;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountActionKind {
+    /// Token account created in the trade transaction (idempotent; rent is only paid when missing).
+    Create,
+    /// Token account closed after the trade; its rent goes back to the payer.
+    Close,
+}
+
+/// One token-account instruction the trade transaction will carry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountAction {
+    pub kind: AccountActionKind,
+    /// Mint of the account (WSOL for wrapped SOL).
+    pub mint: Pubkey,
+    /// Payer token account (seed or standard ATA, following `use_seed_optimize`).
+    pub account: Pubkey,
+    /// Rent paid on create or reclaimed on close, from the SDK rent cache.
+    pub lamports: u64,
+}
+
+/// One submission the trade will fan out to: a SWQOS provider with one gas fee strategy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeLane {
+    pub swqos_type: SwqosType,
+    pub strategy_type: GasFeeStrategyType,
+    pub cu_limit: u32,
+    /// Micro-lamports per compute unit.
+    pub cu_price: u64,
+    pub tip_sol: f64,
+}
+
+impl FeeLane {
+    /// Priority fee in lamports if the whole `cu_limit` is charged.
+    #[inline]
+    pub fn priority_fee_lamports(&self) -> u64 {
+        (self.cu_limit as u128 * self.cu_price as u128 / 1_000_000) as u64
+    }
+}
+
+/// What [`TradingClient::describe_buy`] / [`TradingClient::describe_sell`] expect a trade to do.
+///
+/// Built from the params and client config only; balances, pool state freshness and on-chain
+/// account existence are not checked. `Display` prints a multi-line human-readable summary.
+#[derive(Debug, Clone)]
+pub struct TradePlan {
+    pub dex_type: DexType,
+    pub trade_type: TradeType,
+    pub mint: Pubkey,
+    /// Quote mint paid (buy) or received (sell); `SOL_TOKEN_ACCOUNT` for native SOL.
+    pub quote_mint: Pubkey,
+    /// Quote amount (buy) or token amount (sell); `None` means the whole balance, read at send time.
+    pub input_amount: Option<u64>,
+    /// Native SOL wrapped into WSOL before the swap.
+    pub wrapped_lamports: Option<u64>,
+    /// Fixed output requested via `fixed_output_token_amount` (exact-out instruction).
+    pub exact_output: Option<u64>,
+    /// Curve estimate before slippage. Only computed for PumpFun; `None` on other DEXes or when
+    /// the input amount is resolved at send time.
+    pub expected_output: Option<u64>,
+    pub slippage_basis_points: u64,
+    pub accounts: Vec<AccountAction>,
+    /// Every transaction sent in parallel. Tips below the provider minimum are already dropped
+    /// when the client has `check_min_tip` enabled.
+    pub lanes: Vec<FeeLane>,
+    pub data_size_limit: Option<u32>,
+    /// Heap frame requested with `RequestHeapFrame`, as configured (before rounding).
+    pub heap_frame_bytes: Option<u32>,
+    pub uses_durable_nonce: bool,
+    pub simulate: bool,
+    pub wait_tx_confirmed: bool,
+    /// Suspicious flag combinations spotted while building the plan.
+    pub warnings: Vec<String>,
+}
+
+impl TradePlan {
+    /// Rent paid for accounts created by the trade (upper bound: existing accounts cost nothing).
+    pub fn rent_paid(&self) -> u64 {
+        self.accounts
+            .iter()
+            .filter(|a| a.kind == AccountActionKind::Create)
+            .map(|a| a.lamports)
+            .sum()
+    }
+
+    /// Rent returned by accounts closed after the trade.
+    pub fn rent_reclaimed(&self) -> u64 {
+        self.accounts
+            .iter()
+            .filter(|a| a.kind == AccountActionKind::Close)
+            .map(|a| a.lamports)
+            .sum()
+    }
+
+    /// Highest tip among the lanes; at most one lane lands, so this bounds the tip cost.
+    pub fn max_tip_sol(&self) -> f64 {
+        self.lanes.iter().map(|l| l.tip_sol).fold(0.0, f64::max)
+    }
+}
+
+impl fmt::Display for TradePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:?} {:?} {}", self.dex_type, self.trade_type, self.mint)?;
+        match self.input_amount {
+            Some(amount) => writeln!(f, "  input: {}", amount)?,
+            None => writeln!(f, "  input: whole balance (read at send time)")?,
+        }
+        writeln!(f, "  quote mint: {}", self.quote_mint)?;
+        if let Some(lamports) = self.wrapped_lamports {
+            writeln!(f, "  wrap: {} lamports into WSOL", lamports)?;
+        }
+        if let Some(exact) = self.exact_output {
+            writeln!(f, "  exact output: {}", exact)?;
+        }
+        match self.expected_output {
+            Some(out) => writeln!(f, "  expected output: ~{} (before slippage)", out)?,
+            None => writeln!(f, "  expected output: unknown")?,
+        }
+        writeln!(f, "  slippage: {} bps", self.slippage_basis_points)?;
+        writeln!(f, "  accounts:")?;
+        if self.accounts.is_empty() {
+            writeln!(f, "    (none)")?;
+        }
+        for a in &self.accounts {
+            let (verb, rent) = match a.kind {
+                AccountActionKind::Create => ("create", "rent paid"),
+                AccountActionKind::Close => ("close", "rent reclaimed"),
+            };
+            writeln!(
+                f,
+                "    {} {} (mint {}, {} {} lamports)",
+                verb, a.account, a.mint, rent, a.lamports
+            )?;
+        }
+        writeln!(f, "  lanes ({}):", self.lanes.len())?;
+        for l in &self.lanes {
+            writeln!(
+                f,
+                "    {:?} {} cu_limit={} cu_price={} priority_fee={} lamports tip={} SOL",
+                l.swqos_type,
+                l.strategy_type.as_str(),
+                l.cu_limit,
+                l.cu_price,
+                l.priority_fee_lamports(),
+                l.tip_sol
+            )?;
+        }
+        if let Some(limit) = self.data_size_limit {
+            writeln!(f, "  loaded accounts data size limit: {} bytes", limit)?;
+        }
+        if let Some(bytes) = self.heap_frame_bytes {
+            writeln!(f, "  heap frame: {} bytes", bytes)?;
+        }
+        writeln!(
+            f,
+            "  blockhash: {}, simulate: {}, wait confirmed: {}",
+            if self.uses_durable_nonce { "durable nonce" } else { "recent" },
+            self.simulate,
+            self.wait_tx_confirmed
+        )?;
+        for w in &self.warnings {
+            writeln!(f, "  warning: {}", w)?;
+        }
+        Ok(())
+    }
+}
+
+/// Token program of `mint` as recorded in the protocol params (legacy Token when unknown).
+pub(super) fn mint_token_program(params: &DexParamEnum, mint: &Pubkey) -> Pubkey {
+    match params {
+        DexParamEnum::PumpFun(p) => p.token_program,
+        DexParamEnum::PumpSwap(p) if p.quote_mint == *mint => p.quote_token_program,
+        DexParamEnum::PumpSwap(p) => p.base_token_program,
+        DexParamEnum::Bonk(p) => p.mint_token_program,
+        DexParamEnum::RaydiumCpmm(p) if p.quote_mint == *mint => p.quote_token_program,
+        DexParamEnum::RaydiumCpmm(p) => p.base_token_program,
+        DexParamEnum::RaydiumAmmV4(_) => TOKEN_PROGRAM,
+        DexParamEnum::MeteoraDammV2(p) if p.token_b_mint == *mint => p.token_b_program,
+        DexParamEnum::MeteoraDammV2(p) => p.token_a_program,
+    }
+}
+
+/// Token program of the payer quote account: legacy Token for the built-in quotes.
+pub(super) fn quote_token_program(params: &DexParamEnum, token_type: &TradeTokenType) -> Pubkey {
+    match token_type {
+        TradeTokenType::Spl(mint) => mint_token_program(params, mint),
+        _ => TOKEN_PROGRAM,
+    }
+}
+
+/// Mint of the payer account holding the quote, or `None` when PumpFun settles in native SOL.
+#[inline]
+pub(super) fn quote_account_mint(dex_type: DexType, token_type: &TradeTokenType) -> Option<Pubkey> {
+    match token_type {
+        TradeTokenType::SOL if dex_type == DexType::PumpFun => None,
+        TradeTokenType::SOL => Some(WSOL_TOKEN_ACCOUNT),
+        other => Some(other.quote_mint()),
+    }
+}
+
+impl TradingClient {
+    /// Describe what [`TradingClient::buy`] would do with `params`, without sending anything.
+    ///
+    /// Useful to check create/close ATA combinations, tip and CU settings per provider, and the
+    /// expected fill before going live. No RPC call is made.
+    pub fn describe_buy(&self, params: &TradeBuyParams) -> TradePlan {
+        let mut warnings = Vec::new();
+        let quote_mint = params.input_token_type.quote_mint();
+        let quote_account = quote_account_mint(params.dex_type, &params.input_token_type);
+        let quote_program = quote_token_program(&params.extension_params, &params.input_token_type);
+        let mint_program = mint_token_program(&params.extension_params, &params.mint);
+        let spends_balance = params.input_token_amount.is_none();
+
+        let mut accounts = Vec::new();
+        let mut wrapped_lamports = None;
+        if let Some(account_mint) = quote_account {
+            // Whole-balance buys spend the existing quote account as-is.
+            if params.create_input_token_ata && !spends_balance {
+                accounts.push(self.account_action(
+                    AccountActionKind::Create,
+                    account_mint,
+                    &quote_program,
+                ));
+                if account_mint == WSOL_TOKEN_ACCOUNT {
+                    wrapped_lamports = params.input_token_amount;
+                }
+            } else if params.input_token_type == TradeTokenType::SOL {
+                warnings.push(
+                    "SOL input without create_input_token_ata: the payer WSOL account must \
+                     already hold the amount"
+                        .to_string(),
+                );
+            }
+        }
+        if params.create_mint_ata {
+            accounts.push(self.account_action(
+                AccountActionKind::Create,
+                params.mint,
+                &mint_program,
+            ));
+        } else {
+            warnings.push(
+                "create_mint_ata is false: the buy fails unless the token account already exists"
+                    .to_string(),
+            );
+        }
+        if params.close_input_token_ata {
+            self.push_quote_close(
+                quote_account,
+                "close_input_token_ata",
+                &mut accounts,
+                &mut warnings,
+            );
+        }
+
+        let expected_output = match (&params.extension_params, params.input_token_amount) {
+            (DexParamEnum::PumpFun(p), Some(amount))
+                if params.fixed_output_token_amount.is_none() =>
+            {
+                Some(crate::utils::calc::pumpfun::get_buy_token_amount_from_sol_amount(
+                    p.bonding_curve.virtual_token_reserves as u128,
+                    p.bonding_curve.virtual_sol_reserves as u128,
+                    p.bonding_curve.real_token_reserves as u128,
+                    p.effective_creator_for_trade(),
+                    amount,
+                ))
+            }
+            _ => None,
+        };
+
+        let gas_fee_strategy =
+            self.effective_gas_strategy(params.dex_type, TradeType::Buy, &params.gas_fee_strategy);
+        let mut plan = TradePlan {
+            dex_type: params.dex_type,
+            trade_type: TradeType::Buy,
+            mint: params.mint,
+            quote_mint,
+            input_amount: params.input_token_amount,
+            wrapped_lamports,
+            exact_output: params.fixed_output_token_amount,
+            expected_output,
+            slippage_basis_points: params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+            accounts,
+            lanes: self.plan_lanes(&gas_fee_strategy, TradeType::Buy, true),
+            data_size_limit: params
+                .data_size_limit
+                .or_else(|| gas_fee_strategy.get_data_size_limit(params.dex_type)),
+            heap_frame_bytes: gas_fee_strategy.get_heap_frame(params.dex_type),
+            uses_durable_nonce: params.durable_nonce.is_some(),
+            simulate: params.simulate,
+            wait_tx_confirmed: params.wait_tx_confirmed,
+            warnings,
+        };
+        plan.add_common_warnings(params.slippage_basis_points, params.recent_blockhash.is_some());
+        plan
+    }
+
+    /// Describe what [`TradingClient::sell`] would do with `params`, without sending anything.
+    /// No RPC call is made.
+    pub fn describe_sell(&self, params: &TradeSellParams) -> TradePlan {
+        let mut warnings = Vec::new();
+        let quote_mint = params.output_token_type.quote_mint();
+        let quote_account = quote_account_mint(params.dex_type, &params.output_token_type);
+        let quote_program =
+            quote_token_program(&params.extension_params, &params.output_token_type);
+        let mint_program = mint_token_program(&params.extension_params, &params.mint);
+
+        let mut accounts = Vec::new();
+        if let Some(account_mint) = quote_account {
+            if params.create_output_token_ata {
+                accounts.push(self.account_action(
+                    AccountActionKind::Create,
+                    account_mint,
+                    &quote_program,
+                ));
+            }
+        }
+        let pumpfun_closes = matches!(
+            &params.extension_params,
+            DexParamEnum::PumpFun(p) if p.close_token_account_when_sell.unwrap_or(false)
+        );
+        if params.close_mint_token_ata || pumpfun_closes {
+            accounts.push(self.account_action(
+                AccountActionKind::Close,
+                params.mint,
+                &mint_program,
+            ));
+            if params.input_token_amount.is_some() {
+                warnings.push(
+                    "closing the mint account after a partial amount fails unless the amount \
+                     is the whole balance; pass None to sell everything"
+                        .to_string(),
+                );
+            }
+        }
+        if params.close_output_token_ata {
+            self.push_quote_close(
+                quote_account,
+                "close_output_token_ata",
+                &mut accounts,
+                &mut warnings,
+            );
+        } else if params.output_token_type == TradeTokenType::SOL && quote_account.is_some() {
+            warnings.push(
+                "SOL output without close_output_token_ata: proceeds stay wrapped as WSOL"
+                    .to_string(),
+            );
+        }
+
+        let expected_output = match (&params.extension_params, params.input_token_amount) {
+            (DexParamEnum::PumpFun(p), Some(amount)) => {
+                Some(crate::utils::calc::pumpfun::get_sell_sol_amount_from_token_amount(
+                    p.bonding_curve.virtual_token_reserves as u128,
+                    p.bonding_curve.virtual_sol_reserves as u128,
+                    p.effective_creator_for_trade(),
+                    amount,
+                ))
+            }
+            _ => None,
+        };
+
+        let gas_fee_strategy =
+            self.effective_gas_strategy(params.dex_type, TradeType::Sell, &params.gas_fee_strategy);
+        let mut plan = TradePlan {
+            dex_type: params.dex_type,
+            trade_type: TradeType::Sell,
+            mint: params.mint,
+            quote_mint,
+            input_amount: params.input_token_amount,
+            wrapped_lamports: None,
+            exact_output: params.fixed_output_token_amount,
+            expected_output,
+            slippage_basis_points: params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+            accounts,
+            lanes: self.plan_lanes(&gas_fee_strategy, TradeType::Sell, params.with_tip),
+            data_size_limit: params
+                .data_size_limit
+                .or_else(|| gas_fee_strategy.get_data_size_limit(params.dex_type)),
+            heap_frame_bytes: gas_fee_strategy.get_heap_frame(params.dex_type),
+            uses_durable_nonce: params.durable_nonce.is_some(),
+            simulate: params.simulate,
+            wait_tx_confirmed: params.wait_tx_confirmed,
+            warnings,
+        };
+        plan.add_common_warnings(params.slippage_basis_points, params.recent_blockhash.is_some());
+        plan
+    }
+
+    /// Only WSOL is ever closed by the swap builders; other quote accounts keep their balance.
+    fn push_quote_close(
+        &self,
+        quote_account: Option<Pubkey>,
+        flag: &str,
+        accounts: &mut Vec<AccountAction>,
+        warnings: &mut Vec<String>,
+    ) {
+        match quote_account {
+            Some(WSOL_TOKEN_ACCOUNT) => accounts.push(self.account_action(
+                AccountActionKind::Close,
+                WSOL_TOKEN_ACCOUNT,
+                &TOKEN_PROGRAM,
+            )),
+            Some(mint) => warnings.push(format!(
+                "{} is ignored: only WSOL accounts are closed, {} is kept",
+                flag, mint
+            )),
+            None => {}
+        }
+    }
+
+    /// Payer token account the swap builders use for `mint`.
+    pub(super) fn payer_token_account(&self, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id_fast_use_seed(
+            &self.payer.pubkey(),
+            mint,
+            token_program,
+            // WSOL is always wrapped into the standard ATA.
+            self.use_seed_optimize && *mint != WSOL_TOKEN_ACCOUNT,
+        )
+    }
+
+    fn account_action(
+        &self,
+        kind: AccountActionKind,
+        mint: Pubkey,
+        token_program: &Pubkey,
+    ) -> AccountAction {
+        AccountAction {
+            kind,
+            mint,
+            account: self.payer_token_account(&mint, token_program),
+            lamports: token_account_rent(token_program),
+        }
+    }
+
+    fn plan_lanes(
+        &self,
+        gas_fee_strategy: &GasFeeStrategy,
+        trade_type: TradeType,
+        with_tip: bool,
+    ) -> Vec<FeeLane> {
+        let clients = self.swqos_clients.load_full();
+        let swqos_types: Vec<SwqosType> = clients.iter().map(|c| c.get_swqos_type()).collect();
+        let configs = gas_fee_strategy.get_strategies(trade_type);
+        select_swqos_task_configs(&swqos_types, &configs, with_tip, self.check_min_tip, |t| {
+            clients.iter().find(|c| c.get_swqos_type() == t).map(|c| c.min_tip_sol()).unwrap_or(0.0)
+        })
+        .into_iter()
+        .map(|task| {
+            let (swqos_type, strategy_type, value) = task.gas_fee_config;
+            FeeLane {
+                swqos_type,
+                strategy_type,
+                cu_limit: value.cu_limit,
+                cu_price: value.cu_price,
+                tip_sol: if with_tip { value.tip } else { 0.0 },
+            }
+        })
+        .collect()
+    }
+}
+
+impl TradePlan {
+    fn add_common_warnings(&mut self, slippage: Option<u64>, has_blockhash: bool) {
+        if self.lanes.is_empty() {
+            self.warnings.push(
+                "no submit lane: no gas fee strategy matches a configured SWQOS client".to_string(),
+            );
+        }
+        if !has_blockhash && !self.uses_durable_nonce {
+            self.warnings.push("neither recent_blockhash nor durable_nonce is set".to_string());
+        }
+        if slippage.is_none() {
+            self.warnings.push(format!(
+                "slippage_basis_points not set: default {} bps is used",
+                DEFAULT_SLIPPAGE
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(kind: AccountActionKind, lamports: u64) -> AccountAction {
+        AccountAction { kind, mint: Pubkey::new_unique(), account: Pubkey::new_unique(), lamports }
+    }
+
+    #[test]
+    fn rent_totals_split_by_action() {
+        let plan = TradePlan {
+            dex_type: DexType::PumpSwap,
+            trade_type: TradeType::Buy,
+            mint: Pubkey::new_unique(),
+            quote_mint: WSOL_TOKEN_ACCOUNT,
+            input_amount: Some(1_000),
+            wrapped_lamports: Some(1_000),
+            exact_output: None,
+            expected_output: None,
+            slippage_basis_points: DEFAULT_SLIPPAGE,
+            accounts: vec![
+                action(AccountActionKind::Create, 2_039_280),
+                action(AccountActionKind::Create, 2_074_080),
+                action(AccountActionKind::Close, 2_039_280),
+            ],
+            lanes: vec![FeeLane {
+                swqos_type: SwqosType::Jito,
+                strategy_type: GasFeeStrategyType::Normal,
+                cu_limit: 200_000,
+                cu_price: 1_500_000,
+                tip_sol: 0.001,
+            }],
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            uses_durable_nonce: false,
+            simulate: false,
+            wait_tx_confirmed: true,
+            warnings: Vec::new(),
+        };
+        assert_eq!(plan.rent_paid(), 4_113_360);
+        assert_eq!(plan.rent_reclaimed(), 2_039_280);
+        assert_eq!(plan.lanes[0].priority_fee_lamports(), 300_000);
+        assert!(plan.to_string().contains("Jito Normal"));
+    }
+
+    #[test]
+    fn pumpfun_sol_quote_has_no_wsol_account() {
+        assert_eq!(quote_account_mint(DexType::PumpFun, &TradeTokenType::SOL), None);
+        assert_eq!(
+            quote_account_mint(DexType::PumpSwap, &TradeTokenType::SOL),
+            Some(WSOL_TOKEN_ACCOUNT)
+        );
+        assert_eq!(
+            quote_account_mint(DexType::PumpFun, &TradeTokenType::USDC),
+            Some(crate::constants::USDC_TOKEN_ACCOUNT)
+        );
+    }
+}
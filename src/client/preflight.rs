@@ -0,0 +1,300 @@
+//! Preflight validation of ATA flags against the payer's accounts and balances.
+//! 发送前校验：检查 create/close ATA 参数与账户、余额是否匹配，以及账户是否被冻结或属于错误的代币程序。
+
+use super::plan::{mint_token_program, quote_account_mint, quote_token_program};
+use super::{TradeBuyParams, TradeSellParams, TradingClient};
+use crate::constants::WSOL_TOKEN_ACCOUNT;
+use crate::trading::core::params::DexParamEnum;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+/// Why [`TradingClient::preflight_buy`] / [`TradingClient::preflight_sell`] rejected a trade.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreflightError {
+    /// The trade uses a token account that does not exist and that the flags do not create.
+    MissingAccount {
+        mint: Pubkey,
+        account: Pubkey,
+    },
+    /// The token account holds less than the trade spends or sells.
+    InsufficientBalance {
+        mint: Pubkey,
+        account: Pubkey,
+        balance: u64,
+        required: u64,
+    },
+    /// The payer holds less native SOL than the trade pays or wraps.
+    InsufficientSol {
+        balance: u64,
+        required: u64,
+    },
+    /// The mint account would be closed while still holding tokens after the sell.
+    CloseWithRemainingBalance {
+        account: Pubkey,
+        remaining: u64,
+    },
+    /// The token account is frozen by the mint's freeze authority; every transfer from or into
+    /// it fails.
+    AccountFrozen {
+        mint: Pubkey,
+        account: Pubkey,
+    },
+    /// The token account is owned by a different program than the one the trade uses (e.g. a
+    /// Token-2022 mint traded as SPL Token).
+    WrongTokenProgram {
+        mint: Pubkey,
+        account: Pubkey,
+        expected: Pubkey,
+        actual: Pubkey,
+    },
+    /// The WSOL account would be closed while another trade in this process still uses it,
+    /// or this trade uses it while another one is closing it.
+    WsolAccountInUse {
+        in_flight: usize,
+    },
+    Rpc(String),
+}
+
+impl std::fmt::Display for PreflightError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreflightError::MissingAccount { mint, account } => write!(
+                f,
+                "Preflight: token account {} for mint {} does not exist and is not created",
+                account, mint
+            ),
+            PreflightError::InsufficientBalance { mint, account, balance, required } => write!(
+                f,
+                "Preflight: {} holds {} of mint {}, trade needs {}",
+                account, balance, mint, required
+            ),
+            PreflightError::InsufficientSol { balance, required } => {
+                write!(f, "Preflight: payer holds {} lamports, trade needs {}", balance, required)
+            }
+            PreflightError::CloseWithRemainingBalance { account, remaining } => write!(
+                f,
+                "Preflight: closing {} would fail, {} tokens remain after the sell",
+                account, remaining
+            ),
+            PreflightError::AccountFrozen { mint, account } => {
+                write!(f, "AccountFrozen: token account {} for mint {} is frozen", account, mint)
+            }
+            PreflightError::WrongTokenProgram { mint, account, expected, actual } => write!(
+                f,
+                "WrongTokenProgram: token account {} for mint {} is owned by {}, trade uses {}",
+                account, mint, actual, expected
+            ),
+            PreflightError::WsolAccountInUse { in_flight } => write!(
+                f,
+                "Preflight: WSOL account is in use by {} other trade(s) with a conflicting close",
+                in_flight
+            ),
+            PreflightError::Rpc(e) => write!(f, "Preflight: rpc error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PreflightError {}
+
+/// Token amount of an SPL token account (Token or Token-2022).
+#[inline]
+fn token_amount(account: &Account) -> u64 {
+    account.data.get(64..72).map_or(0, |b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// `AccountState::Frozen` in the token account `state` byte.
+const TOKEN_ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// Balance of `account`, or [`PreflightError::MissingAccount`] when it does not exist.
+fn require_account(
+    account: Option<&Account>,
+    mint: Pubkey,
+    address: Pubkey,
+) -> Result<u64, PreflightError> {
+    account.map(token_amount).ok_or(PreflightError::MissingAccount { mint, account: address })
+}
+
+/// Reject an existing token account owned by another program than `program` or frozen. Missing
+/// accounts pass; [`require_account`] covers those.
+fn check_token_account(
+    account: Option<&Account>,
+    mint: Pubkey,
+    address: Pubkey,
+    program: Pubkey,
+) -> Result<(), PreflightError> {
+    let Some(account) = account else {
+        return Ok(());
+    };
+    if account.owner != program {
+        return Err(PreflightError::WrongTokenProgram {
+            mint,
+            account: address,
+            expected: program,
+            actual: account.owner,
+        });
+    }
+    if account.data.get(108) == Some(&TOKEN_ACCOUNT_STATE_FROZEN) {
+        return Err(PreflightError::AccountFrozen { mint, account: address });
+    }
+    Ok(())
+}
+
+fn require_balance(
+    balance: u64,
+    required: u64,
+    mint: Pubkey,
+    account: Pubkey,
+) -> Result<(), PreflightError> {
+    if balance < required {
+        return Err(PreflightError::InsufficientBalance { mint, account, balance, required });
+    }
+    Ok(())
+}
+
+impl TradingClient {
+    /// Check `params` against the payer's accounts before sending a buy.
+    ///
+    /// Catches frozen or wrong-program token accounts, paying from a quote account that does not
+    /// exist or holds too little (e.g. WSOL
+    /// with `create_input_token_ata = false`), too little SOL to pay or wrap, and buying into a
+    /// missing token account with `create_mint_ata = false`. Costs one `getMultipleAccounts`.
+    /// Fees, tips and rent are not included in the SOL requirement.
+    pub async fn preflight_buy(&self, params: &TradeBuyParams) -> Result<(), PreflightError> {
+        let quote_account_mint = quote_account_mint(params.dex_type, &params.input_token_type);
+        let quote_program = quote_token_program(&params.extension_params, &params.input_token_type);
+        let mint_program = mint_token_program(&params.extension_params, &params.mint);
+        let quote_account =
+            quote_account_mint.map(|mint| self.payer_token_account(&mint, &quote_program));
+        let mint_account = self.payer_token_account(&params.mint, &mint_program);
+
+        let mut addresses = vec![self.payer.pubkey(), mint_account];
+        addresses.extend(quote_account);
+        let accounts = self
+            .infrastructure
+            .rpc
+            .get_multiple_accounts(&addresses)
+            .await
+            .map_err(|e| PreflightError::Rpc(e.to_string()))?;
+        let sol_balance = accounts[0].as_ref().map_or(0, |a| a.lamports);
+        check_token_account(accounts[1].as_ref(), params.mint, mint_account, mint_program)?;
+        if let (Some(mint), Some(address)) = (quote_account_mint, quote_account) {
+            check_token_account(accounts[2].as_ref(), mint, address, quote_program)?;
+        }
+
+        if !params.create_mint_ata {
+            require_account(accounts[1].as_ref(), params.mint, mint_account)?;
+        }
+
+        let amount = params.input_token_amount;
+        // Native SOL is paid directly (PumpFun) or wrapped into WSOL by `create_input_token_ata`.
+        let wraps = params.create_input_token_ata
+            && quote_account_mint == Some(WSOL_TOKEN_ACCOUNT)
+            && amount.is_some();
+        match (quote_account_mint, quote_account) {
+            (Some(mint), Some(address)) if !wraps => {
+                let balance = require_account(accounts[2].as_ref(), mint, address)?;
+                if let Some(required) = amount {
+                    require_balance(balance, required, mint, address)?;
+                }
+            }
+            _ => {
+                let required = amount.unwrap_or(0);
+                if sol_balance < required {
+                    return Err(PreflightError::InsufficientSol { balance: sol_balance, required });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `params` against the payer's accounts before sending a sell.
+    ///
+    /// Catches a frozen token account (Token-2022 mints with a freeze authority), one owned by
+    /// another token program than the trade uses, selling more than the token account holds, closing the token account while part
+    /// of the balance stays behind, and receiving into a missing quote account with
+    /// `create_output_token_ata = false`. Costs one `getMultipleAccounts`.
+    pub async fn preflight_sell(&self, params: &TradeSellParams) -> Result<(), PreflightError> {
+        let quote_account_mint = quote_account_mint(params.dex_type, &params.output_token_type);
+        let quote_program =
+            quote_token_program(&params.extension_params, &params.output_token_type);
+        let mint_program = mint_token_program(&params.extension_params, &params.mint);
+        let quote_account =
+            quote_account_mint.map(|mint| self.payer_token_account(&mint, &quote_program));
+        let mint_account = self.payer_token_account(&params.mint, &mint_program);
+
+        let mut addresses = vec![mint_account];
+        addresses.extend(quote_account);
+        let accounts = self
+            .infrastructure
+            .rpc
+            .get_multiple_accounts(&addresses)
+            .await
+            .map_err(|e| PreflightError::Rpc(e.to_string()))?;
+
+        let balance = require_account(accounts[0].as_ref(), params.mint, mint_account)?;
+        check_token_account(accounts[0].as_ref(), params.mint, mint_account, mint_program)?;
+        if let (Some(mint), Some(address)) = (quote_account_mint, quote_account) {
+            check_token_account(accounts[1].as_ref(), mint, address, quote_program)?;
+        }
+        if let Some(required) = params.input_token_amount {
+            require_balance(balance, required, params.mint, mint_account)?;
+            let closes = params.close_mint_token_ata
+                || matches!(
+                    &params.extension_params,
+                    DexParamEnum::PumpFun(p) if p.close_token_account_when_sell.unwrap_or(false)
+                );
+            if closes && balance > required {
+                return Err(PreflightError::CloseWithRemainingBalance {
+                    account: mint_account,
+                    remaining: balance - required,
+                });
+            }
+        }
+        if let (Some(mint), Some(address)) = (quote_account_mint, quote_account) {
+            if !params.create_output_token_ata {
+                require_account(accounts[1].as_ref(), mint, address)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_balance_checks() {
+        let (mint, address) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&500u64.to_le_bytes());
+        let account = Account { data, ..Account::default() };
+
+        assert_eq!(require_account(Some(&account), mint, address), Ok(500));
+        assert_eq!(
+            require_account(None, mint, address),
+            Err(PreflightError::MissingAccount { mint, account: address })
+        );
+        assert!(require_balance(500, 500, mint, address).is_ok());
+        let program = crate::constants::TOKEN_PROGRAM;
+        assert!(check_token_account(Some(&account), mint, address, program).is_err());
+        let mut account = Account { owner: program, ..account };
+        assert!(check_token_account(Some(&account), mint, address, program).is_ok());
+        account.data[108] = TOKEN_ACCOUNT_STATE_FROZEN;
+        assert_eq!(
+            check_token_account(Some(&account), mint, address, program),
+            Err(PreflightError::AccountFrozen { mint, account: address })
+        );
+        assert!(check_token_account(None, mint, address, program).is_ok());
+        assert_eq!(
+            require_balance(500, 501, mint, address),
+            Err(PreflightError::InsufficientBalance {
+                mint,
+                account: address,
+                balance: 500,
+                required: 501
+            })
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Token account pre-provisioning for mints expected to be traded soon (e.g. seen in a shred
+//! stream before the decision to buy), so the trade itself carries no account creation.
+//! 预建代币账户：对即将交易的 mint 提前创建 ATA（或 seed 账户），下单时交易更小、更便宜。
+
+use super::{TradingClient, UtilityTxOptions};
+use crate::common::fast_fn::create_associated_token_account_idempotent_fast_use_seed;
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use crate::utils::accounts::MAX_ACCOUNTS_PER_REQUEST;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use std::collections::HashSet;
+
+/// Account creations per transaction. A seed-optimized creation takes two instructions and two
+/// new account keys; four still fit in one packet next to a compute budget and a relay tip.
+pub const ATAS_PER_TRANSACTION: usize = 4;
+
+/// Result of [`TradingClient::provision_atas`].
+#[derive(Debug, Clone, Default)]
+pub struct AtaProvisioning {
+    /// Mints whose token account this call created.
+    pub created: Vec<Pubkey>,
+    /// Mints whose token account already existed.
+    pub existing: Vec<Pubkey>,
+    /// Mints not on chain yet, or not owned by Token / Token-2022; nothing was sent for them.
+    pub unavailable: Vec<Pubkey>,
+    /// One signature per creation transaction.
+    pub signatures: Vec<String>,
+    /// Mints of the failed creation transaction and of every batch after it, which was not sent.
+    pub failed: Vec<Pubkey>,
+    /// Why the creation of `failed` stopped; `None` when every missing account was created.
+    pub error: Option<String>,
+}
+
+impl AtaProvisioning {
+    /// Stop at a failed creation: `unsent` starts with the failed batch.
+    fn record_failure(&mut self, unsent: &[(Pubkey, Pubkey)], error: anyhow::Error) {
+        self.failed.extend(unsent.iter().map(|(mint, _)| *mint));
+        self.error = Some(error.to_string());
+    }
+}
+
+impl TradingClient {
+    /// Create the payer's token accounts for `mints` ahead of trading them, so the trade can
+    /// skip the creation ([`super::AccountPolicy::HotPathMinimal`] or `create_mint_ata = false`)
+    /// and stay small. Accounts follow `use_seed_optimize`, exactly like the trades' own.
+    ///
+    /// Costs two `getMultipleAccounts` per 100 mints; the missing accounts are then created
+    /// [`ATAS_PER_TRANSACTION`] per transaction with the fee settings in `options`. Stops at the
+    /// first failed transaction and still returns what it did: accounts created before it stay
+    /// in `created`, the rest are in [`AtaProvisioning::failed`] with the error. Only the account
+    /// lookups, which run before anything is sent, fail the call.
+    pub async fn provision_atas(
+        &self,
+        mints: &[Pubkey],
+        options: &UtilityTxOptions,
+    ) -> Result<AtaProvisioning, anyhow::Error> {
+        let rpc = &self.infrastructure.rpc;
+        let payer = self.payer.pubkey();
+        let mut seen = HashSet::with_capacity(mints.len());
+        let mints: Vec<Pubkey> = mints.iter().copied().filter(|mint| seen.insert(*mint)).collect();
+
+        let mut outcome = AtaProvisioning::default();
+        let mut missing = Vec::new();
+        for chunk in mints.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+            let mint_accounts = rpc.get_multiple_accounts(chunk).await?;
+            let mut candidates = Vec::with_capacity(chunk.len());
+            for (mint, account) in chunk.iter().zip(mint_accounts) {
+                match account.map(|account| account.owner) {
+                    Some(owner) if owner == TOKEN_PROGRAM || owner == TOKEN_PROGRAM_2022 => {
+                        candidates.push((*mint, owner))
+                    }
+                    _ => outcome.unavailable.push(*mint),
+                }
+            }
+            let addresses: Vec<Pubkey> = candidates
+                .iter()
+                .map(|(mint, token_program)| self.payer_token_account(mint, token_program))
+                .collect();
+            let accounts = rpc.get_multiple_accounts(&addresses).await?;
+            for (candidate, account) in candidates.into_iter().zip(accounts) {
+                match account {
+                    Some(_) => outcome.existing.push(candidate.0),
+                    None => missing.push(candidate),
+                }
+            }
+        }
+
+        for (index, batch) in missing.chunks(ATAS_PER_TRANSACTION).enumerate() {
+            let instructions = creation_instructions(&payer, batch, self.use_seed_optimize);
+            match self.send_utility_instructions(instructions, options).await {
+                Ok(signature) => {
+                    outcome.signatures.push(signature);
+                    outcome.created.extend(batch.iter().map(|(mint, _)| *mint));
+                }
+                Err(e) => {
+                    outcome.record_failure(&missing[index * ATAS_PER_TRANSACTION..], e);
+                    break;
+                }
+            }
+        }
+        Ok(outcome)
+    }
+}
+
+/// Creation instructions for `(mint, token_program)` pairs, at the addresses the trades use.
+fn creation_instructions(
+    payer: &Pubkey,
+    mints: &[(Pubkey, Pubkey)],
+    use_seed: bool,
+) -> Vec<Instruction> {
+    mints
+        .iter()
+        .flat_map(|(mint, token_program)| {
+            create_associated_token_account_idempotent_fast_use_seed(
+                payer,
+                payer,
+                mint,
+                token_program,
+                use_seed,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::common::build_transaction;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::Keypair;
+    use std::sync::Arc;
+
+    #[test]
+    fn a_full_seed_batch_fits_in_one_transaction() {
+        let payer = Arc::new(Keypair::new());
+        let batch: Vec<(Pubkey, Pubkey)> =
+            (0..ATAS_PER_TRANSACTION).map(|_| (Pubkey::new_unique(), TOKEN_PROGRAM_2022)).collect();
+        let instructions = creation_instructions(&payer.pubkey(), &batch, true);
+        assert_eq!(instructions.len(), 2 * ATAS_PER_TRANSACTION);
+
+        build_transaction(
+            &payer,
+            200_000,
+            100_000,
+            &instructions,
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            None,
+            None,
+            true,
+            true,
+            &Pubkey::new_unique(),
+            0.001,
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn a_failed_batch_reports_itself_and_every_later_mint() {
+        let missing: Vec<(Pubkey, Pubkey)> = (0..ATAS_PER_TRANSACTION * 2 + 1)
+            .map(|_| (Pubkey::new_unique(), TOKEN_PROGRAM))
+            .collect();
+        let mut outcome = AtaProvisioning::default();
+        outcome.created.extend(missing[..ATAS_PER_TRANSACTION].iter().map(|(mint, _)| *mint));
+        outcome.record_failure(&missing[ATAS_PER_TRANSACTION..], anyhow::anyhow!("blockhash"));
+
+        assert_eq!(outcome.created.len(), ATAS_PER_TRANSACTION);
+        assert_eq!(outcome.failed.len(), ATAS_PER_TRANSACTION + 1);
+        assert_eq!(outcome.failed[0], missing[ATAS_PER_TRANSACTION].0);
+        assert_eq!(outcome.error.as_deref(), Some("blockhash"));
+    }
+}
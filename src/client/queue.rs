@@ -0,0 +1,494 @@
+//! Bounded priority queue in front of [`TradingClient`] for event-driven bots.
+//!
+//! During a hot launch hundreds of events can each want a trade at once. Instead of spawning a
+//! task per event, submit them to a [`TradeQueue`]: trades wait in one lane per
+//! [`TradePriority`] and a single dispatcher starts the highest-priority trade that fits the
+//! concurrency limits (overall and per SWQOS provider the trade would send through). Trades that
+//! waited longer than their priority's max age are shed, and a full queue evicts its newest
+//! lowest-priority trade for a more important one. Shed or rejected trades resolve with a
+//! [`TRADE_SHED_ERROR_CODE`] [`TradeError`].
+//! 交易优先级队列：按优先级派发、限制整体与各 SWQOS 并发、丢弃过期项，过载时平滑降级。
+
+use super::{TradeBuyParams, TradeSellParams, TradingClient};
+use crate::swqos::common::{TradeError, TRADE_SHED_ERROR_CODE};
+use crate::swqos::{SwqosType, TradeType};
+use parking_lot::Mutex;
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, Notify};
+
+/// Result of a queued trade, as returned by [`TradingClient::buy`] / [`TradingClient::sell`].
+pub type QueuedTradeResult =
+    Result<(bool, Vec<Signature>, Option<TradeError>, Vec<(SwqosType, i64)>), anyhow::Error>;
+
+/// Dispatch order of queued trades; higher runs first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TradePriority {
+    /// Account cleanup, dust sells and other housekeeping.
+    Cleanup,
+    /// Copy-trading follows.
+    Copy,
+    /// Launch snipes.
+    Sniper,
+}
+
+impl TradePriority {
+    const ALL_DESC: [TradePriority; 3] =
+        [TradePriority::Sniper, TradePriority::Copy, TradePriority::Cleanup];
+
+    #[inline]
+    fn lane(self) -> usize {
+        self as usize
+    }
+}
+
+/// A trade waiting in a [`TradeQueue`].
+pub enum QueuedTrade {
+    Buy(TradeBuyParams),
+    Sell(TradeSellParams),
+}
+
+impl QueuedTrade {
+    fn providers(&self) -> Vec<SwqosType> {
+        let mut providers: Vec<SwqosType> = match self {
+            QueuedTrade::Buy(p) => p.gas_fee_strategy.get_strategies(TradeType::Buy),
+            QueuedTrade::Sell(p) => p.gas_fee_strategy.get_strategies(TradeType::Sell),
+        }
+        .into_iter()
+        .map(|(swqos_type, ..)| swqos_type)
+        .collect();
+        let mut seen = Vec::with_capacity(providers.len());
+        providers.retain(|p| {
+            let first = !seen.contains(p);
+            seen.push(*p);
+            first
+        });
+        providers
+    }
+
+    fn grpc_recv_us(&self) -> Option<i64> {
+        match self {
+            QueuedTrade::Buy(p) => p.grpc_recv_us,
+            QueuedTrade::Sell(p) => p.grpc_recv_us,
+        }
+    }
+}
+
+/// Limits of a [`TradeQueue`]. Zero limits are raised to 1, whether set through the builder
+/// methods or the fields, since a queue that can run nothing would hold every trade forever.
+#[derive(Debug, Clone)]
+pub struct TradeQueueConfig {
+    /// Trades waiting across all priorities.
+    pub capacity: usize,
+    /// Trades running at once.
+    pub max_concurrency: usize,
+    /// Trades running at once that send through a provider; providers not listed are unlimited.
+    pub per_provider_concurrency: HashMap<SwqosType, usize>,
+    /// Max wait per priority, measured from the trade's `grpc_recv_us` when set, else from
+    /// submission. Priorities not listed never expire.
+    pub max_age: HashMap<TradePriority, Duration>,
+}
+
+impl Default for TradeQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            max_concurrency: 16,
+            per_provider_concurrency: HashMap::new(),
+            max_age: HashMap::from([
+                (TradePriority::Sniper, Duration::from_millis(400)),
+                (TradePriority::Copy, Duration::from_secs(2)),
+            ]),
+        }
+    }
+}
+
+impl TradeQueueConfig {
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    pub fn provider_concurrency(mut self, provider: SwqosType, limit: usize) -> Self {
+        self.per_provider_concurrency.insert(provider, limit.max(1));
+        self
+    }
+
+    /// `None` keeps trades of `priority` until they run.
+    pub fn max_age(mut self, priority: TradePriority, max_age: Option<Duration>) -> Self {
+        match max_age {
+            Some(age) => self.max_age.insert(priority, age),
+            None => self.max_age.remove(&priority),
+        };
+        self
+    }
+
+    /// Clamp limits set directly on the fields the way the builder methods do.
+    fn normalized(mut self) -> Self {
+        self.capacity = self.capacity.max(1);
+        self.max_concurrency = self.max_concurrency.max(1);
+        for limit in self.per_provider_concurrency.values_mut() {
+            *limit = (*limit).max(1);
+        }
+        self
+    }
+}
+
+struct Pending {
+    trade: QueuedTrade,
+    providers: Vec<SwqosType>,
+    /// Deadline after which the trade is shed; `None` never expires.
+    expires_at: Option<Instant>,
+    reply: oneshot::Sender<QueuedTradeResult>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    lanes: [VecDeque<Pending>; 3],
+    running: usize,
+    running_per_provider: HashMap<SwqosType, usize>,
+}
+
+impl QueueState {
+    fn queued(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+}
+
+struct Inner {
+    client: Arc<TradingClient>,
+    config: TradeQueueConfig,
+    state: Mutex<QueueState>,
+    notify: Notify,
+    closed: AtomicBool,
+}
+
+/// Counters of a [`TradeQueue`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TradeQueueStats {
+    pub queued: usize,
+    pub running: usize,
+}
+
+fn shed_error(message: String) -> anyhow::Error {
+    anyhow::Error::new(TradeError { code: TRADE_SHED_ERROR_CODE, message, instruction: None })
+}
+
+/// Bounded, prioritized trade executor over one [`TradingClient`]. Dropping it stops dispatch;
+/// trades still waiting resolve as shed, running ones finish.
+pub struct TradeQueue {
+    inner: Arc<Inner>,
+}
+
+impl TradeQueue {
+    /// Start the dispatcher on the current tokio runtime.
+    pub fn new(client: Arc<TradingClient>, config: TradeQueueConfig) -> Self {
+        let inner = Arc::new(Inner {
+            client,
+            config: config.normalized(),
+            state: Mutex::new(QueueState::default()),
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+        tokio::spawn(dispatch_loop(inner.clone()));
+        Self { inner }
+    }
+
+    /// Queue `trade`; the receiver yields its result once it ran or was shed.
+    ///
+    /// A full queue evicts its newest trade of the lowest priority below `priority`; when there
+    /// is none, `trade` itself is rejected right away.
+    pub fn submit(
+        &self,
+        priority: TradePriority,
+        trade: QueuedTrade,
+    ) -> oneshot::Receiver<QueuedTradeResult> {
+        let (reply, rx) = oneshot::channel();
+        let now_us = crate::common::clock::now_micros();
+        let expires_at = self.inner.config.max_age.get(&priority).map(|max_age| {
+            let waited = trade
+                .grpc_recv_us()
+                .map(|recv_us| Duration::from_micros(now_us.saturating_sub(recv_us).max(0) as u64))
+                .unwrap_or_default();
+            Instant::now() + max_age.saturating_sub(waited)
+        });
+        let pending = Pending { providers: trade.providers(), trade, expires_at, reply };
+
+        let evicted = {
+            let mut state = self.inner.state.lock();
+            if state.queued() < self.inner.config.capacity {
+                state.lanes[priority.lane()].push_back(pending);
+                None
+            } else {
+                let victim_lane = TradePriority::ALL_DESC
+                    .iter()
+                    .rev()
+                    .take_while(|p| **p < priority)
+                    .find(|p| !state.lanes[p.lane()].is_empty())
+                    .map(|p| p.lane());
+                match victim_lane {
+                    Some(lane) => {
+                        let victim = state.lanes[lane].pop_back();
+                        state.lanes[priority.lane()].push_back(pending);
+                        victim.map(|v| (v, "evicted by a higher-priority trade"))
+                    }
+                    None => Some((pending, "queue is full")),
+                }
+            }
+        };
+        if let Some((shed, reason)) = evicted {
+            let _ = shed.reply.send(Err(shed_error(format!("TradeShed: {}", reason))));
+        }
+        self.inner.notify.notify_one();
+        rx
+    }
+
+    pub fn stats(&self) -> TradeQueueStats {
+        let state = self.inner.state.lock();
+        TradeQueueStats { queued: state.queued(), running: state.running }
+    }
+}
+
+impl Drop for TradeQueue {
+    fn drop(&mut self) {
+        self.inner.closed.store(true, Ordering::Release);
+        self.inner.notify.notify_one();
+    }
+}
+
+/// How often waiting trades are checked for expiry when nothing else wakes the dispatcher.
+const SHED_INTERVAL: Duration = Duration::from_millis(50);
+
+async fn dispatch_loop(inner: Arc<Inner>) {
+    loop {
+        let closed = inner.closed.load(Ordering::Acquire);
+        let (ready, shed) =
+            take_ready(&mut inner.state.lock(), &inner.config, closed, Instant::now());
+        for pending in shed {
+            let _ = pending.reply.send(Err(shed_error(if closed {
+                "TradeShed: queue was dropped".to_string()
+            } else {
+                "TradeShed: waited past its max age".to_string()
+            })));
+        }
+        for pending in ready {
+            tokio::spawn(run_trade(inner.clone(), pending));
+        }
+        if closed {
+            return;
+        }
+        tokio::select! {
+            _ = inner.notify.notified() => {}
+            _ = tokio::time::sleep(SHED_INTERVAL) => {}
+        }
+    }
+}
+
+/// Under the state lock: drop expired trades and reserve capacity for every trade that fits,
+/// highest priority first and FIFO within a priority.
+fn take_ready(
+    state: &mut QueueState,
+    config: &TradeQueueConfig,
+    closed: bool,
+    now: Instant,
+) -> (Vec<Pending>, Vec<Pending>) {
+    let mut shed = Vec::new();
+    if closed {
+        for lane in state.lanes.iter_mut() {
+            shed.extend(lane.drain(..));
+        }
+        return (Vec::new(), shed);
+    }
+    for lane in state.lanes.iter_mut() {
+        let (expired, waiting): (VecDeque<_>, VecDeque<_>) =
+            lane.drain(..).partition(|p| p.expires_at.is_some_and(|at| at <= now));
+        shed.extend(expired);
+        *lane = waiting;
+    }
+
+    let mut ready = Vec::new();
+    for priority in TradePriority::ALL_DESC {
+        let lane = priority.lane();
+        while state.running < config.max_concurrency {
+            let Some(front) = state.lanes[lane].front() else { break };
+            let fits = front.providers.iter().all(|provider| {
+                match config.per_provider_concurrency.get(provider) {
+                    Some(limit) => {
+                        state.running_per_provider.get(provider).copied().unwrap_or(0) < *limit
+                    }
+                    None => true,
+                }
+            });
+            // Lower priorities wait too, so a saturated provider cannot be bypassed by them.
+            if !fits {
+                return (ready, shed);
+            }
+            let pending = state.lanes[lane].pop_front().expect("front exists");
+            state.running += 1;
+            for provider in &pending.providers {
+                *state.running_per_provider.entry(*provider).or_insert(0) += 1;
+            }
+            ready.push(pending);
+        }
+    }
+    (ready, shed)
+}
+
+async fn run_trade(inner: Arc<Inner>, pending: Pending) {
+    let Pending { trade, providers, reply, .. } = pending;
+    let result = match trade {
+        QueuedTrade::Buy(params) => inner.client.buy(params).await,
+        QueuedTrade::Sell(params) => inner.client.sell(params).await,
+    };
+    {
+        let mut state = inner.state.lock();
+        state.running -= 1;
+        for provider in &providers {
+            if let Some(n) = state.running_per_provider.get_mut(provider) {
+                *n = n.saturating_sub(1);
+            }
+        }
+    }
+    inner.notify.notify_one();
+    let _ = reply.send(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::dummy_pumpfun_params;
+    use super::super::{BuyAmount, SimpleBuyParams, TradeTokenType};
+    use super::*;
+    use crate::common::GasFeeStrategy;
+    use crate::trading::factory::DexType;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+
+    /// A waiting buy of a fresh mint sending through `providers`.
+    fn pending(providers: &[SwqosType], expires_at: Option<Instant>) -> (Pending, Pubkey) {
+        let mint = Pubkey::new_unique();
+        let params = SimpleBuyParams::new(
+            DexType::PumpFun,
+            TradeTokenType::SOL,
+            mint,
+            BuyAmount::ExactInput(1_000),
+            dummy_pumpfun_params(),
+            Hash::default(),
+            GasFeeStrategy::new(),
+        );
+        let trade = QueuedTrade::Buy(params.into());
+        let (reply, _) = oneshot::channel();
+        (Pending { trade, providers: providers.to_vec(), expires_at, reply }, mint)
+    }
+
+    fn queue(state: &mut QueueState, priority: TradePriority, pending: Pending) {
+        state.lanes[priority.lane()].push_back(pending);
+    }
+
+    fn mints(trades: &[Pending]) -> Vec<Pubkey> {
+        trades
+            .iter()
+            .map(|p| match &p.trade {
+                QueuedTrade::Buy(params) => params.mint,
+                QueuedTrade::Sell(params) => params.mint,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn take_ready_runs_higher_priorities_first_and_sheds_expired_trades() {
+        let config = TradeQueueConfig::default().max_concurrency(3);
+        let now = Instant::now();
+        let mut state = QueueState::default();
+        let (cleanup, cleanup_mint) = pending(&[], None);
+        let (copy, copy_mint) = pending(&[], None);
+        let (sniper, sniper_mint) = pending(&[], None);
+        let (second_sniper, second_sniper_mint) = pending(&[], None);
+        let (stale, stale_mint) = pending(&[], Some(now));
+        queue(&mut state, TradePriority::Cleanup, cleanup);
+        queue(&mut state, TradePriority::Copy, stale);
+        queue(&mut state, TradePriority::Copy, copy);
+        queue(&mut state, TradePriority::Sniper, sniper);
+        queue(&mut state, TradePriority::Sniper, second_sniper);
+
+        let (ready, shed) = take_ready(&mut state, &config, false, now);
+        assert_eq!(mints(&ready), vec![sniper_mint, second_sniper_mint, copy_mint]);
+        assert_eq!(mints(&shed), vec![stale_mint]);
+        assert_eq!(state.running, 3);
+        let waiting = state.lanes[TradePriority::Cleanup.lane()].make_contiguous();
+        assert_eq!(mints(waiting), vec![cleanup_mint]);
+    }
+
+    #[test]
+    fn take_ready_holds_every_lane_behind_a_saturated_provider() {
+        let config = TradeQueueConfig::default().provider_concurrency(SwqosType::Jito, 1);
+        let mut state = QueueState {
+            running: 1,
+            running_per_provider: HashMap::from([(SwqosType::Jito, 1)]),
+            ..Default::default()
+        };
+        let (sniper, sniper_mint) = pending(&[SwqosType::Jito], None);
+        let (sniper_free, _) = pending(&[], None);
+        let (copy_free, _) = pending(&[], None);
+        queue(&mut state, TradePriority::Sniper, sniper);
+        queue(&mut state, TradePriority::Sniper, sniper_free);
+        queue(&mut state, TradePriority::Copy, copy_free);
+
+        let (ready, shed) = take_ready(&mut state, &config, false, Instant::now());
+        assert!(ready.is_empty() && shed.is_empty());
+        assert_eq!(state.queued(), 3);
+
+        // Once the provider frees up, the blocked head runs first.
+        state.running = 0;
+        state.running_per_provider.insert(SwqosType::Jito, 0);
+        let (ready, _) = take_ready(&mut state, &config, false, Instant::now());
+        assert_eq!(mints(&ready)[0], sniper_mint);
+        assert_eq!(ready.len(), 3);
+        assert_eq!(state.running_per_provider[&SwqosType::Jito], 1);
+    }
+
+    #[test]
+    fn zero_limits_set_on_the_fields_are_clamped() {
+        let config = TradeQueueConfig {
+            capacity: 0,
+            max_concurrency: 0,
+            per_provider_concurrency: HashMap::from([(SwqosType::Jito, 0)]),
+            max_age: HashMap::new(),
+        }
+        .normalized();
+        assert_eq!(config.capacity, 1);
+        assert_eq!(config.max_concurrency, 1);
+        assert_eq!(config.per_provider_concurrency[&SwqosType::Jito], 1);
+
+        let mut state = QueueState::default();
+        let (trade, mint) = pending(&[SwqosType::Jito], None);
+        queue(&mut state, TradePriority::Copy, trade);
+        let (ready, _) = take_ready(&mut state, &config, false, Instant::now());
+        assert_eq!(mints(&ready), vec![mint]);
+    }
+
+    #[test]
+    fn lanes_run_sniper_first_and_config_clamps_limits() {
+        assert!(TradePriority::Sniper > TradePriority::Copy);
+        assert!(TradePriority::Copy > TradePriority::Cleanup);
+        assert_eq!(TradePriority::ALL_DESC[0], TradePriority::Sniper);
+
+        let config = TradeQueueConfig::default()
+            .capacity(0)
+            .max_concurrency(0)
+            .provider_concurrency(SwqosType::Jito, 0)
+            .max_age(TradePriority::Sniper, None);
+        assert_eq!(config.capacity, 1);
+        assert_eq!(config.max_concurrency, 1);
+        assert_eq!(config.per_provider_concurrency[&SwqosType::Jito], 1);
+        assert!(!config.max_age.contains_key(&TradePriority::Sniper));
+        assert!(config.max_age.contains_key(&TradePriority::Copy));
+    }
+}
@@ -1,10 +1,17 @@
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
+use smallvec::SmallVec;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
 };
-use std::{hash::Hash, sync::Arc};
+use std::{
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use crate::common::{
     spl_associated_token_account::get_associated_token_address_with_program_id,
@@ -37,6 +44,67 @@ where
     }
 }
 
+/// Derived address with the tick of its last lookup, for least-recently-used eviction.
+struct LruSlot {
+    address: Pubkey,
+    last_used: AtomicU64,
+}
+
+/// Monotonic lookup counter shared by the PDA and ATA caches (cheaper than reading a clock).
+static LRU_TICK: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) static PDA_HITS: AtomicU64 = AtomicU64::new(0);
+pub(crate) static PDA_MISSES: AtomicU64 = AtomicU64::new(0);
+pub(crate) static ATA_HITS: AtomicU64 = AtomicU64::new(0);
+pub(crate) static ATA_MISSES: AtomicU64 = AtomicU64::new(0);
+
+#[inline]
+fn lru_tick() -> u64 {
+    LRU_TICK.fetch_add(1, Ordering::Relaxed)
+}
+
+#[inline]
+fn lru_get<K: Eq + Hash>(cache: &DashMap<K, LruSlot>, key: &K) -> Option<Pubkey> {
+    cache.get(key).map(|slot| {
+        slot.last_used.store(lru_tick(), Ordering::Relaxed);
+        slot.address
+    })
+}
+
+#[inline]
+fn lru_insert<K: Eq + Hash + Clone>(
+    cache: &DashMap<K, LruSlot>,
+    key: K,
+    address: Pubkey,
+    max: usize,
+) {
+    cache.insert(key, LruSlot { address, last_used: AtomicU64::new(lru_tick()) });
+    if cache.len() > max {
+        prune_lru(cache, max);
+    }
+}
+
+/// Evict the least recently used entries once `cache` exceeds `max_size` (1/16 at a time so a
+/// full cache does not prune on every insert).
+#[cold]
+fn prune_lru<K: Eq + Hash + Clone>(cache: &DashMap<K, LruSlot>, max_size: usize) {
+    let len = cache.len();
+    if len <= max_size {
+        return;
+    }
+    let remove_count = (len - max_size).max(max_size / 16).min(len);
+    let mut entries: Vec<(u64, K)> = cache
+        .iter()
+        .map(|entry| (entry.value().last_used.load(Ordering::Relaxed), entry.key().clone()))
+        .collect();
+    if remove_count < entries.len() {
+        entries.select_nth_unstable_by_key(remove_count, |(tick, _)| *tick);
+    }
+    for (_, key) in entries.into_iter().take(remove_count) {
+        cache.remove(&key);
+    }
+}
+
 // --------------------- Instruction Cache ---------------------
 
 /// Instruction cache key for uniquely identifying instruction types and parameters
@@ -187,10 +255,15 @@ pub enum PdaCacheKey {
     BonkVault(Pubkey, Pubkey),
     PumpSwapUserVolume(Pubkey),
     PumpSwapPoolV2(Pubkey),
+    /// Any other PDA: program id plus the length-prefixed concatenation of its seeds.
+    Seeds {
+        program: Pubkey,
+        seeds: SmallVec<[u8; 136]>,
+    },
 }
 
-/// Global lock-free PDA cache for storing computation results
-static PDA_CACHE: Lazy<DashMap<PdaCacheKey, Pubkey>> =
+/// Global lock-free PDA cache for storing computation results (LRU eviction)
+static PDA_CACHE: Lazy<DashMap<PdaCacheKey, LruSlot>> =
     Lazy::new(|| DashMap::with_capacity(MAX_PDA_CACHE_SIZE));
 
 /// Get cached PDA, compute and cache if not exists (lock-free)
@@ -200,21 +273,42 @@ where
     F: FnOnce() -> Option<Pubkey>,
 {
     // Fast path: check if already in cache
-    if let Some(pda) = PDA_CACHE.get(&cache_key) {
-        return Some(*pda);
+    if let Some(pda) = lru_get(&PDA_CACHE, &cache_key) {
+        PDA_HITS.fetch_add(1, Ordering::Relaxed);
+        return Some(pda);
     }
 
     // Slow path: compute and cache
+    PDA_MISSES.fetch_add(1, Ordering::Relaxed);
     let pda_result = compute_fn();
 
     if let Some(pda) = pda_result {
-        PDA_CACHE.insert(cache_key, pda);
-        prune_cache(&PDA_CACHE, MAX_PDA_CACHE_SIZE);
+        lru_insert(&PDA_CACHE, cache_key, pda, MAX_PDA_CACHE_SIZE);
     }
 
     pda_result
 }
 
+/// Cached `Pubkey::try_find_program_address`, keyed by (seeds, program).
+/// 带缓存的 PDA 推导：同一组 seeds 只做一次 sha256 搜索。
+#[inline]
+pub fn find_program_address_cached(seeds: &[&[u8]], program_id: &Pubkey) -> Option<Pubkey> {
+    let mut key = SmallVec::<[u8; 136]>::new();
+    for seed in seeds {
+        // Seeds are at most 32 bytes; the length prefix keeps ["ab", "c"] apart from ["a", "bc"].
+        key.push(seed.len() as u8);
+        key.extend_from_slice(seed);
+    }
+    get_cached_pda(PdaCacheKey::Seeds { program: *program_id, seeds: key }, || {
+        Pubkey::try_find_program_address(seeds, program_id).map(|(pda, _)| pda)
+    })
+}
+
+/// Number of PDAs currently cached.
+pub fn pda_cache_len() -> usize {
+    PDA_CACHE.len()
+}
+
 // --------------------- ATA ---------------------
 
 /// ATA cache key for Associated Token Address caching
@@ -226,8 +320,8 @@ struct AtaCacheKey {
     use_seed: bool,
 }
 
-/// Global lock-free ATA cache for storing Associated Token Address computation results
-static ATA_CACHE: Lazy<DashMap<AtaCacheKey, Pubkey>> =
+/// Global lock-free ATA cache for storing Associated Token Address computation results (LRU eviction)
+static ATA_CACHE: Lazy<DashMap<AtaCacheKey, LruSlot>> =
     Lazy::new(|| DashMap::with_capacity(MAX_ATA_CACHE_SIZE));
 
 #[inline]
@@ -274,9 +368,11 @@ fn _get_associated_token_address_with_program_id_fast(
     };
 
     // Fast path: check if already in cache (lock-free)
-    if let Some(cached_ata) = ATA_CACHE.get(&cache_key) {
-        return *cached_ata;
+    if let Some(cached_ata) = lru_get(&ATA_CACHE, &cache_key) {
+        ATA_HITS.fetch_add(1, Ordering::Relaxed);
+        return cached_ata;
     }
+    ATA_MISSES.fetch_add(1, Ordering::Relaxed);
 
     // Slow path: compute new ATA
     // Only use seed if the token mint address is not wSOL or SOL
@@ -313,12 +409,16 @@ fn _get_associated_token_address_with_program_id_fast(
     };
 
     // Store computation result in cache (lock-free)
-    ATA_CACHE.insert(cache_key, ata);
-    prune_cache(&ATA_CACHE, MAX_ATA_CACHE_SIZE);
+    lru_insert(&ATA_CACHE, cache_key, ata, MAX_ATA_CACHE_SIZE);
 
     ata
 }
 
+/// Number of token account addresses currently cached.
+pub fn ata_cache_len() -> usize {
+    ATA_CACHE.len()
+}
+
 // --------------------- Initialize Accounts ---------------------
 
 pub fn fast_init(payer: &Pubkey) {
@@ -350,3 +450,45 @@ pub fn fast_init(payer: &Pubkey) {
         },
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_key_matches_uncached_derivation() {
+        let mint = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let expected = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &program).0;
+        assert_eq!(
+            find_program_address_cached(&[b"pool", mint.as_ref()], &program),
+            Some(expected)
+        );
+        let hits = PDA_HITS.load(Ordering::Relaxed);
+        assert_eq!(
+            find_program_address_cached(&[b"pool", mint.as_ref()], &program),
+            Some(expected)
+        );
+        assert!(PDA_HITS.load(Ordering::Relaxed) > hits);
+        // Same bytes, different seed split: a different PDA.
+        assert_ne!(
+            find_program_address_cached(&[b"poo", b"l", mint.as_ref()], &program),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn prune_evicts_least_recently_used() {
+        let cache: DashMap<u32, LruSlot> = DashMap::new();
+        for key in 0..32u32 {
+            lru_insert(&cache, key, Pubkey::new_unique(), usize::MAX);
+        }
+        // Touch the oldest entry so it survives.
+        assert!(lru_get(&cache, &0).is_some());
+        prune_lru(&cache, 16);
+        assert_eq!(cache.len(), 16);
+        assert!(cache.contains_key(&0));
+        assert!(!cache.contains_key(&1));
+        assert!(cache.contains_key(&31));
+    }
+}
@@ -1,38 +1,27 @@
 //! 🚀 快速计时模块 - 减少 Instant::now() 系统调用开销
 //!
-//! 使用 syscall_bypass 提供的快速时间戳避免频繁的系统调用
+//! 以进程内单一 `Instant` 为基准，返回自基准以来的纳秒数（vDSO 单调时钟，无系统调用）
 
-use crate::perf::syscall_bypass::SystemCallBypassManager;
 use once_cell::sync::Lazy;
 use std::time::{Duration, Instant};
 
 /// 全局快速时间提供器
-static FAST_TIMER: Lazy<FastTimer> = Lazy::new(|| FastTimer::new());
+static FAST_TIMER: Lazy<FastTimer> = Lazy::new(FastTimer::new);
 
 /// 快速计时器 - 减少系统调用开销
 pub struct FastTimer {
-    bypass_manager: SystemCallBypassManager,
-    _base_instant: Instant,
-    _base_nanos: u64,
+    base_instant: Instant,
 }
 
 impl FastTimer {
     fn new() -> Self {
-        use crate::perf::syscall_bypass::SyscallBypassConfig;
-
-        let bypass_manager = SystemCallBypassManager::new(SyscallBypassConfig::default())
-            .expect("Failed to create SystemCallBypassManager");
-
-        let base_instant = Instant::now();
-        let base_nanos = bypass_manager.fast_timestamp_nanos();
-
-        Self { bypass_manager, _base_instant: base_instant, _base_nanos: base_nanos }
+        Self { base_instant: Instant::now() }
     }
 
-    /// 🚀 获取当前时间戳（纳秒） - 使用快速系统调用绕过
+    /// 🚀 获取当前时间戳（纳秒，自计时器创建起）
     #[inline(always)]
     pub fn now_nanos(&self) -> u64 {
-        self.bypass_manager.fast_timestamp_nanos()
+        self.base_instant.elapsed().as_nanos() as u64
     }
 
     /// 🚀 获取当前时间戳（微秒）
@@ -62,7 +51,7 @@ impl FastTimer {
 
 /// 🚀 快速获取当前时间戳（纳秒）- 全局函数
 ///
-/// 使用 syscall_bypass 避免频繁的 clock_gettime 系统调用
+/// 仅用于计算耗时，不是墙钟时间
 #[inline(always)]
 pub fn fast_now_nanos() -> u64 {
     FAST_TIMER.now_nanos()
@@ -1,4 +1,5 @@
 use crate::swqos::{SwqosType, TradeType};
+use crate::trading::factory::DexType;
 use arc_swap::ArcSwap;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -32,15 +33,107 @@ pub struct GasFeeStrategyValue {
     pub tip: f64,
 }
 
+/// Network congestion seen by a [`GasFeeModel`], in micro-lamports per CU.
+///
+/// Filled by [`crate::TradingClient::refresh_congestion`] (recent prioritization fees) or set by
+/// the caller's own monitor with [`crate::TradingClient::set_congestion`]. `None` means unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Congestion {
+    pub median_cu_price: Option<u64>,
+    pub p90_cu_price: Option<u64>,
+}
+
+impl Congestion {
+    /// Median and 90th percentile of a sample of recent prioritization fees.
+    pub fn from_prioritization_fees(fees: impl IntoIterator<Item = u64>) -> Self {
+        let mut fees: Vec<u64> = fees.into_iter().collect();
+        if fees.is_empty() {
+            return Self::default();
+        }
+        fees.sort_unstable();
+        let percentile = |p: usize| fees[(fees.len() - 1) * p / 100];
+        Self { median_cu_price: Some(percentile(50)), p90_cu_price: Some(percentile(90)) }
+    }
+}
+
+/// Fees for one trade: the lanes to send (one transaction per entry), the optional
+/// loaded-accounts data size limit and the optional heap frame size.
+#[derive(Debug, Clone, Default)]
+pub struct FeeParams {
+    pub lanes: Vec<(SwqosType, GasFeeStrategyType, GasFeeStrategyValue)>,
+    pub data_size_limit: Option<u32>,
+    pub heap_frame_bytes: Option<u32>,
+}
+
+/// Pluggable fee model, e.g. ML- or oracle-driven, consulted for every trade when set with
+/// [`crate::TradingClient::with_gas_fee_model`]. [`GasFeeStrategy`] is the default
+/// implementation and ignores congestion.
+///
+/// Called on the trade path, so keep it cheap: read precomputed values, do not fetch.
+/// 自定义费率模型：按 DEX、买卖方向与网络拥堵给出每个通道的费用。
+pub trait GasFeeModel: Send + Sync {
+    fn fees_for(&self, dex: DexType, trade_type: TradeType, congestion: &Congestion) -> FeeParams;
+}
+
+impl GasFeeModel for GasFeeStrategy {
+    fn fees_for(&self, dex: DexType, trade_type: TradeType, _congestion: &Congestion) -> FeeParams {
+        FeeParams {
+            lanes: self.get_strategies(trade_type),
+            data_size_limit: self.get_data_size_limit(dex),
+            heap_frame_bytes: self.get_heap_frame(dex),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct GasFeeStrategy {
     strategies:
         Arc<ArcSwap<HashMap<(SwqosType, TradeType, GasFeeStrategyType), GasFeeStrategyValue>>>,
+    /// Loaded-accounts data size limit (bytes) per DEX.
+    data_size_limits: Arc<ArcSwap<HashMap<DexType, u32>>>,
+    /// Requested heap frame (bytes) per DEX.
+    heap_frames: Arc<ArcSwap<HashMap<DexType, u32>>>,
+}
+
+/// The strategies and limits currently loaded.
+impl std::fmt::Debug for GasFeeStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GasFeeStrategy")
+            .field("strategies", &**self.strategies.load())
+            .field("data_size_limits", &**self.data_size_limits.load())
+            .field("heap_frames", &**self.heap_frames.load())
+            .finish()
+    }
 }
 
 impl GasFeeStrategy {
     pub fn new() -> Self {
-        Self { strategies: Arc::new(ArcSwap::from_pointee(HashMap::new())) }
+        Self {
+            strategies: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            data_size_limits: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            heap_frames: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+        }
+    }
+
+    /// Strategy holding exactly `fees` for `trade_type` on `dex`, as built from a
+    /// [`GasFeeModel`].
+    pub fn from_fee_params(dex: DexType, trade_type: TradeType, fees: &FeeParams) -> Self {
+        let strategy = Self::new();
+        strategy.strategies.store(Arc::new(
+            fees.lanes
+                .iter()
+                .map(|(swqos_type, strategy_type, value)| {
+                    ((*swqos_type, trade_type, *strategy_type), *value)
+                })
+                .collect(),
+        ));
+        if let Some(bytes) = fees.data_size_limit {
+            strategy.set_data_size_limit(dex, bytes);
+        }
+        if let Some(bytes) = fees.heap_frame_bytes {
+            strategy.set_heap_frame(dex, bytes);
+        }
+        strategy
     }
 
     /// 设置全局费率策略
@@ -325,10 +418,69 @@ impl GasFeeStrategy {
         result
     }
 
-    /// 清空所有策略。
-    /// Clear all strategies
+    /// 设置指定 DEX 的账户数据加载上限（字节），交易会附带 SetLoadedAccountsDataSizeLimit。
+    /// Set the loaded-accounts data size limit (bytes) for a DEX. A smaller limit lowers the
+    /// fee-relevant cost of the transaction; too small and it fails to load its accounts.
+    pub fn set_data_size_limit(&self, dex_type: DexType, bytes: u32) {
+        self.data_size_limits.rcu(|current_map| {
+            let mut new_map = (**current_map).clone();
+            new_map.insert(dex_type, bytes);
+            Arc::new(new_map)
+        });
+    }
+
+    /// 移除指定 DEX 的账户数据加载上限。
+    /// Remove the data size limit for a DEX (the runtime default applies)
+    pub fn del_data_size_limit(&self, dex_type: DexType) {
+        self.data_size_limits.rcu(|current_map| {
+            let mut new_map = (**current_map).clone();
+            new_map.remove(&dex_type);
+            Arc::new(new_map)
+        });
+    }
+
+    /// 获取指定 DEX 的账户数据加载上限。
+    /// Get the data size limit for a DEX
+    #[inline]
+    pub fn get_data_size_limit(&self, dex_type: DexType) -> Option<u32> {
+        self.data_size_limits.load().get(&dex_type).copied()
+    }
+
+    /// 设置指定 DEX 的堆内存大小（字节），交易会附带 RequestHeapFrame。
+    /// Request a heap frame of `bytes` for a DEX whose swaps outgrow the default 32 KiB heap
+    /// (e.g. CLMM swaps crossing many ticks). Rounded up to a multiple of 1 KiB and clamped to
+    /// 32..=256 KiB when the instruction is built. A bigger heap costs compute units.
+    pub fn set_heap_frame(&self, dex_type: DexType, bytes: u32) {
+        self.heap_frames.rcu(|current_map| {
+            let mut new_map = (**current_map).clone();
+            new_map.insert(dex_type, bytes);
+            Arc::new(new_map)
+        });
+    }
+
+    /// 移除指定 DEX 的堆内存设置。
+    /// Remove the heap frame for a DEX (the default 32 KiB heap applies)
+    pub fn del_heap_frame(&self, dex_type: DexType) {
+        self.heap_frames.rcu(|current_map| {
+            let mut new_map = (**current_map).clone();
+            new_map.remove(&dex_type);
+            Arc::new(new_map)
+        });
+    }
+
+    /// 获取指定 DEX 的堆内存大小。
+    /// Get the heap frame for a DEX
+    #[inline]
+    pub fn get_heap_frame(&self, dex_type: DexType) -> Option<u32> {
+        self.heap_frames.load().get(&dex_type).copied()
+    }
+
+    /// 清空所有策略（含账户数据加载上限与堆内存设置）。
+    /// Clear all strategies, data size limits and heap frames included
     pub fn clear(&self) {
         self.strategies.store(Arc::new(HashMap::new()));
+        self.data_size_limits.store(Arc::new(HashMap::new()));
+        self.heap_frames.store(Arc::new(HashMap::new()));
     }
 
     /// 动态更新买入小费（保持其他参数不变）
@@ -533,4 +685,74 @@ mod tests {
         assert_eq!(sell.cu_price, 800_000);
         assert_eq!(sell.tip, 0.0);
     }
+
+    #[test]
+    fn data_size_limit_is_per_dex() {
+        let strategy = GasFeeStrategy::new();
+
+        strategy.set_data_size_limit(DexType::PumpFun, 128 * 1024);
+        strategy.set_data_size_limit(DexType::RaydiumCpmm, 256 * 1024);
+        assert_eq!(strategy.get_data_size_limit(DexType::PumpFun), Some(128 * 1024));
+        assert_eq!(strategy.get_data_size_limit(DexType::RaydiumCpmm), Some(256 * 1024));
+        assert_eq!(strategy.get_data_size_limit(DexType::PumpSwap), None);
+
+        strategy.del_data_size_limit(DexType::PumpFun);
+        assert_eq!(strategy.get_data_size_limit(DexType::PumpFun), None);
+
+        strategy.clear();
+        assert_eq!(strategy.get_data_size_limit(DexType::RaydiumCpmm), None);
+    }
+
+    #[test]
+    fn heap_frame_is_per_dex() {
+        let strategy = GasFeeStrategy::new();
+
+        strategy.set_heap_frame(DexType::MeteoraDammV2, 64 * 1024);
+        assert_eq!(strategy.get_heap_frame(DexType::MeteoraDammV2), Some(64 * 1024));
+        assert_eq!(strategy.get_heap_frame(DexType::PumpFun), None);
+
+        let fees =
+            strategy.fees_for(DexType::MeteoraDammV2, TradeType::Buy, &Congestion::default());
+        assert_eq!(fees.heap_frame_bytes, Some(64 * 1024));
+
+        strategy.del_heap_frame(DexType::MeteoraDammV2);
+        assert_eq!(strategy.get_heap_frame(DexType::MeteoraDammV2), None);
+    }
+
+    struct CongestionScaled;
+
+    impl GasFeeModel for CongestionScaled {
+        fn fees_for(&self, _: DexType, _: TradeType, congestion: &Congestion) -> FeeParams {
+            let cu_price = congestion.p90_cu_price.unwrap_or(1_000) * 2;
+            let value = GasFeeStrategyValue { cu_limit: 150_000, cu_price, tip: 0.001 };
+            FeeParams {
+                lanes: vec![(SwqosType::Jito, GasFeeStrategyType::Normal, value)],
+                data_size_limit: Some(64 * 1024),
+                heap_frame_bytes: Some(64 * 1024),
+            }
+        }
+    }
+
+    #[test]
+    fn custom_model_becomes_a_strategy() {
+        let congestion = Congestion::from_prioritization_fees([10, 500, 20, 30, 40]);
+        assert_eq!(congestion.median_cu_price, Some(30));
+        assert_eq!(congestion.p90_cu_price, Some(40));
+
+        let model: &dyn GasFeeModel = &CongestionScaled;
+        let fees = model.fees_for(DexType::PumpSwap, TradeType::Sell, &congestion);
+        let strategy = GasFeeStrategy::from_fee_params(DexType::PumpSwap, TradeType::Sell, &fees);
+        let sell = find_strategy(
+            &strategy.get_strategies(TradeType::Sell),
+            SwqosType::Jito,
+            GasFeeStrategyType::Normal,
+        );
+        assert_eq!(sell.cu_price, 80);
+        assert!(strategy.get_strategies(TradeType::Buy).is_empty());
+        assert_eq!(strategy.get_data_size_limit(DexType::PumpSwap), Some(64 * 1024));
+        assert_eq!(strategy.get_heap_frame(DexType::PumpSwap), Some(64 * 1024));
+
+        let round_trip = strategy.fees_for(DexType::PumpSwap, TradeType::Sell, &congestion);
+        assert_eq!(round_trip.lanes.len(), 1);
+    }
 }
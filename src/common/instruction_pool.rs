@@ -0,0 +1,168 @@
+//! Thread-local pool of instruction and account-meta vectors.
+//!
+//! Instruction builders and the transaction assembler take their `Vec<Instruction>` and
+//! `Vec<AccountMeta>` from here. Once every SWQOS lane of a trade has submitted, the trade's
+//! instructions are handed back with [`recycle_instructions`] and their capacity is reused by
+//! the next build on the same thread. This is a free list, not a per-trade arena: nothing is
+//! scoped to a trade, and vectors that are never recycled are simply dropped. Free lists are
+//! bounded, so an event storm settles into a steady state with no allocator traffic for these
+//! vectors.
+//!
+//! Enabled by the `instruction-pool` feature; without it every function falls back to a plain
+//! `Vec::with_capacity` / drop.
+//! 线程内向量池：指令与账户列表在提交后回收复用，降低事件风暴下的分配压力。
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+
+#[cfg(feature = "instruction-pool")]
+mod free_lists {
+    use super::{AccountMeta, Instruction};
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Vectors kept per kind per thread.
+    const MAX_FREE: usize = 64;
+    /// Larger vectors are dropped instead of cached so one outlier trade doesn't pin memory.
+    const MAX_INSTRUCTIONS: usize = 64;
+    const MAX_ACCOUNT_METAS: usize = 128;
+
+    static REUSED: AtomicU64 = AtomicU64::new(0);
+    static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+
+    struct FreeLists {
+        instructions: Vec<Vec<Instruction>>,
+        account_metas: Vec<Vec<AccountMeta>>,
+    }
+
+    thread_local! {
+        static FREE: RefCell<FreeLists> = const {
+            RefCell::new(FreeLists {
+                instructions: Vec::new(),
+                account_metas: Vec::new(),
+            })
+        };
+    }
+
+    #[inline]
+    fn take<T>(list: Option<Vec<T>>, capacity: usize) -> Vec<T> {
+        match list {
+            Some(mut v) => {
+                REUSED.fetch_add(1, Ordering::Relaxed);
+                v.reserve(capacity);
+                v
+            }
+            None => {
+                ALLOCATED.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(capacity)
+            }
+        }
+    }
+
+    #[inline]
+    fn give<T>(list: &mut Vec<Vec<T>>, mut v: Vec<T>, max_capacity: usize) {
+        if v.capacity() == 0 || v.capacity() > max_capacity || list.len() >= MAX_FREE {
+            return;
+        }
+        v.clear();
+        list.push(v);
+    }
+
+    // `try_with` so calls during thread teardown fall back to plain allocation / drop.
+    pub(super) fn instruction_vec(capacity: usize) -> Vec<Instruction> {
+        take(FREE.try_with(|f| f.borrow_mut().instructions.pop()).ok().flatten(), capacity)
+    }
+
+    pub(super) fn account_meta_vec(capacity: usize) -> Vec<AccountMeta> {
+        take(FREE.try_with(|f| f.borrow_mut().account_metas.pop()).ok().flatten(), capacity)
+    }
+
+    pub(super) fn recycle_instructions(mut instructions: Vec<Instruction>) {
+        let _ = FREE.try_with(|f| {
+            let mut f = f.borrow_mut();
+            for ix in instructions.drain(..) {
+                give(&mut f.account_metas, ix.accounts, MAX_ACCOUNT_METAS);
+            }
+            give(&mut f.instructions, instructions, MAX_INSTRUCTIONS);
+        });
+    }
+
+    pub(super) fn stats() -> (u64, u64) {
+        (REUSED.load(Ordering::Relaxed), ALLOCATED.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(not(feature = "instruction-pool"))]
+mod free_lists {
+    use super::{AccountMeta, Instruction};
+
+    #[inline(always)]
+    pub(super) fn instruction_vec(capacity: usize) -> Vec<Instruction> {
+        Vec::with_capacity(capacity)
+    }
+
+    #[inline(always)]
+    pub(super) fn account_meta_vec(capacity: usize) -> Vec<AccountMeta> {
+        Vec::with_capacity(capacity)
+    }
+
+    #[inline(always)]
+    pub(super) fn recycle_instructions(_instructions: Vec<Instruction>) {}
+
+    pub(super) fn stats() -> (u64, u64) {
+        (0, 0)
+    }
+}
+
+/// Instruction list with room for at least `capacity` instructions.
+#[inline]
+pub fn instruction_vec(capacity: usize) -> Vec<Instruction> {
+    free_lists::instruction_vec(capacity)
+}
+
+/// Account list with room for at least `capacity` metas.
+#[inline]
+pub fn account_meta_vec(capacity: usize) -> Vec<AccountMeta> {
+    free_lists::account_meta_vec(capacity)
+}
+
+/// Return a finished trade's instructions and their account vectors to the pool.
+#[inline]
+pub fn recycle_instructions(instructions: Vec<Instruction>) {
+    free_lists::recycle_instructions(instructions)
+}
+
+/// `(reused, allocated)` vectors handed out since process start; both 0 without
+/// `instruction-pool`.
+pub fn pool_stats() -> (u64, u64) {
+    free_lists::stats()
+}
+
+#[cfg(all(test, feature = "instruction-pool"))]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn recycled_vectors_are_reused_on_the_same_thread() {
+        let mut instructions = instruction_vec(4);
+        let mut accounts = account_meta_vec(2);
+        accounts.push(AccountMeta::new(Pubkey::new_unique(), false));
+        let accounts_ptr = accounts.as_ptr();
+        instructions.push(Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts,
+            data: vec![1],
+        });
+        let instructions_ptr = instructions.as_ptr();
+
+        recycle_instructions(instructions);
+
+        let (reused_before, _) = pool_stats();
+        let instructions = instruction_vec(4);
+        let accounts = account_meta_vec(2);
+        assert!(instructions.is_empty() && accounts.is_empty());
+        assert_eq!(instructions.as_ptr(), instructions_ptr);
+        assert_eq!(accounts.as_ptr(), accounts_ptr);
+        assert!(pool_stats().0 >= reused_before + 2);
+    }
+}
@@ -1,5 +1,94 @@
-use anyhow::{Context, Result};
+//! Keypair loading, so secrets live in files, the environment or a wallet seed phrase instead of
+//! source code.
+//! 从文件、环境变量或助记词加载钱包密钥。
+
+use anyhow::{anyhow, Context, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
 use solana_sdk::signature::Keypair;
+use std::path::PathBuf;
+
+/// Derivation path of the first account in Phantom, Solflare and Backpack.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// Where [`load_keypair`] reads the keypair from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Solana CLI keypair file (64-byte JSON array, e.g. `~/.config/solana/id.json`) or a file
+    /// holding a base58 keypair.
+    File(PathBuf),
+    /// Environment variable holding a base58 keypair or a 64-byte JSON array.
+    Env(String),
+    /// BIP39 seed phrase (no passphrase) and SLIP-0010 derivation path, e.g.
+    /// [`DEFAULT_DERIVATION_PATH`]; every segment is hardened whether or not it ends in `'`.
+    /// `"m"` uses the master key.
+    Mnemonic(String, String),
+}
+
+/// Load a keypair from `source`. Errors name the file or variable, never the secret.
+pub fn load_keypair(source: Source) -> Result<Keypair> {
+    match source {
+        Source::File(path) => {
+            let value = std::fs::read_to_string(&path)
+                .with_context(|| format!("cannot read keypair file {}", path.display()))?;
+            load_keypair_from_string(&value)
+                .with_context(|| format!("invalid keypair file {}", path.display()))
+        }
+        Source::Env(name) => load_keypair_from_env(&name),
+        Source::Mnemonic(phrase, derivation_path) => {
+            load_keypair_from_mnemonic(&phrase, &derivation_path)
+        }
+    }
+}
+
+/// Keypair of `derivation_path` under an English BIP39 seed phrase with an empty passphrase.
+/// Unknown words and bad checksums are rejected.
+pub fn load_keypair_from_mnemonic(phrase: &str, derivation_path: &str) -> Result<Keypair> {
+    // Word list and checksum are checked, so a typo fails here instead of loading an empty wallet.
+    let mnemonic = bip39::Mnemonic::parse_in_normalized(bip39::Language::English, phrase)
+        .map_err(|e| anyhow!("invalid seed phrase: {}", e))?;
+    let path = parse_derivation_path(derivation_path)?;
+
+    let seed = mnemonic.to_seed_normalized("");
+    Ok(Keypair::new_from_array(derive_ed25519(&seed, &path)))
+}
+
+/// Path segments as hardened indices; `m/44'/501'/0'/0'` -> `[44, 501, 0, 0]` | hardened bit.
+fn parse_derivation_path(path: &str) -> Result<Vec<u32>> {
+    let mut segments = path.trim().split('/');
+    if segments.next() != Some("m") {
+        anyhow::bail!("derivation path must start with m/, got {}", path);
+    }
+    segments
+        .map(|segment| {
+            let index = segment.strip_suffix('\'').unwrap_or(segment);
+            match index.parse::<u32>() {
+                Ok(index) if index < HARDENED_OFFSET => Ok(index | HARDENED_OFFSET),
+                _ => Err(anyhow!("invalid derivation path segment {} in {}", segment, path)),
+            }
+        })
+        .collect()
+}
+
+/// SLIP-0010 ed25519 private key of `path` (hardened indices) under `seed`.
+fn derive_ed25519(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let hmac_sha512 = |key: &[u8], data: &[&[u8]]| {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC takes keys of any length");
+        for part in data {
+            mac.update(part);
+        }
+        let out = mac.finalize().into_bytes();
+        let (key, chain_code) = out.split_at(32);
+        (<[u8; 32]>::try_from(key).unwrap(), <[u8; 32]>::try_from(chain_code).unwrap())
+    };
+    let (mut key, mut chain_code) = hmac_sha512(b"ed25519 seed", &[seed]);
+    for index in path {
+        (key, chain_code) = hmac_sha512(&chain_code, &[&[0], &key, &index.to_be_bytes()]);
+    }
+    key
+}
 
 /// Load a Solana keypair from a base58 string or a 64-byte JSON array in an environment variable.
 pub fn load_keypair_from_env(name: &str) -> Result<Keypair> {
@@ -42,6 +131,51 @@ mod tests {
         assert_eq!(load_keypair_from_string(&json).unwrap().pubkey(), keypair.pubkey());
     }
 
+    #[test]
+    fn mnemonic_matches_wallet_derivation() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                      abandon abandon about";
+        let keypair =
+            load_keypair(Source::Mnemonic(phrase.to_string(), DEFAULT_DERIVATION_PATH.to_string()))
+                .unwrap();
+        // First Phantom account of the BIP39 test phrase.
+        assert_eq!(keypair.pubkey().to_string(), "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+
+        // SLIP-0010 ed25519 test vector 1, chain m/0H.
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(derive_ed25519(&seed, &parse_derivation_path("m/0'").unwrap())),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert!(parse_derivation_path("44'/501'").is_err());
+        assert!(parse_derivation_path("m/44'/x'").is_err());
+        assert!(load_keypair_from_mnemonic("abandon about", DEFAULT_DERIVATION_PATH).is_err());
+    }
+
+    #[test]
+    fn mnemonic_rejects_bad_checksums_and_unknown_words() {
+        let bad_checksum = ["abandon"; 12].join(" ");
+        let error = load_keypair_from_mnemonic(&bad_checksum, DEFAULT_DERIVATION_PATH).unwrap_err();
+        assert!(!error.to_string().contains("abandon"));
+
+        let typo = format!("{} abandn", ["abandon"; 11].join(" "));
+        assert!(load_keypair_from_mnemonic(&typo, DEFAULT_DERIVATION_PATH).is_err());
+    }
+
+    #[test]
+    fn file_source_reads_cli_keypair_files() {
+        let keypair = Keypair::new();
+        let path = std::env::temp_dir().join(format!("keypair-{}.json", keypair.pubkey()));
+        std::fs::write(&path, serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap())
+            .unwrap();
+        let loaded = load_keypair(Source::File(path.clone()));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap().pubkey(), keypair.pubkey());
+
+        let error = load_keypair(Source::Env("KEYPAIR_TEST_UNSET_VAR".to_string())).unwrap_err();
+        assert!(error.to_string().contains("KEYPAIR_TEST_UNSET_VAR"));
+    }
+
     #[test]
     fn rejects_placeholders_and_wrong_lengths() {
         assert!(load_keypair_from_string("use_your_payer_keypair_here").is_err());
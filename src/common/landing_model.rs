@@ -0,0 +1,282 @@
+//! Landing-price learning: which CU price and tip the client's own transactions landed at.
+//!
+//! After a confirmed trade every lane's [`SwqosSubmitTiming`] says whether its transaction landed
+//! (the executor marks the one the confirmation poll saw). [`LandingFeeModel`] keeps those
+//! outcomes per UTC hour of day and, as a [`GasFeeModel`], floors each lane of its base
+//! [`GasFeeStrategy`] at the hour's landing price (the 80th percentile of landed submissions by
+//! default). Hours with too few landings keep the base fees.
+//!
+//! Lanes are floored at that price, so later landings never sit below it. Samples therefore
+//! decay with age (half-life of one day by default): the price drifts down after congestion
+//! clears instead of only ever ratcheting up.
+//!
+//! Install it with [`crate::TradingClient::with_landing_fee_model`], which also feeds it.
+//! 落地费率学习：按小时统计已落地交易的 CU 价格与小费分位数，反馈给费率策略。
+
+use crate::common::gas_fee_strategy::{Congestion, FeeParams, GasFeeModel, GasFeeStrategy};
+use crate::common::SwqosSubmitTiming;
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::factory::DexType;
+use parking_lot::Mutex;
+use solana_sdk::signature::Signature;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+pub const DEFAULT_LANDING_PERCENTILE: usize = 80;
+/// Landed submissions an hour needs before its landing price is used.
+pub const DEFAULT_MIN_LANDED_SAMPLES: usize = 20;
+/// Outcomes kept per hour of day; older ones are dropped first.
+pub const SAMPLES_PER_HOUR: usize = 512;
+/// Age at which a sample's CU price and tip count half.
+pub const DEFAULT_SAMPLE_HALF_LIFE: Duration = Duration::from_secs(24 * 3600);
+
+const HOURS: usize = 24;
+const MICROS_PER_HOUR: i64 = 3_600_000_000;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Landing price of one hour of day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LandingPrice {
+    /// CU price (micro-lamports) at the model's percentile of landed submissions.
+    pub cu_price: u64,
+    /// Tip (SOL) at the same percentile of landed submissions that carried one; 0.0 when none did.
+    /// Both are decayed by sample age.
+    pub tip: f64,
+    pub landed: usize,
+    /// Landed share of all recorded submissions of the hour.
+    pub landing_rate: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+    cu_price: u64,
+    tip_lamports: u64,
+    landed: bool,
+    recorded_us: i64,
+}
+
+/// Per-hour landing history doubling as a [`GasFeeModel`] over a base strategy.
+pub struct LandingFeeModel {
+    base: GasFeeStrategy,
+    percentile: usize,
+    min_landed: usize,
+    half_life_us: i64,
+    hours: Mutex<[VecDeque<Outcome>; HOURS]>,
+}
+
+impl LandingFeeModel {
+    pub fn new(base: GasFeeStrategy) -> Self {
+        Self {
+            base,
+            percentile: DEFAULT_LANDING_PERCENTILE,
+            min_landed: DEFAULT_MIN_LANDED_SAMPLES,
+            half_life_us: DEFAULT_SAMPLE_HALF_LIFE.as_micros() as i64,
+            hours: Mutex::new(std::array::from_fn(|_| VecDeque::new())),
+        }
+    }
+
+    /// Percentile (1..=100) of landed submissions used as the landing price.
+    pub fn with_percentile(mut self, percentile: usize) -> Self {
+        self.percentile = percentile.clamp(1, 100);
+        self
+    }
+
+    /// Landed submissions an hour needs before its landing price replaces the base fees.
+    pub fn with_min_landed(mut self, min_landed: usize) -> Self {
+        self.min_landed = min_landed.max(1);
+        self
+    }
+
+    /// Age at which a sample's CU price and tip count half; shorter follows fees down faster.
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life_us = (half_life.as_micros() as i64).max(1);
+        self
+    }
+
+    /// Record the lanes of a confirmed trade; lanes without an outcome (`landed: None`) are
+    /// skipped, and a signature reported by several lanes counts once.
+    pub fn record(&self, timings: &[SwqosSubmitTiming]) {
+        self.record_at(current_hour(), timings);
+    }
+
+    /// [`Self::record`] into `hour` (UTC, 0..24).
+    pub fn record_at(&self, hour: usize, timings: &[SwqosSubmitTiming]) {
+        self.record_at_us(hour, crate::common::clock::now_micros(), timings);
+    }
+
+    fn record_at_us(&self, hour: usize, recorded_us: i64, timings: &[SwqosSubmitTiming]) {
+        let mut hours = self.hours.lock();
+        let samples = &mut hours[hour % HOURS];
+        for (index, timing) in timings.iter().enumerate() {
+            let Some(landed) = timing.landed else { continue };
+            // Lanes that failed before signing all carry the default signature.
+            let signature = timing.signature;
+            if signature != Signature::default()
+                && timings[..index].iter().any(|earlier| earlier.signature == signature)
+            {
+                continue;
+            }
+            if samples.len() == SAMPLES_PER_HOUR {
+                samples.pop_front();
+            }
+            samples.push_back(Outcome {
+                cu_price: timing.cu_price,
+                tip_lamports: timing.tip_lamports,
+                landed,
+                recorded_us,
+            });
+        }
+    }
+
+    /// Landing price of `hour` (UTC, 0..24), `None` until it has enough landed submissions.
+    pub fn landing_price(&self, hour: usize) -> Option<LandingPrice> {
+        self.landing_price_at_us(hour, crate::common::clock::now_micros())
+    }
+
+    fn landing_price_at_us(&self, hour: usize, now_us: i64) -> Option<LandingPrice> {
+        let hours = self.hours.lock();
+        let samples = &hours[hour % HOURS];
+        let decay = |outcome: &Outcome, value: u64| {
+            let age = now_us.saturating_sub(outcome.recorded_us).max(0) as f64;
+            (value as f64 * 0.5f64.powf(age / self.half_life_us as f64)).round() as u64
+        };
+        let mut cu_prices: Vec<u64> =
+            samples.iter().filter(|o| o.landed).map(|o| decay(o, o.cu_price)).collect();
+        if cu_prices.len() < self.min_landed {
+            return None;
+        }
+        let mut tips: Vec<u64> = samples
+            .iter()
+            .filter(|o| o.landed && o.tip_lamports > 0)
+            .map(|o| decay(o, o.tip_lamports))
+            .collect();
+        cu_prices.sort_unstable();
+        tips.sort_unstable();
+        let index = |len: usize| (len - 1) * self.percentile / 100;
+        Some(LandingPrice {
+            cu_price: cu_prices[index(cu_prices.len())],
+            tip: if tips.is_empty() {
+                0.0
+            } else {
+                tips[index(tips.len())] as f64 / LAMPORTS_PER_SOL
+            },
+            landed: cu_prices.len(),
+            landing_rate: cu_prices.len() as f64 / samples.len() as f64,
+        })
+    }
+
+    /// Landing price of the current UTC hour.
+    pub fn current_landing_price(&self) -> Option<LandingPrice> {
+        self.landing_price(current_hour())
+    }
+}
+
+impl GasFeeModel for LandingFeeModel {
+    /// Base fees with every lane's CU price, and every tipped lane's tip, raised to the current
+    /// hour's landing price. The Default (RPC) lane carries no tip.
+    fn fees_for(&self, dex: DexType, trade_type: TradeType, congestion: &Congestion) -> FeeParams {
+        let mut fees = self.base.fees_for(dex, trade_type, congestion);
+        if let Some(price) = self.current_landing_price() {
+            for (swqos_type, _, value) in &mut fees.lanes {
+                value.cu_price = value.cu_price.max(price.cu_price);
+                if *swqos_type != SwqosType::Default {
+                    value.tip = value.tip.max(price.tip);
+                }
+            }
+        }
+        fees
+    }
+}
+
+#[inline]
+fn current_hour() -> usize {
+    (crate::common::clock::now_micros() / MICROS_PER_HOUR).rem_euclid(HOURS as i64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::GasFeeStrategyType;
+
+    fn lane(cu_price: u64, tip_lamports: u64, landed: Option<bool>) -> SwqosSubmitTiming {
+        SwqosSubmitTiming {
+            swqos_type: SwqosType::Jito,
+            strategy_type: GasFeeStrategyType::Normal,
+            submit_done_us: 0,
+            signature: Signature::default(),
+            cu_price,
+            tip_lamports,
+            landed,
+            landed_slot: None,
+            block_time: None,
+            event_to_land_slots: None,
+            raw_transaction: None,
+        }
+    }
+
+    #[test]
+    fn landing_price_is_the_percentile_of_landed_lanes() {
+        let model = LandingFeeModel::new(GasFeeStrategy::new()).with_min_landed(10);
+        let landed: Vec<_> = (1..=10).map(|i| lane(i * 100, i * 1_000_000, Some(true))).collect();
+        model.record_at(3, &landed[..9]);
+        assert_eq!(model.landing_price(3), None);
+
+        model.record_at(3, &landed[9..]);
+        model.record_at(3, &[lane(50_000, 500_000_000, Some(false)), lane(1, 1, None)]);
+        let price = model.landing_price(27).unwrap();
+        assert_eq!(price.cu_price, 800);
+        assert_eq!(price.tip, 0.008);
+        assert_eq!(price.landed, 10);
+        assert!((price.landing_rate - 10.0 / 11.0).abs() < 1e-9);
+        assert_eq!(model.landing_price(4), None);
+    }
+
+    #[test]
+    fn a_signature_confirmed_by_several_lanes_counts_once() {
+        let model = LandingFeeModel::new(GasFeeStrategy::new()).with_min_landed(1);
+        let mut first = lane(1_000, 1_000_000, Some(true));
+        first.signature = Signature::new_unique();
+        let mut second = first.clone();
+        second.swqos_type = SwqosType::Helius;
+        model.record_at(5, &[first, second, lane(2_000, 0, Some(false))]);
+
+        let price = model.landing_price(5).unwrap();
+        assert_eq!(price.landed, 1);
+        assert!((price.landing_rate - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fees_are_floored_at_the_landing_price() {
+        let base = GasFeeStrategy::new();
+        base.set_global_fee_strategy(200_000, 200_000, 1_000, 1_000, 0.001, 0.001);
+        let model = LandingFeeModel::new(base).with_min_landed(1);
+        for hour in 0..HOURS {
+            model.record_at(hour, &[lane(5_000, 2_000_000, Some(true))]);
+        }
+
+        let fees = model.fees_for(DexType::PumpSwap, TradeType::Buy, &Congestion::default());
+        assert!(!fees.lanes.is_empty());
+        for (swqos_type, _, value) in &fees.lanes {
+            assert_eq!(value.cu_price, 5_000);
+            let tip = if *swqos_type == SwqosType::Default { 0.0 } else { 0.002 };
+            assert_eq!(value.tip, tip);
+        }
+    }
+
+    #[test]
+    fn landing_price_decays_so_the_floor_can_come_down() {
+        let day_us = DEFAULT_SAMPLE_HALF_LIFE.as_micros() as i64;
+        let model = LandingFeeModel::new(GasFeeStrategy::new()).with_min_landed(1);
+        model.record_at_us(7, 0, &[lane(8_000, 4_000_000, Some(true))]);
+
+        let fresh = model.landing_price_at_us(7, 0).unwrap();
+        assert_eq!((fresh.cu_price, fresh.tip), (8_000, 0.004));
+        let day_old = model.landing_price_at_us(7, day_us).unwrap();
+        assert_eq!((day_old.cu_price, day_old.tip), (4_000, 0.002));
+
+        // Cheaper landings after congestion clears pull the price further down.
+        model.record_at_us(7, day_us, &[lane(1_000, 1_000_000, Some(true))]);
+        let price = model.with_percentile(50).landing_price_at_us(7, 2 * day_us).unwrap();
+        assert_eq!(price.cu_price, 500);
+    }
+}
@@ -0,0 +1,292 @@
+//! End-to-end latency budget for one trade.
+//!
+//! The clock starts at the trade's `grpc_recv_us` (or when the executor starts when unset) and
+//! every stage — build, sign, serialize, submit — checks the remaining budget when it ends. Over
+//! budget, [`LatencyBudgetAction::Abort`] stops before the next send while
+//! [`LatencyBudgetAction::Warn`] only logs; either way [`LatencyBudget::report`] shows where the
+//! time went. Sign/serialize/submit run once per SWQOS lane, so they are reported per lane.
+//! 交易端到端延迟预算：各阶段结束时检查剩余预算，超时可中止或仅告警，并记录耗时分布。
+
+use crate::swqos::{common::TradeError, SwqosType};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// [`TradeError::code`] for trades stopped because they ran past their [`LatencyBudget`].
+pub const LATENCY_BUDGET_ERROR_CODE: u32 = 1004;
+
+/// Trade stage a [`LatencyBudget`] is checked after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LatencyStage {
+    /// Instruction building and middleware (once per trade).
+    Build,
+    /// Transaction assembly and signing (per lane).
+    Sign,
+    /// Wire serialization and encoding (per lane).
+    Serialize,
+    /// SWQOS submit round trip (per lane). Already sent, so never aborted.
+    Submit,
+}
+
+impl LatencyStage {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            LatencyStage::Build => "build",
+            LatencyStage::Sign => "sign",
+            LatencyStage::Serialize => "serialize",
+            LatencyStage::Submit => "submit",
+        }
+    }
+}
+
+/// What to do when a stage ends past its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LatencyBudgetAction {
+    /// Log a warning and keep going.
+    #[default]
+    Warn,
+    /// Stop before sending; the trade fails with a [`LATENCY_BUDGET_ERROR_CODE`] error.
+    Abort,
+}
+
+/// Per-lane stage durations in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaneLatency {
+    pub swqos_type: SwqosType,
+    pub sign_us: i64,
+    pub serialize_us: i64,
+    pub submit_us: i64,
+    /// Time from budget start until this lane finished.
+    pub elapsed_us: i64,
+    /// Stage this lane was aborted after, if any.
+    pub aborted_after: Option<LatencyStage>,
+}
+
+/// Where the time of one trade went.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyReport {
+    pub budget_us: i64,
+    /// Time from budget start (event receive or executor start) until building began.
+    pub queued_us: i64,
+    pub build_us: i64,
+    /// Lanes in completion order; lanes still in flight when the trade returned appear later.
+    pub lanes: Vec<LaneLatency>,
+    /// First stage that ended past its deadline.
+    pub exceeded: Option<LatencyStage>,
+}
+
+/// Latency budget attached to a trade. Clones share one report, so keep a clone to read
+/// [`Self::report`] after the trade returns.
+#[derive(Debug, Clone)]
+pub struct LatencyBudget {
+    pub total: Duration,
+    pub action: LatencyBudgetAction,
+    stage_limits: [Option<Duration>; LatencyStage::COUNT],
+    state: Arc<Mutex<BudgetState>>,
+}
+
+#[derive(Debug, Default)]
+struct BudgetState {
+    start_us: i64,
+    report: LatencyReport,
+}
+
+impl LatencyBudget {
+    pub fn new(total: Duration, action: LatencyBudgetAction) -> Self {
+        Self {
+            total,
+            action,
+            stage_limits: [None; LatencyStage::COUNT],
+            state: Arc::new(Mutex::new(BudgetState::default())),
+        }
+    }
+
+    /// Also cap how long `stage` alone may take (per lane for lane stages).
+    pub fn with_stage_limit(mut self, stage: LatencyStage, limit: Duration) -> Self {
+        self.stage_limits[stage.index()] = Some(limit);
+        self
+    }
+
+    /// Breakdown of the most recent trade run with this budget.
+    pub fn report(&self) -> LatencyReport {
+        self.state.lock().report.clone()
+    }
+
+    #[inline]
+    fn total_us(&self) -> i64 {
+        duration_us(self.total)
+    }
+
+    /// Reset the report and start the clock at `start_us`.
+    pub(crate) fn start(&self, start_us: i64) {
+        let mut state = self.state.lock();
+        state.start_us = start_us;
+        state.report = LatencyReport { budget_us: self.total_us(), ..Default::default() };
+    }
+
+    pub(crate) fn start_us(&self) -> i64 {
+        self.state.lock().start_us
+    }
+
+    /// Record the build stage; `build_start_us` is when instruction building began.
+    pub(crate) fn end_build(&self, build_start_us: i64, now_us: i64) -> Result<(), TradeError> {
+        let start_us = {
+            let mut state = self.state.lock();
+            state.report.queued_us = build_start_us - state.start_us;
+            state.report.build_us = now_us - build_start_us;
+            state.start_us
+        };
+        self.check(LatencyStage::Build, None, start_us, build_start_us, now_us)
+    }
+
+    /// Clock for one SWQOS lane.
+    pub(crate) fn lane(&self, swqos_type: SwqosType) -> LaneClock<'_> {
+        let now_us = crate::common::clock::now_micros();
+        LaneClock {
+            budget: self,
+            start_us: self.start_us(),
+            stage_start_us: now_us,
+            lane: LaneLatency {
+                swqos_type,
+                sign_us: 0,
+                serialize_us: 0,
+                submit_us: 0,
+                elapsed_us: 0,
+                aborted_after: None,
+            },
+        }
+    }
+
+    fn check(
+        &self,
+        stage: LatencyStage,
+        swqos_type: Option<SwqosType>,
+        start_us: i64,
+        stage_start_us: i64,
+        now_us: i64,
+    ) -> Result<(), TradeError> {
+        let elapsed_us = now_us - start_us;
+        let over_total = elapsed_us > self.total_us();
+        let over_stage = self.stage_limits[stage.index()]
+            .is_some_and(|limit| now_us - stage_start_us > duration_us(limit));
+        if !over_total && !over_stage {
+            return Ok(());
+        }
+        let first = {
+            let mut state = self.state.lock();
+            let first = state.report.exceeded.is_none();
+            state.report.exceeded.get_or_insert(stage);
+            first
+        };
+        let message = format!(
+            "LatencyBudgetExceeded: {} stage{} ended at {:.3} ms (budget {:.3} ms, stage took {:.3} ms)",
+            stage.as_str(),
+            swqos_type.map(|t| format!(" [{:?}]", t)).unwrap_or_default(),
+            elapsed_us as f64 / 1000.0,
+            self.total_us() as f64 / 1000.0,
+            (now_us - stage_start_us) as f64 / 1000.0,
+        );
+        if self.action == LatencyBudgetAction::Abort && stage != LatencyStage::Submit {
+            return Err(TradeError { code: LATENCY_BUDGET_ERROR_CODE, message, instruction: None });
+        }
+        if first && crate::common::sdk_log::sdk_log_enabled() {
+            tracing::warn!(target: "sol_trade_sdk", "{}", message);
+        }
+        Ok(())
+    }
+}
+
+/// Stage clock for one SWQOS lane; the lane is added to the report on [`Self::finish`].
+pub(crate) struct LaneClock<'a> {
+    budget: &'a LatencyBudget,
+    start_us: i64,
+    stage_start_us: i64,
+    lane: LaneLatency,
+}
+
+impl LaneClock<'_> {
+    /// End `stage` now and start the next one. Err means this lane must not send.
+    pub(crate) fn end(&mut self, stage: LatencyStage) -> Result<(), TradeError> {
+        let now_us = crate::common::clock::now_micros();
+        let took_us = now_us - self.stage_start_us;
+        match stage {
+            LatencyStage::Build => {}
+            LatencyStage::Sign => self.lane.sign_us = took_us,
+            LatencyStage::Serialize => self.lane.serialize_us = took_us,
+            LatencyStage::Submit => self.lane.submit_us = took_us,
+        }
+        let result = self.budget.check(
+            stage,
+            Some(self.lane.swqos_type),
+            self.start_us,
+            self.stage_start_us,
+            now_us,
+        );
+        self.stage_start_us = now_us;
+        self.lane.elapsed_us = now_us - self.start_us;
+        if result.is_err() {
+            self.lane.aborted_after = Some(stage);
+        }
+        result
+    }
+
+    pub(crate) fn finish(self) {
+        self.budget.state.lock().report.lanes.push(self.lane);
+    }
+}
+
+#[inline]
+fn duration_us(duration: Duration) -> i64 {
+    duration.as_micros().min(i64::MAX as u128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abort_stops_lane_stages_but_not_submit() {
+        let budget = LatencyBudget::new(Duration::from_millis(5), LatencyBudgetAction::Abort);
+        let now = crate::common::clock::now_micros();
+        budget.start(now - 10_000);
+
+        let err = budget.end_build(now - 9_000, now).unwrap_err();
+        assert_eq!(err.code, LATENCY_BUDGET_ERROR_CODE);
+        assert!(err.message.contains("build stage"));
+
+        let mut lane = budget.lane(SwqosType::Jito);
+        assert!(lane.end(LatencyStage::Sign).is_err());
+        assert!(lane.end(LatencyStage::Submit).is_ok());
+        lane.finish();
+
+        let report = budget.report();
+        assert_eq!(report.budget_us, 5_000);
+        assert_eq!(report.queued_us, 1_000);
+        assert_eq!(report.build_us, 9_000);
+        assert_eq!(report.exceeded, Some(LatencyStage::Build));
+        assert_eq!(report.lanes.len(), 1);
+        assert_eq!(report.lanes[0].aborted_after, Some(LatencyStage::Sign));
+    }
+
+    #[test]
+    fn stage_limit_applies_within_total_and_warn_never_fails() {
+        let budget = LatencyBudget::new(Duration::from_secs(1), LatencyBudgetAction::Warn)
+            .with_stage_limit(LatencyStage::Build, Duration::from_millis(1));
+        let now = crate::common::clock::now_micros();
+        budget.start(now - 3_000);
+
+        assert!(budget.end_build(now - 2_000, now).is_ok());
+        assert_eq!(budget.report().exceeded, Some(LatencyStage::Build));
+
+        let clone = budget.clone();
+        clone.start(now);
+        assert!(clone.end_build(now, now + 100).is_ok());
+        assert_eq!(budget.report().exceeded, None);
+    }
+}
@@ -0,0 +1,325 @@
+//! Per-pool market state built from swap events: last price, rolling volume and OHLC candles.
+//! 行情缓存：按池子消费成交事件，维护最新价、滚动成交量与 K 线，供策略直接查询。
+//!
+//! Feed it from a gRPC/shred event stream via [`MarketDataCache::on_swap`], or from confirmed
+//! fills via [`MarketDataCache::on_fill`]. Time is taken from the events, so replays and
+//! backtests produce the same candles as live data.
+
+use crate::swqos::TradeType;
+use crate::trading::common::fill::Fill;
+use dashmap::DashMap;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// One swap on a pool. Amounts are raw base units; `price` is quote per token in raw units
+/// (scale by `10^(token_decimals - quote_decimals)` for a UI price).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwapTick {
+    pub pool: Pubkey,
+    pub side: TradeType,
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    /// Event time in milliseconds (block time or receive time).
+    pub timestamp_ms: i64,
+}
+
+impl SwapTick {
+    #[inline]
+    pub fn price(&self) -> Option<f64> {
+        (self.token_amount > 0).then(|| self.quote_amount as f64 / self.token_amount as f64)
+    }
+
+    /// Tick from a decoded [`Fill`]; `None` without a block time or for non-swap sides.
+    pub fn from_fill(fill: &Fill) -> Option<Self> {
+        let (token_amount, quote_amount) = match fill.side {
+            TradeType::Buy | TradeType::CreateAndBuy => (fill.amount_out, fill.amount_in),
+            TradeType::Sell => (fill.amount_in, fill.amount_out),
+            TradeType::Create => return None,
+        };
+        Some(Self {
+            pool: fill.pool,
+            side: fill.side,
+            token_amount,
+            quote_amount,
+            timestamp_ms: fill.block_time? * 1_000,
+        })
+    }
+}
+
+/// OHLC candle; `open_time_ms` is aligned to the cache interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub open_time_ms: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub token_volume: u64,
+    pub quote_volume: u64,
+    pub trades: u32,
+}
+
+impl Candle {
+    fn new(open_time_ms: i64, price: f64) -> Self {
+        Self {
+            open_time_ms,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            token_volume: 0,
+            quote_volume: 0,
+            trades: 0,
+        }
+    }
+
+    fn apply(&mut self, price: f64, tick: &SwapTick) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.token_volume = self.token_volume.saturating_add(tick.token_amount);
+        self.quote_volume = self.quote_volume.saturating_add(tick.quote_amount);
+        self.trades += 1;
+    }
+}
+
+/// Rolling volume of one pool over the cache's volume window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RollingVolume {
+    pub buy_quote: u64,
+    pub sell_quote: u64,
+    pub trades: u32,
+}
+
+impl RollingVolume {
+    #[inline]
+    pub fn total_quote(&self) -> u64 {
+        self.buy_quote.saturating_add(self.sell_quote)
+    }
+}
+
+#[derive(Default)]
+struct PoolMarket {
+    last_price: Option<f64>,
+    last_timestamp_ms: i64,
+    /// Oldest first; the last entry is the candle currently forming.
+    candles: VecDeque<Candle>,
+    /// Ticks inside the volume window, ordered by timestamp.
+    window: VecDeque<(i64, TradeType, u64)>,
+    volume: RollingVolume,
+}
+
+/// Concurrent per-pool price cache. Share it behind an `Arc` between the event consumer and
+/// strategies.
+pub struct MarketDataCache {
+    interval_ms: i64,
+    max_candles: usize,
+    volume_window_ms: i64,
+    pools: DashMap<Pubkey, PoolMarket>,
+}
+
+impl MarketDataCache {
+    /// `candle_interval` sets the OHLC bucket size, `max_candles` how many are kept per pool and
+    /// `volume_window` the span of [`Self::volume`].
+    pub fn new(candle_interval: Duration, max_candles: usize, volume_window: Duration) -> Self {
+        Self {
+            interval_ms: (candle_interval.as_millis() as i64).max(1),
+            max_candles: max_candles.max(1),
+            volume_window_ms: volume_window.as_millis() as i64,
+            pools: DashMap::new(),
+        }
+    }
+
+    /// Apply a swap. Ticks older than the pool's current candle only update volume, and ticks
+    /// already outside the volume window are not counted at all.
+    pub fn on_swap(&self, tick: SwapTick) {
+        let Some(price) = tick.price() else { return };
+        let mut market = self.pools.entry(tick.pool).or_default();
+        let market = &mut *market;
+
+        let open_time_ms = tick.timestamp_ms - tick.timestamp_ms.rem_euclid(self.interval_ms);
+        match market.candles.back().map(|candle| candle.open_time_ms) {
+            Some(current) if current == open_time_ms => {
+                if let Some(candle) = market.candles.back_mut() {
+                    candle.apply(price, &tick);
+                }
+            }
+            Some(current) if current > open_time_ms => {}
+            _ => {
+                let mut candle = Candle::new(open_time_ms, price);
+                candle.apply(price, &tick);
+                market.candles.push_back(candle);
+                if market.candles.len() > self.max_candles {
+                    market.candles.pop_front();
+                }
+            }
+        }
+        if tick.timestamp_ms >= market.last_timestamp_ms {
+            market.last_price = Some(price);
+            market.last_timestamp_ms = tick.timestamp_ms;
+        }
+
+        let cutoff = market.last_timestamp_ms - self.volume_window_ms;
+        if tick.timestamp_ms > cutoff {
+            // Late ticks go in timestamp order so expiry from the front stays exact.
+            let at = market.window.partition_point(|&(ts, _, _)| ts <= tick.timestamp_ms);
+            market.window.insert(at, (tick.timestamp_ms, tick.side, tick.quote_amount));
+            Self::add_volume(&mut market.volume, tick.side, tick.quote_amount, true);
+        }
+        while let Some(&(ts, side, quote)) = market.window.front() {
+            if ts > cutoff {
+                break;
+            }
+            market.window.pop_front();
+            Self::add_volume(&mut market.volume, side, quote, false);
+        }
+    }
+
+    /// Apply a confirmed [`Fill`]; ignored when it has no block time.
+    pub fn on_fill(&self, fill: &Fill) {
+        if let Some(tick) = SwapTick::from_fill(fill) {
+            self.on_swap(tick);
+        }
+    }
+
+    fn add_volume(volume: &mut RollingVolume, side: TradeType, quote: u64, add: bool) {
+        let bucket = match side {
+            TradeType::Sell => &mut volume.sell_quote,
+            _ => &mut volume.buy_quote,
+        };
+        if add {
+            *bucket = bucket.saturating_add(quote);
+            volume.trades += 1;
+        } else {
+            *bucket = bucket.saturating_sub(quote);
+            volume.trades = volume.trades.saturating_sub(1);
+        }
+    }
+
+    pub fn last_price(&self, pool: &Pubkey) -> Option<f64> {
+        self.pools.get(pool).and_then(|market| market.last_price)
+    }
+
+    /// Volume within the window ending at the pool's latest tick.
+    pub fn volume(&self, pool: &Pubkey) -> RollingVolume {
+        self.pools.get(pool).map(|market| market.volume).unwrap_or_default()
+    }
+
+    /// Candles oldest first; the last one may still be forming.
+    pub fn candles(&self, pool: &Pubkey) -> Vec<Candle> {
+        self.pools
+            .get(pool)
+            .map(|market| market.candles.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn current_candle(&self, pool: &Pubkey) -> Option<Candle> {
+        self.pools.get(pool).and_then(|market| market.candles.back().copied())
+    }
+
+    /// Drop all state for `pool` (e.g. after migration).
+    pub fn remove(&self, pool: &Pubkey) {
+        self.pools.remove(pool);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(pool: Pubkey, side: TradeType, token: u64, quote: u64, ts: i64) -> SwapTick {
+        SwapTick { pool, side, token_amount: token, quote_amount: quote, timestamp_ms: ts }
+    }
+
+    #[test]
+    fn builds_ohlc_candles_per_interval() {
+        let pool = Pubkey::new_unique();
+        let cache = MarketDataCache::new(Duration::from_secs(60), 10, Duration::from_secs(300));
+
+        cache.on_swap(tick(pool, TradeType::Buy, 100, 200, 0));
+        cache.on_swap(tick(pool, TradeType::Buy, 100, 500, 10_000));
+        cache.on_swap(tick(pool, TradeType::Sell, 100, 100, 20_000));
+        cache.on_swap(tick(pool, TradeType::Buy, 100, 300, 61_000));
+
+        let candles = cache.candles(&pool);
+        assert_eq!(candles.len(), 2);
+        assert_eq!(
+            candles[0],
+            Candle {
+                open_time_ms: 0,
+                open: 2.0,
+                high: 5.0,
+                low: 1.0,
+                close: 1.0,
+                token_volume: 300,
+                quote_volume: 800,
+                trades: 3,
+            }
+        );
+        assert_eq!(candles[1].open_time_ms, 60_000);
+        assert_eq!(cache.last_price(&pool), Some(3.0));
+    }
+
+    #[test]
+    fn rolling_volume_expires_old_ticks() {
+        let pool = Pubkey::new_unique();
+        let cache = MarketDataCache::new(Duration::from_secs(60), 10, Duration::from_secs(30));
+
+        cache.on_swap(tick(pool, TradeType::Buy, 10, 1_000, 0));
+        cache.on_swap(tick(pool, TradeType::Sell, 10, 400, 20_000));
+        assert_eq!(cache.volume(&pool).total_quote(), 1_400);
+
+        cache.on_swap(tick(pool, TradeType::Buy, 10, 50, 35_000));
+        assert_eq!(
+            cache.volume(&pool),
+            RollingVolume { buy_quote: 50, sell_quote: 400, trades: 2 }
+        );
+    }
+
+    #[test]
+    fn out_of_order_tick_expires_by_its_own_timestamp() {
+        let pool = Pubkey::new_unique();
+        let cache = MarketDataCache::new(Duration::from_secs(60), 10, Duration::from_secs(30));
+
+        cache.on_swap(tick(pool, TradeType::Buy, 10, 1_000, 20_000));
+        cache.on_swap(tick(pool, TradeType::Sell, 10, 400, 5_000));
+        assert_eq!(cache.volume(&pool).total_quote(), 1_400);
+
+        // 5s drops out at 35s while the newer 20s tick stays.
+        cache.on_swap(tick(pool, TradeType::Buy, 10, 50, 35_000));
+        assert_eq!(
+            cache.volume(&pool),
+            RollingVolume { buy_quote: 1_050, sell_quote: 0, trades: 2 }
+        );
+    }
+
+    #[test]
+    fn tick_older_than_the_window_is_not_counted() {
+        let pool = Pubkey::new_unique();
+        let cache = MarketDataCache::new(Duration::from_secs(60), 10, Duration::from_secs(30));
+
+        cache.on_swap(tick(pool, TradeType::Buy, 10, 1_000, 100_000));
+        cache.on_swap(tick(pool, TradeType::Sell, 10, 400, 60_000));
+        cache.on_swap(tick(pool, TradeType::Sell, 10, 300, 70_000));
+
+        assert_eq!(
+            cache.volume(&pool),
+            RollingVolume { buy_quote: 1_000, sell_quote: 0, trades: 1 }
+        );
+        assert_eq!(cache.last_price(&pool), Some(100.0));
+    }
+
+    #[test]
+    fn keeps_at_most_max_candles() {
+        let pool = Pubkey::new_unique();
+        let cache = MarketDataCache::new(Duration::from_secs(1), 2, Duration::from_secs(1));
+
+        for i in 0..5 {
+            cache.on_swap(tick(pool, TradeType::Buy, 1, i + 1, i as i64 * 1_000));
+        }
+        let candles = cache.candles(&pool);
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[1].close, 5.0);
+    }
+}
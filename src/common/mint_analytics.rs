@@ -0,0 +1,539 @@
+//! Per-mint snapshot for sniper/safety filters: recent volume, holder count, concentration, LP share,
+//! and bundled-launch detection from how the early buyers were funded.
+//! 代币分析快照：5 分钟/1 小时成交量、持有人数、前十持仓占比与流动性池占比，以及捆绑发射检测。
+
+use crate::common::market_data::{Candle, MarketDataCache};
+use crate::common::types::SolanaRpcClient;
+use crate::instruction::utils::{
+    bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_cpmm,
+};
+use crate::swqos::TradeType;
+use crate::trading::common::fill::{parse_trade_from_signature, Fill};
+use anyhow::anyhow;
+use futures::StreamExt;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_filter::Memcmp;
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::RpcFilterType;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const FIVE_MINUTES_MS: i64 = 5 * 60 * 1_000;
+const ONE_HOUR_MS: i64 = 60 * 60 * 1_000;
+/// SPL Token account size; Token-2022 accounts with extensions are larger.
+const TOKEN_ACCOUNT_LEN: u64 = 165;
+/// Largest holders whose owner is checked against DEX programs for the LP share.
+const LP_CANDIDATES: usize = 20;
+const RPC_PARSE_CONCURRENCY: usize = 8;
+/// Oldest transactions of a wallet searched for the one that funded it.
+const FUNDING_TXS_CHECKED: usize = 3;
+/// Newest signatures fetched per wallet; a wallet with more history is not a fresh bundle wallet,
+/// and its oldest page is not worth walking back to.
+const FUNDING_SIGNATURE_LIMIT: usize = 100;
+
+/// Pool authorities whose token accounts hold pool liquidity rather than a trader's position.
+const POOL_AUTHORITIES: [Pubkey; 4] = [
+    raydium_amm_v4::accounts::AUTHORITY,
+    raydium_cpmm::accounts::AUTHORITY,
+    meteora_damm_v2::accounts::AUTHORITY,
+    bonk::accounts::AUTHORITY,
+];
+
+/// Programs whose accounts (PumpFun bonding curve, PumpSwap pool) own pool token accounts directly.
+const POOL_OWNER_PROGRAMS: [Pubkey; 2] =
+    [pumpfun::accounts::PUMPFUN, pumpswap::accounts::AMM_PROGRAM];
+
+/// Where [`analyze_mint`] takes volume from.
+pub enum VolumeSource<'a> {
+    /// Candles of the mint's pool in a [`MarketDataCache`]; resolution is the cache interval.
+    Cache { cache: &'a MarketDataCache, pool: Pubkey },
+    /// Parse up to `max_transactions` of the mint's signatures from the last hour.
+    Rpc { max_transactions: usize },
+}
+
+/// Snapshot returned by [`analyze_mint`]. Volumes are quote base units; shares are 0.0..=1.0 of supply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MintAnalytics {
+    pub mint: Pubkey,
+    pub supply: u64,
+    pub volume_5m: u64,
+    pub volume_1h: u64,
+    /// Distinct owners with a non-zero balance (pool accounts included).
+    pub holders: usize,
+    /// Share of the ten largest owners, excluding pool liquidity.
+    pub top10_share: f64,
+    /// Share held by DEX pools / bonding curves.
+    pub lp_share: f64,
+}
+
+#[inline]
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+/// Build a [`MintAnalytics`] for `mint` from RPC holder data plus `volume`.
+///
+/// Holder data comes from `getProgramAccounts` on the mint's token program, which some RPC
+/// providers restrict; it is cheap for fresh launches and expensive for widely held tokens.
+pub async fn analyze_mint(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    volume: VolumeSource<'_>,
+) -> Result<MintAnalytics, anyhow::Error> {
+    let mint_account = rpc.get_account(mint).await?;
+    // Mint layout: COption<authority> (36 bytes), then supply u64.
+    let supply = mint_account
+        .data
+        .get(36..44)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("Account {} is not a token mint", mint))?;
+
+    let balances = fetch_owner_balances(rpc, &mint_account.owner, mint).await?;
+    let lp_owners = detect_pool_owners(rpc, &balances).await?;
+    let (holders, top10_share, lp_share) = summarize_holders(&balances, supply, &lp_owners);
+
+    let now = now_ms();
+    let (volume_5m, volume_1h) = match volume {
+        VolumeSource::Cache { cache, pool } => {
+            let candles = cache.candles(&pool);
+            (
+                volume_from_candles(&candles, now, FIVE_MINUTES_MS),
+                volume_from_candles(&candles, now, ONE_HOUR_MS),
+            )
+        }
+        VolumeSource::Rpc { max_transactions } => {
+            let fills = fetch_recent_fills(rpc, mint, now, max_transactions).await?;
+            (
+                volume_from_fills(&fills, mint, now, FIVE_MINUTES_MS),
+                volume_from_fills(&fills, mint, now, ONE_HOUR_MS),
+            )
+        }
+    };
+
+    Ok(MintAnalytics { mint: *mint, supply, volume_5m, volume_1h, holders, top10_share, lp_share })
+}
+
+/// Non-zero balances of `mint` summed per owner.
+async fn fetch_owner_balances(
+    rpc: &SolanaRpcClient,
+    token_program: &Pubkey,
+    mint: &Pubkey,
+) -> Result<HashMap<Pubkey, u64>, anyhow::Error> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref()))];
+    if *token_program == crate::constants::TOKEN_PROGRAM {
+        filters.push(RpcFilterType::DataSize(TOKEN_ACCOUNT_LEN));
+    }
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            // owner (32) + amount (8)
+            data_slice: Some(UiDataSliceConfig { offset: 32, length: 40 }),
+            commitment: None,
+            min_context_slot: None,
+        },
+        with_context: None,
+        sort_results: None,
+    };
+    #[allow(deprecated)]
+    let accounts = rpc.get_program_accounts_with_config(token_program, config).await?;
+    let mut balances: HashMap<Pubkey, u64> = HashMap::new();
+    for (_, account) in accounts {
+        if account.data.len() < 40 {
+            continue;
+        }
+        let owner = Pubkey::try_from(&account.data[..32]).unwrap_or_default();
+        let amount = u64::from_le_bytes(account.data[32..40].try_into().unwrap());
+        if amount > 0 {
+            let total = balances.entry(owner).or_default();
+            *total = total.saturating_add(amount);
+        }
+    }
+    Ok(balances)
+}
+
+/// Owners among the largest holders that are pool authorities or accounts of pool programs.
+async fn detect_pool_owners(
+    rpc: &SolanaRpcClient,
+    balances: &HashMap<Pubkey, u64>,
+) -> Result<HashSet<Pubkey>, anyhow::Error> {
+    let mut largest: Vec<(&Pubkey, &u64)> = balances.iter().collect();
+    largest.sort_unstable_by(|a, b| b.1.cmp(a.1));
+    let candidates: Vec<Pubkey> =
+        largest.into_iter().take(LP_CANDIDATES).map(|(owner, _)| *owner).collect();
+
+    let mut pool_owners: HashSet<Pubkey> =
+        candidates.iter().filter(|owner| POOL_AUTHORITIES.contains(owner)).copied().collect();
+    let accounts = rpc.get_multiple_accounts(&candidates).await?;
+    for (owner, account) in candidates.iter().zip(accounts) {
+        if account.is_some_and(|account| POOL_OWNER_PROGRAMS.contains(&account.owner)) {
+            pool_owners.insert(*owner);
+        }
+    }
+    Ok(pool_owners)
+}
+
+/// `(holders, top10_share, lp_share)` from per-owner balances.
+pub fn summarize_holders(
+    balances: &HashMap<Pubkey, u64>,
+    supply: u64,
+    lp_owners: &HashSet<Pubkey>,
+) -> (usize, f64, f64) {
+    if supply == 0 {
+        return (balances.len(), 0.0, 0.0);
+    }
+    let lp: u64 = lp_owners.iter().filter_map(|owner| balances.get(owner)).sum();
+    let mut holders: Vec<u64> = balances
+        .iter()
+        .filter(|(owner, _)| !lp_owners.contains(owner))
+        .map(|(_, amount)| *amount)
+        .collect();
+    holders.sort_unstable_by(|a, b| b.cmp(a));
+    let top10: u64 = holders.iter().take(10).sum();
+    (balances.len(), top10 as f64 / supply as f64, lp as f64 / supply as f64)
+}
+
+/// Quote volume of candles opened within `window_ms` before `now_ms`.
+pub fn volume_from_candles(candles: &[Candle], now_ms: i64, window_ms: i64) -> u64 {
+    candles
+        .iter()
+        .filter(|candle| candle.open_time_ms >= now_ms - window_ms)
+        .map(|candle| candle.quote_volume)
+        .sum()
+}
+
+/// Quote volume of `mint` fills with a block time within `window_ms` before `now_ms`.
+pub fn volume_from_fills(fills: &[Fill], mint: &Pubkey, now_ms: i64, window_ms: i64) -> u64 {
+    fills
+        .iter()
+        .filter(|fill| fill.mint == *mint)
+        .filter(|fill| fill.block_time.is_some_and(|t| t * 1_000 >= now_ms - window_ms))
+        .map(|fill| match fill.side {
+            TradeType::Sell => fill.amount_out,
+            _ => fill.amount_in,
+        })
+        .sum()
+}
+
+async fn fetch_recent_fills(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    now_ms: i64,
+    max_transactions: usize,
+) -> Result<Vec<Fill>, anyhow::Error> {
+    let since_secs = (now_ms - ONE_HOUR_MS) / 1_000;
+    let signatures: Vec<Signature> = rpc
+        .get_signatures_for_address(mint)
+        .await?
+        .into_iter()
+        .filter(|status| status.err.is_none())
+        .take_while(|status| !status.block_time.is_some_and(|t| t < since_secs))
+        .filter_map(|status| Signature::from_str(&status.signature).ok())
+        .take(max_transactions)
+        .collect();
+    // Transactions that are not supported swaps (transfers, ATA creation) are skipped.
+    Ok(futures::stream::iter(signatures)
+        .map(|signature| async move { parse_trade_from_signature(rpc, &signature).await })
+        .buffer_unordered(RPC_PARSE_CONCURRENCY)
+        .filter_map(|fill| async move { fill.ok() })
+        .collect()
+        .await)
+}
+
+/// Early buyers of a launch that share a funding wallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FundingCluster {
+    pub funder: Pubkey,
+    pub wallets: Vec<Pubkey>,
+}
+
+/// Result of [`analyze_bundles`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleAnalysis {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    /// Distinct buyers other than the creator, earliest first.
+    pub early_buyers: Vec<Pubkey>,
+    /// Funders of two or more early buyers, or of any buyer when the funder is the creator or the
+    /// creator's own funder; largest first.
+    pub clusters: Vec<FundingCluster>,
+    /// Early buyers funded by the creator or by the wallet that funded the creator.
+    pub creator_linked: usize,
+    /// Share of early buyers in a cluster, 0.0..=1.0. Organic launches stay near 0; bundled ones,
+    /// where one source spreads SOL over the sniping wallets, approach 1.
+    pub score: f64,
+}
+
+/// Flag a bundled launch of `mint`: find the first `max_buyers` buyers besides `creator`, trace
+/// the wallet that funded each one (and the creator), and group buyers by funder.
+///
+/// A wallet's funder is the account that sent it the most SOL in the first of its oldest
+/// transactions that credited it; wallets with long histories are not traced back and count as
+/// unfunded. Shared funders such as exchange hot wallets also form clusters, so treat the score
+/// as a filter signal rather than proof. Costs one `getTransaction` per early trade plus a few per
+/// buyer; meant for fresh launches whose whole history fits one signature page.
+pub async fn analyze_bundles(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+    creator: &Pubkey,
+    max_buyers: usize,
+) -> Result<BundleAnalysis, anyhow::Error> {
+    let mut signatures: Vec<Signature> = rpc
+        .get_signatures_for_address(mint)
+        .await?
+        .into_iter()
+        .filter(|status| status.err.is_none())
+        .filter_map(|status| Signature::from_str(&status.signature).ok())
+        .collect();
+    signatures.reverse();
+
+    let mut early_buyers = Vec::new();
+    for chunk in signatures.chunks(RPC_PARSE_CONCURRENCY) {
+        let fills: Vec<_> = futures::stream::iter(chunk)
+            .map(|signature| parse_trade_from_signature(rpc, signature))
+            .buffered(RPC_PARSE_CONCURRENCY)
+            .collect()
+            .await;
+        for fill in fills.into_iter().flatten() {
+            if fill.mint == *mint
+                && fill.side == TradeType::Buy
+                && fill.trader != *creator
+                && !early_buyers.contains(&fill.trader)
+                && early_buyers.len() < max_buyers
+            {
+                early_buyers.push(fill.trader);
+            }
+        }
+        if early_buyers.len() >= max_buyers {
+            break;
+        }
+    }
+
+    let creator_funder = find_funder(rpc, creator).await?;
+    let funders: HashMap<Pubkey, Pubkey> = futures::stream::iter(&early_buyers)
+        .map(|wallet| async move { (*wallet, find_funder(rpc, wallet).await) })
+        .buffer_unordered(RPC_PARSE_CONCURRENCY)
+        .filter_map(|(wallet, funder)| async move { Some((wallet, funder.ok()??)) })
+        .collect()
+        .await;
+
+    let (clusters, creator_linked, score) =
+        score_bundles(&early_buyers, &funders, creator, creator_funder.as_ref());
+    Ok(BundleAnalysis {
+        mint: *mint,
+        creator: *creator,
+        early_buyers,
+        clusters,
+        creator_linked,
+        score,
+    })
+}
+
+/// `(clusters, creator_linked, score)` of `buyers` given each one's funder (see [`BundleAnalysis`]).
+pub fn score_bundles(
+    buyers: &[Pubkey],
+    funders: &HashMap<Pubkey, Pubkey>,
+    creator: &Pubkey,
+    creator_funder: Option<&Pubkey>,
+) -> (Vec<FundingCluster>, usize, f64) {
+    let creator_side = |funder: &Pubkey| funder == creator || Some(funder) == creator_funder;
+    let mut by_funder: HashMap<Pubkey, Vec<Pubkey>> = HashMap::new();
+    for buyer in buyers {
+        if let Some(funder) = funders.get(buyer) {
+            by_funder.entry(*funder).or_default().push(*buyer);
+        }
+    }
+    let mut clusters: Vec<FundingCluster> = by_funder
+        .into_iter()
+        .filter(|(funder, wallets)| wallets.len() >= 2 || creator_side(funder))
+        .map(|(funder, wallets)| FundingCluster { funder, wallets })
+        .collect();
+    clusters.sort_unstable_by(|a, b| {
+        b.wallets.len().cmp(&a.wallets.len()).then(a.funder.cmp(&b.funder))
+    });
+
+    let creator_linked = clusters
+        .iter()
+        .filter(|cluster| creator_side(&cluster.funder))
+        .map(|cluster| cluster.wallets.len())
+        .sum();
+    let clustered: usize = clusters.iter().map(|cluster| cluster.wallets.len()).sum();
+    let score = if buyers.is_empty() { 0.0 } else { clustered as f64 / buyers.len() as f64 };
+    (clusters, creator_linked, score)
+}
+
+/// Account that sent `wallet` the most SOL in a transaction where `wallet`'s balance rose.
+pub fn funder_from_balances(
+    wallet: &Pubkey,
+    account_keys: &[Pubkey],
+    pre_balances: &[u64],
+    post_balances: &[u64],
+) -> Option<Pubkey> {
+    let index = account_keys.iter().position(|key| key == wallet)?;
+    if post_balances.get(index)? <= pre_balances.get(index)? {
+        return None;
+    }
+    account_keys
+        .iter()
+        .zip(pre_balances.iter().zip(post_balances))
+        .filter(|(key, (pre, post))| *key != wallet && post < pre)
+        .max_by_key(|(_, (pre, post))| *pre - *post)
+        .map(|(key, _)| *key)
+}
+
+/// Funder of `wallet` from its oldest transactions, if its history is short enough to reach them.
+async fn find_funder(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+) -> Result<Option<Pubkey>, anyhow::Error> {
+    let statuses = rpc
+        .get_signatures_for_address_with_config(
+            wallet,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(FUNDING_SIGNATURE_LIMIT),
+                ..Default::default()
+            },
+        )
+        .await?;
+    if statuses.len() >= FUNDING_SIGNATURE_LIMIT {
+        return Ok(None);
+    }
+    let oldest = statuses
+        .iter()
+        .rev()
+        .filter(|status| status.err.is_none())
+        .filter_map(|status| Signature::from_str(&status.signature).ok())
+        .take(FUNDING_TXS_CHECKED);
+    for signature in oldest {
+        let tx = rpc
+            .get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    max_supported_transaction_version: Some(0),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            )
+            .await?;
+        let (Some(meta), Some(decoded)) =
+            (tx.transaction.meta, tx.transaction.transaction.decode())
+        else {
+            continue;
+        };
+        let mut account_keys = decoded.message.static_account_keys().to_vec();
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+                account_keys.push(Pubkey::from_str(key)?);
+            }
+        }
+        if let Some(funder) =
+            funder_from_balances(wallet, &account_keys, &meta.pre_balances, &meta.post_balances)
+        {
+            return Ok(Some(funder));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::factory::DexType;
+
+    #[test]
+    fn summarizes_holders_excluding_lp_from_top10() {
+        let pool = Pubkey::new_unique();
+        let mut balances = HashMap::from([(pool, 600)]);
+        for amount in [100, 50, 50, 40, 30, 30, 20, 20, 20, 10, 10, 10] {
+            balances.insert(Pubkey::new_unique(), amount);
+        }
+        let (holders, top10, lp) = summarize_holders(&balances, 1_000, &HashSet::from([pool]));
+
+        assert_eq!(holders, 13);
+        assert!((lp - 0.6).abs() < 1e-9);
+        assert!((top10 - 0.37).abs() < 1e-9);
+    }
+
+    #[test]
+    fn volume_windows_from_candles_and_fills() {
+        let candle = |open_time_ms, quote_volume| Candle {
+            open_time_ms,
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            token_volume: 0,
+            quote_volume,
+            trades: 1,
+        };
+        let now = 10 * ONE_HOUR_MS;
+        let candles = [
+            candle(now - 2 * ONE_HOUR_MS, 7),
+            candle(now - 30 * 60_000, 5),
+            candle(now - 60_000, 3),
+        ];
+        assert_eq!(volume_from_candles(&candles, now, FIVE_MINUTES_MS), 3);
+        assert_eq!(volume_from_candles(&candles, now, ONE_HOUR_MS), 8);
+
+        let mint = Pubkey::new_unique();
+        let fill = |side, amount_in, amount_out, secs_ago: i64| Fill {
+            signature: Signature::default(),
+            slot: 0,
+            block_time: Some(now / 1_000 - secs_ago),
+            dex: DexType::PumpSwap,
+            pool: Pubkey::default(),
+            trader: Pubkey::default(),
+            side,
+            mint,
+            quote_mint: Pubkey::default(),
+            amount_in,
+            amount_out,
+            network_fee: 0,
+            priority_fee: 0,
+            tip: 0,
+        };
+        let fills = [fill(TradeType::Buy, 100, 1, 60), fill(TradeType::Sell, 1, 40, 1_200)];
+        assert_eq!(volume_from_fills(&fills, &mint, now, FIVE_MINUTES_MS), 100);
+        assert_eq!(volume_from_fills(&fills, &mint, now, ONE_HOUR_MS), 140);
+    }
+
+    #[test]
+    fn scores_shared_and_creator_funders() {
+        let creator = Pubkey::new_unique();
+        let (source, creator_source) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let buyers: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+        let funders = HashMap::from([
+            (buyers[0], source),
+            (buyers[1], source),
+            (buyers[2], source),
+            (buyers[3], creator_source),
+            (buyers[4], Pubkey::new_unique()),
+        ]);
+        let (clusters, creator_linked, score) =
+            score_bundles(&buyers, &funders, &creator, Some(&creator_source));
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].funder, source);
+        assert_eq!(clusters[0].wallets.len(), 3);
+        assert_eq!(creator_linked, 1);
+        assert!((score - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(score_bundles(&[], &funders, &creator, None).2, 0.0);
+
+        let (wallet, funder, fee_payer) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let keys = [fee_payer, funder, wallet];
+        assert_eq!(
+            funder_from_balances(&wallet, &keys, &[10_000, 5_000_000, 0], &[5_000, 0, 5_000_000]),
+            Some(funder)
+        );
+        assert_eq!(funder_from_balances(&wallet, &keys, &[0, 0, 7], &[0, 0, 7]), None);
+    }
+}
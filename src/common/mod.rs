@@ -5,15 +5,35 @@ pub mod fast_fn;
 pub mod fast_timing;
 pub mod gas_fee_strategy;
 pub mod global;
+pub mod instruction_pool;
 pub mod keypair;
+pub mod landing_model;
+pub mod latency_budget;
+pub mod market_data;
+pub mod mint_analytics;
 pub mod nonce_cache;
+pub mod order_dedup;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod protocol_layout;
+pub mod risk_manager;
+pub mod rpc_cache;
+pub mod rpc_usage;
 pub mod sdk_log;
 pub mod seed;
+pub mod seed_accounts;
+pub mod slot_tracker;
 pub mod spl_associated_token_account;
 pub mod spl_token;
 pub mod spl_token_2022;
+pub mod state_snapshot;
 pub mod subscription_handle;
+pub mod trade_events;
+pub mod trading_halt;
 pub mod types;
+pub mod wallet_watcher;
 
 pub use gas_fee_strategy::*;
+/// `common::keys::load_keypair(Source::…)`.
+pub use keypair as keys;
 pub use types::*;
@@ -0,0 +1,196 @@
+//! Idempotency for trade submission: reject repeated `client_order_id`s within a window.
+//! 下单幂等：同一 `client_order_id` 在窗口期内只允许提交一次，防止重连导致重复买入。
+
+use crate::common::state_snapshot::{instant_to_unix_ms, unix_ms_to_instant, DedupKeyState};
+use crate::swqos::common::{TradeError, DUPLICATE_ORDER_ERROR_CODE};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Pluggable store backing order-id deduplication (e.g. swap in Redis for multi-process bots).
+pub trait OrderIdStore: Send + Sync {
+    /// Atomically claim `id`. Returns `false` when it was already claimed less than `window` ago.
+    fn try_reserve(&self, id: &str, window: Duration) -> bool;
+
+    /// Ids claimed less than `window` ago, for [`crate::StateSnapshot`]. Stores that persist
+    /// their own state (e.g. Redis) can keep the default.
+    fn export_keys(&self, _window: Duration) -> Vec<DedupKeyState> {
+        Vec::new()
+    }
+
+    /// Claim ids from a snapshot, keeping their original claim times.
+    fn import_keys(&self, _keys: &[DedupKeyState]) {}
+}
+
+/// Entries kept before expired ids are swept on insert.
+const PRUNE_THRESHOLD: usize = 4096;
+
+/// Process-local [`OrderIdStore`].
+#[derive(Default)]
+pub struct InMemoryOrderIdStore {
+    seen: DashMap<String, Instant>,
+}
+
+impl InMemoryOrderIdStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl OrderIdStore for InMemoryOrderIdStore {
+    fn try_reserve(&self, id: &str, window: Duration) -> bool {
+        let now = Instant::now();
+        if self.seen.len() >= PRUNE_THRESHOLD {
+            self.seen.retain(|_, claimed| now.duration_since(*claimed) < window);
+        }
+        match self.seen.entry(id.to_string()) {
+            Entry::Occupied(mut entry) => {
+                if now.duration_since(*entry.get()) < window {
+                    return false;
+                }
+                entry.insert(now);
+                true
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(now);
+                true
+            }
+        }
+    }
+
+    fn export_keys(&self, window: Duration) -> Vec<DedupKeyState> {
+        self.seen
+            .iter()
+            .filter(|entry| entry.value().elapsed() < window)
+            .map(|entry| DedupKeyState {
+                id: entry.key().clone(),
+                claimed_at_unix_ms: instant_to_unix_ms(*entry.value()),
+            })
+            .collect()
+    }
+
+    fn import_keys(&self, keys: &[DedupKeyState]) {
+        for key in keys {
+            self.seen.insert(key.id.clone(), unix_ms_to_instant(key.claimed_at_unix_ms));
+        }
+    }
+}
+
+/// Store plus window applied by [`crate::TradingClient`] to params carrying `client_order_id`.
+///
+/// The id is claimed right before the first transaction of the order is sent (see
+/// [`OrderClaim`]), so local rejections such as the slot gate, stale reserves or a failed build
+/// leave it free. Once sent it stays claimed for the whole window even if the submission
+/// fails, so a retry after a reconnect must use a new id once the caller has confirmed the
+/// first attempt did not land.
+#[derive(Clone)]
+pub struct OrderDedup {
+    pub store: Arc<dyn OrderIdStore>,
+    pub window: Duration,
+}
+
+impl OrderDedup {
+    pub fn new(store: Arc<dyn OrderIdStore>, window: Duration) -> Self {
+        Self { store, window }
+    }
+
+    pub fn in_memory(window: Duration) -> Self {
+        Self::new(Arc::new(InMemoryOrderIdStore::new()), window)
+    }
+}
+
+/// One order's claim on its `client_order_id`, taken by the executor just before sending.
+///
+/// Clones share the claim, so the retries of an order only claim the id once.
+#[derive(Clone)]
+pub struct OrderClaim {
+    dedup: OrderDedup,
+    id: String,
+    claimed: Arc<AtomicBool>,
+}
+
+impl OrderClaim {
+    pub fn new(dedup: OrderDedup, id: impl Into<String>) -> Self {
+        Self { dedup, id: id.into(), claimed: Arc::new(AtomicBool::new(false)) }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Claim the id, or reject a duplicate (`DuplicateOrder`) within the window. A no-op once
+    /// this order holds the claim.
+    pub fn claim(&self) -> Result<(), TradeError> {
+        if self.claimed.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        if self.dedup.store.try_reserve(&self.id, self.dedup.window) {
+            self.claimed.store(true, Ordering::Release);
+            return Ok(());
+        }
+        Err(TradeError {
+            code: DUPLICATE_ORDER_ERROR_CODE,
+            message: format!(
+                "DuplicateOrder: client_order_id {} already submitted within {:?}",
+                self.id, self.dedup.window
+            ),
+            instruction: None,
+        })
+    }
+
+    pub fn is_claimed(&self) -> bool {
+        self.claimed.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_repeat_within_window() {
+        let store = InMemoryOrderIdStore::new();
+        let window = Duration::from_secs(60);
+
+        assert!(store.try_reserve("order-1", window));
+        assert!(!store.try_reserve("order-1", window));
+        assert!(store.try_reserve("order-2", window));
+    }
+
+    #[test]
+    fn exported_keys_stay_claimed_after_import() {
+        let window = Duration::from_secs(60);
+        let store = InMemoryOrderIdStore::new();
+        assert!(store.try_reserve("order-1", window));
+
+        let restored = InMemoryOrderIdStore::new();
+        restored.import_keys(&store.export_keys(window));
+        assert!(!restored.try_reserve("order-1", window));
+        assert!(restored.try_reserve("order-2", window));
+    }
+
+    #[test]
+    fn order_claims_once_across_retries_and_rejects_other_orders() {
+        let dedup = OrderDedup::in_memory(Duration::from_secs(60));
+        let order = OrderClaim::new(dedup.clone(), "order-1");
+        let retry = order.clone();
+
+        assert!(!order.is_claimed());
+        assert!(order.claim().is_ok());
+        assert!(retry.claim().is_ok());
+        assert!(retry.is_claimed());
+
+        let duplicate = OrderClaim::new(dedup, "order-1").claim().unwrap_err();
+        assert_eq!(duplicate.code, DUPLICATE_ORDER_ERROR_CODE);
+    }
+
+    #[test]
+    fn allows_repeat_after_window() {
+        let store = InMemoryOrderIdStore::new();
+
+        assert!(store.try_reserve("order-1", Duration::ZERO));
+        assert!(store.try_reserve("order-1", Duration::ZERO));
+    }
+}
@@ -0,0 +1,98 @@
+//! OpenTelemetry spans for trades (feature `otel`).
+//!
+//! Every buy and sell opens a client span named `buy` / `sell` on the global tracer
+//! ([`TRACER_NAME`]) as a child of the current OpenTelemetry context, and closes it with the
+//! outcome: signatures, the lane that landed, and one `submit` event per SWQOS lane. The SDK only
+//! emits; install a tracer provider with an OTLP exporter (Jaeger, Tempo, ...) in the
+//! application, and nothing is recorded until one is installed.
+//! OpenTelemetry 链路追踪：每笔买卖一个 span（dex、mint、数量、通道属性），由应用配置导出器。
+
+use crate::client::DetailedTradeResult;
+use crate::swqos::TradeType;
+use crate::trading::factory::DexType;
+use opentelemetry::global::{self, BoxedSpan};
+use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
+use opentelemetry::{Context, KeyValue};
+use solana_sdk::pubkey::Pubkey;
+
+/// Instrumentation scope of the SDK's spans.
+pub const TRACER_NAME: &str = "sol-trade-sdk";
+
+/// A trade's span; ended by [`TradeSpan::finish`], or on drop when the trade bails out early.
+pub(crate) struct TradeSpan(BoxedSpan);
+
+impl TradeSpan {
+    pub(crate) fn start(
+        trade_type: TradeType,
+        dex_type: DexType,
+        mint: &Pubkey,
+        input_amount: Option<u64>,
+        simulate: bool,
+        client_order_id: Option<&str>,
+    ) -> Self {
+        let mut attributes = vec![
+            KeyValue::new("sol_trade.dex", format!("{:?}", dex_type)),
+            KeyValue::new("sol_trade.mint", mint.to_string()),
+            KeyValue::new("sol_trade.simulate", simulate),
+        ];
+        if let Some(amount) = input_amount {
+            // Attribute values are signed; clamp instead of wrapping.
+            let amount = i64::try_from(amount).unwrap_or(i64::MAX);
+            attributes.push(KeyValue::new("sol_trade.input_amount", amount));
+        }
+        if let Some(id) = client_order_id {
+            attributes.push(KeyValue::new("sol_trade.client_order_id", id.to_string()));
+        }
+        let name = if trade_type == TradeType::Sell { "sell" } else { "buy" };
+        let tracer = global::tracer(TRACER_NAME);
+        let span = tracer
+            .span_builder(name)
+            .with_kind(SpanKind::Client)
+            .with_attributes(attributes)
+            .start_with_context(&tracer, &Context::current());
+        Self(span)
+    }
+
+    /// Record the outcome and end the span.
+    pub(crate) fn finish(mut self, result: &DetailedTradeResult) {
+        let span = &mut self.0;
+        match result {
+            Ok((ok, signatures, error, timings)) => {
+                span.set_attribute(KeyValue::new("sol_trade.success", *ok));
+                span.set_attribute(KeyValue::new("sol_trade.lanes", timings.len() as i64));
+                if let Some(signature) = signatures.first() {
+                    span.set_attribute(KeyValue::new("sol_trade.signature", signature.to_string()));
+                }
+                for timing in timings {
+                    let mut attributes = vec![
+                        KeyValue::new("sol_trade.provider", timing.swqos_type.as_str()),
+                        KeyValue::new("sol_trade.strategy", timing.strategy_type.as_str()),
+                        KeyValue::new("sol_trade.cu_price", timing.cu_price as i64),
+                        KeyValue::new("sol_trade.tip_lamports", timing.tip_lamports as i64),
+                        KeyValue::new("sol_trade.submit_done_us", timing.submit_done_us),
+                    ];
+                    if let Some(landed) = timing.landed {
+                        attributes.push(KeyValue::new("sol_trade.landed", landed));
+                    }
+                    span.add_event("submit", attributes);
+                    if timing.landed == Some(true) {
+                        span.set_attribute(KeyValue::new(
+                            "sol_trade.landed_provider",
+                            timing.swqos_type.as_str(),
+                        ));
+                        if let Some(slot) = timing.landed_slot {
+                            span.set_attribute(KeyValue::new("sol_trade.landed_slot", slot as i64));
+                        }
+                    }
+                }
+                match (ok, error) {
+                    (true, _) => span.set_status(Status::Ok),
+                    (false, Some(error)) => span.set_status(Status::error(error.message.clone())),
+                    (false, None) => span.set_status(Status::error("trade failed")),
+                }
+            }
+            Err(error) => span.set_status(Status::error(error.to_string())),
+        }
+        span.end();
+    }
+}
@@ -0,0 +1,242 @@
+//! On-chain account layout checks for protocol state.
+//!
+//! Params built by RPC (`from_mint_by_rpc`, `from_pool_address_by_rpc`, ...) decode pool and curve
+//! accounts at fixed offsets. When a protocol upgrade changes an account's size or type, the old
+//! decoder can still "succeed" on the new bytes and the trade fails on-chain with an unrelated
+//! error. Each fetch first matches the raw account against the layouts this SDK knows
+//! (discriminator + data length) and fails with a `ProtocolLayoutChanged` [`TradeError`] (code
+//! [`PROTOCOL_LAYOUT_CHANGED_ERROR_CODE`]) when none match. [`set_layout_version`] pins a protocol
+//! to one known generation, e.g. to refuse legacy PumpSwap pools. The pin is process-global: the
+//! RPC fetchers are free functions that do not see a client, so it applies to every client and
+//! every direct `*_by_rpc` call alike.
+//! 协议账户布局检查：构建参数时校验判别符与数据长度，布局变更时返回 ProtocolLayoutChanged，可固定布局版本。
+
+use crate::instruction::utils::{
+    bonk_types, meteora_damm_v2_types, pumpfun, pumpswap_types, raydium_amm_v4_types,
+    raydium_cpmm_types,
+};
+use crate::swqos::common::{TradeError, PROTOCOL_LAYOUT_CHANGED_ERROR_CODE};
+use crate::trading::factory::DexType;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Which known account layout generation to accept for a protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LayoutVersion {
+    /// Accept every layout this SDK can decode.
+    #[default]
+    Auto,
+    /// Accept only this generation (see [`known_layout_versions`]); anything else is rejected.
+    Pinned(u8),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DataLen {
+    Exact(usize),
+    AtLeast(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KnownLayout {
+    version: u8,
+    /// Anchor account discriminator; `None` for non-Anchor programs.
+    discriminator: Option<[u8; 8]>,
+    data_len: DataLen,
+}
+
+impl KnownLayout {
+    fn matches(&self, data: &[u8]) -> bool {
+        let len_ok = match self.data_len {
+            DataLen::Exact(n) => data.len() == n,
+            DataLen::AtLeast(n) => data.len() >= n,
+        };
+        len_ok
+            && match self.discriminator {
+                Some(d) => data.get(..8) == Some(&d[..]),
+                None => true,
+            }
+    }
+}
+
+/// Borsh bytes `fetch_bonding_curve_account` reads after the discriminator.
+const BONDING_CURVE_FIELDS_LEN: usize = 8 * 5 + 1 + 32 + 1 + 1 + 32;
+
+const PUMPFUN_LAYOUTS: &[KnownLayout] = &[KnownLayout {
+    version: 1,
+    discriminator: Some(pumpfun::BONDING_CURVE_ACCOUNT_DISCRIMINATOR),
+    data_len: DataLen::AtLeast(8 + BONDING_CURVE_FIELDS_LEN),
+}];
+
+const PUMPSWAP_LAYOUTS: &[KnownLayout] = &[
+    // Pool without `virtual_quote_reserves`.
+    KnownLayout {
+        version: 1,
+        discriminator: Some(pumpswap_types::POOL_DISCRIMINATOR),
+        data_len: DataLen::Exact(8 + pumpswap_types::LEGACY_POOL_SIZE),
+    },
+    KnownLayout {
+        version: 2,
+        discriminator: Some(pumpswap_types::POOL_DISCRIMINATOR),
+        data_len: DataLen::AtLeast(8 + pumpswap_types::POOL_SIZE),
+    },
+];
+
+const BONK_LAYOUTS: &[KnownLayout] = &[KnownLayout {
+    version: 1,
+    discriminator: Some(bonk_types::POOL_STATE_DISCRIMINATOR),
+    data_len: DataLen::AtLeast(8 + bonk_types::POOL_STATE_SIZE),
+}];
+
+const RAYDIUM_CPMM_LAYOUTS: &[KnownLayout] = &[KnownLayout {
+    version: 1,
+    discriminator: Some(raydium_cpmm_types::POOL_STATE_DISCRIMINATOR),
+    data_len: DataLen::AtLeast(8 + raydium_cpmm_types::POOL_STATE_SIZE),
+}];
+
+const RAYDIUM_AMM_V4_LAYOUTS: &[KnownLayout] = &[KnownLayout {
+    version: 1,
+    discriminator: None,
+    data_len: DataLen::Exact(raydium_amm_v4_types::AMM_INFO_SIZE),
+}];
+
+const METEORA_DAMM_V2_LAYOUTS: &[KnownLayout] = &[KnownLayout {
+    version: 1,
+    discriminator: Some(meteora_damm_v2_types::POOL_DISCRIMINATOR),
+    data_len: DataLen::AtLeast(8 + meteora_damm_v2_types::POOL_SIZE),
+}];
+
+fn known_layouts(dex: DexType) -> &'static [KnownLayout] {
+    match dex {
+        DexType::PumpFun => PUMPFUN_LAYOUTS,
+        DexType::PumpSwap => PUMPSWAP_LAYOUTS,
+        DexType::Bonk => BONK_LAYOUTS,
+        DexType::RaydiumCpmm => RAYDIUM_CPMM_LAYOUTS,
+        DexType::RaydiumAmmV4 => RAYDIUM_AMM_V4_LAYOUTS,
+        DexType::MeteoraDammV2 => METEORA_DAMM_V2_LAYOUTS,
+    }
+}
+
+/// 0 = [`LayoutVersion::Auto`], otherwise the pinned version; indexed by [`pin_slot`].
+static PINNED: [AtomicU8; 6] = [
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+    AtomicU8::new(0),
+];
+
+#[inline]
+fn pin_slot(dex: DexType) -> usize {
+    match dex {
+        DexType::PumpFun => 0,
+        DexType::PumpSwap => 1,
+        DexType::Bonk => 2,
+        DexType::RaydiumCpmm => 3,
+        DexType::RaydiumAmmV4 => 4,
+        DexType::MeteoraDammV2 => 5,
+    }
+}
+
+/// Layout generations the SDK can decode for `dex`, oldest first.
+pub fn known_layout_versions(dex: DexType) -> Vec<u8> {
+    known_layouts(dex).iter().map(|l| l.version).collect()
+}
+
+/// Pin (or unpin with [`LayoutVersion::Auto`]) the layout accepted for `dex`, process-wide.
+///
+/// This is global state, not a per-client option: every [`crate::TradingClient`] (including the
+/// ones in a multi-client registry) and every direct params fetch sees the new pin at once.
+///
+/// Pinning a version the SDK does not know makes every fetch for `dex` fail, which is the point
+/// when you want trading to stop until the SDK is upgraded.
+pub fn set_layout_version(dex: DexType, version: LayoutVersion) {
+    let raw = match version {
+        LayoutVersion::Auto => 0,
+        LayoutVersion::Pinned(v) => v.max(1),
+    };
+    PINNED[pin_slot(dex)].store(raw, Ordering::Relaxed);
+}
+
+/// Layout currently accepted for `dex`.
+pub fn layout_version(dex: DexType) -> LayoutVersion {
+    match PINNED[pin_slot(dex)].load(Ordering::Relaxed) {
+        0 => LayoutVersion::Auto,
+        v => LayoutVersion::Pinned(v),
+    }
+}
+
+/// Known layout generation `data` (raw account data, discriminator included) matches, if any.
+pub fn detect_layout(dex: DexType, data: &[u8]) -> Option<u8> {
+    known_layouts(dex).iter().find(|l| l.matches(data)).map(|l| l.version)
+}
+
+/// `ProtocolLayoutChanged` error for `dex`.
+pub fn layout_changed(dex: DexType, detail: impl std::fmt::Display) -> TradeError {
+    TradeError {
+        code: PROTOCOL_LAYOUT_CHANGED_ERROR_CODE,
+        message: format!("ProtocolLayoutChanged: {:?} {}", dex, detail),
+        instruction: None,
+    }
+}
+
+/// Check a fetched account against the known and pinned layouts for `dex`; returns its version.
+pub fn check_layout(dex: DexType, data: &[u8]) -> Result<u8, TradeError> {
+    let Some(detected) = detect_layout(dex, data) else {
+        let discriminator = data.get(..8).unwrap_or(data);
+        return Err(layout_changed(
+            dex,
+            format_args!(
+                "account layout is unknown (len {}, discriminator {:?}); known versions {:?}",
+                data.len(),
+                discriminator,
+                known_layout_versions(dex)
+            ),
+        ));
+    };
+    match layout_version(dex) {
+        LayoutVersion::Pinned(pinned) if pinned != detected => Err(layout_changed(
+            dex,
+            format_args!("account is layout v{} but v{} is pinned", detected, pinned),
+        )),
+        _ => Ok(detected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pumpswap_pool(fields_len: usize) -> Vec<u8> {
+        let mut data = pumpswap_types::POOL_DISCRIMINATOR.to_vec();
+        data.resize(8 + fields_len, 0);
+        data
+    }
+
+    #[test]
+    fn detects_and_pins_pumpswap_pool_layouts() {
+        let legacy = pumpswap_pool(pumpswap_types::LEGACY_POOL_SIZE);
+        let current = pumpswap_pool(pumpswap_types::POOL_SIZE);
+        assert_eq!(check_layout(DexType::PumpSwap, &legacy).unwrap(), 1);
+        assert_eq!(check_layout(DexType::PumpSwap, &current).unwrap(), 2);
+
+        let mut retyped = current.clone();
+        retyped[0] ^= 1;
+        let err = check_layout(DexType::PumpSwap, &retyped).unwrap_err();
+        assert_eq!(err.code, PROTOCOL_LAYOUT_CHANGED_ERROR_CODE);
+        assert!(err.message.starts_with("ProtocolLayoutChanged"));
+        assert!(check_layout(DexType::PumpSwap, &current[..current.len() - 1]).is_err());
+
+        set_layout_version(DexType::PumpSwap, LayoutVersion::Pinned(2));
+        assert!(check_layout(DexType::PumpSwap, &legacy).is_err());
+        assert!(check_layout(DexType::PumpSwap, &current).is_ok());
+        set_layout_version(DexType::PumpSwap, LayoutVersion::Auto);
+        assert_eq!(layout_version(DexType::PumpSwap), LayoutVersion::Auto);
+    }
+
+    #[test]
+    fn amm_v4_has_no_discriminator_and_exact_size() {
+        let data = vec![7u8; raydium_amm_v4_types::AMM_INFO_SIZE];
+        assert_eq!(detect_layout(DexType::RaydiumAmmV4, &data), Some(1));
+        assert_eq!(detect_layout(DexType::RaydiumAmmV4, &data[1..]), None);
+    }
+}
@@ -0,0 +1,485 @@
+//! Pre-trade risk limits: per-trade size, per-mint exposure, daily loss and cooldowns.
+//! 交易前风控：单笔上限、单币敞口、日内亏损上限与冷却时间。
+//!
+//! Amounts are quote base units (lamports for SOL/WSOL). Use one manager per quote asset if a
+//! bot trades several quotes.
+
+use crate::common::state_snapshot::{
+    instant_to_unix_ms, unix_ms_to_instant, PositionState, StateSnapshot,
+};
+use crate::swqos::TradeType;
+use crate::trading::common::fill::Fill;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Limits enforced by [`RiskManager`]. `None` disables a limit.
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    /// Max quote spent by a single buy.
+    pub max_sol_per_trade: Option<u64>,
+    /// Max open cost basis per mint (bought minus received back).
+    pub max_exposure_per_mint: Option<u64>,
+    /// Buys stop once realized loss for the current UTC day reaches this.
+    pub max_daily_loss: Option<u64>,
+    /// Min time between two buys of the same mint.
+    pub buy_cooldown: Option<Duration>,
+}
+
+/// Why [`RiskManager::check_buy`] rejected a trade.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RiskRejection {
+    TradeTooLarge { amount: u64, max: u64 },
+    ExposureExceeded { mint: Pubkey, current: u64, requested: u64, max: u64 },
+    DailyLossLimit { loss: u64, max: u64 },
+    Cooldown { mint: Pubkey, remaining: Duration },
+}
+
+impl std::fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RiskRejection::TradeTooLarge { amount, max } => {
+                write!(f, "RiskLimit: trade size {} exceeds max {}", amount, max)
+            }
+            RiskRejection::ExposureExceeded { mint, current, requested, max } => write!(
+                f,
+                "RiskLimit: exposure on {} would be {} + {} (max {})",
+                mint, current, requested, max
+            ),
+            RiskRejection::DailyLossLimit { loss, max } => {
+                write!(f, "RiskLimit: daily loss {} reached max {}", loss, max)
+            }
+            RiskRejection::Cooldown { mint, remaining } => {
+                write!(f, "RiskLimit: {} is cooling down for another {:?}", mint, remaining)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RiskRejection {}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct MintPosition {
+    spent: u64,
+    received: u64,
+    /// Quote of in-flight buys held by [`BuyReservation`]s; counts against the exposure limit.
+    reserved: u64,
+    last_buy: Option<Instant>,
+}
+
+impl MintPosition {
+    #[inline]
+    fn exposure(&self) -> u64 {
+        self.spent.saturating_sub(self.received)
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.spent == 0 && self.received == 0 && self.reserved == 0 && self.last_buy.is_none()
+    }
+}
+
+#[derive(Default)]
+struct RiskState {
+    positions: HashMap<Pubkey, MintPosition>,
+    day: u64,
+    realized_pnl: i128,
+}
+
+#[inline]
+fn utc_day() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0)
+}
+
+/// Risk limits consulted by [`crate::TradingClient::buy`] before building the transaction.
+///
+/// The client reserves each buy with [`Self::reserve_buy`] and records it once confirmed; a
+/// buy sent without waiting for confirmation keeps only its cooldown, so record its fill with
+/// [`Self::record_fill`]. Sells are never blocked since they reduce risk. The client records
+/// confirmed sells with [`Self::record_sell_fill`], which realizes the PnL when the sell closes
+/// the position; other sells are reported with [`Self::record_sell`] or [`Self::record_fill`]
+/// and realized with [`Self::close_position`].
+pub struct RiskManager {
+    limits: RiskLimits,
+    state: Mutex<RiskState>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self { limits, state: Mutex::new(RiskState::default()) }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Check a buy of `mint` spending `amount` quote units, without reserving anything.
+    pub fn check_buy(&self, mint: &Pubkey, amount: u64) -> Result<(), RiskRejection> {
+        let mut state = self.state.lock();
+        self.check_locked(&mut state, mint, amount)
+    }
+
+    /// Check a buy like [`Self::check_buy`] and, under the same lock, reserve its exposure and
+    /// start the mint's cooldown, so concurrent buys cannot all pass the limits. Settle the
+    /// returned [`BuyReservation`]; dropping it (e.g. when the send fails) releases both.
+    pub fn reserve_buy(
+        &self,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Result<BuyReservation<'_>, RiskRejection> {
+        let mut state = self.state.lock();
+        self.check_locked(&mut state, mint, amount)?;
+        let started = Instant::now();
+        let position = state.positions.entry(*mint).or_default();
+        let previous_last_buy = position.last_buy.replace(started);
+        position.reserved = position.reserved.saturating_add(amount);
+        Ok(BuyReservation {
+            risk: self,
+            mint: *mint,
+            amount,
+            started,
+            previous_last_buy,
+            settled: false,
+        })
+    }
+
+    fn check_locked(
+        &self,
+        state: &mut RiskState,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Result<(), RiskRejection> {
+        if let Some(max) = self.limits.max_sol_per_trade {
+            if amount > max {
+                return Err(RiskRejection::TradeTooLarge { amount, max });
+            }
+        }
+        Self::roll_day(state);
+        if let Some(max) = self.limits.max_daily_loss {
+            let loss = u64::try_from((-state.realized_pnl).max(0)).unwrap_or(u64::MAX);
+            if loss >= max {
+                return Err(RiskRejection::DailyLossLimit { loss, max });
+            }
+        }
+        let position = state.positions.get(mint).copied().unwrap_or_default();
+        if let (Some(cooldown), Some(last_buy)) = (self.limits.buy_cooldown, position.last_buy) {
+            let elapsed = last_buy.elapsed();
+            if elapsed < cooldown {
+                return Err(RiskRejection::Cooldown { mint: *mint, remaining: cooldown - elapsed });
+            }
+        }
+        if let Some(max) = self.limits.max_exposure_per_mint {
+            let current = position.exposure().saturating_add(position.reserved);
+            if current.saturating_add(amount) > max {
+                return Err(RiskRejection::ExposureExceeded {
+                    mint: *mint,
+                    current,
+                    requested: amount,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Record quote spent on a buy of `mint`.
+    pub fn record_buy(&self, mint: &Pubkey, amount: u64) {
+        let mut state = self.state.lock();
+        let position = state.positions.entry(*mint).or_default();
+        position.spent = position.spent.saturating_add(amount);
+        position.last_buy = Some(Instant::now());
+    }
+
+    /// Record quote received from a sell of `mint`.
+    pub fn record_sell(&self, mint: &Pubkey, amount: u64) {
+        let mut state = self.state.lock();
+        if let Some(position) = state.positions.get_mut(mint) {
+            position.received = position.received.saturating_add(amount);
+        }
+    }
+
+    /// Record a confirmed [`Fill`] (e.g. from `parse_trade_from_signature`). Buys placed through
+    /// the client are already recorded, so feed it sells and externally placed buys only.
+    pub fn record_fill(&self, fill: &Fill) {
+        match fill.side {
+            TradeType::Buy | TradeType::CreateAndBuy => self.record_buy(&fill.mint, fill.amount_in),
+            TradeType::Sell => self.record_sell(&fill.mint, fill.amount_out),
+            TradeType::Create => {}
+        }
+    }
+
+    /// Record a confirmed sell [`Fill`]; when it `closes_position` (whole balance sold), also
+    /// realize the position like [`Self::close_position`] and return the PnL.
+    pub fn record_sell_fill(&self, fill: &Fill, closes_position: bool) -> Option<i128> {
+        self.record_sell(&fill.mint, fill.amount_out);
+        if closes_position {
+            self.close_position(&fill.mint)
+        } else {
+            None
+        }
+    }
+
+    /// Realize the position's PnL into today's tally and forget it. Returns the realized PnL.
+    pub fn close_position(&self, mint: &Pubkey) -> Option<i128> {
+        let mut state = self.state.lock();
+        Self::roll_day(&mut state);
+        let position = state.positions.remove(mint)?;
+        let pnl = position.received as i128 - position.spent as i128;
+        state.realized_pnl += pnl;
+        Some(pnl)
+    }
+
+    /// Open cost basis on `mint`.
+    pub fn exposure(&self, mint: &Pubkey) -> u64 {
+        self.state.lock().positions.get(mint).map(MintPosition::exposure).unwrap_or(0)
+    }
+
+    /// Realized PnL for the current UTC day.
+    pub fn daily_pnl(&self) -> i128 {
+        let mut state = self.state.lock();
+        Self::roll_day(&mut state);
+        state.realized_pnl
+    }
+
+    /// Copy open positions and today's realized PnL into `snapshot`.
+    pub fn save_state(&self, snapshot: &mut StateSnapshot) {
+        let mut state = self.state.lock();
+        Self::roll_day(&mut state);
+        snapshot.positions = state
+            .positions
+            .iter()
+            .map(|(mint, position)| PositionState {
+                mint: *mint,
+                spent: position.spent,
+                received: position.received,
+                last_buy_unix_ms: position.last_buy.map(instant_to_unix_ms),
+            })
+            .collect();
+        snapshot.realized_pnl = state.realized_pnl;
+        snapshot.realized_pnl_day = state.day;
+    }
+
+    /// Replace positions with the ones in `snapshot`; its realized PnL is kept only when it is
+    /// from the current UTC day.
+    pub fn restore_state(&self, snapshot: &StateSnapshot) {
+        let mut state = self.state.lock();
+        state.positions = snapshot
+            .positions
+            .iter()
+            .map(|p| {
+                let position = MintPosition {
+                    spent: p.spent,
+                    received: p.received,
+                    reserved: 0,
+                    last_buy: p.last_buy_unix_ms.map(unix_ms_to_instant),
+                };
+                (p.mint, position)
+            })
+            .collect();
+        state.day = snapshot.realized_pnl_day;
+        state.realized_pnl = snapshot.realized_pnl;
+        Self::roll_day(&mut state);
+    }
+
+    fn roll_day(state: &mut RiskState) {
+        let today = utc_day();
+        if state.day != today {
+            state.day = today;
+            state.realized_pnl = 0;
+        }
+    }
+}
+
+/// Exposure and cooldown held for an in-flight buy, from [`RiskManager::reserve_buy`].
+#[must_use = "dropping the reservation releases it"]
+pub struct BuyReservation<'a> {
+    risk: &'a RiskManager,
+    mint: Pubkey,
+    amount: u64,
+    started: Instant,
+    previous_last_buy: Option<Instant>,
+    settled: bool,
+}
+
+impl BuyReservation<'_> {
+    /// The buy confirmed: its reserved quote becomes spent.
+    pub fn confirm(mut self) {
+        self.settle(true, true);
+    }
+
+    /// The buy was sent but not confirmed: the exposure is released and the cooldown kept,
+    /// since the buy may still land. Record its fill with [`RiskManager::record_fill`].
+    pub fn submitted(mut self) {
+        self.settle(false, true);
+    }
+
+    fn settle(&mut self, spend: bool, keep_cooldown: bool) {
+        self.settled = true;
+        let mut state = self.risk.state.lock();
+        let position = state.positions.entry(self.mint).or_default();
+        position.reserved = position.reserved.saturating_sub(self.amount);
+        if spend {
+            position.spent = position.spent.saturating_add(self.amount);
+        }
+        // A later buy of the same mint may have restarted the cooldown since.
+        if !keep_cooldown && position.last_buy == Some(self.started) {
+            position.last_buy = self.previous_last_buy;
+        }
+        if position.is_empty() {
+            state.positions.remove(&self.mint);
+        }
+    }
+}
+
+impl Drop for BuyReservation<'_> {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.settle(false, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_oversized_trade_and_exposure() {
+        let mint = Pubkey::new_unique();
+        let risk = RiskManager::new(RiskLimits {
+            max_sol_per_trade: Some(1_000),
+            max_exposure_per_mint: Some(1_500),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            risk.check_buy(&mint, 1_001),
+            Err(RiskRejection::TradeTooLarge { amount: 1_001, max: 1_000 })
+        );
+        assert!(risk.check_buy(&mint, 1_000).is_ok());
+        risk.record_buy(&mint, 1_000);
+        assert!(matches!(
+            risk.check_buy(&mint, 600),
+            Err(RiskRejection::ExposureExceeded { current: 1_000, requested: 600, .. })
+        ));
+        risk.record_sell(&mint, 400);
+        assert_eq!(risk.exposure(&mint), 600);
+        assert!(risk.check_buy(&mint, 600).is_ok());
+    }
+
+    #[test]
+    fn cooldown_blocks_repeat_buys() {
+        let mint = Pubkey::new_unique();
+        let risk = RiskManager::new(RiskLimits {
+            buy_cooldown: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+
+        risk.record_buy(&mint, 10);
+        assert!(matches!(risk.check_buy(&mint, 10), Err(RiskRejection::Cooldown { .. })));
+        assert!(risk.check_buy(&Pubkey::new_unique(), 10).is_ok());
+    }
+
+    #[test]
+    fn daily_loss_stops_buys_after_realized_losses() {
+        let mint = Pubkey::new_unique();
+        let risk = RiskManager::new(RiskLimits { max_daily_loss: Some(500), ..Default::default() });
+
+        risk.record_buy(&mint, 1_000);
+        risk.record_sell(&mint, 400);
+        assert_eq!(risk.close_position(&mint), Some(-600));
+        assert_eq!(risk.daily_pnl(), -600);
+        assert_eq!(
+            risk.check_buy(&Pubkey::new_unique(), 1),
+            Err(RiskRejection::DailyLossLimit { loss: 600, max: 500 })
+        );
+    }
+
+    #[test]
+    fn concurrent_reservations_share_the_exposure_limit() {
+        let mint = Pubkey::new_unique();
+        let risk = RiskManager::new(RiskLimits {
+            max_exposure_per_mint: Some(1_500),
+            ..Default::default()
+        });
+
+        let first = risk.reserve_buy(&mint, 1_000).unwrap();
+        assert!(matches!(
+            risk.reserve_buy(&mint, 1_000),
+            Err(RiskRejection::ExposureExceeded { current: 1_000, requested: 1_000, .. })
+        ));
+        first.confirm();
+        assert_eq!(risk.exposure(&mint), 1_000);
+        assert!(risk.reserve_buy(&mint, 1_000).is_err());
+    }
+
+    #[test]
+    fn failed_buy_releases_its_reservation_and_cooldown() {
+        let mint = Pubkey::new_unique();
+        let risk = RiskManager::new(RiskLimits {
+            max_exposure_per_mint: Some(1_000),
+            buy_cooldown: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+
+        let reservation = risk.reserve_buy(&mint, 1_000).unwrap();
+        assert!(matches!(risk.check_buy(&mint, 1), Err(RiskRejection::Cooldown { .. })));
+        drop(reservation);
+        assert_eq!(risk.exposure(&mint), 0);
+        assert!(risk.check_buy(&mint, 1_000).is_ok());
+
+        // Sent without confirmation: no spend is recorded, the cooldown stays.
+        risk.reserve_buy(&mint, 1_000).unwrap().submitted();
+        assert_eq!(risk.exposure(&mint), 0);
+        assert!(matches!(risk.check_buy(&mint, 1), Err(RiskRejection::Cooldown { .. })));
+    }
+
+    #[test]
+    fn closing_sell_fill_realizes_pnl() {
+        let mint = Pubkey::new_unique();
+        let risk = RiskManager::new(RiskLimits::default());
+        risk.record_buy(&mint, 1_000);
+        let sell = |amount_out| Fill {
+            signature: solana_sdk::signature::Signature::default(),
+            slot: 0,
+            block_time: None,
+            dex: crate::trading::factory::DexType::PumpSwap,
+            pool: Pubkey::default(),
+            trader: Pubkey::default(),
+            side: TradeType::Sell,
+            mint,
+            quote_mint: Pubkey::default(),
+            amount_in: 10,
+            amount_out,
+            network_fee: 0,
+            priority_fee: 0,
+            tip: 0,
+        };
+
+        assert_eq!(risk.record_sell_fill(&sell(300), false), None);
+        assert_eq!(risk.exposure(&mint), 700);
+        assert_eq!(risk.record_sell_fill(&sell(900), true), Some(200));
+        assert_eq!(risk.daily_pnl(), 200);
+        assert_eq!(risk.exposure(&mint), 0);
+    }
+
+    #[test]
+    fn restores_positions_and_cooldown_from_snapshot() {
+        let (open, closed) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let limits =
+            RiskLimits { buy_cooldown: Some(Duration::from_secs(60)), ..Default::default() };
+        let risk = RiskManager::new(limits.clone());
+        risk.record_buy(&open, 1_000);
+        risk.record_buy(&closed, 300);
+        risk.close_position(&closed);
+
+        let mut snapshot = StateSnapshot::new();
+        risk.save_state(&mut snapshot);
+        let restored = RiskManager::new(limits);
+        restored.restore_state(&snapshot);
+        assert_eq!(restored.exposure(&open), 1_000);
+        assert_eq!(restored.daily_pnl(), -300);
+        assert!(matches!(restored.check_buy(&open, 10), Err(RiskRejection::Cooldown { .. })));
+    }
+}
@@ -0,0 +1,119 @@
+//! Thin account-read layer over [`SolanaRpcClient`]: identical in-flight `getAccountInfo` calls
+//! share one request, and immutable accounts (mints, configs) are cached for the process lifetime.
+//! 合并并发的相同账户请求（同一池子的多个事件只发一次 RPC）；不可变账户（mint、配置）永久缓存。
+
+use super::SolanaRpcClient;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Accounts are keyed by RPC endpoint so clients on different clusters never share results.
+type AccountKey = (String, Pubkey);
+type FetchOutcome = Option<Result<Account, String>>;
+
+static IN_FLIGHT: Lazy<DashMap<AccountKey, watch::Receiver<FetchOutcome>>> =
+    Lazy::new(DashMap::new);
+static IMMUTABLE_ACCOUNTS: Lazy<DashMap<AccountKey, Arc<Account>>> = Lazy::new(DashMap::new);
+
+/// Removes the in-flight entry when the leading request finishes or is cancelled; waiters of a
+/// cancelled request see the channel close and retry.
+struct InFlightEntry(AccountKey);
+
+impl Drop for InFlightEntry {
+    fn drop(&mut self) {
+        IN_FLIGHT.remove(&self.0);
+    }
+}
+
+/// `get_account`, sharing one RPC request between all concurrent callers asking for the same
+/// account on the same endpoint. Nothing is cached once the request completes.
+pub async fn get_account_coalesced(
+    rpc: &SolanaRpcClient,
+    pubkey: &Pubkey,
+) -> Result<Account, anyhow::Error> {
+    let key = (rpc.url(), *pubkey);
+    loop {
+        let sender = match IN_FLIGHT.entry(key.clone()) {
+            Entry::Occupied(entry) => {
+                let mut receiver = entry.get().clone();
+                drop(entry);
+                let outcome = receiver.wait_for(Option::is_some).await.map(|o| o.clone());
+                match outcome {
+                    Ok(Some(result)) => {
+                        return result
+                            .map_err(|e| anyhow::anyhow!("get_account {} failed: {}", pubkey, e));
+                    }
+                    // The leading request was dropped before finishing.
+                    _ => continue,
+                }
+            }
+            Entry::Vacant(entry) => {
+                let (sender, receiver) = watch::channel(None);
+                entry.insert(receiver);
+                sender
+            }
+        };
+        let _entry = InFlightEntry(key);
+        let result = rpc.get_account(pubkey).await.map_err(|e| e.to_string());
+        let _ = sender.send(Some(result.clone()));
+        return result.map_err(|e| anyhow::anyhow!("get_account {} failed: {}", pubkey, e));
+    }
+}
+
+/// Account that never changes in the fields the caller reads (mint owner/decimals, program
+/// configs), fetched once per endpoint and kept for the process lifetime. Do not use it for
+/// balances, supplies or reserves.
+pub async fn get_immutable_account(
+    rpc: &SolanaRpcClient,
+    pubkey: &Pubkey,
+) -> Result<Arc<Account>, anyhow::Error> {
+    let key = (rpc.url(), *pubkey);
+    if let Some(account) = IMMUTABLE_ACCOUNTS.get(&key) {
+        return Ok(account.clone());
+    }
+    let account = Arc::new(get_account_coalesced(rpc, pubkey).await?);
+    IMMUTABLE_ACCOUNTS.insert(key, account.clone());
+    Ok(account)
+}
+
+/// Drop `pubkey` from the immutable cache on every endpoint (e.g. after a config migration).
+pub fn forget_immutable_account(pubkey: &Pubkey) {
+    IMMUTABLE_ACCOUNTS.retain(|(_, key), _| key != pubkey);
+}
+
+/// Number of immutable accounts currently cached.
+pub fn immutable_account_count() -> usize {
+    IMMUTABLE_ACCOUNTS.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forget_drops_every_endpoint() {
+        let pubkey = Pubkey::new_unique();
+        let account = Arc::new(Account::default());
+        IMMUTABLE_ACCOUNTS.insert(("http://a".to_string(), pubkey), account.clone());
+        IMMUTABLE_ACCOUNTS.insert(("http://b".to_string(), pubkey), account);
+        forget_immutable_account(&pubkey);
+        assert!(!IMMUTABLE_ACCOUNTS.iter().any(|e| e.key().1 == pubkey));
+    }
+
+    #[tokio::test]
+    async fn cancelled_leader_releases_waiters() {
+        let key = ("http://test".to_string(), Pubkey::new_unique());
+        let (sender, receiver) = watch::channel(None);
+        IN_FLIGHT.insert(key.clone(), receiver.clone());
+        let entry = InFlightEntry(key.clone());
+
+        let mut waiter = receiver;
+        drop((entry, sender));
+        assert!(!IN_FLIGHT.contains_key(&key));
+        assert!(waiter.wait_for(Option::is_some).await.is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_CACHE};
 use crate::common::SolanaRpcClient;
 use fnv::FnvHasher;
 use once_cell::sync::Lazy;
@@ -34,12 +35,12 @@ pub fn set_default_rents() {
 }
 
 pub fn start_rent_updater(client: Arc<SolanaRpcClient>) {
-    tokio::spawn(async move {
+    tokio::spawn(with_rpc_scope(RPC_SCOPE_CACHE, async move {
         loop {
             if let Err(_e) = update_rents(&client).await {}
             sleep(Duration::from_secs(60 * 60)).await;
         }
-    });
+    }));
 }
 
 async fn fetch_rent_for_token_account(
@@ -69,6 +70,24 @@ fn derive_seed_from_mint(mint: &Pubkey) -> String {
     seed
 }
 
+/// Cached rent-exempt balance of a token account for `token_program` (default until
+/// [`update_rents`] / [`set_default_rents`] ran).
+#[inline]
+pub(crate) fn token_account_rent(token_program: &Pubkey) -> u64 {
+    // 🚀 优化：原子读取租金缓存
+    // Relaxed: 租金值不变，无需同步；Release/Acquire 在 update_rents 保证初始化可见性
+    let v = if token_program == &crate::constants::TOKEN_PROGRAM_2022 {
+        SPL_TOKEN_2022_RENT.load(Ordering::Relaxed)
+    } else {
+        SPL_TOKEN_RENT.load(Ordering::Relaxed)
+    };
+    if v == u64::MAX {
+        DEFAULT_TOKEN_ACCOUNT_RENT
+    } else {
+        v
+    }
+}
+
 pub fn create_associated_token_account_use_seed(
     payer: &Pubkey,
     owner: &Pubkey,
@@ -76,24 +95,7 @@ pub fn create_associated_token_account_use_seed(
     token_program: &Pubkey,
 ) -> Result<Vec<Instruction>, anyhow::Error> {
     let is_2022_token = token_program == &crate::constants::TOKEN_PROGRAM_2022;
-
-    // 🚀 优化：原子读取租金缓存
-    // Relaxed: 租金值不变，无需同步；Release/Acquire 在 update_rents 保证初始化可见性
-    let rent = if is_2022_token {
-        let v = SPL_TOKEN_2022_RENT.load(Ordering::Relaxed);
-        if v == u64::MAX {
-            DEFAULT_TOKEN_ACCOUNT_RENT
-        } else {
-            v
-        }
-    } else {
-        let v = SPL_TOKEN_RENT.load(Ordering::Relaxed);
-        if v == u64::MAX {
-            DEFAULT_TOKEN_ACCOUNT_RENT
-        } else {
-            v
-        }
-    };
+    let rent = token_account_rent(token_program);
 
     let seed = derive_seed_from_mint(mint);
     // 🔧 修复：使用传入的 token_program 生成地址（支持 Token 和 Token-2022）
@@ -0,0 +1,350 @@
+//! Seed-derived token accounts (the `use_seed_optimize` path) as a public API.
+//! Seed 代币账户公开接口：查询 / 创建 / 关闭 / 迁移，并把地址冲突等失败情况以类型化错误返回。
+//!
+//! A seed account lives at `create_with_seed(wallet, seed(mint), token_program)` and is created
+//! with `create_account_with_seed` + `InitializeAccount3`, which is cheaper than the ATA program.
+//! Unlike an idempotent ATA create, it fails if anything already sits at that address, so check
+//! [`lookup`] (or pass [`SeedFallback::StandardAta`]) before creating.
+
+use crate::common::seed::{
+    create_associated_token_account_use_seed, get_associated_token_address_with_program_id_use_seed,
+};
+use crate::common::spl_associated_token_account::{
+    create_associated_token_account_idempotent, get_associated_token_address_with_program_id,
+};
+use crate::common::SolanaRpcClient;
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+/// SPL token account layout: mint (0..32), owner (32..64), amount (64..72), state (108).
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const STATE_OFFSET: usize = 108;
+
+/// Why a seed account cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedAccountError {
+    /// The token program is neither SPL Token nor Token-2022.
+    UnsupportedTokenProgram(Pubkey),
+    /// The seed address could not be derived.
+    Derivation(String),
+    /// Something that is not a token account of `token_program` occupies the address.
+    AddressCollision {
+        address: Pubkey,
+        program_owner: Pubkey,
+    },
+    /// A token account exists at the address but for another mint.
+    MintMismatch {
+        address: Pubkey,
+        mint: Pubkey,
+    },
+    /// A token account exists at the address but is owned by another wallet.
+    OwnerMismatch {
+        address: Pubkey,
+        owner: Pubkey,
+    },
+    /// `close` was asked for an account that still holds tokens.
+    NonZeroBalance {
+        address: Pubkey,
+        amount: u64,
+    },
+    Rpc(String),
+}
+
+impl std::fmt::Display for SeedAccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeedAccountError::UnsupportedTokenProgram(program) => {
+                write!(f, "SeedAccount: unsupported token program {}", program)
+            }
+            SeedAccountError::Derivation(e) => write!(f, "SeedAccount: derivation failed: {}", e),
+            SeedAccountError::AddressCollision { address, program_owner } => write!(
+                f,
+                "SeedAccount: {} is occupied by an account owned by {}",
+                address, program_owner
+            ),
+            SeedAccountError::MintMismatch { address, mint } => {
+                write!(f, "SeedAccount: {} holds mint {}", address, mint)
+            }
+            SeedAccountError::OwnerMismatch { address, owner } => {
+                write!(f, "SeedAccount: {} is owned by {}", address, owner)
+            }
+            SeedAccountError::NonZeroBalance { address, amount } => {
+                write!(f, "SeedAccount: {} still holds {} tokens", address, amount)
+            }
+            SeedAccountError::Rpc(e) => write!(f, "SeedAccount: rpc error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SeedAccountError {}
+
+/// State of the seed account of `(wallet, mint)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedAccountStatus {
+    Missing { address: Pubkey },
+    Ready { address: Pubkey, amount: u64 },
+}
+
+impl SeedAccountStatus {
+    pub fn address(&self) -> Pubkey {
+        match self {
+            SeedAccountStatus::Missing { address } | SeedAccountStatus::Ready { address, .. } => {
+                *address
+            }
+        }
+    }
+}
+
+/// What [`create`] does when the seed address is unusable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeedFallback {
+    /// Return the error.
+    #[default]
+    Fail,
+    /// Use the wallet's standard ATA instead (idempotent create).
+    StandardAta,
+}
+
+/// Token account chosen by [`create`] plus the instructions to create it (empty if it exists).
+#[derive(Debug, Clone)]
+pub struct SeedAccountSetup {
+    pub address: Pubkey,
+    /// `true` when the standard ATA was used because of [`SeedFallback::StandardAta`].
+    pub is_standard_ata: bool,
+    pub instructions: Vec<Instruction>,
+}
+
+#[inline]
+fn check_token_program(token_program: &Pubkey) -> Result<(), SeedAccountError> {
+    if *token_program == TOKEN_PROGRAM || *token_program == TOKEN_PROGRAM_2022 {
+        Ok(())
+    } else {
+        Err(SeedAccountError::UnsupportedTokenProgram(*token_program))
+    }
+}
+
+/// Seed account address of `wallet` for `mint`.
+pub fn address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Pubkey, SeedAccountError> {
+    check_token_program(token_program)?;
+    get_associated_token_address_with_program_id_use_seed(wallet, mint, token_program)
+        .map_err(|e| SeedAccountError::Derivation(e.to_string()))
+}
+
+/// Classify the account found at a seed address (`None` = nothing there).
+pub fn classify_account(
+    address: Pubkey,
+    account: Option<&Account>,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<SeedAccountStatus, SeedAccountError> {
+    let Some(account) = account else {
+        return Ok(SeedAccountStatus::Missing { address });
+    };
+    let data = &account.data;
+    if account.owner != *token_program || data.len() < TOKEN_ACCOUNT_LEN || data[STATE_OFFSET] == 0
+    {
+        return Err(SeedAccountError::AddressCollision { address, program_owner: account.owner });
+    }
+    let account_mint = Pubkey::try_from(&data[0..32]).unwrap_or_default();
+    if account_mint != *mint {
+        return Err(SeedAccountError::MintMismatch { address, mint: account_mint });
+    }
+    let owner = Pubkey::try_from(&data[32..64]).unwrap_or_default();
+    if owner != *wallet {
+        return Err(SeedAccountError::OwnerMismatch { address, owner });
+    }
+    let amount = u64::from_le_bytes(data[64..72].try_into().unwrap());
+    Ok(SeedAccountStatus::Ready { address, amount })
+}
+
+/// Look up the seed account of `wallet` for `mint`.
+pub async fn lookup(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<SeedAccountStatus, SeedAccountError> {
+    let address = address(wallet, mint, token_program)?;
+    let account = rpc
+        .get_account_with_commitment(&address, rpc.commitment())
+        .await
+        .map_err(|e| SeedAccountError::Rpc(e.to_string()))?
+        .value;
+    classify_account(address, account.as_ref(), wallet, mint, token_program)
+}
+
+/// Instructions creating the seed account of `wallet` (payer and owner) for `mint`.
+///
+/// Existing usable accounts yield no instructions. With [`SeedFallback::StandardAta`] an unusable
+/// seed address falls back to the wallet's ATA instead of failing.
+pub async fn create(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    fallback: SeedFallback,
+) -> Result<SeedAccountSetup, SeedAccountError> {
+    let status = match lookup(rpc, wallet, mint, token_program).await {
+        Ok(status) => status,
+        Err(SeedAccountError::Rpc(e)) => return Err(SeedAccountError::Rpc(e)),
+        Err(e) if fallback == SeedFallback::StandardAta => {
+            if crate::common::sdk_log::sdk_log_enabled() {
+                tracing::warn!(target: "sol_trade_sdk", "{}; using standard ATA", e);
+            }
+            return Ok(standard_ata_setup(wallet, mint, token_program));
+        }
+        Err(e) => return Err(e),
+    };
+    match status {
+        SeedAccountStatus::Ready { address, .. } => {
+            Ok(SeedAccountSetup { address, is_standard_ata: false, instructions: Vec::new() })
+        }
+        SeedAccountStatus::Missing { address } => {
+            let instructions =
+                create_associated_token_account_use_seed(wallet, wallet, mint, token_program)
+                    .map_err(|e| SeedAccountError::Derivation(e.to_string()))?;
+            Ok(SeedAccountSetup { address, is_standard_ata: false, instructions })
+        }
+    }
+}
+
+fn standard_ata_setup(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> SeedAccountSetup {
+    SeedAccountSetup {
+        address: get_associated_token_address_with_program_id(wallet, mint, token_program),
+        is_standard_ata: true,
+        instructions: vec![create_associated_token_account_idempotent(
+            wallet,
+            wallet,
+            mint,
+            token_program,
+        )],
+    }
+}
+
+/// Instruction closing the (empty) seed account and returning its rent to `wallet`.
+pub async fn close(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Option<Instruction>, SeedAccountError> {
+    match lookup(rpc, wallet, mint, token_program).await? {
+        SeedAccountStatus::Missing { .. } => Ok(None),
+        SeedAccountStatus::Ready { address, amount } if amount > 0 => {
+            Err(SeedAccountError::NonZeroBalance { address, amount })
+        }
+        SeedAccountStatus::Ready { address, .. } => {
+            Ok(Some(close_instruction(&address, wallet, token_program)?))
+        }
+    }
+}
+
+fn close_instruction(
+    address: &Pubkey,
+    wallet: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction, SeedAccountError> {
+    crate::common::spl_token::close_account(token_program, address, wallet, wallet, &[])
+        .map_err(|e| SeedAccountError::Derivation(e.to_string()))
+}
+
+/// Instructions moving the seed account's balance into the wallet's standard ATA and closing it
+/// (e.g. before handing the wallet to tools that only understand ATAs). Empty when missing.
+pub async fn migrate_to_ata(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Vec<Instruction>, SeedAccountError> {
+    let SeedAccountStatus::Ready { address, amount } =
+        lookup(rpc, wallet, mint, token_program).await?
+    else {
+        return Ok(Vec::new());
+    };
+    let ata = standard_ata_setup(wallet, mint, token_program);
+    let mut instructions = ata.instructions;
+    if amount > 0 {
+        let mint_account =
+            rpc.get_account(mint).await.map_err(|e| SeedAccountError::Rpc(e.to_string()))?;
+        let decimals = *mint_account
+            .data
+            .get(44)
+            .ok_or_else(|| SeedAccountError::Rpc(format!("{} is not a token mint", mint)))?;
+        instructions.push(
+            crate::common::spl_token::transfer_checked(
+                token_program,
+                &address,
+                mint,
+                &ata.address,
+                wallet,
+                amount,
+                decimals,
+                &[],
+            )
+            .map_err(|e| SeedAccountError::Derivation(e.to_string()))?,
+        );
+    }
+    instructions.push(close_instruction(&address, wallet, token_program)?);
+    Ok(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64, program: Pubkey) -> Account {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[STATE_OFFSET] = 1;
+        Account { lamports: 2_039_280, data, owner: program, executable: false, rent_epoch: 0 }
+    }
+
+    #[test]
+    fn classifies_seed_address_contents() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let address = address(&wallet, &mint, &TOKEN_PROGRAM).unwrap();
+        let classify = |account: Option<&Account>| {
+            classify_account(address, account, &wallet, &mint, &TOKEN_PROGRAM)
+        };
+
+        assert_eq!(classify(None), Ok(SeedAccountStatus::Missing { address }));
+        assert_eq!(
+            classify(Some(&token_account(&mint, &wallet, 5, TOKEN_PROGRAM))),
+            Ok(SeedAccountStatus::Ready { address, amount: 5 })
+        );
+        let stranger = Pubkey::new_unique();
+        assert_eq!(
+            classify(Some(&token_account(&mint, &stranger, 5, TOKEN_PROGRAM))),
+            Err(SeedAccountError::OwnerMismatch { address, owner: stranger })
+        );
+        let other_mint = Pubkey::new_unique();
+        assert_eq!(
+            classify(Some(&token_account(&other_mint, &wallet, 5, TOKEN_PROGRAM))),
+            Err(SeedAccountError::MintMismatch { address, mint: other_mint })
+        );
+        let system = crate::constants::SYSTEM_PROGRAM;
+        assert_eq!(
+            classify(Some(&Account { owner: system, ..Default::default() })),
+            Err(SeedAccountError::AddressCollision { address, program_owner: system })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_token_program() {
+        let program = Pubkey::new_unique();
+        assert_eq!(
+            address(&Pubkey::new_unique(), &Pubkey::new_unique(), &program),
+            Err(SeedAccountError::UnsupportedTokenProgram(program))
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//! Latest-slot tracker for slot-aware trade gating and blockhash expiry estimates.
+//! 最新 slot 跟踪：用于按 slot 延迟拦截过期跟单，并按实测 slot 时长估算 blockhash 剩余有效期。
+
+use crate::common::clock::now_micros;
+use crate::common::subscription_handle::SubscriptionHandle;
+use futures::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Blocks a blockhash stays usable after the block that produced it.
+pub const BLOCKHASH_VALIDITY_SLOTS: u64 = 150;
+/// Target slot time, used until the tracker has measured the real one.
+pub const NOMINAL_SLOT_DURATION: Duration = Duration::from_millis(400);
+/// Bounds of a single slot-time sample; longer gaps are stream stalls, not slow slots.
+const MIN_SLOT_US: u64 = 50_000;
+const MAX_SLOT_US: u64 = 2_000_000;
+
+#[derive(Default)]
+struct SlotClock {
+    latest: AtomicU64,
+    /// `now_micros` when `latest` was observed.
+    observed_at_us: AtomicI64,
+    /// Moving average of the observed slot time, 0 until measured.
+    slot_us: AtomicU64,
+}
+
+/// Shared latest cluster slot. Cheap to clone; reads are a single atomic load.
+///
+/// Feed it either from [`SlotTracker::subscribe`] (websocket `slotSubscribe`) or from an
+/// existing gRPC stream via [`SlotTracker::observe`]. The tracker also measures how long slots
+/// actually take, so it can extrapolate the current slot between updates and tell how long a
+/// blockhash has left ([`SlotTracker::blockhash_validity`]).
+#[derive(Clone, Default)]
+pub struct SlotTracker {
+    clock: Arc<SlotClock>,
+}
+
+/// Remaining validity of a blockhash, see [`SlotTracker::blockhash_validity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockhashValidity {
+    pub remaining_slots: u64,
+    pub remaining: Duration,
+}
+
+impl BlockhashValidity {
+    pub fn is_expired(&self) -> bool {
+        self.remaining_slots == 0
+    }
+
+    /// Whether to refresh now: expired, or expiring within `margin` (signing, sending and
+    /// landing time).
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.is_expired() || self.remaining <= margin
+    }
+}
+
+impl SlotTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest observed slot, `None` until the first update arrives.
+    #[inline]
+    pub fn latest_slot(&self) -> Option<u64> {
+        match self.clock.latest.load(Ordering::Acquire) {
+            0 => None,
+            slot => Some(slot),
+        }
+    }
+
+    /// Record a slot; out-of-order updates never move the tracker backwards.
+    #[inline]
+    pub fn observe(&self, slot: u64) {
+        self.observe_at(slot, now_micros());
+    }
+
+    fn observe_at(&self, slot: u64, now_us: i64) {
+        let previous = self.clock.latest.fetch_max(slot, Ordering::AcqRel);
+        if slot <= previous {
+            return;
+        }
+        let previous_at = self.clock.observed_at_us.swap(now_us, Ordering::AcqRel);
+        if previous == 0 {
+            return;
+        }
+        let sample = ((now_us - previous_at).max(0) as u64 / (slot - previous))
+            .clamp(MIN_SLOT_US, MAX_SLOT_US);
+        let average = match self.clock.slot_us.load(Ordering::Acquire) {
+            0 => sample,
+            average => average - average / 8 + sample / 8,
+        };
+        self.clock.slot_us.store(average, Ordering::Release);
+    }
+
+    /// Measured slot time, [`NOMINAL_SLOT_DURATION`] until two slots have been observed.
+    pub fn slot_duration(&self) -> Duration {
+        match self.clock.slot_us.load(Ordering::Acquire) {
+            0 => NOMINAL_SLOT_DURATION,
+            slot_us => Duration::from_micros(slot_us),
+        }
+    }
+
+    /// Latest slot advanced by the time since it was observed, at the measured slot time.
+    pub fn estimated_slot(&self) -> Option<u64> {
+        self.estimated_slot_at(now_micros())
+    }
+
+    fn estimated_slot_at(&self, now_us: i64) -> Option<u64> {
+        let latest = self.latest_slot()?;
+        let elapsed_us = (now_us - self.clock.observed_at_us.load(Ordering::Acquire)).max(0);
+        Some(latest + elapsed_us as u64 / self.slot_duration().as_micros() as u64)
+    }
+
+    /// How long a blockhash produced at `blockhash_slot` (e.g. the context slot of
+    /// `getLatestBlockhash`) stays usable, or `None` before the first slot update.
+    ///
+    /// Expiry is counted in slots while the cluster counts blocks; skipped slots only make the
+    /// real validity longer, so the estimate errs on the side of refreshing early.
+    pub fn blockhash_validity(&self, blockhash_slot: u64) -> Option<BlockhashValidity> {
+        self.blockhash_validity_at(blockhash_slot, now_micros())
+    }
+
+    fn blockhash_validity_at(&self, blockhash_slot: u64, now_us: i64) -> Option<BlockhashValidity> {
+        let expiry_slot = blockhash_slot + BLOCKHASH_VALIDITY_SLOTS;
+        let remaining_slots = expiry_slot.saturating_sub(self.estimated_slot_at(now_us)?);
+        let latest = self.latest_slot()?;
+        let expiry_us = self.clock.observed_at_us.load(Ordering::Acquire)
+            + (expiry_slot.saturating_sub(latest) * self.slot_duration().as_micros() as u64) as i64;
+        let remaining = match remaining_slots {
+            0 => Duration::ZERO,
+            _ => Duration::from_micros((expiry_us - now_us).max(0) as u64),
+        };
+        Some(BlockhashValidity { remaining_slots, remaining })
+    }
+
+    /// Start a websocket `slotSubscribe` that keeps the returned tracker up to date.
+    pub async fn subscribe(ws_url: &str) -> Result<(Self, SubscriptionHandle), anyhow::Error> {
+        let tracker = Self::new();
+        let client = PubsubClient::new(ws_url).await?;
+        let updater = tracker.clone();
+        let task = tokio::spawn(async move {
+            let (mut stream, unsubscribe) = match client.slot_subscribe().await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    if crate::common::sdk_log::sdk_log_enabled() {
+                        tracing::warn!(target: "sol_trade_sdk", "slotSubscribe failed: {}", e);
+                    }
+                    return;
+                }
+            };
+            while let Some(info) = stream.next().await {
+                updater.observe(info.slot);
+            }
+            unsubscribe().await;
+        });
+        Ok((tracker, SubscriptionHandle { task, unsub_fn: Box::new(|| {}) }))
+    }
+}
+
+/// Slots the cluster has advanced past `event_slot`, or `None` when no slot is known yet.
+#[inline]
+pub fn slot_lag(tracker: &SlotTracker, event_slot: u64) -> Option<u64> {
+    tracker.latest_slot().map(|latest| latest.saturating_sub(event_slot))
+}
+
+/// Drops trades whose triggering event is more than `max_slot_lag` slots behind the cluster.
+#[derive(Clone)]
+pub struct SlotGate {
+    pub tracker: SlotTracker,
+    pub max_slot_lag: u64,
+}
+
+impl SlotGate {
+    pub fn new(tracker: SlotTracker, max_slot_lag: u64) -> Self {
+        Self { tracker, max_slot_lag }
+    }
+
+    /// Returns the lag when it exceeds `max_slot_lag`; unknown cluster slot never blocks.
+    #[inline]
+    pub fn exceeded_lag(&self, event_slot: u64) -> Option<u64> {
+        slot_lag(&self.tracker, event_slot).filter(|lag| *lag > self.max_slot_lag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observe_keeps_highest_slot() {
+        let tracker = SlotTracker::new();
+        assert_eq!(tracker.latest_slot(), None);
+        assert_eq!(slot_lag(&tracker, 10), None);
+
+        tracker.observe(105);
+        tracker.observe(103);
+
+        assert_eq!(tracker.latest_slot(), Some(105));
+        assert_eq!(slot_lag(&tracker, 100), Some(5));
+        assert_eq!(slot_lag(&tracker, 110), Some(0));
+    }
+
+    #[test]
+    fn gate_blocks_only_beyond_max_lag() {
+        let gate = SlotGate::new(SlotTracker::new(), 2);
+        assert_eq!(gate.exceeded_lag(1), None);
+
+        gate.tracker.observe(100);
+        assert_eq!(gate.exceeded_lag(98), None);
+        assert_eq!(gate.exceeded_lag(97), Some(3));
+    }
+
+    #[test]
+    fn estimates_blockhash_expiry_from_measured_slot_time() {
+        let tracker = SlotTracker::new();
+        assert_eq!(tracker.blockhash_validity(100), None);
+        assert_eq!(tracker.slot_duration(), NOMINAL_SLOT_DURATION);
+
+        // Slots arrive every 500 ms, slower than nominal.
+        for i in 0..=40u64 {
+            tracker.observe_at(1_000 + i, i as i64 * 500_000);
+        }
+        assert_eq!(tracker.slot_duration(), Duration::from_millis(500));
+        let now = 40 * 500_000 + 1_000_000;
+        assert_eq!(tracker.estimated_slot_at(now), Some(1_042));
+
+        let validity = tracker.blockhash_validity_at(1_000, now).unwrap();
+        assert_eq!(validity.remaining_slots, 108);
+        assert_eq!(validity.remaining, Duration::from_millis(108 * 500));
+        assert!(!validity.expires_within(Duration::from_secs(10)));
+        assert!(validity.expires_within(Duration::from_secs(60)));
+        assert!(tracker.blockhash_validity_at(800, now).unwrap().is_expired());
+    }
+}
@@ -68,3 +68,83 @@ pub fn initialize_account3(
     ];
     Ok(Instruction { program_id: *token_program_id, accounts, data })
 }
+
+/// TransferChecked; works for both Token and Token-2022 (required by mints with extensions).
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
+    token_program_id: &Pubkey,
+    source_pubkey: &Pubkey,
+    mint_pubkey: &Pubkey,
+    destination_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    amount: u64,
+    decimals: u8,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    // TransferChecked
+    let mut data = Vec::with_capacity(10);
+    data.push(12);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+
+    let mut accounts = Vec::with_capacity(4 + signers.len());
+    accounts.push(AccountMeta::new(*source_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*destination_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*owner_pubkey, signers.is_empty()));
+
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction { program_id: *token_program_id, accounts, data })
+}
+
+/// InitializeMint2 (no rent sysvar account); works for both Token and Token-2022.
+pub fn initialize_mint2(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    mint_authority_pubkey: &Pubkey,
+    freeze_authority_pubkey: Option<&Pubkey>,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    // InitializeMint2
+    let mut data = Vec::with_capacity(67);
+    data.push(20);
+    data.push(decimals);
+    data.extend_from_slice(mint_authority_pubkey.as_ref());
+    match freeze_authority_pubkey {
+        Some(freeze_authority) => {
+            data.push(1);
+            data.extend_from_slice(freeze_authority.as_ref());
+        }
+        None => data.push(0),
+    }
+    let accounts = vec![AccountMeta::new(*mint_pubkey, false)];
+    Ok(Instruction { program_id: *token_program_id, accounts, data })
+}
+
+pub fn mint_to(
+    token_program_id: &Pubkey,
+    mint_pubkey: &Pubkey,
+    account_pubkey: &Pubkey,
+    owner_pubkey: &Pubkey,
+    amount: u64,
+    signers: &[&Pubkey],
+) -> Result<Instruction, ProgramError> {
+    // MintTo
+    let mut data = Vec::with_capacity(9);
+    data.push(7);
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let mut accounts = Vec::with_capacity(3 + signers.len());
+    accounts.push(AccountMeta::new(*mint_pubkey, false));
+    accounts.push(AccountMeta::new(*account_pubkey, false));
+    accounts.push(AccountMeta::new_readonly(*owner_pubkey, signers.is_empty()));
+
+    for signer in signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer, true));
+    }
+
+    Ok(Instruction { program_id: *token_program_id, accounts, data })
+}
@@ -0,0 +1,232 @@
+//! Engine state that survives a restart: open positions, pending orders, DCA progress and order
+//! dedup keys, saved to and loaded from one JSON file.
+//!
+//! [`crate::TradingClient::snapshot_state`] fills the parts the client owns (risk-manager
+//! positions, in-memory dedup keys); strategies add their pending orders and DCA progress before
+//! [`StateSnapshot::save`], and read them back after [`StateSnapshot::load`] and
+//! [`crate::TradingClient::restore_state`]. Times are Unix milliseconds so a snapshot stays
+//! meaningful across processes.
+//! 状态快照：持仓、挂单、DCA 进度与去重键，保存到磁盘并在重启后恢复。
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Format version written to [`StateSnapshot::version`]; [`StateSnapshot::load`] rejects others.
+pub const STATE_SNAPSHOT_VERSION: u32 = 1;
+
+/// Side of a pending order or DCA schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// Open position on one mint, in quote base units (lamports for SOL/WSOL).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionState {
+    pub mint: Pubkey,
+    /// Quote spent buying.
+    pub spent: u64,
+    /// Quote received back from sells.
+    pub received: u64,
+    /// Last buy, for buy cooldowns.
+    pub last_buy_unix_ms: Option<u64>,
+}
+
+/// Order accepted by a strategy but not filled yet (e.g. a limit order waiting for its price).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingOrderState {
+    /// Strategy-assigned id; also the trade's `client_order_id` when it is submitted.
+    pub id: String,
+    pub mint: Pubkey,
+    pub side: OrderSide,
+    /// Quote to spend (buy) or tokens to sell, in base units.
+    pub amount: u64,
+    /// Trigger price in quote per token, when the order waits for one.
+    pub limit_price: Option<f64>,
+    pub created_at_unix_ms: u64,
+    /// Order expires unfilled after this time.
+    pub expires_at_unix_ms: Option<u64>,
+}
+
+/// Progress of a dollar-cost-averaging schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DcaProgressState {
+    pub id: String,
+    pub mint: Pubkey,
+    pub side: OrderSide,
+    /// Amount per slice, in base units.
+    pub slice_amount: u64,
+    pub slices_total: u32,
+    pub slices_done: u32,
+    /// Amount filled so far, in base units.
+    pub filled_amount: u64,
+    pub next_slice_at_unix_ms: Option<u64>,
+}
+
+/// A claimed `client_order_id` (see [`crate::OrderDedup`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupKeyState {
+    pub id: String,
+    pub claimed_at_unix_ms: u64,
+}
+
+/// Everything an engine needs to resume after a restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub version: u32,
+    pub saved_at_unix_ms: u64,
+    pub positions: Vec<PositionState>,
+    /// Realized PnL of the UTC day `realized_pnl_day` (days since the Unix epoch).
+    pub realized_pnl: i128,
+    pub realized_pnl_day: u64,
+    pub pending_orders: Vec<PendingOrderState>,
+    pub dca_progress: Vec<DcaProgressState>,
+    pub dedup_keys: Vec<DedupKeyState>,
+}
+
+impl Default for StateSnapshot {
+    fn default() -> Self {
+        Self {
+            version: STATE_SNAPSHOT_VERSION,
+            saved_at_unix_ms: unix_ms(SystemTime::now()),
+            positions: Vec::new(),
+            realized_pnl: 0,
+            realized_pnl_day: 0,
+            pending_orders: Vec::new(),
+            dca_progress: Vec::new(),
+            dedup_keys: Vec::new(),
+        }
+    }
+}
+
+impl StateSnapshot {
+    /// Empty snapshot stamped with the current time.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the snapshot as JSON. The file is replaced atomically (written and fsynced next to
+    /// `path`, renamed, then the directory fsynced on Unix), so a crash mid-save keeps either the
+    /// previous snapshot or the new one, never a truncated file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), anyhow::Error> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        {
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(&serde_json::to_vec_pretty(self)?)?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp, path)?;
+        #[cfg(unix)]
+        {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            std::fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    /// Read a snapshot written by [`Self::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref();
+        let snapshot: Self = serde_json::from_slice(&std::fs::read(path)?)
+            .map_err(|e| anyhow::anyhow!("Invalid state snapshot {}: {}", path.display(), e))?;
+        if snapshot.version != STATE_SNAPSHOT_VERSION {
+            return Err(anyhow::anyhow!(
+                "State snapshot {} has version {}, expected {}",
+                path.display(),
+                snapshot.version,
+                STATE_SNAPSHOT_VERSION
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// [`Self::load`], or `None` when `path` does not exist yet (first start).
+    pub fn load_if_exists(path: impl AsRef<Path>) -> Result<Option<Self>, anyhow::Error> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::load(path).map(Some)
+    }
+}
+
+/// Milliseconds since the Unix epoch.
+pub(crate) fn unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Unix milliseconds of a past `instant`.
+pub(crate) fn instant_to_unix_ms(instant: Instant) -> u64 {
+    unix_ms(SystemTime::now() - instant.elapsed())
+}
+
+/// `Instant` of a past Unix-millisecond time; clamped to now for future times.
+pub(crate) fn unix_ms_to_instant(unix_ms_value: u64) -> Instant {
+    let age = unix_ms(SystemTime::now()).saturating_sub(unix_ms_value);
+    let now = Instant::now();
+    now.checked_sub(Duration::from_millis(age)).unwrap_or(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let mut snapshot = StateSnapshot::new();
+        snapshot.positions.push(PositionState {
+            mint: Pubkey::new_unique(),
+            spent: 1_000,
+            received: 400,
+            last_buy_unix_ms: Some(1_700_000_000_000),
+        });
+        snapshot.pending_orders.push(PendingOrderState {
+            id: "limit-1".to_string(),
+            mint: Pubkey::new_unique(),
+            side: OrderSide::Sell,
+            amount: 5_000,
+            limit_price: Some(0.000_042),
+            created_at_unix_ms: 1_700_000_000_000,
+            expires_at_unix_ms: None,
+        });
+        snapshot.realized_pnl = -250;
+        snapshot.dedup_keys.push(DedupKeyState {
+            id: "order-1".to_string(),
+            claimed_at_unix_ms: 1_700_000_000_000,
+        });
+
+        let path =
+            std::env::temp_dir().join(format!("sol-trade-sdk-state-{}.json", Pubkey::new_unique()));
+        snapshot.save(&path).unwrap();
+        let loaded = StateSnapshot::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, snapshot);
+        assert!(StateSnapshot::load_if_exists(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn save_replaces_the_previous_snapshot_without_leaving_the_temp_file() {
+        let path =
+            std::env::temp_dir().join(format!("sol-trade-sdk-state-{}.json", Pubkey::new_unique()));
+        StateSnapshot::new().save(&path).unwrap();
+        let mut newer = StateSnapshot::new();
+        newer.realized_pnl = 7;
+        newer.save(&path).unwrap();
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        assert!(!Path::new(&tmp).exists());
+        assert_eq!(StateSnapshot::load(&path).unwrap(), newer);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+//! Internal event bus of executed trades.
+//!
+//! After a buy or sell confirms (`wait_tx_confirmed = true`, not simulated), the client decodes the
+//! landed transaction into a [`Fill`] and publishes a dex-agnostic [`TradeExecuted`] on its
+//! [`TradeEventBus`]. Portfolio tracking, trade journals, notifications and copy-trading can each
+//! [`TradeEventBus::subscribe`] instead of hooking the trade call sites. Decoding costs one
+//! `getTransaction` off the trade path and is skipped entirely while nobody is subscribed.
+//!
+//! A transaction sent through several SWQOS lanes can be reported confirmed by more than one of
+//! them, and a bus shared by several clients can see the same landing twice. The bus remembers
+//! the signatures it published ([`ConfirmationDedup`]) and emits each one exactly once.
+//! 成交事件总线：确认后的买卖统一发布 TradeExecuted，供持仓、日志、通知、跟单等订阅。
+
+use crate::swqos::TradeType;
+use crate::trading::common::fill::Fill;
+use crate::trading::factory::DexType;
+use dashmap::DashMap;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber; a slower subscriber sees `RecvError::Lagged` and skips ahead.
+pub const TRADE_EVENT_CAPACITY: usize = 1024;
+
+/// How long a published signature is remembered. Far longer than a blockhash stays valid, so a
+/// late confirmation from a slow lane cannot slip through.
+pub const CONFIRMATION_DEDUP_WINDOW: Duration = Duration::from_secs(300);
+
+/// Entries kept before expired signatures are swept on insert.
+const PRUNE_THRESHOLD: usize = 4096;
+
+/// Signatures already confirmed, so each landing is reported once however many lanes or clients
+/// observe it.
+#[derive(Debug)]
+pub struct ConfirmationDedup {
+    seen: DashMap<Signature, Instant>,
+    window: Duration,
+}
+
+impl Default for ConfirmationDedup {
+    fn default() -> Self {
+        Self::new(CONFIRMATION_DEDUP_WINDOW)
+    }
+}
+
+impl ConfirmationDedup {
+    pub fn new(window: Duration) -> Self {
+        Self { seen: DashMap::new(), window }
+    }
+
+    /// Claim `signature`; false when it was already claimed less than the window ago.
+    pub fn first_confirmation(&self, signature: &Signature) -> bool {
+        let now = Instant::now();
+        if self.seen.len() >= PRUNE_THRESHOLD {
+            self.seen.retain(|_, seen| now.duration_since(*seen) < self.window);
+        }
+        let mut first = true;
+        self.seen
+            .entry(*signature)
+            .and_modify(|seen| {
+                if now.duration_since(*seen) < self.window {
+                    first = false;
+                } else {
+                    *seen = now;
+                }
+            })
+            .or_insert(now);
+        first
+    }
+}
+
+/// A confirmed trade, normalized across protocols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeExecuted {
+    pub signature: Signature,
+    pub slot: u64,
+    pub dex: DexType,
+    pub side: TradeType,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    /// Quote spent (buy) or mint sold (sell), in base units.
+    pub amount_in: u64,
+    /// Mint received (buy) or quote received (sell), in base units.
+    pub amount_out: u64,
+    /// Quote base units per mint base unit; 0.0 when the mint side is zero.
+    pub price: f64,
+    /// Base + priority fee paid by the trader, in lamports. Protocol fees are inside the amounts.
+    pub network_fee: u64,
+    /// Priority part of `network_fee`, in lamports.
+    pub priority_fee: u64,
+    /// Lamports paid to SWQOS tip accounts.
+    pub tip: u64,
+    /// `client_order_id` of the originating request, if any.
+    pub client_order_id: Option<String>,
+}
+
+impl TradeExecuted {
+    pub fn from_fill(fill: Fill, client_order_id: Option<String>) -> Self {
+        let (quote_amount, mint_amount) = if fill.side == TradeType::Sell {
+            (fill.amount_out, fill.amount_in)
+        } else {
+            (fill.amount_in, fill.amount_out)
+        };
+        let price = if mint_amount == 0 { 0.0 } else { quote_amount as f64 / mint_amount as f64 };
+        Self {
+            signature: fill.signature,
+            slot: fill.slot,
+            dex: fill.dex,
+            side: fill.side,
+            mint: fill.mint,
+            quote_mint: fill.quote_mint,
+            pool: fill.pool,
+            trader: fill.trader,
+            amount_in: fill.amount_in,
+            amount_out: fill.amount_out,
+            price,
+            network_fee: fill.network_fee,
+            priority_fee: fill.priority_fee,
+            tip: fill.tip,
+            client_order_id,
+        }
+    }
+}
+
+/// Broadcast channel of [`TradeExecuted`]. Cloning shares the channel and its
+/// [`ConfirmationDedup`], so one bus can be handed to several clients (e.g. one per wallet) and
+/// subscribed to once.
+#[derive(Debug, Clone)]
+pub struct TradeEventBus {
+    sender: broadcast::Sender<TradeExecuted>,
+    confirmed: Arc<ConfirmationDedup>,
+}
+
+impl Default for TradeEventBus {
+    fn default() -> Self {
+        Self::new(TRADE_EVENT_CAPACITY)
+    }
+}
+
+impl TradeEventBus {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_dedup_window(capacity, CONFIRMATION_DEDUP_WINDOW)
+    }
+
+    /// Like [`Self::new`], remembering published signatures for `window` instead of
+    /// [`CONFIRMATION_DEDUP_WINDOW`]. Fixed at construction so every clone shares one window.
+    pub fn with_dedup_window(capacity: usize, window: Duration) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender, confirmed: Arc::new(ConfirmationDedup::new(window)) }
+    }
+
+    /// New receiver seeing every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<TradeExecuted> {
+        self.sender.subscribe()
+    }
+
+    /// Whether anyone would receive a published event.
+    #[inline]
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    /// Publish `event`; returns how many subscribers received it (0 when none, or when its
+    /// signature was already published to someone).
+    pub fn publish(&self, event: TradeExecuted) -> usize {
+        if !self.has_subscribers() || !self.confirmed.first_confirmation(&event.signature) {
+            return 0;
+        }
+        self.sender.send(event).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: TradeType, amount_in: u64, amount_out: u64) -> Fill {
+        Fill {
+            signature: Signature::default(),
+            slot: 42,
+            block_time: None,
+            dex: DexType::PumpSwap,
+            pool: Pubkey::new_unique(),
+            trader: Pubkey::new_unique(),
+            side,
+            mint: Pubkey::new_unique(),
+            quote_mint: crate::constants::WSOL_TOKEN_ACCOUNT,
+            amount_in,
+            amount_out,
+            network_fee: 5_000,
+            priority_fee: 0,
+            tip: 0,
+        }
+    }
+
+    #[test]
+    fn price_is_quote_per_mint_for_both_sides() {
+        let buy = TradeExecuted::from_fill(fill(TradeType::Buy, 1_000, 4_000), None);
+        assert_eq!(buy.price, 0.25);
+        let sell = TradeExecuted::from_fill(fill(TradeType::Sell, 4_000, 1_000), Some("a".into()));
+        assert_eq!(sell.price, 0.25);
+        assert_eq!(sell.client_order_id.as_deref(), Some("a"));
+    }
+
+    #[tokio::test]
+    async fn publishes_to_every_subscriber() {
+        let bus = TradeEventBus::default();
+        let event = TradeExecuted::from_fill(fill(TradeType::Buy, 1, 1), None);
+        assert!(!bus.has_subscribers());
+        assert_eq!(bus.publish(event.clone()), 0);
+
+        let (mut a, mut b) = (bus.subscribe(), bus.clone().subscribe());
+        assert_eq!(bus.publish(event.clone()), 2);
+        assert_eq!(a.recv().await.unwrap(), event);
+        assert_eq!(b.recv().await.unwrap(), event);
+    }
+
+    #[tokio::test]
+    async fn each_signature_is_published_once_across_clones() {
+        let bus = TradeEventBus::default();
+        let mut receiver = bus.subscribe();
+        let event = TradeExecuted::from_fill(fill(TradeType::Sell, 1, 1), None);
+
+        assert_eq!(bus.publish(event.clone()), 1);
+        assert_eq!(bus.clone().publish(event.clone()), 0);
+        assert_eq!(receiver.recv().await.unwrap(), event);
+        assert!(receiver.try_recv().is_err());
+
+        let expired = TradeEventBus::with_dedup_window(TRADE_EVENT_CAPACITY, Duration::ZERO);
+        let _receiver = expired.subscribe();
+        assert_eq!(expired.publish(event.clone()), 1);
+        assert_eq!(expired.publish(event), 1);
+    }
+}
@@ -0,0 +1,100 @@
+//! Kill switch for a running bot: halt all trading, or trading of single mints, without stopping
+//! the process.
+//!
+//! A halted trade is rejected before anything is built or sent, with a `TradingHalted`
+//! [`TradeError`]. Sells flagged `reduce_only` still go through so positions can be unwound.
+//! 交易熔断：全局或按 mint 拒绝新的买卖，仍允许显式的减仓卖出。
+
+use crate::swqos::common::TradeError;
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// [`TradeError::code`] for trades rejected because trading is halted (`TradingHalted`).
+pub const TRADING_HALTED_ERROR_CODE: u32 = 1008;
+
+/// Global and per-mint halt flags, shared by a client and its clones.
+#[derive(Debug, Default)]
+pub struct TradingHalt {
+    all: AtomicBool,
+    mints: RwLock<HashSet<Pubkey>>,
+}
+
+impl TradingHalt {
+    /// Reject every new trade.
+    pub fn halt(&self) {
+        self.all.store(true, Ordering::Release);
+    }
+
+    /// Lift [`Self::halt`]; mints halted individually stay halted.
+    pub fn resume(&self) {
+        self.all.store(false, Ordering::Release);
+    }
+
+    /// Reject new trades of `mint`.
+    pub fn halt_mint(&self, mint: Pubkey) {
+        self.mints.write().insert(mint);
+    }
+
+    /// Lift [`Self::halt_mint`] for `mint`.
+    pub fn resume_mint(&self, mint: &Pubkey) {
+        self.mints.write().remove(mint);
+    }
+
+    /// Whether all trading is halted.
+    pub fn is_halted(&self) -> bool {
+        self.all.load(Ordering::Acquire)
+    }
+
+    /// Whether trades of `mint` are rejected, globally or for the mint alone.
+    pub fn is_mint_halted(&self, mint: &Pubkey) -> bool {
+        self.is_halted() || self.mints.read().contains(mint)
+    }
+
+    /// Mints halted individually.
+    pub fn halted_mints(&self) -> Vec<Pubkey> {
+        self.mints.read().iter().copied().collect()
+    }
+
+    /// Reject a `side` ("buy" / "sell") trade of `mint` with a `TradingHalted` [`TradeError`].
+    pub fn check(&self, side: &str, mint: &Pubkey) -> Result<(), anyhow::Error> {
+        let scope = if self.is_halted() {
+            "all trading"
+        } else if self.mints.read().contains(mint) {
+            "this mint"
+        } else {
+            return Ok(());
+        };
+        Err(anyhow::Error::new(TradeError {
+            code: TRADING_HALTED_ERROR_CODE,
+            message: format!("TradingHalted: {} of {} rejected, {} is halted", side, mint, scope),
+            instruction: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halts_globally_and_per_mint() {
+        let halt = TradingHalt::default();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(halt.check("buy", &a).is_ok());
+
+        halt.halt_mint(a);
+        let err = halt.check("buy", &a).unwrap_err();
+        assert_eq!(err.downcast_ref::<TradeError>().unwrap().code, TRADING_HALTED_ERROR_CODE);
+        assert!(halt.check("buy", &b).is_ok());
+
+        halt.halt();
+        assert!(halt.is_mint_halted(&b));
+        halt.resume();
+        assert!(halt.check("sell", &b).is_ok());
+        assert!(halt.is_mint_halted(&a));
+        halt.resume_mint(&a);
+        assert!(halt.check("sell", &a).is_ok());
+    }
+}
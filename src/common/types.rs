@@ -1,6 +1,11 @@
 use crate::common::GasFeeStrategyType;
+use crate::swqos::common::{HttpClientTuning, RpcSendOptions};
 use crate::swqos::{SwqosConfig, SwqosType};
 use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
 /// Infrastructure-only configuration (wallet-independent)
@@ -16,6 +21,8 @@ pub struct InfrastructureConfig {
     /// (Astralane, BlockRazor, Glaive) use MEV-protected endpoints/modes. Glaive HTTP adds
     /// `mev-protect=true`; Glaive QUIC sets auth-frame flag bit 0. Default false.
     pub mev_protection: bool,
+    /// HTTP client tuning per provider; providers without an entry use the SDK defaults.
+    pub swqos_http_tuning: HashMap<SwqosType, HttpClientTuning>,
 }
 
 impl InfrastructureConfig {
@@ -30,6 +37,7 @@ impl InfrastructureConfig {
             commitment,
             swqos_cores_from_end: false,
             mev_protection: false,
+            swqos_http_tuning: HashMap::new(),
         }
     }
 
@@ -41,9 +49,15 @@ impl InfrastructureConfig {
             commitment: config.commitment.clone(),
             swqos_cores_from_end: config.swqos_cores_from_end,
             mev_protection: config.mev_protection,
+            swqos_http_tuning: config.swqos_http_tuning.clone(),
         }
     }
 
+    /// HTTP client tuning of `swqos_type`; the SDK defaults when none is configured.
+    pub fn http_tuning_for(&self, swqos_type: SwqosType) -> HttpClientTuning {
+        self.swqos_http_tuning.get(&swqos_type).cloned().unwrap_or_default()
+    }
+
     /// Generate a cache key for this infrastructure configuration
     pub fn cache_key(&self) -> String {
         use std::collections::hash_map::DefaultHasher;
@@ -61,6 +75,10 @@ impl Hash for InfrastructureConfig {
         format!("{:?}", self.commitment).hash(state);
         self.swqos_cores_from_end.hash(state);
         self.mev_protection.hash(state);
+        // Map order is arbitrary; hash the entries in a fixed provider order.
+        let mut tuning: Vec<_> = self.swqos_http_tuning.iter().collect();
+        tuning.sort_by_key(|(swqos_type, _)| swqos_type.as_str());
+        tuning.hash(state);
     }
 }
 
@@ -71,16 +89,128 @@ impl PartialEq for InfrastructureConfig {
             && self.commitment == other.commitment
             && self.swqos_cores_from_end == other.swqos_cores_from_end
             && self.mev_protection == other.mev_protection
+            && self.swqos_http_tuning == other.swqos_http_tuning
     }
 }
 
 impl Eq for InfrastructureConfig {}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SwqosSubmitTiming {
     pub swqos_type: SwqosType,
     pub strategy_type: GasFeeStrategyType,
     pub submit_done_us: i64,
+    /// Signature of this lane's transaction (default when it was never signed).
+    pub signature: Signature,
+    /// Compute unit price (micro-lamports) and tip (lamports) this lane was sent with. The price
+    /// is read from the signed transaction: 0 when it set none (e.g. `SendMode::JitoTipOnly`)
+    /// or was never signed.
+    pub cu_price: u64,
+    pub tip_lamports: u64,
+    /// Set once the trade was confirmed: `Some(true)` for the lane whose transaction landed,
+    /// `Some(false)` for the others (or all of them on a confirmation timeout).
+    pub landed: Option<bool>,
+    /// Slot and block time (unix seconds) of the landed transaction, on the landed lane only.
+    /// The block time is `None` when the RPC node does not have it yet.
+    pub landed_slot: Option<u64>,
+    pub block_time: Option<i64>,
+    /// Slots from the trade's `event_slot` to `landed_slot`, when the trade carried one.
+    pub event_to_land_slots: Option<u64>,
+    /// Transaction this lane sent; only captured when raw transactions are enabled
+    /// (`TradeConfig::raw_transactions`).
+    pub raw_transaction: Option<RawTransaction>,
+}
+
+/// Cluster that [`RawTransaction::explorer_url`] links into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExplorerCluster {
+    #[default]
+    MainnetBeta,
+    Devnet,
+}
+
+impl ExplorerCluster {
+    /// Solscan page of the transaction `signature`.
+    pub fn transaction_url(&self, signature: &Signature) -> String {
+        match self {
+            Self::MainnetBeta => format!("https://solscan.io/tx/{}", signature),
+            Self::Devnet => format!("https://solscan.io/tx/{}?cluster=devnet", signature),
+        }
+    }
+}
+
+/// A submitted transaction as it went out, for debugging failed or missing trades
+/// (`solana decode-transaction`, re-simulation, replay). 已提交交易的原始数据与浏览器链接。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawTransaction {
+    pub signature: Signature,
+    /// Bincode wire transaction, base64-encoded, exactly as submitted.
+    pub base64: String,
+    pub explorer_url: String,
+}
+
+impl RawTransaction {
+    /// Reuses the encoding shared with the providers when the transaction was already serialized.
+    pub fn capture(
+        transaction: &VersionedTransaction,
+        cluster: ExplorerCluster,
+    ) -> anyhow::Result<Self> {
+        let encoded = crate::swqos::serialization::encode_transaction_shared(transaction)?;
+        let signature = encoded.signature();
+        Ok(Self {
+            signature,
+            base64: encoded.base64().to_string(),
+            explorer_url: cluster.transaction_url(&signature),
+        })
+    }
+}
+
+/// What to do when trade params were built from an event older than the allowed reserve age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StaleReserveAction {
+    /// Re-read pool reserves via RPC, then continue with the trade.
+    #[default]
+    Refresh,
+    /// Reject the trade with a `StaleQuote` error (code [`crate::swqos::common::STALE_QUOTE_ERROR_CODE`]).
+    Abort,
+}
+
+/// Maximum age of the event that produced the pool reserves in trade params.
+///
+/// Age is measured from the params' `grpc_recv_us`; trades without it are never treated as stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleReserveGuard {
+    pub max_age_us: i64,
+    pub action: StaleReserveAction,
+}
+
+impl StaleReserveGuard {
+    pub fn new(max_age: std::time::Duration, action: StaleReserveAction) -> Self {
+        Self { max_age_us: max_age.as_micros().min(i64::MAX as u128) as i64, action }
+    }
+
+    /// Whether reserves observed at `event_us` are too old at `now_us`.
+    #[inline]
+    pub fn is_stale(&self, event_us: i64, now_us: i64) -> bool {
+        now_us.saturating_sub(event_us) > self.max_age_us
+    }
+}
+
+/// What to do with a trade whose instructions (ATA creations + swap + tip) do not fit in one
+/// transaction, e.g. Raydium CLMM with seed accounts and no lookup table. Split trades cannot be
+/// simulated: with `simulate = true` they fail with a `SimulationUnsupported` error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionSplit {
+    /// Send the account setup (ATA creation, SOL wrapping) untipped through the `Default` (RPC)
+    /// route first, wait for it to land, then send the swap with its CU settings. When the swap
+    /// fails, the accounts it would have closed (e.g. the wrapped SOL) are closed on their own.
+    /// Needs an RPC client, the `Default` route and a recent blockhash.
+    #[default]
+    Sequential,
+    /// Send the setup and the swap as one Jito bundle, tipped in the swap transaction.
+    JitoBundle,
+    /// Fail with a "transaction too large" error.
+    Off,
 }
 
 #[derive(Debug, Clone)]
@@ -95,7 +225,7 @@ pub struct TradeConfig {
     pub use_seed_optimize: bool,
     /// Whether to output all SDK logs (timing, SWQOS submit/confirm, WSOL, blacklist, etc.). Default true.
     pub log_enabled: bool,
-    /// Whether to check minimum tip per SWQOS provider (filter out configs below min). Default false to save latency.
+    /// Whether to check minimum tip per SWQOS provider (filter out configs below min). Default true.
     pub check_min_tip: bool,
     /// When true, SWQOS uses the *last* N cores (instead of the first N). Use when main thread / tokio use low-numbered cores to reduce CPU contention. Default false.
     pub swqos_cores_from_end: bool,
@@ -103,6 +233,29 @@ pub struct TradeConfig {
     /// (Astralane, BlockRazor, Glaive) use their MEV-protected endpoints/modes. Glaive HTTP
     /// adds `mev-protect=true`; Glaive QUIC sets auth-frame flag bit 0. Default false.
     pub mev_protection: bool,
+    /// Destinations allowed for `TradingClient::withdraw`. `None` allows any destination. Default None.
+    pub withdraw_allowlist: Option<Vec<Pubkey>>,
+    /// Stale-reserve check applied before buy/sell. `None` disables the check. Default None.
+    pub stale_reserve_guard: Option<StaleReserveGuard>,
+    /// Extra attempts after a confirmed slippage failure, each with refreshed reserves. Default 0 (off).
+    pub slippage_retry_attempts: u32,
+    /// Resends with a fresh blockhash after a confirmation timeout whose blockhash expired, every
+    /// attempt watched until one lands. Default 0 (off).
+    pub blockhash_retry_attempts: u32,
+    /// Simulate every real trade first and abort on failure, waiting at most this long for the
+    /// simulation before sending anyway. `None` disables the gate. Default None.
+    pub simulation_gate: Option<std::time::Duration>,
+    /// `sendTransaction` settings of the `Default` (RPC) route for trades that do not set their
+    /// own. Default skips preflight with 3 node retries.
+    pub rpc_send_options: RpcSendOptions,
+    /// Attach each lane's wire transaction and explorer link to trade results (see
+    /// `TradingClient::buy_detailed`). `None` disables the capture. Default None.
+    pub raw_transactions: Option<ExplorerCluster>,
+    /// Handling of trades too large for one transaction. Default [`TransactionSplit::Sequential`].
+    pub transaction_split: TransactionSplit,
+    /// HTTP client tuning per SWQOS provider (pool size, HTTP/2, TCP_NODELAY, IPv4-only).
+    /// Providers without an entry use the SDK defaults. Default empty.
+    pub swqos_http_tuning: HashMap<SwqosType, HttpClientTuning>,
 }
 
 impl TradeConfig {
@@ -112,15 +265,24 @@ impl TradeConfig {
     /// - `.create_wsol_ata_on_startup(bool)` — check & create WSOL ATA on init (default: true)
     /// - `.use_seed_optimize(bool)`           — seed optimization for ATA ops (default: true)
     /// - `.log_enabled(bool)`                 — SDK timing/SWQOS logs (default: true)
-    /// - `.check_min_tip(bool)`               — filter SWQOS below min tip (default: false)
+    /// - `.check_min_tip(bool)`               — filter SWQOS below min tip (default: true)
     /// - `.swqos_cores_from_end(bool)`        — bind SWQOS to last N cores (default: false)
     /// - `.mev_protection(bool)`              — MEV protection for Astralane/BlockRazor/Glaive (default: false)
+    /// - `.withdraw_allowlist(Vec<Pubkey>)`   — restrict `withdraw` destinations (default: any)
+    /// - `.max_reserve_age(Duration, action)` — refresh/abort on stale event reserves (default: off)
+    /// - `.slippage_retry_attempts(u32)`      — re-quote and resend after slippage failure (default: 0)
+    /// - `.blockhash_retry_attempts(u32)`     — resend after blockhash expiry (default: 0)
+    /// - `.simulation_gate(Duration)`         — simulate before send, abort on failure (default: off)
+    /// - `.rpc_send_options(RpcSendOptions)`  — RPC route preflight / node retries (default: no preflight)
+    /// - `.raw_transactions(ExplorerCluster)` — base64 tx + explorer link in results (default: off)
+    /// - `.transaction_split(TransactionSplit)` — split oversized trades (default: sequential)
+    /// - `.swqos_http_tuning(SwqosType, HttpClientTuning)` — provider HTTP client tuning
     ///
     /// # Example
     /// ```rust,ignore
     /// let config = TradeConfig::builder(rpc_url, swqos_configs, commitment)
     ///     .mev_protection(true)
-    ///     .check_min_tip(true)
+    ///     .check_min_tip(false)
     ///     .log_enabled(false)
     ///     .build();
     /// ```
@@ -157,6 +319,15 @@ pub struct TradeConfigBuilder {
     check_min_tip: bool,
     swqos_cores_from_end: bool,
     mev_protection: bool,
+    withdraw_allowlist: Option<Vec<Pubkey>>,
+    stale_reserve_guard: Option<StaleReserveGuard>,
+    slippage_retry_attempts: u32,
+    blockhash_retry_attempts: u32,
+    simulation_gate: Option<std::time::Duration>,
+    rpc_send_options: RpcSendOptions,
+    raw_transactions: Option<ExplorerCluster>,
+    transaction_split: TransactionSplit,
+    swqos_http_tuning: HashMap<SwqosType, HttpClientTuning>,
 }
 
 impl TradeConfigBuilder {
@@ -168,9 +339,18 @@ impl TradeConfigBuilder {
             create_wsol_ata_on_startup: true,
             use_seed_optimize: true,
             log_enabled: true,
-            check_min_tip: false,
+            check_min_tip: true,
             swqos_cores_from_end: false,
             mev_protection: false,
+            withdraw_allowlist: None,
+            stale_reserve_guard: None,
+            slippage_retry_attempts: 0,
+            blockhash_retry_attempts: 0,
+            simulation_gate: None,
+            rpc_send_options: RpcSendOptions::default(),
+            raw_transactions: None,
+            transaction_split: TransactionSplit::default(),
+            swqos_http_tuning: HashMap::new(),
         }
     }
 
@@ -192,8 +372,10 @@ impl TradeConfigBuilder {
         self
     }
 
-    /// Filter out SWQOS providers whose tip is below their minimum requirement.
-    /// Adds a small check on the hot path; disable for lowest latency. Default: `false`.
+    /// Filter out SWQOS providers whose tip is below their minimum requirement
+    /// ([`crate::swqos::SwqosType::min_tip_sol`]). When no lane is left the trade fails with a
+    /// `TipBelowMinimum` error naming the provider and its minimum.
+    /// Adds a small check on the hot path; disable for lowest latency. Default: `true`.
     pub fn check_min_tip(mut self, v: bool) -> Self {
         self.check_min_tip = v;
         self
@@ -217,6 +399,79 @@ impl TradeConfigBuilder {
         self
     }
 
+    /// Only allow `TradingClient::withdraw` to send to these destinations, guarding against
+    /// fat-fingered addresses in operational tooling. Default: any destination.
+    pub fn withdraw_allowlist(mut self, destinations: Vec<Pubkey>) -> Self {
+        self.withdraw_allowlist = Some(destinations);
+        self
+    }
+
+    /// Treat params whose `grpc_recv_us` is older than `max_age` as stale: either refresh pool
+    /// reserves via RPC before sending or abort with `StaleQuote`. Default: no check.
+    pub fn max_reserve_age(
+        mut self,
+        max_age: std::time::Duration,
+        action: StaleReserveAction,
+    ) -> Self {
+        self.stale_reserve_guard = Some(StaleReserveGuard::new(max_age, action));
+        self
+    }
+
+    /// After a trade confirms as failed with the protocol's slippage error, refetch reserves,
+    /// recompute min-out with the same slippage bps and resend, up to `attempts` times.
+    /// Only applies when `wait_tx_confirmed` is set. Default: `0` (no retry).
+    pub fn slippage_retry_attempts(mut self, attempts: u32) -> Self {
+        self.slippage_retry_attempts = attempts;
+        self
+    }
+
+    /// When a confirmed trade times out and its recent blockhash has expired, rebuild it with a
+    /// fresh blockhash and resend, up to `attempts` times. The earlier signatures stay watched, so
+    /// whichever attempt lands resolves the order. Only applies when `wait_tx_confirmed` is set
+    /// and the params carry `recent_blockhash` (not a durable nonce). Default: `0` (no retry).
+    pub fn blockhash_retry_attempts(mut self, attempts: u32) -> Self {
+        self.blockhash_retry_attempts = attempts;
+        self
+    }
+
+    /// Preflight every real trade with `simulateTransaction` (`replace_recent_blockhash=true`) and
+    /// only send when it succeeds; the protocols' on-chain min-out checks run in the simulation.
+    /// If the simulation takes longer than `max_latency` the trade is sent ungated. Default: off.
+    pub fn simulation_gate(mut self, max_latency: std::time::Duration) -> Self {
+        self.simulation_gate = Some(max_latency);
+        self
+    }
+
+    /// `sendTransaction` settings of the `Default` (RPC) route, e.g. preflight at `confirmed`
+    /// for conservative bots. Trades override it with their own `rpc_send_options`. Default:
+    /// preflight skipped, 3 node retries.
+    pub fn rpc_send_options(mut self, options: RpcSendOptions) -> Self {
+        self.rpc_send_options = options;
+        self
+    }
+
+    /// Capture the base64 wire transaction of every lane, with a `cluster` explorer link, in
+    /// the results of `buy_detailed` / `sell_detailed`. Costs one string copy per lane.
+    pub fn raw_transactions(mut self, cluster: ExplorerCluster) -> Self {
+        self.raw_transactions = Some(cluster);
+        self
+    }
+
+    /// Split trades whose ATA creations + swap + tip do not fit in one transaction into a setup
+    /// and a swap transaction, or fail them with [`TransactionSplit::Off`]. Default: sequential.
+    pub fn transaction_split(mut self, split: TransactionSplit) -> Self {
+        self.transaction_split = split;
+        self
+    }
+
+    /// Build `swqos_type`'s HTTP client with `tuning` on top of the SDK defaults. Only HTTP
+    /// transports are affected; a bad setting fails that provider's client at startup.
+    /// 为该 SWQOS 的 HTTP 客户端指定调优参数（仅 HTTP 传输生效）。
+    pub fn swqos_http_tuning(mut self, swqos_type: SwqosType, tuning: HttpClientTuning) -> Self {
+        self.swqos_http_tuning.insert(swqos_type, tuning);
+        self
+    }
+
     /// Consume the builder and produce a [`TradeConfig`].
     pub fn build(self) -> TradeConfig {
         TradeConfig {
@@ -229,6 +484,15 @@ impl TradeConfigBuilder {
             check_min_tip: self.check_min_tip,
             swqos_cores_from_end: self.swqos_cores_from_end,
             mev_protection: self.mev_protection,
+            withdraw_allowlist: self.withdraw_allowlist,
+            stale_reserve_guard: self.stale_reserve_guard,
+            slippage_retry_attempts: self.slippage_retry_attempts,
+            blockhash_retry_attempts: self.blockhash_retry_attempts,
+            simulation_gate: self.simulation_gate,
+            rpc_send_options: self.rpc_send_options,
+            raw_transactions: self.raw_transactions,
+            transaction_split: self.transaction_split,
+            swqos_http_tuning: self.swqos_http_tuning,
         }
     }
 }
@@ -0,0 +1,354 @@
+//! Wallet activity monitor: normalized token/SOL movements for a set of wallets.
+//! 钱包行为监听：把指定钱包的代币买入/卖出、SOL 变动归一化后通过 channel 推送。
+//!
+//! Built on the RPC websocket (`logsSubscribe` mentions + `getTransaction`) rather than the
+//! Yellowstone streamer, so it works with any RPC endpoint. See [`WalletWatcher`] for what that
+//! costs.
+
+use crate::common::subscription_handle::SubscriptionHandle;
+use crate::common::types::SolanaRpcClient;
+use futures::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+
+/// Normalized wallet activity. Token amounts are raw (base units).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletEvent {
+    /// Token balance owned by `wallet` increased (buy or incoming transfer).
+    TokenReceived { wallet: Pubkey, mint: Pubkey, amount: u64, signature: Signature, slot: u64 },
+    /// Token balance owned by `wallet` decreased (sell or outgoing transfer).
+    TokenSold { wallet: Pubkey, mint: Pubkey, amount: u64, signature: Signature, slot: u64 },
+    /// Native SOL balance changed; the network fee is excluded when `wallet` paid it.
+    SolMoved { wallet: Pubkey, lamports_delta: i64, signature: Signature, slot: u64 },
+}
+
+/// Streams normalized activity for a set of wallets over the RPC websocket.
+///
+/// This does not use a Yellowstone/streamer subscription. Each wallet gets its own
+/// `logsSubscribe` (most RPCs accept a single address per `mentions` filter) and every
+/// notification costs one `getTransaction`, so:
+/// - events arrive at `confirmed` commitment plus one RPC round trip, slower than a gRPC stream;
+/// - busy wallets spend RPC credits per transaction and may hit provider rate limits;
+/// - a failed `getTransaction` drops that transaction's events (logged, not retried);
+/// - a websocket disconnect ends the stream; resubscribe to resume.
+pub struct WalletWatcher;
+
+impl WalletWatcher {
+    /// Subscribe to `wallets` and stream their confirmed activity.
+    ///
+    /// Failed transactions are skipped. [`SubscriptionHandle::shutdown`] unsubscribes every
+    /// wallet and closes the websocket; dropping the receiver stops each wallet at its next
+    /// event.
+    pub async fn subscribe(
+        ws_url: &str,
+        rpc: Arc<SolanaRpcClient>,
+        wallets: Vec<Pubkey>,
+    ) -> Result<(mpsc::UnboundedReceiver<WalletEvent>, SubscriptionHandle), anyhow::Error> {
+        if wallets.is_empty() {
+            return Err(anyhow::anyhow!("WalletWatcher requires at least one wallet"));
+        }
+        let client = PubsubClient::new(ws_url).await?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let worker = tokio::spawn(async move {
+            futures::future::join_all(
+                wallets
+                    .iter()
+                    .map(|wallet| watch_wallet(&client, &rpc, *wallet, &tx, stop_rx.clone())),
+            )
+            .await;
+            let _ = client.shutdown().await;
+        });
+        // `shutdown` aborts the handle's task right after `unsub_fn`; the task only awaits the
+        // worker, so the worker still gets to unsubscribe and close the socket.
+        let task = tokio::spawn(async move {
+            let _ = worker.await;
+        });
+        let unsub_fn = Box::new(move || {
+            let _ = stop_tx.send(true);
+        });
+        Ok((rx, SubscriptionHandle { task, unsub_fn }))
+    }
+}
+
+/// Resolves once a stop was requested; never resolves if the handle was dropped without one.
+async fn stop_requested(stop: &mut watch::Receiver<bool>) {
+    loop {
+        if *stop.borrow_and_update() {
+            return;
+        }
+        if stop.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+async fn watch_wallet(
+    client: &PubsubClient,
+    rpc: &SolanaRpcClient,
+    wallet: Pubkey,
+    tx: &mpsc::UnboundedSender<WalletEvent>,
+    mut stop: watch::Receiver<bool>,
+) {
+    let (mut stream, unsubscribe) = match client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![wallet.to_string()]),
+            RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) },
+        )
+        .await
+    {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            if crate::common::sdk_log::sdk_log_enabled() {
+                tracing::warn!(target: "sol_trade_sdk", "logsSubscribe {} failed: {}", wallet, e);
+            }
+            return;
+        }
+    };
+    loop {
+        let response = tokio::select! {
+            response = stream.next() => match response {
+                Some(response) => response,
+                None => break,
+            },
+            _ = stop_requested(&mut stop) => break,
+        };
+        if response.value.err.is_some() {
+            continue;
+        }
+        let Ok(signature) = Signature::from_str(&response.value.signature) else {
+            continue;
+        };
+        match fetch_wallet_events(rpc, &wallet, signature, response.context.slot).await {
+            Ok(events) => {
+                for event in events {
+                    if tx.send(event).is_err() {
+                        unsubscribe().await;
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    tracing::warn!(
+                        target: "sol_trade_sdk",
+                        "WalletWatcher failed to load {}: {}",
+                        signature,
+                        e
+                    );
+                }
+            }
+        }
+    }
+    unsubscribe().await;
+}
+
+async fn fetch_wallet_events(
+    rpc: &SolanaRpcClient,
+    wallet: &Pubkey,
+    signature: Signature,
+    slot: u64,
+) -> Result<Vec<WalletEvent>, anyhow::Error> {
+    let tx = rpc
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                max_supported_transaction_version: Some(0),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )
+        .await?;
+    let meta = tx
+        .transaction
+        .meta
+        .ok_or_else(|| anyhow::anyhow!("transaction {} has no meta", signature))?;
+    let decoded = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow::anyhow!("transaction {} could not be decoded", signature))?;
+
+    // Incoming transfers don't need the wallet's signature and v0 transactions can load it
+    // through a lookup table, so balances are indexed over static + loaded keys.
+    let mut account_keys = decoded.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            account_keys.push(Pubkey::from_str(key)?);
+        }
+    }
+    let sol_delta =
+        sol_delta(wallet, &account_keys, &meta.pre_balances, &meta.post_balances, meta.fee);
+
+    let owner = wallet.to_string();
+    Ok(wallet_events(
+        wallet,
+        signature,
+        slot,
+        sol_delta,
+        &owned_token_amounts(&owner, &meta.pre_token_balances),
+        &owned_token_amounts(&owner, &meta.post_token_balances),
+    ))
+}
+
+/// Lamport change of `wallet`, with the network fee added back when it paid it (index 0).
+fn sol_delta(
+    wallet: &Pubkey,
+    account_keys: &[Pubkey],
+    pre_balances: &[u64],
+    post_balances: &[u64],
+    fee: u64,
+) -> i64 {
+    account_keys
+        .iter()
+        .position(|key| key == wallet)
+        .and_then(|index| {
+            let pre = *pre_balances.get(index)? as i64;
+            let post = *post_balances.get(index)? as i64;
+            let fee = if index == 0 { fee as i64 } else { 0 };
+            Some(post - pre + fee)
+        })
+        .unwrap_or(0)
+}
+
+/// Sum of raw token amounts per mint across all token accounts owned by `owner`.
+fn owned_token_amounts(
+    owner: &str,
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+) -> HashMap<Pubkey, u64> {
+    let mut amounts = HashMap::new();
+    if let OptionSerializer::Some(balances) = balances {
+        for balance in balances {
+            if !matches!(&balance.owner, OptionSerializer::Some(o) if o == owner) {
+                continue;
+            }
+            let (Ok(mint), Ok(amount)) =
+                (Pubkey::from_str(&balance.mint), balance.ui_token_amount.amount.parse::<u64>())
+            else {
+                continue;
+            };
+            let total: &mut u64 = amounts.entry(mint).or_default();
+            *total = total.saturating_add(amount);
+        }
+    }
+    amounts
+}
+
+/// Diff pre/post balances into normalized events (SOL first, then tokens by mint).
+pub fn wallet_events(
+    wallet: &Pubkey,
+    signature: Signature,
+    slot: u64,
+    sol_delta: i64,
+    pre_tokens: &HashMap<Pubkey, u64>,
+    post_tokens: &HashMap<Pubkey, u64>,
+) -> Vec<WalletEvent> {
+    let mut events = Vec::new();
+    if sol_delta != 0 {
+        events.push(WalletEvent::SolMoved {
+            wallet: *wallet,
+            lamports_delta: sol_delta,
+            signature,
+            slot,
+        });
+    }
+    let mut mints: Vec<&Pubkey> = pre_tokens.keys().chain(post_tokens.keys()).collect();
+    mints.sort();
+    mints.dedup();
+    for mint in mints {
+        let pre = pre_tokens.get(mint).copied().unwrap_or(0);
+        let post = post_tokens.get(mint).copied().unwrap_or(0);
+        if post > pre {
+            events.push(WalletEvent::TokenReceived {
+                wallet: *wallet,
+                mint: *mint,
+                amount: post - pre,
+                signature,
+                slot,
+            });
+        } else if pre > post {
+            events.push(WalletEvent::TokenSold {
+                wallet: *wallet,
+                mint: *mint,
+                amount: pre - post,
+                signature,
+                slot,
+            });
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_balances_into_events() {
+        let wallet = Pubkey::new_unique();
+        let bought = Pubkey::new_unique();
+        let sold = Pubkey::new_unique();
+        let unchanged = Pubkey::new_unique();
+        let signature = Signature::default();
+        let pre = HashMap::from([(sold, 500), (unchanged, 7)]);
+        let post = HashMap::from([(bought, 1_000), (sold, 200), (unchanged, 7)]);
+
+        let events = wallet_events(&wallet, signature, 42, -1_000_000, &pre, &post);
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0],
+            WalletEvent::SolMoved { wallet, lamports_delta: -1_000_000, signature, slot: 42 }
+        );
+        assert!(events.contains(&WalletEvent::TokenReceived {
+            wallet,
+            mint: bought,
+            amount: 1_000,
+            signature,
+            slot: 42,
+        }));
+        assert!(events.contains(&WalletEvent::TokenSold {
+            wallet,
+            mint: sold,
+            amount: 300,
+            signature,
+            slot: 42,
+        }));
+    }
+
+    #[test]
+    fn sol_delta_covers_non_signers_and_lookup_table_keys() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let from_lookup_table = Pubkey::new_unique();
+        // Static keys first, then loaded writable addresses, as in the balance arrays.
+        let keys = [payer, recipient, from_lookup_table];
+        let pre = [10_000_000, 1_000, 2_000];
+        let post = [8_995_000, 501_000, 502_000];
+
+        assert_eq!(sol_delta(&payer, &keys, &pre, &post, 5_000), -1_000_000);
+        assert_eq!(sol_delta(&recipient, &keys, &pre, &post, 5_000), 500_000);
+        assert_eq!(sol_delta(&from_lookup_table, &keys, &pre, &post, 5_000), 500_000);
+        assert_eq!(sol_delta(&Pubkey::new_unique(), &keys, &pre, &post, 5_000), 0);
+    }
+
+    #[test]
+    fn no_change_yields_no_events() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let balances = HashMap::from([(mint, 10)]);
+
+        assert!(wallet_events(&wallet, Signature::default(), 1, 0, &balances, &balances).is_empty());
+    }
+}
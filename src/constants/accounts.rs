@@ -8,6 +8,11 @@ pub const SYSTEM_PROGRAM_META: solana_sdk::instruction::AccountMeta =
         is_writable: false,
     };
 
+pub const COMPUTE_BUDGET_PROGRAM: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// SPL Memo (v2).
+pub const MEMO_PROGRAM: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
 pub const TOKEN_PROGRAM: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 pub const TOKEN_PROGRAM_META: solana_sdk::instruction::AccountMeta =
     solana_sdk::instruction::AccountMeta {
@@ -0,0 +1,313 @@
+//! Devnet faucet and test-token helpers, behind the `devnet-tools` feature.
+//!
+//! Lets new users run the whole pipeline (params, quotes, signing, SWQoS routing, confirmation)
+//! without risking mainnet funds: [`request_airdrop`] funds a fresh keypair, [`create_test_mint`]
+//! creates an SPL mint with a supply held by the payer, and [`seed_pumpswap_pool`] opens a
+//! PumpSwap pool of that mint against WSOL. PumpSwap is deployed under the same program id on
+//! devnet, so the pool trades through the regular
+//! [`PumpSwapParams`](crate::trading::core::params::PumpSwapParams) path:
+//!
+//! ```no_run
+//! # async fn run(rpc: &sol_trade_sdk::common::SolanaRpcClient) -> anyhow::Result<()> {
+//! use sol_trade_sdk::devnet::{create_test_mint, request_airdrop, seed_pumpswap_pool};
+//! use sol_trade_sdk::trading::core::params::PumpSwapParams;
+//! use solana_sdk::signature::{Keypair, Signer};
+//!
+//! let payer = Keypair::new();
+//! request_airdrop(rpc, &payer.pubkey(), 2_000_000_000).await?;
+//! let test_mint = create_test_mint(rpc, &payer, 6, 1_000_000_000_000).await?;
+//! let pool = seed_pumpswap_pool(rpc, &payer, &test_mint.mint, 500_000_000_000, 1_000_000_000)
+//!     .await?;
+//! let params = PumpSwapParams::from_pool_address_by_rpc(rpc, &pool).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every helper that sends a transaction refuses to run against mainnet-beta.
+//! Devnet 测试工具：空投、创建测试代币、在 devnet 上建立 PumpSwap 池，无需主网资金即可体验完整交易流程。
+
+use crate::common::spl_associated_token_account::{
+    create_associated_token_account_idempotent, get_associated_token_address_with_program_id,
+};
+use crate::common::spl_token::{initialize_mint2, mint_to};
+use crate::common::SolanaRpcClient;
+use crate::constants::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM, TOKEN_PROGRAM, TOKEN_PROGRAM_2022,
+    WSOL_TOKEN_ACCOUNT,
+};
+use crate::instruction::utils::pumpswap::{accounts, seeds};
+use crate::trading::common::get_mint_program_and_decimals;
+use crate::trading::common::wsol_manager::handle_wsol;
+use anyhow::anyhow;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use solana_system_interface::instruction as system_instruction;
+use std::time::{Duration, Instant};
+
+/// Public devnet RPC endpoint (rate-limited; airdrops are capped per request and per day).
+pub const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+
+const MAINNET_BETA_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+/// Size of an SPL Token mint account without extensions.
+const MINT_ACCOUNT_LEN: usize = 82;
+const AIRDROP_CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// PumpSwap `create_pool` instruction discriminator.
+pub const CREATE_POOL_DISCRIMINATOR: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
+const POOL_LP_MINT_SEED: &[u8] = b"pool_lp_mint";
+/// Pool index used by [`seed_pumpswap_pool`]; one pool per creator and mint.
+pub const SEED_POOL_INDEX: u16 = 0;
+
+/// Mint created by [`create_test_mint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestMint {
+    pub mint: Pubkey,
+    /// The payer's associated token account, holding the whole supply.
+    pub token_account: Pubkey,
+    pub decimals: u8,
+}
+
+/// Fail unless `rpc` points at a cluster other than mainnet-beta (devnet, testnet or a local
+/// validator).
+pub async fn ensure_not_mainnet(rpc: &SolanaRpcClient) -> Result<(), anyhow::Error> {
+    let genesis_hash = rpc.get_genesis_hash().await?;
+    if genesis_hash.to_string() == MAINNET_BETA_GENESIS_HASH {
+        return Err(anyhow!("devnet tools refuse to run against mainnet-beta ({})", rpc.url()));
+    }
+    Ok(())
+}
+
+/// Airdrop `lamports` to `to` and wait until the airdrop is confirmed.
+pub async fn request_airdrop(
+    rpc: &SolanaRpcClient,
+    to: &Pubkey,
+    lamports: u64,
+) -> Result<Signature, anyhow::Error> {
+    ensure_not_mainnet(rpc).await?;
+    let signature = rpc.request_airdrop(to, lamports).await?;
+    let started = Instant::now();
+    loop {
+        let confirmed = rpc
+            .confirm_transaction_with_commitment(&signature, CommitmentConfig::confirmed())
+            .await?
+            .value;
+        if confirmed {
+            return Ok(signature);
+        }
+        if started.elapsed() > AIRDROP_CONFIRM_TIMEOUT {
+            return Err(anyhow!(
+                "airdrop {} not confirmed after {:?}",
+                signature,
+                AIRDROP_CONFIRM_TIMEOUT
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Create an SPL Token mint with `payer` as mint authority (no freeze authority) and mint
+/// `supply` base units to the payer's associated token account.
+pub async fn create_test_mint(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    decimals: u8,
+    supply: u64,
+) -> Result<TestMint, anyhow::Error> {
+    ensure_not_mainnet(rpc).await?;
+    let mint = Keypair::new();
+    let rent = rpc.get_minimum_balance_for_rent_exemption(MINT_ACCOUNT_LEN).await?;
+    let instructions =
+        create_test_mint_instructions(&payer.pubkey(), &mint.pubkey(), rent, decimals, supply)?;
+    send_and_confirm(rpc, &instructions, payer, &[&mint]).await?;
+    Ok(TestMint {
+        mint: mint.pubkey(),
+        token_account: get_associated_token_address_with_program_id(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &TOKEN_PROGRAM,
+        ),
+        decimals,
+    })
+}
+
+/// Instructions of [`create_test_mint`]: allocate and initialize `mint`, create the payer's
+/// token account and mint `supply` into it.
+pub fn create_test_mint_instructions(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    rent_lamports: u64,
+    decimals: u8,
+    supply: u64,
+) -> Result<Vec<Instruction>, anyhow::Error> {
+    let token_account = get_associated_token_address_with_program_id(payer, mint, &TOKEN_PROGRAM);
+    Ok(vec![
+        system_instruction::create_account(
+            payer,
+            mint,
+            rent_lamports,
+            MINT_ACCOUNT_LEN as u64,
+            &TOKEN_PROGRAM,
+        ),
+        initialize_mint2(&TOKEN_PROGRAM, mint, payer, None, decimals)?,
+        create_associated_token_account_idempotent(payer, payer, mint, &TOKEN_PROGRAM),
+        mint_to(&TOKEN_PROGRAM, mint, &token_account, payer, supply, &[])?,
+    ])
+}
+
+/// PumpSwap pool PDA (seeds: ["pool", index, creator, base_mint, quote_mint]).
+pub fn pumpswap_pool_pda(
+    index: u16,
+    creator: &Pubkey,
+    base_mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seeds::POOL_SEED,
+            &index.to_le_bytes(),
+            creator.as_ref(),
+            base_mint.as_ref(),
+            quote_mint.as_ref(),
+        ],
+        &accounts::AMM_PROGRAM,
+    )
+    .0
+}
+
+/// PumpSwap `create_pool` of `base_mint` against WSOL, funded from `creator`'s token accounts.
+/// `creator` is also the pool's coin creator. Wrap `quote_amount` lamports into the creator's
+/// WSOL account first (see [`seed_pumpswap_pool`]).
+pub fn create_pumpswap_pool_instruction(
+    creator: &Pubkey,
+    base_mint: &Pubkey,
+    base_token_program: &Pubkey,
+    index: u16,
+    base_amount: u64,
+    quote_amount: u64,
+) -> Instruction {
+    let quote_mint = WSOL_TOKEN_ACCOUNT;
+    let pool = pumpswap_pool_pda(index, creator, base_mint, &quote_mint);
+    let lp_mint =
+        Pubkey::find_program_address(&[POOL_LP_MINT_SEED, pool.as_ref()], &accounts::AMM_PROGRAM).0;
+    let ata = get_associated_token_address_with_program_id;
+
+    let mut data = Vec::with_capacity(8 + 2 + 8 + 8 + 32 + 1);
+    data.extend_from_slice(&CREATE_POOL_DISCRIMINATOR);
+    data.extend_from_slice(&index.to_le_bytes());
+    data.extend_from_slice(&base_amount.to_le_bytes());
+    data.extend_from_slice(&quote_amount.to_le_bytes());
+    data.extend_from_slice(creator.as_ref());
+    // is_mayhem_mode; deployments without the flag ignore the trailing byte.
+    data.push(0);
+
+    Instruction {
+        program_id: accounts::AMM_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(accounts::GLOBAL_ACCOUNT, false),
+            AccountMeta::new(*creator, true),
+            AccountMeta::new_readonly(*base_mint, false),
+            AccountMeta::new_readonly(quote_mint, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new(ata(creator, base_mint, base_token_program), false),
+            AccountMeta::new(ata(creator, &quote_mint, &TOKEN_PROGRAM), false),
+            AccountMeta::new(ata(creator, &lp_mint, &TOKEN_PROGRAM_2022), false),
+            AccountMeta::new(ata(&pool, base_mint, base_token_program), false),
+            AccountMeta::new(ata(&pool, &quote_mint, &TOKEN_PROGRAM), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_2022, false),
+            AccountMeta::new_readonly(*base_token_program, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(accounts::EVENT_AUTHORITY, false),
+            AccountMeta::new_readonly(accounts::AMM_PROGRAM, false),
+        ],
+        data,
+    }
+}
+
+/// Open a PumpSwap pool of `base_mint` against WSOL with `base_amount` tokens from `creator`'s
+/// associated token account and `quote_lamports` of SOL, and return the pool address.
+pub async fn seed_pumpswap_pool(
+    rpc: &SolanaRpcClient,
+    creator: &Keypair,
+    base_mint: &Pubkey,
+    base_amount: u64,
+    quote_lamports: u64,
+) -> Result<Pubkey, anyhow::Error> {
+    ensure_not_mainnet(rpc).await?;
+    let (base_token_program, _) = get_mint_program_and_decimals(rpc, base_mint).await?;
+    let mut instructions = handle_wsol(&creator.pubkey(), quote_lamports).to_vec();
+    instructions.push(create_pumpswap_pool_instruction(
+        &creator.pubkey(),
+        base_mint,
+        &base_token_program,
+        SEED_POOL_INDEX,
+        base_amount,
+        quote_lamports,
+    ));
+    send_and_confirm(rpc, &instructions, creator, &[]).await?;
+    Ok(pumpswap_pool_pda(SEED_POOL_INDEX, &creator.pubkey(), base_mint, &WSOL_TOKEN_ACCOUNT))
+}
+
+async fn send_and_confirm(
+    rpc: &SolanaRpcClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    extra_signers: &[&Keypair],
+) -> Result<Signature, anyhow::Error> {
+    let mut signers = vec![payer];
+    signers.extend_from_slice(extra_signers);
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &signers,
+        recent_blockhash,
+    );
+    Ok(rpc.send_and_confirm_transaction(&transaction).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_pool_lays_out_args_and_derives_pool_accounts() {
+        let creator = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ix = create_pumpswap_pool_instruction(&creator, &mint, &TOKEN_PROGRAM, 3, 500, 700);
+
+        assert_eq!(&ix.data[..8], &CREATE_POOL_DISCRIMINATOR);
+        assert_eq!(&ix.data[8..10], &3u16.to_le_bytes());
+        assert_eq!(&ix.data[10..18], &500u64.to_le_bytes());
+        assert_eq!(&ix.data[18..26], &700u64.to_le_bytes());
+        assert_eq!(&ix.data[26..58], creator.as_ref());
+
+        let pool = pumpswap_pool_pda(3, &creator, &mint, &WSOL_TOKEN_ACCOUNT);
+        assert_eq!(ix.accounts.len(), 18);
+        assert_eq!(ix.accounts[0].pubkey, pool);
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(
+            ix.accounts[9].pubkey,
+            get_associated_token_address_with_program_id(&pool, &mint, &TOKEN_PROGRAM)
+        );
+    }
+
+    #[test]
+    fn test_mint_supply_lands_in_the_payer_account() {
+        let (payer, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ixs = create_test_mint_instructions(&payer, &mint, 1_461_600, 6, 42).unwrap();
+        assert_eq!(ixs.len(), 4);
+        assert_eq!(ixs[1].data[..2], [20, 6]);
+        let token_account =
+            get_associated_token_address_with_program_id(&payer, &mint, &TOKEN_PROGRAM);
+        assert_eq!(ixs[3].accounts[1].pubkey, token_account);
+        assert_eq!(ixs[3].data[1..], 42u64.to_le_bytes());
+    }
+}
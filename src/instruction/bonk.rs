@@ -77,8 +77,8 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // ========================================
         let amount_in: u64 = params.input_amount.unwrap_or(0);
         let share_fee_rate: u64 = 0;
-        let minimum_amount_out: u64 = match params.fixed_output_amount {
-            Some(fixed_amount) => fixed_amount,
+        let minimum_amount_out: u64 = match params.min_out_override {
+            Some(min_out) => min_out,
             None => get_buy_token_amount_from_sol_amount(
                 amount_in,
                 protocol_params.virtual_base,
@@ -118,7 +118,7 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(6);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(6);
 
         if params.create_input_mint_ata {
             push_create_or_wrap_user_token_account(
@@ -230,8 +230,8 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // Trade calculation and account address preparation
         // ========================================
         let share_fee_rate: u64 = 0;
-        let minimum_amount_out: u64 = match params.fixed_output_amount {
-            Some(fixed_amount) => fixed_amount,
+        let minimum_amount_out: u64 = match params.min_out_override {
+            Some(min_out) => min_out,
             None => get_sell_sol_amount_from_token_amount(
                 amount,
                 protocol_params.virtual_base,
@@ -271,7 +271,7 @@ impl InstructionBuilder for BonkInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(4);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(4);
 
         if params.create_output_mint_ata {
             push_create_user_token_account(
@@ -368,10 +368,14 @@ mod tests {
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
             wait_tx_confirmed: false,
+            confirmation: Default::default(),
+            rpc_send_options: Default::default(),
+            raw_transactions: None,
             protocol_params: DexParamEnum::Bonk(bonk_params()),
             open_seed_optimize: true,
             swqos_clients: Arc::new(Vec::new()),
             middleware_manager: None,
+            compute_budget_manager: None,
             durable_nonce: None,
             with_tip: false,
             create_input_mint_ata: false,
@@ -379,7 +383,11 @@ mod tests {
             create_output_mint_ata: false,
             close_output_mint_ata: false,
             fixed_output_amount: Some(42),
+            min_out_override: None,
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            memo: None,
             simulate: true,
             log_enabled: false,
             wait_for_all_submits: false,
@@ -390,6 +398,20 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            backrun_target: None,
+            simulation_gate: None,
+            latency_budget: None,
+            signing_pool: None,
+            additional_signers: Vec::new(),
+            fee_payer: None,
+            send_mode: Default::default(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            transaction_split: Default::default(),
+            simulation_prelude: Vec::new(),
+            prior_signatures: Vec::new(),
+            wsol_guard: None,
+            order_claim: None,
         }
     }
 
@@ -423,6 +445,19 @@ mod tests {
         assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 100_000);
     }
 
+    #[tokio::test]
+    async fn bonk_sell_exact_in_uses_min_out_override() {
+        let mut params = swap_params(TradeType::Sell);
+        params.fixed_output_amount = None;
+        params.min_out_override = Some(42);
+        let instructions = BonkInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let ix = instructions.last().unwrap();
+
+        assert_eq!(&ix.data[..8], SELL_EXECT_IN_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 42);
+    }
+
     #[tokio::test]
     async fn bonk_usd1_buy_create_input_builds_usd1_ata_not_wsol_wrap() {
         let mut params = swap_params(TradeType::Buy);
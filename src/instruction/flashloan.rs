@@ -0,0 +1,496 @@
+//! Flash-loan wrappers: borrow → arb instructions → repay inside one transaction.
+//! 闪电贷封装：在同一笔交易内 借款 → 套利指令 → 还款，实现无本金套利。
+//!
+//! Solend and Kamino check the repay by the absolute index of the borrow instruction, and marginfi
+//! by the index of `end_flashloan`. The SDK transaction builder rewrites those indices from the
+//! final instruction list ([`fix_flash_loan_indices`]), so caller compute-budget instructions and
+//! custom compute budget managers are accounted for; transactions built elsewhere need the right
+//! `instruction_offset` up front (see [`builder_prefix_len`]).
+
+use crate::instruction::marginfi::{self, MarginfiAccounts};
+use crate::trading::common::compute_budget_manager::{
+    ComputeBudgetManager, ComputeBudgetRequest, DefaultComputeBudgetManager,
+};
+use anyhow::anyhow;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use std::sync::Arc;
+
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const SOLEND_PROGRAM: Pubkey = pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo");
+    pub const KAMINO_LEND_PROGRAM: Pubkey = pubkey!("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
+    pub const SYSVAR_INSTRUCTIONS: Pubkey = pubkey!("Sysvar1nstructions1111111111111111111111111");
+}
+
+const SOLEND_FLASH_BORROW_TAG: u8 = 19;
+const SOLEND_FLASH_REPAY_TAG: u8 = 20;
+pub const KAMINO_FLASH_BORROW_DISCRIMINATOR: [u8; 8] = [135, 231, 52, 167, 7, 52, 212, 193];
+pub const KAMINO_FLASH_REPAY_DISCRIMINATOR: [u8; 8] = [185, 117, 0, 203, 96, 245, 180, 186];
+
+/// Solend reserve accounts (from the reserve account / Solend API).
+#[derive(Debug, Clone)]
+pub struct SolendReserve {
+    pub reserve: Pubkey,
+    pub lending_market: Pubkey,
+    pub liquidity_supply: Pubkey,
+    pub liquidity_fee_receiver: Pubkey,
+    /// Host fee share; pass `liquidity_fee_receiver` again when there is no host.
+    pub host_fee_receiver: Pubkey,
+}
+
+/// Kamino (KLend) reserve accounts.
+#[derive(Debug, Clone)]
+pub struct KaminoReserve {
+    pub reserve: Pubkey,
+    pub lending_market: Pubkey,
+    pub liquidity_mint: Pubkey,
+    pub liquidity_supply: Pubkey,
+    pub liquidity_fee_receiver: Pubkey,
+}
+
+/// marginfi bank plus the borrower's marginfi account.
+#[derive(Debug, Clone)]
+pub struct MarginfiBank {
+    pub group: Pubkey,
+    pub marginfi_account: Pubkey,
+    pub bank: Pubkey,
+    /// Bank/oracle pairs of the account's active balances, checked by `end_flashloan`.
+    pub health_accounts: Vec<Pubkey>,
+}
+
+/// Lending protocol to borrow from.
+#[derive(Debug, Clone)]
+pub enum FlashLoanSource {
+    Solend(SolendReserve),
+    Kamino(KaminoReserve),
+    Marginfi(MarginfiBank),
+}
+
+/// Flash-loan sizing. `amount` is normally the first leg's input amount.
+#[derive(Debug, Clone)]
+pub struct FlashLoanParams {
+    pub source: FlashLoanSource,
+    /// Signs the borrow/repay (usually the payer; otherwise add it as an additional signer).
+    pub borrower: Pubkey,
+    /// Borrower token account receiving the loan and paying it back.
+    pub token_account: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+    /// Flash-loan fee from the reserve config, in bps (marginfi charges none).
+    pub fee_bps: u64,
+    /// Guaranteed output of the wrapped instructions (e.g. min out of the last leg). When set it
+    /// must cover the repay amount, otherwise wrapping fails instead of sending a losing trade.
+    pub expected_amount_out: Option<u64>,
+}
+
+impl FlashLoanParams {
+    /// Borrowed amount plus fee (rounded up) that the repay will take from `token_account`.
+    pub fn repay_amount(&self) -> u64 {
+        let fee = (self.amount as u128 * self.fee_bps as u128).div_ceil(10_000);
+        self.amount.saturating_add(u64::try_from(fee).unwrap_or(u64::MAX))
+    }
+}
+
+/// Number of instructions the SDK transaction builder places before the business instructions:
+/// nonce advance, tip transfer and whatever `compute_budget_manager` (the default one when `None`)
+/// emits for `request`.
+pub fn builder_prefix_len(
+    durable_nonce: bool,
+    with_tip: bool,
+    request: &ComputeBudgetRequest,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+) -> usize {
+    let mut budget = Vec::new();
+    match compute_budget_manager {
+        Some(manager) => manager.extend_instructions(&mut budget, request),
+        None => DefaultComputeBudgetManager.extend_instructions(&mut budget, request),
+    }
+    durable_nonce as usize + with_tip as usize + budget.len()
+}
+
+/// Wrap `instructions` in a flash loan: `[borrow, ..instructions, repay]` (marginfi adds
+/// `start_flashloan`/`end_flashloan` around those).
+///
+/// `instruction_offset` is the index the first returned instruction will have in the final
+/// transaction, e.g. [`builder_prefix_len`]. Transactions built by the SDK get the indices
+/// rewritten by [`fix_flash_loan_indices`], so there it only has to stay below 256.
+pub fn wrap_with_flash_loan(
+    params: &FlashLoanParams,
+    instructions: &[Instruction],
+    instruction_offset: usize,
+) -> Result<Vec<Instruction>, anyhow::Error> {
+    if params.amount == 0 {
+        return Err(anyhow!("Flash loan amount must be greater than 0"));
+    }
+    if instructions.is_empty() {
+        return Err(anyhow!("Flash loan needs at least one instruction to wrap"));
+    }
+    let repay_amount = params.repay_amount();
+    if let Some(expected) = params.expected_amount_out {
+        if expected < repay_amount {
+            return Err(anyhow!(
+                "Flash loan not covered: expected out {} < repay amount {}",
+                expected,
+                repay_amount
+            ));
+        }
+    }
+
+    let mut wrapped = Vec::with_capacity(instructions.len() + 4);
+    match &params.source {
+        FlashLoanSource::Solend(reserve) => {
+            let borrow_index = borrow_index_u8(instruction_offset)?;
+            wrapped.push(solend_flash_borrow(params, reserve));
+            wrapped.extend_from_slice(instructions);
+            wrapped.push(solend_flash_repay(params, reserve, borrow_index));
+        }
+        FlashLoanSource::Kamino(reserve) => {
+            let borrow_index = borrow_index_u8(instruction_offset)?;
+            wrapped.push(kamino_flash_borrow(params, reserve));
+            wrapped.extend_from_slice(instructions);
+            wrapped.push(kamino_flash_repay(params, reserve, borrow_index));
+        }
+        FlashLoanSource::Marginfi(bank) => {
+            let lending = MarginfiAccounts {
+                group: bank.group,
+                marginfi_account: bank.marginfi_account,
+                authority: params.borrower,
+                bank: bank.bank,
+                token_account: params.token_account,
+                token_program: params.token_program,
+            };
+            // start, borrow, ..instructions, repay, end
+            let end_index = instruction_offset + instructions.len() + 3;
+            wrapped.push(marginfi::start_flashloan(
+                &bank.marginfi_account,
+                &params.borrower,
+                end_index as u64,
+            ));
+            // Health is only checked by end_flashloan, so no observation accounts on the borrow.
+            wrapped.push(marginfi::borrow(&lending, params.amount, &[]));
+            wrapped.extend_from_slice(instructions);
+            wrapped.push(marginfi::repay(&lending, repay_amount, true));
+            wrapped.push(marginfi::end_flashloan(
+                &bank.marginfi_account,
+                &params.borrower,
+                &bank.health_accounts,
+            ));
+        }
+    }
+    Ok(wrapped)
+}
+
+/// Point every Solend/Kamino flash repay at the preceding borrow and every marginfi
+/// `start_flashloan` at the following `end_flashloan`, using their positions in `instructions`,
+/// the complete instruction list of the transaction.
+pub fn fix_flash_loan_indices(instructions: &mut [Instruction]) -> Result<(), anyhow::Error> {
+    let mut solend_borrow = None;
+    let mut kamino_borrow = None;
+    let mut marginfi_start = None;
+    for index in 0..instructions.len() {
+        let ix = &mut instructions[index];
+        if ix.program_id == accounts::SOLEND_PROGRAM {
+            match ix.data.first() {
+                Some(&SOLEND_FLASH_BORROW_TAG) => solend_borrow = Some(index),
+                Some(&SOLEND_FLASH_REPAY_TAG) if ix.data.len() == 10 => {
+                    if let Some(borrow) = solend_borrow.take() {
+                        ix.data[9] = borrow_index_u8(borrow)?;
+                    }
+                }
+                _ => {}
+            }
+        } else if ix.program_id == accounts::KAMINO_LEND_PROGRAM {
+            if ix.data.starts_with(&KAMINO_FLASH_BORROW_DISCRIMINATOR) {
+                kamino_borrow = Some(index);
+            } else if ix.data.starts_with(&KAMINO_FLASH_REPAY_DISCRIMINATOR) && ix.data.len() == 17
+            {
+                if let Some(borrow) = kamino_borrow.take() {
+                    ix.data[16] = borrow_index_u8(borrow)?;
+                }
+            }
+        } else if ix.program_id == marginfi::accounts::MARGINFI_PROGRAM {
+            if ix.data.starts_with(&marginfi::START_FLASHLOAN_DISCRIMINATOR) && ix.data.len() == 16
+            {
+                marginfi_start = Some(index);
+            } else if ix.data == marginfi::END_FLASHLOAN_DISCRIMINATOR {
+                if let Some(start) = marginfi_start.take() {
+                    instructions[start].data[8..16].copy_from_slice(&(index as u64).to_le_bytes());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+fn borrow_index_u8(index: usize) -> Result<u8, anyhow::Error> {
+    u8::try_from(index).map_err(|_| anyhow!("Flash borrow instruction index {} exceeds 255", index))
+}
+
+fn amount_data(prefix: &[u8], amount: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(prefix.len() + 9);
+    data.extend_from_slice(prefix);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+fn solend_flash_borrow(params: &FlashLoanParams, reserve: &SolendReserve) -> Instruction {
+    let (market_authority, _) =
+        Pubkey::find_program_address(&[reserve.lending_market.as_ref()], &accounts::SOLEND_PROGRAM);
+    Instruction {
+        program_id: accounts::SOLEND_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(reserve.liquidity_supply, false),
+            AccountMeta::new(params.token_account, false),
+            AccountMeta::new(reserve.reserve, false),
+            AccountMeta::new_readonly(reserve.lending_market, false),
+            AccountMeta::new_readonly(market_authority, false),
+            AccountMeta::new_readonly(accounts::SYSVAR_INSTRUCTIONS, false),
+            AccountMeta::new_readonly(params.token_program, false),
+        ],
+        data: amount_data(&[SOLEND_FLASH_BORROW_TAG], params.amount),
+    }
+}
+
+fn solend_flash_repay(
+    params: &FlashLoanParams,
+    reserve: &SolendReserve,
+    borrow_index: u8,
+) -> Instruction {
+    let mut data = amount_data(&[SOLEND_FLASH_REPAY_TAG], params.amount);
+    data.push(borrow_index);
+    Instruction {
+        program_id: accounts::SOLEND_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(params.token_account, false),
+            AccountMeta::new(reserve.liquidity_supply, false),
+            AccountMeta::new(reserve.liquidity_fee_receiver, false),
+            AccountMeta::new(reserve.host_fee_receiver, false),
+            AccountMeta::new(reserve.reserve, false),
+            AccountMeta::new_readonly(reserve.lending_market, false),
+            AccountMeta::new_readonly(params.borrower, true),
+            AccountMeta::new_readonly(accounts::SYSVAR_INSTRUCTIONS, false),
+            AccountMeta::new_readonly(params.token_program, false),
+        ],
+        data,
+    }
+}
+
+/// KLend borrow and repay take the same accounts (reserve liquidity supply, then user token account).
+fn kamino_accounts(params: &FlashLoanParams, reserve: &KaminoReserve) -> Vec<AccountMeta> {
+    let (market_authority, _) = Pubkey::find_program_address(
+        &[b"lma", reserve.lending_market.as_ref()],
+        &accounts::KAMINO_LEND_PROGRAM,
+    );
+    vec![
+        AccountMeta::new_readonly(params.borrower, true),
+        AccountMeta::new_readonly(market_authority, false),
+        AccountMeta::new_readonly(reserve.lending_market, false),
+        AccountMeta::new(reserve.reserve, false),
+        AccountMeta::new_readonly(reserve.liquidity_mint, false),
+        AccountMeta::new(reserve.liquidity_supply, false),
+        AccountMeta::new(params.token_account, false),
+        AccountMeta::new(reserve.liquidity_fee_receiver, false),
+        // No referrer: optional accounts are passed as the program id.
+        AccountMeta::new_readonly(accounts::KAMINO_LEND_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::KAMINO_LEND_PROGRAM, false),
+        AccountMeta::new_readonly(accounts::SYSVAR_INSTRUCTIONS, false),
+        AccountMeta::new_readonly(params.token_program, false),
+    ]
+}
+
+fn kamino_flash_borrow(params: &FlashLoanParams, reserve: &KaminoReserve) -> Instruction {
+    Instruction {
+        program_id: accounts::KAMINO_LEND_PROGRAM,
+        accounts: kamino_accounts(params, reserve),
+        data: amount_data(&KAMINO_FLASH_BORROW_DISCRIMINATOR, params.amount),
+    }
+}
+
+fn kamino_flash_repay(
+    params: &FlashLoanParams,
+    reserve: &KaminoReserve,
+    borrow_index: u8,
+) -> Instruction {
+    let mut data = amount_data(&KAMINO_FLASH_REPAY_DISCRIMINATOR, params.amount);
+    data.push(borrow_index);
+    Instruction {
+        program_id: accounts::KAMINO_LEND_PROGRAM,
+        accounts: kamino_accounts(params, reserve),
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TOKEN_PROGRAM;
+
+    fn params(source: FlashLoanSource, fee_bps: u64) -> FlashLoanParams {
+        FlashLoanParams {
+            source,
+            borrower: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM,
+            amount: 1_000_000,
+            fee_bps,
+            expected_amount_out: None,
+        }
+    }
+
+    fn solend() -> FlashLoanSource {
+        FlashLoanSource::Solend(SolendReserve {
+            reserve: Pubkey::new_unique(),
+            lending_market: Pubkey::new_unique(),
+            liquidity_supply: Pubkey::new_unique(),
+            liquidity_fee_receiver: Pubkey::new_unique(),
+            host_fee_receiver: Pubkey::new_unique(),
+        })
+    }
+
+    fn swap_ix() -> Instruction {
+        Instruction { program_id: Pubkey::new_unique(), accounts: vec![], data: vec![9] }
+    }
+
+    fn budget_request() -> ComputeBudgetRequest {
+        ComputeBudgetRequest {
+            unit_price: 1_000,
+            unit_limit: 200_000,
+            dex_type: None,
+            is_buy: true,
+        }
+    }
+
+    #[test]
+    fn solend_repay_points_at_borrow_index() {
+        let params = params(solend(), 30);
+        let offset = builder_prefix_len(false, true, &budget_request(), None);
+        let wrapped = wrap_with_flash_loan(&params, &[swap_ix(), swap_ix()], offset).unwrap();
+
+        assert_eq!(offset, 3);
+        assert_eq!(wrapped.len(), 4);
+        assert_eq!(wrapped[0].data[0], SOLEND_FLASH_BORROW_TAG);
+        let repay = wrapped.last().unwrap();
+        assert_eq!(repay.data[0], SOLEND_FLASH_REPAY_TAG);
+        assert_eq!(&repay.data[1..9], &1_000_000u64.to_le_bytes());
+        assert_eq!(repay.data[9], offset as u8);
+    }
+
+    #[test]
+    fn marginfi_start_points_at_end_flashloan() {
+        let bank = MarginfiBank {
+            group: Pubkey::new_unique(),
+            marginfi_account: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            health_accounts: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let params = params(FlashLoanSource::Marginfi(bank), 0);
+        let wrapped = wrap_with_flash_loan(&params, &[swap_ix()], 2).unwrap();
+
+        assert_eq!(wrapped.len(), 5);
+        assert_eq!(&wrapped[0].data[8..16], &(2u64 + 4).to_le_bytes());
+        assert_eq!(wrapped[4].data, marginfi::END_FLASHLOAN_DISCRIMINATOR.to_vec());
+        assert_eq!(wrapped[4].accounts.len(), 4);
+    }
+
+    #[test]
+    fn builder_rewrites_indices_from_the_final_instructions() {
+        use crate::trading::common::build_transaction;
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+        use solana_hash::Hash;
+        use solana_sdk::signature::Keypair;
+
+        /// Emits a heap frame on top of the usual price and limit.
+        struct WithHeap;
+        impl ComputeBudgetManager for WithHeap {
+            fn extend_instructions(
+                &self,
+                instructions: &mut Vec<Instruction>,
+                request: &ComputeBudgetRequest,
+            ) {
+                instructions.push(ComputeBudgetInstruction::request_heap_frame(64 * 1024));
+                instructions
+                    .push(ComputeBudgetInstruction::set_compute_unit_price(request.unit_price));
+            }
+        }
+
+        let payer = Arc::new(Keypair::new());
+        let params = params(solend(), 30);
+        // Guessed for the default layout (tip, price, limit), missing the caller's limit.
+        let offset = builder_prefix_len(false, true, &budget_request(), None);
+        let mut business = vec![ComputeBudgetInstruction::set_compute_unit_limit(300_000)];
+        business.extend(wrap_with_flash_loan(&params, &[swap_ix()], offset).unwrap());
+        let manager: Arc<dyn ComputeBudgetManager> = Arc::new(WithHeap);
+        let tx = build_transaction(
+            &payer,
+            200_000,
+            1_000,
+            &business,
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            Some(&manager),
+            None,
+            true,
+            true,
+            &Pubkey::new_unique(),
+            0.001,
+            None,
+        )
+        .unwrap();
+
+        let keys = tx.message.static_account_keys();
+        let instructions = tx.message.instructions();
+        let borrow = instructions
+            .iter()
+            .position(|ix| {
+                keys[ix.program_id_index as usize] == accounts::SOLEND_PROGRAM
+                    && ix.data[0] == SOLEND_FLASH_BORROW_TAG
+            })
+            .unwrap();
+        // tip, heap frame, price, caller limit
+        assert_eq!(borrow, 4);
+        assert_eq!(instructions.last().unwrap().data[9], borrow as u8);
+    }
+
+    #[test]
+    fn prefix_len_counts_what_the_manager_emits() {
+        use crate::trading::common::TipOnlyComputeBudgetManager;
+
+        let request = budget_request();
+        assert_eq!(builder_prefix_len(true, true, &request, None), 4);
+        let tip_only: Arc<dyn ComputeBudgetManager> =
+            Arc::new(TipOnlyComputeBudgetManager::new(None));
+        assert_eq!(builder_prefix_len(true, true, &request, Some(&tip_only)), 3);
+    }
+
+    #[test]
+    fn marginfi_end_index_follows_the_final_layout() {
+        let bank = MarginfiBank {
+            group: Pubkey::new_unique(),
+            marginfi_account: Pubkey::new_unique(),
+            bank: Pubkey::new_unique(),
+            health_accounts: vec![],
+        };
+        let params = params(FlashLoanSource::Marginfi(bank), 0);
+        let mut instructions = vec![swap_ix(), swap_ix()];
+        instructions.extend(wrap_with_flash_loan(&params, &[swap_ix()], 0).unwrap());
+        fix_flash_loan_indices(&mut instructions).unwrap();
+
+        assert_eq!(&instructions[2].data[8..16], &6u64.to_le_bytes());
+    }
+
+    #[test]
+    fn rejects_uncovered_repay() {
+        let mut params = params(solend(), 30);
+        assert_eq!(params.repay_amount(), 1_003_000);
+
+        params.expected_amount_out = Some(1_002_999);
+        assert!(wrap_with_flash_loan(&params, &[swap_ix()], 0).is_err());
+        params.expected_amount_out = Some(1_003_000);
+        assert!(wrap_with_flash_loan(&params, &[swap_ix()], 0).is_ok());
+    }
+}
@@ -0,0 +1,184 @@
+//! marginfi v2 lending-account instructions (deposit / borrow / repay / withdraw / flashloan).
+//! marginfi 借贷账户指令，供闪电贷与杠杆仓位复用。
+//!
+//! `health_accounts` are the bank/oracle pairs of every balance active after the instruction;
+//! marginfi checks account health against them.
+
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+pub mod accounts {
+    use solana_sdk::{pubkey, pubkey::Pubkey};
+    pub const MARGINFI_PROGRAM: Pubkey = pubkey!("MFv2hWf31Z9kbCa1snEPYctwafyhdvnV7FZnsebVacA");
+    pub const SYSVAR_INSTRUCTIONS: Pubkey = pubkey!("Sysvar1nstructions1111111111111111111111111");
+}
+
+pub mod seeds {
+    pub const LIQUIDITY_VAULT_SEED: &[u8] = b"liquidity_vault";
+    pub const LIQUIDITY_VAULT_AUTHORITY_SEED: &[u8] = b"liquidity_vault_auth";
+}
+
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [171, 94, 235, 103, 82, 64, 212, 140];
+pub const BORROW_DISCRIMINATOR: [u8; 8] = [4, 126, 116, 53, 48, 5, 212, 31];
+pub const REPAY_DISCRIMINATOR: [u8; 8] = [79, 209, 172, 177, 222, 51, 173, 151];
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [36, 72, 74, 19, 210, 210, 192, 192];
+pub const START_FLASHLOAN_DISCRIMINATOR: [u8; 8] = [14, 131, 33, 220, 81, 186, 180, 107];
+pub const END_FLASHLOAN_DISCRIMINATOR: [u8; 8] = [105, 124, 201, 106, 153, 2, 8, 156];
+
+/// Accounts shared by every lending-account instruction on one bank.
+#[derive(Debug, Clone, Copy)]
+pub struct MarginfiAccounts {
+    pub group: Pubkey,
+    pub marginfi_account: Pubkey,
+    pub authority: Pubkey,
+    pub bank: Pubkey,
+    /// Authority's token account for the bank mint.
+    pub token_account: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn get_liquidity_vault_pda(bank: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::LIQUIDITY_VAULT_SEED, bank.as_ref()],
+        &accounts::MARGINFI_PROGRAM,
+    )
+    .0
+}
+
+pub fn get_liquidity_vault_authority_pda(bank: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[seeds::LIQUIDITY_VAULT_AUTHORITY_SEED, bank.as_ref()],
+        &accounts::MARGINFI_PROGRAM,
+    )
+    .0
+}
+
+/// Discriminator + u64 amount + optional `Option<bool>` flag (Borsh).
+fn amount_data(discriminator: &[u8; 8], amount: u64, flag: Option<Option<bool>>) -> Vec<u8> {
+    let mut data = Vec::with_capacity(19);
+    data.extend_from_slice(discriminator);
+    data.extend_from_slice(&amount.to_le_bytes());
+    match flag {
+        Some(Some(value)) => data.extend_from_slice(&[1, value as u8]),
+        Some(None) => data.push(0),
+        None => {}
+    }
+    data
+}
+
+#[inline]
+fn readonly_metas(keys: &[Pubkey]) -> impl Iterator<Item = AccountMeta> + '_ {
+    keys.iter().map(|key| AccountMeta::new_readonly(*key, false))
+}
+
+pub fn deposit(lending: &MarginfiAccounts, amount: u64) -> Instruction {
+    Instruction {
+        program_id: accounts::MARGINFI_PROGRAM,
+        accounts: vec![
+            AccountMeta::new_readonly(lending.group, false),
+            AccountMeta::new(lending.marginfi_account, false),
+            AccountMeta::new_readonly(lending.authority, true),
+            AccountMeta::new(lending.bank, false),
+            AccountMeta::new(lending.token_account, false),
+            AccountMeta::new(get_liquidity_vault_pda(&lending.bank), false),
+            AccountMeta::new_readonly(lending.token_program, false),
+        ],
+        data: amount_data(&DEPOSIT_DISCRIMINATOR, amount, Some(None)),
+    }
+}
+
+/// Borrow `amount` into `token_account`. Inside a flashloan pass no `health_accounts`.
+pub fn borrow(lending: &MarginfiAccounts, amount: u64, health_accounts: &[Pubkey]) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new_readonly(lending.group, false),
+        AccountMeta::new(lending.marginfi_account, false),
+        AccountMeta::new_readonly(lending.authority, true),
+        AccountMeta::new(lending.bank, false),
+        AccountMeta::new(lending.token_account, false),
+        AccountMeta::new(get_liquidity_vault_authority_pda(&lending.bank), false),
+        AccountMeta::new(get_liquidity_vault_pda(&lending.bank), false),
+        AccountMeta::new_readonly(lending.token_program, false),
+    ];
+    metas.extend(readonly_metas(health_accounts));
+    Instruction {
+        program_id: accounts::MARGINFI_PROGRAM,
+        accounts: metas,
+        data: amount_data(&BORROW_DISCRIMINATOR, amount, None),
+    }
+}
+
+/// Repay `amount`; with `repay_all` the whole liability (interest included) is repaid and closed.
+pub fn repay(lending: &MarginfiAccounts, amount: u64, repay_all: bool) -> Instruction {
+    Instruction {
+        program_id: accounts::MARGINFI_PROGRAM,
+        accounts: vec![
+            AccountMeta::new_readonly(lending.group, false),
+            AccountMeta::new(lending.marginfi_account, false),
+            AccountMeta::new_readonly(lending.authority, true),
+            AccountMeta::new(lending.bank, false),
+            AccountMeta::new(lending.token_account, false),
+            AccountMeta::new(get_liquidity_vault_pda(&lending.bank), false),
+            AccountMeta::new_readonly(lending.token_program, false),
+        ],
+        data: amount_data(&REPAY_DISCRIMINATOR, amount, Some(Some(repay_all))),
+    }
+}
+
+/// Withdraw `amount`; with `withdraw_all` the whole deposit is withdrawn and the balance closed.
+pub fn withdraw(
+    lending: &MarginfiAccounts,
+    amount: u64,
+    withdraw_all: bool,
+    health_accounts: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new_readonly(lending.group, false),
+        AccountMeta::new(lending.marginfi_account, false),
+        AccountMeta::new_readonly(lending.authority, true),
+        AccountMeta::new(lending.bank, false),
+        AccountMeta::new(lending.token_account, false),
+        AccountMeta::new(get_liquidity_vault_authority_pda(&lending.bank), false),
+        AccountMeta::new(get_liquidity_vault_pda(&lending.bank), false),
+        AccountMeta::new_readonly(lending.token_program, false),
+    ];
+    metas.extend(readonly_metas(health_accounts));
+    Instruction {
+        program_id: accounts::MARGINFI_PROGRAM,
+        accounts: metas,
+        data: amount_data(&WITHDRAW_DISCRIMINATOR, amount, Some(Some(withdraw_all))),
+    }
+}
+
+/// `end_index` is the absolute index of the matching [`end_flashloan`] in the transaction.
+pub fn start_flashloan(
+    marginfi_account: &Pubkey,
+    authority: &Pubkey,
+    end_index: u64,
+) -> Instruction {
+    Instruction {
+        program_id: accounts::MARGINFI_PROGRAM,
+        accounts: vec![
+            AccountMeta::new(*marginfi_account, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(accounts::SYSVAR_INSTRUCTIONS, false),
+        ],
+        data: amount_data(&START_FLASHLOAN_DISCRIMINATOR, end_index, None),
+    }
+}
+
+pub fn end_flashloan(
+    marginfi_account: &Pubkey,
+    authority: &Pubkey,
+    health_accounts: &[Pubkey],
+) -> Instruction {
+    let mut metas = vec![
+        AccountMeta::new(*marginfi_account, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+    metas.extend(readonly_metas(health_accounts));
+    Instruction {
+        program_id: accounts::MARGINFI_PROGRAM,
+        accounts: metas,
+        data: END_FLASHLOAN_DISCRIMINATOR.to_vec(),
+    }
+}
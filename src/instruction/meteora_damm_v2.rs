@@ -1,5 +1,6 @@
 use crate::{
     instruction::{
+        quote_side::{pool_accepts_quote, quote_is_first},
         token_account_setup::{
             push_close_wsol_if_needed, push_create_or_wrap_user_token_account,
             push_create_user_token_account,
@@ -38,19 +39,22 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             .downcast_ref::<MeteoraDammV2Params>()
             .ok_or_else(|| anyhow!("Invalid protocol params for MeteoraDammV2"))?;
 
-        let is_wsol = protocol_params.token_a_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.token_b_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
-        let is_usdc = protocol_params.token_a_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            || protocol_params.token_b_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        if !pool_accepts_quote(
+            &protocol_params.token_a_mint,
+            &protocol_params.token_b_mint,
+            &params.input_mint,
+        ) {
+            return Err(anyhow!("Pool must contain WSOL, USDC or the requested quote mint"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let is_a_in = protocol_params.token_a_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.token_a_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let is_a_in = quote_is_first(
+            &protocol_params.token_a_mint,
+            &protocol_params.token_b_mint,
+            &params.input_mint,
+        )?;
         let input_mint =
             if is_a_in { protocol_params.token_a_mint } else { protocol_params.token_b_mint };
         let input_token_program =
@@ -68,8 +72,9 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
                 (amount_out, amount_in)
             }
             SWAP_MODE_EXACT_IN | SWAP_MODE_PARTIAL_FILL => {
-                let minimum_amount_out = params.fixed_output_amount.ok_or_else(|| {
-                    anyhow!("fixed_output_amount must be set for MeteoraDammV2 swap2 min output")
+                // No on-client quote for concentrated liquidity; the caller supplies the min out.
+                let minimum_amount_out = params.min_out_override.ok_or_else(|| {
+                    anyhow!("min_output_token_amount must be set for MeteoraDammV2 swaps")
                 })?;
                 (amount_in, minimum_amount_out)
             }
@@ -94,7 +99,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(6);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(6);
 
         if params.create_input_mint_ata {
             push_create_or_wrap_user_token_account(
@@ -118,7 +123,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
         }
 
         // Create buy instruction
-        let mut account_metas = Vec::with_capacity(
+        let mut account_metas = crate::common::instruction_pool::account_meta_vec(
             13 + usize::from(protocol_params.referral_token_account.is_some())
                 + usize::from(protocol_params.include_rate_limiter_sysvar),
         );
@@ -179,19 +184,22 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
             return Err(anyhow!("Token amount is not set"));
         }
 
-        let is_wsol = protocol_params.token_b_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.token_a_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
-        let is_usdc = protocol_params.token_b_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            || protocol_params.token_a_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        if !pool_accepts_quote(
+            &protocol_params.token_a_mint,
+            &protocol_params.token_b_mint,
+            &params.output_mint,
+        ) {
+            return Err(anyhow!("Pool must contain WSOL, USDC or the requested quote mint"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let is_a_in = protocol_params.token_b_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.token_b_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let is_a_in = quote_is_first(
+            &protocol_params.token_b_mint,
+            &protocol_params.token_a_mint,
+            &params.output_mint,
+        )?;
         let input_mint =
             if is_a_in { protocol_params.token_a_mint } else { protocol_params.token_b_mint };
         let input_token_program =
@@ -209,8 +217,9 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
                 (amount_out, amount_in)
             }
             SWAP_MODE_EXACT_IN | SWAP_MODE_PARTIAL_FILL => {
-                let minimum_amount_out = params.fixed_output_amount.ok_or_else(|| {
-                    anyhow!("fixed_output_amount must be set for MeteoraDammV2 swap2 min output")
+                // No on-client quote for concentrated liquidity; the caller supplies the min out.
+                let minimum_amount_out = params.min_out_override.ok_or_else(|| {
+                    anyhow!("min_output_token_amount must be set for MeteoraDammV2 swaps")
                 })?;
                 (amount_in, minimum_amount_out)
             }
@@ -235,7 +244,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(4);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(4);
 
         if params.create_output_mint_ata {
             push_create_user_token_account(
@@ -248,7 +257,7 @@ impl InstructionBuilder for MeteoraDammV2InstructionBuilder {
         }
 
         // Create buy instruction
-        let mut account_metas = Vec::with_capacity(
+        let mut account_metas = crate::common::instruction_pool::account_meta_vec(
             13 + usize::from(protocol_params.referral_token_account.is_some())
                 + usize::from(protocol_params.include_rate_limiter_sysvar),
         );
@@ -350,18 +359,26 @@ mod tests {
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
             wait_tx_confirmed: false,
+            confirmation: Default::default(),
+            rpc_send_options: Default::default(),
+            raw_transactions: None,
             protocol_params: DexParamEnum::MeteoraDammV2(protocol_params),
             open_seed_optimize: true,
             swqos_clients: Arc::new(Vec::new()),
             middleware_manager: None,
+            compute_budget_manager: None,
             durable_nonce: None,
             with_tip: false,
             create_input_mint_ata: false,
             close_input_mint_ata: false,
             create_output_mint_ata: false,
             close_output_mint_ata: false,
-            fixed_output_amount: Some(1),
+            fixed_output_amount: None,
+            min_out_override: Some(1),
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            memo: None,
             simulate: true,
             log_enabled: false,
             wait_for_all_submits: false,
@@ -372,6 +389,20 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            backrun_target: None,
+            simulation_gate: None,
+            latency_budget: None,
+            signing_pool: None,
+            additional_signers: Vec::new(),
+            fee_payer: None,
+            send_mode: Default::default(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            transaction_split: Default::default(),
+            simulation_prelude: Vec::new(),
+            prior_signatures: Vec::new(),
+            wsol_guard: None,
+            order_claim: None,
         }
     }
 
@@ -424,10 +455,11 @@ mod tests {
     #[tokio::test]
     async fn meteora_swap2_exact_out_uses_amount_out_then_max_input() {
         let protocol_params = meteora_params(None).with_swap_mode(SWAP_MODE_EXACT_OUT);
-        let instructions = MeteoraDammV2InstructionBuilder
-            .build_buy_instructions(&swap_params(protocol_params))
-            .await
-            .unwrap();
+        let mut params = swap_params(protocol_params);
+        params.fixed_output_amount = Some(1);
+        params.min_out_override = None;
+        let instructions =
+            MeteoraDammV2InstructionBuilder.build_buy_instructions(&params).await.unwrap();
         let ix = instructions.last().unwrap();
 
         assert_eq!(&ix.data[..8], SWAP2_DISCRIMINATOR);
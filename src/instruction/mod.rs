@@ -1,9 +1,12 @@
 pub mod bonk;
+pub mod flashloan;
+pub mod marginfi;
 pub mod meteora_damm_v2;
 pub mod pumpfun;
 pub(crate) mod pumpfun_ix_data;
 pub mod pumpswap;
 pub(crate) mod pumpswap_ix_data;
+pub(crate) mod quote_side;
 pub mod raydium_amm_v4;
 pub mod raydium_cpmm;
 pub(crate) mod token_account_setup;
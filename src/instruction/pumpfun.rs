@@ -168,6 +168,11 @@ fn build_buy(params: &SwapParams) -> Result<Vec<Instruction>> {
 
 #[inline]
 fn build_sell(params: &SwapParams) -> Result<Vec<Instruction>> {
+    if params.fixed_output_amount.is_some() {
+        return Err(anyhow!(
+            "PumpFun has no exact-output sell; use min_out_override for the minimum quote out"
+        ));
+    }
     if should_use_v2_layout(params)? {
         build_sell_unified(params)
     } else {
@@ -247,7 +252,7 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
     let user_volume_accumulator = get_user_volume_accumulator_pda(&params.payer.pubkey())
         .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
 
-    let mut instructions = Vec::with_capacity(2);
+    let mut instructions = crate::common::instruction_pool::instruction_vec(2);
 
     if params.create_output_mint_ata {
         instructions.extend(
@@ -274,8 +279,11 @@ fn build_buy_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
             creator,
             lamports_in,
         );
-        if params.use_exact_sol_amount.unwrap_or(true) {
-            let min_tokens_out = calculate_with_slippage_sell(buy_token_amount, slippage_bp);
+        // A min-out override only fits the exact-quote-in instruction.
+        if params.min_out_override.is_some() || params.use_exact_sol_amount.unwrap_or(true) {
+            let min_tokens_out = params
+                .min_out_override
+                .unwrap_or_else(|| calculate_with_slippage_sell(buy_token_amount, slippage_bp));
             encode_pumpfun_buy_exact_quote_in_ix_data(lamports_in, min_tokens_out, ix_version)
         } else {
             let max_sol_cost = calculate_with_slippage_buy(lamports_in, slippage_bp);
@@ -366,8 +374,8 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
         })?
     };
 
-    let min_sol_output = if let Some(fixed) = params.fixed_output_amount {
-        fixed
+    let min_sol_output = if let Some(min_out) = params.min_out_override {
+        min_out
     } else {
         let creator = protocol_params.effective_creator_for_trade();
         let sol_amount = get_sell_sol_amount_from_token_amount(
@@ -409,7 +417,7 @@ fn build_sell_legacy(params: &SwapParams) -> Result<Vec<Instruction>> {
     let user_volume_accumulator = get_user_volume_accumulator_pda(&params.payer.pubkey())
         .ok_or_else(|| anyhow!("user_volume_accumulator PDA derivation failed"))?;
 
-    let mut instructions = Vec::with_capacity(2);
+    let mut instructions = crate::common::instruction_pool::instruction_vec(2);
     let sell_data = encode_pumpfun_sell_ix_data(
         token_amount,
         min_sol_output,
@@ -579,7 +587,7 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
             &quote_token_program,
         );
 
-    let mut instructions = Vec::with_capacity(6);
+    let mut instructions = crate::common::instruction_pool::instruction_vec(6);
 
     if params.create_output_mint_ata {
         instructions.extend(
@@ -603,8 +611,11 @@ fn build_buy_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
             creator,
             lamports_in,
         );
-        if params.use_exact_sol_amount.unwrap_or(true) {
-            let min_tokens_out = calculate_with_slippage_sell(buy_token_amount, slippage_bp);
+        // A min-out override only fits the exact-quote-in instruction.
+        if params.min_out_override.is_some() || params.use_exact_sol_amount.unwrap_or(true) {
+            let min_tokens_out = params
+                .min_out_override
+                .unwrap_or_else(|| calculate_with_slippage_sell(buy_token_amount, slippage_bp));
             (
                 encode_pumpfun_buy_exact_quote_in_ix_data(
                     lamports_in,
@@ -718,8 +729,8 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
         })?
     };
 
-    let min_sol_output = if let Some(fixed) = params.fixed_output_amount {
-        fixed
+    let min_sol_output = if let Some(min_out) = params.min_out_override {
+        min_out
     } else {
         let creator = protocol_params.effective_creator_for_trade();
         let sol_amount = get_sell_sol_amount_from_token_amount(
@@ -812,7 +823,7 @@ fn build_sell_unified(params: &SwapParams) -> Result<Vec<Instruction>> {
             &quote_token_program,
         );
 
-    let mut instructions = Vec::with_capacity(4);
+    let mut instructions = crate::common::instruction_pool::instruction_vec(4);
 
     if params.create_output_mint_ata {
         push_create_user_token_account(
@@ -947,10 +958,14 @@ mod tests {
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
             wait_tx_confirmed: false,
+            confirmation: Default::default(),
+            rpc_send_options: Default::default(),
+            raw_transactions: None,
             protocol_params: DexParamEnum::PumpFun(params),
             open_seed_optimize: true,
             swqos_clients: Arc::new(Vec::new()),
             middleware_manager: None,
+            compute_budget_manager: None,
             durable_nonce: None,
             with_tip: true,
             create_input_mint_ata: false,
@@ -958,7 +973,11 @@ mod tests {
             create_output_mint_ata: true,
             close_output_mint_ata: false,
             fixed_output_amount: None,
+            min_out_override: None,
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            memo: None,
             simulate: false,
             log_enabled: false,
             wait_for_all_submits: false,
@@ -969,6 +988,20 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: Some(true),
+            backrun_target: None,
+            simulation_gate: None,
+            latency_budget: None,
+            signing_pool: None,
+            additional_signers: Vec::new(),
+            fee_payer: None,
+            send_mode: Default::default(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            transaction_split: Default::default(),
+            simulation_prelude: Vec::new(),
+            prior_signatures: Vec::new(),
+            wsol_guard: None,
+            order_claim: None,
         }
     }
 
@@ -1128,7 +1161,8 @@ mod tests {
             &[],
             Some(solana_hash::Hash::new_unique()),
             None,
-            "PumpFun",
+            None,
+            Some(crate::trading::factory::DexType::PumpFun),
             true,
             true,
             &Pubkey::new_unique(),
@@ -1163,7 +1197,8 @@ mod tests {
             &[],
             Some(solana_hash::Hash::new_unique()),
             None,
-            "PumpFun",
+            None,
+            Some(crate::trading::factory::DexType::PumpFun),
             true,
             true,
             &Pubkey::new_unique(),
@@ -1507,14 +1542,14 @@ mod tests {
     }
 
     #[test]
-    fn pumpfun_v1_sell_fixed_output_uses_min_sol_directly() {
+    fn pumpfun_v1_sell_min_out_override_uses_min_sol_directly() {
         let mint = pump_mint();
         let mut params = swap_params_for_buy(mint, TOKEN_PROGRAM);
         params.trade_type = crate::swqos::TradeType::Sell;
         params.input_mint = mint;
         params.output_mint = crate::constants::SOL_TOKEN_ACCOUNT;
         params.create_output_mint_ata = false;
-        params.fixed_output_amount = Some(42);
+        params.min_out_override = Some(42);
 
         let instructions = build_sell(&params).unwrap();
         let ix = instructions.last().unwrap();
@@ -1529,14 +1564,14 @@ mod tests {
     }
 
     #[test]
-    fn pumpfun_v2_usdc_sell_fixed_output_uses_min_quote_directly() {
+    fn pumpfun_v2_usdc_sell_min_out_override_uses_min_quote_directly() {
         let mint = pump_mint();
         let mut params = swap_params_for_buy(mint, TOKEN_PROGRAM);
         params.trade_type = crate::swqos::TradeType::Sell;
         params.input_mint = mint;
         params.output_mint = crate::constants::USDC_TOKEN_ACCOUNT;
         params.create_output_mint_ata = false;
-        params.fixed_output_amount = Some(42);
+        params.min_out_override = Some(42);
         if let DexParamEnum::PumpFun(protocol_params) = &mut params.protocol_params {
             *protocol_params =
                 protocol_params.clone().with_quote_mint(crate::constants::USDC_TOKEN_ACCOUNT);
@@ -1553,4 +1588,37 @@ mod tests {
         );
         assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 42);
     }
+
+    #[test]
+    fn pumpfun_sell_rejects_fixed_output() {
+        let mint = pump_mint();
+        let mut params = swap_params_for_buy(mint, TOKEN_PROGRAM);
+        params.trade_type = crate::swqos::TradeType::Sell;
+        params.input_mint = mint;
+        params.output_mint = crate::constants::SOL_TOKEN_ACCOUNT;
+        params.fixed_output_amount = Some(42);
+
+        assert!(build_sell(&params).is_err());
+    }
+
+    #[test]
+    fn pumpfun_buy_min_out_override_uses_exact_quote_in() {
+        let mut params = swap_params_for_buy(pump_mint(), TOKEN_PROGRAM);
+        params.create_output_mint_ata = false;
+        params.min_out_override = Some(42);
+        params.use_exact_sol_amount = Some(false);
+
+        let instructions = build_buy(&params).unwrap();
+        let ix = instructions.last().unwrap();
+
+        assert_eq!(
+            &ix.data[..8],
+            crate::instruction::utils::pumpfun::BUY_EXACT_SOL_IN_DISCRIMINATOR
+        );
+        assert_eq!(
+            u64::from_le_bytes(ix.data[8..16].try_into().unwrap()),
+            params.input_amount.unwrap()
+        );
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 42);
+    }
 }
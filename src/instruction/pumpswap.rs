@@ -134,7 +134,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         {
             return Err(anyhow!("PumpSwap buy request mints do not match the supplied pool"));
         }
-        let fee_basis_points = protocol_params.fee_basis_points;
+        let fee_basis_points = protocol_params.quoted_fee_basis_points();
 
         let (token_amount, sol_amount) = if let Some(output_amount) = params.fixed_output_amount {
             if output_amount >= pool_base_token_reserves {
@@ -164,7 +164,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             )
             .map_err(anyhow::Error::msg)?;
             // min_quote_amount_out, base_amount_in
-            (result.min_quote, params.input_amount.unwrap_or(0))
+            (params.min_out_override.unwrap_or(result.min_quote), params.input_amount.unwrap_or(0))
         };
 
         let user_base_token_account =
@@ -195,7 +195,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(6);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(6);
 
         if create_input_ata {
             // Determine wrap amount based on instruction type:
@@ -228,7 +228,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         }
 
         // Create buy instruction
-        let mut accounts = Vec::with_capacity(28);
+        let mut accounts = crate::common::instruction_pool::account_meta_vec(28);
         accounts.extend([
             AccountMeta::new(pool, false),                          // pool_id
             AccountMeta::new(params.payer.pubkey(), true),          // user (signer)
@@ -291,11 +291,15 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         if quote_is_wsol_or_usdc {
             let ix_data = if params.fixed_output_amount.is_some() {
                 encode_pumpswap_buy_ix_data(token_amount, sol_amount, track_volume)
-            } else if params.use_exact_sol_amount.unwrap_or(true) {
-                let min_base_amount_out = crate::utils::calc::common::calculate_with_slippage_sell(
-                    token_amount,
-                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-                );
+            } else if params.min_out_override.is_some()
+                || params.use_exact_sol_amount.unwrap_or(true)
+            {
+                let min_base_amount_out = params.min_out_override.unwrap_or_else(|| {
+                    crate::utils::calc::common::calculate_with_slippage_sell(
+                        token_amount,
+                        params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                    )
+                });
                 encode_pumpswap_buy_exact_quote_in_ix_data(
                     params.input_amount.unwrap_or(0),
                     min_base_amount_out,
@@ -391,7 +395,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         {
             return Err(anyhow!("PumpSwap sell request mints do not match the supplied pool"));
         }
-        let fee_basis_points = protocol_params.fee_basis_points;
+        let fee_basis_points = protocol_params.quoted_fee_basis_points();
 
         let (token_amount, sol_amount) = if let Some(output_amount) = params.fixed_output_amount {
             if output_amount >= pool_base_token_reserves {
@@ -409,7 +413,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
             )
             .map_err(anyhow::Error::msg)?;
             // base_amount_in, min_quote_amount_out
-            (params.input_amount.unwrap(), result.min_quote)
+            (params.input_amount.unwrap(), params.min_out_override.unwrap_or(result.min_quote))
         } else {
             let result = buy_quote_input_internal_with_fees(
                 params.input_amount.unwrap(),
@@ -452,7 +456,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(4);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(4);
 
         if create_output_ata {
             push_create_user_token_account(
@@ -465,7 +469,7 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
         }
 
         // Create sell instruction
-        let mut accounts = Vec::with_capacity(28);
+        let mut accounts = crate::common::instruction_pool::account_meta_vec(28);
         accounts.extend([
             AccountMeta::new(pool, false),                          // pool_id
             AccountMeta::new(params.payer.pubkey(), true),          // user (signer)
@@ -537,10 +541,12 @@ impl InstructionBuilder for PumpSwapInstructionBuilder {
                 accounts,
             ));
         } else {
-            let min_base_amount_out = crate::utils::calc::common::calculate_with_slippage_sell(
-                sol_amount,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            );
+            let min_base_amount_out = params.min_out_override.unwrap_or_else(|| {
+                crate::utils::calc::common::calculate_with_slippage_sell(
+                    sol_amount,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+            });
             let ix_data = encode_pumpswap_buy_exact_quote_in_ix_data(
                 params.input_amount.unwrap_or(0),
                 min_base_amount_out,
@@ -688,10 +694,14 @@ mod tests {
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
             wait_tx_confirmed: false,
+            confirmation: Default::default(),
+            rpc_send_options: Default::default(),
+            raw_transactions: None,
             protocol_params: DexParamEnum::PumpSwap(pumpswap_params()),
             open_seed_optimize: true,
             swqos_clients: Arc::new(Vec::new()),
             middleware_manager: None,
+            compute_budget_manager: None,
             durable_nonce: None,
             with_tip: false,
             create_input_mint_ata: false,
@@ -699,7 +709,11 @@ mod tests {
             create_output_mint_ata: false,
             close_output_mint_ata: false,
             fixed_output_amount,
+            min_out_override: None,
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            memo: None,
             simulate: true,
             log_enabled: false,
             wait_for_all_submits: false,
@@ -710,6 +724,20 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: Some(true),
+            backrun_target: None,
+            simulation_gate: None,
+            latency_budget: None,
+            signing_pool: None,
+            additional_signers: Vec::new(),
+            fee_payer: None,
+            send_mode: Default::default(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            transaction_split: Default::default(),
+            simulation_prelude: Vec::new(),
+            prior_signatures: Vec::new(),
+            wsol_guard: None,
+            order_claim: None,
         }
     }
 
@@ -739,6 +767,20 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn pumpswap_sell_min_out_override_sets_min_quote_out() {
+        let mut params = swap_params(TradeType::Sell, None);
+        params.min_out_override = Some(42);
+
+        let instructions =
+            PumpSwapInstructionBuilder.build_sell_instructions(&params).await.unwrap();
+        let ix = instructions.last().unwrap();
+
+        assert_eq!(&ix.data[..8], crate::instruction::utils::pumpswap::SELL_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(ix.data[8..16].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[16..24].try_into().unwrap()), 42);
+    }
+
     #[tokio::test]
     async fn pumpswap_reverse_sell_fixed_output_uses_current_buy_layout() {
         let mut params = swap_params(TradeType::Sell, Some(42));
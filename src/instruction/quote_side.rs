@@ -0,0 +1,114 @@
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+/// Native SOL trades settle through the WSOL account, so treat both sentinels as WSOL.
+#[inline]
+fn normalize_quote_mint(mint: &Pubkey) -> Pubkey {
+    if *mint == crate::constants::SOL_TOKEN_ACCOUNT {
+        crate::constants::WSOL_TOKEN_ACCOUNT
+    } else {
+        *mint
+    }
+}
+
+#[inline]
+fn is_supported_quote(mint: &Pubkey) -> bool {
+    *mint == crate::constants::WSOL_TOKEN_ACCOUNT || *mint == crate::constants::USDC_TOKEN_ACCOUNT
+}
+
+/// Whether the pool can be traded against the caller's quote: it either holds that mint or a
+/// WSOL/USDC side the builders detect on their own.
+pub(crate) fn pool_accepts_quote(
+    first_mint: &Pubkey,
+    second_mint: &Pubkey,
+    trade_quote_mint: &Pubkey,
+) -> bool {
+    let trade_quote_mint = normalize_quote_mint(trade_quote_mint);
+    trade_quote_mint == *first_mint
+        || trade_quote_mint == *second_mint
+        || is_supported_quote(first_mint)
+        || is_supported_quote(second_mint)
+}
+
+/// Whether `first_mint` is the quote side of a two-token pool for this trade.
+///
+/// `trade_quote_mint` is what the caller pays with on buy (`input_mint`) or receives on sell
+/// (`output_mint`). When it is one of the pool mints it decides the direction, so pools pairing
+/// two supported quotes (e.g. WSOL/USDC) route the way the caller asked. A WSOL/USDC quote that is
+/// not in the pool is rejected instead of silently paying with the other asset. Any other value
+/// falls back to detecting WSOL/USDC on `first_mint`.
+pub(crate) fn quote_is_first(
+    first_mint: &Pubkey,
+    second_mint: &Pubkey,
+    trade_quote_mint: &Pubkey,
+) -> Result<bool> {
+    let trade_quote_mint = normalize_quote_mint(trade_quote_mint);
+    if trade_quote_mint == *first_mint {
+        return Ok(true);
+    }
+    if trade_quote_mint == *second_mint {
+        return Ok(false);
+    }
+    if is_supported_quote(&trade_quote_mint) {
+        return Err(anyhow!(
+            "Pool {}/{} is not quoted in {}; trade with the pool's quote token",
+            first_mint,
+            second_mint,
+            trade_quote_mint
+        ));
+    }
+    Ok(is_supported_quote(first_mint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{SOL_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+
+    #[test]
+    fn trade_quote_mint_decides_direction() {
+        let token = Pubkey::new_unique();
+
+        assert!(quote_is_first(&USDC_TOKEN_ACCOUNT, &token, &USDC_TOKEN_ACCOUNT).unwrap());
+        assert!(!quote_is_first(&token, &USDC_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT).unwrap());
+        assert!(quote_is_first(&WSOL_TOKEN_ACCOUNT, &token, &SOL_TOKEN_ACCOUNT).unwrap());
+    }
+
+    #[test]
+    fn wsol_usdc_pool_follows_trade_quote() {
+        assert!(
+            !quote_is_first(&WSOL_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT).unwrap()
+        );
+        assert!(
+            quote_is_first(&WSOL_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT, &SOL_TOKEN_ACCOUNT).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_quote_missing_from_pool() {
+        let token = Pubkey::new_unique();
+
+        assert!(quote_is_first(&USDC_TOKEN_ACCOUNT, &token, &SOL_TOKEN_ACCOUNT).is_err());
+        assert!(quote_is_first(&token, &WSOL_TOKEN_ACCOUNT, &USDC_TOKEN_ACCOUNT).is_err());
+    }
+
+    #[test]
+    fn pool_accepts_arbitrary_spl_quote_it_holds() {
+        let token = Pubkey::new_unique();
+        let jitosol = Pubkey::new_unique();
+
+        assert!(pool_accepts_quote(&jitosol, &token, &jitosol));
+        assert!(quote_is_first(&jitosol, &token, &jitosol).unwrap());
+        assert!(!quote_is_first(&token, &jitosol, &jitosol).unwrap());
+        assert!(!pool_accepts_quote(&jitosol, &token, &Pubkey::new_unique()));
+        assert!(pool_accepts_quote(&USDC_TOKEN_ACCOUNT, &token, &Pubkey::default()));
+    }
+
+    #[test]
+    fn unknown_trade_quote_falls_back_to_pool_detection() {
+        let token = Pubkey::new_unique();
+
+        assert!(quote_is_first(&USDC_TOKEN_ACCOUNT, &token, &Pubkey::default()).unwrap());
+        assert!(!quote_is_first(&token, &WSOL_TOKEN_ACCOUNT, &Pubkey::default()).unwrap());
+    }
+}
@@ -1,12 +1,14 @@
 use crate::{
     constants::trade::trade::DEFAULT_SLIPPAGE,
     instruction::{
+        quote_side::{pool_accepts_quote, quote_is_first},
         token_account_setup::{
             push_close_wsol_if_needed, push_create_or_wrap_user_token_account,
             push_create_user_token_account,
         },
         utils::raydium_amm_v4::{
-            accounts, SWAP_BASE_IN_DISCRIMINATOR, SWAP_BASE_OUT_DISCRIMINATOR,
+            accounts, SWAP_BASE_IN_DISCRIMINATOR, SWAP_BASE_IN_V2_DISCRIMINATOR,
+            SWAP_BASE_OUT_DISCRIMINATOR, SWAP_BASE_OUT_V2_DISCRIMINATOR,
         },
     },
     trading::core::{
@@ -25,12 +27,16 @@ use solana_sdk::{
 /// Instruction builder for RaydiumCpmm protocol
 pub struct RaydiumAmmV4InstructionBuilder;
 
-fn ensure_market_accounts(params: &RaydiumAmmV4Params) -> Result<()> {
+/// Whether the swap goes through the OpenBook market layout. Pools without a market (or params
+/// built with `new` and no market accounts) use the v2 swaps, which skip the order book.
+fn uses_market_accounts(params: &RaydiumAmmV4Params) -> Result<bool> {
+    if params.serum_market == Pubkey::default() {
+        return Ok(false);
+    }
     let required = [
         ("amm_open_orders", params.amm_open_orders),
         ("amm_target_orders", params.amm_target_orders),
         ("serum_program", params.serum_program),
-        ("serum_market", params.serum_market),
         ("serum_bids", params.serum_bids),
         ("serum_asks", params.serum_asks),
         ("serum_event_queue", params.serum_event_queue),
@@ -42,12 +48,56 @@ fn ensure_market_accounts(params: &RaydiumAmmV4Params) -> Result<()> {
     for (name, account) in required {
         if account == Pubkey::default() {
             return Err(anyhow!(
-                "Raydium AMM v4 requires {}; use RaydiumAmmV4Params::from_amm_address_by_rpc or with_market_accounts",
+                "Raydium AMM v4 market swap requires {}; use RaydiumAmmV4Params::from_amm_address_by_rpc or with_market_accounts",
                 name
             ));
         }
     }
-    Ok(())
+    Ok(true)
+}
+
+/// (base-in, base-out) instruction tags for the layout.
+fn swap_tags(with_market: bool) -> (&'static [u8], &'static [u8]) {
+    if with_market {
+        (SWAP_BASE_IN_DISCRIMINATOR, SWAP_BASE_OUT_DISCRIMINATOR)
+    } else {
+        (SWAP_BASE_IN_V2_DISCRIMINATOR, SWAP_BASE_OUT_V2_DISCRIMINATOR)
+    }
+}
+
+fn swap_accounts(
+    params: &RaydiumAmmV4Params,
+    with_market: bool,
+    user_source_token_account: Pubkey,
+    user_destination_token_account: Pubkey,
+    owner: Pubkey,
+) -> Vec<AccountMeta> {
+    let mut metas = Vec::with_capacity(if with_market { 18 } else { 8 });
+    metas.push(crate::constants::TOKEN_PROGRAM_META); // Token Program (readonly)
+    metas.push(AccountMeta::new(params.amm, false)); // Amm
+    metas.push(accounts::AUTHORITY_META); // Authority (readonly)
+    if with_market {
+        metas.push(AccountMeta::new(params.amm_open_orders, false)); // Amm Open Orders
+        metas.push(AccountMeta::new(params.amm_target_orders, false)); // Amm Target Orders
+    }
+    metas.push(AccountMeta::new(params.token_coin, false)); // Pool Coin Token Account
+    metas.push(AccountMeta::new(params.token_pc, false)); // Pool Pc Token Account
+    if with_market {
+        metas.extend([
+            AccountMeta::new_readonly(params.serum_program, false), // Serum Program
+            AccountMeta::new(params.serum_market, false),           // Serum Market
+            AccountMeta::new(params.serum_bids, false),             // Serum Bids
+            AccountMeta::new(params.serum_asks, false),             // Serum Asks
+            AccountMeta::new(params.serum_event_queue, false),      // Serum Event Queue
+            AccountMeta::new(params.serum_coin_vault_account, false), // Serum Coin Vault Account
+            AccountMeta::new(params.serum_pc_vault_account, false), // Serum Pc Vault Account
+            AccountMeta::new_readonly(params.serum_vault_signer, false), // Serum Vault Signer
+        ]);
+    }
+    metas.push(AccountMeta::new(user_source_token_account, false)); // User Source Token Account
+    metas.push(AccountMeta::new(user_destination_token_account, false)); // User Destination Token Account
+    metas.push(AccountMeta::new(owner, true)); // User Source Owner
+    metas
 }
 
 #[async_trait::async_trait]
@@ -64,23 +114,25 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             .as_any()
             .downcast_ref::<RaydiumAmmV4Params>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
-        ensure_market_accounts(protocol_params)?;
-
-        let is_wsol = protocol_params.coin_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.pc_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
-
-        let is_usdc = protocol_params.coin_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            || protocol_params.pc_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        let with_market = uses_market_accounts(protocol_params)?;
+        let (base_in_tag, base_out_tag) = swap_tags(with_market);
+
+        if !pool_accepts_quote(
+            &protocol_params.coin_mint,
+            &protocol_params.pc_mint,
+            &params.input_mint,
+        ) {
+            return Err(anyhow!("Pool must contain WSOL, USDC or the requested quote mint"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let is_base_in = protocol_params.coin_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.coin_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let is_base_in = quote_is_first(
+            &protocol_params.coin_mint,
+            &protocol_params.pc_mint,
+            &params.input_mint,
+        )?;
         let amount_in: u64 = params.input_amount.unwrap_or(0);
         let input_mint =
             if is_base_in { protocol_params.coin_mint } else { protocol_params.pc_mint };
@@ -105,7 +157,7 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(6);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(6);
 
         if params.create_input_mint_ata {
             push_create_or_wrap_user_token_account(
@@ -128,52 +180,36 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             );
         }
 
-        // Create buy instruction
-        let accounts: [AccountMeta; 18] = [
-            crate::constants::TOKEN_PROGRAM_META, // Token Program (readonly)
-            AccountMeta::new(protocol_params.amm, false), // Amm
-            accounts::AUTHORITY_META,             // Authority (readonly)
-            AccountMeta::new(protocol_params.amm_open_orders, false), // Amm Open Orders
-            AccountMeta::new(protocol_params.amm_target_orders, false), // Amm Target Orders
-            AccountMeta::new(protocol_params.token_coin, false), // Pool Coin Token Account
-            AccountMeta::new(protocol_params.token_pc, false), // Pool Pc Token Account
-            AccountMeta::new_readonly(protocol_params.serum_program, false), // Serum Program
-            AccountMeta::new(protocol_params.serum_market, false), // Serum Market
-            AccountMeta::new(protocol_params.serum_bids, false), // Serum Bids
-            AccountMeta::new(protocol_params.serum_asks, false), // Serum Asks
-            AccountMeta::new(protocol_params.serum_event_queue, false), // Serum Event Queue
-            AccountMeta::new(protocol_params.serum_coin_vault_account, false), // Serum Coin Vault Account
-            AccountMeta::new(protocol_params.serum_pc_vault_account, false), // Serum Pc Vault Account
-            AccountMeta::new_readonly(protocol_params.serum_vault_signer, false), // Serum Vault Signer
-            AccountMeta::new(user_source_token_account, false), // User Source Token Account
-            AccountMeta::new(user_destination_token_account, false), // User Destination Token Account
-            AccountMeta::new(params.payer.pubkey(), true),           // User Source Owner
-        ];
+        let accounts = swap_accounts(
+            protocol_params,
+            with_market,
+            user_source_token_account,
+            user_destination_token_account,
+            params.payer.pubkey(),
+        );
         // Create instruction data
         let mut data = [0u8; 17];
         if let Some(amount_out) = params.fixed_output_amount {
-            data[..1].copy_from_slice(&SWAP_BASE_OUT_DISCRIMINATOR);
+            data[..1].copy_from_slice(base_out_tag);
             data[1..9].copy_from_slice(&amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&amount_out.to_le_bytes());
         } else {
-            let minimum_amount_out = compute_swap_amount(
-                protocol_params.coin_reserve,
-                protocol_params.pc_reserve,
-                is_base_in,
-                amount_in,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
-            data[..1].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
+            let minimum_amount_out = params.min_out_override.unwrap_or_else(|| {
+                compute_swap_amount(
+                    protocol_params.coin_reserve,
+                    protocol_params.pc_reserve,
+                    is_base_in,
+                    amount_in,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+                .min_amount_out
+            });
+            data[..1].copy_from_slice(base_in_tag);
             data[1..9].copy_from_slice(&amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&minimum_amount_out.to_le_bytes());
         }
 
-        instructions.push(Instruction::new_with_bytes(
-            accounts::RAYDIUM_AMM_V4,
-            &data,
-            accounts.to_vec(),
-        ));
+        instructions.push(Instruction::new_with_bytes(accounts::RAYDIUM_AMM_V4, &data, accounts));
 
         if params.close_input_mint_ata {
             push_close_wsol_if_needed(&mut instructions, &params.payer.pubkey(), &input_mint);
@@ -191,27 +227,29 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             .as_any()
             .downcast_ref::<RaydiumAmmV4Params>()
             .ok_or_else(|| anyhow!("Invalid protocol params for RaydiumAmmV4"))?;
-        ensure_market_accounts(protocol_params)?;
+        let with_market = uses_market_accounts(protocol_params)?;
+        let (base_in_tag, base_out_tag) = swap_tags(with_market);
 
         if params.input_amount.is_none() || params.input_amount.unwrap_or(0) == 0 {
             return Err(anyhow!("Token amount is not set"));
         }
 
-        let is_wsol = protocol_params.coin_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.pc_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
-
-        let is_usdc = protocol_params.coin_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            || protocol_params.pc_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        if !pool_accepts_quote(
+            &protocol_params.coin_mint,
+            &protocol_params.pc_mint,
+            &params.output_mint,
+        ) {
+            return Err(anyhow!("Pool must contain WSOL, USDC or the requested quote mint"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let is_base_in = protocol_params.pc_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.pc_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let is_base_in = quote_is_first(
+            &protocol_params.pc_mint,
+            &protocol_params.coin_mint,
+            &params.output_mint,
+        )?;
         let input_mint =
             if is_base_in { protocol_params.coin_mint } else { protocol_params.pc_mint };
         let output_mint =
@@ -235,7 +273,7 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(4);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(4);
 
         if params.create_output_mint_ata {
             push_create_user_token_account(
@@ -247,53 +285,37 @@ impl InstructionBuilder for RaydiumAmmV4InstructionBuilder {
             );
         }
 
-        // Create buy instruction
-        let accounts: [AccountMeta; 18] = [
-            crate::constants::TOKEN_PROGRAM_META, // Token Program (readonly)
-            AccountMeta::new(protocol_params.amm, false), // Amm
-            accounts::AUTHORITY_META,             // Authority (readonly)
-            AccountMeta::new(protocol_params.amm_open_orders, false), // Amm Open Orders
-            AccountMeta::new(protocol_params.amm_target_orders, false), // Amm Target Orders
-            AccountMeta::new(protocol_params.token_coin, false), // Pool Coin Token Account
-            AccountMeta::new(protocol_params.token_pc, false), // Pool Pc Token Account
-            AccountMeta::new_readonly(protocol_params.serum_program, false), // Serum Program
-            AccountMeta::new(protocol_params.serum_market, false), // Serum Market
-            AccountMeta::new(protocol_params.serum_bids, false), // Serum Bids
-            AccountMeta::new(protocol_params.serum_asks, false), // Serum Asks
-            AccountMeta::new(protocol_params.serum_event_queue, false), // Serum Event Queue
-            AccountMeta::new(protocol_params.serum_coin_vault_account, false), // Serum Coin Vault Account
-            AccountMeta::new(protocol_params.serum_pc_vault_account, false), // Serum Pc Vault Account
-            AccountMeta::new_readonly(protocol_params.serum_vault_signer, false), // Serum Vault Signer
-            AccountMeta::new(user_source_token_account, false), // User Source Token Account
-            AccountMeta::new(user_destination_token_account, false), // User Destination Token Account
-            AccountMeta::new(params.payer.pubkey(), true),           // User Source Owner
-        ];
+        let accounts = swap_accounts(
+            protocol_params,
+            with_market,
+            user_source_token_account,
+            user_destination_token_account,
+            params.payer.pubkey(),
+        );
         // Create instruction data
         let mut data = [0u8; 17];
         let amount_in = params.input_amount.unwrap_or(0);
         if let Some(amount_out) = params.fixed_output_amount {
-            data[..1].copy_from_slice(&SWAP_BASE_OUT_DISCRIMINATOR);
+            data[..1].copy_from_slice(base_out_tag);
             data[1..9].copy_from_slice(&amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&amount_out.to_le_bytes());
         } else {
-            let minimum_amount_out = compute_swap_amount(
-                protocol_params.coin_reserve,
-                protocol_params.pc_reserve,
-                is_base_in,
-                amount_in,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
-            data[..1].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
+            let minimum_amount_out = params.min_out_override.unwrap_or_else(|| {
+                compute_swap_amount(
+                    protocol_params.coin_reserve,
+                    protocol_params.pc_reserve,
+                    is_base_in,
+                    amount_in,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+                .min_amount_out
+            });
+            data[..1].copy_from_slice(base_in_tag);
             data[1..9].copy_from_slice(&amount_in.to_le_bytes());
             data[9..17].copy_from_slice(&minimum_amount_out.to_le_bytes());
         }
 
-        instructions.push(Instruction::new_with_bytes(
-            accounts::RAYDIUM_AMM_V4,
-            &data,
-            accounts.to_vec(),
-        ));
+        instructions.push(Instruction::new_with_bytes(accounts::RAYDIUM_AMM_V4, &data, accounts));
 
         if params.close_output_mint_ata {
             push_close_wsol_if_needed(&mut instructions, &params.payer.pubkey(), &output_mint);
@@ -368,10 +390,14 @@ mod tests {
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
             wait_tx_confirmed: false,
+            confirmation: Default::default(),
+            rpc_send_options: Default::default(),
+            raw_transactions: None,
             protocol_params: DexParamEnum::RaydiumAmmV4(protocol_params),
             open_seed_optimize: true,
             swqos_clients: Arc::new(Vec::new()),
             middleware_manager: None,
+            compute_budget_manager: None,
             durable_nonce: None,
             with_tip: false,
             create_input_mint_ata: false,
@@ -379,7 +405,11 @@ mod tests {
             create_output_mint_ata: false,
             close_output_mint_ata: false,
             fixed_output_amount,
+            min_out_override: None,
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            memo: None,
             simulate: true,
             log_enabled: false,
             wait_for_all_submits: false,
@@ -390,6 +420,20 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            backrun_target: None,
+            simulation_gate: None,
+            latency_budget: None,
+            signing_pool: None,
+            additional_signers: Vec::new(),
+            fee_payer: None,
+            send_mode: Default::default(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            transaction_split: Default::default(),
+            simulation_prelude: Vec::new(),
+            prior_signatures: Vec::new(),
+            wsol_guard: None,
+            order_claim: None,
         }
     }
 
@@ -430,26 +474,62 @@ mod tests {
         assert_eq!(u64::from_le_bytes(ix.data[9..17].try_into().unwrap()), 42);
     }
 
+    #[tokio::test]
+    async fn raydium_amm_v4_base_in_uses_min_out_override() {
+        let mut params = swap_params(market_params(), None);
+        params.min_out_override = Some(42);
+        let instructions =
+            RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let ix = instructions.last().unwrap();
+
+        assert_eq!(&ix.data[..1], SWAP_BASE_IN_DISCRIMINATOR);
+        assert_eq!(u64::from_le_bytes(ix.data[1..9].try_into().unwrap()), 100_000);
+        assert_eq!(u64::from_le_bytes(ix.data[9..17].try_into().unwrap()), 42);
+    }
+
     #[tokio::test]
     async fn raydium_amm_v4_rejects_placeholder_market_accounts() {
+        let mut protocol_params = market_params();
+        protocol_params.amm_open_orders = Pubkey::default();
         let err = RaydiumAmmV4InstructionBuilder
-            .build_buy_instructions(&swap_params(
-                RaydiumAmmV4Params::new(
-                    pk(1),
-                    crate::constants::WSOL_TOKEN_ACCOUNT,
-                    pk(2),
-                    pk(3),
-                    pk(4),
-                    1_000_000_000,
-                    2_000_000_000,
-                ),
-                None,
-            ))
+            .build_buy_instructions(&swap_params(protocol_params, None))
             .await
             .unwrap_err();
         assert!(err.to_string().contains("amm_open_orders"));
     }
 
+    #[tokio::test]
+    async fn raydium_amm_v4_without_market_uses_v2_swap() {
+        let protocol_params = RaydiumAmmV4Params::new(
+            pk(1),
+            crate::constants::WSOL_TOKEN_ACCOUNT,
+            pk(2),
+            pk(3),
+            pk(4),
+            1_000_000_000,
+            2_000_000_000,
+        );
+        assert!(!protocol_params.has_market_accounts());
+
+        let instructions = RaydiumAmmV4InstructionBuilder
+            .build_buy_instructions(&swap_params(protocol_params.clone(), None))
+            .await
+            .unwrap();
+        let ix = instructions.last().unwrap();
+        assert_eq!(ix.accounts.len(), 8);
+        assert_eq!(&ix.data[..1], SWAP_BASE_IN_V2_DISCRIMINATOR);
+        assert_eq!(ix.accounts[1].pubkey, pk(1));
+        assert_eq!(ix.accounts[3].pubkey, pk(3));
+        assert_eq!(ix.accounts[4].pubkey, pk(4));
+        assert!(ix.accounts[7].is_signer);
+
+        let instructions = RaydiumAmmV4InstructionBuilder
+            .build_buy_instructions(&swap_params(protocol_params, Some(42)))
+            .await
+            .unwrap();
+        assert_eq!(&instructions.last().unwrap().data[..1], SWAP_BASE_OUT_V2_DISCRIMINATOR);
+    }
+
     #[tokio::test]
     async fn raydium_amm_v4_usdc_buy_create_input_builds_usdc_ata() {
         let mut protocol_params = market_params();
@@ -467,4 +547,16 @@ mod tests {
         assert_eq!(create_ix.program_id, crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID);
         assert_eq!(create_ix.accounts[3].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
     }
+
+    #[tokio::test]
+    async fn raydium_amm_v4_rejects_sol_input_on_usdc_pool() {
+        let mut protocol_params = market_params();
+        protocol_params.coin_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+
+        let mut params = swap_params(protocol_params, None);
+        params.input_mint = crate::constants::SOL_TOKEN_ACCOUNT;
+
+        let err = RaydiumAmmV4InstructionBuilder.build_buy_instructions(&params).await.unwrap_err();
+        assert!(err.to_string().contains("is not quoted in"));
+    }
 }
@@ -2,6 +2,7 @@ use crate::{
     common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed,
     constants::trade::trade::DEFAULT_SLIPPAGE,
     instruction::{
+        quote_side::{pool_accepts_quote, quote_is_first},
         token_account_setup::{
             push_close_wsol_if_needed, push_create_or_wrap_user_token_account,
             push_create_user_token_account,
@@ -54,21 +55,22 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             protocol_params.pool_state
         };
 
-        let is_wsol = protocol_params.base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
-
-        let is_usdc = protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        if !pool_accepts_quote(
+            &protocol_params.base_mint,
+            &protocol_params.quote_mint,
+            &params.input_mint,
+        ) {
+            return Err(anyhow!("Pool must contain WSOL, USDC or the requested quote mint"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let is_base_in = protocol_params.base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let is_base_in = quote_is_first(
+            &protocol_params.base_mint,
+            &protocol_params.quote_mint,
+            &params.input_mint,
+        )?;
         let input_mint =
             if is_base_in { protocol_params.base_mint } else { protocol_params.quote_mint };
         let input_token_program = if is_base_in {
@@ -111,7 +113,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(6);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(6);
 
         if params.create_input_mint_ata {
             push_create_or_wrap_user_token_account(
@@ -157,14 +159,16 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             data[8..16].copy_from_slice(&amount_in.to_le_bytes());
             data[16..24].copy_from_slice(&amount_out.to_le_bytes());
         } else {
-            let minimum_amount_out = compute_swap_amount(
-                protocol_params.base_reserve,
-                protocol_params.quote_reserve,
-                is_base_in,
-                amount_in,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
+            let minimum_amount_out = params.min_out_override.unwrap_or_else(|| {
+                compute_swap_amount(
+                    protocol_params.base_reserve,
+                    protocol_params.quote_reserve,
+                    is_base_in,
+                    amount_in,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+                .min_amount_out
+            });
             data[..8].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
             data[8..16].copy_from_slice(&amount_in.to_le_bytes());
             data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
@@ -208,21 +212,22 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             protocol_params.pool_state
         };
 
-        let is_wsol = protocol_params.base_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT;
-
-        let is_usdc = protocol_params.base_mint == crate::constants::USDC_TOKEN_ACCOUNT
-            || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
-
-        if !is_wsol && !is_usdc {
-            return Err(anyhow!("Pool must contain WSOL or USDC"));
+        if !pool_accepts_quote(
+            &protocol_params.base_mint,
+            &protocol_params.quote_mint,
+            &params.output_mint,
+        ) {
+            return Err(anyhow!("Pool must contain WSOL, USDC or the requested quote mint"));
         }
 
         // ========================================
         // Trade calculation and account address preparation
         // ========================================
-        let is_quote_out = protocol_params.quote_mint == crate::constants::WSOL_TOKEN_ACCOUNT
-            || protocol_params.quote_mint == crate::constants::USDC_TOKEN_ACCOUNT;
+        let is_quote_out = quote_is_first(
+            &protocol_params.quote_mint,
+            &protocol_params.base_mint,
+            &params.output_mint,
+        )?;
         let input_mint =
             if is_quote_out { protocol_params.base_mint } else { protocol_params.quote_mint };
         let input_token_program = if is_quote_out {
@@ -263,7 +268,7 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
         // ========================================
         // Build instructions
         // ========================================
-        let mut instructions = Vec::with_capacity(4);
+        let mut instructions = crate::common::instruction_pool::instruction_vec(4);
 
         if params.create_output_mint_ata {
             push_create_user_token_account(
@@ -299,14 +304,16 @@ impl InstructionBuilder for RaydiumCpmmInstructionBuilder {
             data[8..16].copy_from_slice(&amount_in.to_le_bytes());
             data[16..24].copy_from_slice(&amount_out.to_le_bytes());
         } else {
-            let minimum_amount_out = compute_swap_amount(
-                protocol_params.base_reserve,
-                protocol_params.quote_reserve,
-                is_quote_out,
-                amount_in,
-                params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
-            )
-            .min_amount_out;
+            let minimum_amount_out = params.min_out_override.unwrap_or_else(|| {
+                compute_swap_amount(
+                    protocol_params.base_reserve,
+                    protocol_params.quote_reserve,
+                    is_quote_out,
+                    amount_in,
+                    params.slippage_basis_points.unwrap_or(DEFAULT_SLIPPAGE),
+                )
+                .min_amount_out
+            });
             data[..8].copy_from_slice(&SWAP_BASE_IN_DISCRIMINATOR);
             data[8..16].copy_from_slice(&amount_in.to_le_bytes());
             data[16..24].copy_from_slice(&minimum_amount_out.to_le_bytes());
@@ -380,10 +387,14 @@ mod tests {
             address_lookup_table_accounts: Vec::new(),
             recent_blockhash: None,
             wait_tx_confirmed: false,
+            confirmation: Default::default(),
+            rpc_send_options: Default::default(),
+            raw_transactions: None,
             protocol_params: DexParamEnum::RaydiumCpmm(cpmm_params()),
             open_seed_optimize: true,
             swqos_clients: Arc::new(Vec::new()),
             middleware_manager: None,
+            compute_budget_manager: None,
             durable_nonce: None,
             with_tip: false,
             create_input_mint_ata: false,
@@ -391,7 +402,11 @@ mod tests {
             create_output_mint_ata: false,
             close_output_mint_ata: false,
             fixed_output_amount,
+            min_out_override: None,
             gas_fee_strategy: GasFeeStrategy::new(),
+            data_size_limit: None,
+            heap_frame_bytes: None,
+            memo: None,
             simulate: true,
             log_enabled: false,
             wait_for_all_submits: false,
@@ -402,6 +417,20 @@ mod tests {
             check_min_tip: false,
             grpc_recv_us: None,
             use_exact_sol_amount: None,
+            backrun_target: None,
+            simulation_gate: None,
+            latency_budget: None,
+            signing_pool: None,
+            additional_signers: Vec::new(),
+            fee_payer: None,
+            send_mode: Default::default(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            transaction_split: Default::default(),
+            simulation_prelude: Vec::new(),
+            prior_signatures: Vec::new(),
+            wsol_guard: None,
+            order_claim: None,
         }
     }
 
@@ -450,4 +479,43 @@ mod tests {
         assert_eq!(create_ix.accounts[3].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
         assert_eq!(swap_ix.accounts[10].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
     }
+
+    #[tokio::test]
+    async fn raydium_cpmm_wsol_usdc_pool_pays_with_requested_quote() {
+        let mut protocol_params = cpmm_params();
+        protocol_params.base_mint = crate::constants::WSOL_TOKEN_ACCOUNT;
+        protocol_params.quote_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+
+        let mut params = swap_params(None);
+        params.protocol_params = DexParamEnum::RaydiumCpmm(protocol_params);
+        params.input_mint = crate::constants::USDC_TOKEN_ACCOUNT;
+        params.output_mint = crate::constants::WSOL_TOKEN_ACCOUNT;
+
+        let instructions =
+            RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let swap_ix = instructions.last().unwrap();
+
+        assert_eq!(swap_ix.accounts[10].pubkey, crate::constants::USDC_TOKEN_ACCOUNT);
+        assert_eq!(swap_ix.accounts[11].pubkey, crate::constants::WSOL_TOKEN_ACCOUNT);
+    }
+
+    #[tokio::test]
+    async fn raydium_cpmm_spl_quote_pool_pays_with_that_mint() {
+        let jitosol = pk(41);
+        let mut protocol_params = cpmm_params();
+        protocol_params.base_mint = pk(3);
+        protocol_params.quote_mint = jitosol;
+
+        let mut params = swap_params(None);
+        params.protocol_params = DexParamEnum::RaydiumCpmm(protocol_params);
+        params.input_mint = jitosol;
+        params.output_mint = pk(3);
+
+        let instructions =
+            RaydiumCpmmInstructionBuilder.build_buy_instructions(&params).await.unwrap();
+        let swap_ix = instructions.last().unwrap();
+
+        assert_eq!(swap_ix.accounts[10].pubkey, jitosol);
+        assert_eq!(swap_ix.accounts[11].pubkey, pk(3));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{
-    common::SolanaRpcClient,
+    common::{protocol_layout, SolanaRpcClient},
     instruction::utils::bonk_types::{pool_state_decode, PoolState},
+    trading::factory::DexType,
 };
 use anyhow::anyhow;
 use solana_sdk::pubkey::Pubkey;
@@ -65,12 +66,23 @@ pub async fn fetch_pool_state(
     rpc: &SolanaRpcClient,
     pool_address: &Pubkey,
 ) -> Result<PoolState, anyhow::Error> {
-    let account = rpc.get_account(pool_address).await?;
+    let account = crate::common::rpc_cache::get_account_coalesced(rpc, pool_address).await?;
     if account.owner != accounts::BONK {
         return Err(anyhow!("Account is not owned by Bonk program"));
     }
+    protocol_layout::check_layout(DexType::Bonk, &account.data)?;
     let pool_state = pool_state_decode(&account.data[8..])
         .ok_or_else(|| anyhow!("Failed to decode pool state"))?;
+    // Buy/sell send one of the two known global configs, picked from this field.
+    if pool_state.global_config != accounts::GLOBAL_CONFIG
+        && pool_state.global_config != accounts::USD1_GLOBAL_CONFIG
+    {
+        return Err(protocol_layout::layout_changed(
+            DexType::Bonk,
+            format_args!("pool uses unknown global config {}", pool_state.global_config),
+        )
+        .into());
+    }
     Ok(pool_state)
 }
 
@@ -185,13 +197,11 @@ pub fn get_platform_associated_account(platform_config: &Pubkey) -> Option<Pubke
     let seeds: &[&[u8]; 2] =
         &[platform_config.as_ref(), crate::constants::WSOL_TOKEN_ACCOUNT.as_ref()];
     let program_id: &Pubkey = &accounts::BONK;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
 
 pub fn get_creator_associated_account(creator: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] = &[creator.as_ref(), crate::constants::WSOL_TOKEN_ACCOUNT.as_ref()];
     let program_id: &Pubkey = &accounts::BONK;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
@@ -107,6 +107,8 @@ pub struct PoolState {
     pub creator: Pubkey,
     pub padding: [u64; 8],
 }
+/// Anchor discriminator of `PoolState` accounts.
+pub const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
 pub const POOL_STATE_SIZE: usize = 8 + 1 * 5 + 8 * 10 + 32 * 7 + 8 * 8 + 8 * 5;
 pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
     if data.len() < POOL_STATE_SIZE {
@@ -114,3 +116,110 @@ pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
     }
     borsh::from_slice::<PoolState>(&data[..POOL_STATE_SIZE]).ok()
 }
+
+/// Zero-copy view of the Bonk [`PoolState`] account body (after the 8-byte discriminator),
+/// e.g. for Geyser account bytes.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PoolStateLayout {
+    epoch: [u8; 8],
+    auth_bump: u8,
+    status: u8,
+    base_decimals: u8,
+    quote_decimals: u8,
+    migrate_type: u8,
+    supply: [u8; 8],
+    total_base_sell: [u8; 8],
+    virtual_base: [u8; 8],
+    virtual_quote: [u8; 8],
+    real_base: [u8; 8],
+    real_quote: [u8; 8],
+    total_quote_fund_raising: [u8; 8],
+    _fees: [u8; 24],
+    _vesting_schedule: [u8; 40],
+    global_config: [u8; 32],
+    platform_config: [u8; 32],
+    base_mint: [u8; 32],
+    quote_mint: [u8; 32],
+    base_vault: [u8; 32],
+    quote_vault: [u8; 32],
+    creator: [u8; 32],
+    _padding: [u8; 64],
+}
+
+// SAFETY: `repr(C)` over bytes only: alignment 1, no padding, every bit pattern valid.
+unsafe impl bytemuck::Zeroable for PoolStateLayout {}
+unsafe impl bytemuck::Pod for PoolStateLayout {}
+
+impl PoolStateLayout {
+    /// Borrow the account body (no discriminator) as a layout; `None` when too short.
+    #[inline]
+    pub fn decode_from_slice(data: &[u8]) -> Option<&Self> {
+        bytemuck::try_from_bytes(data.get(..POOL_STATE_SIZE)?).ok()
+    }
+
+    #[inline]
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+    #[inline]
+    pub fn decimals(&self) -> (u8, u8) {
+        (self.base_decimals, self.quote_decimals)
+    }
+    #[inline]
+    pub fn supply(&self) -> u64 {
+        u64::from_le_bytes(self.supply)
+    }
+    #[inline]
+    pub fn total_base_sell(&self) -> u64 {
+        u64::from_le_bytes(self.total_base_sell)
+    }
+    #[inline]
+    pub fn virtual_base(&self) -> u64 {
+        u64::from_le_bytes(self.virtual_base)
+    }
+    #[inline]
+    pub fn virtual_quote(&self) -> u64 {
+        u64::from_le_bytes(self.virtual_quote)
+    }
+    #[inline]
+    pub fn real_base(&self) -> u64 {
+        u64::from_le_bytes(self.real_base)
+    }
+    #[inline]
+    pub fn real_quote(&self) -> u64 {
+        u64::from_le_bytes(self.real_quote)
+    }
+    #[inline]
+    pub fn total_quote_fund_raising(&self) -> u64 {
+        u64::from_le_bytes(self.total_quote_fund_raising)
+    }
+    #[inline]
+    pub fn global_config(&self) -> Pubkey {
+        Pubkey::new_from_array(self.global_config)
+    }
+    #[inline]
+    pub fn platform_config(&self) -> Pubkey {
+        Pubkey::new_from_array(self.platform_config)
+    }
+    #[inline]
+    pub fn base_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.base_mint)
+    }
+    #[inline]
+    pub fn quote_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.quote_mint)
+    }
+    #[inline]
+    pub fn base_vault(&self) -> Pubkey {
+        Pubkey::new_from_array(self.base_vault)
+    }
+    #[inline]
+    pub fn quote_vault(&self) -> Pubkey {
+        Pubkey::new_from_array(self.quote_vault)
+    }
+    #[inline]
+    pub fn creator(&self) -> Pubkey {
+        Pubkey::new_from_array(self.creator)
+    }
+}
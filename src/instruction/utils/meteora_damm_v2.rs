@@ -52,15 +52,23 @@ pub async fn fetch_pool(
     rpc: &SolanaRpcClient,
     pool_address: &Pubkey,
 ) -> Result<Pool, anyhow::Error> {
-    let account = rpc.get_account(pool_address).await?;
+    let account = crate::common::rpc_cache::get_account_coalesced(rpc, pool_address).await?;
     if account.owner != accounts::METEORA_DAMM_V2 {
         return Err(anyhow!("Account is not owned by Meteora Damm V2 program"));
     }
+    crate::common::protocol_layout::check_layout(
+        crate::trading::factory::DexType::MeteoraDammV2,
+        &account.data,
+    )?;
     let pool = pool_decode(&account.data[8..]).ok_or_else(|| anyhow!("Failed to decode pool"))?;
     Ok(pool)
 }
 
 #[inline]
 pub fn get_event_authority_pda() -> Pubkey {
-    Pubkey::find_program_address(&[seeds::EVENT_AUTHORITY_SEED], &accounts::METEORA_DAMM_V2).0
+    crate::common::fast_fn::find_program_address_cached(
+        &[seeds::EVENT_AUTHORITY_SEED],
+        &accounts::METEORA_DAMM_V2,
+    )
+    .expect("no viable event authority bump")
 }
@@ -104,6 +104,8 @@ pub struct Pool {
     pub reward_infos: [RewardInfo; 2],
 }
 
+/// Anchor discriminator of `Pool` accounts.
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
 pub const POOL_SIZE: usize = 1104;
 
 pub fn pool_decode(data: &[u8]) -> Option<Pool> {
@@ -176,6 +176,9 @@ pub mod accounts {
 /// Minimum bonding curve account data length (`sdk.ts` `BONDING_CURVE_NEW_SIZE`).
 pub const PUMP_BONDING_CURVE_MIN_DATA_LEN: usize = 151;
 
+/// Anchor discriminator of `BondingCurve` accounts.
+pub const BONDING_CURVE_ACCOUNT_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
 pub const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
 pub const BUY_EXACT_SOL_IN_DISCRIMINATOR: [u8; 8] = [56, 252, 116, 8, 158, 223, 205, 95];
 pub const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
@@ -511,10 +514,14 @@ pub async fn fetch_bonding_curve_account(
     let bonding_curve_pda: Pubkey =
         get_bonding_curve_pda(mint).ok_or_else(|| anyhow!("Bonding curve not found"))?;
 
-    let account = rpc.get_account(&bonding_curve_pda).await?;
+    let account = crate::common::rpc_cache::get_account_coalesced(rpc, &bonding_curve_pda).await?;
     if account.data.is_empty() {
         return Err(anyhow!("Bonding curve not found"));
     }
+    crate::common::protocol_layout::check_layout(
+        crate::trading::factory::DexType::PumpFun,
+        &account.data,
+    )?;
 
     // Use `deserialize` instead of `try_from_slice` so that extra trailing bytes
     // (from on-chain schema additions like new fields) are silently ignored.
@@ -4,6 +4,7 @@ use crate::{
     },
     constants::WSOL_TOKEN_ACCOUNT,
     instruction::utils::pumpswap_types::{pool_decode, Pool, POOL_DISCRIMINATOR},
+    trading::factory::DexType,
 };
 use anyhow::anyhow;
 use once_cell::sync::Lazy;
@@ -639,21 +640,26 @@ pub fn get_protocol_extra_fee_recipient_random() -> Pubkey {
 /// Pool v2 PDA (seeds: ["pool-v2", base_mint]). Required at end of buy/sell/buy_exact_quote_in accounts.
 #[inline]
 pub fn get_pool_v2_pda(base_mint: &Pubkey) -> Option<Pubkey> {
-    let (pda, _) = Pubkey::find_program_address(
-        &[seeds::POOL_V2_SEED, base_mint.as_ref()],
-        &accounts::AMM_PROGRAM,
-    );
-    Some(pda)
+    crate::common::fast_fn::get_cached_pda(
+        crate::common::fast_fn::PdaCacheKey::PumpSwapPoolV2(*base_mint),
+        || {
+            Pubkey::try_find_program_address(
+                &[seeds::POOL_V2_SEED, base_mint.as_ref()],
+                &accounts::AMM_PROGRAM,
+            )
+            .map(|(pda, _)| pda)
+        },
+    )
 }
 
 /// Pump 程序上的 pool-authority PDA（canonical pool 的 creator），与 @pump-fun/pump-swap-sdk 一致。
 #[inline]
 pub fn get_pump_pool_authority_pda(mint: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(
+    crate::common::fast_fn::find_program_address_cached(
         &[seeds::POOL_AUTHORITY_SEED, mint.as_ref()],
         &accounts::PUMP_PROGRAM_ID,
     )
-    .0
+    .expect("no viable pool-authority bump")
 }
 
 /// Canonical Pump 池 PDA：index=0，creator=pumpPoolAuthorityPda(mint)，base_mint=mint，quote_mint=WSOL。
@@ -662,7 +668,7 @@ pub fn get_pump_pool_authority_pda(mint: &Pubkey) -> Pubkey {
 pub fn get_canonical_pool_pda(mint: &Pubkey) -> Pubkey {
     const CANONICAL_POOL_INDEX: u16 = 0;
     let authority = get_pump_pool_authority_pda(mint);
-    let (pda, _) = Pubkey::find_program_address(
+    crate::common::fast_fn::find_program_address_cached(
         &[
             seeds::POOL_SEED,
             &CANONICAL_POOL_INDEX.to_le_bytes(),
@@ -671,8 +677,8 @@ pub fn get_canonical_pool_pda(mint: &Pubkey) -> Pubkey {
             WSOL_TOKEN_ACCOUNT.as_ref(),
         ],
         &accounts::AMM_PROGRAM,
-    );
-    pda
+    )
+    .expect("no viable canonical pool bump")
 }
 
 // Find a pool for a specific mint
@@ -682,11 +688,11 @@ pub async fn find_pool(rpc: &SolanaRpcClient, mint: &Pubkey) -> Result<Pubkey, a
 }
 
 pub(crate) fn coin_creator_vault_authority(coin_creator: Pubkey) -> Pubkey {
-    let (pump_pool_authority, _) = Pubkey::find_program_address(
-        &[b"creator_vault", &coin_creator.to_bytes()],
+    crate::common::fast_fn::find_program_address_cached(
+        &[b"creator_vault", coin_creator.as_ref()],
         &accounts::AMM_PROGRAM,
-    );
-    pump_pool_authority
+    )
+    .expect("no viable creator vault bump")
 }
 
 pub(crate) fn coin_creator_vault_ata(
@@ -756,15 +762,17 @@ pub fn get_user_volume_accumulator_quote_ata(
 pub fn get_global_volume_accumulator_pda() -> Option<Pubkey> {
     let seeds: &[&[u8]; 1] = &[&seeds::GLOBAL_VOLUME_ACCUMULATOR_SEED];
     let program_id: &Pubkey = &accounts::AMM_PROGRAM;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
 
 pub async fn fetch_pool(
     rpc: &SolanaRpcClient,
     pool_address: &Pubkey,
 ) -> Result<Pool, anyhow::Error> {
-    let account = rpc.get_account(pool_address).await?;
+    let account = crate::common::rpc_cache::get_account_coalesced(rpc, pool_address).await?;
+    if account.owner == accounts::AMM_PROGRAM {
+        crate::common::protocol_layout::check_layout(DexType::PumpSwap, &account.data)?;
+    }
     decode_pool_account(&account).map_err(anyhow::Error::msg)
 }
 
@@ -846,6 +854,9 @@ fn decode_pool_accounts(
 ) -> Vec<(Pubkey, Pool)> {
     accounts
         .into_iter()
+        .filter(|(_, acc)| {
+            crate::common::protocol_layout::check_layout(DexType::PumpSwap, &acc.data).is_ok()
+        })
         .filter_map(|(addr, acc)| decode_pool_account(&acc).ok().map(|pool| (addr, pool)))
         .collect()
 }
@@ -1047,8 +1058,7 @@ pub async fn get_token_balances(
 pub fn get_fee_config_pda() -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] = &[seeds::FEE_CONFIG_SEED, accounts::AMM_PROGRAM.as_ref()];
     let program_id: &Pubkey = &accounts::FEE_PROGRAM;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
 
 #[cfg(test)]
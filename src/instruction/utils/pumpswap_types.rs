@@ -82,6 +82,83 @@ pub fn pool_decode(data: &[u8]) -> Option<Pool> {
     None
 }
 
+/// Zero-copy view of the current [`Pool`] account body (after the 8-byte discriminator).
+/// Legacy pools without `virtual_quote_reserves` are shorter; decode those with [`pool_decode`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PoolLayout {
+    pool_bump: u8,
+    index: [u8; 2],
+    creator: [u8; 32],
+    base_mint: [u8; 32],
+    quote_mint: [u8; 32],
+    lp_mint: [u8; 32],
+    pool_base_token_account: [u8; 32],
+    pool_quote_token_account: [u8; 32],
+    lp_supply: [u8; 8],
+    coin_creator: [u8; 32],
+    is_mayhem_mode: u8,
+    is_cashback_coin: u8,
+    virtual_quote_reserves: [u8; 16],
+}
+
+// SAFETY: `repr(C)` over bytes only: alignment 1, no padding, every bit pattern valid.
+unsafe impl bytemuck::Zeroable for PoolLayout {}
+unsafe impl bytemuck::Pod for PoolLayout {}
+
+impl PoolLayout {
+    /// Borrow the account body (no discriminator) as a layout; `None` when too short.
+    #[inline]
+    pub fn decode_from_slice(data: &[u8]) -> Option<&Self> {
+        bytemuck::try_from_bytes(data.get(..POOL_SIZE)?).ok()
+    }
+
+    #[inline]
+    pub fn index(&self) -> u16 {
+        u16::from_le_bytes(self.index)
+    }
+    #[inline]
+    pub fn creator(&self) -> Pubkey {
+        Pubkey::new_from_array(self.creator)
+    }
+    #[inline]
+    pub fn base_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.base_mint)
+    }
+    #[inline]
+    pub fn quote_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.quote_mint)
+    }
+    #[inline]
+    pub fn pool_base_token_account(&self) -> Pubkey {
+        Pubkey::new_from_array(self.pool_base_token_account)
+    }
+    #[inline]
+    pub fn pool_quote_token_account(&self) -> Pubkey {
+        Pubkey::new_from_array(self.pool_quote_token_account)
+    }
+    #[inline]
+    pub fn lp_supply(&self) -> u64 {
+        u64::from_le_bytes(self.lp_supply)
+    }
+    #[inline]
+    pub fn coin_creator(&self) -> Pubkey {
+        Pubkey::new_from_array(self.coin_creator)
+    }
+    #[inline]
+    pub fn is_mayhem_mode(&self) -> bool {
+        self.is_mayhem_mode != 0
+    }
+    #[inline]
+    pub fn is_cashback_coin(&self) -> bool {
+        self.is_cashback_coin != 0
+    }
+    #[inline]
+    pub fn virtual_quote_reserves(&self) -> i128 {
+        i128::from_le_bytes(self.virtual_quote_reserves)
+    }
+}
+
 /// Compute the quote reserves used by PumpSwap pricing.
 ///
 /// Returns `None` when the signed sum is non-positive or cannot fit in a `u64`.
@@ -124,6 +201,23 @@ mod tests {
         assert!(!pool.is_cashback_coin);
     }
 
+    #[test]
+    fn pool_layout_matches_borsh_decode() {
+        let data = pool_payload(-55);
+        let pool = pool_decode(&data).unwrap();
+        let layout = PoolLayout::decode_from_slice(&data).unwrap();
+
+        assert_eq!(std::mem::size_of::<PoolLayout>(), POOL_SIZE);
+        assert_eq!(layout.index(), pool.index);
+        assert_eq!(layout.base_mint(), pool.base_mint);
+        assert_eq!(layout.pool_quote_token_account(), pool.pool_quote_token_account);
+        assert_eq!(layout.lp_supply(), pool.lp_supply);
+        assert_eq!(layout.coin_creator(), pool.coin_creator);
+        assert_eq!(layout.is_mayhem_mode(), pool.is_mayhem_mode);
+        assert_eq!(layout.virtual_quote_reserves(), pool.virtual_quote_reserves);
+        assert!(PoolLayout::decode_from_slice(&data[..LEGACY_POOL_SIZE]).is_none());
+    }
+
     #[test]
     fn decodes_legacy_pool_with_zero_virtual_quote_reserves() {
         let mut data = pool_payload(0);
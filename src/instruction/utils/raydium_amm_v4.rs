@@ -1,7 +1,8 @@
 use crate::{
     common::SolanaRpcClient,
     instruction::utils::raydium_amm_v4_types::{
-        amm_info_decode, market_state_decode, AmmInfo, MarketState,
+        amm_info_decode, market_state_decode, open_orders_totals_decode, AmmInfo, AmmReserves,
+        MarketState,
     },
 };
 use anyhow::anyhow;
@@ -35,9 +36,16 @@ pub mod accounts {
 
 pub const SWAP_BASE_IN_DISCRIMINATOR: &[u8] = &[9];
 pub const SWAP_BASE_OUT_DISCRIMINATOR: &[u8] = &[11];
+/// Swaps without the OpenBook accounts, for pools that have no market.
+pub const SWAP_BASE_IN_V2_DISCRIMINATOR: &[u8] = &[16];
+pub const SWAP_BASE_OUT_V2_DISCRIMINATOR: &[u8] = &[17];
 
 pub async fn fetch_amm_info(rpc: &SolanaRpcClient, amm: Pubkey) -> Result<AmmInfo, anyhow::Error> {
     let amm_info = rpc.get_account_data(&amm).await?;
+    crate::common::protocol_layout::check_layout(
+        crate::trading::factory::DexType::RaydiumAmmV4,
+        &amm_info,
+    )?;
     let amm_info =
         amm_info_decode(&amm_info).ok_or_else(|| anyhow!("Failed to decode amm info"))?;
     Ok(amm_info)
@@ -51,6 +59,67 @@ pub async fn fetch_market_state(
     market_state_decode(&market_data).ok_or_else(|| anyhow!("Failed to decode market state"))
 }
 
+/// Market state of `amm_info.market`, or `None` when the pool has no OpenBook market (unset, closed,
+/// or not owned by the pool's `serum_dex`); such pools swap through the v2 instructions.
+pub async fn fetch_market_state_if_any(
+    rpc: &SolanaRpcClient,
+    amm_info: &AmmInfo,
+) -> Result<Option<MarketState>, anyhow::Error> {
+    if amm_info.market == Pubkey::default() || amm_info.serum_dex == Pubkey::default() {
+        return Ok(None);
+    }
+    let account = rpc.get_multiple_accounts(&[amm_info.market]).await?.pop().flatten();
+    Ok(account
+        .filter(|account| account.owner == amm_info.serum_dex)
+        .and_then(|account| market_state_decode(&account.data)))
+}
+
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+fn token_account_amount(data: &[u8]) -> Option<u64> {
+    data.get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_le_bytes)
+}
+
+/// Fetch both pool vaults and the open orders account in one `getMultipleAccounts` call,
+/// so the returned reserves come from the same slot. Pools without an open orders account
+/// (no OpenBook market) report zero open orders totals.
+pub async fn fetch_amm_reserves(
+    rpc: &SolanaRpcClient,
+    amm_info: &AmmInfo,
+) -> Result<AmmReserves, anyhow::Error> {
+    let accounts = rpc
+        .get_multiple_accounts(&[amm_info.token_coin, amm_info.token_pc, amm_info.open_orders])
+        .await?;
+    let account_data = |index: usize, name: &str| {
+        accounts
+            .get(index)
+            .and_then(Option::as_ref)
+            .map(|account| account.data.as_slice())
+            .ok_or_else(|| anyhow!("Raydium AMM v4 {} account was not found", name))
+    };
+    let coin_vault_amount = token_account_amount(account_data(0, "coin vault")?)
+        .ok_or_else(|| anyhow!("Failed to decode coin vault amount"))?;
+    let pc_vault_amount = token_account_amount(account_data(1, "pc vault")?)
+        .ok_or_else(|| anyhow!("Failed to decode pc vault amount"))?;
+    let (open_orders_coin_total, open_orders_pc_total) =
+        match accounts.get(2).and_then(Option::as_ref) {
+            Some(account) => open_orders_totals_decode(&account.data)
+                .ok_or_else(|| anyhow!("Failed to decode open orders"))?,
+            None => (0, 0),
+        };
+
+    Ok(AmmReserves {
+        coin_vault_amount,
+        pc_vault_amount,
+        open_orders_coin_total,
+        open_orders_pc_total,
+        need_take_pnl_coin: amm_info.out_put.need_take_pnl_coin,
+        need_take_pnl_pc: amm_info.out_put.need_take_pnl_pc,
+    })
+}
+
 pub fn derive_serum_vault_signer(
     serum_program: &Pubkey,
     serum_market: &Pubkey,
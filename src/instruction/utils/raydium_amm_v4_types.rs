@@ -78,6 +78,129 @@ pub fn amm_info_decode(data: &[u8]) -> Option<AmmInfo> {
     borsh::from_slice::<AmmInfo>(&data[..AMM_INFO_SIZE]).ok()
 }
 
+/// Zero-copy view of the [`AmmInfo`] account: borrowed straight from account bytes (RPC or
+/// Geyser) without decoding the fields the trade path never reads.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AmmInfoLayout {
+    status: [u8; 8],
+    _nonce_to_depth: [u8; 24],
+    coin_decimals: [u8; 8],
+    pc_decimals: [u8; 8],
+    _state_to_sys_decimal_value: [u8; 80],
+    _min_separate: [u8; 16],
+    trade_fee_numerator: [u8; 8],
+    trade_fee_denominator: [u8; 8],
+    _pnl_ratio: [u8; 16],
+    swap_fee_numerator: [u8; 8],
+    swap_fee_denominator: [u8; 8],
+    need_take_pnl_coin: [u8; 8],
+    need_take_pnl_pc: [u8; 8],
+    _out_put_rest: [u8; 128],
+    token_coin: [u8; 32],
+    token_pc: [u8; 32],
+    coin_mint: [u8; 32],
+    pc_mint: [u8; 32],
+    lp_mint: [u8; 32],
+    open_orders: [u8; 32],
+    market: [u8; 32],
+    serum_dex: [u8; 32],
+    target_orders: [u8; 32],
+    _withdraw_queue: [u8; 32],
+    _token_temp_lp: [u8; 32],
+    amm_owner: [u8; 32],
+    lp_amount: [u8; 8],
+    _tail: [u8; 24],
+}
+
+// SAFETY: `repr(C)` over byte arrays only: alignment 1, no padding, every bit pattern valid.
+unsafe impl bytemuck::Zeroable for AmmInfoLayout {}
+unsafe impl bytemuck::Pod for AmmInfoLayout {}
+
+impl AmmInfoLayout {
+    /// Borrow the AMM account bytes (no discriminator) as a layout; `None` when too short.
+    #[inline]
+    pub fn decode_from_slice(data: &[u8]) -> Option<&Self> {
+        bytemuck::try_from_bytes(data.get(..AMM_INFO_SIZE)?).ok()
+    }
+
+    #[inline]
+    pub fn status(&self) -> u64 {
+        u64::from_le_bytes(self.status)
+    }
+    #[inline]
+    pub fn coin_decimals(&self) -> u64 {
+        u64::from_le_bytes(self.coin_decimals)
+    }
+    #[inline]
+    pub fn pc_decimals(&self) -> u64 {
+        u64::from_le_bytes(self.pc_decimals)
+    }
+    #[inline]
+    pub fn trade_fee(&self) -> (u64, u64) {
+        (
+            u64::from_le_bytes(self.trade_fee_numerator),
+            u64::from_le_bytes(self.trade_fee_denominator),
+        )
+    }
+    #[inline]
+    pub fn swap_fee(&self) -> (u64, u64) {
+        (u64::from_le_bytes(self.swap_fee_numerator), u64::from_le_bytes(self.swap_fee_denominator))
+    }
+    #[inline]
+    pub fn need_take_pnl_coin(&self) -> u64 {
+        u64::from_le_bytes(self.need_take_pnl_coin)
+    }
+    #[inline]
+    pub fn need_take_pnl_pc(&self) -> u64 {
+        u64::from_le_bytes(self.need_take_pnl_pc)
+    }
+    #[inline]
+    pub fn token_coin(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token_coin)
+    }
+    #[inline]
+    pub fn token_pc(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token_pc)
+    }
+    #[inline]
+    pub fn coin_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.coin_mint)
+    }
+    #[inline]
+    pub fn pc_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.pc_mint)
+    }
+    #[inline]
+    pub fn lp_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.lp_mint)
+    }
+    #[inline]
+    pub fn open_orders(&self) -> Pubkey {
+        Pubkey::new_from_array(self.open_orders)
+    }
+    #[inline]
+    pub fn market(&self) -> Pubkey {
+        Pubkey::new_from_array(self.market)
+    }
+    #[inline]
+    pub fn serum_dex(&self) -> Pubkey {
+        Pubkey::new_from_array(self.serum_dex)
+    }
+    #[inline]
+    pub fn target_orders(&self) -> Pubkey {
+        Pubkey::new_from_array(self.target_orders)
+    }
+    #[inline]
+    pub fn amm_owner(&self) -> Pubkey {
+        Pubkey::new_from_array(self.amm_owner)
+    }
+    #[inline]
+    pub fn lp_amount(&self) -> u64 {
+        u64::from_le_bytes(self.lp_amount)
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, BorshDeserialize)]
 pub struct MarketState {
     pub padding: [u8; 5],
@@ -112,3 +235,106 @@ pub fn market_state_decode(data: &[u8]) -> Option<MarketState> {
     }
     borsh::from_slice::<MarketState>(&data[..MARKET_STATE_SIZE]).ok()
 }
+
+/// Serum/OpenBook `OpenOrders` layout: 5-byte padding, account_flags, market, owner,
+/// then native_coin_free, native_coin_total, native_pc_free, native_pc_total.
+const OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET: usize = 85;
+const OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET: usize = 101;
+
+/// Decode `(native_coin_total, native_pc_total)` from an AMM open orders account.
+pub fn open_orders_totals_decode(data: &[u8]) -> Option<(u64, u64)> {
+    let read = |offset: usize| {
+        data.get(offset..offset + 8).and_then(|b| b.try_into().ok()).map(u64::from_le_bytes)
+    };
+    Some((read(OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET)?, read(OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET)?))
+}
+
+/// Reserve snapshot of an AMM v4 pool, read in a single RPC round trip.
+///
+/// The program prices swaps against `vault + open_orders_total - need_take_pnl`, not the raw
+/// vault balance; use [`AmmReserves::coin_reserve`] / [`AmmReserves::pc_reserve`] for swap math.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AmmReserves {
+    pub coin_vault_amount: u64,
+    pub pc_vault_amount: u64,
+    pub open_orders_coin_total: u64,
+    pub open_orders_pc_total: u64,
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+}
+
+impl AmmReserves {
+    /// Effective coin reserve used by the AMM program.
+    #[inline]
+    pub fn coin_reserve(&self) -> u64 {
+        self.coin_vault_amount
+            .saturating_add(self.open_orders_coin_total)
+            .saturating_sub(self.need_take_pnl_coin)
+    }
+
+    /// Effective pc reserve used by the AMM program.
+    #[inline]
+    pub fn pc_reserve(&self) -> u64 {
+        self.pc_vault_amount
+            .saturating_add(self.open_orders_pc_total)
+            .saturating_sub(self.need_take_pnl_pc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_open_orders_totals() {
+        let mut data = vec![0u8; 3228];
+        data[OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET..OPEN_ORDERS_NATIVE_COIN_TOTAL_OFFSET + 8]
+            .copy_from_slice(&11u64.to_le_bytes());
+        data[OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET..OPEN_ORDERS_NATIVE_PC_TOTAL_OFFSET + 8]
+            .copy_from_slice(&22u64.to_le_bytes());
+
+        assert_eq!(open_orders_totals_decode(&data), Some((11, 22)));
+        assert_eq!(open_orders_totals_decode(&data[..100]), None);
+    }
+
+    #[test]
+    fn amm_info_layout_matches_borsh_decode() {
+        let mut data = vec![0u8; AMM_INFO_SIZE];
+        data[32..40].copy_from_slice(&6u64.to_le_bytes());
+        data[192..200].copy_from_slice(&10u64.to_le_bytes());
+        data[200..208].copy_from_slice(&300u64.to_le_bytes());
+        for (i, chunk) in data[336..720].chunks_mut(32).enumerate() {
+            chunk.fill(i as u8 + 1);
+        }
+        data[720..728].copy_from_slice(&99u64.to_le_bytes());
+
+        let info = amm_info_decode(&data).unwrap();
+        let layout = AmmInfoLayout::decode_from_slice(&data).unwrap();
+        assert_eq!(std::mem::size_of::<AmmInfoLayout>(), AMM_INFO_SIZE);
+        assert_eq!(layout.coin_decimals(), info.coin_decimals);
+        assert_eq!(layout.need_take_pnl_coin(), info.out_put.need_take_pnl_coin);
+        assert_eq!(layout.need_take_pnl_pc(), info.out_put.need_take_pnl_pc);
+        assert_eq!(layout.token_coin(), info.token_coin);
+        assert_eq!(layout.open_orders(), info.open_orders);
+        assert_eq!(layout.market(), info.market);
+        assert_eq!(layout.target_orders(), info.target_orders);
+        assert_eq!(layout.amm_owner(), info.amm_owner);
+        assert_eq!(layout.lp_amount(), info.lp_amount);
+        assert!(AmmInfoLayout::decode_from_slice(&data[1..]).is_none());
+    }
+
+    #[test]
+    fn effective_reserves_adjust_for_open_orders_and_pnl() {
+        let reserves = AmmReserves {
+            coin_vault_amount: 1_000,
+            pc_vault_amount: 5_000,
+            open_orders_coin_total: 50,
+            open_orders_pc_total: 200,
+            need_take_pnl_coin: 10,
+            need_take_pnl_pc: 300,
+        };
+
+        assert_eq!(reserves.coin_reserve(), 1_040);
+        assert_eq!(reserves.pc_reserve(), 4_900);
+    }
+}
@@ -39,10 +39,14 @@ pub async fn fetch_pool_state(
     rpc: &SolanaRpcClient,
     pool_address: &Pubkey,
 ) -> Result<PoolState, anyhow::Error> {
-    let account = rpc.get_account(pool_address).await?;
+    let account = crate::common::rpc_cache::get_account_coalesced(rpc, pool_address).await?;
     if account.owner != accounts::RAYDIUM_CPMM {
         return Err(anyhow!("Account is not owned by Raydium Cpmm program"));
     }
+    crate::common::protocol_layout::check_layout(
+        crate::trading::factory::DexType::RaydiumCpmm,
+        &account.data,
+    )?;
     let pool_state = pool_state_decode(&account.data[8..])
         .ok_or_else(|| anyhow!("Failed to decode pool state"))?;
     Ok(pool_state)
@@ -52,22 +56,19 @@ pub fn get_pool_pda(amm_config: &Pubkey, mint1: &Pubkey, mint2: &Pubkey) -> Opti
     let seeds: &[&[u8]; 4] =
         &[seeds::POOL_SEED, amm_config.as_ref(), mint1.as_ref(), mint2.as_ref()];
     let program_id: &Pubkey = &accounts::RAYDIUM_CPMM;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
 
 pub fn get_vault_pda(pool_state: &Pubkey, mint: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 3] = &[seeds::POOL_VAULT_SEED, pool_state.as_ref(), mint.as_ref()];
     let program_id: &Pubkey = &accounts::RAYDIUM_CPMM;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
 
 pub fn get_observation_state_pda(pool_state: &Pubkey) -> Option<Pubkey> {
     let seeds: &[&[u8]; 2] = &[seeds::OBSERVATION_STATE_SEED, pool_state.as_ref()];
     let program_id: &Pubkey = &accounts::RAYDIUM_CPMM;
-    let pda: Option<(Pubkey, u8)> = Pubkey::try_find_program_address(seeds, program_id);
-    pda.map(|pubkey| pubkey.0)
+    crate::common::fast_fn::find_program_address_cached(seeds, program_id)
 }
 
 /// Get the balances of two tokens in the pool
@@ -29,6 +29,8 @@ pub struct PoolState {
     pub padding: [u64; 31],
 }
 
+/// Anchor discriminator of `PoolState` accounts.
+pub const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
 pub const POOL_STATE_SIZE: usize = 629;
 
 pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
@@ -37,3 +39,128 @@ pub fn pool_state_decode(data: &[u8]) -> Option<PoolState> {
     }
     borsh::from_slice::<PoolState>(&data[..POOL_STATE_SIZE]).ok()
 }
+
+/// Zero-copy view of the CPMM [`PoolState`] account body (after the 8-byte discriminator),
+/// e.g. for Geyser account bytes.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PoolStateLayout {
+    amm_config: [u8; 32],
+    pool_creator: [u8; 32],
+    token0_vault: [u8; 32],
+    token1_vault: [u8; 32],
+    lp_mint: [u8; 32],
+    token0_mint: [u8; 32],
+    token1_mint: [u8; 32],
+    token0_program: [u8; 32],
+    token1_program: [u8; 32],
+    observation_key: [u8; 32],
+    auth_bump: u8,
+    status: u8,
+    lp_mint_decimals: u8,
+    mint0_decimals: u8,
+    mint1_decimals: u8,
+    lp_supply: [u8; 8],
+    protocol_fees_token0: [u8; 8],
+    protocol_fees_token1: [u8; 8],
+    fund_fees_token0: [u8; 8],
+    fund_fees_token1: [u8; 8],
+    open_time: [u8; 8],
+    recent_epoch: [u8; 8],
+    _padding: [u8; 248],
+}
+
+// SAFETY: `repr(C)` over bytes only: alignment 1, no padding, every bit pattern valid.
+unsafe impl bytemuck::Zeroable for PoolStateLayout {}
+unsafe impl bytemuck::Pod for PoolStateLayout {}
+
+impl PoolStateLayout {
+    /// Borrow the account body (no discriminator) as a layout; `None` when too short.
+    #[inline]
+    pub fn decode_from_slice(data: &[u8]) -> Option<&Self> {
+        bytemuck::try_from_bytes(data.get(..POOL_STATE_SIZE)?).ok()
+    }
+
+    #[inline]
+    pub fn amm_config(&self) -> Pubkey {
+        Pubkey::new_from_array(self.amm_config)
+    }
+    #[inline]
+    pub fn token0_vault(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token0_vault)
+    }
+    #[inline]
+    pub fn token1_vault(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token1_vault)
+    }
+    #[inline]
+    pub fn token0_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token0_mint)
+    }
+    #[inline]
+    pub fn token1_mint(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token1_mint)
+    }
+    #[inline]
+    pub fn token0_program(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token0_program)
+    }
+    #[inline]
+    pub fn token1_program(&self) -> Pubkey {
+        Pubkey::new_from_array(self.token1_program)
+    }
+    #[inline]
+    pub fn observation_key(&self) -> Pubkey {
+        Pubkey::new_from_array(self.observation_key)
+    }
+    #[inline]
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+    #[inline]
+    pub fn mint_decimals(&self) -> (u8, u8) {
+        (self.mint0_decimals, self.mint1_decimals)
+    }
+    /// Protocol + fund fees held in the vaults that are not part of the tradable reserves.
+    #[inline]
+    pub fn pending_fees(&self) -> (u64, u64) {
+        let fee =
+            |a: [u8; 8], b: [u8; 8]| u64::from_le_bytes(a).saturating_add(u64::from_le_bytes(b));
+        (
+            fee(self.protocol_fees_token0, self.fund_fees_token0),
+            fee(self.protocol_fees_token1, self.fund_fees_token1),
+        )
+    }
+    #[inline]
+    pub fn open_time(&self) -> u64 {
+        u64::from_le_bytes(self.open_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_state_layout_matches_borsh_decode() {
+        let mut data = vec![0u8; POOL_STATE_SIZE];
+        for (i, chunk) in data[..320].chunks_mut(32).enumerate() {
+            chunk.fill(i as u8 + 1);
+        }
+        data[323] = 6;
+        data[324] = 9;
+        data[333..341].copy_from_slice(&7u64.to_le_bytes());
+        data[349..357].copy_from_slice(&5u64.to_le_bytes());
+        data[365..373].copy_from_slice(&1_700_000_000u64.to_le_bytes());
+
+        let pool = pool_state_decode(&data).unwrap();
+        let layout = PoolStateLayout::decode_from_slice(&data).unwrap();
+        assert_eq!(std::mem::size_of::<PoolStateLayout>(), POOL_STATE_SIZE);
+        assert_eq!(layout.token0_vault(), pool.token0_vault);
+        assert_eq!(layout.token1_mint(), pool.token1_mint);
+        assert_eq!(layout.observation_key(), pool.observation_key);
+        assert_eq!(layout.mint_decimals(), (pool.mint0_decimals, pool.mint1_decimals));
+        assert_eq!(layout.pending_fees(), (12, 0));
+        assert_eq!(layout.open_time(), pool.open_time);
+    }
+}
@@ -0,0 +1,187 @@
+//! Account layouts and decoders per protocol, as a stable public surface.
+//!
+//! Geyser plugins and indexers that watch pool and curve accounts should decode them with the
+//! same code the trader uses, so a layout change breaks both the same way instead of the indexer
+//! silently reading shifted fields. Each protocol module re-exports the account struct, its
+//! zero-copy `*Layout` view where one exists, and an `unpack` taking the raw account data as
+//! delivered (Anchor discriminator included). `unpack` first matches the data against the layouts
+//! the SDK knows ([`crate::common::protocol_layout::detect_layout`]) and returns `None` for
+//! anything else. [`unpack_account`] routes by owning program.
+//!
+//! Only protocols the SDK trades are covered; Raydium CLMM and Orca Whirlpool pools are not
+//! decoded anywhere in the SDK yet.
+//! 各协议账户结构与解码函数的公开入口，供 Geyser 插件/索引器复用与交易路径完全相同的解码逻辑。
+
+use crate::common::protocol_layout::detect_layout;
+use crate::trading::factory::DexType;
+use solana_sdk::pubkey::Pubkey;
+
+/// Raw account body after the 8-byte Anchor discriminator, if `data` matches a known layout.
+#[inline]
+fn anchor_body(dex: DexType, data: &[u8]) -> Option<&[u8]> {
+    detect_layout(dex, data)?;
+    data.get(8..)
+}
+
+/// Pump.fun bonding curve.
+pub mod pumpfun {
+    use super::*;
+    use borsh::BorshDeserialize;
+
+    pub use crate::common::bonding_curve::BondingCurveAccount;
+    pub use crate::instruction::utils::pumpfun::BONDING_CURVE_ACCOUNT_DISCRIMINATOR;
+
+    /// Decode a bonding curve account. `account` is left default; set it to the account address
+    /// when the caller needs [`BondingCurveAccount::get_creator_vault_pda`].
+    pub fn unpack(data: &[u8]) -> Option<BondingCurveAccount> {
+        // Same as `fetch_bonding_curve_account`: trailing bytes from newer fields are ignored.
+        BondingCurveAccount::deserialize(&mut anchor_body(DexType::PumpFun, data)?).ok()
+    }
+}
+
+/// PumpSwap AMM pool.
+pub mod pumpswap {
+    use super::*;
+
+    pub use crate::instruction::utils::pumpswap_types::{
+        effective_quote_reserves, pool_decode, Pool, PoolLayout, LEGACY_POOL_SIZE,
+        POOL_DISCRIMINATOR, POOL_SIZE,
+    };
+
+    /// Decode a pool account, current or legacy layout.
+    pub fn unpack(data: &[u8]) -> Option<Pool> {
+        pool_decode(anchor_body(DexType::PumpSwap, data)?)
+    }
+
+    /// Zero-copy view of a current-layout pool; `None` for legacy pools.
+    pub fn unpack_layout(data: &[u8]) -> Option<&PoolLayout> {
+        PoolLayout::decode_from_slice(anchor_body(DexType::PumpSwap, data)?)
+    }
+}
+
+/// Bonk (Raydium LaunchLab) pool state.
+pub mod bonk {
+    use super::*;
+
+    pub use crate::instruction::utils::bonk_types::{
+        pool_state_decode, PoolState, PoolStateLayout, POOL_STATE_DISCRIMINATOR, POOL_STATE_SIZE,
+    };
+
+    /// Decode a pool state account.
+    pub fn unpack(data: &[u8]) -> Option<PoolState> {
+        pool_state_decode(anchor_body(DexType::Bonk, data)?)
+    }
+
+    /// Zero-copy view of a pool state account.
+    pub fn unpack_layout(data: &[u8]) -> Option<&PoolStateLayout> {
+        PoolStateLayout::decode_from_slice(anchor_body(DexType::Bonk, data)?)
+    }
+}
+
+/// Raydium CPMM pool state.
+pub mod raydium_cpmm {
+    use super::*;
+
+    pub use crate::instruction::utils::raydium_cpmm_types::{
+        pool_state_decode, PoolState, PoolStateLayout, POOL_STATE_DISCRIMINATOR, POOL_STATE_SIZE,
+    };
+
+    /// Decode a pool state account.
+    pub fn unpack(data: &[u8]) -> Option<PoolState> {
+        pool_state_decode(anchor_body(DexType::RaydiumCpmm, data)?)
+    }
+
+    /// Zero-copy view of a pool state account.
+    pub fn unpack_layout(data: &[u8]) -> Option<&PoolStateLayout> {
+        PoolStateLayout::decode_from_slice(anchor_body(DexType::RaydiumCpmm, data)?)
+    }
+}
+
+/// Raydium AMM v4 pool (`AmmInfo`, no discriminator) and its OpenBook market.
+pub mod raydium_amm_v4 {
+    use super::*;
+
+    pub use crate::instruction::utils::raydium_amm_v4_types::{
+        amm_info_decode, market_state_decode, open_orders_totals_decode, AmmInfo, AmmInfoLayout,
+        AmmReserves, MarketState, AMM_INFO_SIZE, MARKET_STATE_SIZE,
+    };
+
+    /// Decode an `AmmInfo` account.
+    pub fn unpack(data: &[u8]) -> Option<AmmInfo> {
+        detect_layout(DexType::RaydiumAmmV4, data)?;
+        amm_info_decode(data)
+    }
+
+    /// Zero-copy view of an `AmmInfo` account.
+    pub fn unpack_layout(data: &[u8]) -> Option<&AmmInfoLayout> {
+        detect_layout(DexType::RaydiumAmmV4, data)?;
+        AmmInfoLayout::decode_from_slice(data)
+    }
+}
+
+/// Meteora DAMM v2 pool.
+pub mod meteora_damm_v2 {
+    use super::*;
+
+    pub use crate::instruction::utils::meteora_damm_v2_types::{
+        pool_decode, Pool, POOL_DISCRIMINATOR, POOL_SIZE,
+    };
+
+    /// Decode a pool account.
+    pub fn unpack(data: &[u8]) -> Option<Pool> {
+        pool_decode(anchor_body(DexType::MeteoraDammV2, data)?)
+    }
+}
+
+/// A decoded protocol account, see [`unpack_account`]. Boxed since the layouts range from
+/// ~150 bytes to over 1 KB.
+#[derive(Debug, Clone)]
+pub enum ProtocolAccount {
+    PumpFunBondingCurve(Box<pumpfun::BondingCurveAccount>),
+    PumpSwapPool(Box<pumpswap::Pool>),
+    BonkPoolState(Box<bonk::PoolState>),
+    RaydiumCpmmPoolState(Box<raydium_cpmm::PoolState>),
+    RaydiumAmmV4(Box<raydium_amm_v4::AmmInfo>),
+    MeteoraDammV2Pool(Box<meteora_damm_v2::Pool>),
+}
+
+/// Decode an account owned by `owner`. Bonk and Raydium CPMM pool states share a discriminator,
+/// so the owner decides which layout applies. `None` for other programs and account types.
+pub fn unpack_account(owner: &Pubkey, data: &[u8]) -> Option<ProtocolAccount> {
+    let account = match crate::programs::dex_for_program(owner)? {
+        DexType::PumpFun => ProtocolAccount::PumpFunBondingCurve(Box::new(pumpfun::unpack(data)?)),
+        DexType::PumpSwap => ProtocolAccount::PumpSwapPool(Box::new(pumpswap::unpack(data)?)),
+        DexType::Bonk => ProtocolAccount::BonkPoolState(Box::new(bonk::unpack(data)?)),
+        DexType::RaydiumCpmm => {
+            ProtocolAccount::RaydiumCpmmPoolState(Box::new(raydium_cpmm::unpack(data)?))
+        }
+        DexType::RaydiumAmmV4 => {
+            ProtocolAccount::RaydiumAmmV4(Box::new(raydium_amm_v4::unpack(data)?))
+        }
+        DexType::MeteoraDammV2 => {
+            ProtocolAccount::MeteoraDammV2Pool(Box::new(meteora_damm_v2::unpack(data)?))
+        }
+    };
+    Some(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::programs;
+
+    #[test]
+    fn routes_shared_discriminator_by_owner() {
+        let mut data = bonk::POOL_STATE_DISCRIMINATOR.to_vec();
+        data.resize(8 + bonk::POOL_STATE_SIZE.max(raydium_cpmm::POOL_STATE_SIZE), 0);
+
+        assert!(matches!(
+            unpack_account(&programs::RAYDIUM_CPMM.program, &data),
+            Some(ProtocolAccount::RaydiumCpmmPoolState(_))
+        ));
+        assert!(unpack_account(&Pubkey::new_unique(), &data).is_none());
+        assert!(pumpswap::unpack(&data).is_none());
+        assert!(raydium_cpmm::unpack_layout(&data).is_some());
+        assert!(raydium_cpmm::unpack(&data[..data.len() - 1]).is_none());
+    }
+}
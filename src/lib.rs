@@ -1,17 +1,79 @@
 pub mod client;
 pub mod common;
 pub mod constants;
+#[cfg(feature = "devnet-tools")]
+pub mod devnet;
 pub mod instruction;
+pub mod layouts;
 pub mod perf;
+pub mod programs;
 pub mod swqos;
 pub mod trading;
 pub mod utils;
 
+pub use crate::common::keypair::{
+    load_keypair, load_keypair_from_env, load_keypair_from_mnemonic, load_keypair_from_string,
+    Source, DEFAULT_DERIVATION_PATH,
+};
+pub use crate::common::landing_model::{LandingFeeModel, LandingPrice};
+pub use crate::common::latency_budget::{
+    LaneLatency, LatencyBudget, LatencyBudgetAction, LatencyReport, LatencyStage,
+    LATENCY_BUDGET_ERROR_CODE,
+};
+pub use crate::common::market_data::{Candle, MarketDataCache, RollingVolume, SwapTick};
+pub use crate::common::mint_analytics::{
+    analyze_bundles, analyze_mint, BundleAnalysis, FundingCluster, MintAnalytics, VolumeSource,
+};
 pub use crate::common::nonce_cache::{fetch_nonce_info, DurableNonceInfo};
+pub use crate::common::order_dedup::{InMemoryOrderIdStore, OrderClaim, OrderDedup, OrderIdStore};
+pub use crate::common::protocol_layout::{set_layout_version, LayoutVersion};
+pub use crate::common::risk_manager::{BuyReservation, RiskLimits, RiskManager, RiskRejection};
+pub use crate::common::rpc_usage::{
+    with_rpc_scope, RpcCallStats, RpcCreditTable, RpcUsageStats, RPC_SCOPE_CACHE,
+    RPC_SCOPE_CONFIRMATION, RPC_SCOPE_OTHER, RPC_SCOPE_PARAMS,
+};
+pub use crate::common::seed_accounts;
+pub use crate::common::slot_tracker::{
+    BlockhashValidity, SlotGate, SlotTracker, BLOCKHASH_VALIDITY_SLOTS,
+};
+pub use crate::common::state_snapshot::{
+    DcaProgressState, DedupKeyState, OrderSide, PendingOrderState, PositionState, StateSnapshot,
+    STATE_SNAPSHOT_VERSION,
+};
+pub use crate::common::trade_events::{
+    ConfirmationDedup, TradeEventBus, TradeExecuted, CONFIRMATION_DEDUP_WINDOW,
+};
+pub use crate::common::trading_halt::{TradingHalt, TRADING_HALTED_ERROR_CODE};
+pub use crate::common::wallet_watcher::{WalletEvent, WalletWatcher};
+pub use crate::perf::thread_tuning::{
+    enter_hot_path, HotPathStage, HotPathTuning, StageTuning, ThreadPriority,
+};
+pub use crate::trading::account_discovery::{AccountDiscovery, AccountOverrideMiddleware};
+pub use crate::trading::common::fill::{parse_trade_from_signature, Fill};
+pub use crate::trading::common::{
+    ComputeBudgetManager, ComputeBudgetRequest, DefaultComputeBudgetManager,
+    TipOnlyComputeBudgetManager, WsolLockMode,
+};
+pub use crate::trading::core::signing_pool::SigningPool;
+pub use crate::trading::fee_sponsor::{FeeSponsor, HttpFeeSponsor, SendMode};
+pub use crate::trading::params_provider::ParamsProvider;
+pub use crate::utils::calc::depth::{estimate_max_trade_size, MaxTradeSize, PoolDepth};
 // Re-export transport selectors used by SWQoS configs (including Glaive).
-pub use crate::swqos::{AstralaneTransport, SwqosTransport};
+pub use crate::swqos::common::{
+    ConfirmationLevel, ConfirmationOptions, ConfirmationStatus, HttpClientTuning, RpcSendOptions,
+    CONFIRMATION_TIMEOUT_ERROR_CODE, NO_SWQOS_AVAILABLE_ERROR_CODE,
+    SIMULATION_UNSUPPORTED_ERROR_CODE,
+};
+pub use crate::swqos::{
+    AstralaneTransport, SwqosConfigBuilder, SwqosEndpointTable, SwqosTransport,
+};
 pub use client::{
-    find_pool_by_mint, recommended_sender_thread_core_indices, AccountPolicy, BuyAmount,
-    SellAmount, SimpleBuyParams, SimpleSellParams, SolanaTrade, TradeBuyParams, TradeSellParams,
-    TradeTokenType, TradingClient, TradingInfrastructure,
+    find_pool_by_mint, recommended_sender_thread_core_indices, AccountAction, AccountActionKind,
+    AccountPolicy, AtaProvisioning, BroadcastOptions, BroadcastRouteStats, BuyAmount,
+    CleanupOutcome, DetailedTradeResult, FeeLane, LeverageOutcome, LeverageSwap, LeveragedPosition,
+    OfflineSignature, OfflineTransaction, PreflightError, QueuedTrade, QueuedTradeResult,
+    QuoteKind, SellAmount, SellCheck, SimpleBuyParams, SimpleSellParams, SimulationFunding,
+    SolanaTrade, TradeBuyParams, TradeMemo, TradePlan, TradePriority, TradeQueue, TradeQueueConfig,
+    TradeQueueStats, TradeSellParams, TradeTokenType, TradingClient, TradingInfrastructure,
+    UnsupportedQuoteError, UtilityTxOptions, WithdrawAmount,
 };
@@ -0,0 +1,310 @@
+//! Benchmark harness: offline fixtures for instruction building, transaction assembly and
+//! serialization, shared by `benches/trading.rs`. Replace fields of [`sample_swap_params`]
+//! (gas strategy, lookup tables, nonce, middleware, compute budget manager) or pass your own
+//! [`SwapParams`] to measure your configuration; nothing here touches the network.
+//! 基准测试工具：离线构造各协议交易参数；替换为自己的配置即可测量实际耗时。
+
+use crate::{
+    common::{bonding_curve::BondingCurveAccount, GasFeeStrategy},
+    constants::{TOKEN_PROGRAM, WSOL_TOKEN_ACCOUNT},
+    instruction::utils::{bonk, pumpfun, pumpswap},
+    swqos::TradeType,
+    trading::{
+        common::transaction_builder::build_transaction_with_signers,
+        core::params::{
+            BonkParams, DexParamEnum, MeteoraDammV2Params, PumpFunParams, PumpSwapParams,
+            RaydiumAmmV4Params, RaydiumCpmmParams,
+        },
+        factory::DexType,
+        InstructionBuilder, SwapParams, TradeFactory,
+    },
+};
+use anyhow::Result;
+use solana_hash::Hash;
+use solana_message::AddressLookupTableAccount;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Keypair, transaction::VersionedTransaction,
+};
+use std::sync::Arc;
+
+/// Every protocol the harness has fixtures for.
+pub const ALL_DEX_TYPES: [DexType; 6] = [
+    DexType::PumpFun,
+    DexType::PumpSwap,
+    DexType::Bonk,
+    DexType::RaydiumCpmm,
+    DexType::RaydiumAmmV4,
+    DexType::MeteoraDammV2,
+];
+
+/// Compute unit limit / price used by [`assemble_transaction`].
+pub const BENCH_UNIT_LIMIT: u32 = 200_000;
+pub const BENCH_UNIT_PRICE: u64 = 100_000;
+/// Tip in SOL added by [`assemble_transaction`] when `with_tip` is set.
+pub const BENCH_TIP_SOL: f64 = 0.001;
+
+fn pk(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+/// Instruction builder the trade executor uses for `dex`.
+pub fn instruction_builder(dex: DexType) -> &'static dyn InstructionBuilder {
+    TradeFactory::instruction_builder(dex)
+}
+
+fn dex_type_of(params: &DexParamEnum) -> DexType {
+    match params {
+        DexParamEnum::PumpFun(_) => DexType::PumpFun,
+        DexParamEnum::PumpSwap(_) => DexType::PumpSwap,
+        DexParamEnum::Bonk(_) => DexType::Bonk,
+        DexParamEnum::RaydiumCpmm(_) => DexType::RaydiumCpmm,
+        DexParamEnum::RaydiumAmmV4(_) => DexType::RaydiumAmmV4,
+        DexParamEnum::MeteoraDammV2(_) => DexType::MeteoraDammV2,
+    }
+}
+
+/// Synthetic pool params for `dex` (fixed addresses, mid-curve reserves); the traded token is
+/// [`sample_mint`] against WSOL.
+pub fn sample_protocol_params(dex: DexType) -> DexParamEnum {
+    let mint = sample_mint(dex);
+    match dex {
+        DexType::PumpFun => {
+            let creator = pk(20);
+            let bonding_curve = BondingCurveAccount {
+                account: pumpfun::get_bonding_curve_pda(&mint).unwrap_or_default(),
+                virtual_token_reserves: pumpfun::global_constants::INITIAL_VIRTUAL_TOKEN_RESERVES,
+                virtual_sol_reserves: pumpfun::global_constants::INITIAL_VIRTUAL_SOL_RESERVES,
+                real_token_reserves: pumpfun::global_constants::INITIAL_REAL_TOKEN_RESERVES,
+                creator,
+                ..Default::default()
+            };
+            DexParamEnum::PumpFun(PumpFunParams {
+                bonding_curve: Arc::new(bonding_curve),
+                associated_bonding_curve: Pubkey::default(),
+                observed_trade_creator: Some(creator),
+                creator_vault: pumpfun::get_creator_vault_pda(&creator).unwrap_or_default(),
+                fee_sharing_creator_vault_if_active: None,
+                token_program: TOKEN_PROGRAM,
+                close_token_account_when_sell: None,
+                fee_recipient: pumpfun::global_constants::FEE_RECIPIENT,
+                quote_mint: Pubkey::default(),
+            })
+        }
+        DexType::PumpSwap => DexParamEnum::PumpSwap(PumpSwapParams::new(
+            pk(1),
+            mint,
+            WSOL_TOKEN_ACCOUNT,
+            pk(3),
+            pk(4),
+            1_000_000_000_000,
+            50_000_000_000,
+            0,
+            pk(5),
+            pumpswap::accounts::DEFAULT_COIN_CREATOR_VAULT_AUTHORITY,
+            TOKEN_PROGRAM,
+            TOKEN_PROGRAM,
+            pumpswap::accounts::PROTOCOL_FEE_RECIPIENT,
+            Pubkey::default(),
+            false,
+            0,
+        )),
+        DexType::Bonk => DexParamEnum::Bonk(BonkParams {
+            virtual_base: 1_073_025_605_596_382,
+            virtual_quote: 30_000_852_951,
+            real_base: 100_000_000_000_000,
+            real_quote: 3_000_000_000,
+            mint_token_program: TOKEN_PROGRAM,
+            platform_config: pk(8),
+            platform_associated_account: pk(9),
+            creator_associated_account: pk(10),
+            global_config: bonk::accounts::GLOBAL_CONFIG,
+            ..Default::default()
+        }),
+        DexType::RaydiumCpmm => DexParamEnum::RaydiumCpmm(RaydiumCpmmParams {
+            pool_state: pk(1),
+            amm_config: pk(2),
+            base_mint: WSOL_TOKEN_ACCOUNT,
+            quote_mint: mint,
+            base_reserve: 50_000_000_000,
+            quote_reserve: 1_000_000_000_000,
+            base_vault: pk(4),
+            quote_vault: pk(5),
+            base_token_program: TOKEN_PROGRAM,
+            quote_token_program: TOKEN_PROGRAM,
+            observation_state: pk(6),
+        }),
+        DexType::RaydiumAmmV4 => DexParamEnum::RaydiumAmmV4(
+            RaydiumAmmV4Params::new(
+                pk(1),
+                WSOL_TOKEN_ACCOUNT,
+                mint,
+                pk(3),
+                pk(4),
+                50_000_000_000,
+                1_000_000_000_000,
+            )
+            .with_market_accounts(
+                pk(5),
+                pk(6),
+                pk(7),
+                pk(8),
+                pk(9),
+                pk(10),
+                pk(11),
+                pk(12),
+                pk(13),
+                pk(14),
+            ),
+        ),
+        DexType::MeteoraDammV2 => DexParamEnum::MeteoraDammV2(MeteoraDammV2Params::new(
+            pk(1),
+            pk(2),
+            pk(3),
+            WSOL_TOKEN_ACCOUNT,
+            mint,
+            TOKEN_PROGRAM,
+            TOKEN_PROGRAM,
+        )),
+    }
+}
+
+/// Token bought by the fixtures. PumpFun uses a `pump`-suffixed mint like real launches.
+pub fn sample_mint(dex: DexType) -> Pubkey {
+    match dex {
+        DexType::PumpFun => "E3JvmGcGFDzhu2Cnxyeq5BRvN7HH9JZUsfAUh2v8pump".parse().unwrap(),
+        _ => pk(2),
+    }
+}
+
+/// Buy of 0.01 SOL worth of [`sample_mint`] on `dex`, creating the output ATA, with a tip and
+/// no RPC client. Fields are public; adjust them to benchmark other shapes.
+pub fn sample_swap_params(dex: DexType) -> SwapParams {
+    crate::common::seed::set_default_rents();
+    SwapParams {
+        rpc: None,
+        payer: Arc::new(Keypair::new()),
+        trade_type: TradeType::Buy,
+        input_mint: WSOL_TOKEN_ACCOUNT,
+        input_token_program: None,
+        output_mint: sample_mint(dex),
+        output_token_program: None,
+        input_amount: Some(10_000_000),
+        slippage_basis_points: Some(300),
+        address_lookup_table_accounts: Vec::new(),
+        recent_blockhash: Some(Hash::new_unique()),
+        wait_tx_confirmed: false,
+        confirmation: Default::default(),
+        rpc_send_options: Default::default(),
+        raw_transactions: None,
+        protocol_params: sample_protocol_params(dex),
+        open_seed_optimize: true,
+        swqos_clients: Arc::new(Vec::new()),
+        middleware_manager: None,
+        compute_budget_manager: None,
+        durable_nonce: None,
+        with_tip: true,
+        create_input_mint_ata: false,
+        close_input_mint_ata: false,
+        create_output_mint_ata: true,
+        close_output_mint_ata: false,
+        fixed_output_amount: None,
+        min_out_override: None,
+        gas_fee_strategy: GasFeeStrategy::new(),
+        data_size_limit: None,
+        heap_frame_bytes: None,
+        memo: None,
+        simulate: false,
+        log_enabled: false,
+        wait_for_all_submits: false,
+        use_dedicated_sender_threads: false,
+        sender_thread_cores: None,
+        max_sender_concurrency: 0,
+        effective_core_ids: Arc::new(Vec::new()),
+        check_min_tip: false,
+        grpc_recv_us: None,
+        use_exact_sol_amount: Some(true),
+        backrun_target: None,
+        simulation_gate: None,
+        latency_budget: None,
+        signing_pool: None,
+        additional_signers: Vec::new(),
+        fee_payer: None,
+        send_mode: Default::default(),
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        transaction_split: Default::default(),
+        simulation_prelude: Vec::new(),
+        prior_signatures: Vec::new(),
+        wsol_guard: None,
+        order_claim: None,
+    }
+}
+
+/// Lookup table holding every non-signer account of `instructions` (program ids included), i.e.
+/// the best case for transaction size.
+pub fn sample_lookup_table(instructions: &[Instruction]) -> AddressLookupTableAccount {
+    let mut addresses: Vec<Pubkey> = Vec::new();
+    for ix in instructions {
+        let keys = ix.accounts.iter().filter(|meta| !meta.is_signer).map(|meta| meta.pubkey);
+        for key in std::iter::once(ix.program_id).chain(keys) {
+            if !addresses.contains(&key) {
+                addresses.push(key);
+            }
+        }
+    }
+    AddressLookupTableAccount { key: pk(99), addresses }
+}
+
+/// Build buy instructions with the builder matching `params.protocol_params`.
+pub async fn build_buy_instructions(params: &SwapParams) -> Result<Vec<Instruction>> {
+    instruction_builder(dex_type_of(&params.protocol_params)).build_buy_instructions(params).await
+}
+
+/// Sign a full transaction around `instructions` the way a SWQOS worker does (compute budget,
+/// optional nonce and tip, lookup tables from `params`), at [`BENCH_UNIT_LIMIT`] /
+/// [`BENCH_UNIT_PRICE`].
+pub fn assemble_transaction(
+    params: &SwapParams,
+    instructions: &[Instruction],
+) -> Result<VersionedTransaction> {
+    build_transaction_with_signers(
+        &params.payer,
+        &params.additional_signers,
+        BENCH_UNIT_LIMIT,
+        BENCH_UNIT_PRICE,
+        instructions,
+        &params.address_lookup_table_accounts,
+        params.recent_blockhash,
+        params.middleware_manager.as_ref(),
+        params.compute_budget_manager.as_ref(),
+        Some(dex_type_of(&params.protocol_params)),
+        params.trade_type == TradeType::Buy,
+        params.with_tip,
+        &pk(98),
+        BENCH_TIP_SOL,
+        params.durable_nonce.as_ref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fixtures_build_and_assemble_for_every_dex() {
+        for dex in ALL_DEX_TYPES {
+            let mut params = sample_swap_params(dex);
+            let instructions = build_buy_instructions(&params).await.unwrap();
+            assert!(!instructions.is_empty(), "{dex:?}");
+
+            let plain = assemble_transaction(&params, &instructions).unwrap();
+            params.address_lookup_table_accounts = vec![sample_lookup_table(&instructions)];
+            let with_alt = assemble_transaction(&params, &instructions).unwrap();
+            assert!(
+                bincode::serialized_size(&with_alt).unwrap()
+                    < bincode::serialized_size(&plain).unwrap(),
+                "{dex:?}"
+            );
+        }
+    }
+}
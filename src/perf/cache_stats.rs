@@ -0,0 +1,110 @@
+//! 热路径缓存统计
+//!
+//! PDA / ATA 推导缓存（`common::fast_fn`）的命中率与条目数，用于确认热路径没有重复做 sha256；
+//! 以及发送路径的序列化缓存（`swqos::serialization`）：同一笔交易多通道共享编码、序列化缓冲池复用。
+
+use crate::common::fast_fn::{ATA_HITS, ATA_MISSES, PDA_HITS, PDA_MISSES};
+use crate::swqos::serialization::{self, ENCODE_HITS, ENCODE_MISSES};
+use std::sync::atomic::Ordering;
+
+/// Snapshot of the PDA and ATA derivation caches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DerivationCacheStats {
+    pub pda_hits: u64,
+    pub pda_misses: u64,
+    pub pda_entries: usize,
+    pub ata_hits: u64,
+    pub ata_misses: u64,
+    pub ata_entries: usize,
+}
+
+impl DerivationCacheStats {
+    /// PDA hit rate in `[0, 1]`; 0 before the first lookup.
+    pub fn pda_hit_rate(&self) -> f64 {
+        hit_rate(self.pda_hits, self.pda_misses)
+    }
+
+    /// ATA hit rate in `[0, 1]`; 0 before the first lookup.
+    pub fn ata_hit_rate(&self) -> f64 {
+        hit_rate(self.ata_hits, self.ata_misses)
+    }
+}
+
+#[inline]
+fn hit_rate(hits: u64, misses: u64) -> f64 {
+    let total = hits + misses;
+    if total == 0 {
+        0.0
+    } else {
+        hits as f64 / total as f64
+    }
+}
+
+/// 读取 PDA / ATA 缓存统计（计数自进程启动或上次 reset 起）
+pub fn derivation_cache_stats() -> DerivationCacheStats {
+    DerivationCacheStats {
+        pda_hits: PDA_HITS.load(Ordering::Relaxed),
+        pda_misses: PDA_MISSES.load(Ordering::Relaxed),
+        pda_entries: crate::common::fast_fn::pda_cache_len(),
+        ata_hits: ATA_HITS.load(Ordering::Relaxed),
+        ata_misses: ATA_MISSES.load(Ordering::Relaxed),
+        ata_entries: crate::common::fast_fn::ata_cache_len(),
+    }
+}
+
+/// 清零命中计数（缓存内容保留）
+pub fn reset_derivation_cache_stats() {
+    for counter in [&PDA_HITS, &PDA_MISSES, &ATA_HITS, &ATA_MISSES] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of the send-path serialization caches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SerializationStats {
+    /// Signed transactions whose encoding was reused within their lane (sign, send, capture).
+    pub encode_hits: u64,
+    /// Signed transactions serialized and encoded afresh.
+    pub encode_misses: u64,
+    pub encode_entries: usize,
+    /// Serialization buffers reused from the pool.
+    pub buffer_hits: u64,
+    /// Serialization buffers allocated because the pool was empty.
+    pub buffer_misses: u64,
+    pub buffers_available: usize,
+    pub buffer_capacity: usize,
+}
+
+impl SerializationStats {
+    /// Share of lanes that reused another lane's encoding; 0 before the first encode.
+    pub fn encode_hit_rate(&self) -> f64 {
+        hit_rate(self.encode_hits, self.encode_misses)
+    }
+
+    /// Share of serialization buffers served from the pool; 0 before the first take.
+    pub fn buffer_hit_rate(&self) -> f64 {
+        hit_rate(self.buffer_hits, self.buffer_misses)
+    }
+}
+
+/// 读取发送路径序列化缓存统计（计数自进程启动或上次 reset 起）
+pub fn serialization_stats() -> SerializationStats {
+    let (buffer_hits, buffer_misses) = serialization::serializer_pool_counters();
+    let (buffers_available, buffer_capacity) = serialization::get_serializer_stats();
+    SerializationStats {
+        encode_hits: ENCODE_HITS.load(Ordering::Relaxed),
+        encode_misses: ENCODE_MISSES.load(Ordering::Relaxed),
+        encode_entries: serialization::encoded_cache_len(),
+        buffer_hits,
+        buffer_misses,
+        buffers_available,
+        buffer_capacity,
+    }
+}
+
+/// 清零序列化命中计数（缓存与缓冲池内容保留）
+pub fn reset_serialization_stats() {
+    ENCODE_HITS.store(0, Ordering::Relaxed);
+    ENCODE_MISSES.store(0, Ordering::Relaxed);
+    serialization::reset_serializer_pool_counters();
+}
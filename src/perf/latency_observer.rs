@@ -0,0 +1,212 @@
+//! Latency observers: the timings behind the SDK timing log (build, before-submit, per-SWQOS
+//! submit, confirm) as structured events, for exporting to StatsD, OTLP or any other monitoring.
+//!
+//! Observers are process-wide, like the other perf counters: [`register_latency_observer`] once
+//! at startup and every client's live trades report to it. Callbacks run inline on the trade
+//! path after submission, so they should only record or enqueue. Nothing is timed or reported
+//! while no observer is registered, and simulations are never reported.
+//! 延迟观察者：把构建、提交前、各通道提交、确认耗时以结构化事件推送给外部监控。
+
+use crate::common::gas_fee_strategy::GasFeeStrategyType;
+use crate::common::SwqosSubmitTiming;
+use crate::swqos::{SwqosType, TradeType};
+use crate::trading::factory::DexType;
+use arc_swap::ArcSwap;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Stage of a trade a [`LatencyEvent`] measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LatencyStage {
+    /// Instructions built.
+    Build,
+    /// Ready to sign and submit.
+    BeforeSubmit,
+    /// One SWQOS lane finished submitting.
+    Submit { swqos_type: SwqosType, strategy_type: GasFeeStrategyType },
+    /// The trade landed through this lane (`wait_tx_confirmed` only).
+    Confirm { swqos_type: SwqosType },
+}
+
+impl LatencyStage {
+    /// Short metric name: `build`, `before_submit`, `submit` or `confirm`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Build => "build",
+            Self::BeforeSubmit => "before_submit",
+            Self::Submit { .. } => "submit",
+            Self::Confirm { .. } => "confirm",
+        }
+    }
+}
+
+/// One timing of a live trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyEvent {
+    pub dex_type: DexType,
+    pub trade_type: TradeType,
+    pub stage: LatencyStage,
+    /// Microseconds from the trade's start to the end of `stage`. The start is the event's
+    /// `grpc_recv_us` when the trade carries one, otherwise the SDK's entry into the swap; the
+    /// submit time of a lane alone is its `Submit` minus `BeforeSubmit`.
+    pub elapsed_us: u64,
+}
+
+/// Receives [`LatencyEvent`]s; see the module docs.
+pub trait LatencyObserver: Send + Sync {
+    fn on_latency(&self, event: &LatencyEvent);
+}
+
+/// Handle returned by [`register_latency_observer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LatencyObserverId(u64);
+
+type Observers = Vec<(LatencyObserverId, Arc<dyn LatencyObserver>)>;
+
+static OBSERVERS: Lazy<ArcSwap<Observers>> = Lazy::new(|| ArcSwap::from_pointee(Vec::new()));
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Report every live trade's timings to `observer` from now on.
+pub fn register_latency_observer(observer: Arc<dyn LatencyObserver>) -> LatencyObserverId {
+    let id = LatencyObserverId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    OBSERVERS.rcu(|current| {
+        let mut observers = (**current).clone();
+        observers.push((id, observer.clone()));
+        Arc::new(observers)
+    });
+    id
+}
+
+/// Stop reporting to the observer registered as `id`; false when it was not registered.
+pub fn unregister_latency_observer(id: LatencyObserverId) -> bool {
+    let previous = OBSERVERS.rcu(|current| {
+        let observers: Observers =
+            current.iter().filter(|(other, _)| *other != id).cloned().collect();
+        Arc::new(observers)
+    });
+    previous.iter().any(|(other, _)| *other == id)
+}
+
+/// Whether any observer is registered; the executor only takes timestamps for them when so.
+/// Read from the list itself, so a concurrent register and unregister cannot leave it stale.
+#[inline(always)]
+pub(crate) fn latency_observers_active() -> bool {
+    !OBSERVERS.load().is_empty()
+}
+
+/// Report a trade's timings, taken the same way as for
+/// [`crate::common::sdk_log::print_sdk_timing_block`].
+pub(crate) fn publish_trade_latency(
+    dex_type: DexType,
+    trade_type: TradeType,
+    start_us: i64,
+    build_end_us: Option<i64>,
+    before_submit_us: Option<i64>,
+    submit_timings: &[SwqosSubmitTiming],
+    confirm_us: Option<i64>,
+) {
+    let observers = OBSERVERS.load();
+    if observers.is_empty() {
+        return;
+    }
+    let elapsed = |end_us: i64| (end_us - start_us).max(0) as u64;
+    let mut events = Vec::with_capacity(3 + submit_timings.len());
+    for (stage, end_us) in
+        [(LatencyStage::Build, build_end_us), (LatencyStage::BeforeSubmit, before_submit_us)]
+    {
+        if let Some(end_us) = end_us {
+            events.push((stage, elapsed(end_us)));
+        }
+    }
+    for timing in submit_timings {
+        let stage = LatencyStage::Submit {
+            swqos_type: timing.swqos_type,
+            strategy_type: timing.strategy_type,
+        };
+        events.push((stage, elapsed(timing.submit_done_us)));
+    }
+    if let (Some(confirm_us), Some(landed)) =
+        (confirm_us, submit_timings.iter().find(|timing| timing.landed == Some(true)))
+    {
+        events.push((LatencyStage::Confirm { swqos_type: landed.swqos_type }, elapsed(confirm_us)));
+    }
+
+    for (stage, elapsed_us) in events {
+        let event = LatencyEvent { dex_type, trade_type, stage, elapsed_us };
+        for (_, observer) in observers.iter() {
+            observer.on_latency(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Signature;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<LatencyEvent>>);
+
+    impl LatencyObserver for Recorder {
+        fn on_latency(&self, event: &LatencyEvent) {
+            self.0.lock().unwrap().push(*event);
+        }
+    }
+
+    #[test]
+    fn observers_receive_each_stage_until_unregistered() {
+        let recorder = Arc::new(Recorder::default());
+        let id = register_latency_observer(recorder.clone());
+        assert!(latency_observers_active());
+
+        let timing = |swqos_type, submit_done_us, landed| SwqosSubmitTiming {
+            swqos_type,
+            strategy_type: GasFeeStrategyType::Normal,
+            submit_done_us,
+            signature: Signature::default(),
+            cu_price: 0,
+            tip_lamports: 0,
+            landed,
+            landed_slot: None,
+            block_time: None,
+            event_to_land_slots: None,
+            raw_transaction: None,
+        };
+        let timings = [
+            timing(SwqosType::Jito, 1_900, Some(false)),
+            timing(SwqosType::Default, 1_700, Some(true)),
+        ];
+        publish_trade_latency(
+            DexType::PumpFun,
+            TradeType::Buy,
+            1_000,
+            Some(1_200),
+            Some(1_300),
+            &timings,
+            Some(5_000),
+        );
+
+        let events = recorder.0.lock().unwrap().clone();
+        let stages: Vec<(&str, u64)> =
+            events.iter().map(|event| (event.stage.as_str(), event.elapsed_us)).collect();
+        assert_eq!(
+            stages,
+            [
+                ("build", 200),
+                ("before_submit", 300),
+                ("submit", 900),
+                ("submit", 700),
+                ("confirm", 4_000)
+            ]
+        );
+        assert_eq!(events[4].stage, LatencyStage::Confirm { swqos_type: SwqosType::Default });
+
+        assert!(unregister_latency_observer(id));
+        assert!(!unregister_latency_observer(id));
+        let seen = recorder.0.lock().unwrap().len();
+        publish_trade_latency(DexType::PumpFun, TradeType::Buy, 0, Some(1), None, &[], None);
+        assert_eq!(recorder.0.lock().unwrap().len(), seen);
+    }
+}
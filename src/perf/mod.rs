@@ -1,14 +1,22 @@
-//! Performance: SIMD, cache prefetch, branch hints, zero-copy I/O, syscall bypass, compiler hints.
-//! 性能优化：SIMD、缓存预取、分支提示、零拷贝 I/O、系统调用绕过、编译器提示。
+//! Performance: benchmark harness, SIMD, cache prefetch, derivation/serialization cache stats, latency observers, branch hints, zero-copy I/O, hot-path thread affinity/priority, compiler hints.
+//! 性能优化：基准测试工具、SIMD、缓存预取、地址推导与序列化缓存统计、延迟观察者、分支提示、零拷贝 I/O、热路径线程绑核与优先级、编译器提示。
 
+pub mod bench_harness;
+pub mod cache_stats;
 pub mod compiler_optimization;
 pub mod hardware_optimizations;
+pub mod latency_observer;
 pub mod simd;
-pub mod syscall_bypass;
+pub mod thread_tuning;
 pub mod zero_copy_io;
 
+pub use cache_stats::*;
 pub use compiler_optimization::*;
 pub use hardware_optimizations::*;
+pub use latency_observer::{
+    register_latency_observer, unregister_latency_observer, LatencyEvent, LatencyObserver,
+    LatencyObserverId, LatencyStage,
+};
 pub use simd::*;
-pub use syscall_bypass::*;
+pub use thread_tuning::*;
 pub use zero_copy_io::*;
@@ -0,0 +1,209 @@
+//! CPU affinity and thread priority for the trading hot path.
+//!
+//! Three kinds of threads sit on the latency-critical path of a trade:
+//! - [`HotPathStage::EventCallback`]: the caller's thread that receives the triggering event
+//!   (gRPC / shred stream callback). Call [`enter_hot_path`] at the top of that thread.
+//! - [`HotPathStage::BuildSign`]: transaction signing and serialization, run on a
+//!   [`SigningPool`](crate::trading::core::signing_pool::SigningPool).
+//! - [`HotPathStage::SwqosSubmit`]: the dedicated SWQOS sender threads.
+//!
+//! Describe all three in a [`HotPathTuning`] and hand it to
+//! `TradingClient::with_hot_path_tuning`; the SDK threads are pinned and prioritised when they
+//! start. Priorities other than [`ThreadPriority::Normal`] are Linux-only and usually need
+//! `CAP_SYS_NICE` (or an `rtprio` limit); give each stage cores nothing else runs on.
+//! 交易热路径的 CPU 绑核与线程优先级：事件回调、签名、SWQOS 发送三类线程分别配置。
+
+use anyhow::{anyhow, Result};
+use arc_swap::ArcSwapOption;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Hot-path thread role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HotPathStage {
+    /// Caller thread receiving trade triggers.
+    EventCallback,
+    /// Signing pool threads.
+    BuildSign,
+    /// Dedicated SWQOS sender threads.
+    SwqosSubmit,
+}
+
+/// Scheduling priority of a hot-path thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThreadPriority {
+    /// Leave the scheduler defaults.
+    #[default]
+    Normal,
+    /// Nice value (-20..=19, lower runs first). Below 0 needs `CAP_SYS_NICE`.
+    Nice(i8),
+    /// `SCHED_FIFO` priority (1..=99). A busy FIFO thread never yields its core to normal
+    /// threads, so only use it on isolated cores.
+    Realtime(u8),
+}
+
+/// Cores and priority for one stage. Threads of the stage are pinned round-robin over `cores`;
+/// an empty list leaves affinity alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageTuning {
+    pub cores: Vec<usize>,
+    pub priority: ThreadPriority,
+}
+
+impl StageTuning {
+    pub fn new(cores: Vec<usize>) -> Self {
+        Self { cores, priority: ThreadPriority::Normal }
+    }
+
+    pub fn priority(mut self, priority: ThreadPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Pin the current thread to `cores[slot % len]` and apply the priority.
+    pub fn apply_to_current_thread(&self, slot: usize) -> Result<()> {
+        if !self.cores.is_empty() {
+            pin_current_thread(self.cores[slot % self.cores.len()])?;
+        }
+        set_current_thread_priority(self.priority)
+    }
+}
+
+/// Per-stage affinity and priority for the whole trading pipeline.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HotPathTuning {
+    pub event_callback: Option<StageTuning>,
+    pub build_sign: Option<StageTuning>,
+    pub swqos_submit: Option<StageTuning>,
+}
+
+impl HotPathTuning {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stage(mut self, stage: HotPathStage, tuning: StageTuning) -> Self {
+        *self.slot_mut(stage) = Some(tuning);
+        self
+    }
+
+    pub fn get(&self, stage: HotPathStage) -> Option<&StageTuning> {
+        match stage {
+            HotPathStage::EventCallback => self.event_callback.as_ref(),
+            HotPathStage::BuildSign => self.build_sign.as_ref(),
+            HotPathStage::SwqosSubmit => self.swqos_submit.as_ref(),
+        }
+    }
+
+    fn slot_mut(&mut self, stage: HotPathStage) -> &mut Option<StageTuning> {
+        match stage {
+            HotPathStage::EventCallback => &mut self.event_callback,
+            HotPathStage::BuildSign => &mut self.build_sign,
+            HotPathStage::SwqosSubmit => &mut self.swqos_submit,
+        }
+    }
+
+    /// Make this the process-wide tuning read by [`enter_hot_path`] and by SDK threads started
+    /// afterwards.
+    pub fn install(self) {
+        INSTALLED.store(Some(Arc::new(self)));
+    }
+}
+
+static INSTALLED: ArcSwapOption<HotPathTuning> = ArcSwapOption::const_empty();
+/// Round-robin slot per stage so successive threads land on successive cores.
+static NEXT_SLOT: [AtomicUsize; 3] =
+    [AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0)];
+
+/// Installed tuning for `stage`, if any.
+pub fn installed_tuning(stage: HotPathStage) -> Option<StageTuning> {
+    INSTALLED.load().as_ref().and_then(|tuning| tuning.get(stage).cloned())
+}
+
+/// Apply the installed tuning for `stage` to the calling thread. Each call takes the next core
+/// of the stage. No-op when nothing is installed for the stage.
+pub fn enter_hot_path(stage: HotPathStage) -> Result<()> {
+    let Some(tuning) = installed_tuning(stage) else {
+        return Ok(());
+    };
+    let slot = NEXT_SLOT[stage as usize].fetch_add(1, Ordering::Relaxed);
+    tuning.apply_to_current_thread(slot)
+}
+
+/// Pin the calling thread to the core at `core_index` (index into the OS core list).
+pub fn pin_current_thread(core_index: usize) -> Result<()> {
+    let core_id = core_affinity::get_core_ids()
+        .and_then(|ids| ids.get(core_index).copied())
+        .ok_or_else(|| anyhow!("Core index {} not available", core_index))?;
+    if core_affinity::set_for_current(core_id) {
+        Ok(())
+    } else {
+        Err(anyhow!("Failed to pin thread to core {}", core_index))
+    }
+}
+
+/// Set the calling thread's scheduling priority.
+#[cfg(target_os = "linux")]
+pub fn set_current_thread_priority(priority: ThreadPriority) -> Result<()> {
+    match priority {
+        ThreadPriority::Normal => Ok(()),
+        ThreadPriority::Nice(nice) => {
+            // Linux keeps nice per thread; target this thread's tid, not the process.
+            let tid = unsafe { libc::syscall(libc::SYS_gettid) } as libc::id_t;
+            if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, nice as libc::c_int) } == 0 {
+                Ok(())
+            } else {
+                Err(anyhow!("setpriority({}) failed: {}", nice, std::io::Error::last_os_error()))
+            }
+        }
+        ThreadPriority::Realtime(level) => {
+            let param = libc::sched_param { sched_priority: level.clamp(1, 99) as libc::c_int };
+            // pid 0 = calling thread.
+            if unsafe { libc::sched_setscheduler(0, libc::SCHED_FIFO, &param) } == 0 {
+                Ok(())
+            } else {
+                Err(anyhow!(
+                    "SCHED_FIFO priority {} failed: {}",
+                    level,
+                    std::io::Error::last_os_error()
+                ))
+            }
+        }
+    }
+}
+
+/// Set the calling thread's scheduling priority.
+#[cfg(not(target_os = "linux"))]
+pub fn set_current_thread_priority(priority: ThreadPriority) -> Result<()> {
+    match priority {
+        ThreadPriority::Normal => Ok(()),
+        _ => Err(anyhow!("Thread priority is only supported on Linux")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_tuning_is_looked_up_per_stage() {
+        let tuning = HotPathTuning::new()
+            .stage(HotPathStage::BuildSign, StageTuning::new(vec![2, 3]))
+            .stage(
+                HotPathStage::SwqosSubmit,
+                StageTuning::new(vec![4]).priority(ThreadPriority::Nice(-5)),
+            );
+        assert_eq!(tuning.get(HotPathStage::BuildSign).unwrap().cores, vec![2, 3]);
+        assert_eq!(
+            tuning.get(HotPathStage::SwqosSubmit).unwrap().priority,
+            ThreadPriority::Nice(-5)
+        );
+        assert!(tuning.get(HotPathStage::EventCallback).is_none());
+    }
+
+    #[test]
+    fn normal_priority_without_cores_is_a_no_op() {
+        assert!(StageTuning::default().apply_to_current_thread(7).is_ok());
+        assert!(pin_current_thread(usize::MAX).is_err());
+    }
+}
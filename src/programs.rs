@@ -0,0 +1,241 @@
+//! Program addresses per protocol, as a stable public surface.
+//!
+//! The instruction builders keep their own constants in [`crate::constants`] and
+//! `instruction::utils::<protocol>::accounts`, and those modules are shaped by what the builders
+//! need (metas, fee rates, seeds) and may move. Downstream tooling (indexers, simulators,
+//! allow-lists) should read addresses from here instead: one `const` struct per protocol, built
+//! from the same constants the builders send, so the two cannot drift apart. New fields may be
+//! added; existing ones keep their meaning.
+//! 各协议程序与全局账户地址的稳定公开入口，供下游工具使用；取值与指令构建所用常量同源。
+
+use crate::constants;
+use crate::instruction::{
+    flashloan, marginfi,
+    utils::{bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_cpmm},
+};
+use crate::trading::factory::DexType;
+use solana_sdk::pubkey::Pubkey;
+
+/// Pump.fun bonding curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PumpFunPrograms {
+    pub program: Pubkey,
+    /// Global config account.
+    pub global: Pubkey,
+    pub event_authority: Pubkey,
+    /// Default fee recipient (any of the global's fee recipients is accepted).
+    pub fee_recipient: Pubkey,
+    /// Fee recipients for mayhem-mode curves; any one may be used.
+    pub mayhem_fee_recipients: [Pubkey; 8],
+    /// Buyback fee recipients (v2 buy/sell trailing account).
+    pub buyback_fee_recipients: [Pubkey; 8],
+    /// pump-fees program and its fee config PDA.
+    pub fee_program: Pubkey,
+    pub fee_config: Pubkey,
+    pub global_volume_accumulator: Pubkey,
+    pub authority: Pubkey,
+    pub withdraw_authority: Pubkey,
+    pub mpl_token_metadata: Pubkey,
+}
+
+pub const PUMPFUN: PumpFunPrograms = PumpFunPrograms {
+    program: pumpfun::accounts::PUMPFUN,
+    global: pumpfun::global_constants::GLOBAL_ACCOUNT,
+    event_authority: pumpfun::accounts::EVENT_AUTHORITY,
+    fee_recipient: pumpfun::global_constants::FEE_RECIPIENT,
+    mayhem_fee_recipients: pumpfun::global_constants::MAYHEM_FEE_RECIPIENTS,
+    buyback_fee_recipients: pumpfun::global_constants::BUYBACK_FEE_RECIPIENTS,
+    fee_program: pumpfun::accounts::FEE_PROGRAM,
+    fee_config: pumpfun::accounts::FEE_CONFIG,
+    global_volume_accumulator: pumpfun::accounts::GLOBAL_VOLUME_ACCUMULATOR,
+    authority: pumpfun::global_constants::AUTHORITY,
+    withdraw_authority: pumpfun::global_constants::WITHDRAW_AUTHORITY,
+    mpl_token_metadata: pumpfun::accounts::MPL_TOKEN_METADATA,
+};
+
+/// PumpSwap (Pump AMM).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PumpSwapPrograms {
+    pub program: Pubkey,
+    /// Global config account.
+    pub global_config: Pubkey,
+    pub event_authority: Pubkey,
+    pub protocol_fee_recipient: Pubkey,
+    /// Protocol fee recipients for mayhem-mode pools; any one may be used.
+    pub mayhem_fee_recipients: [Pubkey; 8],
+    /// Buyback fee recipients (trailing account after the optional pool-v2).
+    pub buyback_fee_recipients: [Pubkey; 8],
+    pub fee_program: Pubkey,
+    pub fee_config: Pubkey,
+    pub global_volume_accumulator: Pubkey,
+    /// Creator vault authority of pools without a coin creator.
+    pub default_coin_creator_vault_authority: Pubkey,
+    /// Bonding curve program that migrates into PumpSwap.
+    pub pump_program: Pubkey,
+}
+
+pub const PUMPSWAP: PumpSwapPrograms = PumpSwapPrograms {
+    program: pumpswap::accounts::AMM_PROGRAM,
+    global_config: pumpswap::accounts::GLOBAL_ACCOUNT,
+    event_authority: pumpswap::accounts::EVENT_AUTHORITY,
+    protocol_fee_recipient: pumpswap::accounts::PROTOCOL_FEE_RECIPIENT,
+    mayhem_fee_recipients: pumpswap::accounts::MAYHEM_FEE_RECIPIENTS,
+    buyback_fee_recipients: pumpswap::accounts::PROTOCOL_EXTRA_FEE_RECIPIENTS,
+    fee_program: pumpswap::accounts::FEE_PROGRAM,
+    fee_config: pumpswap::accounts::FEE_CONFIG,
+    global_volume_accumulator: pumpswap::accounts::GLOBAL_VOLUME_ACCUMULATOR,
+    default_coin_creator_vault_authority: pumpswap::accounts::DEFAULT_COIN_CREATOR_VAULT_AUTHORITY,
+    pump_program: pumpswap::accounts::PUMP_PROGRAM_ID,
+};
+
+/// Bonk (Raydium LaunchLab).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BonkPrograms {
+    pub program: Pubkey,
+    pub authority: Pubkey,
+    /// Global config of SOL-quoted pools.
+    pub global_config: Pubkey,
+    /// Global config of USD1-quoted pools.
+    pub usd1_global_config: Pubkey,
+    pub event_authority: Pubkey,
+}
+
+pub const BONK: BonkPrograms = BonkPrograms {
+    program: bonk::accounts::BONK,
+    authority: bonk::accounts::AUTHORITY,
+    global_config: bonk::accounts::GLOBAL_CONFIG,
+    usd1_global_config: bonk::accounts::USD1_GLOBAL_CONFIG,
+    event_authority: bonk::accounts::EVENT_AUTHORITY,
+};
+
+/// Raydium CPMM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RaydiumCpmmPrograms {
+    pub program: Pubkey,
+    /// Vault authority PDA.
+    pub authority: Pubkey,
+}
+
+pub const RAYDIUM_CPMM: RaydiumCpmmPrograms = RaydiumCpmmPrograms {
+    program: raydium_cpmm::accounts::RAYDIUM_CPMM,
+    authority: raydium_cpmm::accounts::AUTHORITY,
+};
+
+/// Raydium AMM v4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RaydiumAmmV4Programs {
+    pub program: Pubkey,
+    /// AMM authority PDA.
+    pub authority: Pubkey,
+}
+
+pub const RAYDIUM_AMM_V4: RaydiumAmmV4Programs = RaydiumAmmV4Programs {
+    program: raydium_amm_v4::accounts::RAYDIUM_AMM_V4,
+    authority: raydium_amm_v4::accounts::AUTHORITY,
+};
+
+/// Meteora DAMM v2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MeteoraDammV2Programs {
+    pub program: Pubkey,
+    /// Pool authority PDA.
+    pub authority: Pubkey,
+}
+
+pub const METEORA_DAMM_V2: MeteoraDammV2Programs = MeteoraDammV2Programs {
+    program: meteora_damm_v2::accounts::METEORA_DAMM_V2,
+    authority: meteora_damm_v2::accounts::AUTHORITY,
+};
+
+/// Lending programs used by flash loans and leveraged positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LendingPrograms {
+    pub marginfi: Pubkey,
+    pub solend: Pubkey,
+    pub kamino_lend: Pubkey,
+}
+
+pub const LENDING: LendingPrograms = LendingPrograms {
+    marginfi: marginfi::accounts::MARGINFI_PROGRAM,
+    solend: flashloan::accounts::SOLEND_PROGRAM,
+    kamino_lend: flashloan::accounts::KAMINO_LEND_PROGRAM,
+};
+
+/// Native and SPL programs, sysvars and quote mints shared by every protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CommonPrograms {
+    pub system_program: Pubkey,
+    pub compute_budget_program: Pubkey,
+    pub token_program: Pubkey,
+    pub token_2022_program: Pubkey,
+    pub associated_token_program: Pubkey,
+    pub rent_sysvar: Pubkey,
+    pub instructions_sysvar: Pubkey,
+    pub wsol_mint: Pubkey,
+    pub usdc_mint: Pubkey,
+    pub usd1_mint: Pubkey,
+}
+
+pub const COMMON: CommonPrograms = CommonPrograms {
+    system_program: constants::SYSTEM_PROGRAM,
+    compute_budget_program: constants::COMPUTE_BUDGET_PROGRAM,
+    token_program: constants::TOKEN_PROGRAM,
+    token_2022_program: constants::TOKEN_PROGRAM_2022,
+    associated_token_program: constants::ASSOCIATED_TOKEN_PROGRAM_ID,
+    rent_sysvar: constants::RENT,
+    instructions_sysvar: meteora_damm_v2::accounts::SYSVAR_INSTRUCTIONS,
+    wsol_mint: constants::WSOL_TOKEN_ACCOUNT,
+    usdc_mint: constants::USDC_TOKEN_ACCOUNT,
+    usd1_mint: constants::USD1_TOKEN_ACCOUNT,
+};
+
+/// Program id the SDK trades `dex` against.
+pub const fn program_id(dex: DexType) -> Pubkey {
+    match dex {
+        DexType::PumpFun => PUMPFUN.program,
+        DexType::PumpSwap => PUMPSWAP.program,
+        DexType::Bonk => BONK.program,
+        DexType::RaydiumCpmm => RAYDIUM_CPMM.program,
+        DexType::RaydiumAmmV4 => RAYDIUM_AMM_V4.program,
+        DexType::MeteoraDammV2 => METEORA_DAMM_V2.program,
+    }
+}
+
+/// Protocol whose program id is `program`, if the SDK trades it.
+pub fn dex_for_program(program: &Pubkey) -> Option<DexType> {
+    [
+        DexType::PumpFun,
+        DexType::PumpSwap,
+        DexType::Bonk,
+        DexType::RaydiumCpmm,
+        DexType::RaydiumAmmV4,
+        DexType::MeteoraDammV2,
+    ]
+    .into_iter()
+    .find(|dex| program_id(*dex) == *program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey;
+
+    #[test]
+    fn addresses_match_known_mainnet_values() {
+        assert_eq!(PUMPFUN.program, pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"));
+        assert_eq!(PUMPSWAP.program, pubkey!("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA"));
+        assert_eq!(PUMPSWAP.pump_program, PUMPFUN.program);
+        assert_eq!(RAYDIUM_AMM_V4.program, pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"));
+        assert_eq!(COMMON.instructions_sysvar, solana_sdk::sysvar::instructions::id());
+        assert_eq!(dex_for_program(&BONK.program), Some(DexType::Bonk));
+        assert_eq!(dex_for_program(&COMMON.system_program), None);
+    }
+}
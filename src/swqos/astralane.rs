@@ -1,4 +1,6 @@
-use crate::swqos::common::{default_http_client_builder, poll_transaction_confirmation};
+use crate::swqos::common::{
+    build_http_client, default_http_client_builder, poll_transaction_confirmation, HttpClientTuning,
+};
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use std::{sync::Arc, time::Instant};
@@ -80,9 +82,15 @@ impl SwqosClientTrait for AstralaneClient {
 
 impl AstralaneClient {
     /// HTTP 提交：`/iris`（Plain）或 `/irisb`（Binary），由 `endpoint` URL 路径区分；`mev_http` 为 true 时附加 `mev-protect=true`。
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String, mev_http: bool) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        mev_http: bool,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
         let ping_handle = Arc::new(tokio::sync::Mutex::new(None));
         let stop_ping = Arc::new(AtomicBool::new(false));
 
@@ -101,7 +109,7 @@ impl AstralaneClient {
         tokio::spawn(async move {
             client_clone.start_ping_task().await;
         });
-        client
+        Ok(client)
     }
 
     /// 使用 QUIC 提交。
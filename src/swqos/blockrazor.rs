@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    build_http_client, default_http_client_builder, poll_transaction_confirmation,
+    serialize_transaction_and_encode, HttpClientTuning,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -150,9 +151,14 @@ impl SwqosClientTrait for BlockRazorClient {
 }
 
 impl BlockRazorClient {
-    pub async fn new(rpc_url: String, endpoint: String, auth_token: String) -> Result<Self> {
+    pub async fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         // 默认使用 HTTP 模式，避免 gRPC FRAME_SIZE_ERROR
-        Ok(Self::new_http(rpc_url, endpoint, auth_token, false))
+        Self::new_http(rpc_url, endpoint, auth_token, false, http_tuning)
     }
 
     pub async fn new_grpc(
@@ -201,9 +207,11 @@ impl BlockRazorClient {
         endpoint: String,
         auth_token: String,
         mev_protection: bool,
-    ) -> Self {
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().user_agent("").build().unwrap();
+        let http_client =
+            build_http_client(default_http_client_builder().user_agent(""), http_tuning)?;
         let ping_handle = Arc::new(tokio::sync::Mutex::new(None));
         let stop_ping = Arc::new(AtomicBool::new(false));
 
@@ -224,7 +232,7 @@ impl BlockRazorClient {
             client_clone.start_ping_task().await;
         });
 
-        client
+        Ok(client)
     }
 
     async fn start_ping_task(&self) {
@@ -1,6 +1,6 @@
-use crate::swqos::common::default_http_client_builder;
 use crate::swqos::common::poll_transaction_confirmation;
 use crate::swqos::common::serialize_transaction_and_encode;
+use crate::swqos::common::{build_http_client, default_http_client_builder, HttpClientTuning};
 use crate::swqos::serialization;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -58,14 +58,21 @@ impl SwqosClientTrait for BloxrouteClient {
 }
 
 impl BloxrouteClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder()
-            .pool_idle_timeout(Duration::from_secs(120))
-            .pool_max_idle_per_host(256)
-            .build()
-            .unwrap();
-        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+        // Bloxroute-specific pool defaults; a configured HttpClientTuning still wins.
+        let http_client = build_http_client(
+            default_http_client_builder()
+                .pool_idle_timeout(Duration::from_secs(120))
+                .pool_max_idle_per_host(256),
+            http_tuning,
+        )?;
+        Ok(Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client })
     }
 
     pub async fn send_transaction(
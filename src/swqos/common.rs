@@ -1,14 +1,16 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_CONFIRMATION};
 use crate::common::types::SolanaRpcClient;
 use crate::swqos::serialization;
 use anyhow::Result;
-use base64::engine::general_purpose::{self, STANDARD};
+use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
 use bincode::serialize;
 use reqwest::Client;
 use serde_json;
 use serde_json::json;
 use solana_client::rpc_client::SerializableTransaction;
-use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcTransactionConfig};
+use solana_commitment_config::CommitmentLevel;
 use solana_sdk::signature::Signature;
 use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::transaction::{Transaction, TransactionError};
@@ -32,7 +34,8 @@ const HTTP_TIMEOUT_MS: u64 = 3000;
 /// Connect timeout (milliseconds). 连接超时（毫秒）。
 const HTTP_CONNECT_TIMEOUT_MS: u64 = 2000;
 
-/// Shared HTTP client builder for SWQOS clients; call `.build().unwrap()` or override pool first. SWQOS 共用 HTTP 客户端构建器。
+/// SDK default HTTP client settings for SWQOS clients, before any [`HttpClientTuning`].
+/// SWQOS 共用 HTTP 客户端默认设置（未应用调优）。
 pub fn default_http_client_builder() -> reqwest::ClientBuilder {
     Client::builder()
         .pool_idle_timeout(Duration::from_secs(HTTP_POOL_IDLE_TIMEOUT_SECS))
@@ -46,6 +49,128 @@ pub fn default_http_client_builder() -> reqwest::ClientBuilder {
         .connect_timeout(Duration::from_millis(HTTP_CONNECT_TIMEOUT_MS))
 }
 
+/// Build a provider's HTTP client: `builder` (usually [`default_http_client_builder`]) with
+/// `tuning` on top. Fails instead of panicking when the tuned settings are rejected.
+pub fn build_http_client(
+    builder: reqwest::ClientBuilder,
+    tuning: &HttpClientTuning,
+) -> Result<Client> {
+    tuning
+        .apply(builder)
+        .build()
+        .map_err(|e| anyhow::anyhow!("failed to build SWQOS HTTP client: {}", e))
+}
+
+/// Overrides for the HTTP client behind an HTTP-transport SWQOS provider, set per provider with
+/// `TradeConfigBuilder::swqos_http_tuning`. `None` keeps the SDK default; QUIC / gRPC transports
+/// ignore it. SWQOS HTTP 客户端调优（连接池、HTTP/2、TCP）。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HttpClientTuning {
+    /// Idle keep-alive connections kept per host (SDK default 4).
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle pooled connection is kept (SDK default 300 s).
+    pub pool_idle_timeout: Option<Duration>,
+    /// Disable Nagle (SDK default true).
+    pub tcp_nodelay: Option<bool>,
+    /// TCP keepalive interval (SDK default 60 s).
+    pub tcp_keepalive: Option<Duration>,
+    /// Speak HTTP/2 without ALPN negotiation; only for endpoints known to support it.
+    pub http2_prior_knowledge: bool,
+    /// HTTP/2 PING interval (SDK default 10 s).
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// HTTP/2 PING timeout (SDK default 5 s).
+    pub http2_keep_alive_timeout: Option<Duration>,
+    /// HTTP/2 BDP-based flow control (SDK default true). Fixed windows apply when false.
+    pub http2_adaptive_window: Option<bool>,
+    pub http2_initial_stream_window_size: Option<u32>,
+    pub http2_initial_connection_window_size: Option<u32>,
+    /// Bind to 0.0.0.0 so only IPv4 is dialled. reqwest does not expose the happy-eyeballs
+    /// fallback delay; on hosts with broken IPv6 this removes the IPv6 attempt altogether.
+    pub ipv4_only: bool,
+    /// Whole-request timeout (SDK default 3000 ms).
+    pub timeout: Option<Duration>,
+    /// Connect timeout (SDK default 2000 ms).
+    pub connect_timeout: Option<Duration>,
+}
+
+impl HttpClientTuning {
+    pub fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(idle) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle);
+        }
+        if let Some(nodelay) = self.tcp_nodelay {
+            builder = builder.tcp_nodelay(nodelay);
+        }
+        if let Some(keepalive) = self.tcp_keepalive {
+            builder = builder.tcp_keepalive(keepalive);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.http2_keep_alive_timeout {
+            builder = builder.http2_keep_alive_timeout(timeout);
+        }
+        if let Some(adaptive) = self.http2_adaptive_window {
+            builder = builder.http2_adaptive_window(adaptive);
+        }
+        if let Some(size) = self.http2_initial_stream_window_size {
+            builder = builder.http2_initial_stream_window_size(size);
+        }
+        if let Some(size) = self.http2_initial_connection_window_size {
+            builder = builder.http2_initial_connection_window_size(size);
+        }
+        if self.ipv4_only {
+            builder = builder.local_address(std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED));
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        builder
+    }
+}
+
+/// [`TradeError::code`] for trades rejected because their pool reserves were too old (`StaleQuote`).
+pub const STALE_QUOTE_ERROR_CODE: u32 = 1001;
+
+/// [`TradeError::code`] for trades dropped because the cluster moved too many slots past the triggering event.
+pub const SLOT_LAG_ERROR_CODE: u32 = 1002;
+
+/// [`TradeError::code`] for trades rejected because their `client_order_id` was already submitted.
+pub const DUPLICATE_ORDER_ERROR_CODE: u32 = 1003;
+
+/// [`TradeError::code`] for params rejected because a protocol account no longer matches a known
+/// layout (`ProtocolLayoutChanged`, see [`crate::common::protocol_layout`]).
+pub const PROTOCOL_LAYOUT_CHANGED_ERROR_CODE: u32 = 1005;
+
+/// [`TradeError::code`] for trades a [`crate::TradeQueue`] dropped before running them (`TradeShed`:
+/// full queue, evicted by a higher priority, waited too long, or queue dropped).
+pub const TRADE_SHED_ERROR_CODE: u32 = 1006;
+
+/// [`TradeError::code`] for trades that did not reach the requested [`ConfirmationLevel`] within
+/// the confirmation timeout (`ConfirmationTimedOut`). The transaction may still land later.
+pub const CONFIRMATION_TIMEOUT_ERROR_CODE: u32 = 1007;
+
+/// [`TradeError::code`] for trades whose tip is below the minimum of every selected SWQoS
+/// provider (`TipBelowMinimum`, see [`crate::swqos::SwqosType::min_tip_sol`]).
+pub const TIP_BELOW_MINIMUM_ERROR_CODE: u32 = 1009;
+
+/// [`TradeError::code`] when no SWQoS provider is active (`NoSwqosAvailable`): every configured
+/// one was blacklisted or failed to connect.
+pub const NO_SWQOS_AVAILABLE_ERROR_CODE: u32 = 1010;
+
+/// [`TradeError::code`] for `simulate = true` trades the SDK cannot simulate
+/// (`SimulationUnsupported`), e.g. a trade split into setup and swap transactions.
+pub const SIMULATION_UNSUPPORTED_ERROR_CODE: u32 = 1011;
+
 /// Trade/on-chain error with code and optional instruction index. 交易/链上错误，含错误码与可选指令下标。
 #[derive(Debug, Clone)]
 pub struct TradeError {
@@ -54,6 +179,44 @@ pub struct TradeError {
     pub instruction: Option<u8>,
 }
 
+impl TradeError {
+    /// `TipBelowMinimum` error for a `tip` (SOL) under `provider`'s `min_tip` (SOL).
+    pub fn tip_below_minimum(provider: crate::swqos::SwqosType, tip: f64, min_tip: f64) -> Self {
+        TradeError {
+            code: TIP_BELOW_MINIMUM_ERROR_CODE,
+            message: format!(
+                "TipBelowMinimum: {} requires a tip of at least {} SOL, got {} SOL",
+                provider.as_str(),
+                min_tip,
+                tip
+            ),
+            instruction: None,
+        }
+    }
+}
+
+impl TradeError {
+    /// `NoSwqosAvailable` error; `detail` says where the provider set came up empty.
+    pub fn no_swqos_available(detail: &str) -> Self {
+        TradeError {
+            code: NO_SWQOS_AVAILABLE_ERROR_CODE,
+            message: format!("NoSwqosAvailable: {}", detail),
+            instruction: None,
+        }
+    }
+}
+
+impl TradeError {
+    /// `SimulationUnsupported` error; `detail` says why the trade cannot be simulated.
+    pub fn simulation_unsupported(detail: &str) -> Self {
+        TradeError {
+            code: SIMULATION_UNSUPPORTED_ERROR_CODE,
+            message: format!("SimulationUnsupported: {}", detail),
+            instruction: None,
+        }
+    }
+}
+
 impl std::fmt::Display for TradeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.message)
@@ -74,13 +237,155 @@ impl From<anyhow::Error> for TradeError {
 // High-performance serialization
 
 pub trait FormatBase64VersionedTransaction {
-    fn to_base64_string(&self) -> String;
+    fn to_base64_string(&self) -> Result<String>;
 }
 
 impl FormatBase64VersionedTransaction for VersionedTransaction {
-    fn to_base64_string(&self) -> String {
-        let tx_bytes = bincode::serialize(self).unwrap();
-        general_purpose::STANDARD.encode(tx_bytes)
+    fn to_base64_string(&self) -> Result<String> {
+        Ok(serialization::encode_transaction_shared(self)?.base64().to_owned())
+    }
+}
+
+/// Confirmation wait used when a trade does not set its own. 默认确认等待时间。
+pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Commitment a trade waits for when `wait_tx_confirmed` is set. 交易确认等级。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ConfirmationLevel {
+    /// Executed by the leader's bank; can still be dropped on a fork.
+    Processed,
+    /// Voted on by a supermajority.
+    #[default]
+    Confirmed,
+    /// Rooted; cannot be rolled back.
+    Finalized,
+}
+
+impl ConfirmationLevel {
+    fn reached_by(self, status: Option<&TransactionConfirmationStatus>) -> bool {
+        let reached = match status {
+            Some(TransactionConfirmationStatus::Finalized) => ConfirmationLevel::Finalized,
+            Some(TransactionConfirmationStatus::Confirmed) => ConfirmationLevel::Confirmed,
+            Some(TransactionConfirmationStatus::Processed) | None => ConfirmationLevel::Processed,
+        };
+        reached >= self
+    }
+
+    /// Status of a transaction that reached this level.
+    fn reached_status(self) -> ConfirmationStatus {
+        match self {
+            ConfirmationLevel::Processed => ConfirmationStatus::Landed,
+            _ => ConfirmationStatus::Confirmed,
+        }
+    }
+}
+
+/// Per-trade confirmation settings; the default is `confirmed` within 15 s. 单笔交易确认设置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationOptions {
+    pub level: ConfirmationLevel,
+    /// Give up (with [`CONFIRMATION_TIMEOUT_ERROR_CODE`]) after this long.
+    pub timeout: Duration,
+}
+
+impl Default for ConfirmationOptions {
+    fn default() -> Self {
+        Self { level: ConfirmationLevel::default(), timeout: DEFAULT_CONFIRMATION_TIMEOUT }
+    }
+}
+
+impl ConfirmationOptions {
+    pub fn new(level: ConfirmationLevel, timeout: Duration) -> Self {
+        Self { level, timeout }
+    }
+
+    /// `processed` lands within a slot or two, so it is polled more often.
+    fn poll_interval(&self) -> Duration {
+        match self.level {
+            ConfirmationLevel::Processed => Duration::from_millis(200),
+            _ => Duration::from_millis(1000),
+        }
+    }
+}
+
+/// `sendTransaction` settings of the `Default` (RPC) route; relays ignore them. The default
+/// skips preflight for the lowest latency. RPC 路由发送选项（preflight 模拟与节点重试次数）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcSendOptions {
+    /// Send without the node's preflight simulation. With preflight, a transaction that would
+    /// fail is rejected by `sendTransaction` instead of landing as a failed, fee-paying one.
+    pub skip_preflight: bool,
+    /// Commitment the preflight simulation runs at.
+    pub preflight_commitment: CommitmentLevel,
+    /// Rebroadcasts by the RPC node; `None` lets the node retry until the blockhash expires.
+    pub max_retries: Option<usize>,
+}
+
+impl Default for RpcSendOptions {
+    fn default() -> Self {
+        Self {
+            skip_preflight: true,
+            preflight_commitment: CommitmentLevel::Processed,
+            max_retries: Some(3),
+        }
+    }
+}
+
+impl RpcSendOptions {
+    /// Run preflight at `commitment` before the node forwards the transaction.
+    pub fn with_preflight(mut self, commitment: CommitmentLevel) -> Self {
+        self.skip_preflight = false;
+        self.preflight_commitment = commitment;
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: Option<usize>) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub(crate) fn send_config(&self) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            skip_preflight: self.skip_preflight,
+            preflight_commitment: Some(self.preflight_commitment),
+            encoding: Some(UiTransactionEncoding::Base64),
+            max_retries: self.max_retries,
+            min_context_slot: None,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Send options of the trade whose transaction is being submitted.
+    pub(crate) static RPC_SEND_OPTIONS: RpcSendOptions;
+}
+
+/// How a trade that waited for confirmation ended. 确认结果：已落地(processed) / 已确认 / 超时。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationStatus {
+    /// Seen at `processed`, which was the requested level; not yet voted on.
+    Landed,
+    /// Reached the requested `confirmed` or `finalized` level.
+    Confirmed,
+    /// The requested level was not reached within the timeout; the transaction may still land.
+    TimedOut,
+}
+
+impl ConfirmationStatus {
+    /// Classify the `(success, .., error, ..)` of a trade that waited at `level`. `None` when it
+    /// failed for another reason, e.g. an on-chain error.
+    pub fn from_result(
+        level: ConfirmationLevel,
+        success: bool,
+        error: Option<&TradeError>,
+    ) -> Option<Self> {
+        if success {
+            Some(level.reached_status())
+        } else if error.is_some_and(|e| e.code == CONFIRMATION_TIMEOUT_ERROR_CODE) {
+            Some(ConfirmationStatus::TimedOut)
+        } else {
+            None
+        }
     }
 }
 
@@ -107,9 +412,37 @@ pub async fn poll_any_transaction_confirmation(
     if !wait_confirmation {
         return Ok(signatures[0]);
     }
+    poll_any_transaction_confirmation_with(rpc, signatures, ConfirmationOptions::default())
+        .await
+        .map(|(signature, _)| signature)
+}
+
+/// [`poll_any_transaction_confirmation`] at `options.level` within `options.timeout`. A timeout
+/// fails with a [`CONFIRMATION_TIMEOUT_ERROR_CODE`] [`TradeError`].
+pub async fn poll_any_transaction_confirmation_with(
+    rpc: &SolanaRpcClient,
+    signatures: &[Signature],
+    options: ConfirmationOptions,
+) -> Result<(Signature, ConfirmationStatus)> {
+    poll_any_transaction_landing(rpc, signatures, options)
+        .await
+        .map(|(signature, status, _)| (signature, status))
+}
+
+/// [`poll_any_transaction_confirmation_with`], also returning the slot the transaction landed in.
+pub async fn poll_any_transaction_landing(
+    rpc: &SolanaRpcClient,
+    signatures: &[Signature],
+    options: ConfirmationOptions,
+) -> Result<(Signature, ConfirmationStatus, u64)> {
+    if signatures.is_empty() {
+        return Err(anyhow::anyhow!("No signatures to confirm"));
+    }
 
-    let timeout: Duration = Duration::from_secs(15);
-    let interval: Duration = Duration::from_millis(1000);
+    let timeout = options.timeout;
+    let interval = options.poll_interval();
+    // Fetch the landed transaction for its error after ~10 s of polling, whatever the interval.
+    let fetch_after_polls = (10_000 / interval.as_millis().max(1)) as u32;
     let start: Instant = Instant::now();
     let mut poll_count = 0u32;
     // Track which signature landed (confirmed or failed on-chain)
@@ -117,24 +450,31 @@ pub async fn poll_any_transaction_confirmation(
 
     loop {
         if start.elapsed() >= timeout {
-            return Err(anyhow::anyhow!(
-                "Transaction confirmation timed out after {}s ({} signatures polled)",
-                timeout.as_secs(),
-                signatures.len()
-            ));
+            let landed = landed_sig
+                .map(|sig| format!("; {} landed below that level", sig))
+                .unwrap_or_default();
+            return Err(anyhow::Error::new(TradeError {
+                code: CONFIRMATION_TIMEOUT_ERROR_CODE,
+                message: format!(
+                    "ConfirmationTimedOut: {:?} not reached after {:?} ({} signatures polled){}",
+                    options.level,
+                    timeout,
+                    signatures.len(),
+                    landed
+                ),
+                instruction: None,
+            }));
         }
 
         poll_count += 1;
 
-        let status = rpc.get_signature_statuses(signatures).await?;
-        // Check all signatures for any that confirmed successfully
+        let status =
+            with_rpc_scope(RPC_SCOPE_CONFIRMATION, rpc.get_signature_statuses(signatures)).await?;
+        // Check all signatures for any that reached the requested level successfully
         for (i, maybe_status) in status.value.iter().enumerate() {
             if let Some(s) = maybe_status {
-                if s.err.is_none()
-                    && (s.confirmation_status == Some(TransactionConfirmationStatus::Confirmed)
-                        || s.confirmation_status == Some(TransactionConfirmationStatus::Finalized))
-                {
-                    return Ok(signatures[i]);
+                if s.err.is_none() && options.level.reached_by(s.confirmation_status.as_ref()) {
+                    return Ok((signatures[i], options.level.reached_status(), s.slot));
                 }
                 // Track the first signature that landed on-chain (even if errored)
                 if landed_sig.is_none() {
@@ -150,23 +490,25 @@ pub async fn poll_any_transaction_confirmation(
         }
 
         let landed = landed_sig.unwrap();
-        let should_get_transaction = poll_count >= 10;
+        let should_get_transaction = poll_count >= fetch_after_polls;
 
         if !should_get_transaction {
             sleep(interval).await;
             continue;
         }
 
-        let tx_details = match rpc
-            .get_transaction_with_config(
+        let tx_details = match with_rpc_scope(
+            RPC_SCOPE_CONFIRMATION,
+            rpc.get_transaction_with_config(
                 &landed,
                 RpcTransactionConfig {
                     encoding: Some(UiTransactionEncoding::JsonParsed),
                     max_supported_transaction_version: Some(0),
                     commitment: Some(solana_commitment_config::CommitmentConfig::confirmed()),
                 },
-            )
-            .await
+            ),
+        )
+        .await
         {
             Ok(details) => details,
             Err(_) => {
@@ -182,7 +524,11 @@ pub async fn poll_any_transaction_confirmation(
         } else {
             let meta = meta.unwrap();
             if meta.err.is_none() {
-                return Ok(landed);
+                // Fetched at `confirmed`; that only satisfies `finalized` once the status says so.
+                if options.level <= ConfirmationLevel::Confirmed {
+                    return Ok((landed, options.level.reached_status(), tx_details.slot));
+                }
+                sleep(interval).await;
             } else {
                 // Extract error message from log_messages
                 let mut error_msg = String::new();
@@ -210,53 +556,50 @@ pub async fn poll_any_transaction_confirmation(
                 let tx_err: TransactionError =
                     serde_json::from_value(serde_json::to_value(&ui_err)?)?;
 
-                // Use Solana InstructionError codes directly
-                let mut code = 0u32;
-                let mut index = None;
-                match &tx_err {
-                    TransactionError::InstructionError(i, i_error) => {
-                        // Match all InstructionError variants including Custom
-                        code = match i_error {
-                            solana_sdk::instruction::InstructionError::Custom(c) => *c,
-                            solana_sdk::instruction::InstructionError::GenericError => 1,
-                            solana_sdk::instruction::InstructionError::InvalidArgument => 2,
-                            solana_sdk::instruction::InstructionError::InvalidInstructionData => 3,
-                            solana_sdk::instruction::InstructionError::InvalidAccountData => 4,
-                            solana_sdk::instruction::InstructionError::AccountDataTooSmall => 5,
-                            solana_sdk::instruction::InstructionError::InsufficientFunds => 6,
-                            solana_sdk::instruction::InstructionError::IncorrectProgramId => 7,
-                            solana_sdk::instruction::InstructionError::MissingRequiredSignature => 8,
-                            solana_sdk::instruction::InstructionError::AccountAlreadyInitialized => 9,
-                            solana_sdk::instruction::InstructionError::UninitializedAccount => 10,
-                            _ => 999, // Other unknown errors
-                        };
-                        index = Some(*i);
-                    }
-                    _ => {}
-                }
-
-                return Err(anyhow::Error::new(TradeError {
-                    code: code,
-                    message: format!("{} {:?}", tx_err, error_msg),
-                    instruction: index,
-                }));
+                return Err(anyhow::Error::new(landed_trade_error(
+                    &tx_err,
+                    format!("{} {:?}", tx_err, error_msg),
+                )));
             }
         }
     }
 }
 
+/// [`TradeError`] of a transaction that landed with `tx_err`, carrying Solana's
+/// `InstructionError` codes directly (custom program errors keep their own code).
+pub(crate) fn landed_trade_error(tx_err: &TransactionError, message: String) -> TradeError {
+    let mut code = 0u32;
+    let mut index = None;
+    if let TransactionError::InstructionError(i, i_error) = tx_err {
+        // Match all InstructionError variants including Custom
+        code = match i_error {
+            solana_sdk::instruction::InstructionError::Custom(c) => *c,
+            solana_sdk::instruction::InstructionError::GenericError => 1,
+            solana_sdk::instruction::InstructionError::InvalidArgument => 2,
+            solana_sdk::instruction::InstructionError::InvalidInstructionData => 3,
+            solana_sdk::instruction::InstructionError::InvalidAccountData => 4,
+            solana_sdk::instruction::InstructionError::AccountDataTooSmall => 5,
+            solana_sdk::instruction::InstructionError::InsufficientFunds => 6,
+            solana_sdk::instruction::InstructionError::IncorrectProgramId => 7,
+            solana_sdk::instruction::InstructionError::MissingRequiredSignature => 8,
+            solana_sdk::instruction::InstructionError::AccountAlreadyInitialized => 9,
+            solana_sdk::instruction::InstructionError::UninitializedAccount => 10,
+            _ => 999, // Other unknown errors
+        };
+        index = Some(*i);
+    }
+    TradeError { code, message, instruction: index }
+}
+
 pub async fn send_nb_transaction(
     client: Client,
     endpoint: &str,
     auth_token: &str,
     transaction: &Transaction,
 ) -> Result<Signature, anyhow::Error> {
-    // Serialize transaction
-    let serialized = bincode::serialize(transaction)
-        .map_err(|e| anyhow::anyhow!("Transaction serialization failed: {}", e))?;
-
-    // Base64 encode
-    let encoded = STANDARD.encode(serialized);
+    let (encoded, _) =
+        serialization::serialize_transaction_sync(transaction, UiTransactionEncoding::Base64)
+            .map_err(|e| anyhow::anyhow!("Transaction serialization failed: {}", e))?;
 
     let request_data = json!({
         "transaction": {
@@ -327,3 +670,129 @@ pub async fn serialize_smart_transaction_and_encode(
     };
     Ok((serialized, *signature))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// HTTP/1.1 server answering every request with an empty 200 on a kept-alive connection;
+    /// returns its URL and the number of connections it accepted.
+    async fn keep_alive_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while let Ok(n) = socket.read(&mut buf).await {
+                        let response = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n";
+                        if n == 0 || socket.write_all(response).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        (url, accepted)
+    }
+
+    async fn get_twice(client: &Client, url: &str) {
+        for _ in 0..2 {
+            client.get(url).send().await.unwrap().bytes().await.unwrap();
+            // Let the connection settle back into the pool before the next request.
+            sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn tuned_pool_size_reaches_the_client() {
+        let (url, accepted) = keep_alive_server().await;
+
+        let pooled =
+            build_http_client(default_http_client_builder(), &HttpClientTuning::default()).unwrap();
+        get_twice(&pooled, &url).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+
+        let unpooled = build_http_client(
+            default_http_client_builder(),
+            &HttpClientTuning { pool_max_idle_per_host: Some(0), ..Default::default() },
+        )
+        .unwrap();
+        get_twice(&unpooled, &url).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn tuned_timeout_reaches_the_client() {
+        // Accepts connections and never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let tuning =
+            HttpClientTuning { timeout: Some(Duration::from_millis(100)), ..Default::default() };
+        let client = build_http_client(default_http_client_builder(), &tuning).unwrap();
+        let started = Instant::now();
+        let error = client.get(&url).send().await.unwrap_err();
+
+        assert!(error.is_timeout());
+        assert!(started.elapsed() < Duration::from_millis(HTTP_TIMEOUT_MS));
+    }
+
+    #[test]
+    fn confirmation_levels_and_result_status() {
+        use TransactionConfirmationStatus as S;
+        assert!(ConfirmationLevel::Processed.reached_by(None));
+        assert!(ConfirmationLevel::Confirmed.reached_by(Some(&S::Finalized)));
+        assert!(!ConfirmationLevel::Confirmed.reached_by(Some(&S::Processed)));
+        assert!(!ConfirmationLevel::Finalized.reached_by(Some(&S::Confirmed)));
+
+        let timeout = TradeError {
+            code: CONFIRMATION_TIMEOUT_ERROR_CODE,
+            message: "ConfirmationTimedOut".into(),
+            instruction: None,
+        };
+        let slippage = TradeError { code: 6004, message: String::new(), instruction: Some(2) };
+        let status = ConfirmationStatus::from_result;
+        assert_eq!(
+            status(ConfirmationLevel::Processed, true, None),
+            Some(ConfirmationStatus::Landed)
+        );
+        assert_eq!(
+            status(ConfirmationLevel::Finalized, true, None),
+            Some(ConfirmationStatus::Confirmed)
+        );
+        assert_eq!(
+            status(ConfirmationLevel::Confirmed, false, Some(&timeout)),
+            Some(ConfirmationStatus::TimedOut)
+        );
+        assert_eq!(status(ConfirmationLevel::Confirmed, false, Some(&slippage)), None);
+    }
+
+    #[test]
+    fn rpc_send_options_map_to_send_config() {
+        let fast = RpcSendOptions::default().send_config();
+        assert!(fast.skip_preflight);
+        assert_eq!(fast.max_retries, Some(3));
+
+        let safe = RpcSendOptions::default()
+            .with_preflight(CommitmentLevel::Confirmed)
+            .with_max_retries(None)
+            .send_config();
+        assert!(!safe.skip_preflight);
+        assert_eq!(safe.preflight_commitment, Some(CommitmentLevel::Confirmed));
+        assert_eq!(safe.max_retries, None);
+    }
+}
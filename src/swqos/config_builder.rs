@@ -0,0 +1,387 @@
+//! Builders for [`SwqosConfig`] that keep provider tokens out of source code.
+//!
+//! `SwqosConfig::jito().region(SwqosRegion::Frankfurt).uuid_from_env("JITO_UUID").build()?`
+//! produces the same config as `SwqosConfig::Jito(uuid, SwqosRegion::Frankfurt, None)` with the
+//! uuid read from `JITO_UUID`. A missing variable is reported by [`SwqosConfigBuilder::build`],
+//! naming the variable, never the secret.
+//!
+//! [`SwqosConfig::from_env`] assembles the whole provider list from `SWQOS_*` variables; see its
+//! docs for the names.
+//! SwqosConfig 构建器：从环境变量读取 token / uuid，避免密钥写进源码。
+
+use super::{AstralaneTransport, SwqosConfig, SwqosRegion, SwqosTransport, SwqosType};
+use anyhow::{anyhow, bail, Result};
+use std::str::FromStr;
+
+/// Region used by [`SwqosConfig::from_env`] for providers without their own `_REGION`.
+pub const SWQOS_REGION_ENV: &str = "SWQOS_REGION";
+
+/// Step-by-step [`SwqosConfig`]; start with [`SwqosConfig::builder`] or a provider shorthand
+/// such as [`SwqosConfig::jito`].
+#[derive(Debug, Clone)]
+pub struct SwqosConfigBuilder {
+    swqos_type: SwqosType,
+    token: Option<String>,
+    /// Variable a `*_from_env` call found unset; reported by `build`.
+    missing_env: Option<String>,
+    region: SwqosRegion,
+    custom_url: Option<String>,
+    transport: Option<SwqosTransport>,
+    astralane_transport: Option<AstralaneTransport>,
+    swqos_only: Option<bool>,
+}
+
+macro_rules! provider_builders {
+    ($($name:ident => $swqos_type:ident),* $(,)?) => {
+        impl SwqosConfig {
+            $(
+                #[doc = concat!("Builder for [`SwqosConfig::", stringify!($swqos_type), "`].")]
+                pub fn $name() -> SwqosConfigBuilder {
+                    Self::builder(SwqosType::$swqos_type)
+                }
+            )*
+        }
+    };
+}
+
+provider_builders! {
+    jito => Jito,
+    nextblock => NextBlock,
+    bloxroute => Bloxroute,
+    temporal => Temporal,
+    zeroslot => ZeroSlot,
+    node1 => Node1,
+    flashblock => FlashBlock,
+    blockrazor => BlockRazor,
+    astralane => Astralane,
+    stellium => Stellium,
+    lightspeed => Lightspeed,
+    soyas => Soyas,
+    speedlanding => Speedlanding,
+    helius => Helius,
+    solami => Solami,
+    lunarlander => LunarLander,
+    glaive => Glaive,
+}
+
+impl SwqosConfig {
+    /// Builder for `swqos_type`; the region defaults to [`SwqosRegion::Default`]. The Default
+    /// (RPC) lane takes its endpoint from [`SwqosConfigBuilder::custom_url`].
+    pub fn builder(swqos_type: SwqosType) -> SwqosConfigBuilder {
+        SwqosConfigBuilder {
+            swqos_type,
+            token: None,
+            missing_env: None,
+            region: SwqosRegion::Default,
+            custom_url: None,
+            transport: None,
+            astralane_transport: None,
+            swqos_only: None,
+        }
+    }
+
+    /// Provider list from the process environment. For every provider `<P>` (the upper-cased
+    /// [`SwqosType::as_str`], e.g. `JITO`, `NEXTBLOCK`, `LUNARLANDER`):
+    ///
+    /// * `SWQOS_<P>_TOKEN` — api token (Jito: uuid); setting it, even empty, enables the provider
+    /// * `SWQOS_<P>_URL` — custom endpoint; also enables the provider
+    /// * `SWQOS_<P>_REGION` — region, falling back to `SWQOS_REGION`, then `Default`
+    /// * `SWQOS_<P>_TRANSPORT` — `http` / `grpc` / `quic`; Astralane: `binary` / `plain` / `quic`
+    /// * `SWQOS_HELIUS_SWQOS_ONLY` — `true` / `false`
+    ///
+    /// The Default (RPC) lane is added when `SWQOS_DEFAULT_URL` is set. Providers come back in
+    /// [`SwqosType::values`] order; malformed values are errors naming the variable.
+    /// 从 `SWQOS_*` 环境变量组装 SWQOS 列表。
+    pub fn from_env() -> Result<Vec<SwqosConfig>> {
+        from_lookup(|name| std::env::var(name).ok())
+    }
+}
+
+impl SwqosConfigBuilder {
+    pub fn region(mut self, region: SwqosRegion) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Api token (Jito: uuid; Helius, Lightspeed, Solami, Lunar Lander: api key).
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Same as [`Self::token`]; reads better for Jito and Glaive.
+    pub fn uuid(self, uuid: impl Into<String>) -> Self {
+        self.token(uuid)
+    }
+
+    /// Token from environment variable `var`; `build` fails if it is unset or not unicode.
+    pub fn token_from_env(mut self, var: &str) -> Self {
+        match std::env::var(var) {
+            Ok(token) => self.token = Some(token),
+            Err(_) => self.missing_env = Some(var.to_string()),
+        }
+        self
+    }
+
+    /// Same as [`Self::token_from_env`]; reads better for Jito and Glaive.
+    pub fn uuid_from_env(self, var: &str) -> Self {
+        self.token_from_env(var)
+    }
+
+    /// Endpoint overriding the region table; required for Lightspeed and the Default lane.
+    pub fn custom_url(mut self, url: impl Into<String>) -> Self {
+        self.custom_url = Some(url.into());
+        self
+    }
+
+    /// Transport for Node1, BlockRazor, Lunar Lander and Glaive.
+    pub fn transport(mut self, transport: SwqosTransport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Submission mode for Astralane.
+    pub fn astralane_transport(mut self, transport: AstralaneTransport) -> Self {
+        self.astralane_transport = Some(transport);
+        self
+    }
+
+    /// Helius SWQOS-only routing (lower minimum tip).
+    pub fn swqos_only(mut self, swqos_only: bool) -> Self {
+        self.swqos_only = Some(swqos_only);
+        self
+    }
+
+    /// The config, or an error for an unset env variable, a missing token or endpoint, or an
+    /// option the provider does not take.
+    pub fn build(self) -> Result<SwqosConfig> {
+        let swqos_type = self.swqos_type;
+        let name = swqos_type.as_str();
+        if let Some(var) = self.missing_env {
+            bail!("Environment variable {} for {} is not set", var, name);
+        }
+        if self.transport.is_some()
+            && !matches!(
+                swqos_type,
+                SwqosType::Node1
+                    | SwqosType::BlockRazor
+                    | SwqosType::LunarLander
+                    | SwqosType::Glaive
+            )
+        {
+            bail!("{} does not take a transport", name);
+        }
+        if self.astralane_transport.is_some() && swqos_type != SwqosType::Astralane {
+            bail!("{} does not take an Astralane transport", name);
+        }
+        if self.swqos_only.is_some() && swqos_type != SwqosType::Helius {
+            bail!("{} does not take swqos_only", name);
+        }
+        if self.custom_url.is_none()
+            && matches!(swqos_type, SwqosType::Lightspeed | SwqosType::Default)
+        {
+            bail!("{} needs a custom_url", name);
+        }
+
+        // Jito works without a uuid and Helius without an api key; the others need a token.
+        let token_optional =
+            matches!(swqos_type, SwqosType::Jito | SwqosType::Helius | SwqosType::Default);
+        let token = match self.token {
+            Some(token) => token,
+            None if token_optional => String::new(),
+            None => bail!("{} needs an api token", name),
+        };
+        let (region, url, transport) = (self.region, self.custom_url, self.transport);
+        Ok(match swqos_type {
+            SwqosType::Default => SwqosConfig::Default(url.unwrap_or_default()),
+            SwqosType::Jito => SwqosConfig::Jito(token, region, url),
+            SwqosType::NextBlock => SwqosConfig::NextBlock(token, region, url),
+            SwqosType::Bloxroute => SwqosConfig::Bloxroute(token, region, url),
+            SwqosType::Temporal => SwqosConfig::Temporal(token, region, url),
+            SwqosType::ZeroSlot => SwqosConfig::ZeroSlot(token, region, url),
+            SwqosType::Node1 => SwqosConfig::Node1(token, region, url, transport),
+            SwqosType::FlashBlock => SwqosConfig::FlashBlock(token, region, url),
+            SwqosType::BlockRazor => SwqosConfig::BlockRazor(token, region, url, transport),
+            SwqosType::Astralane => {
+                SwqosConfig::Astralane(token, region, url, self.astralane_transport)
+            }
+            SwqosType::Stellium => SwqosConfig::Stellium(token, region, url),
+            SwqosType::Lightspeed => SwqosConfig::Lightspeed(token, region, url),
+            SwqosType::Soyas => SwqosConfig::Soyas(token, region, url),
+            SwqosType::Speedlanding => SwqosConfig::Speedlanding(token, region, url),
+            SwqosType::Helius => SwqosConfig::Helius(token, region, url, self.swqos_only),
+            SwqosType::Solami => SwqosConfig::Solami(token, region, url),
+            SwqosType::LunarLander => SwqosConfig::LunarLander(token, region, url, transport),
+            SwqosType::Glaive => SwqosConfig::Glaive(token, region, url, transport),
+        })
+    }
+}
+
+/// Case-insensitive; `_`, `-` and spaces are ignored (`new_york`, `NewYork`, `los-angeles`).
+impl FromStr for SwqosRegion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match normalize(s).as_str() {
+            "newyork" | "ny" => SwqosRegion::NewYork,
+            "frankfurt" => SwqosRegion::Frankfurt,
+            "amsterdam" => SwqosRegion::Amsterdam,
+            "dublin" => SwqosRegion::Dublin,
+            "slc" | "saltlakecity" => SwqosRegion::SLC,
+            "tokyo" => SwqosRegion::Tokyo,
+            "singapore" => SwqosRegion::Singapore,
+            "london" => SwqosRegion::London,
+            "losangeles" | "la" => SwqosRegion::LosAngeles,
+            "default" => SwqosRegion::Default,
+            _ => bail!("Unknown SWQOS region: {}", s),
+        })
+    }
+}
+
+impl FromStr for SwqosTransport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match normalize(s).as_str() {
+            "http" => SwqosTransport::Http,
+            "grpc" => SwqosTransport::Grpc,
+            "quic" => SwqosTransport::Quic,
+            _ => bail!("Unknown SWQOS transport: {}", s),
+        })
+    }
+}
+
+impl FromStr for AstralaneTransport {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match normalize(s).as_str() {
+            "binary" => AstralaneTransport::Binary,
+            "plain" => AstralaneTransport::Plain,
+            "quic" => AstralaneTransport::Quic,
+            _ => bail!("Unknown Astralane transport: {}", s),
+        })
+    }
+}
+
+fn normalize(s: &str) -> String {
+    s.chars().filter(|c| !matches!(c, '_' | '-' | ' ')).collect::<String>().to_lowercase()
+}
+
+/// [`SwqosConfig::from_env`] over any variable lookup.
+fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Vec<SwqosConfig>> {
+    let default_region = match lookup(SWQOS_REGION_ENV) {
+        Some(region) => region.parse().map_err(|e| anyhow!("{}: {}", SWQOS_REGION_ENV, e))?,
+        None => SwqosRegion::Default,
+    };
+
+    let mut configs = Vec::new();
+    for swqos_type in SwqosType::values() {
+        let prefix = format!("SWQOS_{}", swqos_type.as_str().to_uppercase());
+        let var = |suffix: &str| format!("{}_{}", prefix, suffix);
+        let token = lookup(&var("TOKEN"));
+        let url = lookup(&var("URL"));
+        if token.is_none() && url.is_none() {
+            continue;
+        }
+
+        let mut builder = SwqosConfig::builder(swqos_type).region(default_region.clone());
+        if let Some(token) = token {
+            builder = builder.token(token);
+        }
+        if let Some(url) = url {
+            builder = builder.custom_url(url);
+        }
+        if let Some(region) = lookup(&var("REGION")) {
+            let region = region.parse().map_err(|e| anyhow!("{}: {}", var("REGION"), e))?;
+            builder = builder.region(region);
+        }
+        if let Some(transport) = lookup(&var("TRANSPORT")) {
+            let error = |e: anyhow::Error| anyhow!("{}: {}", var("TRANSPORT"), e);
+            builder = if swqos_type == SwqosType::Astralane {
+                builder.astralane_transport(transport.parse().map_err(error)?)
+            } else {
+                builder.transport(transport.parse().map_err(error)?)
+            };
+        }
+        if let Some(swqos_only) = lookup(&var("SWQOS_ONLY")) {
+            let swqos_only = swqos_only
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("{}: expected true or false", var("SWQOS_ONLY")))?;
+            builder = builder.swqos_only(swqos_only);
+        }
+        configs.push(builder.build()?);
+    }
+    Ok(configs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn builder_reads_the_token_from_the_environment() {
+        std::env::set_var("SWQOS_BUILDER_TEST_JITO_UUID", "uuid-from-env");
+        let config = SwqosConfig::jito()
+            .region(SwqosRegion::Frankfurt)
+            .uuid_from_env("SWQOS_BUILDER_TEST_JITO_UUID")
+            .build()
+            .unwrap();
+        assert_eq!(
+            config,
+            SwqosConfig::Jito("uuid-from-env".to_string(), SwqosRegion::Frankfurt, None)
+        );
+
+        let error = SwqosConfig::glaive()
+            .uuid_from_env("SWQOS_BUILDER_TEST_UNSET")
+            .build()
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("SWQOS_BUILDER_TEST_UNSET"));
+        assert!(SwqosConfig::nextblock().build().is_err());
+        assert!(SwqosConfig::jito().transport(SwqosTransport::Quic).build().is_err());
+    }
+
+    #[test]
+    fn from_env_assembles_configured_providers() {
+        let vars: HashMap<&str, &str> = [
+            ("SWQOS_REGION", "new_york"),
+            ("SWQOS_JITO_TOKEN", ""),
+            ("SWQOS_GLAIVE_TOKEN", "glaive-key"),
+            ("SWQOS_GLAIVE_REGION", "Frankfurt"),
+            ("SWQOS_GLAIVE_TRANSPORT", "http"),
+            ("SWQOS_ASTRALANE_TOKEN", "astra-key"),
+            ("SWQOS_ASTRALANE_TRANSPORT", "quic"),
+            ("SWQOS_DEFAULT_URL", "http://127.0.0.1:8899"),
+        ]
+        .into();
+        let configs = from_lookup(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(
+            configs,
+            vec![
+                SwqosConfig::Jito(String::new(), SwqosRegion::NewYork, None),
+                SwqosConfig::Astralane(
+                    "astra-key".to_string(),
+                    SwqosRegion::NewYork,
+                    None,
+                    Some(AstralaneTransport::Quic),
+                ),
+                SwqosConfig::Glaive(
+                    "glaive-key".to_string(),
+                    SwqosRegion::Frankfurt,
+                    None,
+                    Some(SwqosTransport::Http),
+                ),
+                SwqosConfig::Default("http://127.0.0.1:8899".to_string()),
+            ]
+        );
+
+        let error = from_lookup(|name| {
+            (name == "SWQOS_SOYAS_TOKEN" || name == "SWQOS_SOYAS_REGION").then(|| "mars".into())
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("SWQOS_SOYAS_REGION"));
+    }
+}
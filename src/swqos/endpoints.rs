@@ -0,0 +1,241 @@
+//! Runtime overrides of the built-in SWQOS endpoint tables (`constants::swqos::SWQOS_ENDPOINTS_*`).
+//!
+//! Every client resolves its region's URL through [`SwqosEndpointTable::endpoint`], which prefers
+//! an override set with [`set_swqos_endpoint`] or [`load_swqos_endpoints_from_env`]. When a
+//! provider adds or renames a PoP, point the region at the new URL from config instead of waiting
+//! for a release; unlike a per-config `custom_url`, the override applies to every client built
+//! for that region afterwards. A `custom_url` still wins over both.
+//! SWQOS 端点表运行时覆盖：服务商新增/更名区域端点时，可通过配置指向新 URL，无需等待新版本。
+
+use super::SwqosRegion;
+use crate::constants::swqos::{
+    SWQOS_ENDPOINTS_ASTRALANE_BINARY, SWQOS_ENDPOINTS_ASTRALANE_PLAIN,
+    SWQOS_ENDPOINTS_ASTRALANE_QUIC, SWQOS_ENDPOINTS_ASTRALANE_QUIC_MEV, SWQOS_ENDPOINTS_BLOCKRAZOR,
+    SWQOS_ENDPOINTS_BLOCKRAZOR_GRPC, SWQOS_ENDPOINTS_BLOX, SWQOS_ENDPOINTS_FLASHBLOCK,
+    SWQOS_ENDPOINTS_GLAIVE, SWQOS_ENDPOINTS_GLAIVE_QUIC, SWQOS_ENDPOINTS_HELIUS,
+    SWQOS_ENDPOINTS_JITO, SWQOS_ENDPOINTS_LUNARLANDER, SWQOS_ENDPOINTS_LUNARLANDER_QUIC,
+    SWQOS_ENDPOINTS_NEXTBLOCK, SWQOS_ENDPOINTS_NODE1, SWQOS_ENDPOINTS_NODE1_QUIC,
+    SWQOS_ENDPOINTS_SOLAMI, SWQOS_ENDPOINTS_SOYAS, SWQOS_ENDPOINTS_SPEEDLANDING,
+    SWQOS_ENDPOINTS_STELLIUM, SWQOS_ENDPOINTS_TEMPORAL, SWQOS_ENDPOINTS_ZERO_SLOT,
+};
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
+/// Regions in table order (`SwqosRegion as usize`).
+const REGIONS: [SwqosRegion; 10] = [
+    SwqosRegion::NewYork,
+    SwqosRegion::Frankfurt,
+    SwqosRegion::Amsterdam,
+    SwqosRegion::Dublin,
+    SwqosRegion::SLC,
+    SwqosRegion::Tokyo,
+    SwqosRegion::Singapore,
+    SwqosRegion::London,
+    SwqosRegion::LosAngeles,
+    SwqosRegion::Default,
+];
+
+static OVERRIDES: Lazy<RwLock<HashMap<(SwqosEndpointTable, SwqosRegion), String>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// One built-in endpoint table; providers with several transports have one table each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwqosEndpointTable {
+    Jito,
+    NextBlock,
+    ZeroSlot,
+    Temporal,
+    Bloxroute,
+    Node1,
+    Node1Quic,
+    FlashBlock,
+    BlockRazor,
+    BlockRazorGrpc,
+    AstralanePlain,
+    AstralaneBinary,
+    AstralaneQuic,
+    AstralaneQuicMev,
+    Stellium,
+    Soyas,
+    Speedlanding,
+    LunarLander,
+    LunarLanderQuic,
+    Glaive,
+    GlaiveQuic,
+    Helius,
+    Solami,
+}
+
+impl SwqosEndpointTable {
+    pub fn values() -> &'static [Self] {
+        &[
+            Self::Jito,
+            Self::NextBlock,
+            Self::ZeroSlot,
+            Self::Temporal,
+            Self::Bloxroute,
+            Self::Node1,
+            Self::Node1Quic,
+            Self::FlashBlock,
+            Self::BlockRazor,
+            Self::BlockRazorGrpc,
+            Self::AstralanePlain,
+            Self::AstralaneBinary,
+            Self::AstralaneQuic,
+            Self::AstralaneQuicMev,
+            Self::Stellium,
+            Self::Soyas,
+            Self::Speedlanding,
+            Self::LunarLander,
+            Self::LunarLanderQuic,
+            Self::Glaive,
+            Self::GlaiveQuic,
+            Self::Helius,
+            Self::Solami,
+        ]
+    }
+
+    /// Upper-case name used in environment variables, e.g. `JITO`, `NODE1_QUIC`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Jito => "JITO",
+            Self::NextBlock => "NEXTBLOCK",
+            Self::ZeroSlot => "ZEROSLOT",
+            Self::Temporal => "TEMPORAL",
+            Self::Bloxroute => "BLOXROUTE",
+            Self::Node1 => "NODE1",
+            Self::Node1Quic => "NODE1_QUIC",
+            Self::FlashBlock => "FLASHBLOCK",
+            Self::BlockRazor => "BLOCKRAZOR",
+            Self::BlockRazorGrpc => "BLOCKRAZOR_GRPC",
+            Self::AstralanePlain => "ASTRALANE_PLAIN",
+            Self::AstralaneBinary => "ASTRALANE_BINARY",
+            Self::AstralaneQuic => "ASTRALANE_QUIC",
+            Self::AstralaneQuicMev => "ASTRALANE_QUIC_MEV",
+            Self::Stellium => "STELLIUM",
+            Self::Soyas => "SOYAS",
+            Self::Speedlanding => "SPEEDLANDING",
+            Self::LunarLander => "LUNARLANDER",
+            Self::LunarLanderQuic => "LUNARLANDER_QUIC",
+            Self::Glaive => "GLAIVE",
+            Self::GlaiveQuic => "GLAIVE_QUIC",
+            Self::Helius => "HELIUS",
+            Self::Solami => "SOLAMI",
+        }
+    }
+
+    fn defaults(&self) -> &'static [&'static str; 10] {
+        match self {
+            Self::Jito => &SWQOS_ENDPOINTS_JITO,
+            Self::NextBlock => &SWQOS_ENDPOINTS_NEXTBLOCK,
+            Self::ZeroSlot => &SWQOS_ENDPOINTS_ZERO_SLOT,
+            Self::Temporal => &SWQOS_ENDPOINTS_TEMPORAL,
+            Self::Bloxroute => &SWQOS_ENDPOINTS_BLOX,
+            Self::Node1 => &SWQOS_ENDPOINTS_NODE1,
+            Self::Node1Quic => &SWQOS_ENDPOINTS_NODE1_QUIC,
+            Self::FlashBlock => &SWQOS_ENDPOINTS_FLASHBLOCK,
+            Self::BlockRazor => &SWQOS_ENDPOINTS_BLOCKRAZOR,
+            Self::BlockRazorGrpc => &SWQOS_ENDPOINTS_BLOCKRAZOR_GRPC,
+            Self::AstralanePlain => &SWQOS_ENDPOINTS_ASTRALANE_PLAIN,
+            Self::AstralaneBinary => &SWQOS_ENDPOINTS_ASTRALANE_BINARY,
+            Self::AstralaneQuic => &SWQOS_ENDPOINTS_ASTRALANE_QUIC,
+            Self::AstralaneQuicMev => &SWQOS_ENDPOINTS_ASTRALANE_QUIC_MEV,
+            Self::Stellium => &SWQOS_ENDPOINTS_STELLIUM,
+            Self::Soyas => &SWQOS_ENDPOINTS_SOYAS,
+            Self::Speedlanding => &SWQOS_ENDPOINTS_SPEEDLANDING,
+            Self::LunarLander => &SWQOS_ENDPOINTS_LUNARLANDER,
+            Self::LunarLanderQuic => &SWQOS_ENDPOINTS_LUNARLANDER_QUIC,
+            Self::Glaive => &SWQOS_ENDPOINTS_GLAIVE,
+            Self::GlaiveQuic => &SWQOS_ENDPOINTS_GLAIVE_QUIC,
+            Self::Helius => &SWQOS_ENDPOINTS_HELIUS,
+            Self::Solami => &SWQOS_ENDPOINTS_SOLAMI,
+        }
+    }
+
+    /// URL for `region`: the override when one is set, otherwise the built-in entry.
+    pub fn endpoint(&self, region: &SwqosRegion) -> String {
+        if let Some(url) = OVERRIDES.read().get(&(*self, region.clone())) {
+            return url.clone();
+        }
+        self.defaults()[region.clone() as usize].to_string()
+    }
+}
+
+/// Point `table`'s `region` entry at `url` for clients built from now on.
+pub fn set_swqos_endpoint(table: SwqosEndpointTable, region: SwqosRegion, url: impl Into<String>) {
+    OVERRIDES.write().insert((table, region), url.into());
+}
+
+/// Drop every override, back to the built-in tables.
+pub fn clear_swqos_endpoints() {
+    OVERRIDES.write().clear();
+}
+
+/// Apply overrides from `SWQOS_ENDPOINT_<TABLE>_<REGION>` variables, e.g.
+/// `SWQOS_ENDPOINT_JITO_FRANKFURT` or `SWQOS_ENDPOINT_NODE1_QUIC_NEWYORK`. `<TABLE>` is
+/// [`SwqosEndpointTable::as_str`]; `<REGION>` is the upper-cased [`SwqosRegion`] name without
+/// separators (`NEWYORK`, `SLC`, `LOSANGELES`, `DEFAULT`). Returns the number applied.
+/// 从 `SWQOS_ENDPOINT_<TABLE>_<REGION>` 环境变量加载端点覆盖。
+pub fn load_swqos_endpoints_from_env() -> Result<usize> {
+    apply_from_lookup(|name| std::env::var(name).ok())
+}
+
+/// [`load_swqos_endpoints_from_env`] over any variable lookup.
+fn apply_from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<usize> {
+    let mut found = Vec::new();
+    for table in SwqosEndpointTable::values() {
+        for region in REGIONS {
+            let region_name = format!("{:?}", region).to_uppercase();
+            let var = format!("SWQOS_ENDPOINT_{}_{}", table.as_str(), region_name);
+            if let Some(url) = lookup(&var) {
+                let url = url.trim().to_string();
+                if url.is_empty() {
+                    bail!("{}: empty endpoint", var);
+                }
+                found.push((*table, region, url));
+            }
+        }
+    }
+    let applied = found.len();
+    let mut overrides = OVERRIDES.write();
+    for (table, region, url) in found {
+        overrides.insert((table, region), url);
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_single_region_entries() {
+        // Tables and regions no other test touches, since the overrides are process-wide.
+        let table = SwqosEndpointTable::Speedlanding;
+        let default = SWQOS_ENDPOINTS_SPEEDLANDING[SwqosRegion::Tokyo as usize];
+        assert_eq!(table.endpoint(&SwqosRegion::Tokyo), default);
+
+        set_swqos_endpoint(table, SwqosRegion::Tokyo, "https://tokyo-2.example");
+        assert_eq!(table.endpoint(&SwqosRegion::Tokyo), "https://tokyo-2.example");
+        assert_eq!(
+            table.endpoint(&SwqosRegion::London),
+            SWQOS_ENDPOINTS_SPEEDLANDING[SwqosRegion::London as usize]
+        );
+
+        let applied = apply_from_lookup(|name| {
+            (name == "SWQOS_ENDPOINT_SOLAMI_LOSANGELES").then(|| " https://la.example ".into())
+        })
+        .unwrap();
+        assert_eq!(applied, 1);
+        assert_eq!(
+            SwqosEndpointTable::Solami.endpoint(&SwqosRegion::LosAngeles),
+            "https://la.example"
+        );
+        let error =
+            apply_from_lookup(|name| (name == "SWQOS_ENDPOINT_SOYAS_SLC").then(String::new))
+                .unwrap_err();
+        assert!(error.to_string().contains("SWQOS_ENDPOINT_SOYAS_SLC"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    build_http_client, default_http_client_builder, poll_transaction_confirmation,
+    serialize_transaction_and_encode, HttpClientTuning,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -57,10 +58,15 @@ impl SwqosClientTrait for FlashBlockClient {
 }
 
 impl FlashBlockClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
-        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
+        Ok(Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client })
     }
 
     pub async fn send_transaction(
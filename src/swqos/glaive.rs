@@ -19,7 +19,10 @@ use crate::{
     common::SolanaRpcClient,
     constants::swqos::GLAIVE_TIP_ACCOUNTS,
     swqos::{
-        common::{default_http_client_builder, poll_transaction_confirmation},
+        common::{
+            build_http_client, default_http_client_builder, poll_transaction_confirmation,
+            HttpClientTuning,
+        },
         glaive_quic::GlaiveQuicClient,
         serialization::serialize_transaction_bincode_sync,
         SwqosClientTrait, SwqosType, TradeType,
@@ -50,11 +53,12 @@ impl GlaiveClient {
         endpoint: String,
         api_key: String,
         mev_protection: bool,
+        http_tuning: &HttpClientTuning,
     ) -> Result<Self> {
         validate_api_key(&api_key)?;
         let submit_url = build_binary_url(&endpoint, &api_key, mev_protection)?;
         let health_url = build_health_url(&endpoint)?;
-        let http_client = default_http_client_builder().build()?;
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
         let stop_ping = Arc::new(AtomicBool::new(false));
         let ping_handle = Mutex::new(None);
 
@@ -8,7 +8,8 @@
 //! Optional query: api-key (custom TPS only), swqos_only (SWQOS-only routing, lower min tip).
 
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    build_http_client, default_http_client_builder, poll_transaction_confirmation,
+    serialize_transaction_and_encode, HttpClientTuning,
 };
 use anyhow::Result;
 use rand::seq::IndexedRandom;
@@ -41,11 +42,12 @@ impl HeliusClient {
         endpoint: String,
         api_key: Option<String>,
         swqos_only: bool,
-    ) -> Self {
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
         let submit_url = Self::build_submit_url(&endpoint, api_key.as_deref(), swqos_only);
-        Self { submit_url, rpc_client: Arc::new(rpc_client), http_client, swqos_only }
+        Ok(Self { submit_url, rpc_client: Arc::new(rpc_client), http_client, swqos_only })
     }
 
     /// Build URL once at construction; no per-request allocation.
@@ -1,6 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
-    FormatBase64VersionedTransaction,
+    build_http_client, default_http_client_builder, poll_transaction_confirmation,
+    serialize_transaction_and_encode, FormatBase64VersionedTransaction, HttpClientTuning,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -57,10 +57,15 @@ impl SwqosClientTrait for JitoClient {
 }
 
 impl JitoClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
-        Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client }
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
+        Ok(Self { rpc_client: Arc::new(rpc_client), endpoint, auth_token, http_client })
     }
 
     pub async fn send_transaction_impl(
@@ -164,7 +169,7 @@ impl JitoClient {
     ) -> Result<()> {
         let start_time = Instant::now();
         let txs_base64 =
-            transactions.iter().map(|tx| tx.to_base64_string()).collect::<Vec<String>>();
+            transactions.iter().map(|tx| tx.to_base64_string()).collect::<Result<Vec<String>>>()?;
         let body = serde_json::json!({
             "jsonrpc": "2.0",
             "method": "sendBundle",
@@ -193,19 +198,38 @@ impl JitoClient {
             .text()
             .await?;
 
-        if let Ok(response_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
-            if response_json.get("result").is_some() {
-                println!(" jito {} submitted: {:?}", trade_type, start_time.elapsed());
-            } else if let Some(_error) = response_json.get("error") {
-                eprintln!(
-                    " jito {} submission failed after {:?}: {:?}",
+        // Bundles are all-or-nothing (e.g. backrun mode), so a rejected bundle is reported to the caller.
+        match serde_json::from_str::<serde_json::Value>(&response_text) {
+            Ok(response_json) if response_json.get("result").is_some() => {
+                crate::common::sdk_log::log_swqos_submitted(
+                    "jito",
                     trade_type,
                     start_time.elapsed(),
-                    _error
                 );
+                Ok(())
+            }
+            Ok(response_json) => {
+                let error = response_json.get("error").unwrap_or(&response_json);
+                if crate::common::sdk_log::sdk_log_enabled() {
+                    tracing::warn!(
+                        target: "sol_trade_sdk",
+                        "jito {} bundle rejected after {:?}: {}",
+                        trade_type,
+                        start_time.elapsed(),
+                        error
+                    );
+                }
+                Err(anyhow::anyhow!("jito bundle submission failed: {}", error))
+            }
+            Err(_) => {
+                crate::common::sdk_log::log_swqos_submission_failed(
+                    "jito",
+                    trade_type,
+                    start_time.elapsed(),
+                    &response_text,
+                );
+                Err(anyhow::anyhow!("jito bundle submission failed: {}", response_text))
             }
         }
-
-        Ok(())
     }
 }
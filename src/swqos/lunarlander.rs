@@ -1,4 +1,6 @@
-use crate::swqos::common::{default_http_client_builder, poll_transaction_confirmation};
+use crate::swqos::common::{
+    build_http_client, default_http_client_builder, poll_transaction_confirmation, HttpClientTuning,
+};
 use rand::seq::IndexedRandom;
 use reqwest::Client;
 use std::{sync::Arc, time::Instant};
@@ -73,9 +75,14 @@ impl SwqosClientTrait for LunarLanderClient {
 
 impl LunarLanderClient {
     /// Create an HTTP binary client (POST /send-bin with bincode body).
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
         let ping_handle = Arc::new(tokio::sync::Mutex::new(None));
         let stop_ping = Arc::new(AtomicBool::new(false));
 
@@ -93,7 +100,7 @@ impl LunarLanderClient {
         tokio::spawn(async move {
             client_clone.start_ping_task().await;
         });
-        client
+        Ok(client)
     }
 
     /// Create a QUIC client (port 16888, cert CN = api_key, fire-and-forget unidirectional streams).
@@ -3,6 +3,8 @@ pub mod astralane_quic;
 pub mod blockrazor;
 pub mod bloxroute;
 pub mod common;
+pub mod config_builder;
+pub mod endpoints;
 pub mod flashblock;
 pub mod glaive;
 pub mod glaive_quic;
@@ -29,31 +31,29 @@ use solana_sdk::transaction::VersionedTransaction;
 
 use anyhow::Result;
 
+pub use config_builder::SwqosConfigBuilder;
+pub use endpoints::{
+    clear_swqos_endpoints, load_swqos_endpoints_from_env, set_swqos_endpoint, SwqosEndpointTable,
+};
+
 use crate::{
     common::SolanaRpcClient,
     constants::swqos::{
-        SWQOS_ENDPOINTS_ASTRALANE_BINARY, SWQOS_ENDPOINTS_ASTRALANE_PLAIN,
-        SWQOS_ENDPOINTS_ASTRALANE_QUIC, SWQOS_ENDPOINTS_ASTRALANE_QUIC_MEV,
-        SWQOS_ENDPOINTS_BLOCKRAZOR, SWQOS_ENDPOINTS_BLOCKRAZOR_GRPC, SWQOS_ENDPOINTS_BLOX,
-        SWQOS_ENDPOINTS_FLASHBLOCK, SWQOS_ENDPOINTS_GLAIVE, SWQOS_ENDPOINTS_GLAIVE_QUIC,
-        SWQOS_ENDPOINTS_HELIUS, SWQOS_ENDPOINTS_JITO, SWQOS_ENDPOINTS_LUNARLANDER,
-        SWQOS_ENDPOINTS_LUNARLANDER_QUIC, SWQOS_ENDPOINTS_NEXTBLOCK, SWQOS_ENDPOINTS_NODE1,
-        SWQOS_ENDPOINTS_NODE1_QUIC, SWQOS_ENDPOINTS_SOLAMI, SWQOS_ENDPOINTS_SOYAS,
-        SWQOS_ENDPOINTS_SPEEDLANDING, SWQOS_ENDPOINTS_STELLIUM, SWQOS_ENDPOINTS_TEMPORAL,
-        SWQOS_ENDPOINTS_ZERO_SLOT, SWQOS_MIN_TIP_ASTRALANE, SWQOS_MIN_TIP_BLOCKRAZOR,
-        SWQOS_MIN_TIP_BLOXROUTE, SWQOS_MIN_TIP_DEFAULT, SWQOS_MIN_TIP_FLASHBLOCK,
-        SWQOS_MIN_TIP_GLAIVE, SWQOS_MIN_TIP_HELIUS, SWQOS_MIN_TIP_JITO, SWQOS_MIN_TIP_LIGHTSPEED,
+        SWQOS_MIN_TIP_ASTRALANE, SWQOS_MIN_TIP_BLOCKRAZOR, SWQOS_MIN_TIP_BLOXROUTE,
+        SWQOS_MIN_TIP_DEFAULT, SWQOS_MIN_TIP_FLASHBLOCK, SWQOS_MIN_TIP_GLAIVE,
+        SWQOS_MIN_TIP_HELIUS, SWQOS_MIN_TIP_JITO, SWQOS_MIN_TIP_LIGHTSPEED,
         SWQOS_MIN_TIP_LUNARLANDER, SWQOS_MIN_TIP_NEXTBLOCK, SWQOS_MIN_TIP_NODE1,
         SWQOS_MIN_TIP_SOLAMI, SWQOS_MIN_TIP_SOYAS, SWQOS_MIN_TIP_SPEEDLANDING,
         SWQOS_MIN_TIP_STELLIUM, SWQOS_MIN_TIP_TEMPORAL, SWQOS_MIN_TIP_ZERO_SLOT,
     },
     swqos::{
         astralane::AstralaneClient, blockrazor::BlockRazorClient, bloxroute::BloxrouteClient,
-        flashblock::FlashBlockClient, glaive::GlaiveClient, helius::HeliusClient, jito::JitoClient,
-        lightspeed::LightspeedClient, lunarlander::LunarLanderClient, nextblock::NextBlockClient,
-        node1::Node1Client, node1_quic::Node1QuicClient, solami::SolamiClient,
-        solana_rpc::SolRpcClient, soyas::SoyasClient, speedlanding::SpeedlandingClient,
-        stellium::StelliumClient, temporal::TemporalClient, zeroslot::ZeroSlotClient,
+        common::HttpClientTuning, flashblock::FlashBlockClient, glaive::GlaiveClient,
+        helius::HeliusClient, jito::JitoClient, lightspeed::LightspeedClient,
+        lunarlander::LunarLanderClient, nextblock::NextBlockClient, node1::Node1Client,
+        node1_quic::Node1QuicClient, solami::SolamiClient, solana_rpc::SolRpcClient,
+        soyas::SoyasClient, speedlanding::SpeedlandingClient, stellium::StelliumClient,
+        temporal::TemporalClient, zeroslot::ZeroSlotClient,
     },
 };
 
@@ -159,6 +159,33 @@ impl SwqosType {
         }
     }
 
+    /// Smallest tip in SOL the provider accepts; lower tips are dropped or rejected by the relay.
+    /// Helius Sender in `swqos_only` mode accepts less, see [`SwqosClientTrait::min_tip_sol`].
+    /// 各通道最低小费（SOL），低于该值的交易会被通道丢弃或拒绝。
+    #[inline]
+    pub fn min_tip_sol(self) -> f64 {
+        match self {
+            Self::Jito => SWQOS_MIN_TIP_JITO,
+            Self::NextBlock => SWQOS_MIN_TIP_NEXTBLOCK,
+            Self::ZeroSlot => SWQOS_MIN_TIP_ZERO_SLOT,
+            Self::Temporal => SWQOS_MIN_TIP_TEMPORAL,
+            Self::Bloxroute => SWQOS_MIN_TIP_BLOXROUTE,
+            Self::Node1 => SWQOS_MIN_TIP_NODE1,
+            Self::FlashBlock => SWQOS_MIN_TIP_FLASHBLOCK,
+            Self::BlockRazor => SWQOS_MIN_TIP_BLOCKRAZOR,
+            Self::Astralane => SWQOS_MIN_TIP_ASTRALANE,
+            Self::Stellium => SWQOS_MIN_TIP_STELLIUM,
+            Self::Lightspeed => SWQOS_MIN_TIP_LIGHTSPEED,
+            Self::Soyas => SWQOS_MIN_TIP_SOYAS,
+            Self::Speedlanding => SWQOS_MIN_TIP_SPEEDLANDING,
+            Self::Helius => SWQOS_MIN_TIP_HELIUS,
+            Self::Solami => SWQOS_MIN_TIP_SOLAMI,
+            Self::LunarLander => SWQOS_MIN_TIP_LUNARLANDER,
+            Self::Glaive => SWQOS_MIN_TIP_GLAIVE,
+            Self::Default => SWQOS_MIN_TIP_DEFAULT,
+        }
+    }
+
     pub fn values() -> Vec<Self> {
         vec![
             Self::Jito,
@@ -204,26 +231,7 @@ pub trait SwqosClientTrait {
     /// Minimum tip in SOL required by this provider. Helius returns lower value when swqos_only is true.
     #[inline]
     fn min_tip_sol(&self) -> f64 {
-        match self.get_swqos_type() {
-            SwqosType::Jito => SWQOS_MIN_TIP_JITO,
-            SwqosType::NextBlock => SWQOS_MIN_TIP_NEXTBLOCK,
-            SwqosType::ZeroSlot => SWQOS_MIN_TIP_ZERO_SLOT,
-            SwqosType::Temporal => SWQOS_MIN_TIP_TEMPORAL,
-            SwqosType::Bloxroute => SWQOS_MIN_TIP_BLOXROUTE,
-            SwqosType::Node1 => SWQOS_MIN_TIP_NODE1,
-            SwqosType::FlashBlock => SWQOS_MIN_TIP_FLASHBLOCK,
-            SwqosType::BlockRazor => SWQOS_MIN_TIP_BLOCKRAZOR,
-            SwqosType::Astralane => SWQOS_MIN_TIP_ASTRALANE,
-            SwqosType::Stellium => SWQOS_MIN_TIP_STELLIUM,
-            SwqosType::Lightspeed => SWQOS_MIN_TIP_LIGHTSPEED,
-            SwqosType::Soyas => SWQOS_MIN_TIP_SOYAS,
-            SwqosType::Speedlanding => SWQOS_MIN_TIP_SPEEDLANDING,
-            SwqosType::Helius => SWQOS_MIN_TIP_HELIUS,
-            SwqosType::Solami => SWQOS_MIN_TIP_SOLAMI,
-            SwqosType::LunarLander => SWQOS_MIN_TIP_LUNARLANDER,
-            SwqosType::Glaive => SWQOS_MIN_TIP_GLAIVE,
-            SwqosType::Default => SWQOS_MIN_TIP_DEFAULT,
-        }
+        self.get_swqos_type().min_tip_sol()
     }
 }
 
@@ -323,29 +331,31 @@ impl SwqosConfig {
         SWQOS_BLACKLIST.contains(&self.swqos_type())
     }
 
+    /// `url` when given, otherwise the region's entry of the provider's endpoint table (which
+    /// honours [`set_swqos_endpoint`] overrides).
     pub fn get_endpoint(swqos_type: SwqosType, region: SwqosRegion, url: Option<String>) -> String {
         if let Some(custom_url) = url {
             return custom_url;
         }
 
         match swqos_type {
-            SwqosType::Jito => SWQOS_ENDPOINTS_JITO[region as usize].to_string(),
-            SwqosType::NextBlock => SWQOS_ENDPOINTS_NEXTBLOCK[region as usize].to_string(),
-            SwqosType::ZeroSlot => SWQOS_ENDPOINTS_ZERO_SLOT[region as usize].to_string(),
-            SwqosType::Temporal => SWQOS_ENDPOINTS_TEMPORAL[region as usize].to_string(),
-            SwqosType::Bloxroute => SWQOS_ENDPOINTS_BLOX[region as usize].to_string(),
-            SwqosType::Node1 => SWQOS_ENDPOINTS_NODE1[region as usize].to_string(),
-            SwqosType::FlashBlock => SWQOS_ENDPOINTS_FLASHBLOCK[region as usize].to_string(),
-            SwqosType::BlockRazor => SWQOS_ENDPOINTS_BLOCKRAZOR[region as usize].to_string(),
-            SwqosType::Astralane => SWQOS_ENDPOINTS_ASTRALANE_BINARY[region as usize].to_string(),
-            SwqosType::Stellium => SWQOS_ENDPOINTS_STELLIUM[region as usize].to_string(),
+            SwqosType::Jito => SwqosEndpointTable::Jito.endpoint(&region),
+            SwqosType::NextBlock => SwqosEndpointTable::NextBlock.endpoint(&region),
+            SwqosType::ZeroSlot => SwqosEndpointTable::ZeroSlot.endpoint(&region),
+            SwqosType::Temporal => SwqosEndpointTable::Temporal.endpoint(&region),
+            SwqosType::Bloxroute => SwqosEndpointTable::Bloxroute.endpoint(&region),
+            SwqosType::Node1 => SwqosEndpointTable::Node1.endpoint(&region),
+            SwqosType::FlashBlock => SwqosEndpointTable::FlashBlock.endpoint(&region),
+            SwqosType::BlockRazor => SwqosEndpointTable::BlockRazor.endpoint(&region),
+            SwqosType::Astralane => SwqosEndpointTable::AstralaneBinary.endpoint(&region),
+            SwqosType::Stellium => SwqosEndpointTable::Stellium.endpoint(&region),
             SwqosType::Lightspeed => "".to_string(), // Lightspeed requires custom URL with api_key
-            SwqosType::Soyas => SWQOS_ENDPOINTS_SOYAS[region as usize].to_string(),
-            SwqosType::Speedlanding => SWQOS_ENDPOINTS_SPEEDLANDING[region as usize].to_string(),
-            SwqosType::Helius => SWQOS_ENDPOINTS_HELIUS[region as usize].to_string(),
-            SwqosType::Solami => SWQOS_ENDPOINTS_SOLAMI[region as usize].to_string(),
-            SwqosType::LunarLander => SWQOS_ENDPOINTS_LUNARLANDER[region as usize].to_string(),
-            SwqosType::Glaive => SWQOS_ENDPOINTS_GLAIVE[region as usize].to_string(),
+            SwqosType::Soyas => SwqosEndpointTable::Soyas.endpoint(&region),
+            SwqosType::Speedlanding => SwqosEndpointTable::Speedlanding.endpoint(&region),
+            SwqosType::Helius => SwqosEndpointTable::Helius.endpoint(&region),
+            SwqosType::Solami => SwqosEndpointTable::Solami.endpoint(&region),
+            SwqosType::LunarLander => SwqosEndpointTable::LunarLander.endpoint(&region),
+            SwqosType::Glaive => SwqosEndpointTable::Glaive.endpoint(&region),
             SwqosType::Default => "".to_string(),
         }
     }
@@ -366,95 +376,123 @@ impl SwqosConfig {
                 // transport=None 或 transport=Grpc => gRPC; transport=Http => HTTP
                 let use_http = transport.map_or(false, |t| t == SwqosTransport::Http);
                 if use_http {
-                    SWQOS_ENDPOINTS_BLOCKRAZOR[region as usize].to_string()
+                    SwqosEndpointTable::BlockRazor.endpoint(&region)
                 } else {
-                    SWQOS_ENDPOINTS_BLOCKRAZOR_GRPC[region as usize].to_string()
+                    SwqosEndpointTable::BlockRazorGrpc.endpoint(&region)
                 }
             }
             SwqosType::Node1 => {
                 let use_quic = transport.map_or(false, |t| t == SwqosTransport::Quic);
                 if use_quic {
-                    SWQOS_ENDPOINTS_NODE1_QUIC[region as usize].to_string()
+                    SwqosEndpointTable::Node1Quic.endpoint(&region)
                 } else {
-                    SWQOS_ENDPOINTS_NODE1[region as usize].to_string()
+                    SwqosEndpointTable::Node1.endpoint(&region)
                 }
             }
             SwqosType::LunarLander => {
                 let use_quic = transport.unwrap_or(SwqosTransport::Quic) == SwqosTransport::Quic;
                 if use_quic {
-                    SWQOS_ENDPOINTS_LUNARLANDER_QUIC[region as usize].to_string()
+                    SwqosEndpointTable::LunarLanderQuic.endpoint(&region)
                 } else {
-                    SWQOS_ENDPOINTS_LUNARLANDER[region as usize].to_string()
+                    SwqosEndpointTable::LunarLander.endpoint(&region)
                 }
             }
             SwqosType::Glaive => {
                 let use_quic = transport.unwrap_or(SwqosTransport::Quic) == SwqosTransport::Quic;
                 if use_quic {
-                    SWQOS_ENDPOINTS_GLAIVE_QUIC[region as usize].to_string()
+                    SwqosEndpointTable::GlaiveQuic.endpoint(&region)
                 } else {
-                    SWQOS_ENDPOINTS_GLAIVE[region as usize].to_string()
+                    SwqosEndpointTable::Glaive.endpoint(&region)
                 }
             }
             _ => Self::get_endpoint(swqos_type, region, None),
         }
     }
 
+    /// Connect the client of `swqos_config`. HTTP transports build their client from the SDK
+    /// defaults with `http_tuning` on top; QUIC / gRPC transports ignore it.
     pub async fn get_swqos_client(
         rpc_url: String,
         commitment: CommitmentConfig,
         swqos_config: SwqosConfig,
         mev_protection: bool,
+        http_tuning: &HttpClientTuning,
     ) -> Result<Arc<SwqosClient>> {
         match swqos_config {
             SwqosConfig::Jito(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Jito, region, url);
-                let jito_client = JitoClient::new(rpc_url.clone(), endpoint, auth_token);
+                let jito_client =
+                    JitoClient::new(rpc_url.clone(), endpoint, auth_token, http_tuning)?;
                 Ok(Arc::new(jito_client))
             }
             SwqosConfig::NextBlock(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::NextBlock, region, url);
-                let nextblock_client =
-                    NextBlockClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let nextblock_client = NextBlockClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(nextblock_client))
             }
             SwqosConfig::ZeroSlot(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::ZeroSlot, region, url);
-                let zeroslot_client =
-                    ZeroSlotClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let zeroslot_client = ZeroSlotClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(zeroslot_client))
             }
             SwqosConfig::Temporal(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Temporal, region, url);
-                let temporal_client =
-                    TemporalClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let temporal_client = TemporalClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(temporal_client))
             }
             SwqosConfig::Bloxroute(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Bloxroute, region, url);
-                let bloxroute_client =
-                    BloxrouteClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let bloxroute_client = BloxrouteClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(bloxroute_client))
             }
             SwqosConfig::Node1(auth_token, region, url, transport) => {
                 let use_quic = transport.map_or(false, |t| t == SwqosTransport::Quic);
                 if use_quic {
-                    let quic_endpoint = url
-                        .unwrap_or_else(|| SWQOS_ENDPOINTS_NODE1_QUIC[region as usize].to_string());
+                    let quic_endpoint =
+                        url.unwrap_or_else(|| SwqosEndpointTable::Node1Quic.endpoint(&region));
                     let node1_quic =
                         Node1QuicClient::connect(&quic_endpoint, &auth_token, rpc_url.clone())
                             .await?;
                     Ok(Arc::new(node1_quic))
                 } else {
                     let endpoint = SwqosConfig::get_endpoint(SwqosType::Node1, region, url);
-                    let node1_client =
-                        Node1Client::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                    let node1_client = Node1Client::new(
+                        rpc_url.clone(),
+                        endpoint.to_string(),
+                        auth_token,
+                        http_tuning,
+                    )?;
                     Ok(Arc::new(node1_client))
                 }
             }
             SwqosConfig::FlashBlock(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::FlashBlock, region, url);
-                let flashblock_client =
-                    FlashBlockClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let flashblock_client = FlashBlockClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(flashblock_client))
             }
             SwqosConfig::BlockRazor(auth_token, region, url, transport) => {
@@ -473,7 +511,8 @@ impl SwqosConfig {
                         endpoint.to_string(),
                         auth_token,
                         mev_protection,
-                    );
+                        http_tuning,
+                    )?;
                     Ok(Arc::new(blockrazor_client))
                 } else {
                     // 使用 gRPC 模式（默认或用户明确指定了 gRPC）
@@ -493,9 +532,9 @@ impl SwqosConfig {
                     AstralaneTransport::Quic => {
                         let quic_endpoint = url.unwrap_or_else(|| {
                             if mev_protection {
-                                SWQOS_ENDPOINTS_ASTRALANE_QUIC_MEV[region as usize].to_string()
+                                SwqosEndpointTable::AstralaneQuicMev.endpoint(&region)
                             } else {
-                                SWQOS_ENDPOINTS_ASTRALANE_QUIC[region as usize].to_string()
+                                SwqosEndpointTable::AstralaneQuic.endpoint(&region)
                             }
                         });
                         let astralane_client =
@@ -505,40 +544,50 @@ impl SwqosConfig {
                     }
                     AstralaneTransport::Plain => {
                         let endpoint = url.unwrap_or_else(|| {
-                            SWQOS_ENDPOINTS_ASTRALANE_PLAIN[region as usize].to_string()
+                            SwqosEndpointTable::AstralanePlain.endpoint(&region)
                         });
                         let astralane_client = AstralaneClient::new(
                             rpc_url.clone(),
                             endpoint,
                             auth_token,
                             mev_protection,
-                        );
+                            http_tuning,
+                        )?;
                         Ok(Arc::new(astralane_client))
                     }
                     AstralaneTransport::Binary => {
                         let endpoint = url.unwrap_or_else(|| {
-                            SWQOS_ENDPOINTS_ASTRALANE_BINARY[region as usize].to_string()
+                            SwqosEndpointTable::AstralaneBinary.endpoint(&region)
                         });
                         let astralane_client = AstralaneClient::new(
                             rpc_url.clone(),
                             endpoint,
                             auth_token,
                             mev_protection,
-                        );
+                            http_tuning,
+                        )?;
                         Ok(Arc::new(astralane_client))
                     }
                 }
             }
             SwqosConfig::Stellium(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Stellium, region, url);
-                let stellium_client =
-                    StelliumClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let stellium_client = StelliumClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(stellium_client))
             }
             SwqosConfig::Lightspeed(auth_token, region, url) => {
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Lightspeed, region, url);
-                let lightspeed_client =
-                    LightspeedClient::new(rpc_url.clone(), endpoint.to_string(), auth_token);
+                let lightspeed_client = LightspeedClient::new(
+                    rpc_url.clone(),
+                    endpoint.to_string(),
+                    auth_token,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(lightspeed_client))
             }
             SwqosConfig::Soyas(auth_token, region, url) => {
@@ -558,8 +607,13 @@ impl SwqosConfig {
                 let swqos_only = swqos_only.unwrap_or(false);
                 let endpoint = SwqosConfig::get_endpoint(SwqosType::Helius, region, url.clone());
                 let api_key_opt = if api_key.is_empty() { None } else { Some(api_key.clone()) };
-                let helius_client =
-                    HeliusClient::new(rpc_url.clone(), endpoint, api_key_opt, swqos_only);
+                let helius_client = HeliusClient::new(
+                    rpc_url.clone(),
+                    endpoint,
+                    api_key_opt,
+                    swqos_only,
+                    http_tuning,
+                )?;
                 Ok(Arc::new(helius_client))
             }
             SwqosConfig::Solami(auth_token, region, url) => {
@@ -571,9 +625,8 @@ impl SwqosConfig {
             SwqosConfig::LunarLander(api_key, region, url, transport) => {
                 let use_quic = transport.unwrap_or(SwqosTransport::Quic) == SwqosTransport::Quic;
                 if use_quic {
-                    let quic_endpoint = url.unwrap_or_else(|| {
-                        SWQOS_ENDPOINTS_LUNARLANDER_QUIC[region as usize].to_string()
-                    });
+                    let quic_endpoint = url
+                        .unwrap_or_else(|| SwqosEndpointTable::LunarLanderQuic.endpoint(&region));
                     let lunarlander_client = LunarLanderClient::new_quic(
                         rpc_url.clone(),
                         &quic_endpoint,
@@ -585,16 +638,15 @@ impl SwqosConfig {
                 } else {
                     let endpoint = SwqosConfig::get_endpoint(SwqosType::LunarLander, region, url);
                     let lunarlander_client =
-                        LunarLanderClient::new(rpc_url.clone(), endpoint, api_key);
+                        LunarLanderClient::new(rpc_url.clone(), endpoint, api_key, http_tuning)?;
                     Ok(Arc::new(lunarlander_client))
                 }
             }
             SwqosConfig::Glaive(api_key, region, url, transport) => {
                 match transport.unwrap_or(SwqosTransport::Quic) {
                     SwqosTransport::Quic => {
-                        let endpoint = url.unwrap_or_else(|| {
-                            SWQOS_ENDPOINTS_GLAIVE_QUIC[region as usize].to_string()
-                        });
+                        let endpoint =
+                            url.unwrap_or_else(|| SwqosEndpointTable::GlaiveQuic.endpoint(&region));
                         let client = GlaiveClient::new_quic(
                             rpc_url.clone(),
                             &endpoint,
@@ -605,13 +657,14 @@ impl SwqosConfig {
                         Ok(Arc::new(client))
                     }
                     SwqosTransport::Http => {
-                        let endpoint = url
-                            .unwrap_or_else(|| SWQOS_ENDPOINTS_GLAIVE[region as usize].to_string());
+                        let endpoint =
+                            url.unwrap_or_else(|| SwqosEndpointTable::Glaive.endpoint(&region));
                         let client = GlaiveClient::new_http(
                             rpc_url.clone(),
                             endpoint,
                             api_key,
                             mev_protection,
+                            http_tuning,
                         )?;
                         Ok(Arc::new(client))
                     }
@@ -632,6 +685,10 @@ impl SwqosConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::swqos::{
+        SWQOS_ENDPOINTS_GLAIVE, SWQOS_ENDPOINTS_GLAIVE_QUIC, SWQOS_ENDPOINTS_LUNARLANDER,
+        SWQOS_ENDPOINTS_LUNARLANDER_QUIC,
+    };
 
     #[test]
     fn lunarlander_defaults_to_quic_endpoint() {
@@ -696,9 +753,31 @@ mod tests {
                 Some(SwqosTransport::Grpc),
             ),
             false,
+            &HttpClientTuning::default(),
         )
         .await;
         let error = result.err().expect("Glaive gRPC config must fail");
         assert!(error.to_string().contains("does not support the gRPC transport"));
     }
+
+    #[tokio::test]
+    async fn http_tuning_builds_the_provider_client() {
+        let tuning = HttpClientTuning {
+            pool_max_idle_per_host: Some(32),
+            http2_adaptive_window: Some(false),
+            http2_initial_stream_window_size: Some(1 << 20),
+            ipv4_only: true,
+            ..Default::default()
+        };
+        let client = SwqosConfig::get_swqos_client(
+            "http://127.0.0.1:8899".to_string(),
+            CommitmentConfig::processed(),
+            SwqosConfig::Jito("uuid".to_string(), SwqosRegion::Frankfurt, None),
+            false,
+            &tuning,
+        )
+        .await
+        .unwrap();
+        assert_eq!(client.get_swqos_type(), SwqosType::Jito);
+    }
 }
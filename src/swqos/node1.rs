@@ -1,5 +1,6 @@
 use crate::swqos::common::{
-    default_http_client_builder, poll_transaction_confirmation, serialize_transaction_and_encode,
+    build_http_client, default_http_client_builder, poll_transaction_confirmation,
+    serialize_transaction_and_encode, HttpClientTuning,
 };
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -63,9 +64,14 @@ impl SwqosClientTrait for Node1Client {
 }
 
 impl Node1Client {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
 
         let client = Self {
             rpc_client: Arc::new(rpc_client),
@@ -82,7 +88,7 @@ impl Node1Client {
             client_clone.start_ping_task().await;
         });
 
-        client
+        Ok(client)
     }
 
     /// Start periodic ping task to keep connections active
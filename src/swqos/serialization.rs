@@ -1,16 +1,16 @@
 //! Transaction serialization module.
 
-use crate::perf::{
-    compiler_optimization::CompileTimeOptimizedEventProcessor, simd::SIMDSerializer,
-};
+use crate::perf::simd::SIMDSerializer;
 use anyhow::Result;
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
 use crossbeam_queue::ArrayQueue;
-use once_cell::sync::Lazy;
+use dashmap::DashMap;
+use once_cell::sync::{Lazy, OnceCell};
 use solana_client::rpc_client::SerializableTransaction;
 use solana_sdk::signature::Signature;
 use solana_transaction_status::UiTransactionEncoding;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 /// Max number of reusable buffers kept in the queue.
@@ -19,11 +19,18 @@ const SERIALIZER_POOL_SIZE: usize = 10_000;
 const SERIALIZER_BUFFER_SIZE: usize = 256 * 1024;
 /// Cold-start prewarm count. Keep small to avoid first-submit spikes.
 const SERIALIZER_PREWARM_BUFFERS: usize = 64;
+/// Recently encoded transactions kept for reuse; a lane's transaction is encoded, sent and
+/// captured within milliseconds, so a small bound is enough.
+const ENCODED_CACHE_SIZE: usize = 512;
+/// Initial capacity of an encoded transaction (max packet size).
+const TX_BYTES_CAPACITY: usize = 1232;
 
 /// Zero-allocation serializer using a buffer pool to avoid runtime allocation.
 pub struct ZeroAllocSerializer {
     buffer_pool: Arc<ArrayQueue<Vec<u8>>>,
     buffer_size: usize,
+    pool_hits: AtomicU64,
+    pool_misses: AtomicU64,
 }
 
 impl ZeroAllocSerializer {
@@ -41,7 +48,31 @@ impl ZeroAllocSerializer {
             let _ = pool.push(Vec::with_capacity(buffer_size));
         }
 
-        Self { buffer_pool: Arc::new(pool), buffer_size }
+        Self::from_pool(pool, buffer_size)
+    }
+
+    fn from_pool(pool: ArrayQueue<Vec<u8>>, buffer_size: usize) -> Self {
+        Self {
+            buffer_pool: Arc::new(pool),
+            buffer_size,
+            pool_hits: AtomicU64::new(0),
+            pool_misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Pooled buffer, or a fresh one when the pool is empty (counted as a miss).
+    #[inline]
+    fn take_buffer(&self) -> Vec<u8> {
+        match self.buffer_pool.pop() {
+            Some(buffer) => {
+                self.pool_hits.fetch_add(1, Ordering::Relaxed);
+                buffer
+            }
+            None => {
+                self.pool_misses.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(self.buffer_size)
+            }
+        }
     }
 
     pub fn serialize_zero_alloc<T: serde::Serialize>(
@@ -49,14 +80,11 @@ impl ZeroAllocSerializer {
         data: &T,
         _label: &str,
     ) -> Result<Vec<u8>> {
-        // Try to get a buffer from the pool
-        let mut buffer =
-            self.buffer_pool.pop().unwrap_or_else(|| Vec::with_capacity(self.buffer_size));
+        let mut buffer = self.take_buffer();
 
-        // Serialize into buffer
-        let serialized = bincode::serialize(data)?;
+        // Serialize straight into the pooled buffer (no intermediate Vec)
         buffer.clear();
-        buffer.extend_from_slice(&serialized);
+        bincode::serialize_into(&mut buffer, data)?;
 
         Ok(buffer)
     }
@@ -72,30 +100,28 @@ impl ZeroAllocSerializer {
         let capacity = self.buffer_pool.capacity();
         (available, capacity)
     }
+
+    /// Buffers served from the pool vs freshly allocated: `(hits, misses)`.
+    pub fn pool_counters(&self) -> (u64, u64) {
+        (self.pool_hits.load(Ordering::Relaxed), self.pool_misses.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn reset_pool_counters(&self) {
+        self.pool_hits.store(0, Ordering::Relaxed);
+        self.pool_misses.store(0, Ordering::Relaxed);
+    }
 }
 
 /// Global serializer instance.
 static SERIALIZER: Lazy<Arc<ZeroAllocSerializer>> =
     Lazy::new(|| Arc::new(ZeroAllocSerializer::new(SERIALIZER_POOL_SIZE, SERIALIZER_BUFFER_SIZE)));
 
-/// Compile-time optimized event processor (zero runtime cost).
-static COMPILE_TIME_PROCESSOR: CompileTimeOptimizedEventProcessor =
-    CompileTimeOptimizedEventProcessor::new();
-
 /// Base64 encoder.
 pub struct Base64Encoder;
 
 impl Base64Encoder {
     #[inline(always)]
     pub fn encode(data: &[u8]) -> String {
-        // Use compile-time optimized hash for fast routing
-        let _route = if !data.is_empty() {
-            COMPILE_TIME_PROCESSOR.route_event_zero_cost(data[0])
-        } else {
-            0
-        };
-
-        // Use SIMD-accelerated Base64 encoding
         SIMDSerializer::encode_base64_simd(data)
     }
 
@@ -105,12 +131,143 @@ impl Base64Encoder {
         event_type: &str,
     ) -> Result<String> {
         let serialized = SERIALIZER.serialize_zero_alloc(value, event_type)?;
-        let encoded = STANDARD.encode(&serialized);
+        let encoded = encode_base64(&serialized);
         SERIALIZER.return_buffer(serialized);
         Ok(encoded)
     }
 }
 
+/// Wire forms of one signed transaction. Every provider submitting the same transaction (same
+/// signature) shares one bincode serialization, one base64 and one base58 encoding.
+/// 同一笔交易发往多个通道时只序列化、编码一次。
+pub struct EncodedTransaction {
+    signature: Signature,
+    bytes: Vec<u8>,
+    base64: OnceCell<String>,
+    base58: OnceCell<String>,
+}
+
+impl EncodedTransaction {
+    #[inline]
+    pub fn signature(&self) -> Signature {
+        self.signature
+    }
+
+    /// Bincode wire bytes.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline]
+    pub fn base64(&self) -> &str {
+        self.base64.get_or_init(|| encode_base64(&self.bytes))
+    }
+
+    #[inline]
+    pub fn base58(&self) -> &str {
+        self.base58.get_or_init(|| bs58::encode(&self.bytes).into_string())
+    }
+
+    /// Base64 or base58 form; other encodings are not wire formats.
+    #[inline]
+    pub fn encoded(&self, encoding: UiTransactionEncoding) -> Result<&str> {
+        match encoding {
+            UiTransactionEncoding::Base58 => Ok(self.base58()),
+            UiTransactionEncoding::Base64 => Ok(self.base64()),
+            _ => Err(anyhow::anyhow!("Unsupported encoding")),
+        }
+    }
+}
+
+struct EncodedSlot {
+    encoded: Arc<EncodedTransaction>,
+    last_used: AtomicU64,
+}
+
+static ENCODED_CACHE: Lazy<DashMap<Signature, EncodedSlot>> =
+    Lazy::new(|| DashMap::with_capacity(ENCODED_CACHE_SIZE));
+
+/// Monotonic use counter ordering [`ENCODED_CACHE`] entries for eviction.
+static ENCODE_TICK: AtomicU64 = AtomicU64::new(0);
+
+/// Signed transactions served from / added to [`ENCODED_CACHE`].
+pub(crate) static ENCODE_HITS: AtomicU64 = AtomicU64::new(0);
+pub(crate) static ENCODE_MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// Base64 into an exactly sized String (single allocation, no intermediate buffer).
+#[inline(always)]
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(base64::encoded_len(bytes.len(), true).unwrap_or(0));
+    STANDARD.encode_string(bytes, &mut out);
+    out
+}
+
+/// Serialize `transaction` once and reuse the result for the rest of its lane: the signing
+/// thread or latency budget encodes it, then the provider send and the raw transaction capture
+/// read the same bytes. Each lane signs its own transaction (tip, CU price), so entries are not
+/// shared across lanes. Unsigned transactions (default signature) are never cached.
+pub fn encode_transaction_shared(
+    transaction: &impl SerializableTransaction,
+) -> Result<Arc<EncodedTransaction>> {
+    let signature = *transaction.get_signature();
+    let shareable = signature != Signature::default();
+    if shareable {
+        if let Some(slot) = ENCODED_CACHE.get(&signature) {
+            slot.last_used.store(ENCODE_TICK.fetch_add(1, Ordering::Relaxed), Ordering::Relaxed);
+            ENCODE_HITS.fetch_add(1, Ordering::Relaxed);
+            return Ok(slot.encoded.clone());
+        }
+        ENCODE_MISSES.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let mut bytes = Vec::with_capacity(TX_BYTES_CAPACITY);
+    bincode::serialize_into(&mut bytes, transaction)?;
+    let encoded = Arc::new(EncodedTransaction {
+        signature,
+        bytes,
+        base64: OnceCell::new(),
+        base58: OnceCell::new(),
+    });
+    if shareable {
+        // Concurrent first encodes of the same transaction may both insert; the bytes are equal.
+        let entry = ENCODED_CACHE
+            .entry(signature)
+            .or_insert_with(|| EncodedSlot {
+                encoded: encoded.clone(),
+                last_used: AtomicU64::new(ENCODE_TICK.fetch_add(1, Ordering::Relaxed)),
+            })
+            .encoded
+            .clone();
+        if ENCODED_CACHE.len() > ENCODED_CACHE_SIZE {
+            prune_encoded(&ENCODED_CACHE, ENCODED_CACHE_SIZE);
+        }
+        return Ok(entry);
+    }
+    Ok(encoded)
+}
+
+/// Evict the least recently used encodings once `cache` exceeds `max_size` (1/4 at a time so a
+/// full cache does not prune on every insert).
+#[cold]
+fn prune_encoded(cache: &DashMap<Signature, EncodedSlot>, max_size: usize) {
+    let len = cache.len();
+    if len <= max_size {
+        return;
+    }
+    let remove_count = (len - max_size).max(max_size / 4).min(len);
+    let mut entries: Vec<(u64, Signature)> = cache
+        .iter()
+        .map(|entry| (entry.value().last_used.load(Ordering::Relaxed), *entry.key()))
+        .collect();
+    if remove_count < entries.len() {
+        entries.select_nth_unstable_by_key(remove_count, |(tick, _)| *tick);
+    }
+    for (_, key) in entries.into_iter().take(remove_count) {
+        cache.remove(&key);
+    }
+}
+
 /// Guard that returns the serialization buffer to the pool on drop.
 pub struct PooledTxBufGuard(pub Vec<u8>);
 
@@ -134,9 +291,11 @@ impl Drop for PooledTxBufGuard {
 pub fn serialize_transaction_bincode_sync(
     transaction: &impl SerializableTransaction,
 ) -> Result<(PooledTxBufGuard, Signature)> {
-    let signature = transaction.get_signature();
-    let serialized_tx = SERIALIZER.serialize_zero_alloc(transaction, "transaction")?;
-    Ok((PooledTxBufGuard(serialized_tx), *signature))
+    let encoded = encode_transaction_shared(transaction)?;
+    let mut buffer = SERIALIZER.take_buffer();
+    buffer.clear();
+    buffer.extend_from_slice(encoded.bytes());
+    Ok((PooledTxBufGuard(buffer), encoded.signature()))
 }
 
 /// Return a buffer to the pool (for manual use when not using `PooledTxBufGuard`).
@@ -144,21 +303,14 @@ pub fn return_serialization_buffer(buffer: Vec<u8>) {
     SERIALIZER.return_buffer(buffer);
 }
 
-/// Sync serialize + encode using buffer pool; use in hot path to reduce allocs.
-/// Base64 path uses SIMD-accelerated encoding.
+/// Sync serialize + encode for the hot path; the serialization and encoding are shared with
+/// every other provider submitting the same transaction (see [`encode_transaction_shared`]).
 pub fn serialize_transaction_sync(
     transaction: &impl SerializableTransaction,
     encoding: UiTransactionEncoding,
 ) -> Result<(String, Signature)> {
-    let signature = transaction.get_signature();
-    let serialized_tx = SERIALIZER.serialize_zero_alloc(transaction, "transaction")?;
-    let serialized = match encoding {
-        UiTransactionEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
-        UiTransactionEncoding::Base64 => SIMDSerializer::encode_base64_simd(&serialized_tx),
-        _ => return Err(anyhow::anyhow!("Unsupported encoding")),
-    };
-    SERIALIZER.return_buffer(serialized_tx);
-    Ok((serialized, *signature))
+    let encoded = encode_transaction_shared(transaction)?;
+    Ok((encoded.encoded(encoding)?.to_owned(), encoded.signature()))
 }
 
 /// Serialize a transaction (async; no I/O, kept for API compatibility).
@@ -166,63 +318,26 @@ pub async fn serialize_transaction(
     transaction: &impl SerializableTransaction,
     encoding: UiTransactionEncoding,
 ) -> Result<(String, Signature)> {
-    let signature = transaction.get_signature();
-
-    // Use zero-allocation serialization
-    let serialized_tx = SERIALIZER.serialize_zero_alloc(transaction, "transaction")?;
-
-    let serialized = match encoding {
-        UiTransactionEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
-        UiTransactionEncoding::Base64 => SIMDSerializer::encode_base64_simd(&serialized_tx),
-        _ => return Err(anyhow::anyhow!("Unsupported encoding")),
-    };
-
-    // Return buffer to pool immediately
-    SERIALIZER.return_buffer(serialized_tx);
-
-    Ok((serialized, *signature))
+    serialize_transaction_sync(transaction, encoding)
 }
 
-/// Sync batch serialize + encode using buffer pool.
+/// Sync batch serialize + encode, shared per transaction like [`serialize_transaction_sync`].
 pub fn serialize_transactions_batch_sync(
     transactions: &[impl SerializableTransaction],
     encoding: UiTransactionEncoding,
 ) -> Result<Vec<String>> {
-    let mut results = Vec::with_capacity(transactions.len());
-    for tx in transactions {
-        let serialized_tx = SERIALIZER.serialize_zero_alloc(tx, "transaction")?;
-        let encoded = match encoding {
-            UiTransactionEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
-            UiTransactionEncoding::Base64 => SIMDSerializer::encode_base64_simd(&serialized_tx),
-            _ => return Err(anyhow::anyhow!("Unsupported encoding")),
-        };
-        SERIALIZER.return_buffer(serialized_tx);
-        results.push(encoded);
-    }
-    Ok(results)
+    transactions
+        .iter()
+        .map(|tx| Ok(encode_transaction_shared(tx)?.encoded(encoding)?.to_owned()))
+        .collect()
 }
 
-/// Batch transaction serialization.
+/// Batch transaction serialization (async; no I/O, kept for API compatibility).
 pub async fn serialize_transactions_batch(
     transactions: &[impl SerializableTransaction],
     encoding: UiTransactionEncoding,
 ) -> Result<Vec<String>> {
-    let mut results = Vec::with_capacity(transactions.len());
-
-    for tx in transactions {
-        let serialized_tx = SERIALIZER.serialize_zero_alloc(tx, "transaction")?;
-
-        let encoded = match encoding {
-            UiTransactionEncoding::Base58 => bs58::encode(&serialized_tx).into_string(),
-            UiTransactionEncoding::Base64 => SIMDSerializer::encode_base64_simd(&serialized_tx),
-            _ => return Err(anyhow::anyhow!("Unsupported encoding")),
-        };
-
-        SERIALIZER.return_buffer(serialized_tx);
-        results.push(encoded);
-    }
-
-    Ok(results)
+    serialize_transactions_batch_sync(transactions, encoding)
 }
 
 /// Get serializer statistics.
@@ -230,6 +345,19 @@ pub fn get_serializer_stats() -> (usize, usize) {
     SERIALIZER.get_pool_stats()
 }
 
+/// Global serializer pool `(hits, misses)`.
+pub(crate) fn serializer_pool_counters() -> (u64, u64) {
+    SERIALIZER.pool_counters()
+}
+
+pub(crate) fn reset_serializer_pool_counters() {
+    SERIALIZER.reset_pool_counters();
+}
+
+pub(crate) fn encoded_cache_len() -> usize {
+    ENCODED_CACHE.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,6 +374,61 @@ mod tests {
         assert_eq!(&decoded[..data.len()], data);
     }
 
+    #[test]
+    fn shared_encoding_is_reused_per_signature() {
+        use solana_hash::Hash;
+        use solana_message::Message;
+        use solana_sdk::transaction::{Transaction, VersionedTransaction};
+        use solana_sdk::{signature::Keypair, signer::Signer};
+
+        let payer = Keypair::new();
+        let message = Message::new(&[], Some(&payer.pubkey()));
+        let tx =
+            VersionedTransaction::from(Transaction::new(&[&payer], message, Hash::new_unique()));
+
+        let first = encode_transaction_shared(&tx).unwrap();
+        let hits = ENCODE_HITS.load(Ordering::Relaxed);
+        let second = encode_transaction_shared(&tx).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(ENCODE_HITS.load(Ordering::Relaxed) > hits);
+        assert_eq!(first.bytes(), bincode::serialize(&tx).unwrap().as_slice());
+        assert_eq!(STANDARD.decode(first.base64()).unwrap(), first.bytes());
+        assert_eq!(
+            serialize_transaction_sync(&tx, UiTransactionEncoding::Base64).unwrap().0,
+            first.base64()
+        );
+
+        let unsigned = VersionedTransaction::default();
+        let a = encode_transaction_shared(&unsigned).unwrap();
+        let b = encode_transaction_shared(&unsigned).unwrap();
+        assert!(!Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn prune_evicts_least_recently_used_encodings() {
+        let cache = DashMap::new();
+        let signature = |i: u8| Signature::from([i; 64]);
+        for i in 0..8u8 {
+            let encoded = Arc::new(EncodedTransaction {
+                signature: signature(i),
+                bytes: vec![i],
+                base64: OnceCell::new(),
+                base58: OnceCell::new(),
+            });
+            cache
+                .insert(signature(i), EncodedSlot { encoded, last_used: AtomicU64::new(i as u64) });
+        }
+        // The oldest insert was read most recently.
+        cache.get(&signature(0)).unwrap().last_used.store(100, Ordering::Relaxed);
+
+        prune_encoded(&cache, 4);
+
+        assert_eq!(cache.len(), 4);
+        for kept in [0, 5, 6, 7] {
+            assert!(cache.contains_key(&signature(kept)));
+        }
+    }
+
     #[test]
     fn test_serializer_stats() {
         let (available, capacity) = get_serializer_stats();
@@ -274,6 +457,10 @@ mod tests {
 
         let (available_after, _) = serializer.get_pool_stats();
         assert_eq!(available_after, 1);
+
+        let buf = serializer.serialize_zero_alloc(&"again", "test").unwrap();
+        serializer.return_buffer(buf);
+        assert_eq!(serializer.pool_counters(), (1, 1));
     }
 
     fn legacy_eager_zero_fill_serializer(
@@ -286,7 +473,7 @@ mod tests {
             buffer.resize(buffer_size, 0);
             let _ = pool.push(buffer);
         }
-        ZeroAllocSerializer { buffer_pool: Arc::new(pool), buffer_size }
+        ZeroAllocSerializer::from_pool(pool, buffer_size)
     }
 
     /// Manual perf test: compares old eager cold-start behavior to current bounded prewarm.
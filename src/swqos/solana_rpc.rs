@@ -1,14 +1,14 @@
 use std::{sync::Arc, time::Instant};
 
-use solana_client::rpc_config::RpcSendTransactionConfig;
-use solana_commitment_config::CommitmentLevel;
 use solana_sdk::transaction::VersionedTransaction;
-use solana_transaction_status::UiTransactionEncoding;
 
 use crate::swqos::SwqosClientTrait;
 use crate::{
     common::{sdk_log, SolanaRpcClient},
-    swqos::{common::poll_transaction_confirmation, SwqosType, TradeType},
+    swqos::{
+        common::{poll_transaction_confirmation, RPC_SEND_OPTIONS},
+        SwqosType, TradeType,
+    },
 };
 use anyhow::Result;
 
@@ -26,18 +26,11 @@ impl SwqosClientTrait for SolRpcClient {
         wait_confirmation: bool,
     ) -> Result<()> {
         let submit_start = Instant::now();
+        // Trades set their options around the submit; other sends use the defaults.
+        let options = RPC_SEND_OPTIONS.try_with(|options| *options).unwrap_or_default();
         let signature = self
             .rpc_client
-            .send_transaction_with_config(
-                transaction,
-                RpcSendTransactionConfig {
-                    skip_preflight: true,
-                    preflight_commitment: Some(CommitmentLevel::Processed),
-                    encoding: Some(UiTransactionEncoding::Base64),
-                    max_retries: Some(3),
-                    min_context_slot: Some(0),
-                },
-            )
+            .send_transaction_with_config(transaction, options.send_config())
             .await?;
 
         sdk_log::log_swqos_submitted("Default", trade_type, submit_start.elapsed());
@@ -1,4 +1,6 @@
-use crate::swqos::common::{default_http_client_builder, poll_transaction_confirmation};
+use crate::swqos::common::{
+    build_http_client, default_http_client_builder, poll_transaction_confirmation, HttpClientTuning,
+};
 use bincode;
 use rand::seq::IndexedRandom;
 use reqwest::Client;
@@ -57,9 +59,14 @@ impl SwqosClientTrait for ZeroSlotClient {
 }
 
 impl ZeroSlotClient {
-    pub fn new(rpc_url: String, endpoint: String, auth_token: String) -> Self {
+    pub fn new(
+        rpc_url: String,
+        endpoint: String,
+        auth_token: String,
+        http_tuning: &HttpClientTuning,
+    ) -> Result<Self> {
         let rpc_client = SolanaRpcClient::new(rpc_url);
-        let http_client = default_http_client_builder().build().unwrap();
+        let http_client = build_http_client(default_http_client_builder(), http_tuning)?;
 
         let client = Self {
             rpc_client: Arc::new(rpc_client),
@@ -76,7 +83,7 @@ impl ZeroSlotClient {
             client_clone.start_ping_task().await;
         });
 
-        client
+        Ok(client)
     }
 
     /// Start periodic ping task to keep connections active
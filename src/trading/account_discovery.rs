@@ -0,0 +1,392 @@
+//! Simulation-based account discovery for pools the RPC derivation gets wrong.
+//!
+//! Given a mint and [`DexType`] (or params built elsewhere), [`discover_accounts`] builds a small
+//! probe buy, simulates it with `accounts` returned for every account of the swap instruction and
+//! reads back what the program did with them:
+//! - accounts that do not exist or are owned by an unexpected program;
+//! - Anchor `ConstraintSeeds` / `ConstraintAddress` failures, whose `Left:` / `Right:` logs name
+//!   the account that was passed and the one the program expected. The expected account is
+//!   substituted and the probe re-simulated, a few rounds at most.
+//!
+//! Substitutions found this way are returned as `corrections`; [`AccountOverrideMiddleware`]
+//! applies them to real trades until the derivation is fixed. Nothing is sent; the payer only
+//! needs enough SOL for the probe amount to be simulated.
+//! 模拟发现账户：对未知/边缘池模拟一笔小额买入，根据 Anchor 约束日志纠正账户并校验账户状态。
+
+use crate::common::{GasFeeStrategy, SolanaRpcClient};
+use crate::constants::WSOL_TOKEN_ACCOUNT;
+use crate::swqos::TradeType;
+use crate::trading::core::params::{
+    BonkParams, DexParamEnum, PumpFunParams, PumpSwapParams, SwapParams,
+};
+use crate::trading::factory::{DexType, TradeFactory};
+use crate::trading::middleware::InstructionMiddleware;
+use anyhow::{anyhow, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{
+    RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Quote spent by the probe buy (0.001 SOL).
+pub const PROBE_INPUT_LAMPORTS: u64 = 1_000_000;
+/// Simulations per discovery: the first probe plus re-runs after corrections.
+const MAX_ROUNDS: usize = 3;
+const PROBE_CU_LIMIT: u32 = 400_000;
+
+/// One account of the probe swap instruction and its state after the simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredAccount {
+    pub meta: AccountMeta,
+    /// Owner program after the simulation; `None` when the account does not exist.
+    pub owner: Option<Pubkey>,
+    pub data_len: usize,
+}
+
+/// Outcome of [`discover_accounts`].
+#[derive(Clone)]
+pub struct AccountDiscovery {
+    pub dex: DexType,
+    pub mint: Pubkey,
+    /// Params the probe was built from.
+    pub params: DexParamEnum,
+    /// Accounts of the swap instruction, corrections applied, in instruction order.
+    pub accounts: Vec<DiscoveredAccount>,
+    /// `(passed, expected)` pairs reported by Anchor constraint failures.
+    pub corrections: Vec<(Pubkey, Pubkey)>,
+    /// Error of the last simulation; `None` when the probe swap simulated cleanly.
+    pub simulation_error: Option<String>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl AccountDiscovery {
+    /// The probe swap simulated without error.
+    pub fn verified(&self) -> bool {
+        self.simulation_error.is_none()
+    }
+
+    /// Accounts of the swap that do not exist after the simulation.
+    pub fn missing_accounts(&self) -> Vec<Pubkey> {
+        self.accounts.iter().filter(|a| a.owner.is_none()).map(|a| a.meta.pubkey).collect()
+    }
+
+    /// Middleware substituting [`Self::corrections`] in real trades' protocol instructions.
+    pub fn override_middleware(&self) -> AccountOverrideMiddleware {
+        AccountOverrideMiddleware::new(self.corrections.iter().copied())
+    }
+}
+
+/// Replaces account keys in protocol instructions, e.g. with [`AccountDiscovery::corrections`].
+#[derive(Clone, Default)]
+pub struct AccountOverrideMiddleware {
+    overrides: Arc<HashMap<Pubkey, Pubkey>>,
+}
+
+impl AccountOverrideMiddleware {
+    pub fn new(overrides: impl IntoIterator<Item = (Pubkey, Pubkey)>) -> Self {
+        Self { overrides: Arc::new(overrides.into_iter().collect()) }
+    }
+}
+
+impl InstructionMiddleware for AccountOverrideMiddleware {
+    fn name(&self) -> &'static str {
+        "AccountOverrideMiddleware"
+    }
+
+    fn process_protocol_instructions(
+        &self,
+        mut protocol_instructions: Vec<Instruction>,
+        _dex_type: DexType,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        apply_overrides(&mut protocol_instructions, &self.overrides);
+        Ok(protocol_instructions)
+    }
+
+    fn process_full_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        _dex_type: DexType,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(full_instructions)
+    }
+
+    fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+        Box::new(self.clone())
+    }
+}
+
+fn apply_overrides(instructions: &mut [Instruction], overrides: &HashMap<Pubkey, Pubkey>) {
+    if overrides.is_empty() {
+        return;
+    }
+    for meta in instructions.iter_mut().flat_map(|ix| ix.accounts.iter_mut()) {
+        if let Some(expected) = overrides.get(&meta.pubkey) {
+            meta.pubkey = *expected;
+        }
+    }
+}
+
+/// `(passed, expected)` pairs from Anchor constraint failure logs:
+/// `Program log: Left:` / `<passed>` / `Program log: Right:` / `<expected>`.
+pub fn parse_constraint_mismatches(logs: &[String]) -> Vec<(Pubkey, Pubkey)> {
+    fn key_after(logs: &[String], i: usize) -> Option<Pubkey> {
+        let line = logs.get(i + 1)?;
+        Pubkey::from_str(line.strip_prefix("Program log: ")?.trim()).ok()
+    }
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < logs.len() {
+        if logs[i].trim_end() == "Program log: Left:" {
+            let right = logs.get(i + 2).map(|l| l.trim_end() == "Program log: Right:");
+            if let (Some(passed), Some(true), Some(expected)) =
+                (key_after(logs, i), right, key_after(logs, i + 2))
+            {
+                if passed != expected && !out.contains(&(passed, expected)) {
+                    out.push((passed, expected));
+                }
+                i += 4;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Params for a probe when the caller has none: the SDK's RPC derivation from the mint.
+async fn derive_params(rpc: &SolanaRpcClient, mint: &Pubkey, dex: DexType) -> Result<DexParamEnum> {
+    Ok(match dex {
+        DexType::PumpFun => {
+            DexParamEnum::PumpFun(PumpFunParams::from_mint_by_rpc(rpc, mint).await?)
+        }
+        DexType::PumpSwap => {
+            DexParamEnum::PumpSwap(PumpSwapParams::from_mint_by_rpc(rpc, mint).await?)
+        }
+        DexType::Bonk => match BonkParams::from_mint_by_rpc(rpc, mint, false).await {
+            Ok(params) => DexParamEnum::Bonk(params),
+            Err(_) => DexParamEnum::Bonk(BonkParams::from_mint_by_rpc(rpc, mint, true).await?),
+        },
+        DexType::RaydiumCpmm | DexType::RaydiumAmmV4 | DexType::MeteoraDammV2 => {
+            return Err(anyhow!(
+                "{:?} pools cannot be derived from the mint; use discover_accounts_with_params",
+                dex
+            ))
+        }
+    })
+}
+
+fn probe_swap_params(
+    rpc: &Arc<SolanaRpcClient>,
+    payer: &Arc<Keypair>,
+    mint: &Pubkey,
+    params: DexParamEnum,
+) -> SwapParams {
+    SwapParams {
+        rpc: Some(rpc.clone()),
+        payer: payer.clone(),
+        trade_type: TradeType::Buy,
+        input_mint: WSOL_TOKEN_ACCOUNT,
+        input_token_program: None,
+        output_mint: *mint,
+        output_token_program: None,
+        input_amount: Some(PROBE_INPUT_LAMPORTS),
+        // Only the accounts are under test; accept any fill.
+        slippage_basis_points: Some(9_999),
+        address_lookup_table_accounts: Vec::new(),
+        recent_blockhash: Some(Hash::default()),
+        wait_tx_confirmed: false,
+        confirmation: Default::default(),
+        rpc_send_options: Default::default(),
+        raw_transactions: None,
+        protocol_params: params,
+        open_seed_optimize: false,
+        swqos_clients: Arc::new(Vec::new()),
+        middleware_manager: None,
+        compute_budget_manager: None,
+        durable_nonce: None,
+        with_tip: false,
+        create_input_mint_ata: true,
+        close_input_mint_ata: true,
+        create_output_mint_ata: true,
+        close_output_mint_ata: false,
+        fixed_output_amount: None,
+        min_out_override: None,
+        gas_fee_strategy: GasFeeStrategy::new(),
+        data_size_limit: None,
+        heap_frame_bytes: None,
+        memo: None,
+        simulate: true,
+        log_enabled: false,
+        wait_for_all_submits: false,
+        use_dedicated_sender_threads: false,
+        sender_thread_cores: None,
+        max_sender_concurrency: 0,
+        effective_core_ids: Arc::new(Vec::new()),
+        check_min_tip: false,
+        grpc_recv_us: None,
+        use_exact_sol_amount: Some(true),
+        backrun_target: None,
+        simulation_gate: None,
+        latency_budget: None,
+        signing_pool: None,
+        additional_signers: Vec::new(),
+        fee_payer: None,
+        send_mode: Default::default(),
+        pre_instructions: Vec::new(),
+        post_instructions: Vec::new(),
+        transaction_split: Default::default(),
+        simulation_prelude: Vec::new(),
+        prior_signatures: Vec::new(),
+        wsol_guard: None,
+        order_claim: None,
+    }
+}
+
+/// Discover and verify the accounts of a `dex` swap of `mint`, deriving params by RPC first.
+///
+/// PumpFun, PumpSwap and Bonk pools are found from the mint; other protocols need params, see
+/// [`discover_accounts_with_params`].
+pub async fn discover_accounts(
+    rpc: &Arc<SolanaRpcClient>,
+    payer: &Arc<Keypair>,
+    mint: &Pubkey,
+    dex: DexType,
+) -> Result<AccountDiscovery> {
+    let params = derive_params(rpc, mint, dex).await?;
+    discover_accounts_with_params(rpc, payer, mint, dex, params).await
+}
+
+/// Like [`discover_accounts`], probing with `params` (e.g. built from an event or partially by
+/// hand) instead of deriving them.
+pub async fn discover_accounts_with_params(
+    rpc: &Arc<SolanaRpcClient>,
+    payer: &Arc<Keypair>,
+    mint: &Pubkey,
+    dex: DexType,
+    params: DexParamEnum,
+) -> Result<AccountDiscovery> {
+    let program_id = crate::programs::program_id(dex);
+    let builder = TradeFactory::instruction_builder(dex);
+    let mut instructions = builder
+        .build_buy_instructions(&probe_swap_params(rpc, payer, mint, params.clone()))
+        .await?;
+    let swap_index = instructions
+        .iter()
+        .rposition(|ix| ix.program_id == program_id)
+        .ok_or_else(|| anyhow!("{:?} probe has no swap instruction", dex))?;
+    instructions.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(PROBE_CU_LIMIT));
+    let swap_index = swap_index + 1;
+
+    let mut overrides: HashMap<Pubkey, Pubkey> = HashMap::new();
+    let mut corrections = Vec::new();
+    let mut round = 0;
+    loop {
+        round += 1;
+        let addresses: Vec<String> =
+            instructions[swap_index].accounts.iter().map(|m| m.pubkey.to_string()).collect();
+        let transaction =
+            Transaction::new_unsigned(Message::new(&instructions, Some(&payer.pubkey())));
+        let result = rpc
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::processed()),
+                    encoding: None,
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses,
+                    }),
+                    min_context_slot: None,
+                    inner_instructions: false,
+                },
+            )
+            .await?
+            .value;
+        let logs = result.logs.unwrap_or_default();
+        let simulation_error = result.err.map(|e| format!("{:?}", e));
+        let mismatches: Vec<_> = if simulation_error.is_some() {
+            parse_constraint_mismatches(&logs)
+                .into_iter()
+                .filter(|(passed, _)| !overrides.contains_key(passed))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if mismatches.is_empty() || round == MAX_ROUNDS {
+            let states = result.accounts.unwrap_or_default();
+            let accounts = instructions[swap_index]
+                .accounts
+                .iter()
+                .enumerate()
+                .map(|(i, meta)| {
+                    let state = states.get(i).and_then(|s| s.as_ref());
+                    DiscoveredAccount {
+                        meta: meta.clone(),
+                        owner: state.and_then(|s| Pubkey::from_str(&s.owner).ok()),
+                        data_len: state
+                            .and_then(|s| s.data.decode())
+                            .map(|data| data.len())
+                            .unwrap_or(0),
+                    }
+                })
+                .collect();
+            return Ok(AccountDiscovery {
+                dex,
+                mint: *mint,
+                params,
+                accounts,
+                corrections,
+                simulation_error,
+                logs,
+                units_consumed: result.units_consumed,
+            });
+        }
+        overrides.extend(mismatches.iter().copied());
+        corrections.extend(mismatches);
+        apply_overrides(&mut instructions, &overrides);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_anchor_left_right_pairs() {
+        let (passed, expected) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let logs = vec![
+            "Program log: AnchorError caused by account: creator_vault. Error Code: ConstraintSeeds. Error Number: 2006. Error Message: A seeds constraint was violated.".to_string(),
+            "Program log: Left:".to_string(),
+            format!("Program log: {}", passed),
+            "Program log: Right:".to_string(),
+            format!("Program log: {}", expected),
+            "Program log: Left:".to_string(),
+            "Program log: not-a-key".to_string(),
+        ];
+        assert_eq!(parse_constraint_mismatches(&logs), vec![(passed, expected)]);
+
+        let mut ixs = vec![Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(passed, false)],
+        )];
+        apply_overrides(&mut ixs, &[(passed, expected)].into_iter().collect());
+        assert_eq!(ixs[0].accounts[0].pubkey, expected);
+    }
+}
@@ -0,0 +1,435 @@
+//! Cross-DEX cycle scanner: two- and three-leg arbitrage over pools kept fresh by a
+//! [`ParamsProvider`].
+//!
+//! Every pass reads the latest params of each configured pool, walks every cycle that starts and
+//! ends in [`ScannerConfig::start_mint`] and quotes it leg by leg with the local quote math
+//! (`utils::calc`), pool fees included. A cycle is reported when its output beats the input by
+//! [`ScannerConfig::execution_cost`] (tip plus network fees) and [`ScannerConfig::min_profit`].
+//! Meteora DAMM v2 params carry no reserve snapshot and are skipped.
+//! 跨 DEX 套利扫描：基于本地报价公式评估二/三跳循环，扣除手续费与小费后仍盈利则推送到 channel。
+
+use crate::common::subscription_handle::SubscriptionHandle;
+use crate::constants::{SOL_TOKEN_ACCOUNT, USD1_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use crate::trading::params_provider::ParamsProvider;
+use crate::utils::calc;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// One pool the scanner may route through, keyed like [`ParamsProvider::load`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArbPool {
+    pub dex_type: DexType,
+    /// Provider key: the pool address, or the mint for PumpFun and Bonk.
+    pub key: Pubkey,
+    /// Bonk only: the pool is quoted in USD1 instead of WSOL (Bonk params do not record it).
+    pub usd1_pool: bool,
+}
+
+impl ArbPool {
+    pub fn new(dex_type: DexType, key: Pubkey) -> Self {
+        Self { dex_type, key, usd1_pool: false }
+    }
+
+    /// Mark a Bonk pool as USD1-quoted.
+    pub fn with_usd1_quote(mut self) -> Self {
+        self.usd1_pool = true;
+        self
+    }
+}
+
+/// Scan settings. Amounts are in base units of [`Self::start_mint`].
+#[derive(Debug, Clone)]
+pub struct ScannerConfig {
+    /// Mint every cycle starts and ends in; profit is measured in it. Default WSOL.
+    pub start_mint: Pubkey,
+    /// Input sizes tried on every cycle; the most profitable one is reported.
+    pub input_amounts: Vec<u64>,
+    /// Tip plus network and priority fees of the arb transaction, subtracted from the profit.
+    pub execution_cost: u64,
+    /// Smallest profit, after `execution_cost`, worth reporting.
+    pub min_profit: u64,
+    /// Pause between passes of [`Scanner::spawn`].
+    pub interval: Duration,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            start_mint: WSOL_TOKEN_ACCOUNT,
+            input_amounts: vec![100_000_000, 500_000_000, 1_000_000_000],
+            execution_cost: 0,
+            min_profit: 1,
+            interval: Duration::from_millis(200),
+        }
+    }
+}
+
+/// One swap of a cycle, quoted at the scan's reserves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArbLeg {
+    pub pool: ArbPool,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    /// Expected output after pool fees, before slippage.
+    pub amount_out: u64,
+}
+
+/// A profitable cycle. Read each leg's params from the provider by [`ArbPool::key`] to build it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArbOpportunity {
+    /// Two or three legs; the first takes and the last returns [`ScannerConfig::start_mint`].
+    pub legs: Vec<ArbLeg>,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// `amount_out - amount_in - execution_cost`.
+    pub expected_profit: u64,
+}
+
+/// Evaluates the cycles of a pool set against a [`ParamsProvider`].
+pub struct Scanner {
+    provider: Arc<ParamsProvider>,
+    pools: Vec<ArbPool>,
+    config: ScannerConfig,
+}
+
+/// A pool with the params of the current pass and the two mints it swaps between.
+struct PoolQuote {
+    pool: ArbPool,
+    params: DexParamEnum,
+    mints: (Pubkey, Pubkey),
+}
+
+impl PoolQuote {
+    fn other_mint(&self, mint: &Pubkey) -> Option<Pubkey> {
+        match self.mints {
+            (a, b) if a == *mint => Some(b),
+            (a, b) if b == *mint => Some(a),
+            _ => None,
+        }
+    }
+}
+
+impl Scanner {
+    pub fn new(provider: Arc<ParamsProvider>, pools: Vec<ArbPool>, config: ScannerConfig) -> Self {
+        Self { provider, pools, config }
+    }
+
+    /// One pass over every two- and three-leg cycle, most profitable first. Pools missing from
+    /// the provider are skipped.
+    pub fn scan(&self) -> Vec<ArbOpportunity> {
+        let pools: Vec<PoolQuote> = self
+            .pools
+            .iter()
+            .filter_map(|pool| {
+                let params = self.provider.get(&pool.key)?;
+                let mints = pool_mints(pool, &params)?;
+                Some(PoolQuote { pool: *pool, params, mints })
+            })
+            .collect();
+        let start = self.config.start_mint;
+        let mut found = Vec::new();
+        for (i, first) in pools.iter().enumerate() {
+            let Some(mid) = first.other_mint(&start) else { continue };
+            for (j, second) in pools.iter().enumerate() {
+                if j == i {
+                    continue;
+                }
+                let Some(next) = second.other_mint(&mid) else { continue };
+                if next == start {
+                    found.extend(self.best_size(&[(first, start), (second, mid)]));
+                    continue;
+                }
+                for (k, third) in pools.iter().enumerate() {
+                    if k == i || k == j || third.other_mint(&next) != Some(start) {
+                        continue;
+                    }
+                    found.extend(self.best_size(&[(first, start), (second, mid), (third, next)]));
+                }
+            }
+        }
+        found.sort_by(|a, b| b.expected_profit.cmp(&a.expected_profit));
+        found
+    }
+
+    /// Run [`Self::scan`] every [`ScannerConfig::interval`] and send each new opportunity.
+    ///
+    /// An opportunity is sent again only after a pass without it. Drop the receiver or call
+    /// [`SubscriptionHandle::shutdown`] to stop.
+    pub fn spawn(self) -> (mpsc::UnboundedReceiver<ArbOpportunity>, SubscriptionHandle) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.config.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            let mut last_pass = HashSet::new();
+            loop {
+                ticker.tick().await;
+                let found = self.scan();
+                for opportunity in &found {
+                    if !last_pass.contains(opportunity) && tx.send(opportunity.clone()).is_err() {
+                        return;
+                    }
+                }
+                if tx.is_closed() {
+                    return;
+                }
+                last_pass = found.into_iter().collect();
+            }
+        });
+        (rx, SubscriptionHandle { task, unsub_fn: Box::new(|| {}) })
+    }
+
+    /// Quote a cycle at every configured size and keep the most profitable one.
+    fn best_size(&self, cycle: &[(&PoolQuote, Pubkey)]) -> Option<ArbOpportunity> {
+        self.config
+            .input_amounts
+            .iter()
+            .filter_map(|&amount_in| self.quote_cycle(cycle, amount_in))
+            .max_by_key(|opportunity| opportunity.expected_profit)
+    }
+
+    fn quote_cycle(
+        &self,
+        cycle: &[(&PoolQuote, Pubkey)],
+        amount_in: u64,
+    ) -> Option<ArbOpportunity> {
+        let mut legs = Vec::with_capacity(cycle.len());
+        let mut amount = amount_in;
+        for (pool, input_mint) in cycle {
+            let output_mint = pool.other_mint(input_mint)?;
+            let amount_out = quote_leg(pool, input_mint, amount)?;
+            legs.push(ArbLeg {
+                pool: pool.pool,
+                input_mint: *input_mint,
+                output_mint,
+                amount_in: amount,
+                amount_out,
+            });
+            amount = amount_out;
+        }
+        let expected_profit =
+            amount.checked_sub(amount_in)?.checked_sub(self.config.execution_cost)?;
+        (expected_profit >= self.config.min_profit).then_some(ArbOpportunity {
+            legs,
+            amount_in,
+            amount_out: amount,
+            expected_profit,
+        })
+    }
+}
+
+/// The two mints `params` swaps between, `None` for pools the scanner cannot quote.
+fn pool_mints(pool: &ArbPool, params: &DexParamEnum) -> Option<(Pubkey, Pubkey)> {
+    match (pool.dex_type, params) {
+        (DexType::PumpFun, DexParamEnum::PumpFun(p)) => {
+            let quote = if p.quote_mint == Pubkey::default() || p.quote_mint == SOL_TOKEN_ACCOUNT {
+                WSOL_TOKEN_ACCOUNT
+            } else {
+                p.quote_mint
+            };
+            Some((pool.key, quote))
+        }
+        (DexType::PumpSwap, DexParamEnum::PumpSwap(p)) => Some((p.base_mint, p.quote_mint)),
+        (DexType::Bonk, DexParamEnum::Bonk(_)) => {
+            Some((pool.key, if pool.usd1_pool { USD1_TOKEN_ACCOUNT } else { WSOL_TOKEN_ACCOUNT }))
+        }
+        (DexType::RaydiumCpmm, DexParamEnum::RaydiumCpmm(p)) => Some((p.base_mint, p.quote_mint)),
+        (DexType::RaydiumAmmV4, DexParamEnum::RaydiumAmmV4(p)) => Some((p.coin_mint, p.pc_mint)),
+        _ => None,
+    }
+}
+
+/// Output of swapping `amount_in` of `input_mint` through the pool, after pool fees and before
+/// slippage; `None` when the pool cannot fill it.
+fn quote_leg(pool: &PoolQuote, input_mint: &Pubkey, amount_in: u64) -> Option<u64> {
+    let base_in = *input_mint == pool.mints.0;
+    let out = match &pool.params {
+        DexParamEnum::PumpFun(p) => {
+            let curve = &p.bonding_curve;
+            if curve.complete {
+                return None;
+            }
+            let creator = p.effective_creator_for_trade();
+            if base_in {
+                calc::pumpfun::get_sell_sol_amount_from_token_amount(
+                    curve.virtual_token_reserves as u128,
+                    curve.virtual_sol_reserves as u128,
+                    creator,
+                    amount_in,
+                )
+            } else {
+                calc::pumpfun::get_buy_token_amount_from_sol_amount(
+                    curve.virtual_token_reserves as u128,
+                    curve.virtual_sol_reserves as u128,
+                    curve.real_token_reserves as u128,
+                    creator,
+                    amount_in,
+                )
+            }
+        }
+        DexParamEnum::PumpSwap(p) => {
+            let fees = p.quoted_fee_basis_points();
+            let (base, quote, virtual_quote) =
+                (p.pool_base_token_reserves, p.pool_quote_token_reserves, p.virtual_quote_reserves);
+            if base_in {
+                calc::pumpswap::sell_base_input_internal_with_fees(
+                    amount_in,
+                    0,
+                    base,
+                    quote,
+                    virtual_quote,
+                    &fees,
+                )
+                .ok()?
+                .ui_quote
+            } else {
+                calc::pumpswap::buy_quote_input_internal_with_fees(
+                    amount_in,
+                    0,
+                    base,
+                    quote,
+                    virtual_quote,
+                    &fees,
+                )
+                .ok()?
+                .base
+            }
+        }
+        DexParamEnum::Bonk(p) => {
+            let (vb, vq, rb, rq) = (p.virtual_base, p.virtual_quote, p.real_base, p.real_quote);
+            if base_in {
+                calc::bonk::get_sell_sol_amount_from_token_amount(amount_in, vb, vq, rb, rq, 0)
+            } else {
+                calc::bonk::get_buy_token_amount_from_sol_amount(amount_in, vb, vq, rb, rq, 0)
+            }
+        }
+        DexParamEnum::RaydiumCpmm(p) => {
+            let quote = calc::raydium_cpmm::compute_swap_amount(
+                p.base_reserve,
+                p.quote_reserve,
+                base_in,
+                amount_in,
+                0,
+            );
+            if !quote.all_trade {
+                return None;
+            }
+            quote.amount_out
+        }
+        DexParamEnum::RaydiumAmmV4(p) => {
+            let quote = calc::raydium_amm_v4::compute_swap_amount(
+                p.coin_reserve,
+                p.pc_reserve,
+                base_in,
+                amount_in,
+                0,
+            );
+            if !quote.all_trade {
+                return None;
+            }
+            quote.amount_out
+        }
+        DexParamEnum::MeteoraDammV2(_) => return None,
+    };
+    (out > 0).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::core::params::{RaydiumAmmV4Params, RaydiumCpmmParams};
+
+    fn amm(provider: &ParamsProvider, coin: Pubkey, pc: Pubkey, reserves: (u64, u64)) -> ArbPool {
+        let key = Pubkey::new_unique();
+        let params = RaydiumAmmV4Params::new(
+            key,
+            coin,
+            pc,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            reserves.0,
+            reserves.1,
+        );
+        provider.insert(key, DexParamEnum::RaydiumAmmV4(params));
+        ArbPool::new(DexType::RaydiumAmmV4, key)
+    }
+
+    fn cpmm(
+        provider: &ParamsProvider,
+        base: Pubkey,
+        quote: Pubkey,
+        reserves: (u64, u64),
+    ) -> ArbPool {
+        let key = Pubkey::new_unique();
+        let params = RaydiumCpmmParams::from_trade(
+            key,
+            Pubkey::new_unique(),
+            base,
+            quote,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            reserves.0,
+            reserves.1,
+        );
+        provider.insert(key, DexParamEnum::RaydiumCpmm(params));
+        ArbPool::new(DexType::RaydiumCpmm, key)
+    }
+
+    fn scanner(provider: Arc<ParamsProvider>, pools: Vec<ArbPool>, cost: u64) -> Scanner {
+        let config = ScannerConfig {
+            input_amounts: vec![1_000_000, 10_000_000, 100_000_000],
+            execution_cost: cost,
+            ..Default::default()
+        };
+        Scanner::new(provider, pools, config)
+    }
+
+    #[test]
+    fn two_leg_cycle_across_mispriced_pools() {
+        let provider = Arc::new(ParamsProvider::new());
+        let token = Pubkey::new_unique();
+        // The token is 10% cheaper on the AMM v4 pool than on the CPMM pool.
+        let cheap = amm(&provider, token, WSOL_TOKEN_ACCOUNT, (1_100_000_000_000, 100_000_000_000));
+        let dear = cpmm(&provider, WSOL_TOKEN_ACCOUNT, token, (100_000_000_000, 1_000_000_000_000));
+
+        let found = scanner(provider.clone(), vec![cheap, dear], 5_000).scan();
+        let best = &found[0];
+        assert_eq!(best.legs.iter().map(|l| l.pool).collect::<Vec<_>>(), vec![cheap, dear]);
+        assert_eq!(
+            (best.legs[0].input_mint, best.legs[0].output_mint),
+            (WSOL_TOKEN_ACCOUNT, token)
+        );
+        assert_eq!(best.legs[1].amount_in, best.legs[0].amount_out);
+        assert_eq!(best.expected_profit, best.amount_out - best.amount_in - 5_000);
+        // The reverse direction loses the spread twice.
+        assert!(found.iter().all(|o| o.legs[0].pool == cheap));
+
+        assert!(scanner(provider, vec![cheap, dear], u64::MAX / 2).scan().is_empty());
+    }
+
+    #[test]
+    fn three_leg_cycle_and_balanced_pools() {
+        let provider = Arc::new(ParamsProvider::new());
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let r = 1_000_000_000_000;
+        let sol_a = amm(&provider, a, WSOL_TOKEN_ACCOUNT, (r, r));
+        let sol_b = cpmm(&provider, WSOL_TOKEN_ACCOUNT, b, (r, r));
+        let balanced = amm(&provider, a, b, (r, r));
+        assert!(scanner(provider.clone(), vec![sol_a, sol_b, balanced], 0).scan().is_empty());
+
+        // `a` buys twice as much `b` here as through SOL.
+        let skewed = amm(&provider, a, b, (r, 2 * r));
+        let found = scanner(provider, vec![sol_a, sol_b, skewed], 0).scan();
+        assert_eq!(found.len(), 1);
+        let legs: Vec<_> = found[0].legs.iter().map(|l| (l.pool, l.output_mint)).collect();
+        assert_eq!(legs, vec![(sol_a, a), (skewed, b), (sol_b, WSOL_TOKEN_ACCOUNT)]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::trading::factory::DexType;
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use smallvec::SmallVec;
@@ -35,6 +36,75 @@ where
     }
 }
 
+/// What the transaction builder asks a [`ComputeBudgetManager`] for. A zero `unit_price` /
+/// `unit_limit` means the instruction must not be emitted: either the gas fee strategy set none or
+/// the caller already put one in the business instructions.
+#[derive(Debug, Clone, Copy)]
+pub struct ComputeBudgetRequest {
+    pub unit_price: u64,
+    pub unit_limit: u32,
+    /// Protocol of the trade; `None` for transactions that are not a DEX trade (utility sends).
+    pub dex_type: Option<DexType>,
+    pub is_buy: bool,
+}
+
+/// Pluggable compute budget policy, called once per built transaction (per SWQOS lane) on the
+/// send hot path. Implementations must not block; read oracle prices from memory.
+///
+/// 可替换的计算预算策略，例如从自己的费用预言机读取 CU price。
+pub trait ComputeBudgetManager: Send + Sync {
+    /// Append the compute budget instructions for `request` to `instructions`.
+    fn extend_instructions(
+        &self,
+        instructions: &mut Vec<Instruction>,
+        request: &ComputeBudgetRequest,
+    );
+}
+
+/// Emits the requested price and limit as-is, from the shared instruction cache.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultComputeBudgetManager;
+
+impl ComputeBudgetManager for DefaultComputeBudgetManager {
+    #[inline(always)]
+    fn extend_instructions(
+        &self,
+        instructions: &mut Vec<Instruction>,
+        request: &ComputeBudgetRequest,
+    ) {
+        extend_compute_budget_instructions(instructions, request.unit_price, request.unit_limit);
+    }
+}
+
+/// Wraps a manager (the default one when `None`) and never emits a CU price, only the limit. Used
+/// by [`SendMode::JitoTipOnly`](crate::trading::fee_sponsor::SendMode::JitoTipOnly): Jito orders
+/// bundles by tip, so a priority fee on top is wasted.
+#[derive(Clone, Default)]
+pub struct TipOnlyComputeBudgetManager {
+    inner: Option<Arc<dyn ComputeBudgetManager>>,
+}
+
+impl TipOnlyComputeBudgetManager {
+    pub fn new(inner: Option<Arc<dyn ComputeBudgetManager>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl ComputeBudgetManager for TipOnlyComputeBudgetManager {
+    #[inline]
+    fn extend_instructions(
+        &self,
+        instructions: &mut Vec<Instruction>,
+        request: &ComputeBudgetRequest,
+    ) {
+        let request = ComputeBudgetRequest { unit_price: 0, ..*request };
+        match &self.inner {
+            Some(inner) => inner.extend_instructions(instructions, &request),
+            None => DefaultComputeBudgetManager.extend_instructions(instructions, &request),
+        }
+    }
+}
+
 /// Extend `instructions` with compute budget instructions; on cache hit extends from cached Arc (no SmallVec clone).
 #[inline(always)]
 pub fn extend_compute_budget_instructions(
@@ -62,6 +132,51 @@ pub fn extend_compute_budget_instructions(
     prune_cache(&COMPUTE_BUDGET_CACHE, MAX_COMPUTE_BUDGET_CACHE_SIZE);
 }
 
+/// `SetLoadedAccountsDataSizeLimit` instruction tag.
+const SET_LOADED_ACCOUNTS_DATA_SIZE_LIMIT_TAG: u8 = 4;
+
+/// Append a `SetLoadedAccountsDataSizeLimit(bytes)` unless `instructions` already carries one.
+/// Appended rather than prepended so absolute instruction indices (flash-loan borrow/repay) stay valid.
+#[inline]
+pub fn append_data_size_limit_instruction(instructions: &mut Vec<Instruction>, bytes: u32) {
+    if bytes == 0 {
+        return;
+    }
+    let present = instructions.iter().any(|ix| {
+        ix.program_id == crate::constants::COMPUTE_BUDGET_PROGRAM
+            && ix.data.first() == Some(&SET_LOADED_ACCOUNTS_DATA_SIZE_LIMIT_TAG)
+    });
+    if !present {
+        instructions.push(ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(bytes));
+    }
+}
+
+/// `RequestHeapFrame` instruction tag.
+const REQUEST_HEAP_FRAME_TAG: u8 = 1;
+/// Heap every transaction gets without a `RequestHeapFrame`.
+pub const MIN_HEAP_FRAME_BYTES: u32 = 32 * 1024;
+/// Largest heap the runtime grants.
+pub const MAX_HEAP_FRAME_BYTES: u32 = 256 * 1024;
+
+/// Append a `RequestHeapFrame(bytes)` unless `instructions` already carries one. `bytes` is
+/// rounded up to a multiple of 1 KiB and clamped to
+/// [`MIN_HEAP_FRAME_BYTES`]..=[`MAX_HEAP_FRAME_BYTES`], the sizes the runtime accepts; the
+/// default heap (or less) adds nothing.
+#[inline]
+pub fn append_heap_frame_instruction(instructions: &mut Vec<Instruction>, bytes: u32) {
+    let bytes = bytes.div_ceil(1024).saturating_mul(1024).min(MAX_HEAP_FRAME_BYTES);
+    if bytes <= MIN_HEAP_FRAME_BYTES {
+        return;
+    }
+    let present = instructions.iter().any(|ix| {
+        ix.program_id == crate::constants::COMPUTE_BUDGET_PROGRAM
+            && ix.data.first() == Some(&REQUEST_HEAP_FRAME_TAG)
+    });
+    if !present {
+        instructions.push(ComputeBudgetInstruction::request_heap_frame(bytes));
+    }
+}
+
 /// Returns compute budget instructions (allocates on cache hit; prefer `extend_compute_budget_instructions` on hot path).
 #[inline(always)]
 pub fn compute_budget_instructions(unit_price: u64, unit_limit: u32) -> SmallVec<[Instruction; 2]> {
@@ -0,0 +1,482 @@
+//! Confirmed transaction → normalized fill, for reconciliation and post-trade analytics.
+//! 解析已确认交易为统一的成交记录（Fill）。
+
+use crate::common::types::SolanaRpcClient;
+use crate::constants::{swqos, USD1_TOKEN_ACCOUNT, USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use crate::instruction::utils::{
+    bonk, meteora_damm_v2, pumpfun, pumpswap, raydium_amm_v4, raydium_cpmm,
+};
+use crate::swqos::TradeType;
+use crate::trading::factory::DexType;
+use anyhow::{anyhow, Result};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{UiInstruction, UiTransactionEncoding, UiTransactionTokenBalance};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A single swap decoded from a confirmed transaction.
+///
+/// `mint` amounts are the trader's own balance change. Quote amounts are measured at the pool
+/// (vault or bonding curve), so protocol/creator fees routed to other accounts are not included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fill {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub dex: DexType,
+    pub pool: Pubkey,
+    /// Fee payer of the transaction.
+    pub trader: Pubkey,
+    pub side: TradeType,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// Mint or quote spent, depending on `side`.
+    pub amount_in: u64,
+    /// Mint or quote received, depending on `side`.
+    pub amount_out: u64,
+    /// Base + priority fee paid by the trader, in lamports.
+    pub network_fee: u64,
+    /// Priority part of `network_fee`: the landed transaction's compute-unit price times its
+    /// compute-unit limit, i.e. `network_fee` minus 5,000 lamports per signature.
+    pub priority_fee: u64,
+    /// Lamports paid to known SWQOS tip accounts in this transaction.
+    pub tip: u64,
+}
+
+impl Fill {
+    /// Everything the trader paid on top of the swap amounts: network fee plus tip.
+    #[inline]
+    pub fn total_cost(&self) -> u64 {
+        self.network_fee.saturating_add(self.tip)
+    }
+}
+
+/// Token balance entry from transaction meta, with the owner and mint already decoded.
+#[derive(Debug, Clone)]
+pub struct FillTokenBalance {
+    pub account_index: usize,
+    pub mint: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub amount: u64,
+}
+
+/// Program id and pool account position of each protocol's swap instructions.
+const DEX_PROGRAMS: [(DexType, Pubkey, usize); 6] = [
+    (DexType::PumpFun, pumpfun::accounts::PUMPFUN, 3),
+    (DexType::PumpSwap, pumpswap::accounts::AMM_PROGRAM, 0),
+    (DexType::Bonk, bonk::accounts::BONK, 4),
+    (DexType::RaydiumCpmm, raydium_cpmm::accounts::RAYDIUM_CPMM, 3),
+    (DexType::RaydiumAmmV4, raydium_amm_v4::accounts::RAYDIUM_AMM_V4, 1),
+    (DexType::MeteoraDammV2, meteora_damm_v2::accounts::METEORA_DAMM_V2, 1),
+];
+
+/// Base fee the runtime charges per transaction signature.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Tip accounts of every SWQOS provider the SDK can send through.
+const TIP_ACCOUNT_LISTS: [&[Pubkey]; 17] = [
+    swqos::JITO_TIP_ACCOUNTS,
+    swqos::HELIUS_TIP_ACCOUNTS,
+    swqos::NEXTBLOCK_TIP_ACCOUNTS,
+    swqos::ZEROSLOT_TIP_ACCOUNTS,
+    swqos::NOZOMI_TIP_ACCOUNTS,
+    swqos::BLOX_TIP_ACCOUNTS,
+    swqos::NODE1_TIP_ACCOUNTS,
+    swqos::FLASHBLOCK_TIP_ACCOUNTS,
+    swqos::BLOCKRAZOR_TIP_ACCOUNTS,
+    swqos::ASTRALANE_TIP_ACCOUNTS,
+    swqos::STELLIUM_TIP_ACCOUNTS,
+    swqos::LIGHTSPEED_TIP_ACCOUNTS,
+    swqos::SOYAS_TIP_ACCOUNTS,
+    swqos::SPEEDLANDING_TIP_ACCOUNTS,
+    swqos::SOLAMI_TIP_ACCOUNTS,
+    swqos::LUNARLANDER_TIP_ACCOUNTS,
+    swqos::GLAIVE_TIP_ACCOUNTS,
+];
+
+#[inline]
+fn is_tip_account(key: &Pubkey) -> bool {
+    TIP_ACCOUNT_LISTS.iter().any(|accounts| accounts.contains(key))
+}
+
+#[inline]
+fn is_quote_mint(mint: &Pubkey) -> bool {
+    *mint == WSOL_TOKEN_ACCOUNT || *mint == USDC_TOKEN_ACCOUNT || *mint == USD1_TOKEN_ACCOUNT
+}
+
+/// Fetch `signature` at confirmed commitment and decode the first supported swap in it.
+pub async fn parse_trade_from_signature(
+    rpc: &SolanaRpcClient,
+    signature: &Signature,
+) -> Result<Fill> {
+    let tx = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                max_supported_transaction_version: Some(0),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )
+        .await?;
+    let meta =
+        tx.transaction.meta.ok_or_else(|| anyhow!("transaction {} has no meta", signature))?;
+    if meta.err.is_some() {
+        return Err(anyhow!("transaction {} failed on-chain", signature));
+    }
+    let decoded = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("transaction {} could not be decoded", signature))?;
+
+    let mut account_keys = decoded.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for key in loaded.writable.iter().chain(loaded.readonly.iter()) {
+            account_keys.push(Pubkey::from_str(key)?);
+        }
+    }
+
+    let mut instructions: Vec<(usize, Vec<usize>)> = decoded
+        .message
+        .instructions()
+        .iter()
+        .map(|ix| (ix.program_id_index as usize, ix.accounts.iter().map(|a| *a as usize).collect()))
+        .collect();
+    if let OptionSerializer::Some(inner) = &meta.inner_instructions {
+        for ix in inner.iter().flat_map(|set| set.instructions.iter()) {
+            if let UiInstruction::Compiled(ix) = ix {
+                instructions.push((
+                    ix.program_id_index as usize,
+                    ix.accounts.iter().map(|a| *a as usize).collect(),
+                ));
+            }
+        }
+    }
+
+    parse_fill(
+        *signature,
+        tx.slot,
+        tx.block_time,
+        &account_keys,
+        &instructions,
+        &meta.pre_balances,
+        &meta.post_balances,
+        meta.fee,
+        decoded.message.header().num_required_signatures,
+        &decode_token_balances(&meta.pre_token_balances),
+        &decode_token_balances(&meta.post_token_balances),
+    )
+}
+
+fn decode_token_balances(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+) -> Vec<FillTokenBalance> {
+    let OptionSerializer::Some(balances) = balances else {
+        return Vec::new();
+    };
+    balances
+        .iter()
+        .filter_map(|balance| {
+            Some(FillTokenBalance {
+                account_index: balance.account_index as usize,
+                mint: Pubkey::from_str(&balance.mint).ok()?,
+                owner: match &balance.owner {
+                    OptionSerializer::Some(owner) => Pubkey::from_str(owner).ok(),
+                    _ => None,
+                },
+                amount: balance.ui_token_amount.amount.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Decode a fill from already-fetched transaction data.
+///
+/// `instructions` are `(program_id_index, account_indices)` for top-level and inner
+/// instructions. The first instruction of a supported protocol decides `dex` and `pool`.
+#[allow(clippy::too_many_arguments)]
+pub fn parse_fill(
+    signature: Signature,
+    slot: u64,
+    block_time: Option<i64>,
+    account_keys: &[Pubkey],
+    instructions: &[(usize, Vec<usize>)],
+    pre_balances: &[u64],
+    post_balances: &[u64],
+    fee: u64,
+    signature_count: u8,
+    pre_token_balances: &[FillTokenBalance],
+    post_token_balances: &[FillTokenBalance],
+) -> Result<Fill> {
+    let trader = *account_keys.first().ok_or_else(|| anyhow!("transaction has no accounts"))?;
+    let (dex, pool_position, swap_accounts) = instructions
+        .iter()
+        .find_map(|(program_index, accounts)| {
+            let program = account_keys.get(*program_index)?;
+            DEX_PROGRAMS
+                .iter()
+                .find(|(_, id, _)| id == program)
+                .map(|(dex, _, pool_position)| (*dex, *pool_position, accounts))
+        })
+        .ok_or_else(|| anyhow!("no supported DEX instruction in {}", signature))?;
+    let pool_index = *swap_accounts
+        .get(pool_position)
+        .ok_or_else(|| anyhow!("{:?} instruction is missing the pool account", dex))?;
+    let pool = *account_keys
+        .get(pool_index)
+        .ok_or_else(|| anyhow!("pool account index {} out of range", pool_index))?;
+
+    // Trader's token deltas decide the traded mint and side.
+    let mut trader_deltas: HashMap<Pubkey, i128> = HashMap::new();
+    for (balances, sign) in [(pre_token_balances, -1i128), (post_token_balances, 1i128)] {
+        for balance in balances.iter().filter(|b| b.owner == Some(trader)) {
+            *trader_deltas.entry(balance.mint).or_default() += sign * balance.amount as i128;
+        }
+    }
+    let mut traded: Vec<(Pubkey, i128)> = trader_deltas
+        .iter()
+        .filter(|(mint, delta)| **delta != 0 && !is_quote_mint(mint))
+        .map(|(mint, delta)| (*mint, *delta))
+        .collect();
+    if traded.len() != 1 {
+        return Err(anyhow!(
+            "expected exactly one non-quote token change for the trader, found {}",
+            traded.len()
+        ));
+    }
+    let (mint, mint_delta) = traded.pop().unwrap();
+    let side = if mint_delta > 0 { TradeType::Buy } else { TradeType::Sell };
+
+    // Quote moved at the pool: PumpFun holds SOL on the bonding curve, others use vaults
+    // referenced by the swap instruction.
+    let (quote_mint, quote_amount) = if dex == DexType::PumpFun {
+        let pre = *pre_balances.get(pool_index).unwrap_or(&0) as i128;
+        let post = *post_balances.get(pool_index).unwrap_or(&0) as i128;
+        (WSOL_TOKEN_ACCOUNT, (post - pre).unsigned_abs())
+    } else {
+        let mut vault_deltas: HashMap<(usize, Pubkey), i128> = HashMap::new();
+        for (balances, sign) in [(pre_token_balances, -1i128), (post_token_balances, 1i128)] {
+            for balance in balances.iter().filter(|b| {
+                b.mint != mint
+                    && b.owner != Some(trader)
+                    && swap_accounts.contains(&b.account_index)
+            }) {
+                *vault_deltas.entry((balance.account_index, balance.mint)).or_default() +=
+                    sign * balance.amount as i128;
+            }
+        }
+        vault_deltas
+            .into_iter()
+            .map(|((_, quote_mint), delta)| (quote_mint, delta.unsigned_abs()))
+            .max_by_key(|(_, amount)| *amount)
+            .ok_or_else(|| anyhow!("no quote vault change found for {:?} pool {}", dex, pool))?
+    };
+    let quote_amount = u64::try_from(quote_amount)?;
+    let mint_amount = u64::try_from(mint_delta.unsigned_abs())?;
+    let (amount_in, amount_out) = match side {
+        TradeType::Buy => (quote_amount, mint_amount),
+        _ => (mint_amount, quote_amount),
+    };
+
+    // Lamports that landed on tip accounts; only the balance increase counts.
+    let tip = account_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, key)| is_tip_account(key))
+        .filter_map(|(index, _)| {
+            let pre = *pre_balances.get(index)?;
+            let post = *post_balances.get(index)?;
+            Some(post.saturating_sub(pre))
+        })
+        .fold(0u64, u64::saturating_add);
+    let priority_fee = fee.saturating_sub(LAMPORTS_PER_SIGNATURE * signature_count as u64);
+
+    Ok(Fill {
+        signature,
+        slot,
+        block_time,
+        dex,
+        pool,
+        trader,
+        side,
+        mint,
+        quote_mint,
+        amount_in,
+        amount_out,
+        network_fee: fee,
+        priority_fee,
+        tip,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(account_index: usize, mint: Pubkey, owner: Pubkey, amount: u64) -> FillTokenBalance {
+        FillTokenBalance { account_index, mint, owner: Some(owner), amount }
+    }
+
+    #[test]
+    fn parses_cpmm_buy_from_vault_deltas() {
+        let trader = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        // 0 trader, 1 pool, 2 trader mint ATA, 3 quote vault, 4 base vault, 5 program
+        let keys = [
+            trader,
+            pool,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            raydium_cpmm::accounts::RAYDIUM_CPMM,
+        ];
+        let instructions = vec![(5, vec![0, 0, 0, 1, 0, 2, 3, 4])];
+        let pre = vec![
+            balance(3, WSOL_TOKEN_ACCOUNT, authority, 1_000_000),
+            balance(4, mint, authority, 50_000),
+        ];
+        let post = vec![
+            balance(2, mint, trader, 2_000),
+            balance(3, WSOL_TOKEN_ACCOUNT, authority, 1_100_000),
+            balance(4, mint, authority, 48_000),
+        ];
+
+        let fill = parse_fill(
+            Signature::default(),
+            7,
+            None,
+            &keys,
+            &instructions,
+            &[],
+            &[],
+            5_000,
+            1,
+            &pre,
+            &post,
+        )
+        .unwrap();
+
+        assert_eq!(fill.dex, DexType::RaydiumCpmm);
+        assert_eq!(fill.pool, pool);
+        assert_eq!(fill.trader, trader);
+        assert_eq!(fill.side, TradeType::Buy);
+        assert_eq!(fill.mint, mint);
+        assert_eq!(fill.quote_mint, WSOL_TOKEN_ACCOUNT);
+        assert_eq!(fill.amount_in, 100_000);
+        assert_eq!(fill.amount_out, 2_000);
+        assert_eq!(fill.network_fee, 5_000);
+        assert_eq!(fill.priority_fee, 0);
+        assert_eq!(fill.tip, 0);
+    }
+
+    #[test]
+    fn parses_pumpfun_sell_from_bonding_curve_lamports() {
+        let trader = Pubkey::new_unique();
+        let curve = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        // 0 trader, 1 global, 2 fee recipient, 3 mint, 4 bonding curve, 5 program
+        let keys = [
+            trader,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            mint,
+            curve,
+            pumpfun::accounts::PUMPFUN,
+        ];
+        let instructions = vec![(5, vec![1, 2, 3, 4, 0])];
+        let pre_tokens = vec![balance(6, mint, trader, 10_000)];
+        let post_tokens = vec![balance(6, mint, trader, 0)];
+
+        let fill = parse_fill(
+            Signature::default(),
+            9,
+            Some(1),
+            &keys,
+            &instructions,
+            &[10, 0, 0, 0, 900_000, 1],
+            &[200_000, 0, 0, 0, 700_000, 1],
+            5_000,
+            1,
+            &pre_tokens,
+            &post_tokens,
+        )
+        .unwrap();
+
+        assert_eq!(fill.dex, DexType::PumpFun);
+        assert_eq!(fill.pool, curve);
+        assert_eq!(fill.side, TradeType::Sell);
+        assert_eq!(fill.amount_in, 10_000);
+        assert_eq!(fill.amount_out, 200_000);
+    }
+
+    #[test]
+    fn reports_priority_fee_and_tip() {
+        let trader = Pubkey::new_unique();
+        let curve = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let tip_account = swqos::JITO_TIP_ACCOUNTS[0];
+        // 0 trader, 1 global, 2 fee recipient, 3 mint, 4 bonding curve, 5 program, 6 jito tip
+        let keys = [
+            trader,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            mint,
+            curve,
+            pumpfun::accounts::PUMPFUN,
+            tip_account,
+        ];
+        let instructions = vec![(5, vec![1, 2, 3, 4, 0])];
+        let pre_tokens = vec![balance(7, mint, trader, 0)];
+        let post_tokens = vec![balance(7, mint, trader, 10_000)];
+        // 100_000 micro-lamports/CU × 200_000 CU = 20_000 lamports priority on one signature.
+        let fee = 5_000 + 20_000;
+
+        let fill = parse_fill(
+            Signature::default(),
+            9,
+            Some(1),
+            &keys,
+            &instructions,
+            &[10_000_000, 0, 0, 0, 700_000, 1, 50],
+            &[8_655_000, 0, 0, 0, 900_000, 1, 1_000_050],
+            fee,
+            1,
+            &pre_tokens,
+            &post_tokens,
+        )
+        .unwrap();
+
+        assert_eq!(fill.side, TradeType::Buy);
+        assert_eq!(fill.amount_in, 200_000);
+        assert_eq!(fill.network_fee, 25_000);
+        assert_eq!(fill.priority_fee, 20_000);
+        assert_eq!(fill.tip, 1_000_000);
+        assert_eq!(fill.total_cost(), 1_025_000);
+    }
+
+    #[test]
+    fn rejects_transaction_without_dex_instruction() {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let result = parse_fill(
+            Signature::default(),
+            1,
+            None,
+            &keys,
+            &[(1, vec![0])],
+            &[],
+            &[],
+            0,
+            1,
+            &[],
+            &[],
+        );
+        assert!(result.is_err());
+    }
+}
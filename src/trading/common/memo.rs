@@ -0,0 +1,53 @@
+//! SPL Memo instruction tagging a trade (strategy id, order id) for on-chain attribution.
+//! 交易备注：附加 Memo 指令，便于链上分析按策略归因成交。
+
+use crate::constants::MEMO_PROGRAM;
+use anyhow::anyhow;
+use solana_sdk::instruction::Instruction;
+
+/// Longest memo tag accepted, in bytes. Each byte is paid for in transaction size.
+pub const MAX_MEMO_LEN: usize = 128;
+
+/// A Memo instruction carrying `tag`. No signer accounts are attached, so it adds only the
+/// program id and the tag bytes to the transaction.
+pub fn memo_instruction(tag: &str) -> Result<Instruction, anyhow::Error> {
+    if tag.is_empty() {
+        return Err(anyhow!("Memo tag must not be empty"));
+    }
+    if tag.len() > MAX_MEMO_LEN {
+        return Err(anyhow!("Memo tag is {} bytes, max {}", tag.len(), MAX_MEMO_LEN));
+    }
+    Ok(Instruction::new_with_bytes(MEMO_PROGRAM, tag.as_bytes(), Vec::new()))
+}
+
+/// Append a memo with `tag` unless `instructions` already carries one.
+/// Appended rather than prepended so absolute instruction indices stay valid.
+pub fn append_memo_instruction(
+    instructions: &mut Vec<Instruction>,
+    tag: &str,
+) -> Result<(), anyhow::Error> {
+    if instructions.iter().any(|ix| ix.program_id == MEMO_PROGRAM) {
+        return Ok(());
+    }
+    instructions.push(memo_instruction(tag)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_one_bounded_memo() {
+        let mut instructions = Vec::new();
+        append_memo_instruction(&mut instructions, "strategy-7:order-42").unwrap();
+        append_memo_instruction(&mut instructions, "other").unwrap();
+
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(instructions[0].program_id, MEMO_PROGRAM);
+        assert_eq!(instructions[0].data, b"strategy-7:order-42");
+        assert!(instructions[0].accounts.is_empty());
+        assert!(memo_instruction("").is_err());
+        assert!(memo_instruction(&"x".repeat(MAX_MEMO_LEN + 1)).is_err());
+    }
+}
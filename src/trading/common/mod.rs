@@ -1,4 +1,6 @@
 pub mod compute_budget_manager;
+pub mod fill;
+pub mod memo;
 pub mod nonce_manager;
 pub mod transaction_builder;
 pub mod utils;
@@ -6,6 +8,8 @@ pub mod wsol_manager;
 
 // Re-export commonly used functions
 pub use compute_budget_manager::*;
+pub use fill::*;
+pub use memo::*;
 pub use nonce_manager::*;
 pub use transaction_builder::*;
 pub use utils::*;
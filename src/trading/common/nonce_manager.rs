@@ -1,5 +1,5 @@
 use solana_hash::Hash;
-use solana_sdk::{instruction::Instruction, signature::Keypair, signer::Signer};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer};
 use solana_system_interface::instruction::advance_nonce_account;
 
 use crate::common::nonce_cache::DurableNonceInfo;
@@ -13,10 +13,19 @@ pub fn add_nonce_instruction(
     instructions: &mut Vec<Instruction>,
     payer: &Keypair,
     durable_nonce: Option<&DurableNonceInfo>,
+) -> Result<(), anyhow::Error> {
+    add_nonce_instruction_with_authority(instructions, &payer.pubkey(), durable_nonce)
+}
+
+/// Same as [`add_nonce_instruction`], with the nonce authority given by its pubkey.
+pub fn add_nonce_instruction_with_authority(
+    instructions: &mut Vec<Instruction>,
+    authority: &Pubkey,
+    durable_nonce: Option<&DurableNonceInfo>,
 ) -> Result<(), anyhow::Error> {
     if let Some(durable_nonce) = durable_nonce {
         let nonce_advance_ix =
-            advance_nonce_account(&durable_nonce.nonce_account.unwrap(), &payer.pubkey());
+            advance_nonce_account(&durable_nonce.nonce_account.unwrap(), authority);
         instructions.push(nonce_advance_ix);
     }
 
@@ -1,18 +1,25 @@
 use anyhow::anyhow;
 use solana_hash::Hash;
-use solana_message::AddressLookupTableAccount;
+use solana_message::{AddressLookupTableAccount, VersionedMessage};
 use solana_sdk::{
-    instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
     transaction::VersionedTransaction,
 };
 use solana_system_interface::instruction as system_instruction;
 use std::sync::Arc;
 
-use super::nonce_manager::{add_nonce_instruction, get_transaction_blockhash};
+use super::compute_budget_manager::{
+    ComputeBudgetManager, ComputeBudgetRequest, DefaultComputeBudgetManager,
+};
+use super::nonce_manager::{add_nonce_instruction_with_authority, get_transaction_blockhash};
 use crate::{
     common::nonce_cache::DurableNonceInfo,
     trading::{
         core::transaction_pool::{acquire_builder, release_builder},
+        factory::DexType,
         MiddlewareManager,
     },
 };
@@ -21,7 +28,7 @@ const PACKET_DATA_SIZE: usize = 1232;
 
 /// Convert SOL amount (f64) to lamports without string allocation (hot path).
 #[inline(always)]
-fn sol_f64_to_lamports(sol: f64) -> u64 {
+pub(crate) fn sol_f64_to_lamports(sol: f64) -> u64 {
     if sol <= 0.0 {
         return 0;
     }
@@ -29,6 +36,26 @@ fn sol_f64_to_lamports(sol: f64) -> u64 {
     (lamports.min(u64::MAX as f64)).round() as u64
 }
 
+/// CU price (micro-lamports) `tx` actually pays, read from its compute budget; 0 when it sets
+/// none, e.g. under [`super::TipOnlyComputeBudgetManager`].
+pub(crate) fn compute_unit_price(tx: &VersionedTransaction) -> u64 {
+    let keys = tx.message.static_account_keys();
+    tx.message
+        .instructions()
+        .iter()
+        .filter(|ix| {
+            keys.get(ix.program_id_index as usize)
+                == Some(&crate::constants::COMPUTE_BUDGET_PROGRAM)
+        })
+        .find_map(|ix| match ix.data.split_first() {
+            Some((&SET_COMPUTE_UNIT_PRICE_TAG, price)) => {
+                price.get(..8).map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+            }
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
 /// Build signed transaction (worker hot path, no RPC).
 /// Takes Arc/refs only; one Vec allocation (with_capacity), extend_from_slice for business_instructions, no extra clone of payer/middleware.
 pub fn build_transaction(
@@ -39,34 +66,75 @@ pub fn build_transaction(
     address_lookup_table_accounts: &[AddressLookupTableAccount],
     recent_blockhash: Option<Hash>,
     middleware_manager: Option<&Arc<MiddlewareManager>>,
-    protocol_name: &str,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: Option<DexType>,
     is_buy: bool,
     with_tip: bool,
     tip_account: &Pubkey,
     tip_amount: f64,
     durable_nonce: Option<&DurableNonceInfo>,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let transaction = build_transaction_inner(
+    build_transaction_with_signers(
         payer,
+        &[],
+        unit_limit,
+        unit_price,
+        business_instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        dex_type,
+        is_buy,
+        with_tip,
+        tip_account,
+        tip_amount,
+        durable_nonce,
+    )
+}
+
+/// Same as [`build_transaction`], also signing with `additional_signers` (e.g. a flash-loan
+/// receiver or delegate required by the business instructions). The payer stays fee payer.
+pub fn build_transaction_with_signers(
+    payer: &Arc<Keypair>,
+    additional_signers: &[Arc<Keypair>],
+    unit_limit: u32,
+    unit_price: u64,
+    business_instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: Option<DexType>,
+    is_buy: bool,
+    with_tip: bool,
+    tip_account: &Pubkey,
+    tip_amount: f64,
+    durable_nonce: Option<&DurableNonceInfo>,
+) -> Result<VersionedTransaction, anyhow::Error> {
+    let message = build_message_inner(
+        &payer.pubkey(),
         unit_limit,
         unit_price,
         business_instructions,
         address_lookup_table_accounts,
         recent_blockhash,
         middleware_manager,
-        protocol_name,
+        compute_budget_manager,
+        dex_type,
         is_buy,
         with_tip,
         tip_account,
         tip_amount,
         durable_nonce,
     )?;
+    let transaction = sign_message(Some(payer), additional_signers, message, true)?;
 
     let serialized_len = bincode::serialized_size(&transaction)? as usize;
     if crate::common::sdk_log::sdk_log_enabled() {
         println!(
             " [SDK][tx-size     ] {} {} serialized={} bytes, business_ix={}, nonce={}, tip={}, cu_limit={}, cu_price={}, alt={}",
-            protocol_name,
+            dex_type.map_or("Utility", |dex| dex.name()),
             if is_buy { "buy" } else { "sell" },
             serialized_len,
             business_instructions.len(),
@@ -80,94 +148,431 @@ pub fn build_transaction(
     if serialized_len <= PACKET_DATA_SIZE {
         return Ok(transaction);
     }
+    Err(too_large_error(serialized_len))
+}
 
-    Err(anyhow!(
+fn too_large_error(serialized_len: usize) -> anyhow::Error {
+    anyhow!(
         "transaction too large: {} > {}; SDK did not remove compute budget or relay tip because that changes transaction priority semantics. Use an address lookup table or pre-create token ATAs before submitting",
         serialized_len,
         PACKET_DATA_SIZE
-    ))
+    )
 }
 
-fn build_transaction_inner(
+/// Same as [`build_transaction_with_signers`], but required signers missing from
+/// `additional_signers` keep an empty signature and the packet limit is left to the RPC. Only for
+/// `simulateTransaction` with `sig_verify: false`, e.g. a wealthy wallet lending its balances to
+/// a dry run.
+pub fn build_simulation_transaction(
     payer: &Arc<Keypair>,
+    additional_signers: &[Arc<Keypair>],
     unit_limit: u32,
     unit_price: u64,
     business_instructions: &[Instruction],
     address_lookup_table_accounts: &[AddressLookupTableAccount],
     recent_blockhash: Option<Hash>,
     middleware_manager: Option<&Arc<MiddlewareManager>>,
-    protocol_name: &str,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: Option<DexType>,
     is_buy: bool,
     with_tip: bool,
     tip_account: &Pubkey,
     tip_amount: f64,
     durable_nonce: Option<&DurableNonceInfo>,
 ) -> Result<VersionedTransaction, anyhow::Error> {
-    let mut instructions = Vec::with_capacity(business_instructions.len() + 5);
+    let message = build_message_inner(
+        &payer.pubkey(),
+        unit_limit,
+        unit_price,
+        business_instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        dex_type,
+        is_buy,
+        with_tip,
+        tip_account,
+        tip_amount,
+        durable_nonce,
+    )?;
+    sign_message(Some(payer), additional_signers, message, false)
+}
+
+/// Wire size of the transaction [`build_transaction_with_signers`] would build from the same
+/// arguments, without signing it or enforcing the packet limit.
+pub fn transaction_size(
+    payer: &Arc<Keypair>,
+    unit_limit: u32,
+    unit_price: u64,
+    business_instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: Option<DexType>,
+    is_buy: bool,
+    with_tip: bool,
+    tip_account: &Pubkey,
+    tip_amount: f64,
+    durable_nonce: Option<&DurableNonceInfo>,
+) -> Result<usize, anyhow::Error> {
+    let message = build_message_inner(
+        &payer.pubkey(),
+        unit_limit,
+        unit_price,
+        business_instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        dex_type,
+        is_buy,
+        with_tip,
+        tip_account,
+        tip_amount,
+        durable_nonce,
+    )?;
+    let required = message.header().num_required_signatures as usize;
+    // Signature count is a compact-u16: one byte below 128 signers.
+    Ok(message.serialize().len() + 1 + required * 64)
+}
+
+/// Whether a transaction of `serialized_len` bytes fits in one packet.
+#[inline]
+pub fn fits_in_packet(serialized_len: usize) -> bool {
+    serialized_len <= PACKET_DATA_SIZE
+}
+
+/// Number of leading account-setup instructions (ATA creation, SOL wrapping) in a trade's
+/// instructions; these can land in an earlier transaction when the trade does not fit in one.
+pub fn leading_setup_len(instructions: &[Instruction]) -> usize {
+    instructions.iter().take_while(|ix| is_setup_instruction(ix)).count()
+}
+
+/// Remove the account setup among the first `setup_len` instructions (see
+/// [`leading_setup_len`]) and return it. Compute budget instructions among them stay in
+/// `instructions`, so the swap keeps its CU limit and price.
+pub fn take_setup_instructions(
+    instructions: &mut Vec<Instruction>,
+    setup_len: usize,
+) -> Vec<Instruction> {
+    let (budget, setup): (Vec<_>, Vec<_>) = instructions
+        .drain(..setup_len)
+        .partition(|ix| ix.program_id == crate::constants::COMPUTE_BUDGET_PROGRAM);
+    instructions.splice(0..0, budget);
+    setup
+}
+
+const CLOSE_ACCOUNT_TAG: u8 = 9;
+
+/// Token account closes in `swap` whose account `setup` writes, e.g. the unwrap of the WSOL the
+/// setup funded. Sent on their own after a failed swap, they return that SOL and the rent.
+pub fn setup_cleanup_instructions(swap: &[Instruction], setup: &[Instruction]) -> Vec<Instruction> {
+    use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+
+    let written_by_setup = |pubkey: &Pubkey| {
+        setup
+            .iter()
+            .flat_map(|ix| &ix.accounts)
+            .any(|meta| meta.is_writable && meta.pubkey == *pubkey)
+    };
+    swap.iter()
+        .filter(|ix| {
+            (ix.program_id == TOKEN_PROGRAM || ix.program_id == TOKEN_PROGRAM_2022)
+                && ix.data.first() == Some(&CLOSE_ACCOUNT_TAG)
+                && ix.accounts.first().is_some_and(|closed| written_by_setup(&closed.pubkey))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Transaction for a fee sponsor: `fee_payer` (the sponsor's account) pays the fee and its
+/// signature slot is left empty; every other required signer must be in `signers`. No relay tip;
+/// a recent blockhash is required (sponsors do not advance durable nonces).
+pub fn build_sponsored_transaction(
+    fee_payer: &Pubkey,
+    signers: &[Arc<Keypair>],
+    unit_limit: u32,
+    unit_price: u64,
+    business_instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: Option<DexType>,
+    is_buy: bool,
+) -> Result<VersionedTransaction, anyhow::Error> {
+    if recent_blockhash.is_none() {
+        return Err(anyhow!("sponsored transactions need a recent blockhash"));
+    }
+    let message = build_message_inner(
+        fee_payer,
+        unit_limit,
+        unit_price,
+        business_instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        dex_type,
+        is_buy,
+        false,
+        &Pubkey::default(),
+        0.0,
+        None,
+    )?;
+    let transaction = sign_message(None, signers, message, true)?;
+    let serialized_len = bincode::serialized_size(&transaction)? as usize;
+    if serialized_len > PACKET_DATA_SIZE {
+        return Err(too_large_error(serialized_len));
+    }
+    Ok(transaction)
+}
+
+/// Whether `ix` is account setup around a swap (ATA create/close, SOL wrap/unwrap, budget).
+#[inline]
+fn is_setup_instruction(ix: &Instruction) -> bool {
+    use crate::constants::{
+        ASSOCIATED_TOKEN_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM, SYSTEM_PROGRAM, TOKEN_PROGRAM,
+        TOKEN_PROGRAM_2022,
+    };
+    [
+        SYSTEM_PROGRAM,
+        TOKEN_PROGRAM,
+        TOKEN_PROGRAM_2022,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        COMPUTE_BUDGET_PROGRAM,
+    ]
+    .contains(&ix.program_id)
+}
 
-    if let Err(e) = add_nonce_instruction(&mut instructions, payer.as_ref(), durable_nonce) {
+/// Insert the caller's `pre` instructions before the first swap instruction (after ATA creation
+/// and SOL wrapping) and `post` after the last one (before ATA closes and unwrapping).
+pub fn splice_extra_instructions(
+    instructions: &mut Vec<Instruction>,
+    pre: &[Instruction],
+    post: &[Instruction],
+) {
+    if pre.is_empty() && post.is_empty() {
+        return;
+    }
+    let first = instructions.iter().position(|ix| !is_setup_instruction(ix)).unwrap_or(0);
+    let last = instructions
+        .iter()
+        .rposition(|ix| !is_setup_instruction(ix))
+        .map_or(instructions.len(), |index| index + 1);
+    instructions.splice(last..last, post.iter().cloned());
+    instructions.splice(first..first, pre.iter().cloned());
+}
+
+/// Compute-budget / tip instructions the caller already put in `business_instructions`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct CallerBudgetOverrides {
+    unit_limit: bool,
+    unit_price: bool,
+    tip: bool,
+    /// Same compute-budget instruction appears more than once (rejected by the runtime).
+    duplicates: bool,
+}
+
+const SET_COMPUTE_UNIT_LIMIT_TAG: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE_TAG: u8 = 3;
+const SYSTEM_TRANSFER_TAG: u32 = 2;
+
+#[inline]
+fn is_transfer_to(ix: &Instruction, from: &Pubkey, to: &Pubkey) -> bool {
+    ix.program_id == crate::constants::SYSTEM_PROGRAM
+        && ix.data.len() >= 4
+        && u32::from_le_bytes([ix.data[0], ix.data[1], ix.data[2], ix.data[3]])
+            == SYSTEM_TRANSFER_TAG
+        && ix.accounts.len() >= 2
+        && ix.accounts[0].pubkey == *from
+        && ix.accounts[1].pubkey == *to
+}
+
+/// Single pass over business instructions; the common case (none present) costs one compare per ix.
+fn scan_caller_budget(
+    business_instructions: &[Instruction],
+    payer: &Pubkey,
+    tip_account: &Pubkey,
+) -> CallerBudgetOverrides {
+    let mut overrides = CallerBudgetOverrides::default();
+    let mut seen_tags = 0u32;
+    for ix in business_instructions {
+        if ix.program_id == crate::constants::COMPUTE_BUDGET_PROGRAM {
+            let Some(&tag) = ix.data.first() else { continue };
+            let bit = 1u32 << (tag as u32).min(31);
+            overrides.duplicates |= seen_tags & bit != 0;
+            seen_tags |= bit;
+            overrides.unit_limit |= tag == SET_COMPUTE_UNIT_LIMIT_TAG;
+            overrides.unit_price |= tag == SET_COMPUTE_UNIT_PRICE_TAG;
+        } else if is_transfer_to(ix, payer, tip_account) {
+            overrides.tip = true;
+        }
+    }
+    overrides
+}
+
+/// Keep the first instance of each compute-budget instruction the caller supplied.
+fn push_deduped_business(
+    instructions: &mut Vec<Instruction>,
+    business_instructions: &[Instruction],
+) {
+    let mut seen_tags = 0u32;
+    for ix in business_instructions {
+        if ix.program_id == crate::constants::COMPUTE_BUDGET_PROGRAM {
+            if let Some(&tag) = ix.data.first() {
+                let bit = 1u32 << (tag as u32).min(31);
+                if seen_tags & bit != 0 {
+                    continue;
+                }
+                seen_tags |= bit;
+            }
+        }
+        instructions.push(ix.clone());
+    }
+}
+
+fn build_message_inner(
+    payer_pubkey: &Pubkey,
+    unit_limit: u32,
+    unit_price: u64,
+    business_instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: Option<DexType>,
+    is_buy: bool,
+    with_tip: bool,
+    tip_account: &Pubkey,
+    tip_amount: f64,
+    durable_nonce: Option<&DurableNonceInfo>,
+) -> Result<VersionedMessage, anyhow::Error> {
+    let mut instructions =
+        crate::common::instruction_pool::instruction_vec(business_instructions.len() + 5);
+
+    if let Err(e) =
+        add_nonce_instruction_with_authority(&mut instructions, payer_pubkey, durable_nonce)
+    {
         return Err(e);
     }
 
-    if with_tip && tip_amount > 0.0 {
+    // Caller-provided compute budget / tip win over the SDK's so nothing is sent twice.
+    let overrides = scan_caller_budget(business_instructions, payer_pubkey, tip_account);
+
+    if with_tip && tip_amount > 0.0 && !overrides.tip {
         let tip_lamports = sol_f64_to_lamports(tip_amount);
-        instructions.push(system_instruction::transfer(&payer.pubkey(), tip_account, tip_lamports));
+        instructions.push(system_instruction::transfer(payer_pubkey, tip_account, tip_lamports));
     }
 
-    super::compute_budget_manager::extend_compute_budget_instructions(
-        &mut instructions,
-        unit_price,
-        unit_limit,
-    );
+    let budget_request = ComputeBudgetRequest {
+        unit_price: if overrides.unit_price { 0 } else { unit_price },
+        unit_limit: if overrides.unit_limit { 0 } else { unit_limit },
+        dex_type,
+        is_buy,
+    };
+    match compute_budget_manager {
+        Some(manager) => manager.extend_instructions(&mut instructions, &budget_request),
+        None => DefaultComputeBudgetManager.extend_instructions(&mut instructions, &budget_request),
+    }
 
-    instructions.extend_from_slice(business_instructions);
+    if overrides.duplicates {
+        push_deduped_business(&mut instructions, business_instructions);
+    } else {
+        instructions.extend_from_slice(business_instructions);
+    }
 
     let blockhash = get_transaction_blockhash(recent_blockhash, durable_nonce)?;
 
-    build_versioned_transaction(
-        payer,
+    build_versioned_message(
+        payer_pubkey,
         instructions,
         address_lookup_table_accounts,
         blockhash,
         middleware_manager,
-        protocol_name,
+        dex_type,
         is_buy,
     )
 }
 
-fn build_versioned_transaction(
-    payer: &Arc<Keypair>,
+fn build_versioned_message(
+    payer: &Pubkey,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: &[AddressLookupTableAccount],
     blockhash: Hash,
     middleware_manager: Option<&Arc<MiddlewareManager>>,
-    protocol_name: &str,
+    dex_type: Option<DexType>,
     is_buy: bool,
-) -> Result<VersionedTransaction, anyhow::Error> {
-    let full_instructions = match middleware_manager {
+) -> Result<VersionedMessage, anyhow::Error> {
+    let mut full_instructions = match middleware_manager {
         Some(middleware_manager) => middleware_manager
-            .apply_middlewares_process_full_instructions(instructions, protocol_name, is_buy)?,
+            .apply_middlewares_process_transaction_instructions(instructions, dex_type, is_buy)?,
         None => instructions,
     };
+    // Flash-loan indices are absolute; only the final list knows them.
+    crate::instruction::flashloan::fix_flash_loan_indices(&mut full_instructions)?;
 
     // 使用预分配的交易构建器以降低延迟
     let mut builder = acquire_builder();
 
     let build_result = builder.build_zero_alloc(
-        &payer.pubkey(),
+        payer,
         &full_instructions,
         address_lookup_table_accounts,
         blockhash,
     );
     release_builder(builder);
-    let versioned_msg = build_result?;
+    // The message owns its own copy now; hand the per-lane instruction list back to the pool.
+    crate::common::instruction_pool::recycle_instructions(full_instructions);
+    build_result
+}
 
+/// Signs with the fee `payer` and `additional_signers`; with `require_all` every required signer
+/// must be among them, otherwise the missing ones keep an empty signature. A `None` payer leaves
+/// the fee payer slot empty for a fee sponsor to sign.
+fn sign_message(
+    payer: Option<&Arc<Keypair>>,
+    additional_signers: &[Arc<Keypair>],
+    versioned_msg: VersionedMessage,
+    require_all: bool,
+) -> Result<VersionedTransaction, anyhow::Error> {
     let msg_bytes = versioned_msg.serialize();
-    let signature =
-        payer.as_ref().try_sign_message(&msg_bytes).map_err(|e| anyhow!("sign failed: {e}"))?;
-    let tx = VersionedTransaction { signatures: vec![signature], message: versioned_msg };
+    let signature = match payer {
+        Some(payer) => {
+            payer.as_ref().try_sign_message(&msg_bytes).map_err(|e| anyhow!("sign failed: {e}"))?
+        }
+        None => Signature::default(),
+    };
+    let required = versioned_msg.header().num_required_signatures as usize;
+    if payer.is_some() && required <= 1 && additional_signers.is_empty() {
+        let tx = VersionedTransaction { signatures: vec![signature], message: versioned_msg };
+        return Ok(tx);
+    }
 
-    Ok(tx)
+    // Signatures follow the order of the required signer keys; the payer is always first.
+    let signer_keys = &versioned_msg.static_account_keys()[..required];
+    let mut signatures = vec![Signature::default(); required];
+    signatures[0] = signature;
+    for signer in additional_signers {
+        let pubkey = signer.pubkey();
+        let Some(index) = signer_keys.iter().position(|key| *key == pubkey) else {
+            return Err(anyhow!("signer {} is not required by the transaction", pubkey));
+        };
+        signatures[index] =
+            signer.try_sign_message(&msg_bytes).map_err(|e| anyhow!("sign failed: {e}"))?;
+    }
+    let sponsor_slots = payer.is_none() as usize;
+    if let Some(index) =
+        signatures.iter().skip(sponsor_slots).position(|sig| *sig == Signature::default())
+    {
+        if require_all {
+            let index = index + sponsor_slots;
+            return Err(anyhow!("missing signature for required signer {}", signer_keys[index]));
+        }
+    }
+    Ok(VersionedTransaction { signatures, message: versioned_msg })
 }
 
 #[cfg(test)]
@@ -181,6 +586,330 @@ mod tests {
         Instruction { program_id: Pubkey::new_unique(), accounts, data: vec![7; data_len] }
     }
 
+    fn compute_budget_ix_count(tx: &VersionedTransaction) -> usize {
+        let keys = tx.message.static_account_keys();
+        tx.message
+            .instructions()
+            .iter()
+            .filter(|ix| {
+                keys[ix.program_id_index as usize] == crate::constants::COMPUTE_BUDGET_PROGRAM
+            })
+            .count()
+    }
+
+    #[test]
+    fn caller_compute_budget_and_tip_are_not_duplicated() {
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+        let payer = Arc::new(Keypair::new());
+        let tip_account = Pubkey::new_unique();
+        let business_instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ComputeBudgetInstruction::set_compute_unit_price(5),
+            ComputeBudgetInstruction::set_compute_unit_price(7),
+            system_instruction::transfer(&payer.pubkey(), &tip_account, 1_000),
+            oversized_instruction(2, 8),
+        ];
+
+        let overrides = scan_caller_budget(&business_instructions, &payer.pubkey(), &tip_account);
+        assert_eq!(
+            overrides,
+            CallerBudgetOverrides {
+                unit_limit: true,
+                unit_price: true,
+                tip: true,
+                duplicates: true
+            }
+        );
+
+        let tx = build_transaction(
+            &payer,
+            80_000,
+            100_000,
+            &business_instructions,
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            None,
+            None,
+            true,
+            true,
+            &tip_account,
+            0.001,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(compute_budget_ix_count(&tx), 2);
+        // caller tip transfer + business ix + 2 caller compute budget ixs
+        assert_eq!(tx.message.instructions().len(), 4);
+    }
+
+    #[test]
+    fn custom_compute_budget_manager_replaces_strategy_price() {
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+        struct OraclePrice(u64);
+        impl ComputeBudgetManager for OraclePrice {
+            fn extend_instructions(
+                &self,
+                instructions: &mut Vec<Instruction>,
+                request: &ComputeBudgetRequest,
+            ) {
+                if request.unit_price > 0 {
+                    instructions.push(ComputeBudgetInstruction::set_compute_unit_price(self.0));
+                }
+            }
+        }
+
+        let payer = Arc::new(Keypair::new());
+        let manager: Arc<dyn ComputeBudgetManager> = Arc::new(OraclePrice(42));
+        let build = |business_instructions: &[Instruction]| {
+            build_transaction(
+                &payer,
+                80_000,
+                100_000,
+                business_instructions,
+                &[],
+                Some(Hash::new_unique()),
+                None,
+                Some(&manager),
+                None,
+                true,
+                false,
+                &Pubkey::default(),
+                0.0,
+                None,
+            )
+            .unwrap()
+        };
+        let price_data = ComputeBudgetInstruction::set_compute_unit_price(42).data;
+
+        let tx = build(&[oversized_instruction(2, 8)]);
+        assert_eq!(compute_budget_ix_count(&tx), 1);
+        assert_eq!(tx.message.instructions()[0].data, price_data);
+
+        // The caller's own price still wins over the manager.
+        let caller_price = ComputeBudgetInstruction::set_compute_unit_price(5);
+        let tx = build(&[caller_price.clone(), oversized_instruction(2, 8)]);
+        assert_eq!(compute_budget_ix_count(&tx), 1);
+        assert_eq!(tx.message.instructions()[0].data, caller_price.data);
+    }
+
+    #[test]
+    fn tip_only_manager_keeps_the_limit_and_the_tip() {
+        use super::super::compute_budget_manager::TipOnlyComputeBudgetManager;
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+        let payer = Arc::new(Keypair::new());
+        let tip_account = Pubkey::new_unique();
+        let manager: Arc<dyn ComputeBudgetManager> =
+            Arc::new(TipOnlyComputeBudgetManager::new(None));
+        let tx = build_transaction(
+            &payer,
+            80_000,
+            100_000,
+            &[oversized_instruction(2, 8)],
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            Some(&manager),
+            None,
+            true,
+            true,
+            &tip_account,
+            0.001,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(compute_budget_ix_count(&tx), 1);
+        let limit_data = ComputeBudgetInstruction::set_compute_unit_limit(80_000).data;
+        assert!(tx.message.instructions().iter().any(|ix| ix.data == limit_data));
+        let keys = tx.message.static_account_keys();
+        assert!(keys.contains(&tip_account));
+        assert_eq!(compute_unit_price(&tx), 0);
+
+        let priced = build_transaction(
+            &payer,
+            80_000,
+            100_000,
+            &[oversized_instruction(2, 8)],
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            None,
+            None,
+            true,
+            true,
+            &tip_account,
+            0.001,
+            None,
+        )
+        .unwrap();
+        assert_eq!(compute_unit_price(&priced), 100_000);
+    }
+
+    #[test]
+    fn additional_signers_sign_in_account_order() {
+        let payer = Arc::new(Keypair::new());
+        let delegate = Arc::new(Keypair::new());
+        let business_instructions = vec![Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new_readonly(delegate.pubkey(), true)],
+            data: vec![1],
+        }];
+        let build = |signers: &[Arc<Keypair>]| {
+            build_transaction_with_signers(
+                &payer,
+                signers,
+                0,
+                0,
+                &business_instructions,
+                &[],
+                Some(Hash::new_unique()),
+                None,
+                None,
+                None,
+                true,
+                false,
+                &Pubkey::default(),
+                0.0,
+                None,
+            )
+        };
+
+        let tx = build(&[delegate.clone()]).unwrap();
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+        let err = build(&[]).unwrap_err().to_string();
+        assert!(err.contains("missing signature"), "{err}");
+    }
+
+    #[test]
+    fn separate_fee_payer_pays_tip_and_owner_co_signs() {
+        let fee_payer = Arc::new(Keypair::new());
+        let owner = Arc::new(Keypair::new());
+        let tip_account = Pubkey::new_unique();
+        let business_instructions = vec![Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(owner.pubkey(), true)],
+            data: vec![1],
+        }];
+
+        let tx = build_transaction_with_signers(
+            &fee_payer,
+            &[owner.clone()],
+            0,
+            0,
+            &business_instructions,
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            None,
+            None,
+            true,
+            true,
+            &tip_account,
+            0.001,
+            None,
+        )
+        .unwrap();
+
+        let keys = tx.message.static_account_keys();
+        assert_eq!(keys[0], fee_payer.pubkey());
+        assert!(keys[..2].contains(&owner.pubkey()));
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+        let tip = &tx.message.instructions()[0];
+        assert_eq!(keys[tip.program_id_index as usize], crate::constants::SYSTEM_PROGRAM);
+        assert_eq!(keys[tip.accounts[0] as usize], fee_payer.pubkey());
+    }
+
+    #[test]
+    fn middlewares_see_transactions_without_a_dex() {
+        use crate::trading::middleware::traits::InstructionMiddleware;
+
+        #[derive(Clone)]
+        struct AppendMemo;
+        impl InstructionMiddleware for AppendMemo {
+            fn name(&self) -> &'static str {
+                "AppendMemo"
+            }
+            fn process_protocol_instructions(
+                &self,
+                protocol_instructions: Vec<Instruction>,
+                _dex_type: DexType,
+                _is_buy: bool,
+            ) -> anyhow::Result<Vec<Instruction>> {
+                Ok(protocol_instructions)
+            }
+            fn process_full_instructions(
+                &self,
+                full_instructions: Vec<Instruction>,
+                _dex_type: DexType,
+                _is_buy: bool,
+            ) -> anyhow::Result<Vec<Instruction>> {
+                Ok(full_instructions)
+            }
+            fn process_utility_instructions(
+                &self,
+                mut full_instructions: Vec<Instruction>,
+                _is_buy: bool,
+            ) -> anyhow::Result<Vec<Instruction>> {
+                full_instructions.push(oversized_instruction(0, 4));
+                Ok(full_instructions)
+            }
+            fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
+                Box::new(self.clone())
+            }
+        }
+
+        let payer = Arc::new(Keypair::new());
+        let manager = Arc::new(MiddlewareManager::new().add_middleware(Box::new(AppendMemo)));
+        let tx = build_transaction(
+            &payer,
+            0,
+            0,
+            &[oversized_instruction(1, 8)],
+            &[],
+            Some(Hash::new_unique()),
+            Some(&manager),
+            None,
+            None,
+            true,
+            false,
+            &Pubkey::default(),
+            0.0,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(tx.message.instructions().len(), 2);
+    }
+
+    #[test]
+    fn extra_instructions_wrap_the_swap_inside_the_account_setup() {
+        let owner = Pubkey::new_unique();
+        let create_ata = Instruction {
+            program_id: crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID,
+            accounts: vec![],
+            data: vec![1],
+        };
+        let close_ata = Instruction {
+            program_id: crate::constants::TOKEN_PROGRAM,
+            accounts: vec![],
+            data: vec![9],
+        };
+        let swap = oversized_instruction(1, 1);
+        let pre = system_instruction::transfer(&owner, &Pubkey::new_unique(), 1);
+        let post = oversized_instruction(1, 2);
+
+        let mut instructions = vec![create_ata.clone(), swap.clone(), close_ata.clone()];
+        splice_extra_instructions(&mut instructions, &[pre.clone()], &[post.clone()]);
+        assert_eq!(instructions, vec![create_ata, pre, swap, post, close_ata]);
+    }
+
     #[test]
     fn oversized_transaction_returns_error_without_dropping_priority_semantics() {
         let payer = Arc::new(Keypair::new());
@@ -193,7 +922,8 @@ mod tests {
             &[],
             Some(Hash::new_unique()),
             None,
-            "test",
+            None,
+            None,
             true,
             true,
             &Pubkey::new_unique(),
@@ -206,4 +936,90 @@ mod tests {
         assert!(err.contains("transaction too large"), "{err}");
         assert!(err.contains("did not remove compute budget or relay tip"), "{err}");
     }
+
+    #[test]
+    fn size_probe_matches_the_signed_transaction() {
+        let payer = Arc::new(Keypair::new());
+        let create_ata = Instruction {
+            program_id: crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(payer.pubkey(), true)],
+            data: vec![1],
+        };
+        let instructions = vec![create_ata, oversized_instruction(4, 40)];
+        assert_eq!(leading_setup_len(&instructions), 1);
+
+        let tip_account = Pubkey::new_unique();
+        let blockhash = Some(Hash::new_unique());
+        let size = transaction_size(
+            &payer,
+            80_000,
+            100_000,
+            &instructions,
+            &[],
+            blockhash,
+            None,
+            None,
+            None,
+            true,
+            true,
+            &tip_account,
+            0.001,
+            None,
+        )
+        .unwrap();
+        let transaction = build_transaction(
+            &payer,
+            80_000,
+            100_000,
+            &instructions,
+            &[],
+            blockhash,
+            None,
+            None,
+            None,
+            true,
+            true,
+            &tip_account,
+            0.001,
+            None,
+        )
+        .unwrap();
+        assert_eq!(size, bincode::serialized_size(&transaction).unwrap() as usize);
+        assert!(fits_in_packet(size));
+    }
+
+    #[test]
+    fn split_keeps_compute_budget_with_the_swap_and_closes_only_setup_accounts() {
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+        let payer = Pubkey::new_unique();
+        let (wsol_ata, input_ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let budget = ComputeBudgetInstruction::set_compute_unit_price(100_000);
+        let create_wsol = Instruction {
+            program_id: crate::constants::ASSOCIATED_TOKEN_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(payer, true), AccountMeta::new(wsol_ata, false)],
+            data: vec![1],
+        };
+        let close = |account: Pubkey| Instruction {
+            program_id: crate::constants::TOKEN_PROGRAM,
+            accounts: vec![AccountMeta::new(account, false), AccountMeta::new(payer, false)],
+            data: vec![CLOSE_ACCOUNT_TAG],
+        };
+        let swap = oversized_instruction(1, 1);
+
+        let mut instructions = vec![
+            budget.clone(),
+            create_wsol.clone(),
+            swap.clone(),
+            close(wsol_ata),
+            close(input_ata),
+        ];
+        let setup_len = leading_setup_len(&instructions);
+        assert_eq!(setup_len, 2);
+        let setup = take_setup_instructions(&mut instructions, setup_len);
+        assert_eq!(setup, vec![create_wsol]);
+        assert_eq!(instructions[..2], [budget, swap]);
+        // The input account was not created by the setup and may still hold tokens.
+        assert_eq!(setup_cleanup_instructions(&instructions, &setup), vec![close(wsol_ata)]);
+    }
 }
@@ -1,77 +1,24 @@
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signature::Keypair, signature::Signature,
+    signer::Signer, transaction::Transaction,
+};
 use solana_system_interface::instruction as system_instruction;
 
 use crate::common::{
     fast_fn::{
+        create_associated_token_account_idempotent_fast,
         get_associated_token_address_with_program_id_fast,
         get_associated_token_address_with_program_id_fast_use_seed,
     },
-    spl_token::close_account,
+    spl_token::{close_account, transfer_checked},
     SolanaRpcClient,
 };
 use anyhow::anyhow;
 
-/// Get the balances of two tokens in the pool
-///
-/// # Returns
-/// Returns token0_balance, token1_balance
-pub async fn get_multi_token_balances(
-    rpc: &SolanaRpcClient,
-    token0_vault: &Pubkey,
-    token1_vault: &Pubkey,
-) -> Result<(u64, u64), anyhow::Error> {
-    let token0_balance = rpc.get_token_account_balance(&token0_vault).await?;
-    let token1_balance = rpc.get_token_account_balance(&token1_vault).await?;
-    // Parse balance string to u64
-    let token0_amount = token0_balance
-        .amount
-        .parse::<u64>()
-        .map_err(|e| anyhow!("Failed to parse token0 balance: {}", e))?;
-    let token1_amount = token1_balance
-        .amount
-        .parse::<u64>()
-        .map_err(|e| anyhow!("Failed to parse token1 balance: {}", e))?;
-    Ok((token0_amount, token1_amount))
-}
-
-#[inline]
-pub async fn get_token_balance(
-    rpc: &SolanaRpcClient,
-    payer: &Pubkey,
-    mint: &Pubkey,
-) -> Result<u64, anyhow::Error> {
-    get_token_balance_with_options(rpc, payer, mint, &crate::constants::TOKEN_PROGRAM, false).await
-}
-
-/// 使用与交易指令一致的 ATA 推导（可选 seed）查询余额；卖出/余额查询应与买入使用同一 ATA 地址。
-#[inline]
-pub async fn get_token_balance_with_options(
-    rpc: &SolanaRpcClient,
-    payer: &Pubkey,
-    mint: &Pubkey,
-    token_program: &Pubkey,
-    use_seed: bool,
-) -> Result<u64, anyhow::Error> {
-    let ata = get_associated_token_address_with_program_id_fast_use_seed(
-        payer,
-        mint,
-        token_program,
-        use_seed,
-    );
-    let balance = rpc.get_token_account_balance(&ata).await?;
-    let balance_u64 =
-        balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
-    Ok(balance_u64)
-}
-
-#[inline]
-pub async fn get_sol_balance(
-    rpc: &SolanaRpcClient,
-    account: &Pubkey,
-) -> Result<u64, anyhow::Error> {
-    let balance = rpc.get_balance(account).await?;
-    Ok(balance)
-}
+// Balance queries live in `crate::utils::accounts`; re-exported for existing callers.
+pub use crate::utils::accounts::{
+    get_multi_token_balances, get_sol_balance, get_token_balance, get_token_balance_with_options,
+};
 
 pub async fn transfer_sol(
     rpc: &SolanaRpcClient,
@@ -105,6 +52,147 @@ pub async fn transfer_sol(
     Ok(())
 }
 
+/// Recipients per transaction in [`batch_transfer_token`]: one idempotent ATA create plus one
+/// `TransferChecked` each, which stays below the 1232-byte packet limit without an ALT.
+pub const MAX_TOKEN_TRANSFERS_PER_TX: usize = 8;
+
+/// Byte offset of `decimals` in the SPL Token / Token-2022 mint layout.
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Detect the token program (Token or Token-2022) owning `mint` and read its decimals.
+pub async fn get_mint_program_and_decimals(
+    rpc: &SolanaRpcClient,
+    mint: &Pubkey,
+) -> Result<(Pubkey, u8), anyhow::Error> {
+    let account = crate::common::rpc_cache::get_immutable_account(rpc, mint).await?;
+    if account.owner != crate::constants::TOKEN_PROGRAM
+        && account.owner != crate::constants::TOKEN_PROGRAM_2022
+    {
+        return Err(anyhow!("Mint {} is not owned by a token program: {}", mint, account.owner));
+    }
+    let decimals = *account
+        .data
+        .get(MINT_DECIMALS_OFFSET)
+        .ok_or_else(|| anyhow!("Mint {} account data too short", mint))?;
+    Ok((account.owner, decimals))
+}
+
+/// Build recipient ATA creation (idempotent) and `TransferChecked` instructions for one chunk.
+pub fn build_token_transfer_instructions(
+    payer: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    decimals: u8,
+    recipients: &[(Pubkey, u64)],
+) -> Result<Vec<Instruction>, anyhow::Error> {
+    let mut instructions = Vec::with_capacity(recipients.len() * 2);
+    for (recipient, amount) in recipients {
+        if *amount == 0 {
+            return Err(anyhow!("transfer_token: Amount cannot be zero (recipient {})", recipient));
+        }
+        instructions.extend(create_associated_token_account_idempotent_fast(
+            payer,
+            recipient,
+            mint,
+            token_program,
+        ));
+        let destination =
+            get_associated_token_address_with_program_id_fast(recipient, mint, token_program);
+        instructions.push(transfer_checked(
+            token_program,
+            source,
+            mint,
+            &destination,
+            payer,
+            *amount,
+            decimals,
+            &[],
+        )?);
+    }
+    Ok(instructions)
+}
+
+/// Transfer SPL tokens from the payer's token account to `recipient`, creating the recipient ATA if needed.
+///
+/// `use_seed` must match how the payer's token account was created (see `TradingClient::use_seed_optimize`).
+pub async fn transfer_token(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    mint: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+    use_seed: bool,
+) -> Result<Signature, anyhow::Error> {
+    let signatures =
+        batch_transfer_token(rpc, payer, mint, &[(*recipient, amount)], use_seed).await?;
+    signatures.into_iter().next().ok_or_else(|| anyhow!("transfer_token: no transaction sent"))
+}
+
+/// Transfer SPL tokens to many recipients, chunked into [`MAX_TOKEN_TRANSFERS_PER_TX`] per transaction.
+///
+/// Chunks are sent and confirmed sequentially; on failure the error reports how many
+/// chunks already landed so the caller can resume from the remaining recipients.
+pub async fn batch_transfer_token(
+    rpc: &SolanaRpcClient,
+    payer: &Keypair,
+    mint: &Pubkey,
+    recipients: &[(Pubkey, u64)],
+    use_seed: bool,
+) -> Result<Vec<Signature>, anyhow::Error> {
+    if recipients.is_empty() {
+        return Err(anyhow!("batch_transfer_token: recipients cannot be empty"));
+    }
+
+    let (token_program, decimals) = get_mint_program_and_decimals(rpc, mint).await?;
+    let source = get_associated_token_address_with_program_id_fast_use_seed(
+        &payer.pubkey(),
+        mint,
+        &token_program,
+        use_seed,
+    );
+
+    let total: u128 = recipients.iter().map(|(_, amount)| *amount as u128).sum();
+    let balance = rpc.get_token_account_balance(&source).await?;
+    let balance =
+        balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
+    if (balance as u128) < total {
+        return Err(anyhow!("Insufficient token balance: {} < {}", balance, total));
+    }
+
+    let chunk_count = recipients.len().div_ceil(MAX_TOKEN_TRANSFERS_PER_TX);
+    let mut signatures = Vec::with_capacity(chunk_count);
+    for (index, chunk) in recipients.chunks(MAX_TOKEN_TRANSFERS_PER_TX).enumerate() {
+        let instructions = build_token_transfer_instructions(
+            &payer.pubkey(),
+            &source,
+            mint,
+            &token_program,
+            decimals,
+            chunk,
+        )?;
+        let recent_blockhash = rpc.get_latest_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            recent_blockhash,
+        );
+        let signature = rpc.send_and_confirm_transaction(&transaction).await.map_err(|e| {
+            anyhow!(
+                "batch_transfer_token failed at chunk {} ({} of {} chunks confirmed): {}",
+                index,
+                signatures.len(),
+                chunk_count,
+                e
+            )
+        })?;
+        signatures.push(signature);
+    }
+
+    Ok(signatures)
+}
+
 /// Close token account
 ///
 /// This function is used to close the associated token account for a specified token,
@@ -160,3 +248,59 @@ pub async fn close_token_account(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+
+    #[test]
+    fn token_transfers_create_the_ata_and_check_decimals_under_the_mint_program() {
+        let (payer, source, mint) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let recipients = [(Pubkey::new_unique(), 1_500), (Pubkey::new_unique(), 7)];
+
+        for token_program in [TOKEN_PROGRAM, TOKEN_PROGRAM_2022] {
+            let instructions = build_token_transfer_instructions(
+                &payer,
+                &source,
+                &mint,
+                &token_program,
+                6,
+                &recipients,
+            )
+            .unwrap();
+            assert_eq!(instructions.len(), 2 * recipients.len());
+            for (pair, (recipient, amount)) in instructions.chunks(2).zip(recipients) {
+                let (create, transfer) = (&pair[0], &pair[1]);
+                let destination = get_associated_token_address_with_program_id_fast(
+                    &recipient,
+                    &mint,
+                    &token_program,
+                );
+                assert_eq!(create.program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+                assert!(create.accounts.iter().any(|meta| meta.pubkey == token_program));
+                assert_eq!(create.accounts[1].pubkey, destination);
+
+                assert_eq!(transfer.program_id, token_program);
+                assert_eq!(transfer.data[0], 12);
+                assert_eq!(transfer.data[1..9], amount.to_le_bytes());
+                assert_eq!(transfer.data[9], 6);
+                let keys: Vec<Pubkey> = transfer.accounts.iter().map(|meta| meta.pubkey).collect();
+                assert_eq!(keys, [source, mint, destination, payer]);
+                assert!(transfer.accounts[3].is_signer);
+            }
+        }
+
+        let zero = [(Pubkey::new_unique(), 0)];
+        assert!(build_token_transfer_instructions(
+            &payer,
+            &source,
+            &mint,
+            &TOKEN_PROGRAM,
+            6,
+            &zero
+        )
+        .is_err());
+    }
+}
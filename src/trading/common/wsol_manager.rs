@@ -6,9 +6,94 @@ use crate::common::{
     },
     spl_token::close_account,
 };
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use smallvec::SmallVec;
 use solana_sdk::{instruction::AccountMeta, instruction::Instruction, pubkey::Pubkey};
 use solana_system_interface::instruction as system_instruction;
+use std::sync::Arc;
+use tokio::sync::{OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+
+/// What a trade does when another trade holds the payer WSOL ATA in a conflicting way (a close
+/// against users, or a use against a close).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WsolLockMode {
+    /// Wait until the other trade's transaction is sent.
+    #[default]
+    Wait,
+    /// Fail right away with [`crate::PreflightError::WsolAccountInUse`].
+    FailFast,
+}
+
+/// Per-payer lock on the shared WSOL ATA, across every client in the process. An entry is
+/// evicted once nobody holds or waits for it.
+static WSOL_LOCKS: Lazy<DashMap<Pubkey, Arc<RwLock<()>>>> = Lazy::new(DashMap::new);
+
+/// Access to a payer's WSOL ATA. Clones share it; it is released by [`Self::release`] (the
+/// executor calls it once the transaction is submitted) or when the last clone is dropped.
+#[derive(Clone)]
+pub struct WsolGuard {
+    held: Arc<parking_lot::Mutex<Option<HeldWsol>>>,
+}
+
+struct HeldWsol {
+    payer: Pubkey,
+    shared: Option<OwnedRwLockReadGuard<()>>,
+    exclusive: Option<OwnedRwLockWriteGuard<()>>,
+}
+
+impl Drop for HeldWsol {
+    fn drop(&mut self) {
+        drop((self.shared.take(), self.exclusive.take()));
+        // Only the map still references a lock no other trade holds or waits for.
+        WSOL_LOCKS.remove_if(&self.payer, |_, lock| Arc::strong_count(lock) == 1);
+    }
+}
+
+impl WsolGuard {
+    fn new(
+        payer: Pubkey,
+        shared: Option<OwnedRwLockReadGuard<()>>,
+        exclusive: Option<OwnedRwLockWriteGuard<()>>,
+    ) -> Self {
+        let held = HeldWsol { payer, shared, exclusive };
+        Self { held: Arc::new(parking_lot::Mutex::new(Some(held))) }
+    }
+
+    /// Let other trades use the account now, e.g. once this trade's transaction is sent.
+    pub fn release(&self) {
+        let held = self.held.lock().take();
+        drop(held);
+    }
+}
+
+/// Wait for the WSOL ATA of `payer`. Trades that wrap into or spend from it share access;
+/// closing or draining it (`exclusive`) waits until no other trade uses it, and holds later
+/// trades back until done, so an unwrap never lands between another trade's wrap and swap.
+pub(crate) async fn lock_wsol_account(payer: &Pubkey, exclusive: bool) -> WsolGuard {
+    let lock = WSOL_LOCKS.entry(*payer).or_default().clone();
+    if exclusive {
+        WsolGuard::new(*payer, None, Some(lock.write_owned().await))
+    } else {
+        WsolGuard::new(*payer, Some(lock.read_owned().await), None)
+    }
+}
+
+/// [`lock_wsol_account`] without waiting: `Err` carries the number of other trades holding or
+/// waiting for the account.
+pub(crate) fn try_lock_wsol_account(payer: &Pubkey, exclusive: bool) -> Result<WsolGuard, usize> {
+    let lock = WSOL_LOCKS.entry(*payer).or_default().clone();
+    let acquired = if exclusive {
+        lock.clone().try_write_owned().map(|guard| (None, Some(guard)))
+    } else {
+        lock.clone().try_read_owned().map(|guard| (Some(guard), None))
+    };
+    match acquired {
+        Ok((shared, exclusive)) => Ok(WsolGuard::new(*payer, shared, exclusive)),
+        // Every holder or waiter owns one reference besides the map's and this attempt's.
+        Err(_) => Err(Arc::strong_count(&lock).saturating_sub(2)),
+    }
+}
 
 #[inline]
 pub fn handle_wsol(payer: &Pubkey, amount_in: u64) -> SmallVec<[Instruction; 3]> {
@@ -198,3 +283,47 @@ pub fn wrap_wsol_to_sol_without_create(
 
     Ok(instructions)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn wsol_close_waits_for_shared_users() {
+        let payer = Pubkey::new_unique();
+        let user = lock_wsol_account(&payer, false).await;
+        let other = lock_wsol_account(&payer, false).await;
+
+        let close = tokio::spawn(async move { lock_wsol_account(&payer, true).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!close.is_finished());
+
+        drop((user, other));
+        let closer = close.await.unwrap();
+        let pending =
+            tokio::time::timeout(Duration::from_millis(20), lock_wsol_account(&payer, false)).await;
+        assert!(pending.is_err());
+        drop(closer);
+        // Other payers are never blocked.
+        let _ = lock_wsol_account(&Pubkey::new_unique(), true).await;
+    }
+
+    #[tokio::test]
+    async fn wsol_release_frees_the_account_and_evicts_idle_payers() {
+        let payer = Pubkey::new_unique();
+        let user = lock_wsol_account(&payer, false).await;
+        let in_executor = user.clone();
+        assert_eq!(try_lock_wsol_account(&payer, true).err(), Some(1));
+        let other = try_lock_wsol_account(&payer, false).unwrap();
+
+        // Released on send while the trade still holds its handle for confirmation.
+        in_executor.release();
+        assert_eq!(try_lock_wsol_account(&payer, true).err(), Some(1));
+        drop(other);
+        let closer = try_lock_wsol_account(&payer, true).unwrap();
+        assert_eq!(try_lock_wsol_account(&payer, false).err(), Some(1));
+        drop((user, closer));
+        assert!(!WSOL_LOCKS.contains_key(&payer));
+    }
+}
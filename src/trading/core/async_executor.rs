@@ -19,6 +19,7 @@ use solana_hash::Hash;
 use solana_message::AddressLookupTableAccount;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Keypair, signature::Signature,
+    transaction::VersionedTransaction,
 };
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
@@ -36,10 +37,25 @@ type FnvHashMap<K, V> = HashMap<K, V, BuildHasherDefault<FnvHasher>>;
 
 use crate::{
     common::gas_fee_strategy::{GasFeeStrategyType, GasFeeStrategyValue},
-    common::{nonce_cache::DurableNonceInfo, GasFeeStrategy, SwqosSubmitTiming},
-    swqos::{SwqosClient, SwqosType, TradeType},
-    trading::core::params::SenderConcurrencyConfig,
-    trading::{common::build_transaction, MiddlewareManager},
+    common::{
+        latency_budget::{LatencyBudget, LatencyStage},
+        nonce_cache::DurableNonceInfo,
+        ExplorerCluster, GasFeeStrategy, RawTransaction, SwqosSubmitTiming,
+    },
+    perf::thread_tuning::{installed_tuning, set_current_thread_priority, HotPathStage},
+    swqos::{
+        common::{RpcSendOptions, TradeError, RPC_SEND_OPTIONS},
+        SwqosClient, SwqosType, TradeType,
+    },
+    trading::core::{params::SenderConcurrencyConfig, signing_pool::SigningPool},
+    trading::{
+        common::{
+            build_transaction_with_signers, transaction_builder::compute_unit_price,
+            ComputeBudgetManager,
+        },
+        factory::DexType,
+        MiddlewareManager,
+    },
 };
 
 /// 与 transaction_pool::PARALLEL_SENDER_COUNT 一致，保证多路 build 不串行
@@ -52,18 +68,33 @@ const FAST_SUBMIT_DRAIN_GRACE: Duration = Duration::from_millis(20);
 /// Shared across all jobs in one batch; built once, cloned as single Arc per job (minimal hot-path clone).
 struct SwqosSharedContext {
     payer: Arc<Keypair>,
+    additional_signers: Vec<Arc<Keypair>>,
     instructions: Arc<Vec<Instruction>>,
     address_lookup_table_accounts: Arc<Vec<AddressLookupTableAccount>>,
     recent_blockhash: Option<Hash>,
     durable_nonce: Option<DurableNonceInfo>,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: &'static str,
+    compute_budget_manager: Option<Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
     is_buy: bool,
     wait_transaction_confirmed: bool,
     with_tip: bool,
+    latency_budget: Option<LatencyBudget>,
+    signing_pool: Option<Arc<SigningPool>>,
+    rpc_send_options: RpcSendOptions,
+    raw_transactions: Option<ExplorerCluster>,
     collector: Arc<ResultCollector>,
 }
 
+impl Drop for SwqosSharedContext {
+    /// Last lane done: the trade's instructions go back to the instruction pool.
+    fn drop(&mut self) {
+        if let Some(instructions) = Arc::get_mut(&mut self.instructions) {
+            crate::common::instruction_pool::recycle_instructions(std::mem::take(instructions));
+        }
+    }
+}
+
 /// One SWQOS submit task; only per-task data + one Arc to shared (reduces hot-path clones).
 struct SwqosJob {
     shared: Arc<SwqosSharedContext>,
@@ -78,6 +109,34 @@ struct SwqosJob {
     use_affinity: bool,
 }
 
+/// Build and sign one lane's transaction (sync; runs on the signing pool when configured).
+fn sign_lane_transaction(
+    s: &SwqosSharedContext,
+    unit_limit: u32,
+    unit_price: u64,
+    tip_account: &Pubkey,
+    tip_amount: f64,
+    swqos_type: SwqosType,
+) -> Result<VersionedTransaction> {
+    build_transaction_with_signers(
+        &s.payer,
+        &s.additional_signers,
+        unit_limit,
+        unit_price,
+        s.instructions.as_ref(),
+        s.address_lookup_table_accounts.as_slice(),
+        s.recent_blockhash,
+        s.middleware_manager.as_ref(),
+        s.compute_budget_manager.as_ref(),
+        Some(s.dex_type),
+        s.is_buy,
+        swqos_type != SwqosType::Default,
+        tip_account,
+        tip_amount,
+        s.durable_nonce.as_ref(),
+    )
+}
+
 async fn run_one_swqos_job(job: SwqosJob) {
     let s = &job.shared;
     if job.use_affinity {
@@ -87,22 +146,39 @@ async fn run_one_swqos_job(job: SwqosJob) {
     }
 
     let tip_amount = if s.with_tip { job.tip } else { 0.0 };
-
-    let transaction = match build_transaction(
-        &s.payer,
-        job.unit_limit,
-        job.unit_price,
-        s.instructions.as_ref(),
-        s.address_lookup_table_accounts.as_slice(),
-        s.recent_blockhash,
-        s.middleware_manager.as_ref(),
-        s.protocol_name,
-        s.is_buy,
-        job.swqos_type != SwqosType::Default,
-        &job.tip_account,
-        tip_amount,
-        s.durable_nonce.as_ref(),
-    ) {
+    let mut clock = s.latency_budget.as_ref().map(|budget| budget.lane(job.swqos_type));
+
+    let signed = match s.signing_pool.as_ref() {
+        Some(pool) => {
+            let (shared, tip_account) = (job.shared.clone(), job.tip_account.clone());
+            let (unit_limit, unit_price, swqos_type) =
+                (job.unit_limit, job.unit_price, job.swqos_type);
+            pool.run(move || {
+                let tx = sign_lane_transaction(
+                    &shared,
+                    unit_limit,
+                    unit_price,
+                    &tip_account,
+                    tip_amount,
+                    swqos_type,
+                )?;
+                // Serialize on the signing thread too; the provider reuses the shared encoding.
+                crate::swqos::serialization::encode_transaction_shared(&tx)?;
+                Ok(tx)
+            })
+            .await
+            .and_then(|signed| signed)
+        }
+        None => sign_lane_transaction(
+            s,
+            job.unit_limit,
+            job.unit_price,
+            &job.tip_account,
+            tip_amount,
+            job.swqos_type,
+        ),
+    };
+    let transaction = match signed {
         Ok(tx) => tx,
         Err(e) => {
             s.collector.submit(TaskResult {
@@ -113,27 +189,69 @@ async fn run_one_swqos_job(job: SwqosJob) {
                 strategy_type: job.strategy_type,
                 landed_on_chain: false,
                 submit_done_us: crate::common::clock::now_micros(),
+                raw_transaction: None,
+                cu_price: 0,
+                tip: tip_amount,
             });
             return;
         }
     };
-
-    let (success, err, landed_on_chain) = match job
-        .swqos_client
-        .send_transaction(
-            if s.is_buy { TradeType::Buy } else { TradeType::Sell },
-            &transaction,
-            s.wait_transaction_confirmed,
-        )
-        .await
-    {
-        Ok(()) => (true, None, true),
-        Err(e) => {
-            let landed = is_landed_error(&e);
-            (false, Some(e), landed)
+    let cu_price = compute_unit_price(&transaction);
+
+    // Only with a budget: encode here so serialization is timed on its own; the provider then
+    // reuses the shared encoding (already done by the signing pool when one is set).
+    let budget_error = clock.as_mut().and_then(|clock| {
+        clock
+            .end(LatencyStage::Sign)
+            .and_then(|()| {
+                let _ = crate::swqos::serialization::encode_transaction_shared(&transaction);
+                clock.end(LatencyStage::Serialize)
+            })
+            .err()
+    });
+    if let Some(e) = budget_error {
+        if let Some(clock) = clock {
+            clock.finish();
         }
-    };
+        s.collector.submit(TaskResult {
+            success: false,
+            signature: Signature::default(),
+            error: Some(anyhow::Error::new(e)),
+            swqos_type: job.swqos_type,
+            strategy_type: job.strategy_type,
+            landed_on_chain: false,
+            submit_done_us: crate::common::clock::now_micros(),
+            raw_transaction: None,
+            cu_price,
+            tip: tip_amount,
+        });
+        return;
+    }
+
+    let submit = job.swqos_client.send_transaction(
+        if s.is_buy { TradeType::Buy } else { TradeType::Sell },
+        &transaction,
+        s.wait_transaction_confirmed,
+    );
+    let (success, err, landed_on_chain) =
+        match RPC_SEND_OPTIONS.scope(s.rpc_send_options, submit).await {
+            Ok(()) => (true, None, true),
+            Err(e) => {
+                let landed = is_landed_error(&e);
+                (false, Some(e), landed)
+            }
+        };
 
+    if let Some(mut clock) = clock {
+        // Already sent: over budget here only warns.
+        let _ = clock.end(LatencyStage::Submit);
+        clock.finish();
+    }
+
+    let submit_done_us = crate::common::clock::now_micros();
+    // After the submit so the capture stays off the hot path; the encoding is usually cached.
+    let raw_transaction =
+        s.raw_transactions.and_then(|cluster| RawTransaction::capture(&transaction, cluster).ok());
     let sig = transaction.signatures.first().copied().unwrap_or_default();
     s.collector.submit(TaskResult {
         success,
@@ -142,7 +260,10 @@ async fn run_one_swqos_job(job: SwqosJob) {
         swqos_type: job.swqos_type,
         strategy_type: job.strategy_type,
         landed_on_chain,
-        submit_done_us: crate::common::clock::now_micros(),
+        submit_done_us,
+        raw_transaction,
+        cu_price,
+        tip: tip_amount,
     });
 }
 
@@ -288,6 +409,13 @@ fn ensure_dedicated_worker_count_locked(
             if let Some(cid) = core_id {
                 core_affinity::set_for_current(cid);
             }
+            if let Some(tuning) = installed_tuning(HotPathStage::SwqosSubmit) {
+                if let Err(e) = set_current_thread_priority(tuning.priority) {
+                    if crate::common::sdk_log::sdk_log_enabled() {
+                        tracing::warn!(target: "sol_trade_sdk", "Sender thread priority: {}", e);
+                    }
+                }
+            }
             let rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()
@@ -327,14 +455,18 @@ struct TaskResult {
     landed_on_chain: bool,
     /// Microsecond timestamp when this task finished (SWQOS returned); for per-SWQOS event→submit timing.
     submit_done_us: i64,
+    raw_transaction: Option<RawTransaction>,
+    cu_price: u64,
+    tip: f64,
 }
 
 /// Check if an error indicates the transaction landed on-chain (vs network/timeout error)
 fn is_landed_error(error: &anyhow::Error) -> bool {
-    use crate::swqos::common::TradeError;
-
     // If it's a TradeError with a non-zero code, the tx landed but failed on-chain
     if let Some(trade_error) = error.downcast_ref::<TradeError>() {
+        if trade_error.code == crate::swqos::common::CONFIRMATION_TIMEOUT_ERROR_CODE {
+            return false;
+        }
         // Code 500 with "timed out" message means tx never landed
         if trade_error.code == 500 && trade_error.message.contains("timed out") {
             return false;
@@ -535,27 +667,38 @@ impl ResultCollector {
     }
 }
 
-type GasFeeConfig = (SwqosType, GasFeeStrategyType, GasFeeStrategyValue);
+pub(crate) type GasFeeConfig = (SwqosType, GasFeeStrategyType, GasFeeStrategyValue);
 
 #[derive(Debug, Clone, Copy)]
-struct SwqosTaskConfig {
-    task_ordinal: usize,
-    swqos_index: usize,
-    gas_fee_config: GasFeeConfig,
+pub(crate) struct SwqosTaskConfig {
+    pub(crate) task_ordinal: usize,
+    pub(crate) swqos_index: usize,
+    pub(crate) gas_fee_config: GasFeeConfig,
 }
 
 impl TaskResult {
     #[inline]
     fn submit_timing(&self) -> SwqosSubmitTiming {
+        use crate::trading::common::transaction_builder::sol_f64_to_lamports;
+
         SwqosSubmitTiming {
             swqos_type: self.swqos_type,
             strategy_type: self.strategy_type,
             submit_done_us: self.submit_done_us,
+            signature: self.signature,
+            cu_price: self.cu_price,
+            tip_lamports: sol_f64_to_lamports(self.tip),
+            landed: None,
+            landed_slot: None,
+            block_time: None,
+            event_to_land_slots: None,
+            raw_transaction: self.raw_transaction.clone(),
         }
     }
 }
 
-fn select_swqos_task_configs(
+/// Lanes `execute_parallel` submits on; also used by [`crate::TradingClient::describe_buy`].
+pub(crate) fn select_swqos_task_configs(
     swqos_types: &[SwqosType],
     gas_fee_configs: &[GasFeeConfig],
     with_tip: bool,
@@ -592,18 +735,41 @@ fn select_swqos_task_configs(
     task_configs
 }
 
+/// `TipBelowMinimum` for the first tipped lane whose tip is under its provider's minimum, used
+/// when [`select_swqos_task_configs`] filtered out every lane.
+pub(crate) fn tip_below_minimum(
+    swqos_types: &[SwqosType],
+    gas_fee_configs: &[GasFeeConfig],
+    with_tip: bool,
+    min_tip_by_swqos: impl Fn(SwqosType) -> f64,
+) -> Option<TradeError> {
+    if !with_tip {
+        return None;
+    }
+    gas_fee_configs
+        .iter()
+        .filter(|config| config.0 != SwqosType::Default && swqos_types.contains(&config.0))
+        .find_map(|config| {
+            let min_tip = min_tip_by_swqos(config.0);
+            (config.2.tip < min_tip)
+                .then(|| TradeError::tip_below_minimum(config.0, config.2.tip, min_tip))
+        })
+}
+
 /// Execute trade on multiple SWQOS clients in parallel; returns success flag, all signatures, and last error.
 ///
 /// `sender_config` merges sender_thread_cores, effective_core_ids, max_sender_concurrency (precomputed at SDK init; no get_core_ids on hot path).
 pub async fn execute_parallel(
     swqos_clients: &[Arc<SwqosClient>],
     payer: Arc<Keypair>,
+    additional_signers: Vec<Arc<Keypair>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     recent_blockhash: Option<Hash>,
     durable_nonce: Option<DurableNonceInfo>,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: &'static str,
+    compute_budget_manager: Option<Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
     is_buy: bool,
     wait_transaction_confirmed: bool,
     wait_for_all_submits: bool,
@@ -612,9 +778,12 @@ pub async fn execute_parallel(
     use_dedicated_sender_threads: bool,
     sender_config: SenderConcurrencyConfig,
     check_min_tip: bool,
+    latency_budget: Option<LatencyBudget>,
 ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
     if swqos_clients.is_empty() {
-        return Err(anyhow!("swqos_clients is empty"));
+        return Err(anyhow::Error::new(TradeError::no_swqos_available(
+            "the client has no active SWQOS provider",
+        )));
     }
 
     if !with_tip
@@ -633,21 +802,27 @@ pub async fn execute_parallel(
         gas_fee_strategy.get_strategies(if is_buy { TradeType::Buy } else { TradeType::Sell });
     let swqos_types: Vec<SwqosType> =
         swqos_clients.iter().map(|swqos| swqos.get_swqos_type()).collect();
+    let min_tip_by_swqos = |swqos_type: SwqosType| {
+        swqos_clients
+            .iter()
+            .find(|swqos| swqos.get_swqos_type() == swqos_type)
+            .map(|swqos| swqos.min_tip_sol())
+            .unwrap_or(0.0)
+    };
     let selected_task_configs = select_swqos_task_configs(
         &swqos_types,
         &gas_fee_configs,
         with_tip,
         check_min_tip,
-        |swqos_type| {
-            swqos_clients
-                .iter()
-                .find(|swqos| swqos.get_swqos_type() == swqos_type)
-                .map(|swqos| swqos.min_tip_sol())
-                .unwrap_or(0.0)
-        },
+        &min_tip_by_swqos,
     );
 
     if selected_task_configs.is_empty() {
+        if let Some(error) =
+            tip_below_minimum(&swqos_types, &gas_fee_configs, with_tip, &min_tip_by_swqos)
+        {
+            return Err(anyhow::Error::new(error));
+        }
         return Err(anyhow!("No available gas fee strategy configs"));
     }
 
@@ -656,15 +831,21 @@ pub async fn execute_parallel(
     let collector = Arc::new(ResultCollector::new(channel_count));
     let shared = Arc::new(SwqosSharedContext {
         payer,
+        additional_signers,
         instructions,
         address_lookup_table_accounts: Arc::new(address_lookup_table_accounts),
         recent_blockhash,
         durable_nonce,
         middleware_manager,
-        protocol_name,
+        compute_budget_manager,
+        dex_type,
         is_buy,
         wait_transaction_confirmed,
         with_tip,
+        latency_budget,
+        signing_pool: sender_config.signing_pool.clone(),
+        rpc_send_options: sender_config.rpc_send_options,
+        raw_transactions: sender_config.raw_transactions,
         collector: collector.clone(),
     });
 
@@ -728,6 +909,9 @@ pub async fn execute_parallel(
                     strategy_type: job.strategy_type,
                     landed_on_chain: false,
                     submit_done_us: crate::common::clock::now_micros(),
+                    raw_transaction: None,
+                    cu_price: 0,
+                    tip: job.tip,
                 });
             }
         }
@@ -820,6 +1004,25 @@ mod tests {
         assert_eq!(selected[0].gas_fee_config.1, GasFeeStrategyType::HighTipLowCuPrice);
     }
 
+    #[test]
+    fn tip_below_minimum_names_provider_and_minimum() {
+        let swqos_types = [SwqosType::Lightspeed, SwqosType::Default];
+        let configs = [
+            (SwqosType::Default, GasFeeStrategyType::Normal, value(700_000, 0.0)),
+            (SwqosType::Lightspeed, GasFeeStrategyType::Normal, value(400_000, 0.0005)),
+        ];
+        let min_tip = |swqos_type: SwqosType| swqos_type.min_tip_sol();
+
+        assert!(
+            select_swqos_task_configs(&swqos_types[..1], &configs, true, true, min_tip).is_empty()
+        );
+        let error = tip_below_minimum(&swqos_types, &configs, true, min_tip).unwrap();
+        assert_eq!(error.code, crate::swqos::common::TIP_BELOW_MINIMUM_ERROR_CODE);
+        assert!(error.message.starts_with("TipBelowMinimum: Lightspeed"));
+        assert!(error.message.contains("0.001 SOL"));
+        assert!(tip_below_minimum(&swqos_types, &configs, false, min_tip).is_none());
+    }
+
     #[test]
     fn select_task_configs_without_tip_keeps_default_priority_fee_only() {
         let swqos_types = [SwqosType::Jito, SwqosType::Default];
@@ -3,6 +3,7 @@ use solana_hash::Hash;
 use solana_message::AddressLookupTableAccount;
 use solana_sdk::{
     instruction::Instruction, pubkey::Pubkey, signature::Keypair, signature::Signature,
+    transaction::VersionedTransaction,
 };
 use std::{
     sync::Arc,
@@ -12,41 +13,40 @@ use std::{
 use tracing::{info, trace, warn};
 
 use super::{params::SwapParams, traits::InstructionBuilder};
-use crate::swqos::TradeType;
+use crate::swqos::{SwqosClient, SwqosType, TradeType};
 use crate::{
-    common::{nonce_cache::DurableNonceInfo, GasFeeStrategy, SolanaRpcClient, SwqosSubmitTiming},
-    perf::syscall_bypass::SystemCallBypassManager,
-    swqos::common::poll_any_transaction_confirmation,
+    common::{
+        nonce_cache::DurableNonceInfo,
+        rpc_usage::{with_rpc_scope, RPC_SCOPE_CONFIRMATION},
+        ExplorerCluster, GasFeeStrategy, GasFeeStrategyType, GasFeeStrategyValue, RawTransaction,
+        SolanaRpcClient, SwqosSubmitTiming, TransactionSplit,
+    },
+    swqos::common::poll_any_transaction_landing,
     trading::core::{
         async_executor::execute_parallel,
         execution::{InstructionProcessor, Prefetch},
         traits::TradeExecutor,
     },
-    trading::MiddlewareManager,
+    trading::{
+        common::{transaction_builder::compute_unit_price, ComputeBudgetManager},
+        factory::DexType,
+        fee_sponsor::{verify_sponsored, FeeSponsor, SendMode},
+        MiddlewareManager,
+    },
 };
-use once_cell::sync::Lazy;
-
-/// Global syscall bypass manager (reserved for future time/IO optimizations).
-/// 全局系统调用绕过管理器（预留，后续可接入时间/IO 等优化）。
-#[allow(dead_code)]
-static SYSCALL_BYPASS: Lazy<SystemCallBypassManager> = Lazy::new(|| {
-    use crate::perf::syscall_bypass::SyscallBypassConfig;
-    SystemCallBypassManager::new(SyscallBypassConfig::default())
-        .expect("Failed to create SystemCallBypassManager")
-});
 
 /// Generic trade executor implementation
 pub struct GenericTradeExecutor {
-    instruction_builder: Arc<dyn InstructionBuilder>,
-    protocol_name: &'static str,
+    instruction_builder: &'static dyn InstructionBuilder,
+    dex_type: DexType,
 }
 
 impl GenericTradeExecutor {
-    pub fn new(
-        instruction_builder: Arc<dyn InstructionBuilder>,
-        protocol_name: &'static str,
+    pub const fn new(
+        instruction_builder: &'static dyn InstructionBuilder,
+        dex_type: DexType,
     ) -> Self {
-        Self { instruction_builder, protocol_name }
+        Self { instruction_builder, dex_type }
     }
 }
 
@@ -58,7 +58,10 @@ impl TradeExecutor for GenericTradeExecutor {
     ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
         // Sample total start only when logging or simulate. 仅在有日志或 simulate 时取起点。
         let total_start = (params.log_enabled || params.simulate).then(Instant::now);
-        let timing_start_us: Option<i64> = if params.log_enabled {
+        // Latency observers see live trades only. 延迟观察者只统计真实发送。
+        let observed =
+            !params.simulate && crate::perf::latency_observer::latency_observers_active();
+        let timing_start_us: Option<i64> = if params.log_enabled || observed {
             Some(params.grpc_recv_us.unwrap_or_else(crate::common::clock::now_micros))
         } else {
             None
@@ -66,11 +69,23 @@ impl TradeExecutor for GenericTradeExecutor {
 
         let is_buy =
             params.trade_type == TradeType::Buy || params.trade_type == TradeType::CreateAndBuy;
+        let params = match params.send_mode {
+            SendMode::JitoTipOnly => params.into_jito_tip_only()?,
+            _ => params,
+        };
 
         Prefetch::keypair(&params.payer);
 
+        // Budget clock starts at event receive when known, so queueing before the SDK counts.
+        let budget_build_start_us = params.latency_budget.as_ref().map(|budget| {
+            let now_us = crate::common::clock::now_micros();
+            budget.start(params.grpc_recv_us.unwrap_or(now_us));
+            now_us
+        });
+
         // Time build only when log_enabled to avoid cold-path syscalls. 仅 log_enabled 时计时，减少冷路径 syscall。
         let build_start = params.log_enabled.then(Instant::now);
+        params.validate_output_amounts()?;
         let instructions = if is_buy {
             self.instruction_builder.build_buy_instructions(&params).await?
         } else {
@@ -80,38 +95,101 @@ impl TradeExecutor for GenericTradeExecutor {
 
         InstructionProcessor::preprocess(&instructions)?;
 
-        let final_instructions = match &params.middleware_manager {
+        let mut final_instructions = match &params.middleware_manager {
             Some(middleware_manager) => middleware_manager
                 .apply_middlewares_process_protocol_instructions(
                     instructions,
-                    self.protocol_name,
+                    self.dex_type,
                     is_buy,
                 )?,
             None => instructions,
         };
+        // Counted before the caller's pre-instructions go in, so those stay with the swap.
+        let setup_len = crate::trading::common::leading_setup_len(&final_instructions);
+        crate::trading::common::splice_extra_instructions(
+            &mut final_instructions,
+            &params.pre_instructions,
+            &params.post_instructions,
+        );
+        if let Some(bytes) = params.data_size_limit {
+            crate::trading::common::append_data_size_limit_instruction(
+                &mut final_instructions,
+                bytes,
+            );
+        }
+        if let Some(bytes) = params.heap_frame_bytes {
+            crate::trading::common::append_heap_frame_instruction(&mut final_instructions, bytes);
+        }
+        if let Some(tag) = params.memo.as_deref() {
+            crate::trading::common::append_memo_instruction(&mut final_instructions, tag)?;
+        }
+
+        // Simulation is not latency-sensitive; the budget applies to live sends only.
+        if let (Some(budget), Some(build_start_us), false) =
+            (params.latency_budget.as_ref(), budget_build_start_us, params.simulate)
+        {
+            if let Err(e) = budget.end_build(build_start_us, crate::common::clock::now_micros()) {
+                return Ok((false, Vec::new(), Some(anyhow::Error::new(e)), Vec::new()));
+            }
+        }
 
-        let build_end_us = (params.log_enabled && crate::common::sdk_log::sdk_log_enabled())
+        let build_end_us = ((params.log_enabled && crate::common::sdk_log::sdk_log_enabled())
+            || observed)
             .then(crate::common::clock::now_micros);
         let _before_submit_elapsed =
             total_start.as_ref().map(|s| s.elapsed()).unwrap_or(Duration::ZERO);
-        let before_submit_us = (params.log_enabled && crate::common::sdk_log::sdk_log_enabled())
+        let before_submit_us = ((params.log_enabled && crate::common::sdk_log::sdk_log_enabled())
+            || observed)
             .then(crate::common::clock::now_micros);
         let address_lookup_table_accounts = params.address_lookup_table_accounts.clone();
+        let (fee_payer, signers) = params.transaction_signers();
+
+        // A trade too large for one transaction sends its ATA creation / SOL wrapping first: on
+        // its own before the swap (Sequential) or ahead of it in a Jito bundle (JitoBundle).
+        // `final_instructions` keeps the swap half; both halves are sized before anything runs.
+        let split = match (&params.backrun_target, params.send_mode.sponsor()) {
+            (None, None) => match plan_split(
+                &params,
+                &fee_payer,
+                &mut final_instructions,
+                setup_len,
+                self.dex_type,
+                is_buy,
+            ) {
+                Ok(split) => split,
+                Err(e) => return Ok((false, Vec::new(), Some(e), Vec::new())),
+            },
+            _ => None,
+        };
+        let setup_keypairs =
+            split.as_ref().map(|split| setup_signers(&signers, &split.setup)).unwrap_or_default();
 
         if params.simulate {
             let send_start = crate::common::sdk_log::sdk_log_enabled().then(Instant::now);
+            // `simulateTransaction` runs one transaction against the current state, so the swap
+            // half would always fail on the accounts the setup half has not created yet.
+            if split.is_some() {
+                let e = crate::swqos::common::TradeError::simulation_unsupported(
+                    "trade is split into setup and swap transactions; the swap cannot be \
+                     simulated before the setup lands",
+                );
+                return Ok((false, Vec::new(), Some(e), Vec::new()));
+            }
             let result = simulate_transaction(
                 params.rpc,
-                params.payer,
+                fee_payer,
+                signers,
                 final_instructions,
                 address_lookup_table_accounts,
                 params.recent_blockhash,
                 params.durable_nonce,
                 params.middleware_manager,
-                self.protocol_name,
+                params.compute_budget_manager,
+                self.dex_type,
                 is_buy,
                 if is_buy { true } else { params.with_tip },
                 params.gas_fee_strategy,
+                params.simulation_prelude,
             )
             .await;
             let send_elapsed = send_start.map(|s| s.elapsed()).unwrap_or(Duration::ZERO);
@@ -157,50 +235,230 @@ impl TradeExecutor for GenericTradeExecutor {
             return result;
         }
 
+        // Preflight gate (not for backrun: the bundle target is not on-chain yet, so simulation would fail).
+        let gate = params.simulation_gate.filter(|_| params.backrun_target.is_none());
+        let mut bundle_head = params.backrun_target.clone();
+        if let Some(split) = &split {
+            if let Some(max_latency) = gate {
+                if let Some(e) = simulation_gate(
+                    max_latency,
+                    params.rpc.as_deref(),
+                    &fee_payer,
+                    &setup_keypairs,
+                    &split.setup,
+                    &address_lookup_table_accounts,
+                    params.recent_blockhash,
+                    None,
+                    None,
+                    None,
+                    self.dex_type,
+                    is_buy,
+                    false,
+                    &params.gas_fee_strategy,
+                )
+                .await
+                {
+                    return Ok((false, Vec::new(), Some(e), Vec::new()));
+                }
+            }
+            // The setup half is the order's first send.
+            if let Some(claim) = &params.order_claim {
+                claim.claim()?;
+            }
+            let setup_result = match (params.transaction_split, params.recent_blockhash) {
+                (_, None) => Err(anyhow::anyhow!(
+                    "Splitting an oversized trade needs a recent blockhash for the setup"
+                )),
+                (TransactionSplit::JitoBundle, Some(blockhash)) => build_setup_transaction(
+                    &fee_payer,
+                    &setup_keypairs,
+                    &split.setup,
+                    &address_lookup_table_accounts,
+                    blockhash,
+                    self.dex_type,
+                    is_buy,
+                )
+                .map(Some),
+                (_, Some(blockhash)) => land_split_transaction(
+                    params.rpc.as_deref(),
+                    params.swqos_clients.as_slice(),
+                    params.confirmation,
+                    &fee_payer,
+                    &setup_keypairs,
+                    &split.setup,
+                    &address_lookup_table_accounts,
+                    blockhash,
+                    self.dex_type,
+                    is_buy,
+                )
+                .await
+                .map(|_| None),
+            };
+            match setup_result {
+                Ok(head) => bundle_head = head,
+                Err(e) => return Ok((false, Vec::new(), Some(e), Vec::new())),
+            }
+        }
+        // A bundled swap half cannot be simulated before its setup has run; a sequential one is
+        // gated now that the setup has landed.
+        let bundled_split =
+            split.is_some() && params.transaction_split == TransactionSplit::JitoBundle;
+        if let (Some(max_latency), false) = (gate, bundled_split) {
+            if let Some(e) = simulation_gate(
+                max_latency,
+                params.rpc.as_deref(),
+                &fee_payer,
+                &signers,
+                &final_instructions,
+                &address_lookup_table_accounts,
+                params.recent_blockhash,
+                params.durable_nonce.as_ref(),
+                params.middleware_manager.as_ref(),
+                params.compute_budget_manager.as_ref(),
+                self.dex_type,
+                is_buy,
+                if is_buy { true } else { params.with_tip },
+                &params.gas_fee_strategy,
+            )
+            .await
+            {
+                if let Some(split) = &split {
+                    clean_up_split(
+                        params.rpc.as_deref(),
+                        params.swqos_clients.as_slice(),
+                        params.confirmation,
+                        &fee_payer,
+                        &signers,
+                        split,
+                        self.dex_type,
+                        is_buy,
+                    )
+                    .await;
+                }
+                return Ok((false, Vec::new(), Some(e), Vec::new()));
+            }
+        }
+
+        // Claimed only now, so every local rejection above leaves the order id free.
+        if let Some(claim) = &params.order_claim {
+            claim.claim()?;
+        }
         let need_confirm = params.wait_tx_confirmed;
         // Each SWQOS lane may submit a distinct transaction because relay tips
         // can use different accounts, so confirmation must be able to poll every
         // returned signature when the caller opts in.
         let wait_for_all_submits = params.wait_for_all_submits;
         let sender_config = params.sender_concurrency_config();
-        let result = execute_parallel(
-            params.swqos_clients.as_slice(),
-            params.payer,
-            final_instructions,
-            address_lookup_table_accounts,
-            params.recent_blockhash,
-            params.durable_nonce,
-            params.middleware_manager,
-            self.protocol_name,
-            is_buy,
-            false, // submit only here; confirmation and log timing handled below
-            wait_for_all_submits,
-            if is_buy { true } else { params.with_tip },
-            params.gas_fee_strategy,
-            params.use_dedicated_sender_threads,
-            sender_config,
-            params.check_min_tip,
-        )
-        .await;
+        let result = match (bundle_head, params.send_mode.sponsor()) {
+            (Some(target), _) => {
+                submit_backrun_bundle(
+                    params.swqos_clients.as_slice(),
+                    &fee_payer,
+                    &signers,
+                    &final_instructions,
+                    &address_lookup_table_accounts,
+                    params.recent_blockhash,
+                    params.durable_nonce.as_ref(),
+                    params.middleware_manager.as_ref(),
+                    params.compute_budget_manager.as_ref(),
+                    self.dex_type,
+                    is_buy,
+                    &params.gas_fee_strategy,
+                    &target,
+                    params.raw_transactions,
+                )
+                .await
+            }
+            (None, Some(_)) if params.fee_payer.is_some() => {
+                Err(anyhow::anyhow!("fee_payer and a fee sponsor are mutually exclusive"))
+            }
+            (None, Some(sponsor)) => {
+                send_sponsored(
+                    sponsor.as_ref(),
+                    params.swqos_clients.as_slice(),
+                    &signers,
+                    &final_instructions,
+                    &address_lookup_table_accounts,
+                    params.recent_blockhash,
+                    params.durable_nonce.as_ref(),
+                    params.middleware_manager.as_ref(),
+                    params.compute_budget_manager.as_ref(),
+                    self.dex_type,
+                    is_buy,
+                    &params.gas_fee_strategy,
+                    params.raw_transactions,
+                )
+                .await
+            }
+            (None, None) => {
+                execute_parallel(
+                    params.swqos_clients.as_slice(),
+                    fee_payer,
+                    signers,
+                    final_instructions,
+                    address_lookup_table_accounts,
+                    params.recent_blockhash,
+                    params.durable_nonce,
+                    params.middleware_manager,
+                    params.compute_budget_manager,
+                    self.dex_type,
+                    is_buy,
+                    false, // submit only here; confirmation and log timing handled below
+                    wait_for_all_submits,
+                    if is_buy { true } else { params.with_tip },
+                    params.gas_fee_strategy,
+                    params.use_dedicated_sender_threads,
+                    sender_config,
+                    params.check_min_tip,
+                    params.latency_budget,
+                )
+                .await
+            }
+        };
 
+        // Submitted: other trades may use the WSOL account while this one confirms.
+        if let Some(guard) = &params.wsol_guard {
+            guard.release();
+        }
         let log_enabled = params.log_enabled && crate::common::sdk_log::sdk_log_enabled();
 
-        let (ok, signatures, err, submit_timings) = match result {
+        let (ok, signatures, err, mut submit_timings) = match result {
             Ok((success, sigs, last_error, timings)) => {
                 (success, sigs, last_error.map(|e| anyhow::anyhow!("{}", e)), timings)
             }
-            Err(e) => (false, vec![], Some(anyhow::anyhow!("{}", e)), vec![]),
+            Err(e) => (false, vec![], Some(e), vec![]),
         };
         // submit_timings 为完成先后顺序（先完成的先 push），打印不排序、不增加延迟
-        let submit_timings_ref: &[SwqosSubmitTiming] = submit_timings.as_slice();
-
         let result = if need_confirm {
             let confirm_result = if let Some(rpc) = params.rpc.as_ref() {
-                if signatures.is_empty() {
+                if signatures.is_empty() && params.prior_signatures.is_empty() {
                     (ok, signatures, err)
                 } else {
-                    let poll_res = poll_any_transaction_confirmation(rpc, &signatures, true).await;
-                    let confirm_done_us = log_enabled.then(crate::common::clock::now_micros);
+                    // Earlier attempts of the same order are watched too; the first signature to
+                    // land resolves the order and the others stop being polled.
+                    let mut watched = signatures.clone();
+                    for signature in &params.prior_signatures {
+                        if !watched.contains(signature) {
+                            watched.push(*signature);
+                        }
+                    }
+                    let poll_res =
+                        poll_any_transaction_landing(rpc, &watched, params.confirmation).await;
+                    let confirm_done_us =
+                        (log_enabled || observed).then(crate::common::clock::now_micros);
+                    mark_landed_lane(&mut submit_timings, &poll_res);
+                    if let Ok((signature, _, slot)) = &poll_res {
+                        let block_time =
+                            with_rpc_scope(RPC_SCOPE_CONFIRMATION, rpc.get_block_time(*slot))
+                                .await
+                                .ok();
+                        for timing in submit_timings.iter_mut() {
+                            if timing.signature == *signature {
+                                timing.landed_slot = Some(*slot);
+                                timing.block_time = block_time;
+                            }
+                        }
+                    }
                     if log_enabled {
                         let dir = if is_buy { "Buy" } else { "Sell" };
                         crate::common::sdk_log::print_sdk_timing_block(
@@ -208,11 +466,29 @@ impl TradeExecutor for GenericTradeExecutor {
                             timing_start_us,
                             build_end_us,
                             before_submit_us,
-                            submit_timings_ref,
+                            &submit_timings,
+                            confirm_done_us,
+                        );
+                    }
+                    if let (true, Some(start_us)) = (observed, timing_start_us) {
+                        crate::perf::latency_observer::publish_trade_latency(
+                            self.dex_type,
+                            params.trade_type,
+                            start_us,
+                            build_end_us,
+                            before_submit_us,
+                            &submit_timings,
                             confirm_done_us,
                         );
                     }
                     match poll_res {
+                        // An earlier attempt landed: report its signature first.
+                        Ok((landed, ..)) if !signatures.contains(&landed) => {
+                            let mut resolved = Vec::with_capacity(signatures.len() + 1);
+                            resolved.push(landed);
+                            resolved.extend(signatures);
+                            (true, resolved, None)
+                        }
                         Ok(_) => (true, signatures, None),
                         Err(e) => (false, signatures, Some(e)),
                     }
@@ -232,7 +508,18 @@ impl TradeExecutor for GenericTradeExecutor {
                     timing_start_us,
                     build_end_us,
                     before_submit_us,
-                    submit_timings_ref,
+                    &submit_timings,
+                    None,
+                );
+            }
+            if let (true, Some(start_us)) = (observed, timing_start_us) {
+                crate::perf::latency_observer::publish_trade_latency(
+                    self.dex_type,
+                    params.trade_type,
+                    start_us,
+                    build_end_us,
+                    before_submit_us,
+                    &submit_timings,
                     None,
                 );
             }
@@ -240,12 +527,588 @@ impl TradeExecutor for GenericTradeExecutor {
             Ok((ok, signatures, err, submit_timings))
         };
 
+        // A failed sequential swap would strand what the landed setup wrapped and created.
+        if let (Some(split), Ok((false, ..))) = (&split, &result) {
+            if params.transaction_split == TransactionSplit::Sequential {
+                clean_up_split(
+                    params.rpc.as_deref(),
+                    params.swqos_clients.as_slice(),
+                    params.confirmation,
+                    &fee_payer,
+                    &signers,
+                    split,
+                    self.dex_type,
+                    is_buy,
+                )
+                .await;
+            }
+        }
+
         result
     }
 
-    fn protocol_name(&self) -> &'static str {
-        self.protocol_name
+    fn dex_type(&self) -> DexType {
+        self.dex_type
+    }
+}
+
+/// Bundle mode: build one Jito-tipped transaction and submit `[target, tx]` as a single bundle.
+/// `target` is a backrun target or the setup half of a split trade.
+/// 跟单 backrun：构建一笔 Jito 小费交易，与目标交易一起以 bundle 提交，保证同块紧随目标成交。
+#[allow(clippy::too_many_arguments)]
+async fn submit_backrun_bundle(
+    swqos_clients: &[Arc<SwqosClient>],
+    payer: &Arc<Keypair>,
+    additional_signers: &[Arc<Keypair>],
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    durable_nonce: Option<&DurableNonceInfo>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
+    is_buy: bool,
+    gas_fee_strategy: &GasFeeStrategy,
+    target: &VersionedTransaction,
+    raw_transactions: Option<ExplorerCluster>,
+) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
+    use crate::trading::common::build_transaction_with_signers;
+    use std::str::FromStr;
+
+    let jito = swqos_clients
+        .iter()
+        .find(|client| client.get_swqos_type() == SwqosType::Jito)
+        .ok_or_else(|| anyhow::anyhow!("Jito bundles require a Jito SWQOS client"))?;
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let strategies = gas_fee_strategy.get_strategies(trade_type);
+    let (_, strategy_type, value) = strategies
+        .iter()
+        .filter(|(swqos_type, _, _)| *swqos_type == SwqosType::Jito)
+        .min_by_key(|(_, strategy_type, _)| *strategy_type != GasFeeStrategyType::Normal)
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("No Jito gas fee strategy found for the bundle"))?;
+    let tip_account = Pubkey::from_str(&jito.get_tip_account()?)?;
+
+    let transaction = build_transaction_with_signers(
+        payer,
+        additional_signers,
+        value.cu_limit,
+        value.cu_price,
+        instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        Some(dex_type),
+        is_buy,
+        true,
+        &tip_account,
+        value.tip,
+        durable_nonce,
+    )?;
+    let signature = transaction.signatures[0];
+    let bundle = vec![target.clone(), transaction];
+    let submit_result = jito.send_transactions(trade_type, &bundle, false).await;
+    let timing = SwqosSubmitTiming {
+        swqos_type: SwqosType::Jito,
+        strategy_type,
+        submit_done_us: crate::common::clock::now_micros(),
+        signature,
+        cu_price: compute_unit_price(&transaction),
+        tip_lamports: crate::trading::common::transaction_builder::sol_f64_to_lamports(value.tip),
+        landed: None,
+        landed_slot: None,
+        block_time: None,
+        event_to_land_slots: None,
+        raw_transaction: raw_transactions
+            .and_then(|cluster| RawTransaction::capture(&bundle[1], cluster).ok()),
+    };
+    match submit_result {
+        Ok(()) => Ok((true, vec![signature], None, vec![timing])),
+        Err(e) => Ok((false, vec![signature], Some(e), vec![timing])),
+    }
+}
+
+/// Any account outside a trade's message; the tip transfer of the size probe goes to it.
+const PROBE_TIP_ACCOUNT: Pubkey = Pubkey::new_from_array([1; 32]);
+
+/// A trade split into a setup transaction sent ahead of the swap; see [`split_trade`].
+#[derive(Debug, Clone, PartialEq)]
+struct TradeSplit {
+    /// ATA creations and SOL wrapping, sent first.
+    setup: Vec<Instruction>,
+    /// Closes of the accounts the setup funds, sent alone when a sequential swap fails.
+    cleanup: Vec<Instruction>,
+}
+
+/// [`split_trade`] with the trade's own sizes: the swap half probed with the first gas strategy
+/// and the largest tip, the setup half as [`build_setup_transaction`] builds it. `None` when
+/// splitting is off.
+fn plan_split(
+    params: &SwapParams,
+    fee_payer: &Arc<Keypair>,
+    instructions: &mut Vec<Instruction>,
+    setup_len: usize,
+    dex_type: DexType,
+    is_buy: bool,
+) -> Result<Option<TradeSplit>> {
+    use crate::trading::common::transaction_size;
+
+    if params.transaction_split == TransactionSplit::Off {
+        return Ok(None);
+    }
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let strategies = params.gas_fee_strategy.get_strategies(trade_type);
+    let Some((_, _, value)) = strategies.first().copied() else {
+        return Ok(None);
+    };
+    let tip = strategies.iter().map(|(_, _, value)| value.tip).fold(0.0, f64::max);
+    let alts = &params.address_lookup_table_accounts;
+    split_trade(instructions, setup_len, |instructions, is_setup| {
+        if is_setup {
+            transaction_size(
+                fee_payer,
+                0,
+                0,
+                instructions,
+                alts,
+                params.recent_blockhash,
+                None,
+                None,
+                Some(dex_type),
+                is_buy,
+                false,
+                &Pubkey::default(),
+                0.0,
+                None,
+            )
+        } else {
+            transaction_size(
+                fee_payer,
+                value.cu_limit,
+                value.cu_price,
+                instructions,
+                alts,
+                params.recent_blockhash,
+                params.middleware_manager.as_ref(),
+                params.compute_budget_manager.as_ref(),
+                Some(dex_type),
+                is_buy,
+                is_buy || params.with_tip,
+                &PROBE_TIP_ACCOUNT,
+                tip,
+                params.durable_nonce.as_ref(),
+            )
+        }
+    })
+}
+
+/// Split the leading setup (see [`leading_setup_len`]) off `instructions` when the whole trade
+/// does not fit in one packet, leaving the swap half in `instructions`. `size_of(instructions,
+/// is_setup)` measures a transaction built from them. Both halves must fit, so a trade too large
+/// either way fails before anything is sent; a failed probe of the whole trade is left to the
+/// normal build to report.
+///
+/// [`leading_setup_len`]: crate::trading::common::leading_setup_len
+fn split_trade(
+    instructions: &mut Vec<Instruction>,
+    setup_len: usize,
+    mut size_of: impl FnMut(&[Instruction], bool) -> Result<usize>,
+) -> Result<Option<TradeSplit>> {
+    use crate::trading::common::{
+        fits_in_packet, setup_cleanup_instructions, take_setup_instructions,
+    };
+
+    if setup_len == 0 || setup_len >= instructions.len() {
+        return Ok(None);
+    }
+    match size_of(instructions, false) {
+        Ok(size) if !fits_in_packet(size) => {}
+        _ => return Ok(None),
+    }
+    let mut swap = instructions.clone();
+    let setup = take_setup_instructions(&mut swap, setup_len);
+    if setup.is_empty() {
+        return Ok(None);
+    }
+    let (setup_size, swap_size) = (size_of(&setup, true)?, size_of(&swap, false)?);
+    if !fits_in_packet(setup_size) || !fits_in_packet(swap_size) {
+        anyhow::bail!(
+            "Trade does not fit in one transaction even when split: setup {} bytes, swap {} bytes",
+            setup_size,
+            swap_size
+        );
     }
+    let cleanup = setup_cleanup_instructions(&swap, &setup);
+    *instructions = swap;
+    Ok(Some(TradeSplit { setup, cleanup }))
+}
+
+/// Signers among `signers` that the setup instructions require (the fee payer signs anyway).
+fn setup_signers(signers: &[Arc<Keypair>], setup: &[Instruction]) -> Vec<Arc<Keypair>> {
+    use solana_sdk::signer::Signer;
+
+    signers
+        .iter()
+        .filter(|signer| {
+            let pubkey = signer.pubkey();
+            setup
+                .iter()
+                .flat_map(|ix| &ix.accounts)
+                .any(|meta| meta.is_signer && meta.pubkey == pubkey)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Setup (or cleanup) half of a split trade: no CU settings or tip, and the recent blockhash (a
+/// durable nonce stays with the swap). In a Jito bundle the swap transaction carries the tip.
+fn build_setup_transaction(
+    fee_payer: &Arc<Keypair>,
+    signers: &[Arc<Keypair>],
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+    dex_type: DexType,
+    is_buy: bool,
+) -> Result<VersionedTransaction> {
+    crate::trading::common::build_transaction_with_signers(
+        fee_payer,
+        signers,
+        0,
+        0,
+        instructions,
+        address_lookup_table_accounts,
+        Some(recent_blockhash),
+        None,
+        None,
+        Some(dex_type),
+        is_buy,
+        false,
+        &Pubkey::default(),
+        0.0,
+        None,
+    )
+}
+
+/// Sequential split: send `instructions` untipped through the `Default` (RPC) lane only and wait
+/// until they land. Used for the setup ahead of the swap and the cleanup after a failed one.
+#[allow(clippy::too_many_arguments)]
+async fn land_split_transaction(
+    rpc: Option<&SolanaRpcClient>,
+    swqos_clients: &[Arc<SwqosClient>],
+    confirmation: crate::swqos::common::ConfirmationOptions,
+    fee_payer: &Arc<Keypair>,
+    signers: &[Arc<Keypair>],
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+    dex_type: DexType,
+    is_buy: bool,
+) -> Result<Signature> {
+    let rpc = rpc.ok_or_else(|| {
+        anyhow::anyhow!("Splitting an oversized trade needs an RPC client to confirm the setup")
+    })?;
+    let lane = swqos_clients
+        .iter()
+        .find(|client| client.get_swqos_type() == SwqosType::Default)
+        .ok_or_else(|| {
+            anyhow::Error::new(crate::swqos::common::TradeError::no_swqos_available(
+                "a split trade sends its setup through the Default (RPC) route",
+            ))
+        })?;
+    let transaction = build_setup_transaction(
+        fee_payer,
+        signers,
+        instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        dex_type,
+        is_buy,
+    )?;
+    let signature = transaction.signatures[0];
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    lane.send_transaction(trade_type, &transaction, false).await?;
+    poll_any_transaction_landing(rpc, &[signature], confirmation)
+        .await
+        .map_err(|e| anyhow::anyhow!("Setup transaction did not land: {}", e))?;
+    Ok(signature)
+}
+
+/// Close the accounts a landed setup funded after the sequential swap failed, on a fresh
+/// blockhash (the trade's may have expired by now). Best effort: a failure is only logged.
+#[allow(clippy::too_many_arguments)]
+async fn clean_up_split(
+    rpc: Option<&SolanaRpcClient>,
+    swqos_clients: &[Arc<SwqosClient>],
+    confirmation: crate::swqos::common::ConfirmationOptions,
+    fee_payer: &Arc<Keypair>,
+    signers: &[Arc<Keypair>],
+    split: &TradeSplit,
+    dex_type: DexType,
+    is_buy: bool,
+) {
+    if split.cleanup.is_empty() {
+        return;
+    }
+    let result = async {
+        let rpc = rpc.ok_or_else(|| anyhow::anyhow!("no RPC client"))?;
+        let blockhash = rpc.get_latest_blockhash().await?;
+        land_split_transaction(
+            Some(rpc),
+            swqos_clients,
+            confirmation,
+            fee_payer,
+            &setup_signers(signers, &split.cleanup),
+            &split.cleanup,
+            &[],
+            blockhash,
+            dex_type,
+            is_buy,
+        )
+        .await
+    }
+    .await;
+    if let Err(e) = result {
+        if crate::common::sdk_log::sdk_log_enabled() {
+            warn!(target: "sol_trade_sdk", "Split cleanup did not land: {}", e);
+        }
+    }
+}
+
+/// Simulation gate on `instructions` (see [`preflight_simulate`]): `Some(err)` when the
+/// simulation failed and they must not be sent. When the simulation cannot run or takes longer
+/// than `max_latency`, they are sent ungated.
+#[allow(clippy::too_many_arguments)]
+async fn simulation_gate(
+    max_latency: Duration,
+    rpc: Option<&SolanaRpcClient>,
+    payer: &Arc<Keypair>,
+    additional_signers: &[Arc<Keypair>],
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    durable_nonce: Option<&DurableNonceInfo>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
+    is_buy: bool,
+    with_tip: bool,
+    gas_fee_strategy: &GasFeeStrategy,
+) -> Option<anyhow::Error> {
+    let preflight = tokio::time::timeout(
+        max_latency,
+        preflight_simulate(
+            rpc,
+            payer,
+            additional_signers,
+            instructions,
+            address_lookup_table_accounts,
+            recent_blockhash,
+            durable_nonce,
+            middleware_manager,
+            compute_budget_manager,
+            dex_type,
+            is_buy,
+            with_tip,
+            gas_fee_strategy,
+        ),
+    )
+    .await;
+    match preflight {
+        Ok(Ok(None)) => None,
+        Ok(Ok(Some(err))) => Some(anyhow::anyhow!("PreflightFailed: {}", err)),
+        Ok(Err(e)) => {
+            if crate::common::sdk_log::sdk_log_enabled() {
+                warn!(target: "sol_trade_sdk", "Preflight simulation unavailable, sending ungated: {}", e);
+            }
+            None
+        }
+        Err(_) => {
+            if crate::common::sdk_log::sdk_log_enabled() {
+                warn!(
+                    target: "sol_trade_sdk",
+                    "Preflight simulation exceeded {:?}, sending ungated",
+                    max_latency
+                );
+            }
+            None
+        }
+    }
+}
+
+/// Sponsored mode: build with the sponsor as fee payer, have it co-sign, then broadcast the
+/// result through the `Default` (RPC) route. `signers` are the trade's signers (payer included).
+#[allow(clippy::too_many_arguments)]
+async fn send_sponsored(
+    sponsor: &dyn FeeSponsor,
+    swqos_clients: &[Arc<SwqosClient>],
+    signers: &[Arc<Keypair>],
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    durable_nonce: Option<&DurableNonceInfo>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
+    is_buy: bool,
+    gas_fee_strategy: &GasFeeStrategy,
+    raw_transactions: Option<ExplorerCluster>,
+) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
+    use crate::trading::common::build_sponsored_transaction;
+
+    if durable_nonce.is_some() {
+        return Err(anyhow::anyhow!(
+            "Sponsored sends take a recent blockhash, not a durable nonce"
+        ));
+    }
+    let rpc = swqos_clients
+        .iter()
+        .find(|client| client.get_swqos_type() == SwqosType::Default)
+        .ok_or_else(|| {
+            anyhow::Error::new(crate::swqos::common::TradeError::no_swqos_available(
+                "sponsored sends go through the Default (RPC) route",
+            ))
+        })?;
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let (strategy_type, value) = gas_fee_strategy
+        .get_strategies(trade_type)
+        .into_iter()
+        .filter(|(swqos_type, _, _)| *swqos_type == SwqosType::Default)
+        .min_by_key(|(_, strategy_type, _)| *strategy_type != GasFeeStrategyType::Normal)
+        .map(|(_, strategy_type, value)| (strategy_type, value))
+        .unwrap_or((
+            GasFeeStrategyType::Normal,
+            GasFeeStrategyValue { cu_limit: 0, cu_price: 0, tip: 0.0 },
+        ));
+
+    let transaction = build_sponsored_transaction(
+        &sponsor.fee_payer(),
+        signers,
+        value.cu_limit,
+        value.cu_price,
+        instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        Some(dex_type),
+        is_buy,
+    )?;
+    let sponsored = sponsor
+        .sponsor(transaction.clone())
+        .await
+        .map_err(|e| anyhow::anyhow!("Fee sponsor failed: {}", e))?;
+    verify_sponsored(&transaction, &sponsored)?;
+
+    let signature = sponsored.signatures[0];
+    let submit_result = rpc.send_transaction(trade_type, &sponsored, false).await;
+    let timing = SwqosSubmitTiming {
+        swqos_type: SwqosType::Default,
+        strategy_type,
+        submit_done_us: crate::common::clock::now_micros(),
+        signature,
+        cu_price: compute_unit_price(&sponsored),
+        tip_lamports: 0,
+        landed: None,
+        landed_slot: None,
+        block_time: None,
+        event_to_land_slots: None,
+        raw_transaction: raw_transactions
+            .and_then(|cluster| RawTransaction::capture(&sponsored, cluster).ok()),
+    };
+    match submit_result {
+        Ok(()) => Ok((true, vec![signature], None, vec![timing])),
+        Err(e) => Ok((false, vec![signature], Some(e), vec![timing])),
+    }
+}
+
+/// Record which lane's transaction the confirmation poll saw land. On a confirmation timeout
+/// none did; other errors (landed but failed, RPC errors) leave the lanes unmarked.
+fn mark_landed_lane(
+    timings: &mut [SwqosSubmitTiming],
+    poll_res: &Result<(Signature, crate::swqos::common::ConfirmationStatus, u64)>,
+) {
+    let landed = match poll_res {
+        Ok((signature, ..)) => Some(*signature),
+        Err(e)
+            if e.downcast_ref::<crate::swqos::common::TradeError>().is_some_and(|t| {
+                t.code == crate::swqos::common::CONFIRMATION_TIMEOUT_ERROR_CODE
+            }) =>
+        {
+            None
+        }
+        Err(_) => return,
+    };
+    for timing in timings {
+        timing.landed = Some(landed == Some(timing.signature));
+    }
+}
+
+/// Preflight for the simulation gate. `Ok(Some(err))` means the simulation failed (slippage/min-out
+/// checks included) and the trade must not be sent; `Err` means the simulation could not run.
+/// Uses `replace_recent_blockhash` unless a durable nonce is set (the nonce advance needs the real one).
+#[allow(clippy::too_many_arguments)]
+async fn preflight_simulate(
+    rpc: Option<&SolanaRpcClient>,
+    payer: &Arc<Keypair>,
+    additional_signers: &[Arc<Keypair>],
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Option<Hash>,
+    durable_nonce: Option<&DurableNonceInfo>,
+    middleware_manager: Option<&Arc<MiddlewareManager>>,
+    compute_budget_manager: Option<&Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
+    is_buy: bool,
+    with_tip: bool,
+    gas_fee_strategy: &GasFeeStrategy,
+) -> Result<Option<String>> {
+    use crate::trading::common::build_transaction_with_signers;
+    use solana_client::rpc_config::RpcSimulateTransactionConfig;
+    use solana_commitment_config::CommitmentConfig;
+    use solana_transaction_status::UiTransactionEncoding;
+
+    let rpc = rpc.ok_or_else(|| anyhow::anyhow!("RPC client is required for preflight"))?;
+    let trade_type = if is_buy { TradeType::Buy } else { TradeType::Sell };
+    let strategies = gas_fee_strategy.get_strategies(trade_type);
+    let (_, _, value) = strategies
+        .first()
+        .copied()
+        .ok_or_else(|| anyhow::anyhow!("No gas fee strategy found for preflight"))?;
+
+    let transaction = build_transaction_with_signers(
+        payer,
+        additional_signers,
+        value.cu_limit,
+        value.cu_price,
+        instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+        middleware_manager,
+        compute_budget_manager,
+        Some(dex_type),
+        is_buy,
+        false,
+        &Pubkey::default(),
+        if with_tip { value.tip } else { 0.0 },
+        durable_nonce,
+    )?;
+    let simulate_result = rpc
+        .simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: durable_nonce.is_none(),
+                commitment: Some(CommitmentConfig::processed()),
+                encoding: Some(UiTransactionEncoding::Base64),
+                accounts: None,
+                min_context_slot: None,
+                inner_instructions: false,
+            },
+        )
+        .await?;
+    Ok(simulate_result.value.err.map(|err| format!("{:?}", err)))
 }
 
 /// Simulate mode: single RPC simulation, returns Vec<Signature> for API consistency.
@@ -253,17 +1116,20 @@ impl TradeExecutor for GenericTradeExecutor {
 async fn simulate_transaction(
     rpc: Option<Arc<SolanaRpcClient>>,
     payer: Arc<Keypair>,
+    additional_signers: Vec<Arc<Keypair>>,
     instructions: Vec<Instruction>,
     address_lookup_table_accounts: Vec<AddressLookupTableAccount>,
     recent_blockhash: Option<Hash>,
     durable_nonce: Option<DurableNonceInfo>,
     middleware_manager: Option<Arc<MiddlewareManager>>,
-    protocol_name: &'static str,
+    compute_budget_manager: Option<Arc<dyn ComputeBudgetManager>>,
+    dex_type: DexType,
     is_buy: bool,
     with_tip: bool,
     gas_fee_strategy: GasFeeStrategy,
+    simulation_prelude: Vec<Instruction>,
 ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)> {
-    use crate::trading::common::build_transaction;
+    use crate::trading::common::{build_simulation_transaction, build_transaction_with_signers};
     use solana_client::rpc_config::RpcSimulateTransactionConfig;
     use solana_commitment_config::CommitmentLevel;
     use solana_transaction_status::UiTransactionEncoding;
@@ -284,21 +1150,46 @@ async fn simulate_transaction(
     let unit_limit = default_config.2.cu_limit;
     let unit_price = default_config.2.cu_price;
 
-    let transaction = build_transaction(
-        &payer,
-        unit_limit,
-        unit_price,
-        &instructions,
-        address_lookup_table_accounts.as_slice(),
-        recent_blockhash,
-        middleware_manager.as_ref(),
-        protocol_name,
-        is_buy,
-        false,
-        &Pubkey::default(),
-        tip,
-        durable_nonce.as_ref(),
-    )?;
+    // Lenders in the prelude do not sign; the simulation skips signature checks.
+    let transaction = if simulation_prelude.is_empty() {
+        build_transaction_with_signers(
+            &payer,
+            &additional_signers,
+            unit_limit,
+            unit_price,
+            &instructions,
+            address_lookup_table_accounts.as_slice(),
+            recent_blockhash,
+            middleware_manager.as_ref(),
+            compute_budget_manager.as_ref(),
+            Some(dex_type),
+            is_buy,
+            false,
+            &Pubkey::default(),
+            tip,
+            durable_nonce.as_ref(),
+        )?
+    } else {
+        let mut funded = simulation_prelude;
+        funded.extend(instructions);
+        build_simulation_transaction(
+            &payer,
+            &additional_signers,
+            unit_limit,
+            unit_price,
+            &funded,
+            address_lookup_table_accounts.as_slice(),
+            recent_blockhash,
+            middleware_manager.as_ref(),
+            compute_budget_manager.as_ref(),
+            Some(dex_type),
+            is_buy,
+            false,
+            &Pubkey::default(),
+            tip,
+            durable_nonce.as_ref(),
+        )?
+    };
 
     // Simulate the transaction
     use solana_commitment_config::CommitmentConfig;
@@ -439,4 +1330,60 @@ mod tests {
         println!(" [SDK][{:width$}] {} total: {:.4} ms", "-", dir, 36.51, width = w);
         println!();
     }
+
+    #[test]
+    fn split_trade_sends_the_setup_first_only_when_both_halves_fit() {
+        use super::split_trade;
+        use crate::constants::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM};
+        use solana_compute_budget_interface::ComputeBudgetInstruction;
+        use solana_sdk::instruction::{AccountMeta, Instruction};
+        use solana_sdk::pubkey::Pubkey;
+
+        let (payer, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let ix = |program_id, accounts, len: usize, tag: u8| Instruction {
+            program_id,
+            accounts,
+            data: vec![tag; len],
+        };
+        let budget = ComputeBudgetInstruction::set_compute_unit_limit(200_000);
+        let create_wsol = ix(
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            vec![AccountMeta::new(payer, true), AccountMeta::new(wsol, false)],
+            600,
+            1,
+        );
+        let swap = ix(Pubkey::new_unique(), vec![AccountMeta::new(wsol, false)], 700, 0);
+        let unwrap = ix(
+            TOKEN_PROGRAM,
+            vec![AccountMeta::new(wsol, false), AccountMeta::new(payer, false)],
+            1,
+            9,
+        );
+        // Stand-in for the wire size: the instruction data alone.
+        let size_of = |instructions: &[Instruction], _: bool| -> anyhow::Result<usize> {
+            Ok(instructions.iter().map(|ix| ix.data.len()).sum())
+        };
+
+        let mut fits = vec![budget.clone(), create_wsol.clone(), ix(payer, vec![], 10, 0)];
+        assert_eq!(split_trade(&mut fits, 2, size_of).unwrap(), None);
+        assert_eq!(fits.len(), 3);
+
+        let trade = vec![budget.clone(), create_wsol.clone(), swap.clone(), unwrap.clone()];
+        let mut instructions = trade.clone();
+        let split = split_trade(&mut instructions, 2, size_of).unwrap().unwrap();
+        assert_eq!(split.setup, vec![create_wsol.clone()]);
+        assert_eq!(split.cleanup, vec![unwrap.clone()]);
+        assert_eq!(instructions, vec![budget.clone(), swap, unwrap]);
+
+        // The swap half alone is still too large: fail before sending the setup.
+        let mut oversized = vec![budget, create_wsol, ix(payer, vec![], 1300, 0)];
+        assert!(split_trade(&mut oversized, 2, size_of).is_err());
+        assert_eq!(oversized.len(), 3);
+
+        let mut unprobed = trade;
+        let no_probe = |_: &[Instruction], _: bool| -> anyhow::Result<usize> {
+            Err(anyhow::anyhow!("no blockhash"))
+        };
+        assert_eq!(split_trade(&mut unprobed, 2, no_probe).unwrap(), None);
+    }
 }
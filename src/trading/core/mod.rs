@@ -2,5 +2,6 @@ pub mod async_executor;
 pub mod execution;
 pub mod executor;
 pub mod params;
+pub mod signing_pool;
 pub mod traits;
 pub mod transaction_pool;
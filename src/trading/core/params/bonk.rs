@@ -1,9 +1,11 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_PARAMS};
 use crate::common::SolanaRpcClient;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 
 /// Bonk protocol specific parameters
 /// Configuration parameters specific to Bonk trading protocol
-#[derive(Clone, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct BonkParams {
     pub virtual_base: u128,
     pub virtual_quote: u128,
@@ -13,6 +15,7 @@ pub struct BonkParams {
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
     /// Token program ID
+    /// `Pubkey::default()`: detected from the mint owner before the trade
     pub mint_token_program: Pubkey,
     pub platform_config: Pubkey,
     pub platform_associated_account: Pubkey,
@@ -20,6 +23,26 @@ pub struct BonkParams {
     pub global_config: Pubkey,
 }
 
+impl fmt::Display for BonkParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bonk pool_state={} base_vault={} quote_vault={} platform_config={} global_config={} \
+             mint_token_program={} virtual={}/{} real={}/{}",
+            self.pool_state,
+            self.base_vault,
+            self.quote_vault,
+            self.platform_config,
+            self.global_config,
+            self.mint_token_program,
+            self.virtual_base,
+            self.virtual_quote,
+            self.real_base,
+            self.real_quote
+        )
+    }
+}
+
 impl BonkParams {
     pub fn immediate_sell(
         mint_token_program: Pubkey,
@@ -140,39 +163,43 @@ impl BonkParams {
         mint: &Pubkey,
         usd1_pool: bool,
     ) -> Result<Self, anyhow::Error> {
-        let pool_address = crate::instruction::utils::bonk::get_pool_pda(
-            mint,
-            if usd1_pool {
-                &crate::constants::USD1_TOKEN_ACCOUNT
-            } else {
-                &crate::constants::WSOL_TOKEN_ACCOUNT
-            },
-        )
-        .unwrap();
-        let pool_data =
-            crate::instruction::utils::bonk::fetch_pool_state(rpc, &pool_address).await?;
-        let token_account = rpc.get_account(&pool_data.base_mint).await?;
-        let platform_associated_account =
-            crate::instruction::utils::bonk::get_platform_associated_account(
-                &pool_data.platform_config,
-            );
-        let creator_associated_account =
-            crate::instruction::utils::bonk::get_creator_associated_account(&pool_data.creator);
-        let platform_associated_account = platform_associated_account.unwrap();
-        let creator_associated_account = creator_associated_account.unwrap();
-        Ok(Self {
-            virtual_base: pool_data.virtual_base as u128,
-            virtual_quote: pool_data.virtual_quote as u128,
-            real_base: pool_data.real_base as u128,
-            real_quote: pool_data.real_quote as u128,
-            pool_state: pool_address,
-            base_vault: pool_data.base_vault,
-            quote_vault: pool_data.quote_vault,
-            mint_token_program: token_account.owner,
-            platform_config: pool_data.platform_config,
-            platform_associated_account,
-            creator_associated_account,
-            global_config: pool_data.global_config,
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let pool_address = crate::instruction::utils::bonk::get_pool_pda(
+                mint,
+                if usd1_pool {
+                    &crate::constants::USD1_TOKEN_ACCOUNT
+                } else {
+                    &crate::constants::WSOL_TOKEN_ACCOUNT
+                },
+            )
+            .unwrap();
+            let pool_data =
+                crate::instruction::utils::bonk::fetch_pool_state(rpc, &pool_address).await?;
+            let token_account =
+                crate::common::rpc_cache::get_immutable_account(rpc, &pool_data.base_mint).await?;
+            let platform_associated_account =
+                crate::instruction::utils::bonk::get_platform_associated_account(
+                    &pool_data.platform_config,
+                );
+            let creator_associated_account =
+                crate::instruction::utils::bonk::get_creator_associated_account(&pool_data.creator);
+            let platform_associated_account = platform_associated_account.unwrap();
+            let creator_associated_account = creator_associated_account.unwrap();
+            Ok(Self {
+                virtual_base: pool_data.virtual_base as u128,
+                virtual_quote: pool_data.virtual_quote as u128,
+                real_base: pool_data.real_base as u128,
+                real_quote: pool_data.real_quote as u128,
+                pool_state: pool_address,
+                base_vault: pool_data.base_vault,
+                quote_vault: pool_data.quote_vault,
+                mint_token_program: token_account.owner,
+                platform_config: pool_data.platform_config,
+                platform_associated_account,
+                creator_associated_account,
+                global_config: pool_data.global_config,
+            })
         })
+        .await
     }
 }
@@ -1,9 +1,11 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_PARAMS};
 use crate::common::SolanaRpcClient;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 
 /// MeteoraDammV2 protocol specific parameters
 /// Configuration parameters specific to Meteora Damm V2 trading protocol
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct MeteoraDammV2Params {
     pub pool: Pubkey,
     pub token_a_vault: Pubkey,
@@ -19,6 +21,22 @@ pub struct MeteoraDammV2Params {
     pub include_rate_limiter_sysvar: bool,
 }
 
+impl fmt::Display for MeteoraDammV2Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MeteoraDammV2 pool={} token_a_mint={} token_b_mint={} token_a_vault={} \
+             token_b_vault={} swap_mode={}",
+            self.pool,
+            self.token_a_mint,
+            self.token_b_mint,
+            self.token_a_vault,
+            self.token_b_vault,
+            self.swap_mode
+        )
+    }
+}
+
 impl MeteoraDammV2Params {
     pub fn new(
         pool: Pubkey,
@@ -62,31 +80,35 @@ impl MeteoraDammV2Params {
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let pool_data =
-            crate::instruction::utils::meteora_damm_v2::fetch_pool(rpc, pool_address).await?;
-        let mint_accounts =
-            rpc.get_multiple_accounts(&[pool_data.token_a_mint, pool_data.token_b_mint]).await?;
-        let token_a_program = mint_accounts
-            .get(0)
-            .and_then(|a| a.as_ref())
-            .map(|a| a.owner)
-            .ok_or_else(|| anyhow::anyhow!("Token A mint account not found"))?;
-        let token_b_program = mint_accounts
-            .get(1)
-            .and_then(|a| a.as_ref())
-            .map(|a| a.owner)
-            .ok_or_else(|| anyhow::anyhow!("Token B mint account not found"))?;
-        Ok(Self {
-            pool: *pool_address,
-            token_a_vault: pool_data.token_a_vault,
-            token_b_vault: pool_data.token_b_vault,
-            token_a_mint: pool_data.token_a_mint,
-            token_b_mint: pool_data.token_b_mint,
-            token_a_program,
-            token_b_program,
-            referral_token_account: None,
-            swap_mode: crate::instruction::utils::meteora_damm_v2::SWAP_MODE_PARTIAL_FILL,
-            include_rate_limiter_sysvar: false,
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let pool_data =
+                crate::instruction::utils::meteora_damm_v2::fetch_pool(rpc, pool_address).await?;
+            let mint_accounts = rpc
+                .get_multiple_accounts(&[pool_data.token_a_mint, pool_data.token_b_mint])
+                .await?;
+            let token_a_program = mint_accounts
+                .get(0)
+                .and_then(|a| a.as_ref())
+                .map(|a| a.owner)
+                .ok_or_else(|| anyhow::anyhow!("Token A mint account not found"))?;
+            let token_b_program = mint_accounts
+                .get(1)
+                .and_then(|a| a.as_ref())
+                .map(|a| a.owner)
+                .ok_or_else(|| anyhow::anyhow!("Token B mint account not found"))?;
+            Ok(Self {
+                pool: *pool_address,
+                token_a_vault: pool_data.token_a_vault,
+                token_b_vault: pool_data.token_b_vault,
+                token_a_mint: pool_data.token_a_mint,
+                token_b_mint: pool_data.token_b_mint,
+                token_a_program,
+                token_b_program,
+                referral_token_account: None,
+                swap_mode: crate::instruction::utils::meteora_damm_v2::SWAP_MODE_PARTIAL_FILL,
+                include_rate_limiter_sysvar: false,
+            })
         })
+        .await
     }
 }
@@ -7,6 +7,8 @@ mod pumpfun;
 mod pumpswap;
 mod raydium_amm_v4;
 mod raydium_cpmm;
+#[cfg(feature = "streamer-events")]
+mod streamer_events;
 
 pub use bonk::BonkParams;
 pub use dex_swap::{DexParamEnum, SenderConcurrencyConfig, SwapParams};
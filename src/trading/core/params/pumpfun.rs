@@ -1,8 +1,10 @@
 use crate::common::bonding_curve::BondingCurveAccount;
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_PARAMS};
 use crate::common::spl_associated_token_account::get_associated_token_address_with_program_id;
 use crate::common::SolanaRpcClient;
 use crate::instruction::utils::pumpfun::reconcile_mayhem_mode_for_trade;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 use std::sync::Arc;
 
 /// PumpFun protocol specific parameters
@@ -18,7 +20,7 @@ use std::sync::Arc;
 /// `quote_mint`. Native SOL-paired coins use the legacy SOL layout when `quote_mint`
 /// is default, the Solscan SOL sentinel (`SOL_TOKEN_ACCOUNT`), or the WSOL sentinel
 /// (`WSOL_TOKEN_ACCOUNT`). Non-native quote mints such as USDC use V2.
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct PumpFunParams {
     pub bonding_curve: Arc<BondingCurveAccount>,
     pub associated_bonding_curve: Pubkey,
@@ -49,6 +51,24 @@ pub struct PumpFunParams {
     pub quote_mint: Pubkey,
 }
 
+impl fmt::Display for PumpFunParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "PumpFun bonding_curve={} associated_bonding_curve={} creator_vault={} \
+             fee_recipient={} token_program={} quote_mint={} virtual={}/{}",
+            self.bonding_curve.account,
+            self.associated_bonding_curve,
+            self.creator_vault,
+            self.fee_recipient,
+            self.token_program,
+            self.quote_mint,
+            self.bonding_curve.virtual_token_reserves,
+            self.bonding_curve.virtual_sol_reserves
+        )
+    }
+}
+
 impl PumpFunParams {
     #[inline]
     fn quote_mint_for_layout(quote_mint: Pubkey) -> Pubkey {
@@ -289,56 +309,61 @@ impl PumpFunParams {
         rpc: &SolanaRpcClient,
         mint: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let account =
-            crate::instruction::utils::pumpfun::fetch_bonding_curve_account(rpc, mint).await?;
-        let mint_account = rpc.get_account(&mint).await?;
-        let bonding_curve = BondingCurveAccount {
-            discriminator: 0,
-            account: account.1,
-            virtual_token_reserves: account.0.virtual_token_reserves,
-            virtual_sol_reserves: account.0.virtual_sol_reserves,
-            real_token_reserves: account.0.real_token_reserves,
-            real_sol_reserves: account.0.real_sol_reserves,
-            token_total_supply: account.0.token_total_supply,
-            complete: account.0.complete,
-            creator: account.0.creator,
-            is_mayhem_mode: account.0.is_mayhem_mode,
-            is_cashback_coin: account.0.is_cashback_coin,
-            quote_mint: account.0.quote_mint,
-        };
-        let associated_bonding_curve = get_associated_token_address_with_program_id(
-            &bonding_curve.account,
-            mint,
-            &mint_account.owner,
-        );
-        let fee_sharing_creator_vault_if_active =
-            crate::instruction::utils::pumpfun::fetch_fee_sharing_creator_vault_if_active(
-                rpc, mint,
-            )
-            .await?;
-        let creator_vault =
-            crate::instruction::utils::pumpfun::resolve_creator_vault_for_ix_with_fee_sharing(
-                &bonding_curve.creator,
-                Pubkey::default(),
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let account =
+                crate::instruction::utils::pumpfun::fetch_bonding_curve_account(rpc, mint).await?;
+            let mint_account = crate::common::rpc_cache::get_immutable_account(rpc, mint).await?;
+            let bonding_curve = BondingCurveAccount {
+                discriminator: 0,
+                account: account.1,
+                virtual_token_reserves: account.0.virtual_token_reserves,
+                virtual_sol_reserves: account.0.virtual_sol_reserves,
+                real_token_reserves: account.0.real_token_reserves,
+                real_sol_reserves: account.0.real_sol_reserves,
+                token_total_supply: account.0.token_total_supply,
+                complete: account.0.complete,
+                creator: account.0.creator,
+                is_mayhem_mode: account.0.is_mayhem_mode,
+                is_cashback_coin: account.0.is_cashback_coin,
+                quote_mint: account.0.quote_mint,
+            };
+            let associated_bonding_curve = get_associated_token_address_with_program_id(
+                &bonding_curve.account,
                 mint,
+                &mint_account.owner,
+            );
+            let fee_sharing_creator_vault_if_active =
+                crate::instruction::utils::pumpfun::fetch_fee_sharing_creator_vault_if_active(
+                    rpc, mint,
+                )
+                .await?;
+            let creator_vault =
+                crate::instruction::utils::pumpfun::resolve_creator_vault_for_ix_with_fee_sharing(
+                    &bonding_curve.creator,
+                    Pubkey::default(),
+                    mint,
+                    fee_sharing_creator_vault_if_active,
+                )
+                .or_else(|| {
+                    crate::instruction::utils::pumpfun::get_creator_vault_pda(
+                        &bonding_curve.creator,
+                    )
+                })
+                .unwrap_or_default();
+            let quote_mint = bonding_curve.quote_mint;
+            Ok(Self {
+                bonding_curve: Arc::new(bonding_curve),
+                associated_bonding_curve: associated_bonding_curve,
+                observed_trade_creator: None,
+                creator_vault,
                 fee_sharing_creator_vault_if_active,
-            )
-            .or_else(|| {
-                crate::instruction::utils::pumpfun::get_creator_vault_pda(&bonding_curve.creator)
+                close_token_account_when_sell: None,
+                token_program: mint_account.owner,
+                fee_recipient: Pubkey::default(),
+                quote_mint: Self::quote_mint_for_rpc_return(quote_mint),
             })
-            .unwrap_or_default();
-        let quote_mint = bonding_curve.quote_mint;
-        Ok(Self {
-            bonding_curve: Arc::new(bonding_curve),
-            associated_bonding_curve: associated_bonding_curve,
-            observed_trade_creator: None,
-            creator_vault,
-            fee_sharing_creator_vault_if_active,
-            close_token_account_when_sell: None,
-            token_program: mint_account.owner,
-            fee_recipient: Pubkey::default(),
-            quote_mint: Self::quote_mint_for_rpc_return(quote_mint),
         })
+        .await
     }
 
     /// 链下公式与 **`creator_vault` 推导回退**：**日志/事件 `creator`**（若已写入 `observed_trade_creator`）
@@ -1,8 +1,10 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_PARAMS};
 use crate::common::SolanaRpcClient;
 use crate::instruction::utils::pumpswap::{
     accounts::MAYHEM_FEE_RECIPIENT as MAYHEM_FEE_RECIPIENT_SWAP, PumpSwapFeeBasisPoints,
 };
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 
 /// PumpSwap Protocol Specific Parameters
 ///
@@ -12,7 +14,7 @@ use solana_sdk::pubkey::Pubkey;
 /// **Performance Note**: If these parameters are not provided, the system will attempt to
 /// retrieve the relevant information from RPC, which will increase transaction time.
 /// For optimal performance, it is recommended to provide all necessary parameters in advance.
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct PumpSwapParams {
     /// Liquidity pool address
     pub pool: Pubkey,
@@ -37,11 +39,17 @@ pub struct PumpSwapParams {
     /// Coin creator vault authority
     pub coin_creator_vault_authority: Pubkey,
     /// Token program ID
+    /// `Pubkey::default()`: detected from the mint owner before the trade
     pub base_token_program: Pubkey,
     /// Quote token program ID
+    /// `Pubkey::default()`: detected from the mint owner before the trade
     pub quote_token_program: Pubkey,
-    /// Whether the pool is in mayhem mode
+    /// Whether the pool is in mayhem mode. Mayhem pools are quoted with
+    /// [`pool_fee_basis_points`](crate::utils::calc::pumpswap::pool_fee_basis_points).
     pub is_mayhem_mode: bool,
+    /// Fee schedule of mayhem-mode pools when it differs from [`Self::fee_basis_points`];
+    /// `None` quotes mayhem pools like any other pool.
+    pub mayhem_fee_basis_points: Option<PumpSwapFeeBasisPoints>,
     /// Pool creator. Canonical PumpSwap pools use the Pump program pool-authority PDA here;
     /// fee tiers are selected from this value without doing RPC in the instruction builder.
     pub pool_creator: Pubkey,
@@ -64,6 +72,24 @@ pub struct PumpSwapParams {
     pub fee_basis_points: PumpSwapFeeBasisPoints,
 }
 
+impl fmt::Display for PumpSwapParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "PumpSwap pool={} base_mint={} quote_mint={} pool_base={} pool_quote={} \
+             coin_creator_vault_ata={} reserves={}/{}",
+            self.pool,
+            self.base_mint,
+            self.quote_mint,
+            self.pool_base_token_account,
+            self.pool_quote_token_account,
+            self.coin_creator_vault_ata,
+            self.pool_base_token_reserves,
+            self.pool_quote_token_reserves
+        )
+    }
+}
+
 impl PumpSwapParams {
     pub fn new(
         pool: Pubkey,
@@ -104,6 +130,7 @@ impl PumpSwapParams {
             base_token_program,
             quote_token_program,
             is_mayhem_mode,
+            mayhem_fee_basis_points: None,
             pool_creator: Pubkey::default(),
             coin_creator,
             is_cashback_coin,
@@ -127,6 +154,35 @@ impl PumpSwapParams {
         self
     }
 
+    /// Quote mayhem-mode pools with `fees` wherever they exceed the regular schedule.
+    pub fn with_mayhem_fee_basis_points(mut self, fees: PumpSwapFeeBasisPoints) -> Self {
+        self.mayhem_fee_basis_points = Some(fees);
+        self
+    }
+
+    /// Fee bps min-out / max-in are computed with, mayhem schedule included.
+    #[inline]
+    pub fn quoted_fee_basis_points(&self) -> PumpSwapFeeBasisPoints {
+        crate::utils::calc::pumpswap::pool_fee_basis_points(
+            &self.fee_basis_points,
+            self.is_mayhem_mode,
+            self.mayhem_fee_basis_points.as_ref(),
+        )
+    }
+
+    /// Mint traded against the pool's WSOL/USDC side: the base mint, or the quote mint in a
+    /// reversed pool (WSOL/USDC as base), as the buy/sell instructions pick it. `None` when
+    /// not exactly one side is WSOL/USDC, i.e. the SDK cannot trade the pool.
+    pub fn traded_mint(&self) -> Option<Pubkey> {
+        use crate::constants::{USDC_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+        let is_stable = |mint: Pubkey| mint == WSOL_TOKEN_ACCOUNT || mint == USDC_TOKEN_ACCOUNT;
+        match (is_stable(self.base_mint), is_stable(self.quote_mint)) {
+            (false, true) => Some(self.base_mint),
+            (true, false) => Some(self.quote_mint),
+            _ => None,
+        }
+    }
+
     /// Quote reserves used by PumpSwap pricing and fee-tier selection.
     pub fn effective_quote_reserves(&self) -> Result<u64, anyhow::Error> {
         crate::instruction::utils::pumpswap_types::effective_quote_reserves(
@@ -263,17 +319,24 @@ impl PumpSwapParams {
         rpc: &SolanaRpcClient,
         mint: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let (pool_address, pool) =
-            crate::instruction::utils::pumpswap::find_by_mint(rpc, mint).await?;
-        Self::from_pool_data(rpc, &pool_address, &pool).await
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let (pool_address, pool) =
+                crate::instruction::utils::pumpswap::find_by_mint(rpc, mint).await?;
+            Self::from_pool_data(rpc, &pool_address, &pool).await
+        })
+        .await
     }
 
     pub async fn from_pool_address_by_rpc(
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let pool_data = crate::instruction::utils::pumpswap::fetch_pool(rpc, pool_address).await?;
-        Self::from_pool_data(rpc, pool_address, &pool_data).await
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let pool_data =
+                crate::instruction::utils::pumpswap::fetch_pool(rpc, pool_address).await?;
+            Self::from_pool_data(rpc, pool_address, &pool_data).await
+        })
+        .await
     }
 
     /// Build params from an already-decoded Pool, only fetching token balances.
@@ -286,70 +349,76 @@ impl PumpSwapParams {
         pool_address: &Pubkey,
         pool_data: &crate::instruction::utils::pumpswap_types::Pool,
     ) -> Result<Self, anyhow::Error> {
-        let snapshot =
-            crate::instruction::utils::pumpswap::get_pool_rpc_snapshot(pool_data, rpc).await?;
-        let pool_base_token_reserves = snapshot.base_reserve;
-        let pool_quote_token_reserves = snapshot.quote_reserve;
-        let effective_quote_token_reserves =
-            crate::instruction::utils::pumpswap_types::effective_quote_reserves(
-                pool_quote_token_reserves,
-                pool_data.virtual_quote_reserves,
-            )
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Invalid PumpSwap effective quote reserves: vault={} virtual={}",
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let snapshot =
+                crate::instruction::utils::pumpswap::get_pool_rpc_snapshot(pool_data, rpc).await?;
+            let pool_base_token_reserves = snapshot.base_reserve;
+            let pool_quote_token_reserves = snapshot.quote_reserve;
+            let effective_quote_token_reserves =
+                crate::instruction::utils::pumpswap_types::effective_quote_reserves(
                     pool_quote_token_reserves,
-                    pool_data.virtual_quote_reserves
+                    pool_data.virtual_quote_reserves,
                 )
-            })?;
-        let base_mint_supply = Some(snapshot.base_mint_supply);
-        let fee_config = crate::instruction::utils::pumpswap::fetch_fee_config(rpc).await;
-        let raw_fee_basis_points = crate::instruction::utils::pumpswap::compute_fee_basis_points(
-            fee_config.as_ref(),
-            pool_data.creator,
-            pool_data.base_mint,
-            base_mint_supply,
-            pool_base_token_reserves,
-            effective_quote_token_reserves,
-        );
-        let creator_fee_basis_points = if pool_data.coin_creator == Pubkey::default() {
-            0
-        } else {
-            raw_fee_basis_points.coin_creator_fee_basis_points
-        };
-        let creator = pool_data.coin_creator;
-        let coin_creator_vault_ata = crate::instruction::utils::pumpswap::coin_creator_vault_ata(
-            creator,
-            pool_data.quote_mint,
-            snapshot.quote_token_program,
-        );
-        let coin_creator_vault_authority =
-            crate::instruction::utils::pumpswap::coin_creator_vault_authority(creator);
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Invalid PumpSwap effective quote reserves: vault={} virtual={}",
+                        pool_quote_token_reserves,
+                        pool_data.virtual_quote_reserves
+                    )
+                })?;
+            let base_mint_supply = Some(snapshot.base_mint_supply);
+            let fee_config = crate::instruction::utils::pumpswap::fetch_fee_config(rpc).await;
+            let raw_fee_basis_points =
+                crate::instruction::utils::pumpswap::compute_fee_basis_points(
+                    fee_config.as_ref(),
+                    pool_data.creator,
+                    pool_data.base_mint,
+                    base_mint_supply,
+                    pool_base_token_reserves,
+                    effective_quote_token_reserves,
+                );
+            let creator_fee_basis_points = if pool_data.coin_creator == Pubkey::default() {
+                0
+            } else {
+                raw_fee_basis_points.coin_creator_fee_basis_points
+            };
+            let creator = pool_data.coin_creator;
+            let coin_creator_vault_ata =
+                crate::instruction::utils::pumpswap::coin_creator_vault_ata(
+                    creator,
+                    pool_data.quote_mint,
+                    snapshot.quote_token_program,
+                );
+            let coin_creator_vault_authority =
+                crate::instruction::utils::pumpswap::coin_creator_vault_authority(creator);
 
-        Ok(Self {
-            pool: *pool_address,
-            base_mint: pool_data.base_mint,
-            quote_mint: pool_data.quote_mint,
-            pool_base_token_account: pool_data.pool_base_token_account,
-            pool_quote_token_account: pool_data.pool_quote_token_account,
-            pool_base_token_reserves,
-            pool_quote_token_reserves,
-            virtual_quote_reserves: pool_data.virtual_quote_reserves,
-            coin_creator_vault_ata,
-            coin_creator_vault_authority,
-            base_token_program: snapshot.base_token_program,
-            is_cashback_coin: pool_data.is_cashback_coin,
-            quote_token_program: snapshot.quote_token_program,
-            is_mayhem_mode: pool_data.is_mayhem_mode,
-            pool_creator: pool_data.creator,
-            coin_creator: pool_data.coin_creator,
-            cashback_fee_basis_points: 0,
-            base_mint_supply,
-            fee_basis_points: PumpSwapFeeBasisPoints::new(
-                raw_fee_basis_points.lp_fee_basis_points,
-                raw_fee_basis_points.protocol_fee_basis_points,
-                creator_fee_basis_points,
-            ),
+            Ok(Self {
+                pool: *pool_address,
+                base_mint: pool_data.base_mint,
+                quote_mint: pool_data.quote_mint,
+                pool_base_token_account: pool_data.pool_base_token_account,
+                pool_quote_token_account: pool_data.pool_quote_token_account,
+                pool_base_token_reserves,
+                pool_quote_token_reserves,
+                virtual_quote_reserves: pool_data.virtual_quote_reserves,
+                coin_creator_vault_ata,
+                coin_creator_vault_authority,
+                base_token_program: snapshot.base_token_program,
+                is_cashback_coin: pool_data.is_cashback_coin,
+                quote_token_program: snapshot.quote_token_program,
+                is_mayhem_mode: pool_data.is_mayhem_mode,
+                mayhem_fee_basis_points: None,
+                pool_creator: pool_data.creator,
+                coin_creator: pool_data.coin_creator,
+                cashback_fee_basis_points: 0,
+                base_mint_supply,
+                fee_basis_points: PumpSwapFeeBasisPoints::new(
+                    raw_fee_basis_points.lp_fee_basis_points,
+                    raw_fee_basis_points.protocol_fee_basis_points,
+                    creator_fee_basis_points,
+                ),
+            })
         })
+        .await
     }
 }
@@ -1,10 +1,12 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_PARAMS};
 use crate::common::SolanaRpcClient;
-use crate::trading::common::get_multi_token_balances;
+use crate::instruction::utils::raydium_amm_v4_types::AmmReserves;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct RaydiumAmmV4Params {
     /// AMM pool address
     pub amm: Pubkey,
@@ -36,13 +38,35 @@ pub struct RaydiumAmmV4Params {
     pub serum_pc_vault_account: Pubkey,
     /// Serum/OpenBook vault signer PDA
     pub serum_vault_signer: Pubkey,
-    /// Current coin reserve amount in the pool
+    /// Current coin reserve amount in the pool (open-orders adjusted when fetched by RPC)
     pub coin_reserve: u64,
-    /// Current pc reserve amount in the pool
+    /// Current pc reserve amount in the pool (open-orders adjusted when fetched by RPC)
     pub pc_reserve: u64,
 }
 
+impl fmt::Display for RaydiumAmmV4Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RaydiumAmmV4 amm={} coin_mint={} pc_mint={} token_coin={} token_pc={} \
+             open_orders={} serum_market={} reserves={}/{}",
+            self.amm,
+            self.coin_mint,
+            self.pc_mint,
+            self.token_coin,
+            self.token_pc,
+            self.amm_open_orders,
+            self.serum_market,
+            self.coin_reserve,
+            self.pc_reserve
+        )
+    }
+}
+
 impl RaydiumAmmV4Params {
+    /// Params without OpenBook market accounts; swaps use the v2 instructions, which skip the
+    /// market. Pools that still route through their market need [`Self::with_market_accounts`]
+    /// (or [`Self::from_amm_address_by_rpc`], which fills them in when the pool has a market).
     pub fn new(
         amm: Pubkey,
         coin_mint: Pubkey,
@@ -73,6 +97,7 @@ impl RaydiumAmmV4Params {
         }
     }
 
+    /// Set the OpenBook market accounts so swaps use the original 18-account layout.
     #[allow(clippy::too_many_arguments)]
     pub fn with_market_accounts(
         mut self,
@@ -100,40 +125,72 @@ impl RaydiumAmmV4Params {
         self
     }
 
+    /// Whether swaps go through the pool's OpenBook market (market accounts are set).
+    pub fn has_market_accounts(&self) -> bool {
+        self.serum_market != Pubkey::default()
+    }
+
     pub async fn from_amm_address_by_rpc(
         rpc: &SolanaRpcClient,
         amm: Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let amm_info = crate::instruction::utils::raydium_amm_v4::fetch_amm_info(rpc, amm).await?;
-        let market_state =
-            crate::instruction::utils::raydium_amm_v4::fetch_market_state(rpc, amm_info.market)
-                .await?;
-        let serum_vault_signer =
-            crate::instruction::utils::raydium_amm_v4::derive_serum_vault_signer(
-                &amm_info.serum_dex,
-                &amm_info.market,
-                market_state.vault_signer_nonce,
+        Ok(Self::from_amm_address_by_rpc_with_reserves(rpc, amm).await?.0)
+    }
+
+    /// Same as [`Self::from_amm_address_by_rpc`], also returning the decoded reserve snapshot
+    /// (raw vault balances, open orders totals and pending PnL) the params were priced from.
+    pub async fn from_amm_address_by_rpc_with_reserves(
+        rpc: &SolanaRpcClient,
+        amm: Pubkey,
+    ) -> Result<(Self, AmmReserves), anyhow::Error> {
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let amm_info =
+                crate::instruction::utils::raydium_amm_v4::fetch_amm_info(rpc, amm).await?;
+            let (market_state, reserves) = tokio::try_join!(
+                crate::instruction::utils::raydium_amm_v4::fetch_market_state_if_any(
+                    rpc, &amm_info
+                ),
+                crate::instruction::utils::raydium_amm_v4::fetch_amm_reserves(rpc, &amm_info),
             )?;
-        let (coin_reserve, pc_reserve) =
-            get_multi_token_balances(rpc, &amm_info.token_coin, &amm_info.token_pc).await?;
-        Ok(Self {
-            amm,
-            coin_mint: amm_info.coin_mint,
-            pc_mint: amm_info.pc_mint,
-            token_coin: amm_info.token_coin,
-            token_pc: amm_info.token_pc,
-            amm_open_orders: amm_info.open_orders,
-            amm_target_orders: amm_info.target_orders,
-            serum_program: amm_info.serum_dex,
-            serum_market: amm_info.market,
-            serum_bids: market_state.serum_bids,
-            serum_asks: market_state.serum_asks,
-            serum_event_queue: market_state.serum_event_queue,
-            serum_coin_vault_account: market_state.serum_coin_vault_account,
-            serum_pc_vault_account: market_state.serum_pc_vault_account,
-            serum_vault_signer,
-            coin_reserve,
-            pc_reserve,
+            let mut params = Self::new(
+                amm,
+                amm_info.coin_mint,
+                amm_info.pc_mint,
+                amm_info.token_coin,
+                amm_info.token_pc,
+                reserves.coin_reserve(),
+                reserves.pc_reserve(),
+            );
+            // Pools without an OpenBook market keep the market accounts unset and swap through v2.
+            if let Some(market_state) = market_state {
+                let serum_vault_signer =
+                    crate::instruction::utils::raydium_amm_v4::derive_serum_vault_signer(
+                        &amm_info.serum_dex,
+                        &amm_info.market,
+                        market_state.vault_signer_nonce,
+                    )?;
+                params = params.with_market_accounts(
+                    amm_info.open_orders,
+                    amm_info.target_orders,
+                    amm_info.serum_dex,
+                    amm_info.market,
+                    market_state.serum_bids,
+                    market_state.serum_asks,
+                    market_state.serum_event_queue,
+                    market_state.serum_coin_vault_account,
+                    market_state.serum_pc_vault_account,
+                    serum_vault_signer,
+                );
+            }
+            Ok((params, reserves))
         })
+        .await
+    }
+
+    /// Overwrite reserves from a fresh snapshot (e.g. one refreshed on every swap) without refetching accounts.
+    pub fn with_reserves(mut self, reserves: &AmmReserves) -> Self {
+        self.coin_reserve = reserves.coin_reserve();
+        self.pc_reserve = reserves.pc_reserve();
+        self
     }
 }
@@ -1,9 +1,11 @@
+use crate::common::rpc_usage::{with_rpc_scope, RPC_SCOPE_PARAMS};
 use crate::common::SolanaRpcClient;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 
 /// RaydiumCpmm protocol specific parameters
 /// Configuration parameters specific to Raydium CPMM trading protocol
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct RaydiumCpmmParams {
     /// Pool address
     pub pool_state: Pubkey,
@@ -22,13 +24,34 @@ pub struct RaydiumCpmmParams {
     /// Quote token vault address
     pub quote_vault: Pubkey,
     /// Base token program ID
+    /// `Pubkey::default()`: detected from the mint owner before the trade
     pub base_token_program: Pubkey,
     /// Quote token program ID
+    /// `Pubkey::default()`: detected from the mint owner before the trade
     pub quote_token_program: Pubkey,
     /// Observation state account
     pub observation_state: Pubkey,
 }
 
+impl fmt::Display for RaydiumCpmmParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RaydiumCpmm pool_state={} amm_config={} base_mint={} quote_mint={} base_vault={} \
+             quote_vault={} observation_state={} reserves={}/{}",
+            self.pool_state,
+            self.amm_config,
+            self.base_mint,
+            self.quote_mint,
+            self.base_vault,
+            self.quote_vault,
+            self.observation_state,
+            self.base_reserve,
+            self.quote_reserve
+        )
+    }
+}
+
 impl RaydiumCpmmParams {
     pub fn from_trade(
         pool_state: Pubkey,
@@ -62,28 +85,31 @@ impl RaydiumCpmmParams {
         rpc: &SolanaRpcClient,
         pool_address: &Pubkey,
     ) -> Result<Self, anyhow::Error> {
-        let pool =
-            crate::instruction::utils::raydium_cpmm::fetch_pool_state(rpc, pool_address).await?;
-        let (token0_balance, token1_balance) =
-            crate::instruction::utils::raydium_cpmm::get_pool_token_balances(
-                rpc,
-                pool_address,
-                &pool.token0_mint,
-                &pool.token1_mint,
-            )
-            .await?;
-        Ok(Self {
-            pool_state: *pool_address,
-            amm_config: pool.amm_config,
-            base_mint: pool.token0_mint,
-            quote_mint: pool.token1_mint,
-            base_reserve: token0_balance,
-            quote_reserve: token1_balance,
-            base_vault: pool.token0_vault,
-            quote_vault: pool.token1_vault,
-            base_token_program: pool.token0_program,
-            quote_token_program: pool.token1_program,
-            observation_state: pool.observation_key,
+        with_rpc_scope(RPC_SCOPE_PARAMS, async move {
+            let pool = crate::instruction::utils::raydium_cpmm::fetch_pool_state(rpc, pool_address)
+                .await?;
+            let (token0_balance, token1_balance) =
+                crate::instruction::utils::raydium_cpmm::get_pool_token_balances(
+                    rpc,
+                    pool_address,
+                    &pool.token0_mint,
+                    &pool.token1_mint,
+                )
+                .await?;
+            Ok(Self {
+                pool_state: *pool_address,
+                amm_config: pool.amm_config,
+                base_mint: pool.token0_mint,
+                quote_mint: pool.token1_mint,
+                base_reserve: token0_balance,
+                quote_reserve: token1_balance,
+                base_vault: pool.token0_vault,
+                quote_vault: pool.token1_vault,
+                base_token_program: pool.token0_program,
+                quote_token_program: pool.token1_program,
+                observation_state: pool.observation_key,
+            })
         })
+        .await
     }
 }
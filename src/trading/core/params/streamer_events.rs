@@ -0,0 +1,157 @@
+//! `From`/`TryFrom<&Event>` conversions from solana-streamer-sdk events to protocol params, so
+//! bots do not map event fields by hand (feature `streamer-events`).
+//!
+//! PumpFun trade, PumpSwap buy/sell and Bonk trade events are covered. Raydium AMM V4 and CPMM
+//! swap events carry no pool reserves (AMM V4 not even the mints), so their params still come
+//! from
+//! [`RaydiumAmmV4Params::from_amm_address_by_rpc`](super::RaydiumAmmV4Params::from_amm_address_by_rpc)
+//! and [`RaydiumCpmmParams::from_pool_address_by_rpc`](super::RaydiumCpmmParams::from_pool_address_by_rpc).
+//! solana-streamer-sdk 事件直接转换为交易参数（PumpFun、PumpSwap 与 Bonk，含 base/quote 方向判定）。
+
+use super::{BonkParams, PumpFunParams, PumpSwapParams};
+use crate::constants::{USD1_TOKEN_ACCOUNT, WSOL_TOKEN_ACCOUNT};
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+use solana_streamer_sdk::streaming::event_parser::protocols::{
+    bonk::BonkTradeEvent,
+    pumpfun::PumpFunTradeEvent,
+    pumpswap::{PumpSwapBuyEvent, PumpSwapSellEvent},
+};
+
+impl From<&PumpFunTradeEvent> for PumpFunParams {
+    /// The creator's first buy (`is_dev_create_token_trade`) rebuilds the launch curve from the
+    /// trade amounts; later trades use the reserves after the trade. The curve is SOL-quoted and
+    /// the token account is kept open after a sell (`close_token_account_when_sell = None`).
+    fn from(e: &PumpFunTradeEvent) -> Self {
+        if e.is_dev_create_token_trade {
+            PumpFunParams::from_dev_trade(
+                e.mint,
+                e.token_amount,
+                e.sol_amount,
+                e.creator,
+                e.bonding_curve,
+                e.associated_bonding_curve,
+                e.creator_vault,
+                None,
+                e.fee_recipient,
+                e.token_program,
+                e.is_cashback_coin,
+                Some(e.mayhem_mode),
+            )
+        } else {
+            PumpFunParams::from_trade(
+                e.bonding_curve,
+                e.associated_bonding_curve,
+                e.mint,
+                Pubkey::default(),
+                e.creator,
+                e.creator_vault,
+                e.virtual_token_reserves,
+                e.virtual_sol_reserves,
+                e.real_token_reserves,
+                e.real_sol_reserves,
+                None,
+                e.fee_recipient,
+                e.token_program,
+                e.is_cashback_coin,
+                Some(e.mayhem_mode),
+            )
+        }
+    }
+}
+
+/// Buy and sell events carry the same pool snapshot; both convert the same way.
+macro_rules! impl_pumpswap_from_event {
+    ($event:ty) => {
+        impl TryFrom<&$event> for PumpSwapParams {
+            type Error = anyhow::Error;
+
+            /// Fails for pools without exactly one WSOL/USDC side; use
+            /// [`PumpSwapParams::traded_mint`] for the mint to trade.
+            fn try_from(e: &$event) -> Result<Self, Self::Error> {
+                let params = PumpSwapParams::from_trade_with_fee_basis_points(
+                    e.pool,
+                    e.base_mint,
+                    e.quote_mint,
+                    e.pool_base_token_account,
+                    e.pool_quote_token_account,
+                    e.pool_base_token_reserves,
+                    e.pool_quote_token_reserves,
+                    e.virtual_quote_reserves,
+                    e.coin_creator_vault_ata,
+                    e.coin_creator_vault_authority,
+                    e.base_token_program,
+                    e.quote_token_program,
+                    e.protocol_fee_recipient,
+                    Pubkey::default(),
+                    e.coin_creator,
+                    e.cashback_fee_basis_points != 0 || e.cashback != 0,
+                    e.cashback_fee_basis_points,
+                    e.lp_fee_basis_points,
+                    e.protocol_fee_basis_points,
+                    e.coin_creator_fee_basis_points,
+                );
+                if params.traded_mint().is_none() {
+                    return Err(anyhow!(
+                        "PumpSwap pool {} must pair one token with WSOL or USDC (base {}, quote {})",
+                        e.pool,
+                        e.base_mint,
+                        e.quote_mint
+                    ));
+                }
+                Ok(params)
+            }
+        }
+    };
+}
+
+impl_pumpswap_from_event!(PumpSwapBuyEvent);
+impl_pumpswap_from_event!(PumpSwapSellEvent);
+
+impl TryFrom<&BonkTradeEvent> for BonkParams {
+    type Error = anyhow::Error;
+
+    /// The creator's first buy (`is_dev_create_token_trade`) rebuilds the launch curve from the
+    /// trade amounts; later trades use the reserves after the trade. The traded mint is
+    /// `base_token_mint`; the quote must be WSOL or USD1.
+    fn try_from(e: &BonkTradeEvent) -> Result<Self, Self::Error> {
+        if e.quote_token_mint != WSOL_TOKEN_ACCOUNT && e.quote_token_mint != USD1_TOKEN_ACCOUNT {
+            return Err(anyhow!(
+                "Bonk pool {} quote {} is neither WSOL nor USD1",
+                e.pool_state,
+                e.quote_token_mint
+            ));
+        }
+        let params = if e.is_dev_create_token_trade {
+            BonkParams::from_dev_trade(
+                e.exact_in,
+                e.amount_in,
+                e.amount_out,
+                e.pool_state,
+                e.base_vault,
+                e.quote_vault,
+                e.base_token_program,
+                e.platform_config,
+                e.platform_associated_account,
+                e.creator_associated_account,
+                e.global_config,
+            )
+        } else {
+            BonkParams::from_trade(
+                e.virtual_base,
+                e.virtual_quote,
+                e.real_base_after,
+                e.real_quote_after,
+                e.pool_state,
+                e.base_vault,
+                e.quote_vault,
+                e.base_token_program,
+                e.platform_config,
+                e.platform_associated_account,
+                e.creator_associated_account,
+                e.global_config,
+            )
+        };
+        Ok(params)
+    }
+}
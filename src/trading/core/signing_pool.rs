@@ -0,0 +1,102 @@
+//! Dedicated signing threads.
+//!
+//! Ed25519 signing plus wire serialization is ~tens of µs of pure CPU per SWQOS lane. During a
+//! burst of concurrent trades that runs back-to-back on tokio worker threads and delays the
+//! reactor; with a [`SigningPool`] the SWQOS lanes hand it to plain OS threads (optionally pinned)
+//! and await the result instead.
+//! 专用签名线程池：签名与序列化移出 tokio 线程，突发并发下避免阻塞 reactor。
+
+use crate::perf::thread_tuning::StageTuning;
+use anyhow::{anyhow, Result};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    mpsc, Arc,
+};
+use tokio::sync::oneshot;
+
+type SignJob = Box<dyn FnOnce() + Send>;
+
+/// Fixed pool of signing threads. Jobs are dispatched round-robin, one channel per thread, so
+/// submitting never takes a lock. Threads exit when the pool is dropped.
+pub struct SigningPool {
+    senders: Vec<mpsc::Sender<SignJob>>,
+    next: AtomicUsize,
+}
+
+impl SigningPool {
+    /// Spawn `threads` signing threads (at least one). With `core_indices`, thread `i` is pinned
+    /// to core `core_indices[i % len]`.
+    pub fn new(threads: usize, core_indices: Option<&[usize]>) -> Result<Arc<Self>> {
+        Self::with_tuning(
+            threads,
+            StageTuning::new(core_indices.map(<[usize]>::to_vec).unwrap_or_default()),
+        )
+    }
+
+    /// Spawn `threads` signing threads pinned and prioritised by `tuning` (thread `i` takes core
+    /// `tuning.cores[i % len]`). A core or priority that cannot be applied is logged and skipped.
+    pub fn with_tuning(threads: usize, tuning: StageTuning) -> Result<Arc<Self>> {
+        let threads = threads.max(1);
+        let mut senders = Vec::with_capacity(threads);
+        for i in 0..threads {
+            let (tx, rx) = mpsc::channel::<SignJob>();
+            let tuning = tuning.clone();
+            std::thread::Builder::new()
+                .name(format!("sol-trade-sign-{}", i))
+                .spawn(move || {
+                    if let Err(e) = tuning.apply_to_current_thread(i) {
+                        if crate::common::sdk_log::sdk_log_enabled() {
+                            tracing::warn!(target: "sol_trade_sdk", "Signing thread {} tuning: {}", i, e);
+                        }
+                    }
+                    while let Ok(job) = rx.recv() {
+                        // A panicking job drops its result sender; keep the thread serving.
+                        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                    }
+                })
+                .map_err(|e| anyhow!("Failed to spawn signing thread: {}", e))?;
+            senders.push(tx);
+        }
+        Ok(Arc::new(Self { senders, next: AtomicUsize::new(0) }))
+    }
+
+    pub fn threads(&self) -> usize {
+        self.senders.len()
+    }
+
+    /// Run `f` on a signing thread and await its result.
+    pub async fn run<R, F>(&self, f: F) -> Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce() -> R + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job: SignJob = Box::new(move || {
+            let _ = tx.send(f());
+        });
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.senders.len();
+        self.senders[index].send(job).map_err(|_| anyhow!("Signing thread exited"))?;
+        rx.await.map_err(|_| anyhow!("Signing job panicked"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_jobs_off_the_runtime_thread() {
+        let pool = SigningPool::new(2, None).unwrap();
+        let caller = std::thread::current().id();
+        let names = futures::future::join_all((0..4).map(|_| {
+            pool.run(move || {
+                assert_ne!(std::thread::current().id(), caller);
+                std::thread::current().name().map(str::to_owned)
+            })
+        }))
+        .await;
+        let names: Vec<String> = names.into_iter().map(|n| n.unwrap().unwrap()).collect();
+        assert!(names.contains(&"sol-trade-sign-0".to_string()));
+        assert!(names.contains(&"sol-trade-sign-1".to_string()));
+    }
+}
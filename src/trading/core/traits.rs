@@ -1,4 +1,5 @@
 use crate::common::SwqosSubmitTiming;
+use crate::trading::factory::DexType;
 use crate::trading::SwapParams;
 use anyhow::Result;
 use solana_sdk::{instruction::Instruction, signature::Signature};
@@ -13,8 +14,12 @@ pub trait TradeExecutor: Send + Sync {
         &self,
         params: SwapParams,
     ) -> Result<(bool, Vec<Signature>, Option<anyhow::Error>, Vec<SwqosSubmitTiming>)>;
-    /// 获取协议名称
-    fn protocol_name(&self) -> &'static str;
+    /// 获取协议类型
+    fn dex_type(&self) -> DexType;
+    /// 获取协议名称（仅用于显示）
+    fn protocol_name(&self) -> &'static str {
+        self.dex_type().name()
+    }
 }
 
 /// 指令构建器trait - 负责构建协议特定的交易指令
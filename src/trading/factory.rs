@@ -1,15 +1,24 @@
-use std::sync::Arc;
-
 use crate::instruction::{
     bonk::BonkInstructionBuilder, meteora_damm_v2::MeteoraDammV2InstructionBuilder,
     pumpfun::PumpFunInstructionBuilder, pumpswap::PumpSwapInstructionBuilder,
     raydium_amm_v4::RaydiumAmmV4InstructionBuilder, raydium_cpmm::RaydiumCpmmInstructionBuilder,
 };
 
-use super::core::{executor::GenericTradeExecutor, traits::TradeExecutor};
+use super::core::{
+    executor::GenericTradeExecutor,
+    traits::{InstructionBuilder, TradeExecutor},
+};
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
 
 /// 支持的交易协议
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Parses from and displays as the variant name (`"PumpFun"`), and serializes the same way so
+/// config files can name protocols. Parsing ignores case and `_`/`-`/`.`/space separators and
+/// takes a few common aliases (`"pump_amm"`, `"launchlab"`, `"cpmm"`, `"amm_v4"`, `"damm_v2"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DexType {
     PumpFun,
     PumpSwap,
@@ -19,80 +28,167 @@ pub enum DexType {
     MeteoraDammV2,
 }
 
-/// 交易工厂 - 用于创建不同协议的交易执行器
-pub struct TradeFactory;
+impl DexType {
+    /// Every supported protocol.
+    pub const ALL: [DexType; 6] = [
+        DexType::PumpFun,
+        DexType::PumpSwap,
+        DexType::Bonk,
+        DexType::RaydiumCpmm,
+        DexType::RaydiumAmmV4,
+        DexType::MeteoraDammV2,
+    ];
 
-impl TradeFactory {
-    /// 创建指定协议的交易执行器（零开销单例）
-    pub fn create_executor(dex_type: DexType) -> Arc<dyn TradeExecutor> {
-        match dex_type {
-            DexType::PumpFun => Self::pumpfun_executor(),
-            DexType::PumpSwap => Self::pumpswap_executor(),
-            DexType::Bonk => Self::bonk_executor(),
-            DexType::RaydiumCpmm => Self::raydium_cpmm_executor(),
-            DexType::RaydiumAmmV4 => Self::raydium_amm_v4_executor(),
-            DexType::MeteoraDammV2 => Self::meteora_damm_v2_executor(),
+    /// Display name, the same as the variant name.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            DexType::PumpFun => "PumpFun",
+            DexType::PumpSwap => "PumpSwap",
+            DexType::Bonk => "Bonk",
+            DexType::RaydiumCpmm => "RaydiumCpmm",
+            DexType::RaydiumAmmV4 => "RaydiumAmmV4",
+            DexType::MeteoraDammV2 => "MeteoraDammV2",
         }
     }
 
-    // Static instances created at compile time - zero runtime overhead
-    #[inline]
-    fn pumpfun_executor() -> Arc<dyn TradeExecutor> {
-        static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
-            std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(PumpFunInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "PumpFun"))
-            });
-        INSTANCE.clone()
+    /// On-chain custom error codes this protocol returns when min-out / max-in slippage is exceeded.
+    pub fn slippage_error_codes(&self) -> &'static [u32] {
+        match self {
+            // TooMuchSolRequired, TooLittleSolReceived
+            DexType::PumpFun => &[6002, 6003],
+            DexType::PumpSwap => &[6004],
+            DexType::Bonk => &[6004],
+            DexType::RaydiumCpmm => &[6005],
+            // AmmError::ExceededSlippage (native program, not Anchor)
+            DexType::RaydiumAmmV4 => &[30],
+            DexType::MeteoraDammV2 => &[6002],
+        }
     }
+}
 
-    #[inline]
-    fn pumpswap_executor() -> Arc<dyn TradeExecutor> {
-        static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
-            std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(PumpSwapInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "PumpSwap"))
-            });
-        INSTANCE.clone()
+impl fmt::Display for DexType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
     }
+}
 
-    #[inline]
-    fn bonk_executor() -> Arc<dyn TradeExecutor> {
-        static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
-            std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(BonkInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "Bonk"))
-            });
-        INSTANCE.clone()
+impl FromStr for DexType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key: String = s
+            .chars()
+            .filter(|c| !matches!(c, '_' | '-' | '.' | ' '))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        Ok(match key.as_str() {
+            "pumpfun" => DexType::PumpFun,
+            "pumpswap" | "pumpamm" => DexType::PumpSwap,
+            "bonk" | "launchlab" | "raydiumlaunchlab" => DexType::Bonk,
+            "raydiumcpmm" | "cpmm" => DexType::RaydiumCpmm,
+            "raydiumammv4" | "raydiumamm" | "ammv4" => DexType::RaydiumAmmV4,
+            "meteoradammv2" | "dammv2" => DexType::MeteoraDammV2,
+            _ => {
+                let names: Vec<&str> = DexType::ALL.iter().map(DexType::name).collect();
+                return Err(anyhow!(
+                    "Unknown DEX type `{}`, expected one of {}",
+                    s,
+                    names.join(", ")
+                ));
+            }
+        })
+    }
+}
+
+impl Serialize for DexType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
     }
+}
 
-    #[inline]
-    fn raydium_cpmm_executor() -> Arc<dyn TradeExecutor> {
-        static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
-            std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(RaydiumCpmmInstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumCpmm"))
-            });
-        INSTANCE.clone()
+impl<'de> Deserialize<'de> for DexType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
     }
+}
+
+// Executors and builders are stateless: one static each, handed out by reference so the hot
+// path neither allocates nor touches a refcount.
+static PUMPFUN_EXECUTOR: GenericTradeExecutor =
+    GenericTradeExecutor::new(&PumpFunInstructionBuilder, DexType::PumpFun);
+static PUMPSWAP_EXECUTOR: GenericTradeExecutor =
+    GenericTradeExecutor::new(&PumpSwapInstructionBuilder, DexType::PumpSwap);
+static BONK_EXECUTOR: GenericTradeExecutor =
+    GenericTradeExecutor::new(&BonkInstructionBuilder, DexType::Bonk);
+static RAYDIUM_CPMM_EXECUTOR: GenericTradeExecutor =
+    GenericTradeExecutor::new(&RaydiumCpmmInstructionBuilder, DexType::RaydiumCpmm);
+static RAYDIUM_AMM_V4_EXECUTOR: GenericTradeExecutor =
+    GenericTradeExecutor::new(&RaydiumAmmV4InstructionBuilder, DexType::RaydiumAmmV4);
+static METEORA_DAMM_V2_EXECUTOR: GenericTradeExecutor =
+    GenericTradeExecutor::new(&MeteoraDammV2InstructionBuilder, DexType::MeteoraDammV2);
 
+/// 交易工厂 - 用于获取不同协议的交易执行器
+pub struct TradeFactory;
+
+impl TradeFactory {
+    /// 获取指定协议的交易执行器（静态单例，无分配、无引用计数）
     #[inline]
-    fn raydium_amm_v4_executor() -> Arc<dyn TradeExecutor> {
-        static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
-            std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(RaydiumAmmV4InstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "RaydiumAmmV4"))
-            });
-        INSTANCE.clone()
+    pub fn create_executor(dex_type: DexType) -> &'static dyn TradeExecutor {
+        match dex_type {
+            DexType::PumpFun => &PUMPFUN_EXECUTOR,
+            DexType::PumpSwap => &PUMPSWAP_EXECUTOR,
+            DexType::Bonk => &BONK_EXECUTOR,
+            DexType::RaydiumCpmm => &RAYDIUM_CPMM_EXECUTOR,
+            DexType::RaydiumAmmV4 => &RAYDIUM_AMM_V4_EXECUTOR,
+            DexType::MeteoraDammV2 => &METEORA_DAMM_V2_EXECUTOR,
+        }
     }
 
+    /// 获取指定协议的指令构建器（静态单例）
     #[inline]
-    fn meteora_damm_v2_executor() -> Arc<dyn TradeExecutor> {
-        static INSTANCE: std::sync::LazyLock<Arc<dyn TradeExecutor>> =
-            std::sync::LazyLock::new(|| {
-                let instruction_builder = Arc::new(MeteoraDammV2InstructionBuilder);
-                Arc::new(GenericTradeExecutor::new(instruction_builder, "MeteoraDammV2"))
-            });
-        INSTANCE.clone()
+    pub fn instruction_builder(dex_type: DexType) -> &'static dyn InstructionBuilder {
+        match dex_type {
+            DexType::PumpFun => &PumpFunInstructionBuilder,
+            DexType::PumpSwap => &PumpSwapInstructionBuilder,
+            DexType::Bonk => &BonkInstructionBuilder,
+            DexType::RaydiumCpmm => &RaydiumCpmmInstructionBuilder,
+            DexType::RaydiumAmmV4 => &RaydiumAmmV4InstructionBuilder,
+            DexType::MeteoraDammV2 => &MeteoraDammV2InstructionBuilder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn executors_are_shared_statics() {
+        for dex in DexType::ALL {
+            let (a, b) = (TradeFactory::create_executor(dex), TradeFactory::create_executor(dex));
+            assert!(std::ptr::addr_eq(a, b));
+            assert_eq!(a.dex_type(), dex);
+            assert_eq!(a.protocol_name(), format!("{:?}", dex));
+        }
+    }
+
+    #[test]
+    fn dex_type_parses_displays_and_serializes_by_name() {
+        for dex in DexType::ALL {
+            assert_eq!(dex.to_string().parse::<DexType>().unwrap(), dex);
+            assert_eq!(dex.to_string().to_lowercase().parse::<DexType>().unwrap(), dex);
+            let json = serde_json::to_string(&dex).unwrap();
+            assert_eq!(json, format!("\"{}\"", dex));
+            assert_eq!(serde_json::from_str::<DexType>(&json).unwrap(), dex);
+        }
+        assert_eq!("raydium_amm_v4".parse::<DexType>().unwrap(), DexType::RaydiumAmmV4);
+        assert_eq!("pump-amm".parse::<DexType>().unwrap(), DexType::PumpSwap);
+        assert_eq!(
+            serde_json::from_str::<Vec<DexType>>(r#"["pump.fun", "damm_v2"]"#).unwrap(),
+            vec![DexType::PumpFun, DexType::MeteoraDammV2]
+        );
+        let err = "orca".parse::<DexType>().unwrap_err().to_string();
+        assert!(err.contains("orca") && err.contains("MeteoraDammV2"), "{err}");
     }
 }
@@ -0,0 +1,187 @@
+//! Send modes: direct SWQOS submission, Jito tip-only, and gasless trading through a
+//! fee-sponsorship relayer (Octane-style).
+//!
+//! With [`SendMode::Sponsored`] the SDK builds the trade with the sponsor's
+//! [`FeeSponsor::fee_payer`] as fee payer, signs it with the trading wallet and hands it to
+//! [`FeeSponsor::sponsor`]. The relayer checks it, adds the fee payer signature and returns it;
+//! the SDK verifies that nothing but that signature changed and broadcasts it through the
+//! `Default` (RPC) route. Sponsored transactions carry no relay tip and take a recent blockhash,
+//! not a durable nonce.
+//! 手续费代付：交易交给代付中继签名（中继为 fee payer），再由 SDK 经 RPC 广播。
+
+use anyhow::{anyhow, bail, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SPONSOR_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How a trade reaches the chain.
+#[derive(Clone, Default)]
+pub enum SendMode {
+    /// Sign as the trading wallet and submit through every configured SWQOS route.
+    #[default]
+    Direct,
+    /// Let the relayer pay the fee; see the module docs.
+    Sponsored(Arc<dyn FeeSponsor>),
+    /// Submit through the Jito routes only, always tipped and without a CU price: the bundle tip
+    /// alone buys priority, so paying a priority fee as well is wasted. The CU limit is kept.
+    JitoTipOnly,
+}
+
+impl SendMode {
+    pub fn sponsor(&self) -> Option<&Arc<dyn FeeSponsor>> {
+        match self {
+            SendMode::Direct | SendMode::JitoTipOnly => None,
+            SendMode::Sponsored(sponsor) => Some(sponsor),
+        }
+    }
+}
+
+impl fmt::Debug for SendMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendMode::Direct => f.write_str("Direct"),
+            SendMode::JitoTipOnly => f.write_str("JitoTipOnly"),
+            SendMode::Sponsored(sponsor) => write!(f, "Sponsored({})", sponsor.fee_payer()),
+        }
+    }
+}
+
+/// A relayer that pays transaction fees.
+#[async_trait::async_trait]
+pub trait FeeSponsor: Send + Sync {
+    /// Account the relayer signs and pays fees with; placed first in the message.
+    fn fee_payer(&self) -> Pubkey;
+
+    /// Add the fee payer signature to `transaction`, which every other signer already signed.
+    async fn sponsor(&self, transaction: VersionedTransaction) -> Result<VersionedTransaction>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct SponsorMessage {
+    /// Base64 of the bincode wire transaction.
+    transaction: String,
+}
+
+/// [`FeeSponsor`] over HTTP: POSTs `{"transaction": "<base64>"}` to the endpoint and expects the
+/// co-signed transaction back in the same shape.
+pub struct HttpFeeSponsor {
+    endpoint: String,
+    fee_payer: Pubkey,
+    api_key: Option<String>,
+    client: Client,
+}
+
+impl HttpFeeSponsor {
+    pub fn new(endpoint: impl Into<String>, fee_payer: Pubkey) -> Self {
+        let client = Client::builder()
+            .timeout(SPONSOR_REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self { endpoint: endpoint.into(), fee_payer, api_key: None, client }
+    }
+
+    /// Sent as a bearer token.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl FeeSponsor for HttpFeeSponsor {
+    fn fee_payer(&self) -> Pubkey {
+        self.fee_payer
+    }
+
+    async fn sponsor(&self, transaction: VersionedTransaction) -> Result<VersionedTransaction> {
+        let body =
+            SponsorMessage { transaction: STANDARD.encode(bincode::serialize(&transaction)?) };
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!("Fee sponsor rejected the transaction ({}): {}", status, text);
+        }
+        let reply: SponsorMessage = response.json().await?;
+        let bytes = STANDARD
+            .decode(reply.transaction.trim())
+            .map_err(|e| anyhow!("Fee sponsor returned invalid base64: {}", e))?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+}
+
+/// Check a sponsor's reply against the transaction sent: same message, every signature valid.
+pub(crate) fn verify_sponsored(
+    sent: &VersionedTransaction,
+    sponsored: &VersionedTransaction,
+) -> Result<()> {
+    if sponsored.message != sent.message {
+        bail!("Fee sponsor changed the transaction message");
+    }
+    if sponsored.signatures.len() != sent.signatures.len()
+        || sponsored.signatures[0] == Signature::default()
+    {
+        bail!("Fee sponsor did not sign as fee payer");
+    }
+    if !sponsored.verify_with_results().iter().all(|ok| *ok) {
+        bail!("Sponsored transaction has an invalid signature");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::common::transaction_builder::build_sponsored_transaction;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    #[test]
+    fn sponsored_transaction_leaves_the_fee_payer_slot_to_the_sponsor() {
+        let sponsor = Keypair::new();
+        let owner = Arc::new(Keypair::new());
+        let instructions = vec![Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(owner.pubkey(), true)],
+            data: vec![1],
+        }];
+        let sent = build_sponsored_transaction(
+            &sponsor.pubkey(),
+            &[owner.clone()],
+            200_000,
+            1_000,
+            &instructions,
+            &[],
+            Some(Hash::new_unique()),
+            None,
+            None,
+            None,
+            true,
+        )
+        .unwrap();
+        assert_eq!(sent.message.static_account_keys()[0], sponsor.pubkey());
+        assert_eq!(sent.signatures[0], Signature::default());
+        assert!(verify_sponsored(&sent, &sent).is_err());
+
+        let mut sponsored = sent.clone();
+        sponsored.signatures[0] = sponsor.sign_message(&sent.message.serialize());
+        verify_sponsored(&sent, &sponsored).unwrap();
+
+        let mut tampered = sponsored.clone();
+        tampered.signatures[1] = Keypair::new().sign_message(&sent.message.serialize());
+        assert!(verify_sponsored(&sent, &tampered).is_err());
+    }
+}
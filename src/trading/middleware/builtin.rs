@@ -1,3 +1,4 @@
+use crate::trading::factory::DexType;
 use crate::trading::middleware::traits::InstructionMiddleware;
 use anyhow::Result;
 use solana_sdk::instruction::Instruction;
@@ -14,13 +15,13 @@ impl InstructionMiddleware for LoggingMiddleware {
     fn process_protocol_instructions(
         &self,
         protocol_instructions: Vec<Instruction>,
-        protocol_name: &str,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         println!("-------------------[{}]-------------------", self.name());
         println!("process_protocol_instructions");
         println!("[{}] Instruction count: {}", self.name(), protocol_instructions.len());
-        println!("[{}] Protocol name: {}\n", self.name(), protocol_name);
+        println!("[{}] Protocol name: {}\n", self.name(), dex_type);
         println!("[{}] Is buy: {}", self.name(), is_buy);
         for (i, instruction) in protocol_instructions.iter().enumerate() {
             println!("Instruction {}:", i + 1);
@@ -32,13 +33,13 @@ impl InstructionMiddleware for LoggingMiddleware {
     fn process_full_instructions(
         &self,
         full_instructions: Vec<Instruction>,
-        protocol_name: &str,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         println!("-------------------[{}]-------------------", self.name());
         println!("process_full_instructions");
         println!("[{}] Instruction count: {}", self.name(), full_instructions.len());
-        println!("[{}] Protocol name: {}\n", self.name(), protocol_name);
+        println!("[{}] Protocol name: {}\n", self.name(), dex_type);
         println!("[{}] Is buy: {}", self.name(), is_buy);
         for (i, instruction) in full_instructions.iter().enumerate() {
             println!("Instruction {}:", i + 1);
@@ -47,6 +48,18 @@ impl InstructionMiddleware for LoggingMiddleware {
         Ok(full_instructions)
     }
 
+    fn process_utility_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        println!("-------------------[{}]-------------------", self.name());
+        println!("process_utility_instructions");
+        println!("[{}] Instruction count: {}", self.name(), full_instructions.len());
+        println!("[{}] Is buy: {}", self.name(), is_buy);
+        Ok(full_instructions)
+    }
+
     fn clone_box(&self) -> Box<dyn InstructionMiddleware> {
         Box::new(self.clone())
     }
@@ -1,9 +1,13 @@
+use crate::trading::factory::DexType;
 use anyhow::Result;
 use solana_sdk::instruction::Instruction;
 
 /// Instruction middleware trait
 ///
-/// Used to modify, add or remove protocol_instructions before transaction execution
+/// Used to modify, add or remove protocol_instructions before transaction execution. DEX trades go
+/// through the `process_*` methods with their protocol; transactions that are not a trade
+/// (utility sends, split setup transactions) go through
+/// [`process_utility_instructions`](Self::process_utility_instructions).
 pub trait InstructionMiddleware: Send + Sync {
     /// Middleware name
     fn name(&self) -> &'static str;
@@ -12,7 +16,7 @@ pub trait InstructionMiddleware: Send + Sync {
     ///
     /// # Arguments
     /// * `protocol_instructions` - Current instruction list
-    /// * `protocol_name` - Protocol name
+    /// * `dex_type` - Protocol of the trade
     /// * `is_buy` - Whether the transaction is a buy transaction
     ///
     /// # Returns
@@ -20,7 +24,7 @@ pub trait InstructionMiddleware: Send + Sync {
     fn process_protocol_instructions(
         &self,
         protocol_instructions: Vec<Instruction>,
-        protocol_name: &str,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>>;
 
@@ -28,7 +32,7 @@ pub trait InstructionMiddleware: Send + Sync {
     ///
     /// # Arguments
     /// * `full_instructions` - Current instruction list
-    /// * `protocol_name` - Protocol name
+    /// * `dex_type` - Protocol of the trade
     /// * `is_buy` - Whether the transaction is a buy transaction
     ///
     /// # Returns
@@ -36,10 +40,24 @@ pub trait InstructionMiddleware: Send + Sync {
     fn process_full_instructions(
         &self,
         full_instructions: Vec<Instruction>,
-        protocol_name: &str,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>>;
 
+    /// Processes the full instructions of a transaction that is not a DEX trade. Returns them
+    /// unchanged by default.
+    ///
+    /// # Arguments
+    /// * `full_instructions` - Current instruction list
+    /// * `is_buy` - Side the transaction was built for
+    fn process_utility_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        _is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        Ok(full_instructions)
+    }
+
     /// Clone middleware
     fn clone_box(&self) -> Box<dyn InstructionMiddleware>;
 }
@@ -72,12 +90,28 @@ impl MiddlewareManager {
     pub fn apply_middlewares_process_full_instructions(
         &self,
         mut full_instructions: Vec<Instruction>,
-        protocol_name: &str,
+        dex_type: DexType,
+        is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        for middleware in &self.middlewares {
+            full_instructions =
+                middleware.process_full_instructions(full_instructions, dex_type, is_buy)?;
+            if full_instructions.is_empty() {
+                break;
+            }
+        }
+        Ok(full_instructions)
+    }
+
+    /// Apply all middlewares to the full instructions of a non-DEX transaction
+    pub fn apply_middlewares_process_utility_instructions(
+        &self,
+        mut full_instructions: Vec<Instruction>,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         for middleware in &self.middlewares {
             full_instructions =
-                middleware.process_full_instructions(full_instructions, protocol_name, is_buy)?;
+                middleware.process_utility_instructions(full_instructions, is_buy)?;
             if full_instructions.is_empty() {
                 break;
             }
@@ -85,17 +119,35 @@ impl MiddlewareManager {
         Ok(full_instructions)
     }
 
+    /// Apply all middlewares to the full instructions of a transaction; `dex_type` is `None` for
+    /// transactions that are not a DEX trade.
+    pub fn apply_middlewares_process_transaction_instructions(
+        &self,
+        full_instructions: Vec<Instruction>,
+        dex_type: Option<DexType>,
+        is_buy: bool,
+    ) -> Result<Vec<Instruction>> {
+        match dex_type {
+            Some(dex_type) => self.apply_middlewares_process_full_instructions(
+                full_instructions,
+                dex_type,
+                is_buy,
+            ),
+            None => self.apply_middlewares_process_utility_instructions(full_instructions, is_buy),
+        }
+    }
+
     /// Apply all middlewares to process protocol_instructions
     pub fn apply_middlewares_process_protocol_instructions(
         &self,
         mut protocol_instructions: Vec<Instruction>,
-        protocol_name: &str,
+        dex_type: DexType,
         is_buy: bool,
     ) -> Result<Vec<Instruction>> {
         for middleware in &self.middlewares {
             protocol_instructions = middleware.process_protocol_instructions(
                 protocol_instructions,
-                protocol_name,
+                dex_type,
                 is_buy,
             )?;
             if protocol_instructions.is_empty() {
@@ -1,9 +1,14 @@
+pub mod account_discovery;
+pub mod arb;
 pub mod common;
 pub mod core;
 pub mod factory;
+pub mod fee_sponsor;
 pub mod middleware;
+pub mod params_provider;
 
 pub use core::params::SwapParams;
 pub use core::traits::InstructionBuilder;
 pub use factory::TradeFactory;
 pub use middleware::{InstructionMiddleware, MiddlewareManager};
+pub use params_provider::ParamsProvider;
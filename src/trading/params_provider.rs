@@ -0,0 +1,465 @@
+//! In-memory, subscription-fed store of pre-decoded [`DexParamEnum`]s keyed by pool.
+//! 池参数提供者：订阅池子相关账户的更新，内存中始终保存最新的 `DexParamEnum`，交易时按池子 key 取参数，无需 RPC。
+//!
+//! Pools are loaded once via RPC (or inserted from a parsed event), then only the accounts that
+//! carry reserves are watched: the PumpFun bonding curve, PumpSwap / Raydium CPMM vaults, the
+//! Bonk pool state, and the Raydium AMM v4 vaults, open orders and AMM account. Meteora DAMM v2
+//! params hold no reserve snapshot and never change after loading.
+//!
+//! [`ParamsProvider::subscribe`] feeds updates from the RPC websocket (`accountSubscribe`).
+//! Yellowstone gRPC streams are fed through [`ParamsProvider::apply_account_update`] with the
+//! account key, data and slot of each `SubscribeUpdateAccount`; subscribe to
+//! [`ParamsProvider::watched_accounts`].
+
+use crate::common::bonding_curve::BondingCurveAccount;
+use crate::common::subscription_handle::SubscriptionHandle;
+use crate::common::SolanaRpcClient;
+use crate::instruction::utils::bonk_types::PoolStateLayout as BonkPoolStateLayout;
+use crate::instruction::utils::raydium_amm_v4_types::{
+    open_orders_totals_decode, AmmInfoLayout, AmmReserves,
+};
+use crate::trading::core::params::{
+    BonkParams, DexParamEnum, MeteoraDammV2Params, PumpFunParams, PumpSwapParams,
+    RaydiumAmmV4Params, RaydiumCpmmParams,
+};
+use crate::trading::factory::DexType;
+use borsh::BorshDeserialize;
+use futures::StreamExt;
+use parking_lot::RwLock;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client_api::config::RpcAccountInfoConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Which reserve field of a pool an account update refreshes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchRole {
+    BondingCurve,
+    BaseVault,
+    QuoteVault,
+    BonkPoolState,
+    AmmInfo,
+    AmmCoinVault,
+    AmmPcVault,
+    AmmOpenOrders,
+}
+
+struct PoolEntry {
+    params: DexParamEnum,
+    /// AMM v4 reserves are derived from four accounts; keep the components between updates.
+    amm: AmmComponents,
+}
+
+/// Raw amounts of the four AMM v4 reserve accounts, each with the slot it was read at.
+#[derive(Default)]
+struct AmmComponents {
+    raw: AmmReserves,
+    /// Slots of the AMM account, coin vault, pc vault and open orders updates, in that order.
+    slots: [Option<u64>; 4],
+}
+
+impl AmmComponents {
+    /// Whether every account has reported for `slot`, so `raw` is one consistent snapshot.
+    fn complete_at(&self, slot: u64) -> bool {
+        self.slots.iter().all(|reported| *reported == Some(slot))
+    }
+}
+
+#[derive(Default)]
+struct WatchedAccount {
+    targets: Vec<(Pubkey, WatchRole)>,
+    slot: u64,
+}
+
+#[derive(Default)]
+struct ProviderState {
+    pools: HashMap<Pubkey, PoolEntry>,
+    watched: HashMap<Pubkey, WatchedAccount>,
+}
+
+/// Fresh protocol params for a configured set of pools, readable without RPC.
+///
+/// Pools are keyed by pool address, except PumpFun (mint) and Bonk (mint), matching the
+/// `from_*_by_rpc` constructors used by [`ParamsProvider::load`].
+#[derive(Default)]
+pub struct ParamsProvider {
+    state: RwLock<ProviderState>,
+}
+
+impl ParamsProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetch the params of `key` via RPC and start tracking it. `usd1_pool` only applies to Bonk.
+    pub async fn load(
+        &self,
+        rpc: &SolanaRpcClient,
+        dex_type: DexType,
+        key: Pubkey,
+        usd1_pool: bool,
+    ) -> Result<(), anyhow::Error> {
+        let params = match dex_type {
+            DexType::PumpFun => {
+                DexParamEnum::PumpFun(PumpFunParams::from_mint_by_rpc(rpc, &key).await?)
+            }
+            DexType::PumpSwap => {
+                DexParamEnum::PumpSwap(PumpSwapParams::from_pool_address_by_rpc(rpc, &key).await?)
+            }
+            DexType::Bonk => {
+                DexParamEnum::Bonk(BonkParams::from_mint_by_rpc(rpc, &key, usd1_pool).await?)
+            }
+            DexType::RaydiumCpmm => DexParamEnum::RaydiumCpmm(
+                RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &key).await?,
+            ),
+            DexType::RaydiumAmmV4 => DexParamEnum::RaydiumAmmV4(
+                RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, key).await?,
+            ),
+            DexType::MeteoraDammV2 => DexParamEnum::MeteoraDammV2(
+                MeteoraDammV2Params::from_pool_address_by_rpc(rpc, &key).await?,
+            ),
+        };
+        self.insert(key, params);
+        Ok(())
+    }
+
+    /// Track params built elsewhere (e.g. from a parsed trade event), replacing any previous
+    /// params of `key` and the accounts they watched. AMM v4 keeps the params' `coin_reserve` /
+    /// `pc_reserve` until all four accounts have reported for the same slot.
+    pub fn insert(&self, key: Pubkey, params: DexParamEnum) {
+        let mut state = self.state.write();
+        for watched in state.watched.values_mut() {
+            watched.targets.retain(|(pool, _)| *pool != key);
+        }
+        for (account, role) in watch_list(&params) {
+            state.watched.entry(account).or_default().targets.push((key, role));
+        }
+        // Accounts of the replaced params that no pool watches any more.
+        state.watched.retain(|_, watched| !watched.targets.is_empty());
+        state.pools.insert(key, PoolEntry { params, amm: AmmComponents::default() });
+    }
+
+    /// Stop tracking `key`.
+    pub fn remove(&self, key: &Pubkey) {
+        let mut state = self.state.write();
+        state.pools.remove(key);
+        state.watched.retain(|_, watched| {
+            watched.targets.retain(|(pool, _)| pool != key);
+            !watched.targets.is_empty()
+        });
+    }
+
+    /// Latest params of `key`; clone them into `TradeBuyParams::extension_params`.
+    pub fn get(&self, key: &Pubkey) -> Option<DexParamEnum> {
+        self.state.read().pools.get(key).map(|entry| entry.params.clone())
+    }
+
+    /// Accounts whose updates must be fed to [`Self::apply_account_update`].
+    pub fn watched_accounts(&self) -> Vec<Pubkey> {
+        self.state.read().watched.keys().copied().collect()
+    }
+
+    /// Apply an account update from any stream. Updates older than the last applied slot of the
+    /// same account are ignored. Returns whether any tracked pool changed.
+    pub fn apply_account_update(&self, account: &Pubkey, data: &[u8], slot: u64) -> bool {
+        let mut state = self.state.write();
+        let ProviderState { pools, watched } = &mut *state;
+        let Some(watched) = watched.get_mut(account) else {
+            return false;
+        };
+        if slot < watched.slot {
+            return false;
+        }
+        watched.slot = slot;
+        let mut changed = false;
+        for (pool, role) in &watched.targets {
+            if let Some(entry) = pools.get_mut(pool) {
+                changed |= apply_update(entry, *role, data, slot);
+            }
+        }
+        changed
+    }
+
+    /// Stream updates of every watched account over the RPC websocket. Pools added afterwards
+    /// are not subscribed; load pools first or resubscribe.
+    pub async fn subscribe(
+        self: Arc<Self>,
+        ws_url: &str,
+    ) -> Result<SubscriptionHandle, anyhow::Error> {
+        let accounts = self.watched_accounts();
+        if accounts.is_empty() {
+            return Err(anyhow::anyhow!("ParamsProvider has no pool to watch"));
+        }
+        let client = PubsubClient::new(ws_url).await?;
+        let task = tokio::spawn(async move {
+            futures::future::join_all(
+                accounts.iter().map(|account| watch_account(&client, &self, *account)),
+            )
+            .await;
+        });
+        Ok(SubscriptionHandle { task, unsub_fn: Box::new(|| {}) })
+    }
+}
+
+/// Accounts carrying the reserves of `params`.
+fn watch_list(params: &DexParamEnum) -> Vec<(Pubkey, WatchRole)> {
+    match params {
+        DexParamEnum::PumpFun(p) => vec![(p.bonding_curve.account, WatchRole::BondingCurve)],
+        DexParamEnum::PumpSwap(p) => vec![
+            (p.pool_base_token_account, WatchRole::BaseVault),
+            (p.pool_quote_token_account, WatchRole::QuoteVault),
+        ],
+        DexParamEnum::Bonk(p) => vec![(p.pool_state, WatchRole::BonkPoolState)],
+        DexParamEnum::RaydiumCpmm(p) => {
+            vec![(p.base_vault, WatchRole::BaseVault), (p.quote_vault, WatchRole::QuoteVault)]
+        }
+        DexParamEnum::RaydiumAmmV4(p) => vec![
+            (p.amm, WatchRole::AmmInfo),
+            (p.token_coin, WatchRole::AmmCoinVault),
+            (p.token_pc, WatchRole::AmmPcVault),
+            (p.amm_open_orders, WatchRole::AmmOpenOrders),
+        ],
+        DexParamEnum::MeteoraDammV2(_) => Vec::new(),
+    }
+}
+
+/// SPL token account amount (bytes 64..72).
+#[inline]
+fn token_amount(data: &[u8]) -> Option<u64> {
+    data.get(64..72).and_then(|b| b.try_into().ok()).map(u64::from_le_bytes)
+}
+
+fn apply_update(entry: &mut PoolEntry, role: WatchRole, data: &[u8], slot: u64) -> bool {
+    match (&mut entry.params, role) {
+        (DexParamEnum::PumpFun(p), WatchRole::BondingCurve) => {
+            let Some(fresh) =
+                data.get(8..).and_then(|mut d| BondingCurveAccount::deserialize(&mut d).ok())
+            else {
+                return false;
+            };
+            let mut curve = (*p.bonding_curve).clone();
+            curve.virtual_token_reserves = fresh.virtual_token_reserves;
+            curve.virtual_sol_reserves = fresh.virtual_sol_reserves;
+            curve.real_token_reserves = fresh.real_token_reserves;
+            curve.real_sol_reserves = fresh.real_sol_reserves;
+            curve.complete = fresh.complete;
+            p.bonding_curve = Arc::new(curve);
+            true
+        }
+        (DexParamEnum::PumpSwap(p), WatchRole::BaseVault) => {
+            token_amount(data).map(|amount| p.pool_base_token_reserves = amount).is_some()
+        }
+        (DexParamEnum::PumpSwap(p), WatchRole::QuoteVault) => {
+            token_amount(data).map(|amount| p.pool_quote_token_reserves = amount).is_some()
+        }
+        (DexParamEnum::RaydiumCpmm(p), WatchRole::BaseVault) => {
+            token_amount(data).map(|amount| p.base_reserve = amount).is_some()
+        }
+        (DexParamEnum::RaydiumCpmm(p), WatchRole::QuoteVault) => {
+            token_amount(data).map(|amount| p.quote_reserve = amount).is_some()
+        }
+        (DexParamEnum::Bonk(p), WatchRole::BonkPoolState) => {
+            let Some(pool) = data.get(8..).and_then(BonkPoolStateLayout::decode_from_slice) else {
+                return false;
+            };
+            p.virtual_base = pool.virtual_base() as u128;
+            p.virtual_quote = pool.virtual_quote() as u128;
+            p.real_base = pool.real_base() as u128;
+            p.real_quote = pool.real_quote() as u128;
+            true
+        }
+        (DexParamEnum::RaydiumAmmV4(p), role) => {
+            let amm = &mut entry.amm;
+            let raw = &mut amm.raw;
+            let (index, decoded) = match role {
+                WatchRole::AmmInfo => (
+                    0,
+                    AmmInfoLayout::decode_from_slice(data).map(|info| {
+                        raw.need_take_pnl_coin = info.need_take_pnl_coin();
+                        raw.need_take_pnl_pc = info.need_take_pnl_pc();
+                    }),
+                ),
+                WatchRole::AmmCoinVault => {
+                    (1, token_amount(data).map(|amount| raw.coin_vault_amount = amount))
+                }
+                WatchRole::AmmPcVault => {
+                    (2, token_amount(data).map(|amount| raw.pc_vault_amount = amount))
+                }
+                WatchRole::AmmOpenOrders => (
+                    3,
+                    open_orders_totals_decode(data).map(|(coin, pc)| {
+                        raw.open_orders_coin_total = coin;
+                        raw.open_orders_pc_total = pc;
+                    }),
+                ),
+                _ => return false,
+            };
+            if decoded.is_none() {
+                return false;
+            }
+            amm.slots[index] = Some(slot);
+            if !amm.complete_at(slot) {
+                return false;
+            }
+            p.coin_reserve = amm.raw.coin_reserve();
+            p.pc_reserve = amm.raw.pc_reserve();
+            true
+        }
+        _ => false,
+    }
+}
+
+async fn watch_account(client: &PubsubClient, provider: &ParamsProvider, account: Pubkey) {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(CommitmentConfig::processed()),
+        ..Default::default()
+    };
+    let (mut stream, unsubscribe) = match client.account_subscribe(&account, Some(config)).await {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            if crate::common::sdk_log::sdk_log_enabled() {
+                tracing::warn!(target: "sol_trade_sdk", "accountSubscribe {} failed: {}", account, e);
+            }
+            return;
+        }
+    };
+    while let Some(response) = stream.next().await {
+        if let Some(data) = response.value.data.decode() {
+            provider.apply_account_update(&account, &data, response.context.slot);
+        }
+    }
+    unsubscribe().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vault_data(amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn vault_updates_refresh_cpmm_reserves_in_slot_order() {
+        let provider = ParamsProvider::new();
+        let pool = Pubkey::new_unique();
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let params = RaydiumCpmmParams::from_trade(
+            pool,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            base_vault,
+            quote_vault,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            0,
+            0,
+        );
+        provider.insert(pool, DexParamEnum::RaydiumCpmm(params));
+        assert_eq!(provider.watched_accounts().len(), 2);
+
+        assert!(provider.apply_account_update(&base_vault, &vault_data(500), 10));
+        assert!(provider.apply_account_update(&quote_vault, &vault_data(7), 10));
+        // Stale slot is dropped.
+        assert!(!provider.apply_account_update(&base_vault, &vault_data(1), 9));
+        assert!(!provider.apply_account_update(&Pubkey::new_unique(), &vault_data(1), 11));
+
+        let Some(DexParamEnum::RaydiumCpmm(p)) = provider.get(&pool) else {
+            panic!("pool missing");
+        };
+        assert_eq!((p.base_reserve, p.quote_reserve), (500, 7));
+
+        provider.remove(&pool);
+        assert!(provider.get(&pool).is_none());
+        assert!(provider.watched_accounts().is_empty());
+    }
+
+    fn amm_v4_params(amm: Pubkey, coin_reserve: u64, pc_reserve: u64) -> RaydiumAmmV4Params {
+        RaydiumAmmV4Params {
+            amm,
+            coin_mint: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            token_coin: Pubkey::new_unique(),
+            token_pc: Pubkey::new_unique(),
+            amm_open_orders: Pubkey::new_unique(),
+            amm_target_orders: Pubkey::new_unique(),
+            serum_program: Pubkey::new_unique(),
+            serum_market: Pubkey::new_unique(),
+            serum_bids: Pubkey::new_unique(),
+            serum_asks: Pubkey::new_unique(),
+            serum_event_queue: Pubkey::new_unique(),
+            serum_coin_vault_account: Pubkey::new_unique(),
+            serum_pc_vault_account: Pubkey::new_unique(),
+            serum_vault_signer: Pubkey::new_unique(),
+            coin_reserve,
+            pc_reserve,
+        }
+    }
+
+    fn open_orders_data(coin: u64, pc: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 3228];
+        data[85..93].copy_from_slice(&coin.to_le_bytes());
+        data[101..109].copy_from_slice(&pc.to_le_bytes());
+        data
+    }
+
+    fn amm_reserves(provider: &ParamsProvider, key: &Pubkey) -> (u64, u64) {
+        let Some(DexParamEnum::RaydiumAmmV4(p)) = provider.get(key) else {
+            panic!("pool missing");
+        };
+        (p.coin_reserve, p.pc_reserve)
+    }
+
+    #[test]
+    fn amm_v4_reserves_wait_for_all_four_accounts_in_one_slot() {
+        use crate::instruction::utils::raydium_amm_v4_types::AMM_INFO_SIZE;
+
+        let provider = ParamsProvider::new();
+        let key = Pubkey::new_unique();
+        let params = amm_v4_params(key, 1_000, 2_000);
+        provider.insert(key, DexParamEnum::RaydiumAmmV4(params.clone()));
+        let amm_info = vec![0u8; AMM_INFO_SIZE];
+
+        // Partial updates keep the seeded net reserves.
+        assert!(!provider.apply_account_update(&params.token_coin, &vault_data(900), 10));
+        assert!(!provider.apply_account_update(&params.token_pc, &vault_data(2_100), 10));
+        assert!(!provider.apply_account_update(
+            &params.amm_open_orders,
+            &open_orders_data(5, 7),
+            10
+        ));
+        assert_eq!(amm_reserves(&provider, &key), (1_000, 2_000));
+
+        assert!(provider.apply_account_update(&params.amm, &amm_info, 10));
+        assert_eq!(amm_reserves(&provider, &key), (905, 2_107));
+
+        // A vault moving alone in a later slot does not mix with the older components.
+        assert!(!provider.apply_account_update(&params.token_coin, &vault_data(800), 11));
+        assert_eq!(amm_reserves(&provider, &key), (905, 2_107));
+    }
+
+    #[test]
+    fn reinserting_a_pool_stops_watching_its_old_accounts() {
+        let provider = ParamsProvider::new();
+        let key = Pubkey::new_unique();
+        let old = amm_v4_params(key, 1_000, 2_000);
+        provider.insert(key, DexParamEnum::RaydiumAmmV4(old.clone()));
+
+        let new = RaydiumAmmV4Params { token_coin: Pubkey::new_unique(), ..old.clone() };
+        provider.insert(key, DexParamEnum::RaydiumAmmV4(new.clone()));
+
+        let watched = provider.watched_accounts();
+        assert_eq!(watched.len(), 4);
+        assert!(!watched.contains(&old.token_coin));
+        assert!(watched.contains(&new.token_coin));
+        assert!(!provider.apply_account_update(&old.token_coin, &vault_data(1), 10));
+    }
+}
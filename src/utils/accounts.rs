@@ -0,0 +1,160 @@
+//! Balance queries for SOL and SPL token accounts.
+//!
+//! [`get_multi_token_balances`] decodes any number of token accounts (Token or Token-2022) from
+//! `getMultipleAccounts`, so pool vaults, portfolios or watch lists cost one RPC round trip per
+//! [`MAX_ACCOUNTS_PER_REQUEST`] accounts instead of one `getTokenAccountBalance` each. The
+//! single-account helpers resolve the owner's ATA the same way the trade instructions do.
+//! 账户余额工具：批量查询代币账户余额与所有者，以及 SOL / 单个代币余额。
+
+use crate::common::fast_fn::get_associated_token_address_with_program_id_fast_use_seed;
+use crate::common::SolanaRpcClient;
+use crate::constants::{TOKEN_PROGRAM, TOKEN_PROGRAM_2022};
+use anyhow::anyhow;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+/// Accounts per `getMultipleAccounts` call (RPC limit).
+pub const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Size of the SPL token account layout; Token-2022 extensions follow it.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// A decoded token account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAccountBalance {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    /// Wallet (or PDA) that owns the tokens.
+    pub owner: Pubkey,
+    /// Raw amount in base units.
+    pub amount: u64,
+    /// Token or Token-2022.
+    pub token_program: Pubkey,
+}
+
+impl TokenAccountBalance {
+    /// Decode `account` at `address`; fails when it is not an initialized token account.
+    pub fn decode(address: Pubkey, account: &Account) -> Result<Self, anyhow::Error> {
+        if account.owner != TOKEN_PROGRAM && account.owner != TOKEN_PROGRAM_2022 {
+            return Err(anyhow!(
+                "Account {} is not a token account (owner {})",
+                address,
+                account.owner
+            ));
+        }
+        let data = &account.data;
+        // 165 = mint(32) owner(32) amount(8) delegate(36) state(1) ...; state 0 is uninitialized.
+        if data.len() < TOKEN_ACCOUNT_LEN || data[108] == 0 {
+            return Err(anyhow!("Account {} is not an initialized token account", address));
+        }
+        let pubkey_at = |offset: usize| {
+            Pubkey::new_from_array(data[offset..offset + 32].try_into().expect("32 bytes"))
+        };
+        Ok(Self {
+            address,
+            mint: pubkey_at(0),
+            owner: pubkey_at(32),
+            amount: u64::from_le_bytes(data[64..72].try_into().expect("8 bytes")),
+            token_program: account.owner,
+        })
+    }
+}
+
+/// Balances and owners of `token_accounts`, in order; `None` where the account does not exist.
+///
+/// Any mix of mints and owners is fine. Fails if an existing account is not a token account.
+pub async fn get_multi_token_balances(
+    rpc: &SolanaRpcClient,
+    token_accounts: &[Pubkey],
+) -> Result<Vec<Option<TokenAccountBalance>>, anyhow::Error> {
+    let chunks = futures::future::try_join_all(
+        token_accounts.chunks(MAX_ACCOUNTS_PER_REQUEST).map(|c| rpc.get_multiple_accounts(c)),
+    )
+    .await?;
+    token_accounts
+        .iter()
+        .zip(chunks.into_iter().flatten())
+        .map(|(address, account)| {
+            account.map(|account| TokenAccountBalance::decode(*address, &account)).transpose()
+        })
+        .collect()
+}
+
+#[inline]
+pub async fn get_sol_balance(
+    rpc: &SolanaRpcClient,
+    account: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    let balance = rpc.get_balance(account).await?;
+    Ok(balance)
+}
+
+/// Balance of `payer`'s standard SPL Token ATA for `mint`.
+#[inline]
+pub async fn get_token_balance(
+    rpc: &SolanaRpcClient,
+    payer: &Pubkey,
+    mint: &Pubkey,
+) -> Result<u64, anyhow::Error> {
+    get_token_balance_with_options(rpc, payer, mint, &TOKEN_PROGRAM, false).await
+}
+
+/// 使用与交易指令一致的 ATA 推导（可选 seed）查询余额；卖出/余额查询应与买入使用同一 ATA 地址。
+#[inline]
+pub async fn get_token_balance_with_options(
+    rpc: &SolanaRpcClient,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    use_seed: bool,
+) -> Result<u64, anyhow::Error> {
+    let ata = get_associated_token_address_with_program_id_fast_use_seed(
+        payer,
+        mint,
+        token_program,
+        use_seed,
+    );
+    let balance = rpc.get_token_account_balance(&ata).await?;
+    let balance_u64 =
+        balance.amount.parse::<u64>().map_err(|_| anyhow!("Failed to parse token balance"))?;
+    Ok(balance_u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(owner_program: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN + 7];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        data[108] = 1;
+        Account {
+            lamports: 2_039_280,
+            data,
+            owner: owner_program,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn decodes_token_and_token_2022_accounts() {
+        let (address, mint, owner) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let balance = TokenAccountBalance::decode(
+            address,
+            &token_account(TOKEN_PROGRAM_2022, mint, owner, 42),
+        )
+        .unwrap();
+        assert_eq!((balance.mint, balance.owner, balance.amount), (mint, owner, 42));
+        assert_eq!(balance.token_program, TOKEN_PROGRAM_2022);
+
+        let mut uninitialized = token_account(TOKEN_PROGRAM, mint, owner, 0);
+        uninitialized.data[108] = 0;
+        assert!(TokenAccountBalance::decode(address, &uninitialized).is_err());
+        let not_token = token_account(Pubkey::new_unique(), mint, owner, 1);
+        assert!(TokenAccountBalance::decode(address, &not_token).is_err());
+    }
+}
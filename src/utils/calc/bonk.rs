@@ -1,4 +1,24 @@
 use crate::instruction::utils::bonk::accounts;
+use crate::utils::calc::common::FeeBreakdown;
+
+/// Protocol, platform and share fees charged on `amount` (in SOL lamports).
+#[inline]
+fn bonk_fees(amount: u128) -> FeeBreakdown {
+    let fee = |rate: u128| (amount.saturating_mul(rate) / 10000).min(u64::MAX as u128) as u64;
+    FeeBreakdown {
+        protocol_fee: fee(accounts::PROTOCOL_FEE_RATE),
+        platform_fee: fee(accounts::PLATFORM_FEE_RATE),
+        share_fee: fee(accounts::SHARE_FEE_RATE),
+        ..Default::default()
+    }
+}
+
+/// `amount` less `slippage_basis_points` (capped at 100%), saturated to `u64`.
+#[inline]
+fn apply_slippage(amount: u128, slippage_basis_points: u128) -> u64 {
+    let slippage = amount.saturating_mul(slippage_basis_points.min(10000)) / 10000;
+    (amount - slippage).min(u64::MAX as u128) as u64
+}
 
 /// Calculates the amount of tokens to receive when buying with SOL
 ///
@@ -16,7 +36,8 @@ use crate::instruction::utils::bonk::accounts;
 ///
 /// # Returns
 ///
-/// The minimum amount of tokens that will be received after fees and slippage
+/// The minimum amount of tokens that will be received after fees and slippage, or `0` when the
+/// reserves are inconsistent (`real_base > virtual_base`) or the math overflows `u128`.
 pub fn get_buy_token_amount_from_sol_amount(
     amount_in: u64,
     virtual_base: u128,
@@ -25,34 +46,51 @@ pub fn get_buy_token_amount_from_sol_amount(
     real_quote: u128,
     slippage_basis_points: u128,
 ) -> u64 {
+    get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+        amount_in,
+        virtual_base,
+        virtual_quote,
+        real_base,
+        real_quote,
+        slippage_basis_points,
+    )
+    .0
+}
+
+/// Same as [`get_buy_token_amount_from_sol_amount`], also returning the fees taken from
+/// `amount_in` (in lamports).
+pub fn get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+    amount_in: u64,
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+    slippage_basis_points: u128,
+) -> (u64, FeeBreakdown) {
     let amount_in_u128 = amount_in as u128;
 
     // Calculate various fees deducted from input amount
-    let protocol_fee = (amount_in_u128 * accounts::PROTOCOL_FEE_RATE / 10000) as u128;
-    let platform_fee = (amount_in_u128 * accounts::PLATFORM_FEE_RATE / 10000) as u128;
-    let share_fee = (amount_in_u128 * accounts::SHARE_FEE_RATE / 10000) as u128;
+    let fees = bonk_fees(amount_in_u128);
 
     // Calculate net input amount after deducting all fees
-    let amount_in_net = amount_in_u128
-        .checked_sub(protocol_fee)
-        .unwrap()
-        .checked_sub(platform_fee)
-        .unwrap()
-        .checked_sub(share_fee)
-        .unwrap();
+    let amount_in_net = amount_in_u128.saturating_sub(fees.total() as u128);
 
     // Calculate total reserves (virtual + real)
-    let input_reserve = virtual_quote.checked_add(real_quote).unwrap();
-    let output_reserve = virtual_base.checked_sub(real_base).unwrap();
+    let (Some(input_reserve), Some(output_reserve)) =
+        (virtual_quote.checked_add(real_quote), virtual_base.checked_sub(real_base))
+    else {
+        return (0, fees);
+    };
 
     // Apply constant product formula: amount_out = (amount_in * output_reserve) / (input_reserve + amount_in)
-    let numerator = amount_in_net.checked_mul(output_reserve).unwrap();
-    let denominator = input_reserve.checked_add(amount_in_net).unwrap();
-    let mut amount_out = numerator.checked_div(denominator).unwrap();
+    let amount_out = amount_in_net
+        .checked_mul(output_reserve)
+        .zip(input_reserve.checked_add(amount_in_net))
+        .and_then(|(numerator, denominator)| numerator.checked_div(denominator))
+        .unwrap_or(0);
 
     // Apply slippage protection
-    amount_out = amount_out - (amount_out * slippage_basis_points) / 10000;
-    amount_out as u64
+    (apply_slippage(amount_out, slippage_basis_points), fees)
 }
 
 /// Calculates the amount of SOL to receive when selling tokens
@@ -71,7 +109,8 @@ pub fn get_buy_token_amount_from_sol_amount(
 ///
 /// # Returns
 ///
-/// The minimum amount of SOL that will be received after fees and slippage
+/// The minimum amount of SOL that will be received after fees and slippage, or `0` when the
+/// reserves are inconsistent (`real_base > virtual_base`) or the math overflows `u128`.
 pub fn get_sell_sol_amount_from_token_amount(
     amount_in: u64,
     virtual_base: u128,
@@ -80,33 +119,166 @@ pub fn get_sell_sol_amount_from_token_amount(
     real_quote: u128,
     slippage_basis_points: u128,
 ) -> u64 {
+    get_sell_sol_amount_from_token_amount_with_fee_breakdown(
+        amount_in,
+        virtual_base,
+        virtual_quote,
+        real_base,
+        real_quote,
+        slippage_basis_points,
+    )
+    .0
+}
+
+/// Same as [`get_sell_sol_amount_from_token_amount`], also returning the fees deducted from the
+/// SOL output (in lamports).
+pub fn get_sell_sol_amount_from_token_amount_with_fee_breakdown(
+    amount_in: u64,
+    virtual_base: u128,
+    virtual_quote: u128,
+    real_base: u128,
+    real_quote: u128,
+    slippage_basis_points: u128,
+) -> (u64, FeeBreakdown) {
     let amount_in_u128 = amount_in as u128;
 
     // For sell operation, input_reserve is token reserves, output_reserve is SOL reserves
-    let input_reserve = virtual_base.checked_sub(real_base).unwrap();
-    let output_reserve = virtual_quote.checked_add(real_quote).unwrap();
+    let (Some(input_reserve), Some(output_reserve)) =
+        (virtual_base.checked_sub(real_base), virtual_quote.checked_add(real_quote))
+    else {
+        return (0, FeeBreakdown::default());
+    };
 
     // Use constant product formula to calculate SOL amount received from selling tokens
-    let numerator = amount_in_u128.checked_mul(output_reserve).unwrap();
-    let denominator = input_reserve.checked_add(amount_in_u128).unwrap();
-    let sol_amount_out = numerator.checked_div(denominator).unwrap();
+    let sol_amount_out = amount_in_u128
+        .checked_mul(output_reserve)
+        .zip(input_reserve.checked_add(amount_in_u128))
+        .and_then(|(numerator, denominator)| numerator.checked_div(denominator))
+        .unwrap_or(0);
 
     // Calculate various fees
-    let protocol_fee = (sol_amount_out * accounts::PROTOCOL_FEE_RATE / 10000) as u128;
-    let platform_fee = (sol_amount_out * accounts::PLATFORM_FEE_RATE / 10000) as u128;
-    let share_fee = (sol_amount_out * accounts::SHARE_FEE_RATE / 10000) as u128;
+    let fees = bonk_fees(sol_amount_out);
 
     // Net SOL amount after deducting fees
-    let sol_amount_net = sol_amount_out
-        .checked_sub(protocol_fee)
-        .unwrap()
-        .checked_sub(platform_fee)
-        .unwrap()
-        .checked_sub(share_fee)
-        .unwrap();
+    let sol_amount_net = sol_amount_out.saturating_sub(fees.total() as u128);
 
     // Apply slippage protection
-    let final_amount = sol_amount_net - (sol_amount_net * slippage_basis_points) / 10000;
+    (apply_slippage(sol_amount_net, slippage_basis_points), fees)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Quote reserves small enough for `virtual_quote + real_quote`, and so any SOL output, to
+    /// fit in `u64`.
+    fn quote_reserve() -> impl Strategy<Value = u128> {
+        (0..=u64::MAX / 2).prop_map(u128::from)
+    }
+
+    /// `(virtual_base, real_base)` with `real_base <= virtual_base`, as on a live pool.
+    fn base_reserves() -> impl Strategy<Value = (u128, u128)> {
+        any::<u64>().prop_flat_map(|virtual_base| {
+            (Just(virtual_base as u128), (0..=virtual_base).prop_map(u128::from))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics_on_any_input(
+            amount_in in any::<u64>(),
+            virtual_base in any::<u128>(),
+            virtual_quote in any::<u128>(),
+            real_base in any::<u128>(),
+            real_quote in any::<u128>(),
+            slippage_basis_points in any::<u128>()
+        ) {
+            get_buy_token_amount_from_sol_amount(
+                amount_in,
+                virtual_base,
+                virtual_quote,
+                real_base,
+                real_quote,
+                slippage_basis_points,
+            );
+            get_sell_sol_amount_from_token_amount(
+                amount_in,
+                virtual_base,
+                virtual_quote,
+                real_base,
+                real_quote,
+                slippage_basis_points,
+            );
+        }
+
+        #[test]
+        fn outputs_are_monotonic_in_the_input(
+            (virtual_base, real_base) in base_reserves(),
+            virtual_quote in quote_reserve(),
+            real_quote in quote_reserve(),
+            slippage_basis_points in 0..=10_000u128,
+            a in any::<u64>(),
+            b in any::<u64>()
+        ) {
+            let (lo, hi) = (a.min(b), a.max(b));
+            let buy = |amount| {
+                let (tokens, fees) = get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+                    amount,
+                    virtual_base,
+                    virtual_quote,
+                    real_base,
+                    real_quote,
+                    slippage_basis_points,
+                );
+                (tokens, amount - fees.total())
+            };
+            let sell = |amount| {
+                let (sol, fees) = get_sell_sol_amount_from_token_amount_with_fee_breakdown(
+                    amount,
+                    virtual_base,
+                    virtual_quote,
+                    real_base,
+                    real_quote,
+                    0,
+                );
+                sol + fees.total()
+            };
+            // Each fee is floored on its own, so one more lamport in can add more than one
+            // lamport of fees; compare around the fees instead.
+            let ((tokens_lo, net_lo), (tokens_hi, net_hi)) = (buy(lo), buy(hi));
+            if net_lo <= net_hi {
+                prop_assert!(tokens_lo <= tokens_hi);
+            }
+            prop_assert!(sell(lo) <= sell(hi));
+        }
 
-    final_amount as u64
+        #[test]
+        fn buy_then_sell_never_mints_sol(
+            (virtual_base, real_base) in base_reserves(),
+            virtual_quote in quote_reserve(),
+            real_quote in quote_reserve(),
+            amount_in in any::<u64>()
+        ) {
+            let (tokens, fees) = get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+                amount_in,
+                virtual_base,
+                virtual_quote,
+                real_base,
+                real_quote,
+                0,
+            );
+            // Fees leave the pool; the tokens bought count as sold from its real base reserves.
+            let sol_in = (amount_in - fees.total()) as u128;
+            let sold = get_sell_sol_amount_from_token_amount(
+                tokens,
+                virtual_base,
+                virtual_quote,
+                real_base + tokens as u128,
+                real_quote + sol_in,
+                0,
+            );
+            prop_assert!(sold as u128 <= sol_in);
+        }
+    }
 }
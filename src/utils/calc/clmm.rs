@@ -0,0 +1,268 @@
+//! Tick math and the exact-input swap loop shared by concentrated-liquidity pools. Orca
+//! Whirlpool and Raydium CLMM are both Uniswap v3 style programs over Q64.64 sqrt prices; only
+//! their account fields and fee splits differ.
+//!
+//! Inside a tick range the pool is a constant-liquidity curve over the sqrt price, and crossing
+//! an initialized tick adds or removes its `liquidity_net`. The loop stops at the last tick it is
+//! given and reports a partial fill.
+//! 集中流动性池（Orca Whirlpool / Raydium CLMM）共用的 tick 数学与 exact-in 兑换循环。
+
+use super::fixed_point::{div_round, mul_div, U256};
+
+pub const MIN_TICK_INDEX: i32 = -443_636;
+pub const MAX_TICK_INDEX: i32 = 443_636;
+/// Sqrt price (Q64.64) at [`MIN_TICK_INDEX`].
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+/// Sqrt price (Q64.64) at [`MAX_TICK_INDEX`].
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+/// Fee rates are in hundredths of a basis point (`3000` = 0.3%).
+pub const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// `2^128 / sqrt(1.0001)^(2^i)`, one entry per bit of `|tick|`.
+const INVERSE_SQRT_RATIOS_X128: [u128; 19] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e2139,
+    0xfff2e50f5f656932ef12357cf3c7fdcb,
+    0xffe5caca7e10e4e61c3624eaa0941ccf,
+    0xffcb9843d60f6159c9db58835c926643,
+    0xff973b41fa98c081472e6896dfb254bf,
+    0xff2ea16466c96a3843ec78b326b52860,
+    0xfe5dee046a99a2a811c461f1969c3052,
+    0xfcbe86c7900a88aedcffc83b479aa3a3,
+    0xf987a7253ac413176f2b074cf7815e53,
+    0xf3392b0822b70005940c7a398e4b70f2,
+    0xe7159475a2c29b7443b29c7fa6e889d8,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e4,
+    0x70d869a156d2a1b890bb3df62baf32f6,
+    0x31be135f97d08fd981231505542fcfa5,
+    0x9aa508b5b7a84e1c677de54f3e99bc8,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe97,
+];
+
+pub(crate) const Q64: u128 = 1 << 64;
+
+/// An initialized tick from a pool's tick arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmmTick {
+    pub index: i32,
+    /// Liquidity added when the price crosses the tick upward (removed crossing downward)
+    pub liquidity_net: i128,
+}
+
+/// Pool state a swap starts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmmPool {
+    /// Current sqrt price (Q64.64)
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    /// Liquidity active at the current price
+    pub liquidity: u128,
+    /// Trade fee in hundredths of a basis point
+    pub fee_rate: u32,
+}
+
+/// Outcome of [`swap_exact_in`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmmSwap {
+    /// Whether the entire input amount is traded within the given ticks
+    pub all_trade: bool,
+    /// The input amount actually traded, fees included
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// The trading fee amount, in input tokens
+    pub fee: u64,
+    /// Sqrt price (Q64.64) after the swap
+    pub end_sqrt_price: u128,
+}
+
+/// Sqrt price (Q64.64) at `tick_index`, clamped to the valid tick range.
+///
+/// Uses the same bit decomposition as the program; results can differ from it in the last few
+/// bits, which moves range boundaries by far less than one token unit.
+pub fn sqrt_price_at_tick(tick_index: i32) -> u128 {
+    let tick = tick_index.clamp(MIN_TICK_INDEX, MAX_TICK_INDEX);
+    let abs_tick = tick.unsigned_abs();
+    // `1 / sqrt(1.0001)^|tick|` in Q128, starting just below 1.0.
+    let inverse = INVERSE_SQRT_RATIOS_X128
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| abs_tick & (1 << bit) != 0)
+        .fold(u128::MAX, |ratio, (_, factor)| {
+            U256::full_mul(ratio, *factor).shr(128).to_u128().unwrap_or_default()
+        });
+    let sqrt_price = if tick >= 0 {
+        div_round(U256::MAX, U256::new(inverse), false).map_or(MAX_SQRT_PRICE_X64, |p| p >> 64)
+    } else {
+        inverse >> 64
+    };
+    sqrt_price.clamp(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64)
+}
+
+/// Token A amount between two sqrt prices: `liquidity * (upper - lower) / (upper * lower)`.
+fn amount_delta_a(p0: u128, p1: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    let (lower, upper) = (p0.min(p1), p0.max(p1));
+    let numerator = U256::full_mul(liquidity, upper - lower).checked_shl(64)?;
+    div_round(numerator, U256::full_mul(upper, lower), round_up)
+}
+
+/// Token B amount between two sqrt prices: `liquidity * (upper - lower)`.
+fn amount_delta_b(p0: u128, p1: u128, liquidity: u128, round_up: bool) -> Option<u128> {
+    mul_div(liquidity, p0.abs_diff(p1), Q64, round_up)
+}
+
+/// Sqrt price after `amount` of input enters at `sqrt_price`, rounded so the pool never gives
+/// out more than the input pays for.
+fn next_sqrt_price(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount: u128,
+    zero_for_one: bool,
+) -> Option<u128> {
+    if amount == 0 {
+        return Some(sqrt_price);
+    }
+    if zero_for_one {
+        let numerator = U256::full_mul(liquidity, sqrt_price).checked_shl(64)?;
+        let denominator = U256::new(liquidity)
+            .checked_shl(64)?
+            .checked_add(U256::full_mul(sqrt_price, amount))?;
+        div_round(numerator, denominator, true)
+    } else {
+        sqrt_price.checked_add(mul_div(amount, Q64, liquidity, false)?)
+    }
+}
+
+struct SwapStep {
+    amount_in: u128,
+    amount_out: u128,
+    fee: u128,
+    next_sqrt_price: u128,
+    reached_target: bool,
+}
+
+/// One constant-liquidity step from `sqrt_price` toward `target`, spending at most `remaining`.
+fn swap_step(
+    sqrt_price: u128,
+    target: u128,
+    liquidity: u128,
+    remaining: u128,
+    fee_rate: u128,
+    zero_for_one: bool,
+) -> Option<SwapStep> {
+    let amount_delta_in = |to| {
+        if zero_for_one {
+            amount_delta_a(sqrt_price, to, liquidity, true)
+        } else {
+            amount_delta_b(sqrt_price, to, liquidity, true)
+        }
+    };
+    let remaining_less_fee =
+        mul_div(remaining, FEE_RATE_DENOMINATOR - fee_rate, FEE_RATE_DENOMINATOR, false)?;
+    // `None` when the range takes more input than is left (or than `u128` holds): the step ends
+    // inside it.
+    let max_in = amount_delta_in(target).filter(|max_in| *max_in <= remaining_less_fee);
+    let (amount_in, next_sqrt_price) = match max_in {
+        Some(max_in) => (max_in, target),
+        None => {
+            let next = next_sqrt_price(sqrt_price, liquidity, remaining_less_fee, zero_for_one)?;
+            (amount_delta_in(next)?, next)
+        }
+    };
+    let amount_out = if zero_for_one {
+        amount_delta_b(sqrt_price, next_sqrt_price, liquidity, false)?
+    } else {
+        amount_delta_a(sqrt_price, next_sqrt_price, liquidity, false)?
+    };
+    // A step that stops inside the range keeps the whole rest of the input as fee.
+    let fee = match max_in {
+        Some(_) => mul_div(amount_in, fee_rate, FEE_RATE_DENOMINATOR - fee_rate, true)?,
+        None => remaining.saturating_sub(amount_in),
+    };
+    Some(SwapStep { amount_in, amount_out, fee, next_sqrt_price, reached_target: max_in.is_some() })
+}
+
+/// Swap `amount_in` (fees included) through `pool`, crossing the given initialized ticks.
+///
+/// `ticks` may come in any order. `on_step_fee` sees the fee of each step, for programs that
+/// split it per step (protocol and fund shares are floored step by step on chain).
+pub fn swap_exact_in(
+    pool: &ClmmPool,
+    ticks: &[ClmmTick],
+    zero_for_one: bool,
+    amount_in: u64,
+    mut on_step_fee: impl FnMut(u128),
+) -> Result<ClmmSwap, String> {
+    let fee_rate = pool.fee_rate as u128;
+    if fee_rate >= FEE_RATE_DENOMINATOR {
+        return Err("Fee rate must be below 100%.".to_string());
+    }
+    let overflow = || "CLMM swap amount overflowed.".to_string();
+
+    let mut remaining = amount_in as u128;
+    let mut amount_out = 0u128;
+    let mut fee = 0u128;
+    let mut sqrt_price = pool.sqrt_price;
+    let mut tick_current_index = pool.tick_current_index;
+    let mut liquidity = pool.liquidity;
+
+    while remaining > 0 {
+        // Going down the current tick itself is still ahead; going up it is behind.
+        let next_tick = if zero_for_one {
+            ticks.iter().filter(|t| t.index <= tick_current_index).max_by_key(|t| t.index)
+        } else {
+            ticks.iter().filter(|t| t.index > tick_current_index).min_by_key(|t| t.index)
+        };
+        let Some(next_tick) = next_tick else {
+            break;
+        };
+        // Never target a price behind the current one, whatever the last bits of the tick math.
+        let target = sqrt_price_at_tick(next_tick.index);
+        let target = if zero_for_one { target.min(sqrt_price) } else { target.max(sqrt_price) };
+
+        let step = swap_step(sqrt_price, target, liquidity, remaining, fee_rate, zero_for_one)
+            .ok_or_else(overflow)?;
+        remaining = remaining.saturating_sub(step.amount_in + step.fee);
+        amount_out = amount_out.checked_add(step.amount_out).ok_or_else(overflow)?;
+        fee += step.fee;
+        on_step_fee(step.fee);
+        sqrt_price = step.next_sqrt_price;
+        if !step.reached_target {
+            break;
+        }
+
+        let liquidity_net = if zero_for_one {
+            next_tick.liquidity_net.checked_neg()
+        } else {
+            Some(next_tick.liquidity_net)
+        };
+        liquidity =
+            liquidity_net.and_then(|net| liquidity.checked_add_signed(net)).ok_or_else(|| {
+                format!("Liquidity turns negative crossing tick {}.", next_tick.index)
+            })?;
+        tick_current_index = if zero_for_one { next_tick.index - 1 } else { next_tick.index };
+    }
+
+    Ok(ClmmSwap {
+        all_trade: remaining == 0,
+        amount_in: amount_in - remaining as u64,
+        amount_out: u64::try_from(amount_out).map_err(|_| overflow())?,
+        fee: fee as u64,
+        end_sqrt_price: sqrt_price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_price_matches_the_tick_bounds() {
+        assert_eq!(sqrt_price_at_tick(0), Q64);
+        assert_eq!(sqrt_price_at_tick(MIN_TICK_INDEX), MIN_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_at_tick(MAX_TICK_INDEX), MAX_SQRT_PRICE_X64);
+        assert_eq!(sqrt_price_at_tick(1), 18_447_666_387_855_959_850);
+        assert_eq!(sqrt_price_at_tick(-1), 18_445_821_805_675_392_311);
+    }
+}
@@ -1,3 +1,33 @@
+/// Fees charged by one swap, split by recipient, so net proceeds can be compared across venues.
+///
+/// Each calc function documents the token its fees are charged in (the quote token on most
+/// buys and sells; the input token for Raydium trade fees).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// Fee paid to the protocol (PumpFun/PumpSwap protocol fee, Raydium protocol and fund fees)
+    pub protocol_fee: u64,
+    /// Fee paid to the launch platform (Bonk)
+    pub platform_fee: u64,
+    /// Fee paid to the coin creator, including cashback on PumpSwap
+    pub creator_fee: u64,
+    /// Fee shared with the referrer (Bonk)
+    pub share_fee: u64,
+    /// Fee left in the pool for liquidity providers (PumpSwap, Raydium)
+    pub lp_fee: u64,
+}
+
+impl FeeBreakdown {
+    /// Sum of all fees.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.protocol_fee
+            .saturating_add(self.platform_fee)
+            .saturating_add(self.creator_fee)
+            .saturating_add(self.share_fee)
+            .saturating_add(self.lp_fee)
+    }
+}
+
 /// Calculate transaction fee based on amount and fee basis points
 ///
 /// # Parameters
@@ -107,6 +137,7 @@ pub const fn calculate_with_slippage_sell(amount: u64, basis_points: u64) -> u64
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn ceil_div_handles_u128_max_without_addition_overflow() {
@@ -127,4 +158,17 @@ mod tests {
         assert_eq!(calculate_with_slippage_sell(10_000, u64::MAX), 1);
         assert_eq!(calculate_with_slippage_sell(1, u64::MAX), 1);
     }
+
+    proptest! {
+        #[test]
+        fn fees_and_slippage_stay_within_the_amount(
+            amount in any::<u64>(),
+            fee_basis_points in 0..=10_000u128,
+            basis_points in any::<u64>()
+        ) {
+            prop_assert!(compute_fee(amount as u128, fee_basis_points) <= amount as u128);
+            prop_assert!(calculate_with_slippage_buy(amount, basis_points) >= amount);
+            prop_assert!(calculate_with_slippage_sell(amount, basis_points) <= amount);
+        }
+    }
 }
@@ -0,0 +1,218 @@
+//! Liquidity depth of constant-product pools: price impact of a trade size, and the largest trade
+//! that stays under an impact threshold.
+//!
+//! Price impact is the gap between the average execution price and the spot price, before fees:
+//! putting `dx` into a pool with input reserve `X` costs `dx / (X + dx)`. Fees only shrink the
+//! amount reaching the pool, so a size computed here stays under the threshold with them too.
+//! 池子深度：估算价格冲击，以及在冲击阈值内的最大交易量（用于 TWAP/冰山单、风控与狙击仓位）。
+
+use crate::swqos::TradeType;
+use crate::trading::core::params::DexParamEnum;
+use crate::trading::factory::DexType;
+use anyhow::anyhow;
+use solana_sdk::pubkey::Pubkey;
+
+const BPS: u128 = 10_000;
+
+/// Reserves a trade of one side would swap against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolDepth {
+    /// Reserve the input is added to (quote on buy, mint on sell).
+    pub input_reserve: u64,
+    /// Reserve the output is taken from.
+    pub output_reserve: u64,
+    /// Most output the pool can pay out, when lower than `output_reserve` (bonding curve
+    /// inventory); `None` when the whole reserve is reachable.
+    pub output_cap: Option<u64>,
+}
+
+impl PoolDepth {
+    /// Reserves of `params` for trading `mint` on `side`.
+    pub fn from_params(
+        dex_type: DexType,
+        params: &DexParamEnum,
+        mint: &Pubkey,
+        side: TradeType,
+    ) -> Result<Self, anyhow::Error> {
+        // (mint reserve, quote reserve, cap on mint out, cap on quote out)
+        let (mint_reserve, quote_reserve, mint_cap, quote_cap) = match (dex_type, params) {
+            (DexType::PumpFun, DexParamEnum::PumpFun(p)) => {
+                let curve = &p.bonding_curve;
+                (
+                    curve.virtual_token_reserves as u128,
+                    curve.virtual_sol_reserves as u128,
+                    Some(curve.real_token_reserves as u128),
+                    Some(curve.real_sol_reserves as u128),
+                )
+            }
+            (DexType::PumpSwap, DexParamEnum::PumpSwap(p)) => {
+                let quote = p.pool_quote_token_reserves as i128 + p.virtual_quote_reserves;
+                if quote <= 0 {
+                    return Err(anyhow!("PumpSwap pool {} has no effective quote reserve", p.pool));
+                }
+                let (base, quote) = (p.pool_base_token_reserves as u128, quote as u128);
+                // Pool-v2 virtual quote is priced in but cannot be withdrawn.
+                let quote_cap = Some(p.pool_quote_token_reserves as u128);
+                if *mint == p.base_mint {
+                    (base, quote, None, quote_cap)
+                } else if *mint == p.quote_mint {
+                    (quote, base, quote_cap, None)
+                } else {
+                    return Err(anyhow!("Mint {} is not in PumpSwap pool {}", mint, p.pool));
+                }
+            }
+            (DexType::Bonk, DexParamEnum::Bonk(p)) => (
+                p.virtual_base.saturating_sub(p.real_base),
+                p.virtual_quote.saturating_add(p.real_quote),
+                None,
+                Some(p.real_quote),
+            ),
+            (DexType::RaydiumCpmm, DexParamEnum::RaydiumCpmm(p)) => {
+                let (base, quote) = (p.base_reserve as u128, p.quote_reserve as u128);
+                if *mint == p.base_mint {
+                    (base, quote, None, None)
+                } else if *mint == p.quote_mint {
+                    (quote, base, None, None)
+                } else {
+                    return Err(anyhow!("Mint {} is not in CPMM pool {}", mint, p.pool_state));
+                }
+            }
+            (DexType::RaydiumAmmV4, DexParamEnum::RaydiumAmmV4(p)) => {
+                let (coin, pc) = (p.coin_reserve as u128, p.pc_reserve as u128);
+                if *mint == p.coin_mint {
+                    (coin, pc, None, None)
+                } else if *mint == p.pc_mint {
+                    (pc, coin, None, None)
+                } else {
+                    return Err(anyhow!("Mint {} is not in AMM v4 pool {}", mint, p.amm));
+                }
+            }
+            (DexType::MeteoraDammV2, DexParamEnum::MeteoraDammV2(_)) => {
+                return Err(anyhow!(
+                    "MeteoraDammV2 uses concentrated liquidity; depth needs the pool's sqrt price \
+                     and liquidity, which the trade params do not carry"
+                ));
+            }
+            (dex_type, _) => {
+                return Err(anyhow!("Params do not match dex type {:?}", dex_type));
+            }
+        };
+
+        let (input, output, cap) = match side {
+            TradeType::Buy => (quote_reserve, mint_reserve, mint_cap),
+            TradeType::Sell => (mint_reserve, quote_reserve, quote_cap),
+            other => return Err(anyhow!("Depth is only defined for buy/sell, not {:?}", other)),
+        };
+        if input == 0 || output == 0 {
+            return Err(anyhow!("Pool has an empty reserve"));
+        }
+        let to_u64 = |v: u128| u64::try_from(v).unwrap_or(u64::MAX);
+        Ok(Self {
+            input_reserve: to_u64(input),
+            output_reserve: to_u64(output),
+            output_cap: cap.filter(|c| *c < output).map(to_u64),
+        })
+    }
+
+    /// Output for `amount_in` before fees.
+    pub fn amount_out(&self, amount_in: u64) -> u64 {
+        let (x, y) = (self.input_reserve as u128, self.output_reserve as u128);
+        let out = y * amount_in as u128 / (x + amount_in as u128);
+        let out = self.output_cap.map_or(out, |cap| out.min(cap as u128));
+        out as u64
+    }
+
+    /// Price impact of `amount_in`, in basis points (rounded up).
+    pub fn price_impact_bps(&self, amount_in: u64) -> u64 {
+        let (x, dx) = (self.input_reserve as u128, amount_in as u128);
+        (dx * BPS).div_ceil(x + dx) as u64
+    }
+
+    /// Largest input keeping price impact at or under `max_price_impact_bps`, also bounded by
+    /// [`Self::output_cap`].
+    pub fn max_input(&self, max_price_impact_bps: u64) -> Result<u64, anyhow::Error> {
+        let b = max_price_impact_bps as u128;
+        if b == 0 || b >= BPS {
+            return Err(anyhow!(
+                "max_price_impact_bps must be in 1..=9999, got {}",
+                max_price_impact_bps
+            ));
+        }
+        // dx / (X + dx) <= b / BPS  <=>  dx <= X * b / (BPS - b)
+        let x = self.input_reserve as u128;
+        let mut max = x * b / (BPS - b);
+        if let Some(cap) = self.output_cap {
+            // y * dx / (X + dx) <= cap  <=>  dx <= X * cap / (y - cap)
+            let (y, cap) = (self.output_reserve as u128, cap as u128);
+            max = max.min(x * cap / (y - cap));
+        }
+        Ok(u64::try_from(max).unwrap_or(u64::MAX))
+    }
+}
+
+/// Largest trade under a price impact threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxTradeSize {
+    /// Input amount in base units (quote on buy, mint on sell).
+    pub max_input: u64,
+    /// Output for `max_input`, before fees.
+    pub expected_output: u64,
+    /// Impact of `max_input`, in basis points; at most the requested threshold.
+    pub price_impact_bps: u64,
+    /// The pool's remaining inventory, not the impact threshold, set `max_input`.
+    pub capped_by_inventory: bool,
+    pub depth: PoolDepth,
+}
+
+/// Largest input for trading `mint` on `side` whose price impact stays at or under
+/// `max_price_impact_bps`, from the reserves in `params`.
+///
+/// Refresh the params first (e.g. `from_mint_by_rpc`); the estimate is only as fresh as their
+/// reserves. Meteora DAMM v2 (concentrated liquidity) is not supported.
+pub fn estimate_max_trade_size(
+    dex_type: DexType,
+    params: &DexParamEnum,
+    mint: &Pubkey,
+    side: TradeType,
+    max_price_impact_bps: u64,
+) -> Result<MaxTradeSize, anyhow::Error> {
+    let depth = PoolDepth::from_params(dex_type, params, mint, side)?;
+    let max_input = depth.max_input(max_price_impact_bps)?;
+    let uncapped = PoolDepth { output_cap: None, ..depth }.max_input(max_price_impact_bps)?;
+    Ok(MaxTradeSize {
+        max_input,
+        expected_output: depth.amount_out(max_input),
+        price_impact_bps: depth.price_impact_bps(max_input),
+        capped_by_inventory: max_input < uncapped,
+        depth,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(input: u64, output: u64, cap: Option<u64>) -> PoolDepth {
+        PoolDepth { input_reserve: input, output_reserve: output, output_cap: cap }
+    }
+
+    #[test]
+    fn max_input_inverts_price_impact() {
+        let pool = depth(1_000_000_000, 500_000_000, None);
+        // 1% impact: dx = X / 99.
+        let max = pool.max_input(100).unwrap();
+        assert_eq!(max, 10_101_010);
+        assert!(pool.price_impact_bps(max) <= 100);
+        assert!(pool.price_impact_bps(max + 1_000) > 100);
+        assert!(pool.max_input(0).is_err());
+        assert!(pool.max_input(10_000).is_err());
+    }
+
+    #[test]
+    fn bonding_curve_inventory_caps_the_size() {
+        let pool = depth(30_000_000_000, 1_073_000_000_000_000, Some(1_000_000_000_000));
+        let max = pool.max_input(5_000).unwrap();
+        assert!(max < depth(30_000_000_000, 1_073_000_000_000_000, None).max_input(5_000).unwrap());
+        assert!(pool.amount_out(max) <= 1_000_000_000_000);
+    }
+}
@@ -0,0 +1,165 @@
+//! 256-bit intermediates for Q64.64 price math (CLMM/DLMM quotes), where products of a
+//! liquidity or amount and a sqrt price no longer fit in `u128`.
+//! Q64.64 定点运算所需的 256 位中间值（乘积溢出 u128 时使用）。
+
+/// Unsigned 256-bit integer with just the operations the quote math needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct U256 {
+    // Field order makes the derived `Ord` compare the high word first.
+    hi: u128,
+    lo: u128,
+}
+
+const LOW_64: u128 = u64::MAX as u128;
+
+impl U256 {
+    pub(crate) const ZERO: Self = Self { hi: 0, lo: 0 };
+    pub(crate) const MAX: Self = Self { hi: u128::MAX, lo: u128::MAX };
+
+    #[inline]
+    pub(crate) const fn new(value: u128) -> Self {
+        Self { hi: 0, lo: value }
+    }
+
+    /// Full product `a * b`.
+    #[inline]
+    pub(crate) fn full_mul(a: u128, b: u128) -> Self {
+        let (a1, a0) = (a >> 64, a & LOW_64);
+        let (b1, b0) = (b >> 64, b & LOW_64);
+        let (p00, p01, p10, p11) = (a0 * b0, a0 * b1, a1 * b0, a1 * b1);
+        let mid = (p00 >> 64) + (p01 & LOW_64) + (p10 & LOW_64);
+        Self { hi: p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64), lo: (p00 & LOW_64) | (mid << 64) }
+    }
+
+    #[inline]
+    pub(crate) fn checked_add(self, other: Self) -> Option<Self> {
+        let (lo, carry) = self.lo.overflowing_add(other.lo);
+        let hi = self.hi.checked_add(other.hi)?.checked_add(carry as u128)?;
+        Some(Self { hi, lo })
+    }
+
+    /// `self << shift`, `None` if bits are shifted out.
+    #[inline]
+    pub(crate) fn checked_shl(self, shift: u32) -> Option<Self> {
+        (self.bits() + shift <= 256).then(|| self.shl(shift))
+    }
+
+    #[inline]
+    pub(crate) fn shr(self, shift: u32) -> Self {
+        match shift {
+            0 => self,
+            1..=127 => {
+                Self { hi: self.hi >> shift, lo: (self.lo >> shift) | (self.hi << (128 - shift)) }
+            }
+            128..=255 => Self { hi: 0, lo: self.hi >> (shift - 128) },
+            _ => Self::ZERO,
+        }
+    }
+
+    /// The value if it fits in `u128`.
+    #[inline]
+    pub(crate) fn to_u128(self) -> Option<u128> {
+        (self.hi == 0).then_some(self.lo)
+    }
+
+    /// Quotient and remainder, `None` for a zero divisor.
+    pub(crate) fn checked_div_rem(self, divisor: Self) -> Option<(Self, Self)> {
+        if divisor == Self::ZERO {
+            return None;
+        }
+        if self < divisor {
+            return Some((Self::ZERO, self));
+        }
+        // Shift-and-subtract long division, one quotient bit per round.
+        let shift = self.bits() - divisor.bits();
+        let mut divisor = divisor.shl(shift);
+        let mut remainder = self;
+        let mut quotient = Self::ZERO;
+        for _ in 0..=shift {
+            quotient = quotient.shl(1);
+            if remainder >= divisor {
+                remainder = remainder.wrapping_sub(divisor);
+                quotient.lo |= 1;
+            }
+            divisor = divisor.shr(1);
+        }
+        Some((quotient, remainder))
+    }
+
+    #[inline]
+    fn bits(self) -> u32 {
+        if self.hi != 0 {
+            256 - self.hi.leading_zeros()
+        } else {
+            128 - self.lo.leading_zeros()
+        }
+    }
+
+    #[inline]
+    fn shl(self, shift: u32) -> Self {
+        match shift {
+            0 => self,
+            1..=127 => {
+                Self { hi: (self.hi << shift) | (self.lo >> (128 - shift)), lo: self.lo << shift }
+            }
+            128..=255 => Self { hi: self.lo << (shift - 128), lo: 0 },
+            _ => Self::ZERO,
+        }
+    }
+
+    #[inline]
+    fn wrapping_sub(self, other: Self) -> Self {
+        let (lo, borrow) = self.lo.overflowing_sub(other.lo);
+        Self { hi: self.hi.wrapping_sub(other.hi).wrapping_sub(borrow as u128), lo }
+    }
+}
+
+/// `numerator / denominator` rounded down or up, `None` for a zero denominator or a quotient
+/// above `u128`.
+#[inline]
+pub(crate) fn div_round(numerator: U256, denominator: U256, round_up: bool) -> Option<u128> {
+    let (quotient, remainder) = numerator.checked_div_rem(denominator)?;
+    let quotient = quotient.to_u128()?;
+    if round_up && remainder != U256::ZERO {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// `a * b / denominator` without intermediate overflow, rounded down or up.
+#[inline]
+pub(crate) fn mul_div(a: u128, b: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    div_round(U256::full_mul(a, b), U256::new(denominator), round_up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn wide_products_divide_back_exactly() {
+        assert_eq!(U256::full_mul(u128::MAX, u128::MAX), U256 { hi: u128::MAX - 1, lo: 1 });
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, false), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1, false), None);
+        assert_eq!(mul_div(7, 3, 2, false), Some(10));
+        assert_eq!(mul_div(7, 3, 2, true), Some(11));
+        assert_eq!(mul_div(1, 1, 0, false), None);
+        assert_eq!(U256::MAX.checked_shl(1), None);
+        assert_eq!(U256::new(1).checked_shl(255).map(|v| v.shr(255)), Some(U256::new(1)));
+    }
+
+    proptest! {
+        #[test]
+        fn mul_div_matches_u128_math_when_it_fits(
+            a in any::<u64>(),
+            b in any::<u64>(),
+            denominator in 1..=u128::MAX
+        ) {
+            let (a, b) = (a as u128, b as u128);
+            prop_assert_eq!(mul_div(a, b, denominator, false), Some(a * b / denominator));
+            prop_assert_eq!(mul_div(a, b, denominator, true), Some((a * b).div_ceil(denominator)));
+        }
+    }
+}
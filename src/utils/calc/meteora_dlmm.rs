@@ -0,0 +1,359 @@
+//! Meteora DLMM (liquidity bins) exact-input quotes.
+//!
+//! Liquidity sits in discrete bins, bin `id` holding a fixed price `(1 + bin_step / 10_000)^id`
+//! of Y per X. A swap drains the active bin and moves on to the next one, paying a base fee plus
+//! a variable fee that grows with the number of bins crossed since the pair's reference index.
+//! [`compute_swap_amount`] follows the program's `swap`: pass every bin of the bin arrays the swap
+//! may traverse, empty ones included; the quote stops at the first bin it is not given and
+//! reports a partial fill through `all_trade`.
+//! Meteora DLMM 报价：逐个 bin 兑换，并按跨越 bin 数累积的波动率计算动态手续费。
+
+use super::common::{calculate_with_slippage_sell, FeeBreakdown};
+use super::fixed_point::mul_div;
+
+pub const MIN_BIN_ID: i32 = -443_636;
+pub const MAX_BIN_ID: i32 = 443_636;
+/// Fee rates are in `1e9` precision.
+pub const FEE_PRECISION: u128 = 1_000_000_000;
+/// Total fee rate cap (10%).
+pub const MAX_FEE_RATE: u128 = 100_000_000;
+pub const BASIS_POINT_MAX: u128 = 10_000;
+
+const Q64: u128 = 1 << 64;
+
+/// One bin from the pair's bin arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DlmmBin {
+    pub id: i32,
+    pub amount_x: u64,
+    pub amount_y: u64,
+}
+
+/// Pair fields a quote needs, from the decoded `LbPair` account (`active_id`, `bin_step` and
+/// its static and variable parameters).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DlmmPair {
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub base_factor: u16,
+    pub base_fee_power_factor: u8,
+    pub variable_fee_control: u32,
+    pub max_volatility_accumulator: u32,
+    /// Protocol share of the fee in basis points
+    pub protocol_share: u16,
+    pub volatility_reference: u32,
+    pub index_reference: i32,
+}
+
+impl DlmmPair {
+    /// Move the volatility references the way the program does at the start of a swap made
+    /// `elapsed` seconds after the pair's `last_update_timestamp`. The stored references are
+    /// already right for swaps within `filter_period` of the last one.
+    pub fn update_references(
+        &mut self,
+        volatility_accumulator: u32,
+        elapsed: i64,
+        filter_period: u16,
+        decay_period: u16,
+        reduction_factor: u16,
+    ) {
+        if elapsed < filter_period as i64 {
+            return;
+        }
+        self.index_reference = self.active_id;
+        self.volatility_reference = if elapsed < decay_period as i64 {
+            (volatility_accumulator as u128 * reduction_factor as u128 / BASIS_POINT_MAX) as u32
+        } else {
+            0
+        };
+    }
+
+    /// Base fee rate in [`FEE_PRECISION`].
+    pub fn base_fee_rate(&self) -> u128 {
+        (self.base_factor as u128 * self.bin_step as u128 * 10)
+            .saturating_mul(10u128.saturating_pow(self.base_fee_power_factor as u32))
+    }
+
+    /// Variable fee rate in [`FEE_PRECISION`] for `volatility_accumulator`.
+    pub fn variable_fee_rate(&self, volatility_accumulator: u32) -> u128 {
+        if self.variable_fee_control == 0 {
+            return 0;
+        }
+        let square_vfa_bin = (volatility_accumulator as u128 * self.bin_step as u128).pow(2);
+        let variable_fee = (self.variable_fee_control as u128).saturating_mul(square_vfa_bin);
+        variable_fee.saturating_add(99_999_999_999) / 100_000_000_000
+    }
+
+    /// Total fee rate in [`FEE_PRECISION`], capped at [`MAX_FEE_RATE`].
+    pub fn total_fee_rate(&self, volatility_accumulator: u32) -> u128 {
+        self.base_fee_rate()
+            .saturating_add(self.variable_fee_rate(volatility_accumulator))
+            .min(MAX_FEE_RATE)
+    }
+
+    /// Volatility accumulator while the swap is in bin `active_id`.
+    fn volatility_accumulator(&self, active_id: i32) -> u32 {
+        let delta_id = (self.index_reference as i64 - active_id as i64).unsigned_abs();
+        let accumulator = self.volatility_reference as u64 + delta_id * BASIS_POINT_MAX as u64;
+        accumulator.min(self.max_volatility_accumulator as u64) as u32
+    }
+}
+
+/// Parameters for computing swap amounts and fees.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapParams {
+    /// Whether the entire input amount is traded within the given bins
+    pub all_trade: bool,
+    /// The input amount actually traded, fees included
+    pub amount_in: u64,
+    /// The expected output amount from the swap
+    pub amount_out: u64,
+    /// The minimum acceptable output amount (considering slippage_basis_points)
+    pub min_amount_out: u64,
+    /// The trading fee amount, in input tokens
+    pub fee: u64,
+    /// Fees by recipient, in input tokens
+    pub fees: FeeBreakdown,
+    /// Active bin after the swap
+    pub end_active_id: i32,
+}
+
+/// Price of bin `id` (Q64.64, Y per X), `None` when it does not fit the fixed-point range.
+pub fn price_from_id(id: i32, bin_step: u16) -> Option<u128> {
+    pow(Q64 + ((bin_step as u128) << 64) / BASIS_POINT_MAX, id)
+}
+
+/// `base^exp` in Q64.64 as the program computes it: squaring the inverse of `base` (below 1.0,
+/// so products fit `u128`) and inverting at the end.
+fn pow(base: u128, exp: i32) -> Option<u128> {
+    if exp == 0 {
+        return Some(Q64);
+    }
+    let mut invert = exp.is_negative();
+    let exp = exp.unsigned_abs();
+    if exp >= 0x80000 {
+        return None;
+    }
+    let mut squared_base = base;
+    if squared_base >= Q64 {
+        squared_base = u128::MAX.checked_div(squared_base)?;
+        invert = !invert;
+    }
+    let mut result = Q64;
+    for bit in 0..19 {
+        if exp & (1 << bit) != 0 {
+            result = result.checked_mul(squared_base)? >> 64;
+        }
+        squared_base = squared_base.checked_mul(squared_base)? >> 64;
+    }
+    if result == 0 {
+        return None;
+    }
+    if invert {
+        result = u128::MAX.checked_div(result)?;
+    }
+    Some(result)
+}
+
+struct BinSwap {
+    amount_in: u128,
+    amount_out: u128,
+    fee: u128,
+}
+
+/// Swap up to `amount_in` (fees included) in one bin at `price`.
+fn swap_bin(
+    bin: &DlmmBin,
+    price: u128,
+    amount_in: u128,
+    fee_rate: u128,
+    swap_for_y: bool,
+) -> Option<BinSwap> {
+    let max_amount_out = if swap_for_y { bin.amount_y } else { bin.amount_x } as u128;
+    // Input that buys the whole bin out, then the fee charged on top of it.
+    let max_amount_in = if swap_for_y {
+        mul_div(max_amount_out, Q64, price, true)?
+    } else {
+        mul_div(max_amount_out, price, Q64, true)?
+    };
+    let max_fee = mul_div(max_amount_in, fee_rate, FEE_PRECISION - fee_rate, true)?;
+    let max_amount_in = max_amount_in.checked_add(max_fee)?;
+    if amount_in >= max_amount_in {
+        return Some(BinSwap {
+            amount_in: max_amount_in,
+            amount_out: max_amount_out,
+            fee: max_fee,
+        });
+    }
+
+    let fee = mul_div(amount_in, fee_rate, FEE_PRECISION, true)?;
+    let amount_in_after_fee = amount_in - fee;
+    let amount_out = if swap_for_y {
+        mul_div(amount_in_after_fee, price, Q64, false)?
+    } else {
+        mul_div(amount_in_after_fee, Q64, price, false)?
+    };
+    Some(BinSwap { amount_in, amount_out: amount_out.min(max_amount_out), fee })
+}
+
+/// Computes an exact-input swap through a DLMM pair, bin by bin.
+///
+/// # Arguments
+/// * `pair` - Current pair state, references updated for the swap time (see
+///   [`DlmmPair::update_references`])
+/// * `bins` - Bins of the bin arrays the swap may traverse, in any order
+/// * `swap_for_y` - Whether token X is sold for token Y (active bin moves down)
+/// * `amount_in` - The amount of input tokens to swap, fees included
+/// * `slippage_basis_points` - The acceptable slippage in basis points (e.g., 100 for 1%)
+///
+/// # Returns
+/// A `ComputeSwapParams`; `all_trade` is false when the bins ran out before the input did.
+pub fn compute_swap_amount(
+    pair: &DlmmPair,
+    bins: &[DlmmBin],
+    swap_for_y: bool,
+    amount_in: u64,
+    slippage_basis_points: u64,
+) -> Result<ComputeSwapParams, String> {
+    let overflow = || "DLMM swap amount overflowed.".to_string();
+
+    let mut remaining = amount_in as u128;
+    let mut amount_out = 0u128;
+    let mut fee = 0u128;
+    let mut protocol_fee = 0u128;
+    let mut active_id = pair.active_id;
+
+    while remaining > 0 {
+        let Some(bin) = bins.iter().find(|bin| bin.id == active_id) else {
+            break;
+        };
+        let liquidity = if swap_for_y { bin.amount_y } else { bin.amount_x };
+        if liquidity > 0 {
+            let price = price_from_id(active_id, pair.bin_step)
+                .ok_or_else(|| format!("Bin {active_id} is outside the price range."))?;
+            let fee_rate = pair.total_fee_rate(pair.volatility_accumulator(active_id));
+            let step =
+                swap_bin(bin, price, remaining, fee_rate, swap_for_y).ok_or_else(overflow)?;
+            remaining -= step.amount_in;
+            amount_out = amount_out.checked_add(step.amount_out).ok_or_else(overflow)?;
+            fee += step.fee;
+            protocol_fee += step.fee * pair.protocol_share as u128 / BASIS_POINT_MAX;
+        }
+        if remaining > 0 {
+            let next_id = if swap_for_y { active_id - 1 } else { active_id + 1 };
+            if !(MIN_BIN_ID..=MAX_BIN_ID).contains(&next_id) {
+                break;
+            }
+            active_id = next_id;
+        }
+    }
+
+    let amount_out = u64::try_from(amount_out).map_err(|_| overflow())?;
+    let (fee, protocol_fee) = (fee as u64, protocol_fee as u64);
+
+    Ok(ComputeSwapParams {
+        all_trade: remaining == 0,
+        amount_in: amount_in - remaining as u64,
+        amount_out,
+        min_amount_out: calculate_with_slippage_sell(amount_out, slippage_basis_points),
+        fee,
+        fees: FeeBreakdown { protocol_fee, lp_fee: fee - protocol_fee, ..Default::default() },
+        end_active_id: active_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn pair() -> DlmmPair {
+        DlmmPair {
+            bin_step: 10,
+            base_factor: 10_000,
+            variable_fee_control: 40_000,
+            max_volatility_accumulator: 350_000,
+            protocol_share: 500,
+            ..Default::default()
+        }
+    }
+
+    fn bin(id: i32, amount_x: u64, amount_y: u64) -> DlmmBin {
+        DlmmBin { id, amount_x, amount_y }
+    }
+
+    #[test]
+    fn bin_prices_and_fee_rates() {
+        assert_eq!(price_from_id(0, 10), Some(Q64));
+        assert_eq!(price_from_id(1, 10), Some(18_465_190_817_783_261_167));
+        assert_eq!(price_from_id(-1, 10), Some(18_428_315_757_951_600_016));
+        assert_eq!(price_from_id(MAX_BIN_ID + 0x80000, 10), None);
+
+        let mut pair = pair();
+        assert_eq!(pair.total_fee_rate(0), 1_000_000);
+        assert_eq!(pair.total_fee_rate(pair.volatility_accumulator(-3)), 1_036_000);
+
+        pair.active_id = -3;
+        pair.update_references(30_000, 100, 30, 600, 5_000);
+        assert_eq!((pair.index_reference, pair.volatility_reference), (-3, 15_000));
+        pair.update_references(30_000, 600, 30, 600, 5_000);
+        assert_eq!(pair.volatility_reference, 0);
+    }
+
+    #[test]
+    fn swap_walks_bins_and_stops_where_they_end() {
+        let bins = [
+            bin(0, 1_000_000, 1_000_000),
+            bin(-1, 0, 2_000_000),
+            bin(-2, 0, 0),
+            bin(-3, 0, 1_000_000_000),
+        ];
+        let one_bin = compute_swap_amount(&pair(), &bins, true, 500_000, 100).unwrap();
+        assert!(one_bin.all_trade);
+        assert_eq!(
+            (one_bin.amount_out, one_bin.fee, one_bin.fees.protocol_fee),
+            (499_500, 500, 25)
+        );
+        assert_eq!(one_bin.end_active_id, 0);
+        assert!(one_bin.min_amount_out < one_bin.amount_out);
+
+        let across = compute_swap_amount(&pair(), &bins, true, 3_500_000, 0).unwrap();
+        assert!(across.all_trade);
+        assert_eq!((across.amount_out, across.fee, across.end_active_id), (3_492_991, 3_528, -3));
+
+        let partial = compute_swap_amount(&pair(), &bins[..2], true, 10_000_000, 0).unwrap();
+        assert!(!partial.all_trade);
+        assert_eq!((partial.amount_in, partial.amount_out), (3_005_015, 3_000_000));
+    }
+
+    proptest! {
+        #[test]
+        fn output_is_monotonic_and_never_beats_the_active_price(
+            active_id in -1_000..=1_000i32,
+            bin_step in 1..=100u16,
+            amounts in proptest::collection::vec((any::<u32>(), any::<u32>()), 1..20),
+            swap_for_y in any::<bool>(),
+            a in any::<u64>(),
+            b in any::<u64>()
+        ) {
+            let pair = DlmmPair { active_id, bin_step, index_reference: active_id, ..pair() };
+            let bins: Vec<_> = (0..amounts.len() as i32)
+                .map(|offset| if swap_for_y { active_id - offset } else { active_id + offset })
+                .zip(&amounts)
+                .map(|(id, (x, y))| bin(id, *x as u64, *y as u64))
+                .collect();
+            let quote = |amount| compute_swap_amount(&pair, &bins, swap_for_y, amount, 0).unwrap();
+            let (lo, hi) = (quote(a.min(b)), quote(a.max(b)));
+            prop_assert!(lo.amount_out <= hi.amount_out);
+
+            // Later bins only get worse for the taker.
+            let price = price_from_id(active_id, bin_step).unwrap();
+            let best = if swap_for_y {
+                mul_div(hi.amount_in as u128, price, Q64, false)
+            } else {
+                mul_div(hi.amount_in as u128, Q64, price, false)
+            };
+            prop_assert!(hi.amount_out as u128 <= best.unwrap());
+        }
+    }
+}
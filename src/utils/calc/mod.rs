@@ -1,6 +1,12 @@
 pub mod bonk;
+pub(crate) mod clmm;
 pub mod common;
+pub mod depth;
+pub(crate) mod fixed_point;
+pub mod meteora_dlmm;
+pub mod orca_whirlpool;
 pub mod pumpfun;
 pub mod pumpswap;
 pub mod raydium_amm_v4;
+pub mod raydium_clmm;
 pub mod raydium_cpmm;
@@ -0,0 +1,190 @@
+//! Orca Whirlpool (concentrated liquidity) exact-in quotes.
+//!
+//! [`compute_swap_amount`] maps a Whirlpool onto the shared tick walk in
+//! [`clmm`](super::clmm). Pass every initialized tick of the tick arrays the swap may traverse
+//! (the current array and the next ones in the swap direction); the quote stops at the last tick
+//! it is given and reports a partial fill through `all_trade`.
+//! Orca Whirlpool 集中流动性 exact-in 报价：按 tick 区间逐段计算，跨 tick 时更新流动性。
+
+use super::clmm::{swap_exact_in, ClmmPool};
+use super::common::{calculate_with_slippage_sell, FeeBreakdown};
+
+pub use super::clmm::{
+    sqrt_price_at_tick, ClmmTick as WhirlpoolTick, FEE_RATE_DENOMINATOR, MAX_SQRT_PRICE_X64,
+    MAX_TICK_INDEX, MIN_SQRT_PRICE_X64, MIN_TICK_INDEX,
+};
+
+/// `protocol_fee_rate` is in basis points of the fee.
+pub const PROTOCOL_FEE_RATE_DENOMINATOR: u128 = 10_000;
+
+/// Pool fields a quote needs, from the decoded `Whirlpool` account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhirlpoolState {
+    /// Current sqrt price (Q64.64)
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    /// Liquidity active at the current price
+    pub liquidity: u128,
+    /// Fee in hundredths of a basis point
+    pub fee_rate: u16,
+    /// Protocol share of the fee in basis points
+    pub protocol_fee_rate: u16,
+}
+
+/// Parameters for computing swap amounts and fees.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapParams {
+    /// Whether the entire input amount is traded within the given ticks
+    pub all_trade: bool,
+    /// The input amount actually traded, fees included
+    pub amount_in: u64,
+    /// The expected output amount from the swap
+    pub amount_out: u64,
+    /// The minimum acceptable output amount (considering slippage_basis_points)
+    pub min_amount_out: u64,
+    /// The trading fee amount, in input tokens
+    pub fee: u64,
+    /// Fees by recipient, in input tokens
+    pub fees: FeeBreakdown,
+    /// Sqrt price (Q64.64) after the swap
+    pub end_sqrt_price: u128,
+}
+
+/// Computes an exact-input swap through a Whirlpool, crossing the given initialized ticks.
+///
+/// # Arguments
+/// * `pool` - Current pool state
+/// * `ticks` - Initialized ticks of the tick arrays the swap may traverse, in any order
+/// * `a_to_b` - Whether token A is sold for token B (price moves down)
+/// * `amount_in` - The amount of input tokens to swap, fees included
+/// * `slippage_basis_points` - The acceptable slippage in basis points (e.g., 100 for 1%)
+///
+/// # Returns
+/// A `ComputeSwapParams`; `all_trade` is false when the ticks ran out before the input did.
+pub fn compute_swap_amount(
+    pool: &WhirlpoolState,
+    ticks: &[WhirlpoolTick],
+    a_to_b: bool,
+    amount_in: u64,
+    slippage_basis_points: u64,
+) -> Result<ComputeSwapParams, String> {
+    let clmm_pool = ClmmPool {
+        sqrt_price: pool.sqrt_price,
+        tick_current_index: pool.tick_current_index,
+        liquidity: pool.liquidity,
+        fee_rate: pool.fee_rate as u32,
+    };
+    let mut protocol_fee = 0u128;
+    let swap = swap_exact_in(&clmm_pool, ticks, a_to_b, amount_in, |fee| {
+        protocol_fee += fee * pool.protocol_fee_rate as u128 / PROTOCOL_FEE_RATE_DENOMINATOR;
+    })?;
+    let protocol_fee = protocol_fee as u64;
+
+    Ok(ComputeSwapParams {
+        all_trade: swap.all_trade,
+        amount_in: swap.amount_in,
+        amount_out: swap.amount_out,
+        min_amount_out: calculate_with_slippage_sell(swap.amount_out, slippage_basis_points),
+        fee: swap.fee,
+        fees: FeeBreakdown { protocol_fee, lp_fee: swap.fee - protocol_fee, ..Default::default() },
+        end_sqrt_price: swap.end_sqrt_price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::calc::clmm::Q64;
+    use proptest::prelude::*;
+
+    fn pool(liquidity: u128) -> WhirlpoolState {
+        WhirlpoolState {
+            sqrt_price: Q64,
+            tick_current_index: 0,
+            liquidity,
+            fee_rate: 3000,
+            protocol_fee_rate: 1300,
+        }
+    }
+
+    /// One position of `liquidity` over `[lower, upper)`.
+    fn position(lower: i32, upper: i32, liquidity: u128) -> [WhirlpoolTick; 2] {
+        [
+            WhirlpoolTick { index: lower, liquidity_net: liquidity as i128 },
+            WhirlpoolTick { index: upper, liquidity_net: -(liquidity as i128) },
+        ]
+    }
+
+    #[test]
+    fn swap_inside_one_range_follows_the_constant_product() {
+        let liquidity = 1_000_000_000_000u128;
+        let ticks = position(-1_000, 1_000, liquidity);
+        let quote = compute_swap_amount(&pool(liquidity), &ticks, true, 1_000_000, 100).unwrap();
+
+        // At price 1 the range behaves like reserves of `liquidity` on each side.
+        let net_in = 1_000_000u128 * 997 / 1000;
+        let expected = liquidity * net_in / (liquidity + net_in);
+        assert!(quote.all_trade);
+        assert!(quote.amount_out as u128 <= expected && quote.amount_out as u128 + 2 >= expected);
+        assert_eq!(quote.fee, 3_000);
+        assert_eq!(quote.fees.protocol_fee, 390);
+        assert!(quote.end_sqrt_price < Q64);
+        assert!(quote.min_amount_out < quote.amount_out);
+    }
+
+    #[test]
+    fn crossing_ticks_changes_liquidity_and_stops_where_ticks_end() {
+        let liquidity = 1_000_000u128;
+        let net = liquidity as i128;
+        // A second, deeper position over [-100, -10) shares tick -10 with the first one.
+        let ticks = [
+            WhirlpoolTick { index: -100, liquidity_net: 50 * net },
+            WhirlpoolTick { index: -10, liquidity_net: net - 50 * net },
+            WhirlpoolTick { index: 10, liquidity_net: -net },
+        ];
+        let pool = pool(liquidity);
+
+        let shallow =
+            compute_swap_amount(&pool, &position(-10, 10, liquidity), true, 10_000, 0).unwrap();
+        assert!(!shallow.all_trade);
+        assert_eq!(shallow.end_sqrt_price, sqrt_price_at_tick(-10));
+
+        let deep = compute_swap_amount(&pool, &ticks, true, 10_000, 0).unwrap();
+        assert!(deep.all_trade);
+        assert_eq!(deep.amount_in, 10_000);
+        assert!(deep.amount_out > shallow.amount_out);
+        assert!(deep.end_sqrt_price < sqrt_price_at_tick(-10));
+
+        let empty = compute_swap_amount(&pool, &[], false, 10_000, 0).unwrap();
+        assert_eq!((empty.amount_in, empty.amount_out, empty.all_trade), (0, 0, false));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_never_mints_and_output_is_monotonic(
+            liquidity in 1..=u64::MAX as u128,
+            a in any::<u64>(),
+            b in any::<u64>(),
+            a_to_b in any::<bool>()
+        ) {
+            let ticks = position(MIN_TICK_INDEX, MAX_TICK_INDEX, liquidity);
+            let pool = pool(liquidity);
+            let quote = |pool: &WhirlpoolState, amount| {
+                compute_swap_amount(pool, &ticks, a_to_b, amount, 0)
+            };
+            let (lo, hi) = (a.min(b), a.max(b));
+            if let (Ok(lo), Ok(hi)) = (quote(&pool, lo), quote(&pool, hi)) {
+                prop_assert!(lo.amount_out <= hi.amount_out);
+            }
+
+            let Ok(first) = quote(&pool, a) else {
+                return Ok(());
+            };
+            // Swap the output back from the price the first swap left.
+            let after = WhirlpoolState { sqrt_price: first.end_sqrt_price, ..pool };
+            if let Ok(back) = compute_swap_amount(&after, &ticks, !a_to_b, first.amount_out, 0) {
+                prop_assert!(back.amount_out <= first.amount_in);
+            }
+        }
+    }
+}
@@ -2,9 +2,21 @@ use solana_sdk::pubkey::Pubkey;
 
 use crate::{
     instruction::utils::pumpfun::global_constants::{CREATOR_FEE, FEE_BASIS_POINTS},
-    utils::calc::common::compute_fee,
+    utils::calc::common::{compute_fee, FeeBreakdown},
 };
 
+/// Split `total_fee` (charged on `amount`) into the protocol fee and the creator fee.
+#[inline]
+fn split_fee(amount: u128, total_fee: u128) -> FeeBreakdown {
+    let protocol_fee = compute_fee(amount, FEE_BASIS_POINTS as u128).min(total_fee);
+    let to_u64 = |v: u128| v.min(u64::MAX as u128) as u64;
+    FeeBreakdown {
+        protocol_fee: to_u64(protocol_fee),
+        creator_fee: to_u64(total_fee - protocol_fee),
+        ..Default::default()
+    }
+}
+
 /// Calculates the amount of tokens that can be purchased with a given SOL amount
 /// using the bonding curve formula.
 ///
@@ -25,12 +37,32 @@ pub fn get_buy_token_amount_from_sol_amount(
     creator: Pubkey,
     amount: u64,
 ) -> u64 {
+    get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+        virtual_token_reserves,
+        virtual_sol_reserves,
+        real_token_reserves,
+        creator,
+        amount,
+    )
+    .0
+}
+
+/// Same as [`get_buy_token_amount_from_sol_amount`], also returning the fees taken from
+/// `amount` (in lamports).
+#[inline]
+pub fn get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+    virtual_token_reserves: u128,
+    virtual_sol_reserves: u128,
+    real_token_reserves: u128,
+    creator: Pubkey,
+    amount: u64,
+) -> (u64, FeeBreakdown) {
     if amount == 0 {
-        return 0;
+        return (0, FeeBreakdown::default());
     }
 
     if virtual_token_reserves == 0 {
-        return 0;
+        return (0, FeeBreakdown::default());
     }
 
     let total_fee_basis_points =
@@ -45,9 +77,13 @@ pub fn get_buy_token_amount_from_sol_amount(
         .and_then(|v| v.checked_div(total_fee_basis_points_128 + 10_000))
         .unwrap_or(0);
 
-    let Some(denominator) = virtual_sol_reserves.checked_add(input_amount) else { return 0 };
+    let fees = split_fee(input_amount, amount_128.saturating_sub(input_amount));
+
+    let Some(denominator) = virtual_sol_reserves.checked_add(input_amount) else {
+        return (0, fees);
+    };
     if denominator == 0 {
-        return 0;
+        return (0, fees);
     }
 
     let tokens_received = input_amount
@@ -56,7 +92,7 @@ pub fn get_buy_token_amount_from_sol_amount(
         .unwrap_or(0)
         .min(real_token_reserves);
 
-    tokens_received.min(u64::MAX as u128) as u64
+    (tokens_received.min(u64::MAX as u128) as u64, fees)
 }
 
 /// Calculates the amount of SOL that will be received when selling a given token amount
@@ -77,20 +113,38 @@ pub fn get_sell_sol_amount_from_token_amount(
     creator: Pubkey,
     amount: u64,
 ) -> u64 {
+    get_sell_sol_amount_from_token_amount_with_fee_breakdown(
+        virtual_token_reserves,
+        virtual_sol_reserves,
+        creator,
+        amount,
+    )
+    .0
+}
+
+/// Same as [`get_sell_sol_amount_from_token_amount`], also returning the fees deducted from the
+/// SOL output (in lamports).
+#[inline]
+pub fn get_sell_sol_amount_from_token_amount_with_fee_breakdown(
+    virtual_token_reserves: u128,
+    virtual_sol_reserves: u128,
+    creator: Pubkey,
+    amount: u64,
+) -> (u64, FeeBreakdown) {
     if amount == 0 {
-        return 0;
+        return (0, FeeBreakdown::default());
     }
 
     // migrated bonding curve
     if virtual_token_reserves == 0 {
-        return 0;
+        return (0, FeeBreakdown::default());
     }
 
     let amount_128 = amount as u128;
 
     // Calculate SOL amount received from selling tokens using constant product formula
     let Some(numerator) = amount_128.checked_mul(virtual_sol_reserves) else {
-        return u64::MAX;
+        return (u64::MAX, FeeBreakdown::default());
     };
     let denominator = virtual_token_reserves.checked_add(amount_128).unwrap_or(1);
 
@@ -103,5 +157,102 @@ pub fn get_sell_sol_amount_from_token_amount(
     // Calculate transaction fee
     let fee = compute_fee(sol_cost, total_fee_basis_points_128);
 
-    sol_cost.saturating_sub(fee).min(u64::MAX as u128) as u64
+    (sol_cost.saturating_sub(fee).min(u64::MAX as u128) as u64, split_fee(sol_cost, fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Reserves as stored on chain, in `u64`.
+    fn reserve() -> impl Strategy<Value = u128> {
+        any::<u64>().prop_map(u128::from)
+    }
+
+    fn creator() -> impl Strategy<Value = Pubkey> {
+        prop_oneof![Just(Pubkey::default()), Just(Pubkey::new_from_array([1; 32]))]
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics_on_any_input(
+            virtual_token_reserves in any::<u128>(),
+            virtual_sol_reserves in any::<u128>(),
+            real_token_reserves in any::<u128>(),
+            creator in creator(),
+            amount in any::<u64>()
+        ) {
+            get_buy_token_amount_from_sol_amount(
+                virtual_token_reserves,
+                virtual_sol_reserves,
+                real_token_reserves,
+                creator,
+                amount,
+            );
+            get_sell_sol_amount_from_token_amount(
+                virtual_token_reserves,
+                virtual_sol_reserves,
+                creator,
+                amount,
+            );
+        }
+
+        #[test]
+        fn outputs_are_monotonic_in_the_input(
+            virtual_token_reserves in reserve(),
+            virtual_sol_reserves in reserve(),
+            real_token_reserves in reserve(),
+            creator in creator(),
+            a in any::<u64>(),
+            b in any::<u64>()
+        ) {
+            let (lo, hi) = (a.min(b), a.max(b));
+            let buy = |amount| {
+                get_buy_token_amount_from_sol_amount(
+                    virtual_token_reserves,
+                    virtual_sol_reserves,
+                    real_token_reserves,
+                    creator,
+                    amount,
+                )
+            };
+            let sell = |amount| {
+                get_sell_sol_amount_from_token_amount(
+                    virtual_token_reserves,
+                    virtual_sol_reserves,
+                    creator,
+                    amount,
+                )
+            };
+            prop_assert!(buy(lo) <= buy(hi));
+            prop_assert!(sell(lo) <= sell(hi));
+        }
+
+        #[test]
+        fn buy_then_sell_never_mints_sol(
+            virtual_token_reserves in (1..=u64::MAX).prop_map(u128::from),
+            virtual_sol_reserves in reserve(),
+            real_token_reserves in reserve(),
+            creator in creator(),
+            amount in any::<u64>()
+        ) {
+            let (tokens, fees) = get_buy_token_amount_from_sol_amount_with_fee_breakdown(
+                virtual_token_reserves,
+                virtual_sol_reserves,
+                real_token_reserves,
+                creator,
+                amount,
+            );
+            // Fees leave the curve; only the rest of `amount` is added to the SOL reserves.
+            let sol_in = amount as u128 - fees.total() as u128;
+            let sold = get_sell_sol_amount_from_token_amount(
+                virtual_token_reserves - tokens as u128,
+                virtual_sol_reserves + sol_in,
+                creator,
+                tokens,
+            );
+            prop_assert!(sold as u128 <= sol_in);
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use super::common::{
-    calculate_with_slippage_buy, calculate_with_slippage_sell, ceil_div, compute_fee,
+    calculate_with_slippage_buy, calculate_with_slippage_sell, ceil_div, compute_fee, FeeBreakdown,
 };
 use crate::instruction::utils::pumpswap::accounts::{
     COIN_CREATOR_FEE_BASIS_POINTS, LP_FEE_BASIS_POINTS, PROTOCOL_FEE_BASIS_POINTS,
@@ -7,6 +7,37 @@ use crate::instruction::utils::pumpswap::accounts::{
 use crate::instruction::utils::pumpswap::PumpSwapFeeBasisPoints;
 use solana_sdk::pubkey::Pubkey;
 
+/// Fee bps to quote a pool with: `fees` for regular pools; for mayhem pools, each component is
+/// the larger of `fees` and `mayhem_fees` (the mayhem schedule, when known), so the quote never
+/// under-counts fees.
+/// Mayhem 池子按常规费率与 Mayhem 费率逐项取大报价，避免滑点较紧时交易失败。
+#[inline]
+pub fn pool_fee_basis_points(
+    fees: &PumpSwapFeeBasisPoints,
+    is_mayhem_mode: bool,
+    mayhem_fees: Option<&PumpSwapFeeBasisPoints>,
+) -> PumpSwapFeeBasisPoints {
+    if !is_mayhem_mode {
+        return *fees;
+    }
+    merge_mayhem_fees(fees, mayhem_fees)
+}
+
+#[inline]
+fn merge_mayhem_fees(
+    fees: &PumpSwapFeeBasisPoints,
+    mayhem: Option<&PumpSwapFeeBasisPoints>,
+) -> PumpSwapFeeBasisPoints {
+    let Some(mayhem) = mayhem else {
+        return *fees;
+    };
+    PumpSwapFeeBasisPoints::new(
+        fees.lp_fee_basis_points.max(mayhem.lp_fee_basis_points),
+        fees.protocol_fee_basis_points.max(mayhem.protocol_fee_basis_points),
+        fees.coin_creator_fee_basis_points.max(mayhem.coin_creator_fee_basis_points),
+    )
+}
+
 #[inline]
 fn effective_quote_reserve(
     quote_reserve: u64,
@@ -48,6 +79,11 @@ fn checked_fee(amount: u64, basis_points: u64, name: &str) -> Result<u64, String
     checked_u64(compute_fee(amount as u128, basis_points as u128), name)
 }
 
+#[inline]
+fn fee_breakdown(lp_fee: u64, protocol_fee: u64, coin_creator_fee: u64) -> FeeBreakdown {
+    FeeBreakdown { protocol_fee, creator_fee: coin_creator_fee, lp_fee, ..Default::default() }
+}
+
 /// Result for buying base tokens with base amount input
 #[derive(Clone, Debug)]
 pub struct BuyBaseInputResult {
@@ -57,6 +93,8 @@ pub struct BuyBaseInputResult {
     pub ui_quote: u64,
     /// Maximum quote amount with slippage protection
     pub max_quote: u64,
+    /// Fees on top of `internal_quote_amount`, in quote
+    pub fees: FeeBreakdown,
 }
 
 /// Result for buying base tokens with quote amount input
@@ -68,6 +106,8 @@ pub struct BuyQuoteInputResult {
     pub internal_quote_without_fees: u64,
     /// Maximum quote amount with slippage protection
     pub max_quote: u64,
+    /// Fees on top of `internal_quote_without_fees`, in quote
+    pub fees: FeeBreakdown,
 }
 
 /// Result for selling base tokens with base amount input
@@ -79,6 +119,8 @@ pub struct SellBaseInputResult {
     pub min_quote: u64,
     /// Raw quote amount before fee deduction
     pub internal_quote_amount_out: u64,
+    /// Fees deducted from `internal_quote_amount_out`, in quote
+    pub fees: FeeBreakdown,
 }
 
 /// Result for selling base tokens with quote amount input
@@ -90,6 +132,8 @@ pub struct SellQuoteInputResult {
     pub base: u64,
     /// Minimum quote amount with slippage protection
     pub min_quote: u64,
+    /// Fees deducted from `internal_raw_quote`, in quote
+    pub fees: FeeBreakdown,
 }
 
 /// Calculate quote amount needed to buy a specific amount of base tokens
@@ -177,6 +221,7 @@ pub fn buy_base_input_internal_with_fees(
         internal_quote_amount: quote_amount_in,
         ui_quote: total_quote,
         max_quote,
+        fees: fee_breakdown(lp_fee, protocol_fee, coin_creator_fee),
     })
 }
 
@@ -269,15 +314,25 @@ pub fn buy_quote_input_internal_with_fees(
     }
 
     let base_amount_out = checked_u64(numerator / denominator_effective, "base amount")?;
+    let internal_quote_without_fees = checked_u64(effective_quote, "effective quote amount")?;
+    let fees = fee_breakdown(
+        checked_fee(internal_quote_without_fees, fee_basis_points.lp_fee_basis_points, "LP fee")?,
+        checked_fee(
+            internal_quote_without_fees,
+            fee_basis_points.protocol_fee_basis_points,
+            "protocol fee",
+        )?,
+        checked_fee(
+            internal_quote_without_fees,
+            fee_basis_points.coin_creator_fee_basis_points,
+            "coin creator fee",
+        )?,
+    );
 
     // Calculate max quote with slippage
     let max_quote = calculate_with_slippage_buy(quote, slippage_basis_points);
 
-    Ok(BuyQuoteInputResult {
-        base: base_amount_out,
-        internal_quote_without_fees: checked_u64(effective_quote, "effective quote amount")?,
-        max_quote,
-    })
+    Ok(BuyQuoteInputResult { base: base_amount_out, internal_quote_without_fees, max_quote, fees })
 }
 
 /// Calculate quote tokens received for selling a specific amount of base tokens
@@ -367,6 +422,7 @@ pub fn sell_base_input_internal_with_fees(
         ui_quote: final_quote,
         min_quote,
         internal_quote_amount_out: quote_amount_out,
+        fees: fee_breakdown(lp_fee, protocol_fee, coin_creator_fee),
     })
 }
 
@@ -477,20 +533,69 @@ pub fn sell_quote_input_internal_with_fees(
         "base amount",
     )?;
 
+    let fees = fee_breakdown(
+        lp_fee,
+        checked_fee(raw_quote, fee_basis_points.protocol_fee_basis_points, "protocol fee")?,
+        checked_fee(raw_quote, fee_basis_points.coin_creator_fee_basis_points, "coin creator fee")?,
+    );
+
     // Calculate min quote with slippage
     let min_quote = calculate_with_slippage_sell(quote, slippage_basis_points);
 
-    Ok(SellQuoteInputResult { internal_raw_quote: raw_quote, base: base_amount_in, min_quote })
+    Ok(SellQuoteInputResult {
+        internal_raw_quote: raw_quote,
+        base: base_amount_in,
+        min_quote,
+        fees,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     fn fees() -> PumpSwapFeeBasisPoints {
         PumpSwapFeeBasisPoints::new(20, 5, 0)
     }
 
+    #[test]
+    fn mayhem_fees_raise_each_component_and_tighten_min_out() {
+        let mayhem = PumpSwapFeeBasisPoints::new(20, 95, 0);
+        assert_eq!(merge_mayhem_fees(&fees(), None), fees());
+        let merged = merge_mayhem_fees(&PumpSwapFeeBasisPoints::new(25, 5, 30), Some(&mayhem));
+        assert_eq!(merged, PumpSwapFeeBasisPoints::new(25, 95, 30));
+
+        let regular =
+            sell_base_input_internal_with_fees(10_000, 100, 1_000_000, 1_000_000, 0, &fees())
+                .unwrap();
+        let quoted = sell_base_input_internal_with_fees(
+            10_000,
+            100,
+            1_000_000,
+            1_000_000,
+            0,
+            &merge_mayhem_fees(&fees(), Some(&mayhem)),
+        )
+        .unwrap();
+        assert!(quoted.min_quote < regular.min_quote);
+        assert_eq!(pool_fee_basis_points(&fees(), false, Some(&mayhem)), fees());
+    }
+
+    #[test]
+    fn fee_breakdown_accounts_for_the_quote_difference() {
+        let fees = PumpSwapFeeBasisPoints::new(20, 5, 30);
+        let buy =
+            buy_base_input_internal_with_fees(10_000, 100, 1_000_000, 1_000_000, 0, &fees).unwrap();
+        assert_eq!(buy.ui_quote, buy.internal_quote_amount + buy.fees.total());
+        assert!(buy.fees.creator_fee > 0 && buy.fees.platform_fee == 0);
+
+        let sell = sell_base_input_internal_with_fees(10_000, 100, 1_000_000, 1_000_000, 0, &fees)
+            .unwrap();
+        assert_eq!(sell.ui_quote, sell.internal_quote_amount_out - sell.fees.total());
+        assert_eq!((sell.fees.lp_fee, sell.fees.protocol_fee, sell.fees.creator_fee), (20, 5, 30));
+    }
+
     #[test]
     fn buy_uses_effective_quote_reserves() {
         let result =
@@ -682,4 +787,130 @@ mod tests {
 
         assert_eq!(error, "Quote input is too small after fees.");
     }
+
+    /// Realistic fee bps most of the time, arbitrary ones to hit the overflow checks.
+    fn basis_points() -> impl Strategy<Value = u64> {
+        prop_oneof![0..=1_000u64, any::<u64>()]
+    }
+
+    fn fee_basis_points() -> impl Strategy<Value = PumpSwapFeeBasisPoints> {
+        (0..=100u64, 0..=100u64, 0..=100u64).prop_map(|(lp, protocol, coin_creator)| {
+            PumpSwapFeeBasisPoints::new(lp, protocol, coin_creator)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn quotes_never_panic_on_any_input(
+            amount in any::<u64>(),
+            slippage_basis_points in any::<u64>(),
+            base_reserve in any::<u64>(),
+            quote_reserve in any::<u64>(),
+            virtual_quote_reserves in any::<i128>(),
+            lp in basis_points(),
+            protocol in basis_points(),
+            coin_creator in basis_points()
+        ) {
+            let fees = PumpSwapFeeBasisPoints::new(lp, protocol, coin_creator);
+            let _ = buy_base_input_internal_with_fees(
+                amount,
+                slippage_basis_points,
+                base_reserve,
+                quote_reserve,
+                virtual_quote_reserves,
+                &fees,
+            );
+            let _ = buy_quote_input_internal_with_fees(
+                amount,
+                slippage_basis_points,
+                base_reserve,
+                quote_reserve,
+                virtual_quote_reserves,
+                &fees,
+            );
+            let _ = sell_base_input_internal_with_fees(
+                amount,
+                slippage_basis_points,
+                base_reserve,
+                quote_reserve,
+                virtual_quote_reserves,
+                &fees,
+            );
+            let _ = sell_quote_input_internal_with_fees(
+                amount,
+                slippage_basis_points,
+                base_reserve,
+                quote_reserve,
+                virtual_quote_reserves,
+                &fees,
+            );
+        }
+
+        #[test]
+        fn quotes_are_monotonic_in_the_input(
+            base_reserve in 1..=u64::MAX,
+            quote_reserve in 1..=u64::MAX,
+            virtual_quote_reserves in any::<i64>().prop_map(i128::from),
+            fees in fee_basis_points(),
+            a in any::<u64>(),
+            b in any::<u64>()
+        ) {
+            let (lo, hi) = (a.min(b), a.max(b));
+            let args = (base_reserve, quote_reserve, virtual_quote_reserves);
+            let buy_base = |base| {
+                buy_base_input_internal_with_fees(base, 0, args.0, args.1, args.2, &fees)
+                    .map(|r| r.ui_quote)
+            };
+            // Sell fees are rounded up one by one, so compare the output before them.
+            let sell_base = |base| {
+                sell_base_input_internal_with_fees(base, 0, args.0, args.1, args.2, &fees)
+                    .map(|r| r.internal_quote_amount_out)
+            };
+            let sell_quote = |quote| {
+                sell_quote_input_internal_with_fees(quote, 0, args.0, args.1, args.2, &fees)
+                    .map(|r| r.base)
+            };
+            if let (Ok(lo), Ok(hi)) = (buy_base(lo), buy_base(hi)) {
+                prop_assert!(lo <= hi);
+            }
+            if let (Ok(lo), Ok(hi)) = (sell_base(lo), sell_base(hi)) {
+                prop_assert!(lo <= hi);
+            }
+            if let (Ok(lo), Ok(hi)) = (sell_quote(lo), sell_quote(hi)) {
+                prop_assert!(lo <= hi);
+            }
+        }
+
+        #[test]
+        fn buy_then_sell_never_mints_quote(
+            quote in any::<u64>(),
+            base_reserve in 1..=u64::MAX,
+            quote_reserve in 1..=u64::MAX,
+            fees in fee_basis_points()
+        ) {
+            let Ok(buy) =
+                buy_quote_input_internal_with_fees(quote, 0, base_reserve, quote_reserve, 0, &fees)
+            else {
+                return Ok(());
+            };
+            // The LP fee stays in the pool; protocol and creator fees leave it.
+            let Some(quote_reserve) = quote_reserve
+                .checked_add(buy.internal_quote_without_fees)
+                .and_then(|reserve| reserve.checked_add(buy.fees.lp_fee))
+            else {
+                return Ok(());
+            };
+            let sell = sell_base_input_internal_with_fees(
+                buy.base,
+                0,
+                base_reserve - buy.base,
+                quote_reserve,
+                0,
+                &fees,
+            );
+            if let Ok(sell) = sell {
+                prop_assert!(sell.ui_quote <= quote);
+            }
+        }
+    }
 }
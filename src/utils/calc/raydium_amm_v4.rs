@@ -1,6 +1,7 @@
 use crate::instruction::utils::raydium_amm_v4::accounts::{
     SWAP_FEE_DENOMINATOR, SWAP_FEE_NUMERATOR, TRADE_FEE_DENOMINATOR, TRADE_FEE_NUMERATOR,
 };
+use crate::utils::calc::common::FeeBreakdown;
 
 /// Computes trading fee using ceiling division.
 ///
@@ -41,6 +42,9 @@ pub struct ComputeSwapParams {
     pub min_amount_out: u64,
     /// The trading fee amount
     pub fee: u64,
+    /// Trade fee by recipient, in input tokens: the swap fee share goes to the protocol and the
+    /// rest to LPs, so `fees.total() == fee`
+    pub fees: FeeBreakdown,
 }
 
 /// Result of a swap calculation containing all relevant amounts and fees.
@@ -86,10 +90,11 @@ fn swap_base_input(
 
     let swap_fee = compute_protocol_fund_fee(trade_fee, swap_fee_rate, SWAP_FEE_DENOMINATOR);
 
-    let output_amount_swapped = ((output_vault_amount as u128)
+    // An empty input vault with nothing left after fees swaps nothing instead of dividing by zero.
+    let output_amount_swapped = (output_vault_amount as u128)
         .saturating_mul(input_amount_less_fees as u128)
-        / (input_vault_amount as u128).saturating_add(input_amount_less_fees as u128))
-        as u64;
+        .checked_div((input_vault_amount as u128).saturating_add(input_amount_less_fees as u128))
+        .unwrap_or(0) as u64;
 
     let output_amount = output_amount_swapped.saturating_sub(swap_fee);
 
@@ -146,5 +151,86 @@ pub fn compute_swap_amount(
         amount_out: swap_result.output_amount,
         min_amount_out,
         fee: swap_result.trade_fee,
+        fees: FeeBreakdown {
+            protocol_fee: swap_result.swap_fee,
+            lp_fee: swap_result.trade_fee.saturating_sub(swap_result.swap_fee),
+            ..Default::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn fee_breakdown_splits_the_trade_fee() {
+        let params = compute_swap_amount(1_000_000_000, 2_000_000_000, false, 1_000_000, 0);
+        assert_eq!(params.fee, 2_500);
+        assert_eq!(params.fees.protocol_fee, 6);
+        assert_eq!(params.fees.lp_fee, 2_494);
+        assert_eq!(params.fees.total(), params.fee);
+    }
+
+    /// Empty and tiny reserves next to arbitrary ones.
+    fn reserve() -> impl Strategy<Value = u64> {
+        prop_oneof![Just(0u64), 1..=1_000u64, any::<u64>()]
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics_on_any_input(
+            base_reserve in reserve(),
+            quote_reserve in reserve(),
+            is_base_in in any::<bool>(),
+            amount_in in reserve(),
+            slippage_basis_points in any::<u64>()
+        ) {
+            compute_swap_amount(
+                base_reserve,
+                quote_reserve,
+                is_base_in,
+                amount_in,
+                slippage_basis_points,
+            );
+        }
+
+        #[test]
+        fn output_is_monotonic_in_the_input(
+            base_reserve in reserve(),
+            quote_reserve in reserve(),
+            is_base_in in any::<bool>(),
+            a in any::<u64>(),
+            b in any::<u64>()
+        ) {
+            let (lo, hi) = (a.min(b), a.max(b));
+            // The swap fee is also deducted from the output in whole units; compare before it.
+            let out = |amount| {
+                let params =
+                    compute_swap_amount(base_reserve, quote_reserve, is_base_in, amount, 0);
+                params.amount_out + params.fees.protocol_fee
+            };
+            prop_assert!(out(lo) <= out(hi));
+        }
+
+        #[test]
+        fn buy_then_sell_never_mints_quote(
+            base_reserve in reserve(),
+            quote_reserve in reserve(),
+            amount_in in any::<u64>()
+        ) {
+            let buy = compute_swap_amount(base_reserve, quote_reserve, false, amount_in, 0);
+            // The input less fees joins the quote vault and the swapped base leaves the pool.
+            let quote_in = amount_in - buy.fee - buy.fees.creator_fee;
+            let sell = compute_swap_amount(
+                base_reserve.saturating_sub(buy.amount_out + buy.fees.protocol_fee),
+                quote_reserve.saturating_add(quote_in),
+                true,
+                buy.amount_out,
+                0,
+            );
+            prop_assert!(sell.amount_out <= amount_in);
+        }
     }
 }
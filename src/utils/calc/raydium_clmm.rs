@@ -0,0 +1,133 @@
+//! Raydium CLMM (concentrated liquidity) exact-in quotes.
+//!
+//! [`compute_swap_amount`] simulates a `swap_v2` locally over the pool's tick arrays, so the
+//! swap's `other_amount_threshold` can be derived from `slippage_basis_points` instead of being
+//! supplied by the caller. Pass every initialized tick of the tick arrays the swap may traverse
+//! (the ones the swap instruction lists as remaining accounts); the quote stops at the last tick
+//! it is given and reports a partial fill through `all_trade`.
+//! Raydium CLMM 报价：由 tick arrays 本地模拟兑换，按滑点计算 other_amount_threshold。
+
+use super::clmm::{swap_exact_in, ClmmPool};
+use super::common::{calculate_with_slippage_sell, FeeBreakdown};
+
+pub use super::clmm::{
+    sqrt_price_at_tick, ClmmTick as RaydiumClmmTick, FEE_RATE_DENOMINATOR, MAX_SQRT_PRICE_X64,
+    MAX_TICK_INDEX, MIN_SQRT_PRICE_X64, MIN_TICK_INDEX,
+};
+
+/// Pool fields a quote needs, from the decoded `PoolState` and its `AmmConfig`.
+///
+/// All rates are out of [`FEE_RATE_DENOMINATOR`]; the protocol and fund rates are shares of the
+/// trade fee, as in `AmmConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaydiumClmmState {
+    /// Current sqrt price (Q64.64)
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    /// Liquidity active at the current price
+    pub liquidity: u128,
+    pub trade_fee_rate: u32,
+    pub protocol_fee_rate: u32,
+    pub fund_fee_rate: u32,
+}
+
+/// Parameters for computing swap amounts and fees.
+#[derive(Debug, Clone)]
+pub struct ComputeSwapParams {
+    /// Whether the entire input amount is traded within the given ticks
+    pub all_trade: bool,
+    /// The input amount actually traded, fees included
+    pub amount_in: u64,
+    /// The expected output amount from the swap
+    pub amount_out: u64,
+    /// The minimum acceptable output amount (considering slippage_basis_points); pass it as the
+    /// swap's `other_amount_threshold`
+    pub min_amount_out: u64,
+    /// The trading fee amount, in input tokens
+    pub fee: u64,
+    /// Fees by recipient, in input tokens; `protocol_fee` includes the fund fee
+    pub fees: FeeBreakdown,
+    /// Sqrt price (Q64.64) after the swap
+    pub end_sqrt_price_x64: u128,
+}
+
+/// Computes an exact-input swap through a Raydium CLMM pool, crossing the given initialized ticks.
+///
+/// # Arguments
+/// * `pool` - Current pool state
+/// * `ticks` - Initialized ticks of the tick arrays the swap may traverse, in any order
+/// * `zero_for_one` - Whether token 0 is sold for token 1 (price moves down)
+/// * `amount_in` - The amount of input tokens to swap, fees included
+/// * `slippage_basis_points` - The acceptable slippage in basis points (e.g., 100 for 1%)
+///
+/// # Returns
+/// A `ComputeSwapParams`; `all_trade` is false when the ticks ran out before the input did.
+pub fn compute_swap_amount(
+    pool: &RaydiumClmmState,
+    ticks: &[RaydiumClmmTick],
+    zero_for_one: bool,
+    amount_in: u64,
+    slippage_basis_points: u64,
+) -> Result<ComputeSwapParams, String> {
+    let clmm_pool = ClmmPool {
+        sqrt_price: pool.sqrt_price_x64,
+        tick_current_index: pool.tick_current,
+        liquidity: pool.liquidity,
+        fee_rate: pool.trade_fee_rate,
+    };
+    // The program floors both shares on every step.
+    let mut protocol_fee = 0u128;
+    let swap = swap_exact_in(&clmm_pool, ticks, zero_for_one, amount_in, |fee| {
+        protocol_fee += fee * pool.protocol_fee_rate as u128 / FEE_RATE_DENOMINATOR;
+        protocol_fee += fee * pool.fund_fee_rate as u128 / FEE_RATE_DENOMINATOR;
+    })?;
+    let protocol_fee = (protocol_fee as u64).min(swap.fee);
+
+    Ok(ComputeSwapParams {
+        all_trade: swap.all_trade,
+        amount_in: swap.amount_in,
+        amount_out: swap.amount_out,
+        min_amount_out: calculate_with_slippage_sell(swap.amount_out, slippage_basis_points),
+        fee: swap.fee,
+        fees: FeeBreakdown { protocol_fee, lp_fee: swap.fee - protocol_fee, ..Default::default() },
+        end_sqrt_price_x64: swap.end_sqrt_price,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::calc::clmm::Q64;
+
+    fn pool(liquidity: u128) -> RaydiumClmmState {
+        RaydiumClmmState {
+            sqrt_price_x64: Q64,
+            tick_current: 0,
+            liquidity,
+            trade_fee_rate: 2_500,
+            protocol_fee_rate: 120_000,
+            fund_fee_rate: 40_000,
+        }
+    }
+
+    #[test]
+    fn quote_splits_fees_and_derives_the_threshold() {
+        let liquidity = 1_000_000_000_000u128;
+        let ticks = [
+            RaydiumClmmTick { index: -1_000, liquidity_net: liquidity as i128 },
+            RaydiumClmmTick { index: 1_000, liquidity_net: -(liquidity as i128) },
+        ];
+        for zero_for_one in [true, false] {
+            let quote = compute_swap_amount(&pool(liquidity), &ticks, zero_for_one, 1_000_000, 100)
+                .unwrap();
+            assert!(quote.all_trade);
+            assert_eq!((quote.amount_out, quote.fee), (997_499, 2_500));
+            assert_eq!((quote.fees.protocol_fee, quote.fees.lp_fee), (400, 2_100));
+            assert_eq!(quote.min_amount_out, 987_525);
+            assert_eq!(quote.end_sqrt_price_x64 < Q64, zero_for_one);
+        }
+
+        let empty = compute_swap_amount(&pool(liquidity), &ticks[..1], false, 1_000, 0).unwrap();
+        assert_eq!((empty.amount_in, empty.all_trade), (0, false));
+    }
+}
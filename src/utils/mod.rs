@@ -1,21 +1,21 @@
+pub mod accounts;
 pub mod calc;
 pub mod price;
 use crate::trading;
 use crate::TradingClient;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
+use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::signer::Signer;
 
 impl TradingClient {
     #[inline]
     pub async fn get_sol_balance(&self, payer: &Pubkey) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_sol_balance(&self.infrastructure.rpc, payer).await
+        accounts::get_sol_balance(&self.infrastructure.rpc, payer).await
     }
 
     #[inline]
     pub async fn get_payer_sol_balance(&self) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_sol_balance(&self.infrastructure.rpc, &self.payer.pubkey())
-            .await
+        accounts::get_sol_balance(&self.infrastructure.rpc, &self.payer.pubkey()).await
     }
 
     #[inline]
@@ -24,17 +24,12 @@ impl TradingClient {
         payer: &Pubkey,
         mint: &Pubkey,
     ) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_token_balance(&self.infrastructure.rpc, payer, mint).await
+        accounts::get_token_balance(&self.infrastructure.rpc, payer, mint).await
     }
 
     #[inline]
     pub async fn get_payer_token_balance(&self, mint: &Pubkey) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_token_balance(
-            &self.infrastructure.rpc,
-            &self.payer.pubkey(),
-            mint,
-        )
-        .await
+        accounts::get_token_balance(&self.infrastructure.rpc, &self.payer.pubkey(), mint).await
     }
 
     /// 使用与交易一致的 ATA 推导（含 seed 优化）查询 payer 某 mint 的余额；卖出前查余额应使用此接口并传入池的 base_token_program，否则若使用 seed ATA 会查错账户。
@@ -44,7 +39,7 @@ impl TradingClient {
         mint: &Pubkey,
         token_program: &Pubkey,
     ) -> Result<u64, anyhow::Error> {
-        trading::common::utils::get_token_balance_with_options(
+        accounts::get_token_balance_with_options(
             &self.infrastructure.rpc,
             &self.payer.pubkey(),
             mint,
@@ -54,6 +49,16 @@ impl TradingClient {
         .await
     }
 
+    /// Balances and owners of any token accounts in batched RPC calls; see
+    /// [`accounts::get_multi_token_balances`].
+    #[inline]
+    pub async fn get_token_balances(
+        &self,
+        token_accounts: &[Pubkey],
+    ) -> Result<Vec<Option<accounts::TokenAccountBalance>>, anyhow::Error> {
+        accounts::get_multi_token_balances(&self.infrastructure.rpc, token_accounts).await
+    }
+
     #[inline]
     pub fn get_payer_pubkey(&self) -> Pubkey {
         self.payer.pubkey()
@@ -80,6 +85,44 @@ impl TradingClient {
         .await
     }
 
+    /// Transfer SPL tokens (Token or Token-2022, auto-detected) from the payer to `recipient`.
+    /// Creates the recipient ATA idempotently.
+    #[inline]
+    pub async fn transfer_token(
+        &self,
+        mint: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Result<Signature, anyhow::Error> {
+        trading::common::utils::transfer_token(
+            &self.infrastructure.rpc,
+            self.payer.as_ref(),
+            mint,
+            recipient,
+            amount,
+            self.use_seed_optimize,
+        )
+        .await
+    }
+
+    /// Transfer SPL tokens from the payer to many recipients (airdrop), one signature per chunk of
+    /// [`trading::common::utils::MAX_TOKEN_TRANSFERS_PER_TX`] recipients.
+    #[inline]
+    pub async fn batch_transfer(
+        &self,
+        mint: &Pubkey,
+        recipients: Vec<(Pubkey, u64)>,
+    ) -> Result<Vec<Signature>, anyhow::Error> {
+        trading::common::utils::batch_transfer_token(
+            &self.infrastructure.rpc,
+            self.payer.as_ref(),
+            mint,
+            &recipients,
+            self.use_seed_optimize,
+        )
+        .await
+    }
+
     #[inline]
     pub async fn close_token_account(&self, mint: &Pubkey) -> Result<(), anyhow::Error> {
         trading::common::utils::close_token_account(
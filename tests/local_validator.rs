@@ -0,0 +1,344 @@
+//! End-to-end buy and sell per protocol against a local `solana-test-validator` that clones the
+//! pool accounts from mainnet, so account-ordering regressions in the instruction builders fail
+//! here instead of on mainnet.
+//!
+//! ```text
+//! IT_PUMPSWAP_MINT=<mint> IT_RAYDIUM_CPMM_POOL=<pool> \
+//!     cargo test --features integration-tests --test local_validator -- --nocapture
+//! ```
+//!
+//! Needs `solana-test-validator` on `PATH` and a mainnet RPC to clone from (`MAINNET_RPC_URL`,
+//! the public endpoint by default). A protocol runs when its pool is configured and is skipped
+//! otherwise:
+//! - `IT_PUMPFUN_MINT`, `IT_PUMPSWAP_MINT`, `IT_BONK_MINT`: a mint currently trading there;
+//! - `IT_RAYDIUM_CPMM_POOL`, `IT_RAYDIUM_AMM_V4_POOL`, `IT_METEORA_DAMM_V2_POOL`: a WSOL pool.
+//!
+//! The accounts to clone are those of the SDK's own probe buy (see
+//! [`discover_accounts_with_params`]), so the list follows the builders. Sells use the same pool
+//! accounts; an account only a sell touches would show up as a failed sell.
+//! 本地验证器集成测试：从主网克隆各协议池账户，逐协议执行买入与卖出。
+
+use sol_trade_sdk::{
+    common::{GasFeeStrategy, SolanaRpcClient, TradeConfig},
+    constants::{
+        ASSOCIATED_TOKEN_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM, MEMO_PROGRAM, SYSTEM_PROGRAM,
+        TOKEN_PROGRAM, TOKEN_PROGRAM_2022, WSOL_TOKEN_ACCOUNT,
+    },
+    swqos::SwqosConfig,
+    trading::{
+        account_discovery::discover_accounts_with_params,
+        common::get_mint_program_and_decimals,
+        core::params::{
+            BonkParams, DexParamEnum, MeteoraDammV2Params, PumpFunParams, PumpSwapParams,
+            RaydiumAmmV4Params, RaydiumCpmmParams,
+        },
+        factory::DexType,
+    },
+    BuyAmount, SellAmount, SimpleBuyParams, SimpleSellParams, SolanaTrade, TradeTokenType,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_program::pubkey;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::collections::BTreeSet;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_MAINNET_RPC: &str = "https://api.mainnet-beta.solana.com";
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const BUY_LAMPORTS: u64 = LAMPORTS_PER_SOL / 100;
+const AIRDROP_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+/// Reserves move between fetching params and cloning the pool.
+const SLIPPAGE_BPS: u64 = 5_000;
+const CU_LIMIT: u32 = 400_000;
+const VALIDATOR_START_TIMEOUT: Duration = Duration::from_secs(120);
+
+const BPF_LOADER_UPGRADEABLE: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+/// Programs and sysvars every test validator has at genesis.
+const BUILTIN_OWNERS: [Pubkey; 3] = [
+    pubkey!("NativeLoader1111111111111111111111111111111"),
+    pubkey!("Sysvar1111111111111111111111111111111111111"),
+    pubkey!("BPFLoader2111111111111111111111111111111111"),
+];
+const BUILTIN_PROGRAMS: [Pubkey; 6] = [
+    SYSTEM_PROGRAM,
+    COMPUTE_BUDGET_PROGRAM,
+    MEMO_PROGRAM,
+    TOKEN_PROGRAM,
+    TOKEN_PROGRAM_2022,
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+];
+
+/// One protocol under test: the mint traded against SOL and the params to trade it with.
+struct Case {
+    dex: DexType,
+    mint: Pubkey,
+    params: DexParamEnum,
+}
+
+/// Accounts the local validator clones from mainnet.
+#[derive(Default)]
+struct CloneSet {
+    accounts: BTreeSet<Pubkey>,
+    programs: BTreeSet<Pubkey>,
+}
+
+/// `solana-test-validator` child process, killed on drop.
+struct LocalValidator {
+    child: Child,
+    ledger: PathBuf,
+    rpc_url: String,
+}
+
+impl LocalValidator {
+    async fn start(mainnet_rpc: &str, clones: &CloneSet) -> anyhow::Result<Self> {
+        let ledger =
+            std::env::temp_dir().join(format!("sol-trade-sdk-it-{}", Pubkey::new_unique()));
+        let rpc_port = free_port()?;
+        let mut command = Command::new("solana-test-validator");
+        command
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .arg("--rpc-port")
+            .arg(rpc_port.to_string())
+            .arg("--faucet-port")
+            .arg(free_port()?.to_string())
+            .arg("--url")
+            .arg(mainnet_rpc);
+        for account in &clones.accounts {
+            command.arg("--clone").arg(account.to_string());
+        }
+        for program in &clones.programs {
+            command.arg("--clone-upgradeable-program").arg(program.to_string());
+        }
+        let child = command
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("failed to start solana-test-validator: {}", e))?;
+        let mut validator =
+            Self { child, ledger, rpc_url: format!("http://127.0.0.1:{}", rpc_port) };
+
+        let rpc = SolanaRpcClient::new(validator.rpc_url.clone());
+        let started = Instant::now();
+        while rpc.get_health().await.is_err() {
+            if let Some(status) = validator.child.try_wait()? {
+                anyhow::bail!("solana-test-validator exited with {}", status);
+            }
+            if started.elapsed() > VALIDATOR_START_TIMEOUT {
+                anyhow::bail!(
+                    "solana-test-validator not healthy after {:?}",
+                    VALIDATOR_START_TIMEOUT
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        Ok(validator)
+    }
+}
+
+impl Drop for LocalValidator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+fn free_port() -> anyhow::Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+fn env_pubkey(name: &str) -> anyhow::Result<Option<Pubkey>> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => Ok(Some(Pubkey::from_str(value.trim())?)),
+        _ => Ok(None),
+    }
+}
+
+/// The side of a WSOL pool that is not WSOL.
+fn non_wsol(a: Pubkey, b: Pubkey) -> anyhow::Result<Pubkey> {
+    match (a == WSOL_TOKEN_ACCOUNT, b == WSOL_TOKEN_ACCOUNT) {
+        (true, false) => Ok(b),
+        (false, true) => Ok(a),
+        _ => anyhow::bail!("pool {} / {} is not paired with WSOL", a, b),
+    }
+}
+
+/// Cases for every configured protocol, params fetched from mainnet.
+async fn configured_cases(rpc: &SolanaRpcClient) -> anyhow::Result<Vec<Case>> {
+    let mut cases = Vec::new();
+    if let Some(mint) = env_pubkey("IT_PUMPFUN_MINT")? {
+        let params = DexParamEnum::PumpFun(PumpFunParams::from_mint_by_rpc(rpc, &mint).await?);
+        cases.push(Case { dex: DexType::PumpFun, mint, params });
+    }
+    if let Some(mint) = env_pubkey("IT_PUMPSWAP_MINT")? {
+        let params = DexParamEnum::PumpSwap(PumpSwapParams::from_mint_by_rpc(rpc, &mint).await?);
+        cases.push(Case { dex: DexType::PumpSwap, mint, params });
+    }
+    if let Some(mint) = env_pubkey("IT_BONK_MINT")? {
+        let params = DexParamEnum::Bonk(BonkParams::from_mint_by_rpc(rpc, &mint, false).await?);
+        cases.push(Case { dex: DexType::Bonk, mint, params });
+    }
+    if let Some(pool) = env_pubkey("IT_RAYDIUM_CPMM_POOL")? {
+        let params = RaydiumCpmmParams::from_pool_address_by_rpc(rpc, &pool).await?;
+        let mint = non_wsol(params.base_mint, params.quote_mint)?;
+        cases.push(Case {
+            dex: DexType::RaydiumCpmm,
+            mint,
+            params: DexParamEnum::RaydiumCpmm(params),
+        });
+    }
+    if let Some(amm) = env_pubkey("IT_RAYDIUM_AMM_V4_POOL")? {
+        let params = RaydiumAmmV4Params::from_amm_address_by_rpc(rpc, amm).await?;
+        let mint = non_wsol(params.coin_mint, params.pc_mint)?;
+        cases.push(Case {
+            dex: DexType::RaydiumAmmV4,
+            mint,
+            params: DexParamEnum::RaydiumAmmV4(params),
+        });
+    }
+    if let Some(pool) = env_pubkey("IT_METEORA_DAMM_V2_POOL")? {
+        let params = MeteoraDammV2Params::from_pool_address_by_rpc(rpc, &pool).await?;
+        let mint = non_wsol(params.token_a_mint, params.token_b_mint)?;
+        cases.push(Case {
+            dex: DexType::MeteoraDammV2,
+            mint,
+            params: DexParamEnum::MeteoraDammV2(params),
+        });
+    }
+    Ok(cases)
+}
+
+/// Add the mainnet accounts `case`'s swap reads to `clones`. Accounts that do not exist on
+/// mainnet (the payer's token accounts) are created by the trade itself.
+async fn collect_clones(
+    rpc: &Arc<SolanaRpcClient>,
+    payer: &Arc<Keypair>,
+    case: &Case,
+    clones: &mut CloneSet,
+) -> anyhow::Result<()> {
+    let discovery =
+        discover_accounts_with_params(rpc, payer, &case.mint, case.dex, case.params.clone())
+            .await?;
+    let keys: Vec<Pubkey> = discovery
+        .accounts
+        .iter()
+        .map(|account| account.meta.pubkey)
+        .filter(|key| *key != payer.pubkey() && !BUILTIN_PROGRAMS.contains(key))
+        .collect();
+    clones.programs.insert(sol_trade_sdk::programs::program_id(case.dex));
+    for (key, account) in keys.iter().zip(rpc.get_multiple_accounts(&keys).await?) {
+        let Some(account) = account else { continue };
+        if BUILTIN_OWNERS.contains(&account.owner) {
+            continue;
+        }
+        if account.executable && account.owner == BPF_LOADER_UPGRADEABLE {
+            clones.programs.insert(*key);
+        } else {
+            clones.accounts.insert(*key);
+        }
+    }
+    Ok(())
+}
+
+fn gas_fee_strategy() -> GasFeeStrategy {
+    let strategy = GasFeeStrategy::new();
+    strategy.set_global_fee_strategy(CU_LIMIT, CU_LIMIT, 100_000, 100_000, 0.0, 0.0);
+    strategy
+}
+
+/// Buy [`BUY_LAMPORTS`] of `case.mint` with SOL, then sell the whole balance back.
+async fn buy_and_sell(client: &SolanaTrade, case: &Case) -> anyhow::Result<()> {
+    let rpc = client.get_rpc();
+    let (token_program, _) = get_mint_program_and_decimals(rpc, &case.mint).await?;
+
+    let buy = SimpleBuyParams::new(
+        case.dex,
+        TradeTokenType::SOL,
+        case.mint,
+        BuyAmount::ExactInput(BUY_LAMPORTS),
+        case.params.clone(),
+        rpc.get_latest_blockhash().await?,
+        gas_fee_strategy(),
+    )
+    .slippage_basis_points(SLIPPAGE_BPS)
+    .wait_tx_confirmed(true);
+    let (ok, signatures, err, _) = client.buy_simple(buy).await?;
+    anyhow::ensure!(ok, "buy failed: {:?} {:?}", err, signatures);
+    let bought = client.get_payer_token_balance_with_program(&case.mint, &token_program).await?;
+    anyhow::ensure!(bought > 0, "buy {:?} confirmed but no tokens received", signatures);
+
+    let sell = SimpleSellParams::new(
+        case.dex,
+        TradeTokenType::SOL,
+        case.mint,
+        SellAmount::All,
+        case.params.clone(),
+        rpc.get_latest_blockhash().await?,
+        gas_fee_strategy(),
+    )
+    .slippage_basis_points(SLIPPAGE_BPS)
+    .wait_tx_confirmed(true);
+    let (ok, signatures, err, _) = client.sell_simple(sell).await?;
+    anyhow::ensure!(ok, "sell failed: {:?} {:?}", err, signatures);
+    // Some sells close the token account, which then has no balance to read.
+    let left =
+        client.get_payer_token_balance_with_program(&case.mint, &token_program).await.unwrap_or(0);
+    anyhow::ensure!(left == 0, "sell {:?} left {} of {} tokens", signatures, left, bought);
+    Ok(())
+}
+
+#[tokio::test]
+async fn buy_and_sell_every_configured_protocol() -> anyhow::Result<()> {
+    let mainnet_rpc_url =
+        std::env::var("MAINNET_RPC_URL").unwrap_or_else(|_| DEFAULT_MAINNET_RPC.to_string());
+    let mainnet = Arc::new(SolanaRpcClient::new(mainnet_rpc_url.clone()));
+    let cases = configured_cases(&mainnet).await?;
+    if cases.is_empty() {
+        println!("no IT_* pool configured, skipping");
+        return Ok(());
+    }
+
+    let payer = Arc::new(Keypair::new());
+    let mut clones = CloneSet::default();
+    for case in &cases {
+        collect_clones(&mainnet, &payer, case, &mut clones).await?;
+    }
+    let validator = LocalValidator::start(&mainnet_rpc_url, &clones).await?;
+
+    let local = SolanaRpcClient::new_with_commitment(
+        validator.rpc_url.clone(),
+        CommitmentConfig::confirmed(),
+    );
+    let airdrop = local.request_airdrop(&payer.pubkey(), AIRDROP_LAMPORTS).await?;
+    while !local.confirm_transaction(&airdrop).await? {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let trade_config = TradeConfig::builder(
+        validator.rpc_url.clone(),
+        vec![SwqosConfig::Default(validator.rpc_url.clone())],
+        CommitmentConfig::confirmed(),
+    )
+    .build();
+    let client = SolanaTrade::new(payer, trade_config).await;
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        match buy_and_sell(&client, case).await {
+            Ok(()) => println!("{}: buy and sell ok", case.dex),
+            Err(e) => failures.push(format!("{} ({}): {:#}", case.dex, case.mint, e)),
+        }
+    }
+    anyhow::ensure!(failures.is_empty(), "protocol failures:\n{}", failures.join("\n"));
+    Ok(())
+}